@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{future::Future, path::Path, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use miden_lib::{notes::create_p2id_note, transaction::TransactionKernel};
@@ -19,7 +19,7 @@ use miden_objects::{
     transaction::{ChainMmr, ExecutedTransaction, TransactionArgs, TransactionScript},
     utils::Deserializable,
     vm::AdviceMap,
-    BlockHeader, Felt,
+    BlockHeader, Felt, Word,
 };
 use miden_tx::{
     auth::BasicAuthenticator, utils::Serializable, LocalTransactionProver, ProvingOptions,
@@ -27,11 +27,12 @@ use miden_tx::{
 };
 use rand::{random, rngs::StdRng};
 use tonic::transport::Channel;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::{
     config::FaucetConfig,
-    errors::{ClientError, ImplError},
+    errors::{FaucetClientError, ImplError},
+    metrics::{FaucetMetrics, RpcMethod},
     store::FaucetDataStore,
     COMPONENT,
 };
@@ -39,6 +40,12 @@ use crate::{
 pub const DISTRIBUTE_FUNGIBLE_ASSET_SCRIPT: &str =
     include_str!("transaction_scripts/distribute_fungible_asset.masm");
 
+/// Maximum number of attempts for a request to the node which fails due to connectivity issues.
+const MAX_REQUEST_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry of a failed request, doubled on every further retry.
+const INITIAL_RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
 // FAUCET CLIENT
 // ================================================================================================
 
@@ -50,6 +57,7 @@ pub struct FaucetClient {
     data_store: Arc<FaucetDataStore>,
     id: AccountId,
     rng: RpoRandomCoin,
+    metrics: Arc<FaucetMetrics>,
 }
 
 // TODO: Remove this once https://github.com/0xPolygonMiden/miden-base/issues/909 is resolved
@@ -61,17 +69,21 @@ impl FaucetClient {
     /// # Note
     /// If the faucet account is not found on chain, it will be created on submission of the first
     /// minting transaction.
-    pub async fn new(config: &FaucetConfig) -> Result<Self, ClientError> {
-        let (mut rpc_api, root_block_header, root_chain_mmr) =
-            initialize_faucet_client(config).await?;
-
-        let faucet_account_data = AccountData::read(&config.faucet_account_path)
+    pub async fn new(
+        config: &FaucetConfig,
+        faucet_account_path: &Path,
+        metrics: Arc<FaucetMetrics>,
+    ) -> Result<Self, FaucetClientError> {
+        let (rpc_api, root_block_header, root_chain_mmr) =
+            initialize_faucet_client(config, &metrics).await?;
+
+        let faucet_account_data = AccountData::read(faucet_account_path)
             .context("Failed to load faucet account from file")?;
 
         let id = faucet_account_data.account.id();
 
         info!(target: COMPONENT, "Requesting account state from the node...");
-        let faucet_account = match request_account_state(&mut rpc_api, id).await {
+        let faucet_account = match request_account_state(&rpc_api, id, &metrics).await {
             Ok(account) => {
                 info!(
                     target: COMPONENT,
@@ -84,7 +96,9 @@ impl FaucetClient {
             },
 
             Err(err) => match err {
-                ClientError::RequestError(status) if status.code() == tonic::Code::NotFound => {
+                FaucetClientError::RequestError(status)
+                    if status.code() == tonic::Code::NotFound =>
+                {
                     info!(target: COMPONENT, "Faucet account not found in the node");
 
                     faucet_account_data.account
@@ -95,28 +109,51 @@ impl FaucetClient {
             },
         };
 
-        let data_store = Arc::new(FaucetDataStore::new(
+        Ok(Self::with_account(
+            rpc_api,
             faucet_account,
             faucet_account_data.account_seed,
+            faucet_account_data.auth_secret_key,
             root_block_header,
             root_chain_mmr,
-        ));
+            metrics,
+        ))
+    }
 
-        let public_key = match &faucet_account_data.auth_secret_key {
+    /// Creates a client minting with the given faucet account state, anchored at `block_header`.
+    pub fn with_account(
+        rpc_api: ApiClient<Channel>,
+        faucet_account: Account,
+        account_seed: Option<Word>,
+        auth_secret_key: AuthSecretKey,
+        block_header: BlockHeader,
+        chain_mmr: ChainMmr,
+        metrics: Arc<FaucetMetrics>,
+    ) -> Self {
+        let id = faucet_account.id();
+        let data_store =
+            Arc::new(FaucetDataStore::new(faucet_account, account_seed, block_header, chain_mmr));
+
+        let public_key = match &auth_secret_key {
             AuthSecretKey::RpoFalcon512(secret) => secret.public_key(),
         };
 
-        let authenticator = BasicAuthenticator::<StdRng>::new(&[(
-            public_key.into(),
-            faucet_account_data.auth_secret_key,
-        )]);
+        let authenticator =
+            BasicAuthenticator::<StdRng>::new(&[(public_key.into(), auth_secret_key)]);
 
         let executor = TransactionExecutor::new(data_store.clone(), Some(Arc::new(authenticator)));
 
         let coin_seed: [u64; 4] = random();
         let rng = RpoRandomCoin::new(coin_seed.map(Felt::new));
 
-        Ok(Self { data_store, rpc_api, executor, id, rng })
+        Self {
+            data_store,
+            rpc_api,
+            executor,
+            id,
+            rng,
+            metrics,
+        }
     }
 
     /// Executes a mint transaction for the target account.
@@ -127,9 +164,10 @@ impl FaucetClient {
         target_account_id: AccountId,
         is_private_note: bool,
         asset_amount: u64,
-    ) -> Result<(ExecutedTransaction, Note), ClientError> {
-        let asset =
-            FungibleAsset::new(self.id, asset_amount).context("Failed to create fungible asset")?;
+    ) -> Result<(ExecutedTransaction, Note), FaucetClientError> {
+        let asset = FungibleAsset::new(self.id, asset_amount).map_err(|err| {
+            FaucetClientError::InvalidRequest(format!("Failed to create fungible asset: {err}"))
+        })?;
 
         let note_type = if is_private_note {
             NoteType::Private
@@ -145,7 +183,9 @@ impl FaucetClient {
             Default::default(),
             &mut self.rng,
         )
-        .context("Failed to create P2ID note")?;
+        .map_err(|err| {
+            FaucetClientError::InvalidRequest(format!("Failed to create P2ID note: {err}"))
+        })?;
 
         let transaction_args = build_transaction_arguments(&output_note, note_type, asset)?;
 
@@ -158,10 +198,13 @@ impl FaucetClient {
     }
 
     /// Proves and submits the executed transaction to the node.
+    ///
+    /// The submission is not retried, as a transaction which reached the node before the request
+    /// failed would be submitted twice.
     pub async fn prove_and_submit_transaction(
         &mut self,
         executed_tx: ExecutedTransaction,
-    ) -> Result<u32, ClientError> {
+    ) -> Result<u32, FaucetClientError> {
         // Prepare request with proven transaction.
         // This is needed to be in a separated code block in order to release reference to avoid
         // borrow checker error.
@@ -177,11 +220,12 @@ impl FaucetClient {
             }
         };
 
+        let submission = self.rpc_api.submit_proven_transaction(request);
         let response = self
-            .rpc_api
-            .submit_proven_transaction(request)
+            .metrics
+            .observe_rpc(RpcMethod::SubmitProvenTransaction, submission)
             .await
-            .context("Failed to submit proven transaction")?;
+            .map_err(FaucetClientError::from_submission_status)?;
 
         Ok(response.into_inner().block_height)
     }
@@ -200,25 +244,33 @@ impl FaucetClient {
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Initializes the faucet client by connecting to the node and fetching the root block header.
-pub async fn initialize_faucet_client(
-    config: &FaucetConfig,
-) -> Result<(ApiClient<Channel>, BlockHeader, ChainMmr), ClientError> {
+/// Connects to the node RPC configured in `config`.
+pub async fn connect_node(config: &FaucetConfig) -> Result<ApiClient<Channel>, FaucetClientError> {
     let endpoint = tonic::transport::Endpoint::try_from(config.node_url.clone())
         .context("Failed to parse node URL from configuration file")?
         .timeout(Duration::from_millis(config.timeout_ms));
 
-    let mut rpc_api =
-        ApiClient::connect(endpoint).await.context("Failed to connect to the node")?;
+    ApiClient::connect(endpoint).await.map_err(|err| {
+        FaucetClientError::Connectivity(format!("Failed to connect to the node: {err}"))
+    })
+}
+
+/// Initializes the faucet client by connecting to the node and fetching the root block header.
+pub async fn initialize_faucet_client(
+    config: &FaucetConfig,
+    metrics: &FaucetMetrics,
+) -> Result<(ApiClient<Channel>, BlockHeader, ChainMmr), FaucetClientError> {
+    let rpc_api = connect_node(config).await?;
 
     let request = GetBlockHeaderByNumberRequest {
         block_num: Some(0),
         include_mmr_proof: None,
     };
-    let response = rpc_api
-        .get_block_header_by_number(request)
-        .await
-        .context("Failed to get block header")?;
+    let call = with_retries(|| {
+        let mut rpc_api = rpc_api.clone();
+        async move { rpc_api.get_block_header_by_number(request).await }
+    });
+    let response = metrics.observe_rpc(RpcMethod::GetBlockHeaderByNumber, call).await?;
     let root_block_header = response
         .into_inner()
         .block_header
@@ -241,11 +293,17 @@ pub async fn initialize_faucet_client(
 ///
 /// The account is expected to be public, otherwise, the error is returned.
 async fn request_account_state(
-    rpc_api: &mut ApiClient<Channel>,
+    rpc_api: &ApiClient<Channel>,
     account_id: AccountId,
-) -> Result<Account, ClientError> {
-    let account_info = rpc_api
-        .get_account_details(GetAccountDetailsRequest { account_id: Some(account_id.into()) })
+    metrics: &FaucetMetrics,
+) -> Result<Account, FaucetClientError> {
+    let call = with_retries(|| {
+        let mut rpc_api = rpc_api.clone();
+        let request = GetAccountDetailsRequest { account_id: Some(account_id.into()) };
+        async move { rpc_api.get_account_details(request).await }
+    });
+    let account_info = metrics
+        .observe_rpc(RpcMethod::GetAccountDetails, call)
         .await?
         .into_inner()
         .details
@@ -260,12 +318,43 @@ async fn request_account_state(
         .map_err(Into::into)
 }
 
+/// Sends a request to the node, retrying it with an exponential backoff if the node can't be
+/// reached.
+///
+/// The request is attempted at most [MAX_REQUEST_ATTEMPTS] times, any other failure is returned
+/// immediately. Only idempotent reads are retried, since a failed request may have been served.
+async fn with_retries<T, F, Fut>(mut send_request: F) -> Result<T, tonic::Status>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, tonic::Status>>,
+{
+    let mut backoff = INITIAL_RETRY_BACKOFF;
+    for attempt in 1..MAX_REQUEST_ATTEMPTS {
+        match send_request().await {
+            Err(status) if FaucetClientError::is_connectivity(&status) => {
+                warn!(
+                    target: COMPONENT,
+                    attempt,
+                    %status,
+                    "Node is unreachable, retrying in {}ms",
+                    backoff.as_millis()
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            },
+            result => return result,
+        }
+    }
+
+    send_request().await
+}
+
 /// Builds transaction arguments for the mint transaction.
 fn build_transaction_arguments(
     output_note: &Note,
     note_type: NoteType,
     asset: FungibleAsset,
-) -> Result<TransactionArgs, ClientError> {
+) -> Result<TransactionArgs, FaucetClientError> {
     let recipient = output_note
         .recipient()
         .digest()
@@ -294,3 +383,88 @@ fn build_transaction_arguments(
 
     Ok(transaction_args)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use miden_objects::accounts::account_id::testing::ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN;
+    use tonic::{Code, Status};
+
+    use super::*;
+    use crate::test_utils::{serve_stub_rpc, StubRpc};
+
+    #[tokio::test(start_paused = true)]
+    async fn connectivity_failures_are_retried() {
+        let attempts = AtomicU32::new(0);
+
+        let result = with_retries(|| {
+            let attempt = attempts.fetch_add(1, Ordering::Relaxed) + 1;
+            async move {
+                if attempt < MAX_REQUEST_ATTEMPTS {
+                    Err(Status::unavailable("connection refused"))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), MAX_REQUEST_ATTEMPTS);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_are_bounded() {
+        let attempts = AtomicU32::new(0);
+
+        let result: Result<(), _> = with_retries(|| {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            async { Err(Status::deadline_exceeded("timeout")) }
+        })
+        .await;
+
+        assert!(matches!(
+            FaucetClientError::from(result.unwrap_err()),
+            FaucetClientError::Connectivity(_)
+        ));
+        assert_eq!(attempts.load(Ordering::Relaxed), MAX_REQUEST_ATTEMPTS);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn other_failures_are_not_retried() {
+        let attempts = AtomicU32::new(0);
+
+        let result: Result<(), _> = with_retries(|| {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            async { Err(Status::invalid_argument("invalid transaction")) }
+        })
+        .await;
+
+        assert!(matches!(
+            FaucetClientError::from_submission_status(result.unwrap_err()),
+            FaucetClientError::SubmissionRejected { code: tonic::Code::InvalidArgument, .. }
+        ));
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn account_state_reads_are_retried_through_the_node() {
+        let account_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let cases = [
+            (Code::Unavailable, MAX_REQUEST_ATTEMPTS),
+            (Code::DeadlineExceeded, MAX_REQUEST_ATTEMPTS),
+            (Code::InvalidArgument, 1),
+        ];
+
+        for (code, expected_requests) in cases {
+            let stub = Arc::new(StubRpc::failing_with(code));
+            let rpc_api = serve_stub_rpc(Arc::clone(&stub)).await;
+
+            let result =
+                request_account_state(&rpc_api, account_id, &FaucetMetrics::default()).await;
+
+            assert!(result.is_err(), "{code:?}");
+            assert_eq!(stub.account_details_requests(), expected_requests, "{code:?}");
+        }
+    }
+}
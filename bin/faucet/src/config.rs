@@ -3,7 +3,9 @@ use std::{
     path::PathBuf,
 };
 
-use miden_node_utils::config::{Endpoint, DEFAULT_FAUCET_SERVER_PORT, DEFAULT_NODE_RPC_PORT};
+use miden_node_utils::config::{
+    Endpoint, CONFIG_VERSION, DEFAULT_FAUCET_SERVER_PORT, DEFAULT_NODE_RPC_PORT,
+};
 use serde::{Deserialize, Serialize};
 
 // Faucet config
@@ -12,29 +14,73 @@ use serde::{Deserialize, Serialize};
 /// Default path to the faucet account file
 pub const DEFAULT_FAUCET_ACCOUNT_PATH: &str = "accounts/faucet.mac";
 
+/// Default symbol of the token dispensed by the faucet
+pub const DEFAULT_TOKEN_SYMBOL: &str = "POL";
+
 /// Default timeout for RPC requests
 pub const DEFAULT_RPC_TIMEOUT_MS: u64 = 10000;
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct FaucetConfig {
+    /// Version of the layout of the configuration file, see [CONFIG_VERSION]
+    #[serde(default)]
+    pub config_version: u32,
     /// Endpoint of the faucet
     pub endpoint: Endpoint,
     /// Node RPC gRPC endpoint in the format `http://<host>[:<port>]`
     pub node_url: String,
     /// Timeout for RPC requests in milliseconds
     pub timeout_ms: u64,
+    /// Tokens dispensed by the faucet, each one minted by its own faucet account
+    pub tokens: Vec<TokenConfig>,
+    /// Embed the full private notes in the note links and QR codes, not only their IDs
+    #[serde(default = "default_embed_private_notes")]
+    pub embed_private_notes: bool,
+    /// Endpoint exporting the metrics of the faucet in the Prometheus text format, disabled if
+    /// omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_endpoint: Option<Endpoint>,
+}
+
+/// Configuration of a token dispensed by the faucet.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenConfig {
+    /// Symbol identifying the token in the requests, e.g. `POL`
+    pub token_symbol: String,
+    /// Path to the account file of the faucet minting the token
+    pub faucet_account_path: PathBuf,
     /// Possible options on the amount of asset that should be dispersed on each faucet request
     pub asset_amount_options: Vec<u64>,
-    /// Path to the faucet account file
-    pub faucet_account_path: PathBuf,
+    /// Amount preselected in the faucet website, the first option if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_asset_amount: Option<u64>,
 }
 
 impl Display for FaucetConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "{{ endpoint: \"{}\", node_url: \"{}\", timeout_ms: \"{}\", asset_amount_options: {:?}, faucet_account_path: \"{}\" }}",
-            self.endpoint, self.node_url, self.timeout_ms, self.asset_amount_options, self.faucet_account_path.display()
+            "{{ endpoint: \"{}\", node_url: \"{}\", timeout_ms: \"{}\", embed_private_notes: {}, metrics_endpoint: {:?}, tokens: [",
+            self.endpoint, self.node_url, self.timeout_ms, self.embed_private_notes, self.metrics_endpoint.as_ref().map(ToString::to_string)
+        ))?;
+
+        for (index, token) in self.tokens.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            token.fmt(f)?;
+        }
+
+        f.write_str("] }")
+    }
+}
+
+impl Display for TokenConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{{ token_symbol: \"{}\", faucet_account_path: \"{}\", asset_amount_options: {:?}, default_asset_amount: {:?} }}",
+            self.token_symbol, self.faucet_account_path.display(), self.asset_amount_options, self.default_asset_amount
         ))
     }
 }
@@ -42,11 +88,28 @@ impl Display for FaucetConfig {
 impl Default for FaucetConfig {
     fn default() -> Self {
         Self {
+            config_version: CONFIG_VERSION,
             endpoint: Endpoint::localhost(DEFAULT_FAUCET_SERVER_PORT),
             node_url: Endpoint::localhost(DEFAULT_NODE_RPC_PORT).to_string(),
             timeout_ms: DEFAULT_RPC_TIMEOUT_MS,
-            asset_amount_options: vec![100, 500, 1000],
+            tokens: vec![TokenConfig::default()],
+            embed_private_notes: true,
+            metrics_endpoint: None,
+        }
+    }
+}
+
+fn default_embed_private_notes() -> bool {
+    true
+}
+
+impl Default for TokenConfig {
+    fn default() -> Self {
+        Self {
+            token_symbol: DEFAULT_TOKEN_SYMBOL.to_string(),
             faucet_account_path: DEFAULT_FAUCET_ACCOUNT_PATH.into(),
+            asset_amount_options: vec![100, 500, 1000],
+            default_asset_amount: None,
         }
     }
 }
@@ -1,9 +1,11 @@
 use std::fmt::{Debug, Display};
 
 use axum::{
-    http::{header, StatusCode},
+    http::StatusCode,
     response::{IntoResponse, Response},
+    Json,
 };
+use serde::Serialize;
 use thiserror::Error;
 
 /// Wrapper for implementing `Error` trait for errors, which do not implement it, like
@@ -13,18 +15,79 @@ use thiserror::Error;
 pub struct ImplError<E: Display + Debug>(pub E);
 
 #[derive(Debug, Error)]
-pub enum ClientError {
+pub enum FaucetClientError {
+    #[error("Node is unreachable: {0}")]
+    Connectivity(String),
+
+    #[error("Transaction was rejected by the node ({code}): {message}")]
+    SubmissionRejected { code: tonic::Code, message: String },
+
+    #[error("Transaction submission timed out, it may still have been accepted: {0}")]
+    SubmissionOutcomeUnknown(String),
+
+    #[error("Faucet account state is out of sync with the node: {0}")]
+    StateDesync(String),
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
     #[error("Request error: {0}")]
-    RequestError(#[from] tonic::Status),
+    RequestError(tonic::Status),
 
     #[error("Client error: {0:#}")]
     Other(#[from] anyhow::Error),
 }
 
+impl FaucetClientError {
+    /// Returns `true` if the error is caused by the node being temporarily unreachable, in which
+    /// case the request can be retried.
+    pub fn is_connectivity(status: &tonic::Status) -> bool {
+        matches!(
+            status.code(),
+            tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Cancelled
+        )
+    }
+
+    /// Maps the status returned by the node for a transaction submission.
+    ///
+    /// Unlike [From<tonic::Status>], every failure which is not caused by connectivity or by the
+    /// faucet's account state is reported as a rejection of the submitted transaction. Submissions
+    /// which timed out or were cancelled may have reached the node, their outcome is reported as
+    /// unknown.
+    pub fn from_submission_status(status: tonic::Status) -> Self {
+        if matches!(status.code(), tonic::Code::DeadlineExceeded | tonic::Code::Cancelled) {
+            return Self::SubmissionOutcomeUnknown(status.message().to_string());
+        }
+
+        match Self::from(status) {
+            Self::RequestError(status) => Self::SubmissionRejected {
+                code: status.code(),
+                message: status.message().to_string(),
+            },
+            err => err,
+        }
+    }
+}
+
+impl From<tonic::Status> for FaucetClientError {
+    fn from(status: tonic::Status) -> Self {
+        if Self::is_connectivity(&status) {
+            return Self::Connectivity(status.message().to_string());
+        }
+
+        match status.code() {
+            // The node reports a mismatch between the transaction's initial account state and the
+            // latest known account state as a failed precondition
+            tonic::Code::FailedPrecondition => Self::StateDesync(status.message().to_string()),
+            _ => Self::RequestError(status),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum HandlerError {
     #[error("Node client error: {0}")]
-    ClientError(#[from] ClientError),
+    ClientError(#[from] FaucetClientError),
 
     #[error("Server has encountered an internal error: {0:#}")]
     Internal(#[from] anyhow::Error),
@@ -32,36 +95,201 @@ pub enum HandlerError {
     #[error("Client has submitted a bad request: {0}")]
     BadRequest(String),
 
+    #[error("Client has submitted an invalid account ID: {0}")]
+    InvalidAccountId(String),
+
     #[error("Page not found: {0}")]
     NotFound(String),
 }
 
+/// Body of the error responses, `code` is a stable identifier of the error class which can be
+/// matched on by the frontend.
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    code: &'static str,
+    message: String,
+}
+
 impl HandlerError {
     fn status_code(&self) -> StatusCode {
-        match *self {
-            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+        match self {
+            Self::BadRequest(_)
+            | Self::InvalidAccountId(_)
+            | Self::ClientError(FaucetClientError::InvalidRequest(_)) => StatusCode::BAD_REQUEST,
             Self::NotFound(_) => StatusCode::NOT_FOUND,
-            Self::ClientError(_) | Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::ClientError(FaucetClientError::Connectivity(_)) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            },
+            Self::ClientError(FaucetClientError::StateDesync(_)) => StatusCode::CONFLICT,
+            Self::ClientError(FaucetClientError::SubmissionRejected { .. }) => {
+                StatusCode::BAD_GATEWAY
+            },
+            Self::ClientError(FaucetClientError::SubmissionOutcomeUnknown(_)) => {
+                StatusCode::GATEWAY_TIMEOUT
+            },
+            Self::ClientError(FaucetClientError::RequestError(_) | FaucetClientError::Other(_))
+            | Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_code(&self) -> &'static str {
+        match self {
+            Self::BadRequest(_)
+            | Self::InvalidAccountId(_)
+            | Self::ClientError(FaucetClientError::InvalidRequest(_)) => "invalid_request",
+            Self::NotFound(_) => "not_found",
+            Self::ClientError(FaucetClientError::Connectivity(_)) => "node_unavailable",
+            Self::ClientError(FaucetClientError::StateDesync(_)) => "faucet_state_desync",
+            Self::ClientError(FaucetClientError::SubmissionRejected { .. }) => {
+                "transaction_rejected"
+            },
+            Self::ClientError(FaucetClientError::SubmissionOutcomeUnknown(_)) => {
+                "submission_outcome_unknown"
+            },
+            Self::ClientError(FaucetClientError::RequestError(_) | FaucetClientError::Other(_))
+            | Self::Internal(_) => "internal_error",
         }
     }
 
     fn message(&self) -> String {
         match self {
-            Self::BadRequest(msg) => msg,
-            Self::ClientError(_) | Self::Internal(_) => "Error processing request",
-            Self::NotFound(msg) => msg,
+            Self::BadRequest(msg) | Self::InvalidAccountId(msg) | Self::NotFound(msg) => {
+                msg.clone()
+            },
+            Self::ClientError(FaucetClientError::InvalidRequest(msg)) => msg.clone(),
+            Self::ClientError(FaucetClientError::Connectivity(_)) => {
+                "The node is currently unavailable, please try again later".to_string()
+            },
+            Self::ClientError(FaucetClientError::StateDesync(_)) => {
+                "The faucet is temporarily out of sync with the node, please try again later"
+                    .to_string()
+            },
+            Self::ClientError(FaucetClientError::SubmissionRejected { message, .. }) => {
+                format!("The node rejected the transaction: {message}")
+            },
+            Self::ClientError(FaucetClientError::SubmissionOutcomeUnknown(_)) => {
+                "The node did not confirm the transaction in time, the tokens may still be minted"
+                    .to_string()
+            },
+            Self::ClientError(FaucetClientError::RequestError(_) | FaucetClientError::Other(_))
+            | Self::Internal(_) => "Error processing request".to_string(),
         }
-        .to_string()
     }
 }
 
 impl IntoResponse for HandlerError {
     fn into_response(self) -> Response {
-        (
-            self.status_code(),
-            [(header::CONTENT_TYPE, mime::TEXT_HTML_UTF_8.as_ref())],
-            self.message(),
-        )
-            .into_response()
+        let body = ErrorResponse {
+            code: self.error_code(),
+            message: self.message(),
+        };
+
+        (self.status_code(), Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+    use tonic::{Code, Status};
+
+    use super::*;
+
+    async fn response_parts(err: HandlerError) -> (StatusCode, Value) {
+        let response = err.into_response();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[test]
+    fn node_statuses_are_mapped_to_error_classes() {
+        assert!(matches!(
+            FaucetClientError::from(Status::unavailable("connection refused")),
+            FaucetClientError::Connectivity(_)
+        ));
+        assert!(matches!(
+            FaucetClientError::from(Status::deadline_exceeded("timeout")),
+            FaucetClientError::Connectivity(_)
+        ));
+        assert!(matches!(
+            FaucetClientError::from_submission_status(Status::failed_precondition("mismatch")),
+            FaucetClientError::StateDesync(_)
+        ));
+        assert!(matches!(
+            FaucetClientError::from_submission_status(Status::unavailable("connection refused")),
+            FaucetClientError::Connectivity(_)
+        ));
+        assert!(matches!(
+            FaucetClientError::from_submission_status(Status::deadline_exceeded("timeout")),
+            FaucetClientError::SubmissionOutcomeUnknown(_)
+        ));
+        assert!(matches!(
+            FaucetClientError::from(Status::not_found("account")),
+            FaucetClientError::RequestError(status) if status.code() == Code::NotFound
+        ));
+
+        let FaucetClientError::SubmissionRejected { code, message } =
+            FaucetClientError::from_submission_status(Status::invalid_argument("nullifiers"))
+        else {
+            panic!("invalid argument must be a rejected submission");
+        };
+        assert_eq!(code, Code::InvalidArgument);
+        assert_eq!(message, "nullifiers");
+    }
+
+    #[tokio::test]
+    async fn errors_are_translated_into_http_responses() {
+        let cases = [
+            (
+                HandlerError::ClientError(FaucetClientError::Connectivity("refused".into())),
+                StatusCode::SERVICE_UNAVAILABLE,
+                "node_unavailable",
+            ),
+            (
+                HandlerError::ClientError(FaucetClientError::from_submission_status(
+                    Status::invalid_argument("nullifiers"),
+                )),
+                StatusCode::BAD_GATEWAY,
+                "transaction_rejected",
+            ),
+            (
+                HandlerError::ClientError(FaucetClientError::from_submission_status(
+                    Status::cancelled("cancelled"),
+                )),
+                StatusCode::GATEWAY_TIMEOUT,
+                "submission_outcome_unknown",
+            ),
+            (
+                HandlerError::ClientError(FaucetClientError::StateDesync("mismatch".into())),
+                StatusCode::CONFLICT,
+                "faucet_state_desync",
+            ),
+            (
+                HandlerError::ClientError(FaucetClientError::InvalidRequest("amount".into())),
+                StatusCode::BAD_REQUEST,
+                "invalid_request",
+            ),
+            (
+                HandlerError::BadRequest("account id".into()),
+                StatusCode::BAD_REQUEST,
+                "invalid_request",
+            ),
+            (
+                HandlerError::Internal(anyhow::anyhow!("failure")),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+            ),
+        ];
+
+        for (err, expected_status, expected_code) in cases {
+            let (status, body) = response_parts(err).await;
+
+            assert_eq!(status, expected_status);
+            assert_eq!(body["code"], expected_code);
+            assert!(body["message"].is_string());
+        }
     }
 }
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use anyhow::Context;
 use axum::{
     extract::{Path, State},
@@ -7,6 +9,7 @@ use axum::{
 };
 use http::header;
 use http_body_util::Full;
+use miden_node_proto::generated::requests::GetBlockHeaderByNumberRequest;
 use miden_objects::{
     accounts::AccountId,
     notes::{NoteDetails, NoteExecutionMode, NoteFile, NoteId, NoteTag},
@@ -16,28 +19,69 @@ use serde::{Deserialize, Serialize};
 use tonic::body;
 use tracing::info;
 
-use crate::{errors::HandlerError, state::FaucetState, COMPONENT};
+use crate::{
+    errors::{FaucetClientError, HandlerError},
+    metrics::RpcMethod,
+    note_link::{encode_note_link, MAX_NOTE_LINK_LEN},
+    state::FaucetState,
+    COMPONENT,
+};
 
 #[derive(Deserialize)]
 pub struct FaucetRequest {
     account_id: String,
     is_private_note: bool,
     asset_amount: u64,
+    /// Symbol of the requested token, the first token of the faucet if omitted
+    #[serde(default)]
+    token_symbol: Option<String>,
 }
 
 #[derive(Serialize)]
 pub struct FaucetMetadataReponse {
+    tokens: Vec<TokenMetadata>,
+}
+
+#[derive(Serialize)]
+pub struct TokenMetadata {
+    token_symbol: String,
     id: String,
     asset_amount_options: Vec<u64>,
+    default_asset_amount: u64,
+}
+
+#[derive(Serialize)]
+pub struct HealthResponse {
+    node_reachable: bool,
+    /// Unix timestamp of the last successful mint in seconds.
+    last_successful_mint: Option<u64>,
+    tokens: Vec<TokenHealth>,
+}
+
+#[derive(Serialize)]
+pub struct TokenHealth {
+    token_symbol: String,
+    id: String,
+    /// Whether the faucet account is in sync with the node, i.e. the node didn't reject the last
+    /// mint for being built against an outdated faucet account state.
+    synced: bool,
 }
 
 pub async fn get_metadata(
     State(state): State<FaucetState>,
 ) -> (StatusCode, Json<FaucetMetadataReponse>) {
-    let response = FaucetMetadataReponse {
-        id: state.id.to_string(),
-        asset_amount_options: state.config.asset_amount_options.clone(),
-    };
+    let tokens = state
+        .tokens
+        .iter()
+        .map(|token| TokenMetadata {
+            token_symbol: token.config.token_symbol.clone(),
+            id: token.id.to_string(),
+            asset_amount_options: token.config.asset_amount_options.clone(),
+            default_asset_amount: token.default_asset_amount(),
+        })
+        .collect();
+
+    let response = FaucetMetadataReponse { tokens };
 
     (StatusCode::OK, Json(response))
 }
@@ -46,24 +90,42 @@ pub async fn get_tokens(
     State(state): State<FaucetState>,
     Json(req): Json<FaucetRequest>,
 ) -> Result<impl IntoResponse, HandlerError> {
+    let result = mint_tokens(&state, req).await;
+    state.metrics.record_request(&result);
+
+    result
+}
+
+async fn mint_tokens(
+    state: &FaucetState,
+    req: FaucetRequest,
+) -> Result<Response<body::BoxBody>, HandlerError> {
     info!(
         target: COMPONENT,
         account_id = %req.account_id,
         is_private_note = %req.is_private_note,
         asset_amount = %req.asset_amount,
+        token_symbol = ?req.token_symbol,
         "Received a request",
     );
 
+    let token = state.token(req.token_symbol.as_deref())?;
+
     // Check that the amount is in the asset amount options
-    if !state.config.asset_amount_options.contains(&req.asset_amount) {
+    if !token.config.asset_amount_options.contains(&req.asset_amount) {
         return Err(HandlerError::BadRequest("Invalid asset amount".to_string()));
     }
 
-    let mut client = state.client.lock().await;
-
     // Receive and hex user account id
     let target_account_id = AccountId::from_hex(req.account_id.as_str())
-        .map_err(|err| HandlerError::BadRequest(err.to_string()))?;
+        .map_err(|err| HandlerError::InvalidAccountId(err.to_string()))?;
+
+    // Requests for the same token are queued on its client
+    let mut client = {
+        let _queued = token.metrics.enqueue();
+        token.client.lock().await
+    };
+    let mint_start = Instant::now();
 
     // Execute transaction
     info!(target: COMPONENT, "Executing mint transaction for account.");
@@ -80,10 +142,20 @@ pub async fn get_tokens(
 
     // Run transaction prover & send transaction to node
     info!(target: COMPONENT, "Proving and submitting transaction.");
-    let block_height = client.prove_and_submit_transaction(executed_tx).await?;
+    let block_height = match client.prove_and_submit_transaction(executed_tx).await {
+        Ok(block_height) => block_height,
+        Err(err) => {
+            if matches!(err, FaucetClientError::StateDesync(_)) {
+                token.metrics.record_desync();
+            }
+            return Err(err.into());
+        },
+    };
 
     // Update data store with the new faucet state
+    token.metrics.record_mint(&faucet_account);
     client.data_store().update_faucet_state(faucet_account).await?;
+    state.metrics.record_mint(mint_start.elapsed());
 
     let note_id: NoteId = created_note.id();
     let note_details =
@@ -92,13 +164,18 @@ pub async fn get_tokens(
     let note_tag = NoteTag::from_account_id(target_account_id, NoteExecutionMode::Local)
         .context("failed to build note tag for local execution")?;
 
-    // Serialize note into bytes
-    let bytes = NoteFile::NoteDetails {
+    let note_file = NoteFile::NoteDetails {
         details: note_details,
         after_block_num: block_height,
         tag: Some(note_tag),
-    }
-    .to_bytes();
+    };
+
+    // Private notes can only be imported from the link if it embeds the note
+    let embedded_note = (req.is_private_note && state.embed_private_notes).then_some(&note_file);
+    let note_link = encode_note_link(note_id, embedded_note, MAX_NOTE_LINK_LEN);
+
+    // Serialize note into bytes
+    let bytes = note_file.to_bytes();
 
     info!(target: COMPONENT, %note_id, "A new note has been created");
 
@@ -108,11 +185,59 @@ pub async fn get_tokens(
         .header(header::CONTENT_TYPE, "application/octet-stream")
         .header(header::CONTENT_DISPOSITION, "attachment; filename=note.mno")
         .header("Note-Id", note_id.to_string())
+        .header("Note-Link", note_link)
         .body(body::boxed(Full::from(bytes)))
         .context("Failed to build response")
         .map_err(Into::into)
 }
 
+/// Reports whether the node is reachable and the faucet accounts are in sync with it.
+///
+/// Responds with `503 Service Unavailable` if either isn't the case, along with the same body.
+pub async fn get_health(State(state): State<FaucetState>) -> (StatusCode, Json<HealthResponse>) {
+    let request = GetBlockHeaderByNumberRequest { block_num: None, include_mmr_proof: None };
+    let node_reachable = state
+        .metrics
+        .observe_rpc(
+            RpcMethod::GetBlockHeaderByNumber,
+            state.rpc_api.clone().get_block_header_by_number(request),
+        )
+        .await
+        .is_ok();
+
+    let tokens: Vec<TokenHealth> = state
+        .tokens
+        .iter()
+        .map(|token| TokenHealth {
+            token_symbol: token.config.token_symbol.clone(),
+            id: token.id.to_string(),
+            synced: token.metrics.is_synced(),
+        })
+        .collect();
+
+    let status = if node_reachable && tokens.iter().all(|token| token.synced) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    let response = HealthResponse {
+        node_reachable,
+        last_successful_mint: state.metrics.last_successful_mint(),
+        tokens,
+    };
+
+    (status, Json(response))
+}
+
+/// Exports the metrics of the faucet in the Prometheus text format.
+pub async fn get_metrics(State(state): State<FaucetState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(&state.tokens),
+    )
+}
+
 pub async fn get_index(state: State<FaucetState>) -> Result<impl IntoResponse, HandlerError> {
     get_static_file(state, Path("index.html".to_string())).await
 }
@@ -132,3 +257,133 @@ pub async fn get_static_file(
         .context("Failed to build response")
         .map_err(Into::into)
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+    use tonic::Code;
+
+    use super::*;
+    use crate::test_utils::{
+        serve_stub_rpc, start_stub_rpc, stub_state, stub_token, stub_token_submitting_to,
+        unreachable_rpc, StubRpc, STUB_MAX_SUPPLY,
+    };
+
+    fn request(
+        account_id: &str,
+        asset_amount: u64,
+        token_symbol: Option<&str>,
+    ) -> Json<FaucetRequest> {
+        Json(FaucetRequest {
+            account_id: account_id.to_string(),
+            is_private_note: false,
+            asset_amount,
+            token_symbol: token_symbol.map(ToString::to_string),
+        })
+    }
+
+    async fn health(state: &FaucetState) -> (StatusCode, Value) {
+        let (status, Json(response)) = get_health(State(state.clone())).await;
+
+        (status, serde_json::to_value(response).unwrap())
+    }
+
+    #[tokio::test]
+    async fn requests_are_reflected_in_metrics_and_health() {
+        let state =
+            stub_state(vec![stub_token("POL", 1), stub_token("ETH", 2)], start_stub_rpc().await);
+        // Any valid account ID can request tokens, including the faucets
+        let account_id = state.tokens[1].id.to_hex();
+
+        let result = get_tokens(State(state.clone()), request("0xinvalid", 10, None)).await;
+        assert!(matches!(result, Err(HandlerError::InvalidAccountId(_))));
+        let result = get_tokens(State(state.clone()), request(&account_id, 15, None)).await;
+        assert!(matches!(result, Err(HandlerError::BadRequest(_))));
+        let result = get_tokens(State(state.clone()), request(&account_id, 10, Some("BTC"))).await;
+        assert!(matches!(result, Err(HandlerError::BadRequest(_))));
+
+        let (status, health) = health(&state).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(health["node_reachable"], true);
+        assert_eq!(health["last_successful_mint"], Value::Null);
+        assert_eq!(health["tokens"][0]["token_symbol"], "POL");
+        assert_eq!(health["tokens"][0]["synced"], true);
+        assert_eq!(health["tokens"][1]["synced"], true);
+
+        let metrics = state.metrics.render(&state.tokens);
+        for line in [
+            "miden_faucet_requests_total{outcome=\"success\"} 0".to_string(),
+            "miden_faucet_requests_total{outcome=\"invalid_account\"} 1".to_string(),
+            "miden_faucet_requests_total{outcome=\"invalid_request\"} 2".to_string(),
+            "miden_faucet_requests_total{outcome=\"node_error\"} 0".to_string(),
+            "miden_faucet_mint_duration_seconds_count 0".to_string(),
+            "miden_faucet_node_rpc_duration_seconds_count{method=\"get_block_header_by_number\"} 1"
+                .to_string(),
+            "miden_faucet_queued_requests{token=\"POL\"} 0".to_string(),
+            format!("miden_faucet_remaining_supply{{token=\"ETH\"}} {STUB_MAX_SUPPLY}"),
+        ] {
+            assert!(metrics.lines().any(|metric| metric == line), "missing {line} in\n{metrics}");
+        }
+
+        // A faucet account reported out of sync makes the faucet unhealthy
+        state.tokens[1].metrics.record_desync();
+        let (status, health) = health(&state).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(health["tokens"][0]["synced"], true);
+        assert_eq!(health["tokens"][1]["synced"], false);
+    }
+
+    #[tokio::test]
+    async fn submission_failures_are_reported_by_class() {
+        let cases = [
+            (Code::Unavailable, StatusCode::SERVICE_UNAVAILABLE, "node_unavailable"),
+            (Code::InvalidArgument, StatusCode::BAD_GATEWAY, "transaction_rejected"),
+            (Code::FailedPrecondition, StatusCode::CONFLICT, "faucet_state_desync"),
+            (
+                Code::DeadlineExceeded,
+                StatusCode::GATEWAY_TIMEOUT,
+                "submission_outcome_unknown",
+            ),
+        ];
+
+        for (code, expected_status, expected_code) in cases {
+            let stub = Arc::new(StubRpc::failing_with(code));
+            let rpc_api = serve_stub_rpc(Arc::clone(&stub)).await;
+            let state =
+                stub_state(vec![stub_token_submitting_to("POL", 1, rpc_api.clone())], rpc_api);
+            let account_id = state.tokens[0].id.to_hex();
+
+            let Err(err) = get_tokens(State(state.clone()), request(&account_id, 10, None)).await
+            else {
+                panic!("the mint must fail when the node fails with {code:?}");
+            };
+            let response = err.into_response();
+            let status = response.status();
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let body: Value = serde_json::from_slice(&body).unwrap();
+
+            assert_eq!(status, expected_status, "{code:?}");
+            assert_eq!(body["code"], expected_code, "{code:?}");
+            assert!(body["message"].is_string(), "{code:?}");
+            // Not even connectivity failures are retried, the transaction may have reached the node
+            assert_eq!(stub.submissions(), 1, "{code:?}");
+            assert_eq!(state.tokens[0].metrics.is_synced(), code != Code::FailedPrecondition);
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_node_makes_the_faucet_unhealthy() {
+        let state = stub_state(vec![stub_token("POL", 1)], unreachable_rpc());
+
+        let (status, health) = health(&state).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(health["node_reachable"], false);
+        assert_eq!(health["tokens"][0]["synced"], true);
+    }
+}
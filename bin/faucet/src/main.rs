@@ -2,8 +2,12 @@ mod client;
 mod config;
 mod errors;
 mod handlers;
+mod metrics;
+mod note_link;
 mod state;
 mod store;
+#[cfg(test)]
+mod test_utils;
 
 use std::path::PathBuf;
 
@@ -15,7 +19,11 @@ use axum::{
 use clap::{Parser, Subcommand};
 use http::HeaderValue;
 use miden_lib::{accounts::faucets::create_basic_fungible_faucet, AuthScheme};
-use miden_node_utils::{config::load_config, crypto::get_rpo_random_coin, version::LongVersion};
+use miden_node_utils::{
+    config::{load_config_with, UnknownKeys},
+    crypto::get_rpo_random_coin,
+    version::LongVersion,
+};
 use miden_objects::{
     accounts::{AccountData, AccountStorageMode, AuthSecretKey},
     assets::TokenSymbol,
@@ -25,14 +33,13 @@ use miden_objects::{
 use rand::Rng;
 use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
 use state::FaucetState;
-use tokio::net::TcpListener;
 use tower::ServiceBuilder;
 use tower_http::{cors::CorsLayer, set_header::SetResponseHeaderLayer, trace::TraceLayer};
-use tracing::info;
+use tracing::{error, info};
 
 use crate::{
-    config::{FaucetConfig, DEFAULT_FAUCET_ACCOUNT_PATH},
-    handlers::{get_index, get_metadata, get_static_file, get_tokens},
+    config::{FaucetConfig, TokenConfig, DEFAULT_FAUCET_ACCOUNT_PATH, DEFAULT_TOKEN_SYMBOL},
+    handlers::{get_health, get_index, get_metadata, get_metrics, get_static_file, get_tokens},
 };
 
 // CONSTANTS
@@ -49,6 +56,10 @@ const FAUCET_CONFIG_FILE_PATH: &str = "miden-faucet.toml";
 pub struct Cli {
     #[command(subcommand)]
     pub command: Command,
+
+    /// Log unknown keys of the configuration file as warnings instead of rejecting them
+    #[arg(long, global = true)]
+    pub lenient_config: bool,
 }
 
 #[derive(Subcommand)]
@@ -77,6 +88,9 @@ pub enum Command {
         config_path: String,
         #[arg(short, long, default_value = DEFAULT_FAUCET_ACCOUNT_PATH)]
         faucet_account_path: String,
+        /// Symbol of the token minted by the faucet account
+        #[arg(short, long, default_value = DEFAULT_TOKEN_SYMBOL)]
+        token_symbol: String,
     },
 }
 
@@ -91,8 +105,13 @@ async fn main() -> anyhow::Result<()> {
 
     match &cli.command {
         Command::Start { config } => {
-            let config: FaucetConfig =
-                load_config(config).context("Failed to load configuration file")?;
+            let unknown_keys = if cli.lenient_config {
+                UnknownKeys::Warn
+            } else {
+                UnknownKeys::Deny
+            };
+            let config: FaucetConfig = load_config_with(config, unknown_keys)
+                .context("Failed to load configuration file")?;
 
             let faucet_state = FaucetState::new(config.clone()).await?;
 
@@ -102,6 +121,7 @@ async fn main() -> anyhow::Result<()> {
                 .route("/", get(get_index))
                 .route("/get_metadata", get(get_metadata))
                 .route("/get_tokens", post(get_tokens))
+                .route("/health", get(get_health))
                 .route("/*path", get(get_static_file))
                 .layer(
                     ServiceBuilder::new()
@@ -116,13 +136,34 @@ async fn main() -> anyhow::Result<()> {
                                 .allow_methods(tower_http::cors::Any),
                         ),
                 )
-                .with_state(faucet_state);
-
-            let listener = TcpListener::bind((config.endpoint.host.as_str(), config.endpoint.port))
-                .await
-                .context("Failed to bind TCP listener")?;
-
-            info!(target: COMPONENT, endpoint = %config.endpoint, "Server started");
+                .with_state(faucet_state.clone());
+
+            if let Some(metrics_endpoint) = &config.metrics_endpoint {
+                let metrics_app =
+                    Router::new().route("/metrics", get(get_metrics)).with_state(faucet_state);
+                let listener =
+                    metrics_endpoint.bind().await.context("Failed to bind metrics TCP listener")?;
+                let local_addr =
+                    listener.local_addr().context("Failed to read the metrics server address")?;
+
+                info!(
+                    target: COMPONENT,
+                    endpoint = %metrics_endpoint,
+                    %local_addr,
+                    "Metrics server started"
+                );
+
+                tokio::spawn(async move {
+                    if let Err(err) = axum::serve(listener, metrics_app).await {
+                        error!(target: COMPONENT, %err, "Metrics server failed");
+                    }
+                });
+            }
+
+            let listener = config.endpoint.bind().await.context("Failed to bind TCP listener")?;
+            let local_addr = listener.local_addr().context("Failed to read the server address")?;
+
+            info!(target: COMPONENT, endpoint = %config.endpoint, %local_addr, "Server started");
 
             axum::serve(listener, app).await.unwrap();
         },
@@ -165,14 +206,22 @@ async fn main() -> anyhow::Result<()> {
             println!("Faucet account file successfully created at: {output_path:?}");
         },
 
-        Command::Init { config_path, faucet_account_path } => {
+        Command::Init {
+            config_path,
+            faucet_account_path,
+            token_symbol,
+        } => {
             let current_dir =
                 std::env::current_dir().context("Failed to open current directory")?;
 
             let config_file_path = current_dir.join(config_path);
 
             let config = FaucetConfig {
-                faucet_account_path: faucet_account_path.into(),
+                tokens: vec![TokenConfig {
+                    token_symbol: token_symbol.clone(),
+                    faucet_account_path: faucet_account_path.into(),
+                    ..TokenConfig::default()
+                }],
                 ..FaucetConfig::default()
             };
 
@@ -0,0 +1,395 @@
+//! Metrics of the faucet, exported in the Prometheus text format.
+
+use std::{
+    fmt::Write,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use miden_objects::{accounts::Account, Word};
+
+use crate::{
+    errors::{FaucetClientError, HandlerError},
+    state::TokenFaucet,
+};
+
+/// Upper bounds of the buckets of the mint durations, in seconds.
+const MINT_DURATION_BUCKETS: &[f64] = &[1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0];
+
+/// Upper bounds of the buckets of the node RPC call durations, in seconds.
+const RPC_DURATION_BUCKETS: &[f64] =
+    &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Storage slot of a fungible faucet holding its total issuance, as the last element.
+const FAUCET_DATA_SLOT: u8 = 0;
+
+/// Storage slot of a basic fungible faucet holding its token metadata, as
+/// `[max_supply, decimals, token_symbol, 0]`.
+const TOKEN_METADATA_SLOT: u8 = 2;
+
+// REQUEST OUTCOME
+// ================================================================================================
+
+/// Outcome of a token request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestOutcome {
+    Success,
+    InvalidAccount,
+    InvalidRequest,
+    NodeError,
+    InternalError,
+}
+
+impl RequestOutcome {
+    const ALL: [Self; 5] = [
+        Self::Success,
+        Self::InvalidAccount,
+        Self::InvalidRequest,
+        Self::NodeError,
+        Self::InternalError,
+    ];
+
+    /// Returns the outcome of a request handled with `result`.
+    pub fn of<T>(result: &Result<T, HandlerError>) -> Self {
+        match result {
+            Ok(_) => Self::Success,
+            Err(HandlerError::InvalidAccountId(_)) => Self::InvalidAccount,
+            Err(
+                HandlerError::BadRequest(_)
+                | HandlerError::NotFound(_)
+                | HandlerError::ClientError(FaucetClientError::InvalidRequest(_)),
+            ) => Self::InvalidRequest,
+            Err(HandlerError::ClientError(
+                FaucetClientError::Connectivity(_)
+                | FaucetClientError::SubmissionRejected { .. }
+                | FaucetClientError::SubmissionOutcomeUnknown(_)
+                | FaucetClientError::StateDesync(_)
+                | FaucetClientError::RequestError(_),
+            )) => Self::NodeError,
+            Err(
+                HandlerError::ClientError(FaucetClientError::Other(_)) | HandlerError::Internal(_),
+            ) => Self::InternalError,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::InvalidAccount => "invalid_account",
+            Self::InvalidRequest => "invalid_request",
+            Self::NodeError => "node_error",
+            Self::InternalError => "internal_error",
+        }
+    }
+}
+
+// RPC METHOD
+// ================================================================================================
+
+/// Node RPC methods called by the faucet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcMethod {
+    GetAccountDetails,
+    GetBlockHeaderByNumber,
+    SubmitProvenTransaction,
+}
+
+impl RpcMethod {
+    const ALL: [Self; 3] = [
+        Self::GetAccountDetails,
+        Self::GetBlockHeaderByNumber,
+        Self::SubmitProvenTransaction,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::GetAccountDetails => "get_account_details",
+            Self::GetBlockHeaderByNumber => "get_block_header_by_number",
+            Self::SubmitProvenTransaction => "submit_proven_transaction",
+        }
+    }
+}
+
+// FAUCET METRICS
+// ================================================================================================
+
+/// Metrics of the faucet, shared by the handlers and the clients of the tokens.
+///
+/// The metrics of the individual tokens are kept by [TokenMetrics].
+#[derive(Debug)]
+pub struct FaucetMetrics {
+    requests: [AtomicU64; RequestOutcome::ALL.len()],
+    mint_duration: Histogram,
+    rpc_duration: [Histogram; RpcMethod::ALL.len()],
+    /// Unix timestamp of the last successful mint in seconds, zero if there was none.
+    last_successful_mint: AtomicU64,
+}
+
+impl Default for FaucetMetrics {
+    fn default() -> Self {
+        Self {
+            requests: Default::default(),
+            mint_duration: Histogram::new(MINT_DURATION_BUCKETS),
+            rpc_duration: RpcMethod::ALL.map(|_| Histogram::new(RPC_DURATION_BUCKETS)),
+            last_successful_mint: AtomicU64::new(0),
+        }
+    }
+}
+
+impl FaucetMetrics {
+    /// Records a token request handled with `result`.
+    pub fn record_request<T>(&self, result: &Result<T, HandlerError>) {
+        self.requests[RequestOutcome::of(result) as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a successful mint, which took `duration` from the execution of its transaction to
+    /// its submission.
+    pub fn record_mint(&self, duration: Duration) {
+        self.mint_duration.observe(duration);
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        self.last_successful_mint.store(now.as_secs(), Ordering::Relaxed);
+    }
+
+    /// Returns the Unix timestamp of the last successful mint in seconds, if any.
+    pub fn last_successful_mint(&self) -> Option<u64> {
+        Some(self.last_successful_mint.load(Ordering::Relaxed)).filter(|timestamp| *timestamp > 0)
+    }
+
+    /// Awaits the node RPC call, recording its duration.
+    pub async fn observe_rpc<T>(&self, method: RpcMethod, call: impl Future<Output = T>) -> T {
+        let start = Instant::now();
+        let output = call.await;
+        self.rpc_duration[method as usize].observe(start.elapsed());
+
+        output
+    }
+
+    /// Renders the metrics of the faucet and its `tokens` in the Prometheus text format.
+    pub fn render(&self, tokens: &[TokenFaucet]) -> String {
+        let mut out = String::new();
+
+        write_header(&mut out, "requests_total", "counter", "Token requests, by outcome.");
+        for outcome in RequestOutcome::ALL {
+            let count = self.requests[outcome as usize].load(Ordering::Relaxed);
+            writeln!(out, "miden_faucet_requests_total{{outcome=\"{}\"}} {count}", outcome.label())
+                .unwrap();
+        }
+
+        write_header(
+            &mut out,
+            "mint_duration_seconds",
+            "histogram",
+            "Duration of the successful mints, from the execution of the transaction to its \
+             submission.",
+        );
+        self.mint_duration.render(&mut out, "mint_duration_seconds", "");
+
+        write_header(
+            &mut out,
+            "node_rpc_duration_seconds",
+            "histogram",
+            "Duration of the node RPC calls, including their retries.",
+        );
+        for method in RpcMethod::ALL {
+            let labels = format!("method=\"{}\",", method.label());
+            self.rpc_duration[method as usize].render(
+                &mut out,
+                "node_rpc_duration_seconds",
+                &labels,
+            );
+        }
+
+        write_header(
+            &mut out,
+            "queued_requests",
+            "gauge",
+            "Requests waiting for the client of their token.",
+        );
+        for token in tokens {
+            let queued = token.metrics.queued.load(Ordering::Relaxed);
+            writeln!(
+                out,
+                "miden_faucet_queued_requests{{token=\"{}\"}} {queued}",
+                token.config.token_symbol
+            )
+            .unwrap();
+        }
+
+        write_header(
+            &mut out,
+            "remaining_supply",
+            "gauge",
+            "Amount of the token which can still be minted.",
+        );
+        for token in tokens {
+            if let Some(remaining) = token.metrics.remaining_supply() {
+                writeln!(
+                    out,
+                    "miden_faucet_remaining_supply{{token=\"{}\"}} {remaining}",
+                    token.config.token_symbol
+                )
+                .unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+// TOKEN METRICS
+// ================================================================================================
+
+/// Metrics of a token dispensed by the faucet.
+#[derive(Debug)]
+pub struct TokenMetrics {
+    /// Number of requests waiting for the client of the token.
+    queued: AtomicU64,
+    /// Whether the node reported the faucet account to be out of sync, cleared by the next
+    /// successful mint.
+    desynced: AtomicBool,
+    remaining_supply: Mutex<Option<u64>>,
+}
+
+impl TokenMetrics {
+    /// Creates the metrics of a token minted by the `faucet_account`.
+    pub fn new(faucet_account: &Account) -> Self {
+        Self {
+            queued: AtomicU64::new(0),
+            desynced: AtomicBool::new(false),
+            remaining_supply: Mutex::new(remaining_supply(faucet_account)),
+        }
+    }
+
+    /// Counts a request as queued until the returned guard is dropped.
+    pub fn enqueue(&self) -> QueuedRequest<'_> {
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        QueuedRequest(&self.queued)
+    }
+
+    /// Records a successful mint, leaving the faucet account in the `faucet_account` state.
+    pub fn record_mint(&self, faucet_account: &Account) {
+        self.desynced.store(false, Ordering::Relaxed);
+        *self.remaining_supply.lock().expect("Poisoned lock") = remaining_supply(faucet_account);
+    }
+
+    /// Records that the node reported the faucet account to be out of sync.
+    pub fn record_desync(&self) {
+        self.desynced.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the faucet account is in sync with the node, as far as the faucet knows.
+    pub fn is_synced(&self) -> bool {
+        !self.desynced.load(Ordering::Relaxed)
+    }
+
+    fn remaining_supply(&self) -> Option<u64> {
+        *self.remaining_supply.lock().expect("Poisoned lock")
+    }
+}
+
+/// A request waiting for the client of its token, see [TokenMetrics::enqueue].
+pub struct QueuedRequest<'a>(&'a AtomicU64);
+
+impl Drop for QueuedRequest<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns the amount which the fungible faucet can still mint, or `None` if its storage doesn't
+/// follow the layout of a basic fungible faucet.
+fn remaining_supply(faucet_account: &Account) -> Option<u64> {
+    let storage = faucet_account.storage();
+    let metadata: Word = storage.get_item(TOKEN_METADATA_SLOT).ok()?.into();
+    let faucet_data: Word = storage.get_item(FAUCET_DATA_SLOT).ok()?.into();
+
+    metadata[0].as_int().checked_sub(faucet_data[3].as_int())
+}
+
+// HISTOGRAM
+// ================================================================================================
+
+#[derive(Debug)]
+struct Histogram {
+    /// Upper bounds of the buckets in seconds, in increasing order.
+    bounds: &'static [f64],
+    /// Number of observations of every bucket, and of the observations above the last bound.
+    counts: Vec<AtomicU64>,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let bucket = self.bounds.partition_point(|bound| *bound < seconds);
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Renders the histogram as the `name` metric, `labels` being the comma-terminated labels of
+    /// the series.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            cumulative += count.load(Ordering::Relaxed);
+            let bound = self.bounds.get(bucket).map_or("+Inf".to_string(), ToString::to_string);
+            writeln!(out, "miden_faucet_{name}_bucket{{{labels}le=\"{bound}\"}} {cumulative}")
+                .unwrap();
+        }
+
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let labels = match labels.trim_end_matches(',') {
+            "" => String::new(),
+            labels => format!("{{{labels}}}"),
+        };
+        writeln!(out, "miden_faucet_{name}_sum{labels} {sum}").unwrap();
+        writeln!(out, "miden_faucet_{name}_count{labels} {cumulative}").unwrap();
+    }
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP miden_faucet_{name} {help}").unwrap();
+    writeln!(out, "# TYPE miden_faucet_{name} {kind}").unwrap();
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histograms_are_rendered_cumulatively() {
+        let histogram = Histogram::new(&[0.1, 1.0]);
+        histogram.observe(Duration::from_millis(50));
+        histogram.observe(Duration::from_millis(100));
+        histogram.observe(Duration::from_millis(500));
+        histogram.observe(Duration::from_secs(3));
+
+        let mut out = String::new();
+        histogram.render(&mut out, "test_seconds", "method=\"test\",");
+
+        assert_eq!(
+            out,
+            "miden_faucet_test_seconds_bucket{method=\"test\",le=\"0.1\"} 2\n\
+             miden_faucet_test_seconds_bucket{method=\"test\",le=\"1\"} 3\n\
+             miden_faucet_test_seconds_bucket{method=\"test\",le=\"+Inf\"} 4\n\
+             miden_faucet_test_seconds_sum{method=\"test\"} 3.65\n\
+             miden_faucet_test_seconds_count{method=\"test\"} 4\n"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! Deep links to the notes created by the faucet.
+//!
+//! A note link is a URI which wallets can open, or scan from a QR code, to import a note:
+//!
+//! - `miden://note/<note ID>` refers to a note by its hex-encoded ID. This is enough for public
+//!   notes, whose details wallets fetch from the node.
+//! - `miden://note/<note ID>?file=<note file>` additionally carries the serialized [NoteFile],
+//!   base64url-encoded without padding. This is required for private notes, whose details are not
+//!   known to the node.
+//!
+//! Links are capped in length so that they fit in a QR code readable by phone cameras. Links to
+//! notes whose file exceeds the cap fall back to the note ID.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use miden_objects::{
+    notes::{NoteFile, NoteId},
+    utils::serde::{Deserializable, DeserializationError, Serializable},
+    Digest,
+};
+use thiserror::Error;
+
+/// Prefix of all note links.
+pub const NOTE_LINK_PREFIX: &str = "miden://note/";
+
+/// Maximum length of the note links returned by the faucet.
+///
+/// QR codes hold up to 2953 bytes, but the largest ones are hard to scan from a screen.
+pub const MAX_NOTE_LINK_LEN: usize = 2048;
+
+const FILE_PARAM: &str = "?file=";
+
+/// A decoded note link.
+///
+/// The faucet only encodes links, decoding is the reference for the wallets adopting the format.
+#[allow(dead_code)]
+pub struct NoteLink {
+    pub note_id: NoteId,
+    pub note_file: Option<NoteFile>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Error)]
+pub enum NoteLinkError {
+    #[error("Note links must start with {NOTE_LINK_PREFIX}")]
+    InvalidPrefix,
+    #[error("Invalid note ID {0}")]
+    InvalidNoteId(String),
+    #[error("Invalid note file encoding: {0}")]
+    InvalidEncoding(#[from] base64::DecodeError),
+    #[error("Invalid note file: {0}")]
+    InvalidNoteFile(#[from] DeserializationError),
+    #[error("Note file of note {file_note_id} linked as note {note_id}")]
+    NoteIdMismatch { note_id: NoteId, file_note_id: NoteId },
+}
+
+/// Encodes the link to the note `note_id`, embedding the `note_file` if given and if the link
+/// doesn't exceed `max_len` with it.
+pub fn encode_note_link(note_id: NoteId, note_file: Option<&NoteFile>, max_len: usize) -> String {
+    let link = format!("{NOTE_LINK_PREFIX}{note_id}");
+
+    let Some(note_file) = note_file else {
+        return link;
+    };
+
+    let note_file = URL_SAFE_NO_PAD.encode(note_file.to_bytes());
+    if link.len() + FILE_PARAM.len() + note_file.len() > max_len {
+        return link;
+    }
+
+    format!("{link}{FILE_PARAM}{note_file}")
+}
+
+/// Decodes a note link, checking that the embedded note file, if any, is the one of the linked
+/// note.
+#[allow(dead_code)]
+pub fn decode_note_link(link: &str) -> Result<NoteLink, NoteLinkError> {
+    let link = link.strip_prefix(NOTE_LINK_PREFIX).ok_or(NoteLinkError::InvalidPrefix)?;
+    let (note_id, note_file) = match link.split_once(FILE_PARAM) {
+        Some((note_id, note_file)) => (note_id, Some(note_file)),
+        None => (link, None),
+    };
+
+    let note_id = Digest::try_from(note_id)
+        .map(NoteId::from)
+        .map_err(|_| NoteLinkError::InvalidNoteId(note_id.to_string()))?;
+
+    let note_file = note_file
+        .map(|note_file| -> Result<_, NoteLinkError> {
+            let note_file = NoteFile::read_from_bytes(&URL_SAFE_NO_PAD.decode(note_file)?)?;
+
+            let file_note_id = match &note_file {
+                NoteFile::NoteId(note_id) => *note_id,
+                NoteFile::NoteDetails { details, .. } => details.id(),
+                NoteFile::NoteWithProof(note, _) => note.id(),
+            };
+            if file_note_id != note_id {
+                return Err(NoteLinkError::NoteIdMismatch { note_id, file_note_id });
+            }
+
+            Ok(note_file)
+        })
+        .transpose()?;
+
+    Ok(NoteLink { note_id, note_file })
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_lib::notes::create_p2id_note;
+    use miden_objects::{
+        accounts::{
+            account_id::testing::{
+                ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN,
+                ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN,
+            },
+            AccountId,
+        },
+        assets::FungibleAsset,
+        crypto::rand::RpoRandomCoin,
+        notes::{NoteDetails, NoteType},
+        Felt, ZERO,
+    };
+
+    use super::*;
+
+    /// Returns the file of a private P2ID note, as dispensed by the faucet.
+    fn note_file(seed: u64) -> (NoteId, NoteFile) {
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let target =
+            AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN).unwrap();
+        let asset = FungibleAsset::new(faucet_id, 100).unwrap().into();
+        let mut rng = RpoRandomCoin::new([ZERO, ZERO, ZERO, Felt::new(seed)]);
+        let note =
+            create_p2id_note(faucet_id, target, vec![asset], NoteType::Private, ZERO, &mut rng)
+                .unwrap();
+
+        let details = NoteDetails::new(note.assets().clone(), note.recipient().clone());
+        let note_file = NoteFile::NoteDetails { details, after_block_num: 7, tag: None };
+
+        (note.id(), note_file)
+    }
+
+    #[test]
+    fn note_links_round_trip() {
+        let (note_id, file) = note_file(1);
+
+        let link = encode_note_link(note_id, None, MAX_NOTE_LINK_LEN);
+        assert_eq!(link, format!("miden://note/{note_id}"));
+        let decoded = decode_note_link(&link).unwrap();
+        assert_eq!(decoded.note_id, note_id);
+        assert!(decoded.note_file.is_none());
+
+        let link = encode_note_link(note_id, Some(&file), MAX_NOTE_LINK_LEN);
+        assert!(link.len() <= MAX_NOTE_LINK_LEN);
+        assert!(link.contains("?file="));
+        let decoded = decode_note_link(&link).unwrap();
+        assert_eq!(decoded.note_id, note_id);
+        assert_eq!(decoded.note_file.unwrap().to_bytes(), file.to_bytes());
+    }
+
+    #[test]
+    fn oversized_note_files_are_not_embedded() {
+        let (note_id, file) = note_file(1);
+
+        let link = encode_note_link(note_id, Some(&file), 100);
+        assert_eq!(link, format!("miden://note/{note_id}"));
+    }
+
+    #[test]
+    fn invalid_note_links_are_rejected() {
+        let (note_id, file) = note_file(1);
+        let (other_note_id, _) = note_file(2);
+        let link = encode_note_link(note_id, Some(&file), MAX_NOTE_LINK_LEN);
+
+        let err = decode_note_link(&link.replace("miden://", "https://")).unwrap_err();
+        assert!(matches!(err, NoteLinkError::InvalidPrefix));
+
+        let err = decode_note_link("miden://note/0x1234").unwrap_err();
+        assert!(matches!(err, NoteLinkError::InvalidNoteId(_)));
+
+        let err = decode_note_link(&format!("{link}!")).unwrap_err();
+        assert!(matches!(err, NoteLinkError::InvalidEncoding(_)));
+
+        let mismatched = link.replace(&note_id.to_string(), &other_note_id.to_string());
+        let err = decode_note_link(&mismatched).unwrap_err();
+        assert!(matches!(err, NoteLinkError::NoteIdMismatch { .. }));
+    }
+}
@@ -1,36 +1,258 @@
 use std::{collections::HashMap, sync::Arc};
 
+use anyhow::Context;
+use miden_node_proto::generated::rpc::api_client::ApiClient;
 use miden_objects::accounts::AccountId;
 use static_files::Resource;
 use tokio::sync::Mutex;
+use tonic::transport::Channel;
 use tracing::info;
 
-use crate::{client::FaucetClient, config::FaucetConfig, static_resources, COMPONENT};
+use crate::{
+    client::{connect_node, FaucetClient},
+    config::{FaucetConfig, TokenConfig},
+    errors::HandlerError,
+    metrics::{FaucetMetrics, TokenMetrics},
+    static_resources, COMPONENT,
+};
+
+// TOKEN FAUCET
+// ================================================================================================
+
+/// A token dispensed by the faucet, minted by its own faucet account.
+///
+/// Every token has its own client, hence its own faucet account state and its own queue of mint
+/// requests waiting for the client. A token whose faucet is exhausted or out of sync doesn't hold
+/// up the requests of the other tokens.
+pub struct TokenFaucet {
+    pub id: AccountId,
+    pub config: TokenConfig,
+    pub client: Mutex<FaucetClient>,
+    pub metrics: TokenMetrics,
+}
+
+impl TokenFaucet {
+    pub fn new(config: TokenConfig, client: FaucetClient) -> Self {
+        Self {
+            id: client.get_faucet_id(),
+            config,
+            metrics: TokenMetrics::new(&client.data_store().faucet_account()),
+            client: Mutex::new(client),
+        }
+    }
+
+    /// Returns the amount preselected in the faucet website.
+    pub fn default_asset_amount(&self) -> u64 {
+        self.config
+            .default_asset_amount
+            .or(self.config.asset_amount_options.first().copied())
+            .unwrap_or_default()
+    }
+}
 
 // FAUCET STATE
 // ================================================================================================
 
-/// Stores the client and additional information needed to handle requests.
+/// Stores the clients and additional information needed to handle requests.
 ///
-/// The state is passed to every mint transaction request so the client is
+/// The state is passed to every mint transaction request so the clients are
 /// shared between handler threads.
 #[derive(Clone)]
 pub struct FaucetState {
-    pub id: AccountId,
-    pub client: Arc<Mutex<FaucetClient>>,
-    pub config: FaucetConfig,
+    /// Faucets of the dispensed tokens, in the order of the configuration.
+    pub tokens: Arc<Vec<TokenFaucet>>,
     pub static_files: Arc<HashMap<&'static str, Resource>>,
+    /// Whether private notes are embedded in the note links, see [FaucetConfig].
+    pub embed_private_notes: bool,
+    /// Client of the node used to check its health, independently of the clients of the tokens.
+    pub rpc_api: ApiClient<Channel>,
+    pub metrics: Arc<FaucetMetrics>,
 }
 
 impl FaucetState {
     pub async fn new(config: FaucetConfig) -> anyhow::Result<Self> {
-        let client = FaucetClient::new(&config).await?;
-        let id = client.get_faucet_id();
-        let client = Arc::new(Mutex::new(client));
-        let static_files = Arc::new(static_resources::generate());
+        validate_tokens(&config.tokens)?;
+
+        let metrics = Arc::new(FaucetMetrics::default());
+        let mut tokens: Vec<TokenFaucet> = Vec::with_capacity(config.tokens.len());
+        for token in &config.tokens {
+            let client = FaucetClient::new(&config, &token.faucet_account_path, metrics.clone())
+                .await
+                .with_context(|| format!("Failed to initialize {} faucet", token.token_symbol))?;
+            let token = TokenFaucet::new(token.clone(), client);
+
+            if let Some(other) = tokens.iter().find(|other| other.id == token.id) {
+                anyhow::bail!(
+                    "Tokens {} and {} are minted by the same faucet account {}",
+                    other.config.token_symbol,
+                    token.config.token_symbol,
+                    token.id
+                );
+            }
+
+            info!(
+                target: COMPONENT,
+                token_symbol = %token.config.token_symbol,
+                account_id = %token.id,
+                "Faucet initialization successful"
+            );
+
+            tokens.push(token);
+        }
+
+        let rpc_api = connect_node(&config).await.context("Failed to connect to the node")?;
+
+        Ok(Self {
+            embed_private_notes: config.embed_private_notes,
+            ..Self::from_tokens(tokens, rpc_api, metrics)
+        })
+    }
+
+    /// Creates the state of a faucet dispensing the `tokens`, with the default options.
+    pub fn from_tokens(
+        tokens: Vec<TokenFaucet>,
+        rpc_api: ApiClient<Channel>,
+        metrics: Arc<FaucetMetrics>,
+    ) -> Self {
+        FaucetState {
+            tokens: Arc::new(tokens),
+            static_files: Arc::new(static_resources::generate()),
+            embed_private_notes: true,
+            rpc_api,
+            metrics,
+        }
+    }
+
+    /// Returns the faucet of the token with the given symbol, or of the first configured token if
+    /// no symbol is given.
+    pub fn token(&self, token_symbol: Option<&str>) -> Result<&TokenFaucet, HandlerError> {
+        let token = match token_symbol {
+            Some(symbol) => self.tokens.iter().find(|token| token.config.token_symbol == symbol),
+            None => self.tokens.first(),
+        };
+
+        token.ok_or_else(|| {
+            HandlerError::BadRequest(format!("Unknown token {}", token_symbol.unwrap_or_default()))
+        })
+    }
+}
+
+/// Checks that the tokens can be told apart, and that their amount options are consistent.
+fn validate_tokens(tokens: &[TokenConfig]) -> anyhow::Result<()> {
+    anyhow::ensure!(!tokens.is_empty(), "No token configured");
+
+    for (index, token) in tokens.iter().enumerate() {
+        let symbol = &token.token_symbol;
+
+        anyhow::ensure!(
+            tokens[..index].iter().all(|other| other.token_symbol != *symbol),
+            "Token {symbol} is configured more than once"
+        );
+        anyhow::ensure!(
+            !token.asset_amount_options.is_empty(),
+            "Token {symbol} has no asset amount options"
+        );
+        if let Some(amount) = token.default_asset_amount {
+            anyhow::ensure!(
+                token.asset_amount_options.contains(&amount),
+                "Default asset amount {amount} of token {symbol} is not one of its options"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{
+        accounts::account_id::testing::ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN,
+        assets::Asset, Felt,
+    };
+
+    use super::*;
+    use crate::test_utils::{stub_state, stub_token, unreachable_rpc};
+
+    /// Executes a mint transaction and applies it to the faucet account, returning the minted
+    /// asset.
+    async fn mint(token: &TokenFaucet, amount: u64) -> Asset {
+        let mut client = token.client.lock().await;
+        let target_account_id =
+            AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN).unwrap();
+
+        let (executed_tx, note) =
+            client.execute_mint_transaction(target_account_id, false, amount).unwrap();
+
+        let mut faucet_account = client.data_store().faucet_account();
+        faucet_account.apply_delta(executed_tx.account_delta()).unwrap();
+        client.data_store().update_faucet_state(faucet_account).await.unwrap();
+
+        *note.assets().iter().next().unwrap()
+    }
+
+    async fn nonce(token: &TokenFaucet) -> Felt {
+        token.client.lock().await.data_store().faucet_account().nonce()
+    }
+
+    #[tokio::test]
+    async fn tokens_are_minted_by_their_own_faucet() {
+        let state = stub_state(vec![stub_token("POL", 1), stub_token("ETH", 2)], unreachable_rpc());
+        let pol = state.token(Some("POL")).unwrap();
+        let eth = state.token(Some("ETH")).unwrap();
+        assert_ne!(pol.id, eth.id);
+
+        let asset = mint(pol, 10).await;
+        assert_eq!(asset.faucet_id(), pol.id);
+        let asset = mint(pol, 20).await;
+        assert_eq!(asset.faucet_id(), pol.id);
+
+        // Each faucet account tracks its own nonce
+        assert_eq!(nonce(pol).await, Felt::new(2));
+        assert_eq!(nonce(eth).await, Felt::new(0));
+
+        let asset = mint(eth, 10).await;
+        assert_eq!(asset.faucet_id(), eth.id);
+        assert_eq!(nonce(pol).await, Felt::new(2));
+        assert_eq!(nonce(eth).await, Felt::new(1));
+    }
+
+    #[tokio::test]
+    async fn requests_are_routed_by_token_symbol() {
+        let state = stub_state(vec![stub_token("POL", 1), stub_token("ETH", 2)], unreachable_rpc());
+
+        assert_eq!(state.token(Some("ETH")).unwrap().config.token_symbol, "ETH");
+        assert_eq!(state.token(Some("POL")).unwrap().config.token_symbol, "POL");
+        // Requests which don't select a token are served by the first one
+        assert_eq!(state.token(None).unwrap().config.token_symbol, "POL");
+        assert!(matches!(state.token(Some("BTC")), Err(HandlerError::BadRequest(_))));
+
+        // A busy faucet doesn't hold up the requests of the other tokens
+        let _pol_client = state.token(Some("POL")).unwrap().client.lock().await;
+        assert!(state.token(Some("ETH")).unwrap().client.try_lock().is_ok());
+    }
 
-        info!(target: COMPONENT, account_id = %id, "Faucet initialization successful");
+    #[test]
+    fn inconsistent_tokens_are_rejected() {
+        let token = |symbol: &str| TokenConfig {
+            token_symbol: symbol.to_string(),
+            ..TokenConfig::default()
+        };
 
-        Ok(FaucetState { client, id, config, static_files })
+        assert!(validate_tokens(&[token("POL"), token("ETH")]).is_ok());
+        assert!(validate_tokens(&[]).is_err());
+        assert!(validate_tokens(&[token("POL"), token("POL")]).is_err());
+        assert!(validate_tokens(&[TokenConfig {
+            asset_amount_options: vec![],
+            ..token("POL")
+        }])
+        .is_err());
+        assert!(validate_tokens(&[TokenConfig {
+            default_asset_amount: Some(42),
+            ..token("POL")
+        }])
+        .is_err());
     }
 }
@@ -8,25 +8,34 @@ document.addEventListener('DOMContentLoaded', function () {
     const importCommand = document.getElementById('import-command');
     const noteIdElem = document.getElementById('note-id');
     const accountIdElem = document.getElementById('command-account-id');
+    const noteLinkContainer = document.getElementById('note-link-container');
+    const noteQrCode = document.getElementById('note-qr-code');
+    const noteLinkElem = document.getElementById('note-link');
+    const tokenSelect = document.getElementById('token-symbol');
     const assetSelect = document.getElementById('asset-amount');
     const loading = document.getElementById('loading');
+    let tokens = [];
 
     fetchMetadata();
 
     privateButton.addEventListener('click', () => {handleButtonClick(true)});
     publicButton.addEventListener('click', () => {handleButtonClick(false)});
+    tokenSelect.addEventListener('change', () => {selectToken(tokenSelect.value)});
 
     function fetchMetadata() {
         fetch(window.location.href + 'get_metadata')
             .then(response => response.json())
             .then(data => {
-                faucetIdElem.textContent = data.id;
-                for (const amount of data.asset_amount_options){
+                tokens = data.tokens;
+                for (const token of tokens) {
                     const option = document.createElement('option');
-                    option.value = amount;
-                    option.textContent = amount;
-                    assetSelect.appendChild(option);
+                    option.value = token.token_symbol;
+                    option.textContent = token.token_symbol;
+                    tokenSelect.appendChild(option);
                 }
+                // A single token doesn't need to be selected
+                tokenSelect.style.display = tokens.length > 1 ? 'block' : 'none';
+                selectToken(tokens[0].token_symbol);
             })
             .catch(error => {
                 console.error('Error fetching metadata:', error);
@@ -36,6 +45,20 @@ document.addEventListener('DOMContentLoaded', function () {
             });
     }
 
+    function selectToken(tokenSymbol) {
+        const token = tokens.find(token => token.token_symbol === tokenSymbol);
+        faucetIdElem.textContent = token.id;
+
+        assetSelect.replaceChildren();
+        for (const amount of token.asset_amount_options) {
+            const option = document.createElement('option');
+            option.value = amount;
+            option.textContent = amount;
+            option.selected = amount === token.default_asset_amount;
+            assetSelect.appendChild(option);
+        }
+    }
+
     async function handleButtonClick(isPrivateNote) {
         let accountId = accountIdInput.value.trim();
         errorMessage.style.display = 'none';
@@ -57,11 +80,17 @@ document.addEventListener('DOMContentLoaded', function () {
             const response = await fetch(window.location.href + 'get_tokens', {
                 method: 'POST',
                 headers: { 'Content-Type': 'application/json' },
-                body: JSON.stringify({ account_id: accountId, is_private_note: isPrivateNote, asset_amount: parseInt(assetSelect.value)})
+                body: JSON.stringify({
+                    account_id: accountId,
+                    is_private_note: isPrivateNote,
+                    asset_amount: parseInt(assetSelect.value),
+                    token_symbol: tokenSelect.value,
+                })
             });
 
             if (!response.ok) {
-                throw new Error(`HTTP error! Status: ${response.status}`);
+                const error = await response.json().catch(() => null);
+                throw new Error(error ? error.message : `HTTP error! Status: ${response.status}`);
             }
 
             const blob = await response.blob();
@@ -73,10 +102,18 @@ document.addEventListener('DOMContentLoaded', function () {
             const noteId = response.headers.get('Note-Id');
             noteIdElem.textContent = noteId;
             accountIdElem.textContent = accountId;
+
+            const noteLink = response.headers.get('Note-Link');
+            noteLinkContainer.style.display = noteLink ? 'block' : 'none';
+            if (noteLink) {
+                renderQrCode(noteQrCode, noteLink);
+                noteLinkElem.href = noteLink;
+                noteLinkElem.textContent = noteLink.length > 64 ? noteLink.slice(0, 61) + '...' : noteLink;
+            }
             info.style.display = 'block';
         } catch (error) {
             console.error('Error:', error);
-            errorMessage.textContent = 'Failed to receive tokens. Please try again.';
+            errorMessage.textContent = `Failed to receive tokens. ${error.message}`;
             errorMessage.style.display = 'block';
         }
         loading.style.display = 'none';
@@ -84,6 +121,29 @@ document.addEventListener('DOMContentLoaded', function () {
         publicButton.disabled = false;
     }
 
+    function renderQrCode(canvas, text) {
+        const qrCode = new QRCode(-1, QRErrorCorrectLevel.L);
+        qrCode.addData(text);
+        qrCode.make();
+
+        // Modules are drawn with a quiet zone of 4 modules around the code
+        const moduleCount = qrCode.getModuleCount();
+        const scale = Math.max(2, Math.floor(256 / moduleCount));
+        canvas.width = canvas.height = (moduleCount + 8) * scale;
+
+        const context = canvas.getContext('2d');
+        context.fillStyle = 'white';
+        context.fillRect(0, 0, canvas.width, canvas.height);
+        context.fillStyle = 'black';
+        for (let row = 0; row < moduleCount; row++) {
+            for (let col = 0; col < moduleCount; col++) {
+                if (qrCode.isDark(row, col)) {
+                    context.fillRect((col + 4) * scale, (row + 4) * scale, scale, scale);
+                }
+            }
+        }
+    }
+
     function downloadBlob(blob, filename) {
         const url = window.URL.createObjectURL(blob);
         const a = document.createElement('a');
@@ -0,0 +1,370 @@
+//! Stubs of the node and of the tokens, for testing the faucet without a node.
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use miden_lib::{
+    accounts::faucets::create_basic_fungible_faucet, transaction::TransactionKernel, AuthScheme,
+};
+use miden_node_proto::generated::{
+    requests::{
+        CheckNotesConsumedRequest, CheckNullifiersByPrefixRequest, CheckNullifiersRequest,
+        GetAccountDetailsRequest, GetAccountExpectedStateRequest, GetAccountProofsRequest,
+        GetAccountStateDeltaRequest, GetAccountStorageHeaderRequest, GetAccountVaultAssetsRequest,
+        GetAccountWitnessesRequest, GetBlockByNumberRequest, GetBlockHeaderByCommitmentRequest,
+        GetBlockHeaderByNumberRequest, GetBlockHeadersRangeRequest, GetBlockNoteFiltersRequest,
+        GetDataAvailabilityRequest, GetNoteTagStatisticsRequest, GetNotesByIdRequest,
+        GetNotesByRecipientRequest, SubmitProvenTransactionRequest, SyncAccountCompactRequest,
+        SyncNoteRequest, SyncStateRequest,
+    },
+    responses::{
+        CheckNotesConsumedResponse, CheckNullifiersByPrefixResponse, CheckNullifiersResponse,
+        GetAccountDetailsResponse, GetAccountExpectedStateResponse, GetAccountProofsResponse,
+        GetAccountStateDeltaResponse, GetAccountStorageHeaderResponse,
+        GetAccountVaultAssetsResponse, GetAccountWitnessesResponse, GetBlockByNumberResponse,
+        GetBlockHeaderByCommitmentResponse, GetBlockHeaderByNumberResponse,
+        GetBlockHeadersRangeResponse, GetBlockNoteFiltersResponse, GetDataAvailabilityResponse,
+        GetNoteTagStatisticsResponse, GetNotesByIdResponse, GetNotesByRecipientResponse,
+        SubmitAndWatchProvenTransactionResponse, SubmitProvenTransactionResponse,
+        SyncAccountCompactResponse, SyncNoteResponse, SyncStateResponse,
+    },
+    rpc::{
+        api_client::ApiClient,
+        api_server::{Api, ApiServer},
+    },
+};
+use miden_node_utils::crypto::get_rpo_random_coin;
+use miden_objects::{
+    accounts::{AccountStorageMode, AuthSecretKey},
+    assets::TokenSymbol,
+    crypto::{
+        dsa::rpo_falcon512::SecretKey,
+        merkle::{MmrPeaks, PartialMmr},
+    },
+    transaction::ChainMmr,
+    BlockHeader, Digest, Felt, GENESIS_BLOCK,
+};
+use rand::Rng;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+use tokio::net::TcpListener;
+use tokio_stream::{wrappers::TcpListenerStream, Empty};
+use tonic::{
+    transport::{Channel, Endpoint, Server},
+    Code, Request, Response, Status,
+};
+
+use crate::{
+    client::FaucetClient,
+    config::TokenConfig,
+    metrics::FaucetMetrics,
+    state::{FaucetState, TokenFaucet},
+};
+
+/// Maximum supply of the stub tokens.
+pub const STUB_MAX_SUPPLY: u64 = 1_000_000;
+
+// STUB RPC
+// ================================================================================================
+
+/// Node RPC which is always reachable, but only serves block headers, without their content.
+///
+/// Account details requests and transaction submissions fail with the configured status, or as
+/// unimplemented by default. The number of these requests is recorded.
+#[derive(Default)]
+pub struct StubRpc {
+    failure: Option<Code>,
+    account_details_requests: AtomicU32,
+    submissions: AtomicU32,
+}
+
+impl StubRpc {
+    /// Returns a stub RPC failing the account details requests and the submissions with `code`.
+    pub fn failing_with(code: Code) -> Self {
+        Self { failure: Some(code), ..Self::default() }
+    }
+
+    /// Returns the number of account details requests received.
+    pub fn account_details_requests(&self) -> u32 {
+        self.account_details_requests.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of transaction submissions received.
+    pub fn submissions(&self) -> u32 {
+        self.submissions.load(Ordering::Relaxed)
+    }
+
+    fn failure(&self, method: &str) -> Status {
+        Status::new(self.failure.unwrap_or(Code::Unimplemented), method)
+    }
+}
+
+/// Starts the stub RPC on a free local port, and returns a client connected to it.
+pub async fn start_stub_rpc() -> ApiClient<Channel> {
+    serve_stub_rpc(Arc::default()).await
+}
+
+/// Serves `stub` on a free local port, and returns a client connected to it.
+pub async fn serve_stub_rpc(stub: Arc<StubRpc>) -> ApiClient<Channel> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(
+        Server::builder()
+            .add_service(ApiServer::from_arc(stub))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    ApiClient::connect(url).await.unwrap()
+}
+
+/// Returns a client of a node which can't be reached.
+pub fn unreachable_rpc() -> ApiClient<Channel> {
+    ApiClient::new(Endpoint::from_static("http://127.0.0.1:1").connect_lazy())
+}
+
+#[tonic::async_trait]
+impl Api for StubRpc {
+    type SubmitAndWatchProvenTransactionStream =
+        Empty<Result<SubmitAndWatchProvenTransactionResponse, Status>>;
+
+    async fn get_block_header_by_number(
+        &self,
+        _request: Request<GetBlockHeaderByNumberRequest>,
+    ) -> Result<Response<GetBlockHeaderByNumberResponse>, Status> {
+        Ok(Response::new(GetBlockHeaderByNumberResponse::default()))
+    }
+
+    async fn check_notes_consumed(
+        &self,
+        _request: Request<CheckNotesConsumedRequest>,
+    ) -> Result<Response<CheckNotesConsumedResponse>, Status> {
+        Err(Status::unimplemented("check_notes_consumed"))
+    }
+
+    async fn check_nullifiers(
+        &self,
+        _request: Request<CheckNullifiersRequest>,
+    ) -> Result<Response<CheckNullifiersResponse>, Status> {
+        Err(Status::unimplemented("check_nullifiers"))
+    }
+
+    async fn check_nullifiers_by_prefix(
+        &self,
+        _request: Request<CheckNullifiersByPrefixRequest>,
+    ) -> Result<Response<CheckNullifiersByPrefixResponse>, Status> {
+        Err(Status::unimplemented("check_nullifiers_by_prefix"))
+    }
+
+    async fn get_account_details(
+        &self,
+        _request: Request<GetAccountDetailsRequest>,
+    ) -> Result<Response<GetAccountDetailsResponse>, Status> {
+        self.account_details_requests.fetch_add(1, Ordering::Relaxed);
+        Err(self.failure("get_account_details"))
+    }
+
+    async fn get_account_expected_state(
+        &self,
+        _request: Request<GetAccountExpectedStateRequest>,
+    ) -> Result<Response<GetAccountExpectedStateResponse>, Status> {
+        Err(Status::unimplemented("get_account_expected_state"))
+    }
+
+    async fn get_account_proofs(
+        &self,
+        _request: Request<GetAccountProofsRequest>,
+    ) -> Result<Response<GetAccountProofsResponse>, Status> {
+        Err(Status::unimplemented("get_account_proofs"))
+    }
+
+    async fn get_account_state_delta(
+        &self,
+        _request: Request<GetAccountStateDeltaRequest>,
+    ) -> Result<Response<GetAccountStateDeltaResponse>, Status> {
+        Err(Status::unimplemented("get_account_state_delta"))
+    }
+
+    async fn get_account_storage_header(
+        &self,
+        _request: Request<GetAccountStorageHeaderRequest>,
+    ) -> Result<Response<GetAccountStorageHeaderResponse>, Status> {
+        Err(Status::unimplemented("get_account_storage_header"))
+    }
+
+    async fn get_account_vault_assets(
+        &self,
+        _request: Request<GetAccountVaultAssetsRequest>,
+    ) -> Result<Response<GetAccountVaultAssetsResponse>, Status> {
+        Err(Status::unimplemented("get_account_vault_assets"))
+    }
+
+    async fn get_account_witnesses(
+        &self,
+        _request: Request<GetAccountWitnessesRequest>,
+    ) -> Result<Response<GetAccountWitnessesResponse>, Status> {
+        Err(Status::unimplemented("get_account_witnesses"))
+    }
+
+    async fn get_block_by_number(
+        &self,
+        _request: Request<GetBlockByNumberRequest>,
+    ) -> Result<Response<GetBlockByNumberResponse>, Status> {
+        Err(Status::unimplemented("get_block_by_number"))
+    }
+
+    async fn get_block_note_filters(
+        &self,
+        _request: Request<GetBlockNoteFiltersRequest>,
+    ) -> Result<Response<GetBlockNoteFiltersResponse>, Status> {
+        Err(Status::unimplemented("get_block_note_filters"))
+    }
+
+    async fn get_block_header_by_commitment(
+        &self,
+        _request: Request<GetBlockHeaderByCommitmentRequest>,
+    ) -> Result<Response<GetBlockHeaderByCommitmentResponse>, Status> {
+        Err(Status::unimplemented("get_block_header_by_commitment"))
+    }
+
+    async fn get_block_headers_range(
+        &self,
+        _request: Request<GetBlockHeadersRangeRequest>,
+    ) -> Result<Response<GetBlockHeadersRangeResponse>, Status> {
+        Err(Status::unimplemented("get_block_headers_range"))
+    }
+
+    async fn get_data_availability(
+        &self,
+        _request: Request<GetDataAvailabilityRequest>,
+    ) -> Result<Response<GetDataAvailabilityResponse>, Status> {
+        Err(Status::unimplemented("get_data_availability"))
+    }
+
+    async fn get_note_tag_statistics(
+        &self,
+        _request: Request<GetNoteTagStatisticsRequest>,
+    ) -> Result<Response<GetNoteTagStatisticsResponse>, Status> {
+        Err(Status::unimplemented("get_note_tag_statistics"))
+    }
+
+    async fn get_notes_by_id(
+        &self,
+        _request: Request<GetNotesByIdRequest>,
+    ) -> Result<Response<GetNotesByIdResponse>, Status> {
+        Err(Status::unimplemented("get_notes_by_id"))
+    }
+
+    async fn get_notes_by_recipient(
+        &self,
+        _request: Request<GetNotesByRecipientRequest>,
+    ) -> Result<Response<GetNotesByRecipientResponse>, Status> {
+        Err(Status::unimplemented("get_notes_by_recipient"))
+    }
+
+    async fn submit_and_watch_proven_transaction(
+        &self,
+        _request: Request<SubmitProvenTransactionRequest>,
+    ) -> Result<Response<Self::SubmitAndWatchProvenTransactionStream>, Status> {
+        Err(Status::unimplemented("submit_and_watch_proven_transaction"))
+    }
+
+    async fn submit_proven_transaction(
+        &self,
+        _request: Request<SubmitProvenTransactionRequest>,
+    ) -> Result<Response<SubmitProvenTransactionResponse>, Status> {
+        self.submissions.fetch_add(1, Ordering::Relaxed);
+        Err(self.failure("submit_proven_transaction"))
+    }
+
+    async fn sync_account_compact(
+        &self,
+        _request: Request<SyncAccountCompactRequest>,
+    ) -> Result<Response<SyncAccountCompactResponse>, Status> {
+        Err(Status::unimplemented("sync_account_compact"))
+    }
+
+    async fn sync_notes(
+        &self,
+        _request: Request<SyncNoteRequest>,
+    ) -> Result<Response<SyncNoteResponse>, Status> {
+        Err(Status::unimplemented("sync_notes"))
+    }
+
+    async fn sync_state(
+        &self,
+        _request: Request<SyncStateRequest>,
+    ) -> Result<Response<SyncStateResponse>, Status> {
+        Err(Status::unimplemented("sync_state"))
+    }
+}
+
+// STUB TOKENS
+// ================================================================================================
+
+/// Returns a faucet of `symbol` tokens which mints transactions locally, without a node.
+pub fn stub_token(symbol: &str, seed: u8) -> TokenFaucet {
+    stub_token_submitting_to(symbol, seed, unreachable_rpc())
+}
+
+/// Returns a faucet of `symbol` tokens which submits its mint transactions to `rpc_api`.
+pub fn stub_token_submitting_to(
+    symbol: &str,
+    seed: u8,
+    rpc_api: ApiClient<Channel>,
+) -> TokenFaucet {
+    let mut rng = ChaCha20Rng::from_seed([seed; 32]);
+    let secret = SecretKey::with_rng(&mut get_rpo_random_coin(&mut rng));
+
+    let (account, account_seed) = create_basic_fungible_faucet(
+        rng.gen(),
+        TokenSymbol::try_from(symbol).unwrap(),
+        6,
+        Felt::new(STUB_MAX_SUPPLY),
+        AccountStorageMode::Public,
+        AuthScheme::RpoFalcon512 { pub_key: secret.public_key() },
+    )
+    .unwrap();
+
+    let peaks = MmrPeaks::new(0, Vec::new()).unwrap();
+    let block_header = BlockHeader::new(
+        0,
+        Digest::default(),
+        GENESIS_BLOCK,
+        peaks.hash_peaks(),
+        Digest::default(),
+        Digest::default(),
+        Digest::default(),
+        Digest::default(),
+        TransactionKernel::kernel_root(),
+        Digest::default(),
+        0,
+    );
+    let chain_mmr = ChainMmr::new(PartialMmr::from_peaks(peaks), Vec::new()).unwrap();
+
+    let client = FaucetClient::with_account(
+        rpc_api,
+        account,
+        Some(account_seed),
+        AuthSecretKey::RpoFalcon512(secret),
+        block_header,
+        chain_mmr,
+        Arc::default(),
+    );
+
+    TokenFaucet::new(
+        TokenConfig {
+            token_symbol: symbol.to_string(),
+            asset_amount_options: vec![10, 20],
+            ..TokenConfig::default()
+        },
+        client,
+    )
+}
+
+/// Returns the state of a faucet dispensing the `tokens`, checking the health of the node with
+/// `rpc_api`.
+pub fn stub_state(tokens: Vec<TokenFaucet>, rpc_api: ApiClient<Channel>) -> FaucetState {
+    FaucetState::from_tokens(tokens, rpc_api, Arc::new(FaucetMetrics::default()))
+}
@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use miden_node_rpc::audit::{find_records, AuditRecord};
+use miden_objects::transaction::TransactionId;
+
+// AUDIT LOG
+// ================================================================================================
+
+/// Prints the records of the RPC audit log in `directory`, optionally only those of the given
+/// transaction, one per line.
+pub fn run_audit_log(directory: &Path, tx_id: Option<TransactionId>, json: bool) -> Result<()> {
+    let tx_id = tx_id.map(|tx_id| tx_id.to_hex());
+    let records = find_records(directory, tx_id.as_deref())
+        .with_context(|| format!("Failed to read the audit log in {}", directory.display()))?;
+
+    for record in records {
+        if json {
+            println!("{}", serde_json::to_string(&record)?);
+        } else {
+            println!("{}", format_record(&record));
+        }
+    }
+
+    Ok(())
+}
+
+fn format_record(record: &AuditRecord) -> String {
+    let outcome = if record.accepted {
+        "accepted".to_string()
+    } else {
+        format!(
+            "rejected ({}): {}",
+            record.code.as_deref().unwrap_or_default(),
+            record.message.as_deref().unwrap_or_default()
+        )
+    };
+
+    format!(
+        "{} {} from {} {outcome}",
+        record.timestamp_ms,
+        record.transaction_id.as_deref().unwrap_or("<invalid transaction>"),
+        record.peer.map_or_else(|| "unknown peer".to_string(), |peer| peer.to_string()),
+    )
+}
@@ -0,0 +1,562 @@
+use std::{
+    fmt::{Display, Formatter},
+    fs::{self, File},
+    io::ErrorKind,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+use miden_node_proto::generated::{
+    requests::GetBlockHeaderByNumberRequest, responses::GetBlockHeaderByNumberResponse,
+    rpc::api_client as rpc_client, store::api_client as store_client,
+};
+use miden_node_store::genesis::GenesisState;
+use miden_node_utils::runtime_info::RuntimeInfo;
+use miden_objects::{utils::Deserializable, BlockHeader, GENESIS_BLOCK};
+use serde::Serialize;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    time,
+};
+use tonic::transport::Channel;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum time to wait for a component to accept a connection or to answer a request.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Maximum tolerated difference between the chain tip timestamp and the local clock, when the
+/// chain tip is in the future.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
+/// Age of the chain tip above which the node is suspected to be stalled.
+const MAX_TIP_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Free disk space below which the store is about to run out of space.
+const MIN_FREE_DISK_SPACE: u64 = 1024 * 1024 * 1024;
+
+// CHECK RESULTS
+// ================================================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Display for CheckStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        };
+        f.write_str(status)
+    }
+}
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl CheckResult {
+    fn pass(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Pass, message)
+    }
+
+    fn warn(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warn, message)
+    }
+
+    fn fail(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Fail, message)
+    }
+
+    fn new(name: impl Into<String>, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+// BLOCK HEADER SOURCE
+// ================================================================================================
+
+/// A component which serves block headers, i.e. the store or the RPC.
+pub(crate) trait BlockHeaderSource {
+    /// Returns the header of the given block, or of the latest block if `block_num` is `None`.
+    async fn block_header(&self, block_num: Option<u32>) -> Result<BlockHeader>;
+}
+
+impl BlockHeaderSource for store_client::ApiClient<Channel> {
+    async fn block_header(&self, block_num: Option<u32>) -> Result<BlockHeader> {
+        let request = GetBlockHeaderByNumberRequest { block_num, include_mmr_proof: None };
+        let response = self.clone().get_block_header_by_number(request).await?;
+
+        parse_block_header(response.into_inner())
+    }
+}
+
+impl BlockHeaderSource for rpc_client::ApiClient<Channel> {
+    async fn block_header(&self, block_num: Option<u32>) -> Result<BlockHeader> {
+        let request = GetBlockHeaderByNumberRequest { block_num, include_mmr_proof: None };
+        let response = self.clone().get_block_header_by_number(request).await?;
+
+        parse_block_header(response.into_inner())
+    }
+}
+
+fn parse_block_header(response: GetBlockHeaderByNumberResponse) -> Result<BlockHeader> {
+    let header = response.block_header.context("Block header is missing from the response")?;
+
+    header.try_into().context("Invalid block header")
+}
+
+// RUNTIME INFO
+// ================================================================================================
+
+/// Checks the runtime info file written by the running components, returning its contents if it
+/// could be read.
+pub fn check_runtime_info(path: &Path) -> (CheckResult, Option<RuntimeInfo>) {
+    const CHECK: &str = "runtime info";
+
+    if !path.exists() {
+        let message =
+            format!("No runtime info file at {}, the component is not running", path.display());
+        return (CheckResult::warn(CHECK, message), None);
+    }
+
+    match RuntimeInfo::read(path) {
+        Ok(info) => {
+            let listeners: Vec<_> =
+                info.listeners.iter().map(|(name, addr)| format!("{name} on {addr}")).collect();
+            let message = format!(
+                "{} {} started by process {}, listening: {}",
+                info.component,
+                info.version.version,
+                info.pid,
+                listeners.join(", ")
+            );
+            (CheckResult::pass(CHECK, message), Some(info))
+        },
+        Err(err) => {
+            (CheckResult::fail(CHECK, format!("Cannot read {}: {err}", path.display())), None)
+        },
+    }
+}
+
+// CONNECTIVITY
+// ================================================================================================
+
+/// Checks that a component the checked components depend on accepts connections at `addr`.
+pub async fn check_upstream_endpoint(name: &str, addr: &str) -> CheckResult {
+    let check = format!("{name} connectivity");
+    match connect(addr).await {
+        Ok(()) => CheckResult::pass(check, format!("{addr} accepts connections")),
+        Err(err) => CheckResult::fail(check, format!("Cannot connect to {addr}: {err:#}")),
+    }
+}
+
+/// Checks the endpoint a component listens on.
+///
+/// The endpoint must either be served by the running component, or be free so that the component
+/// can bind it on startup.
+pub async fn check_listen_endpoint(name: &str, addr: &str) -> CheckResult {
+    let check = format!("{name} endpoint");
+    if connect(addr).await.is_ok() {
+        return CheckResult::pass(check, format!("{addr} accepts connections"));
+    }
+
+    match TcpListener::bind(addr).await {
+        Ok(_) => CheckResult::warn(
+            check,
+            format!("Nothing is listening on {addr}, the component is not running"),
+        ),
+        Err(err) if err.kind() == ErrorKind::AddrInUse => CheckResult::fail(
+            check,
+            format!("{addr} is already in use, but doesn't accept connections"),
+        ),
+        Err(err) => CheckResult::fail(check, format!("Cannot bind {addr}: {err}")),
+    }
+}
+
+async fn connect(addr: &str) -> Result<()> {
+    time::timeout(REQUEST_TIMEOUT, TcpStream::connect(addr))
+        .await
+        .map_err(|_| anyhow!("Connection timed out"))??;
+
+    Ok(())
+}
+
+/// Checks that a component answers gRPC requests.
+pub async fn check_node_status(name: &str, node: &impl BlockHeaderSource) -> CheckResult {
+    let check = format!("{name} status");
+    match node.block_header(None).await {
+        Ok(header) => CheckResult::pass(
+            check,
+            format!("Serving the chain tip at block {}", header.block_num()),
+        ),
+        Err(err) => {
+            CheckResult::fail(check, format!("Failed to fetch the latest block header: {err:#}"))
+        },
+    }
+}
+
+// GENESIS
+// ================================================================================================
+
+/// Checks that the local genesis file matches the genesis block of the store.
+pub async fn check_genesis(genesis_filepath: &Path, store: &impl BlockHeaderSource) -> CheckResult {
+    const CHECK: &str = "genesis";
+
+    let local_hash = match read_genesis_header(genesis_filepath) {
+        Ok(header) => header.hash(),
+        Err(err) => return CheckResult::fail(CHECK, format!("{err:#}")),
+    };
+
+    match store.block_header(Some(GENESIS_BLOCK)).await {
+        Ok(header) if header.hash() == local_hash => CheckResult::pass(
+            CHECK,
+            format!("Genesis block {} matches the store", local_hash.to_hex()),
+        ),
+        Ok(header) => CheckResult::fail(
+            CHECK,
+            format!(
+                "Genesis block {} of {} differs from the store's genesis block {}",
+                local_hash.to_hex(),
+                genesis_filepath.display(),
+                header.hash().to_hex()
+            ),
+        ),
+        Err(err) => CheckResult::warn(
+            CHECK,
+            format!("Cannot fetch the genesis block from the store: {err:#}"),
+        ),
+    }
+}
+
+fn read_genesis_header(genesis_filepath: &Path) -> Result<BlockHeader> {
+    let bytes = fs::read(genesis_filepath)
+        .with_context(|| format!("Failed to read {}", genesis_filepath.display()))?;
+    let genesis = GenesisState::read_from_bytes(&bytes)
+        .map_err(|err| anyhow!("Failed to deserialize {}: {err}", genesis_filepath.display()))?;
+
+    Ok(genesis.into_block()?.header())
+}
+
+// DATA DIRECTORIES
+// ================================================================================================
+
+/// Checks that the store can write to `dir`.
+///
+/// Missing directories are created by the store on startup, so for those the closest existing
+/// ancestor must be writable instead.
+pub fn check_directory_writable(name: &str, dir: &Path) -> CheckResult {
+    let check = format!("{name} directory");
+
+    let Some(existing) = dir.ancestors().find(|ancestor| ancestor.is_dir()) else {
+        return CheckResult::fail(check, format!("No parent of {} exists", dir.display()));
+    };
+
+    let probe = existing.join(format!(".miden-node-doctor-{}", std::process::id()));
+    if let Err(err) = File::create(&probe) {
+        return CheckResult::fail(check, format!("{} is not writable: {err}", existing.display()));
+    }
+    let _ = fs::remove_file(&probe);
+
+    if existing == dir {
+        CheckResult::pass(check, format!("{} is writable", dir.display()))
+    } else {
+        CheckResult::pass(check, format!("{} will be created on startup", dir.display()))
+    }
+}
+
+/// Checks that the database file, if it exists, isn't read-only.
+pub fn check_database_file(database_filepath: &Path) -> CheckResult {
+    const CHECK: &str = "database file";
+
+    match fs::metadata(database_filepath) {
+        Ok(metadata) if metadata.permissions().readonly() => {
+            CheckResult::fail(CHECK, format!("{} is read-only", database_filepath.display()))
+        },
+        Ok(_) => CheckResult::pass(CHECK, format!("{} is writable", database_filepath.display())),
+        Err(err) if err.kind() == ErrorKind::NotFound => CheckResult::pass(
+            CHECK,
+            format!("{} will be created on startup", database_filepath.display()),
+        ),
+        Err(err) => CheckResult::fail(
+            CHECK,
+            format!("Cannot access {}: {err}", database_filepath.display()),
+        ),
+    }
+}
+
+/// Checks that the disk holding the database has enough free space for the database to grow.
+pub fn check_disk_space(database_filepath: &Path) -> CheckResult {
+    let dir = database_filepath
+        .ancestors()
+        .skip(1)
+        .find(|ancestor| ancestor.is_dir())
+        .unwrap_or(Path::new("."));
+
+    let available = match fs2::available_space(dir) {
+        Ok(available) => available,
+        Err(err) => {
+            return CheckResult::warn(
+                "disk space",
+                format!("Cannot determine free space of {}: {err}", dir.display()),
+            )
+        },
+    };
+    let database_size = fs::metadata(database_filepath).map(|m| m.len()).unwrap_or_default();
+
+    disk_space_status(available, database_size)
+}
+
+fn disk_space_status(available: u64, database_size: u64) -> CheckResult {
+    const CHECK: &str = "disk space";
+    let summary = format!(
+        "{} MiB free, database size is {} MiB",
+        available / (1024 * 1024),
+        database_size / (1024 * 1024)
+    );
+
+    if available < MIN_FREE_DISK_SPACE {
+        CheckResult::fail(CHECK, format!("{summary}, the disk is almost full"))
+    } else if available < database_size {
+        // Migrations and vacuuming can temporarily require as much space as the database itself
+        CheckResult::warn(CHECK, format!("{summary}, less than the database size"))
+    } else {
+        CheckResult::pass(CHECK, summary)
+    }
+}
+
+// CLOCK
+// ================================================================================================
+
+/// Checks the local clock against the timestamp of the chain tip.
+pub async fn check_clock(store: &impl BlockHeaderSource) -> CheckResult {
+    match store.block_header(None).await {
+        Ok(header) => clock_status(SystemTime::now(), header.timestamp()),
+        Err(err) => CheckResult::warn(
+            "clock",
+            format!("Cannot fetch the chain tip from the store: {err:#}"),
+        ),
+    }
+}
+
+fn clock_status(now: SystemTime, tip_timestamp: u32) -> CheckResult {
+    const CHECK: &str = "clock";
+    let tip = UNIX_EPOCH + Duration::from_secs(tip_timestamp.into());
+
+    match now.duration_since(tip) {
+        Err(err) if err.duration() > MAX_CLOCK_SKEW => CheckResult::fail(
+            CHECK,
+            format!("Local clock is {}s behind the chain tip", err.duration().as_secs()),
+        ),
+        Err(_) => CheckResult::pass(CHECK, "Chain tip was just produced"),
+        Ok(age) if age > MAX_TIP_AGE => CheckResult::warn(
+            CHECK,
+            format!(
+                "Chain tip is {}s old, either no blocks are produced or the local clock is ahead",
+                age.as_secs()
+            ),
+        ),
+        Ok(age) => CheckResult::pass(CHECK, format!("Chain tip is {}s old", age.as_secs())),
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, net::TcpListener as StdTcpListener};
+
+    use miden_objects::utils::Serializable;
+
+    use super::*;
+
+    /// Serves block headers from memory, latest block being the one with the highest number.
+    #[derive(Default)]
+    struct MockNode {
+        headers: BTreeMap<u32, BlockHeader>,
+    }
+
+    impl MockNode {
+        fn new(headers: impl IntoIterator<Item = BlockHeader>) -> Self {
+            let headers = headers.into_iter().map(|header| (header.block_num(), header)).collect();
+            Self { headers }
+        }
+    }
+
+    impl BlockHeaderSource for MockNode {
+        async fn block_header(&self, block_num: Option<u32>) -> Result<BlockHeader> {
+            let header = match block_num {
+                Some(block_num) => self.headers.get(&block_num),
+                None => self.headers.values().last(),
+            };
+
+            header.copied().context("Block not found")
+        }
+    }
+
+    fn genesis_state(timestamp: u32) -> GenesisState {
+        GenesisState::new(vec![], 1, timestamp)
+    }
+
+    fn free_port_addr() -> String {
+        let listener = StdTcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[test]
+    fn runtime_info_is_read_if_present() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("miden-node.runtime.json");
+
+        let (result, info) = check_runtime_info(&path);
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(info.is_none());
+
+        let written = RuntimeInfo::new("node", &crate::long_version())
+            .with_listener("rpc", "127.0.0.1:57291".parse().unwrap());
+        let _file = written.write(&path).unwrap();
+        let (result, info) = check_runtime_info(&path);
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(info, Some(written));
+
+        fs::write(&path, r#"{ "schema_version": 0 }"#).unwrap();
+        let (result, info) = check_runtime_info(&path);
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(info.is_none());
+    }
+
+    #[tokio::test]
+    async fn upstream_endpoint_must_accept_connections() {
+        let listener = StdTcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let result = check_upstream_endpoint("store", &addr).await;
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        let result = check_upstream_endpoint("store", &free_port_addr()).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+    }
+
+    #[tokio::test]
+    async fn listen_endpoint_is_served_or_free() {
+        let listener = StdTcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let result = check_listen_endpoint("rpc", &addr).await;
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        let result = check_listen_endpoint("rpc", &free_port_addr()).await;
+        assert_eq!(result.status, CheckStatus::Warn);
+    }
+
+    #[tokio::test]
+    async fn node_status_requires_latest_header() {
+        let genesis = genesis_state(0).into_block().unwrap().header();
+
+        let result = check_node_status("store", &MockNode::new([genesis])).await;
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        let result = check_node_status("store", &MockNode::default()).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+    }
+
+    #[tokio::test]
+    async fn genesis_must_match_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let genesis_filepath = dir.path().join("genesis.dat");
+        fs::write(&genesis_filepath, genesis_state(10).to_bytes()).unwrap();
+
+        let matching = MockNode::new([genesis_state(10).into_block().unwrap().header()]);
+        let result = check_genesis(&genesis_filepath, &matching).await;
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        let mismatching = MockNode::new([genesis_state(20).into_block().unwrap().header()]);
+        let result = check_genesis(&genesis_filepath, &mismatching).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+
+        let result = check_genesis(&genesis_filepath, &MockNode::default()).await;
+        assert_eq!(result.status, CheckStatus::Warn);
+
+        let result = check_genesis(&dir.path().join("missing.dat"), &matching).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn directories_must_be_writable() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let result = check_directory_writable("blockstore", dir.path());
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        let result = check_directory_writable("blockstore", &dir.path().join("blocks"));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert!(result.message.contains("will be created"));
+    }
+
+    #[test]
+    fn database_file_must_not_be_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_filepath = dir.path().join("miden-store.sqlite3");
+
+        let result = check_database_file(&database_filepath);
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        fs::write(&database_filepath, []).unwrap();
+        let result = check_database_file(&database_filepath);
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        let mut permissions = fs::metadata(&database_filepath).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&database_filepath, permissions).unwrap();
+
+        let result = check_database_file(&database_filepath);
+        assert_eq!(result.status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn disk_space_is_compared_to_database_size() {
+        const GIB: u64 = 1024 * 1024 * 1024;
+
+        assert_eq!(disk_space_status(10 * GIB, 2 * GIB).status, CheckStatus::Pass);
+        assert_eq!(disk_space_status(2 * GIB, 3 * GIB).status, CheckStatus::Warn);
+        assert_eq!(disk_space_status(GIB / 2, 0).status, CheckStatus::Fail);
+    }
+
+    #[tokio::test]
+    async fn clock_is_compared_to_chain_tip() {
+        let tip_timestamp = 1_700_000_000;
+        let tip = UNIX_EPOCH + Duration::from_secs(tip_timestamp.into());
+
+        let result = clock_status(tip + Duration::from_secs(10), tip_timestamp);
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        let result = clock_status(tip - Duration::from_secs(5), tip_timestamp);
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        let result = clock_status(tip - 2 * MAX_CLOCK_SKEW, tip_timestamp);
+        assert_eq!(result.status, CheckStatus::Fail);
+
+        let result = clock_status(tip + 2 * MAX_TIP_AGE, tip_timestamp);
+        assert_eq!(result.status, CheckStatus::Warn);
+
+        let result = check_clock(&MockNode::default()).await;
+        assert_eq!(result.status, CheckStatus::Warn);
+    }
+}
@@ -0,0 +1,270 @@
+use std::{collections::BTreeMap, net::SocketAddr, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use checks::{CheckResult, CheckStatus, REQUEST_TIMEOUT};
+use miden_node_block_producer::config::BlockProducerConfig;
+use miden_node_proto::generated::{
+    rpc::api_client as rpc_client, store::api_client as store_client,
+};
+use miden_node_rpc::config::RpcConfig;
+use miden_node_store::config::StoreConfig;
+use miden_node_utils::config::{load_config_with, local_url, Endpoint, UnknownKeys};
+use serde::Serialize;
+use tonic::transport::{Channel, Uri};
+
+use crate::{config::NodeConfig, StartCommand};
+
+mod checks;
+
+// DOCTOR
+// ================================================================================================
+
+/// Runs diagnostics of the configuration of the given component, and of its environment.
+///
+/// Prints the outcome of every check, and fails if any of the checks failed.
+///
+/// The addresses the running components are bound to are taken from their runtime info file at
+/// `runtime_info_path` if it exists, instead of their configured endpoints.
+pub async fn run_doctor(
+    component: &StartCommand,
+    config_path: &Path,
+    runtime_info_path: &Path,
+    json: bool,
+    unknown_keys: UnknownKeys,
+) -> Result<()> {
+    let targets = DoctorTargets::load(component, config_path, unknown_keys)?;
+    let checks = targets.run(runtime_info_path).await;
+
+    let report = DoctorReport {
+        passed: checks.iter().all(|check| check.status != CheckStatus::Fail),
+        checks,
+    };
+
+    if json {
+        let output =
+            serde_json::to_string_pretty(&report).context("Failed to serialize the report")?;
+        println!("{output}");
+    } else {
+        print!("{}", report.to_table());
+    }
+
+    if report.passed {
+        Ok(())
+    } else {
+        let failed = report.checks.iter().filter(|check| check.status == CheckStatus::Fail);
+        Err(anyhow!("{} check(s) failed", failed.count()))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorReport {
+    passed: bool,
+    checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    fn to_table(&self) -> String {
+        let name_width = self.checks.iter().map(|check| check.name.len()).max().unwrap_or(0);
+
+        self.checks
+            .iter()
+            .map(|check| {
+                format!("[{}] {:name_width$}  {}\n", check.status, check.name, check.message)
+            })
+            .collect()
+    }
+}
+
+// TARGETS
+// ================================================================================================
+
+/// Everything which is checked for a component, derived from its configuration.
+#[derive(Debug, Default)]
+struct DoctorTargets {
+    /// Endpoints the checked components listen on.
+    listen_endpoints: Vec<(&'static str, String)>,
+    /// Endpoints of the components the checked components connect to.
+    upstream_endpoints: Vec<(&'static str, String)>,
+    store_url: Option<String>,
+    rpc_url: Option<String>,
+    store: Option<StoreConfig>,
+}
+
+impl DoctorTargets {
+    fn load(
+        component: &StartCommand,
+        config_path: &Path,
+        unknown_keys: UnknownKeys,
+    ) -> Result<Self> {
+        let targets = match component {
+            StartCommand::Node => {
+                let config: NodeConfig = load_config_with(config_path, unknown_keys)
+                    .context("Loading configuration file")?;
+                let (block_producer, rpc, store) = config.into_parts();
+
+                Self {
+                    listen_endpoints: vec![
+                        ("store", socket_addr(&store.endpoint)),
+                        ("block-producer", socket_addr(&block_producer.endpoint)),
+                        ("rpc", socket_addr(&rpc.endpoint)),
+                    ],
+                    upstream_endpoints: vec![],
+                    store_url: Some(store.endpoint_url()),
+                    rpc_url: Some(rpc.endpoint_url()),
+                    store: Some(store),
+                }
+            },
+            StartCommand::Store => {
+                let store: StoreConfig = load_config_with(config_path, unknown_keys)
+                    .context("Loading configuration file")?;
+
+                Self {
+                    listen_endpoints: vec![("store", socket_addr(&store.endpoint))],
+                    store_url: Some(store.endpoint_url()),
+                    store: Some(store),
+                    ..Default::default()
+                }
+            },
+            StartCommand::BlockProducer => {
+                let block_producer: BlockProducerConfig =
+                    load_config_with(config_path, unknown_keys)
+                        .context("Loading configuration file")?;
+
+                Self {
+                    listen_endpoints: vec![(
+                        "block-producer",
+                        socket_addr(&block_producer.endpoint),
+                    )],
+                    upstream_endpoints: vec![(
+                        "store",
+                        url_socket_addr(&block_producer.store_url)?,
+                    )],
+                    store_url: Some(block_producer.store_url),
+                    ..Default::default()
+                }
+            },
+            StartCommand::Rpc => {
+                let rpc: RpcConfig = load_config_with(config_path, unknown_keys)
+                    .context("Loading configuration file")?;
+
+                Self {
+                    listen_endpoints: vec![("rpc", socket_addr(&rpc.endpoint))],
+                    upstream_endpoints: vec![
+                        ("store", url_socket_addr(&rpc.store_url)?),
+                        ("block-producer", url_socket_addr(&rpc.block_producer_url)?),
+                    ],
+                    store_url: Some(rpc.store_url),
+                    rpc_url: Some(rpc.endpoint_url()),
+                    ..Default::default()
+                }
+            },
+        };
+
+        Ok(targets)
+    }
+
+    /// Replaces the configured endpoints of the running components by the addresses they are
+    /// bound to, which differ for the endpoints configured with port 0.
+    fn use_bound_addresses(&mut self, listeners: &BTreeMap<String, SocketAddr>) {
+        for (name, addr) in &mut self.listen_endpoints {
+            if let Some(bound) = listeners.get(*name) {
+                *addr = bound.to_string();
+            }
+        }
+        if let (Some(store_url), Some(bound)) = (&mut self.store_url, listeners.get("store")) {
+            *store_url = local_url(*bound);
+        }
+        if let (Some(rpc_url), Some(bound)) = (&mut self.rpc_url, listeners.get("rpc")) {
+            *rpc_url = local_url(*bound);
+        }
+    }
+
+    async fn run(mut self, runtime_info_path: &Path) -> Vec<CheckResult> {
+        let mut results = Vec::new();
+
+        let (result, runtime_info) = checks::check_runtime_info(runtime_info_path);
+        results.push(result);
+        if let Some(runtime_info) = runtime_info {
+            self.use_bound_addresses(&runtime_info.listeners);
+        }
+
+        for (name, addr) in &self.listen_endpoints {
+            results.push(checks::check_listen_endpoint(name, addr).await);
+        }
+        for (name, addr) in &self.upstream_endpoints {
+            results.push(checks::check_upstream_endpoint(name, addr).await);
+        }
+
+        if let Some(store) = &self.store {
+            results.push(checks::check_directory_writable("blockstore", &store.blockstore_dir));
+            let database_dir = match store.database_filepath.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            results.push(checks::check_directory_writable("database", database_dir));
+            results.push(checks::check_database_file(&store.database_filepath));
+            results.push(checks::check_disk_space(&store.database_filepath));
+        }
+
+        if let Some(store_url) = &self.store_url {
+            match grpc_channel(store_url).await {
+                Ok(channel) => {
+                    let client = store_client::ApiClient::new(channel);
+                    results.push(checks::check_node_status("store", &client).await);
+                    if let Some(store) = &self.store {
+                        results.push(checks::check_genesis(&store.genesis_filepath, &client).await);
+                    }
+                    results.push(checks::check_clock(&client).await);
+                },
+                Err(err) => results.push(unreachable_node("store", store_url, &err)),
+            }
+        }
+
+        if let Some(rpc_url) = &self.rpc_url {
+            match grpc_channel(rpc_url).await {
+                Ok(channel) => {
+                    let client = rpc_client::ApiClient::new(channel);
+                    results.push(checks::check_node_status("rpc", &client).await);
+                },
+                Err(err) => results.push(unreachable_node("rpc", rpc_url, &err)),
+            }
+        }
+
+        results
+    }
+}
+
+fn unreachable_node(name: &str, url: &str, err: &anyhow::Error) -> CheckResult {
+    CheckResult {
+        name: format!("{name} status"),
+        status: CheckStatus::Fail,
+        message: format!("Cannot connect to {url}: {err:#}"),
+    }
+}
+
+async fn grpc_channel(url: &str) -> Result<Channel> {
+    let channel = Channel::from_shared(url.to_string())?
+        .connect_timeout(REQUEST_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .connect()
+        .await?;
+
+    Ok(channel)
+}
+
+fn socket_addr(endpoint: &Endpoint) -> String {
+    if endpoint.host.contains(':') {
+        format!("[{}]:{}", endpoint.host, endpoint.port)
+    } else {
+        format!("{}:{}", endpoint.host, endpoint.port)
+    }
+}
+
+/// Extracts the `host:port` part of a component url, e.g. `http://localhost:28943`.
+fn url_socket_addr(url: &str) -> Result<String> {
+    let uri: Uri = url.parse().with_context(|| format!("Invalid url {url}"))?;
+    let host = uri.host().with_context(|| format!("Url {url} has no host"))?;
+    let port = uri.port_u16().unwrap_or(80);
+
+    Ok(format!("{host}:{port}"))
+}
@@ -0,0 +1,128 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use miden_node_block_producer::dry_run::{dry_run, DryRunOptions, DryRunReport};
+use miden_node_store::{config::StoreConfig, server::Store};
+use miden_node_utils::{
+    config::{local_url, Endpoint},
+    formatting::format_blake3_digest,
+};
+use miden_objects::{transaction::ProvenTransaction, utils::Deserializable};
+use tempfile::TempDir;
+
+use super::{BLOCKSTORE_DIR, DATABASE_FILE, GENESIS_FILE};
+
+// DRY RUN
+// ================================================================================================
+
+/// Runs the transactions in `transactions_path` through the block production pipeline, against
+/// the store data in `data_directory`, and prints the timings of every stage.
+///
+/// The store is started in-process on a scratch copy of the data directory, which is discarded
+/// afterwards, so the data directory is left untouched.
+pub async fn run_dry_run(
+    data_directory: &Path,
+    transactions_path: &Path,
+    verify_proofs: bool,
+) -> Result<()> {
+    let txs = fs::read(transactions_path)
+        .with_context(|| format!("Failed to read {}", transactions_path.display()))?;
+    let txs = Vec::<ProvenTransaction>::read_from_bytes(&txs)
+        .map_err(|err| anyhow!("Failed to deserialize the transactions: {err}"))?;
+
+    let scratch_dir = TempDir::new().context("Failed to create a scratch directory")?;
+    copy_data_directory(data_directory, scratch_dir.path())?;
+
+    let config = StoreConfig {
+        endpoint: Endpoint::localhost(0),
+        database_filepath: scratch_dir.path().join(DATABASE_FILE),
+        genesis_filepath: scratch_dir.path().join(GENESIS_FILE),
+        blockstore_dir: scratch_dir.path().join(BLOCKSTORE_DIR),
+        ..Default::default()
+    };
+
+    let store = Store::init(config).await.context("Loading store")?;
+    let store_url = local_url(store.local_addr());
+    let store = tokio::spawn(async move { store.serve().await });
+
+    let options = DryRunOptions {
+        verify_tx_proofs: verify_proofs,
+        ..Default::default()
+    };
+    let result = dry_run(store_url, txs, options).await;
+    store.abort();
+
+    let report = result.context("Dry run failed")?;
+    print!("{}", to_table(&report));
+
+    Ok(())
+}
+
+/// Copies the database, the genesis file and the block store from `source` to `target`.
+fn copy_data_directory(source: &Path, target: &Path) -> Result<()> {
+    let database = source.join(DATABASE_FILE);
+    if !database.exists() {
+        return Err(anyhow!("No store database found at {}", database.display()));
+    }
+
+    // The write-ahead log holds the latest transactions of a database in use
+    for suffix in ["", "-wal", "-shm"] {
+        let file = PathBuf::from(format!("{}{suffix}", database.display()));
+        if file.exists() {
+            fs::copy(&file, target.join(format!("{DATABASE_FILE}{suffix}")))
+                .with_context(|| format!("Failed to copy {}", file.display()))?;
+        }
+    }
+
+    fs::copy(source.join(GENESIS_FILE), target.join(GENESIS_FILE))
+        .with_context(|| format!("Failed to copy {}", source.join(GENESIS_FILE).display()))?;
+
+    copy_dir(&source.join(BLOCKSTORE_DIR), &target.join(BLOCKSTORE_DIR))
+}
+
+fn copy_dir(source: &Path, target: &Path) -> Result<()> {
+    fs::create_dir_all(target).with_context(|| format!("Failed to create {}", target.display()))?;
+
+    for entry in
+        fs::read_dir(source).with_context(|| format!("Failed to read {}", source.display()))?
+    {
+        let entry = entry?;
+        let target = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn to_table(report: &DryRunReport) -> String {
+    let mut table = format!("{:<70} {:>12}\n", "STAGE", "DURATION");
+    let mut row = |stage: String, duration: std::time::Duration| {
+        table.push_str(&format!("{stage:<70} {:>12}\n", format!("{duration:.3?}")));
+    };
+
+    row("verify transactions".to_string(), report.verify_txs);
+    for batch in &report.batches {
+        row(
+            format!("build batch {} ({} txs)", format_blake3_digest(batch.id), batch.num_txs),
+            batch.build,
+        );
+    }
+
+    let durations = report.block.stage_durations;
+    row("fetch block inputs".to_string(), durations.inputs_fetch);
+    row("propose block".to_string(), durations.propose);
+    row("prove block".to_string(), durations.prove);
+    row("build block".to_string(), durations.build);
+
+    table.push_str(&format!("\nblock {}: {}\n", report.block.block_num, report.block.block_hash));
+
+    table
+}
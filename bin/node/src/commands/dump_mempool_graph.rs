@@ -0,0 +1,27 @@
+use anyhow::{anyhow, Context, Result};
+use miden_node_proto::generated::{
+    block_producer::api_client::ApiClient,
+    requests::{DumpMempoolGraphRequest, GraphFormat},
+};
+
+// MEMPOOL GRAPH
+// ================================================================================================
+
+/// Prints the dependency graph of the in-flight transactions and batches of the block producer
+/// listening on `url`, in the Graphviz DOT language if `dot` is set, or as JSON otherwise.
+pub async fn run_dump_mempool_graph(url: &str, dot: bool) -> Result<()> {
+    let mut client = ApiClient::connect(url.to_string())
+        .await
+        .with_context(|| format!("Failed to connect to the block producer at {url}"))?;
+
+    let format = if dot { GraphFormat::Dot } else { GraphFormat::Json };
+    let response = client
+        .dump_mempool_graph(DumpMempoolGraphRequest { format: format.into() })
+        .await
+        .map_err(|status| anyhow!("Failed to dump the mempool graph: {}", status.message()))?
+        .into_inner();
+
+    println!("{}", response.graph);
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anyhow::{anyhow, Context, Result};
+use miden_node_proto::generated::{
+    block_producer::api_client::ApiClient, requests::EvictTransactionRequest,
+    transaction::TransactionId as TransactionIdPb,
+};
+use miden_objects::{
+    crypto::{hash::rpo::RpoDigest, utils::bytes_to_hex_string},
+    transaction::TransactionId,
+};
+
+// TRANSACTION EVICTION
+// ================================================================================================
+
+/// Evicts the transaction from the block producer listening on `url`, along with the
+/// transactions depending on it if `cascade` is set.
+pub async fn run_evict_tx(url: &str, tx_id: TransactionId, cascade: bool) -> Result<()> {
+    let mut client = ApiClient::connect(url.to_string())
+        .await
+        .with_context(|| format!("Failed to connect to the block producer at {url}"))?;
+
+    let eviction = client
+        .evict_transaction(EvictTransactionRequest {
+            transaction_id: Some(tx_id.into()),
+            cascade,
+        })
+        .await
+        .map_err(|status| anyhow!("Failed to evict the transaction: {}", status.message()))?
+        .into_inner();
+
+    for tx_id in eviction.evicted_transactions {
+        println!("evicted transaction {}", transaction_id_to_hex(tx_id)?);
+    }
+    for batch_id in eviction.dropped_batches {
+        let batch_id: [u8; 32] =
+            batch_id.try_into().map_err(|_| anyhow!("Invalid batch ID in the response"))?;
+        println!("dropped batch {}", bytes_to_hex_string(batch_id));
+    }
+    for tx_id in eviction.requeued_transactions {
+        println!("requeued transaction {}", transaction_id_to_hex(tx_id)?);
+    }
+
+    Ok(())
+}
+
+/// Parses a transaction ID given as a hex string.
+pub fn parse_transaction_id(tx_id: &str) -> Result<TransactionId> {
+    let digest =
+        RpoDigest::try_from(tx_id).map_err(|err| anyhow!("Invalid transaction ID: {err}"))?;
+
+    Ok(digest.into())
+}
+
+fn transaction_id_to_hex(tx_id: TransactionIdPb) -> Result<String> {
+    let tx_id: TransactionId =
+        tx_id.try_into().context("Invalid transaction ID in the response")?;
+
+    Ok(tx_id.to_hex())
+}
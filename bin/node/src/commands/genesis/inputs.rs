@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 /// They serve as intermediary representations, facilitating the conversion from
 /// placeholder types (like `GenesisInput`) to internal types (like `GenesisState`).
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct GenesisInput {
     pub version: u32,
     pub timestamp: u32,
@@ -22,7 +23,13 @@ pub enum AccountInput {
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct BasicFungibleFaucetInputs {
+    /// Name of the account file, defaults to `faucet` followed by the index of the faucet.
+    pub name: Option<String>,
+    /// Seed of the random generator used to create the account and its keys. The account is
+    /// generated randomly if omitted.
+    pub seed: Option<u64>,
     pub auth_scheme: AuthSchemeInput,
     pub token_symbol: String,
     pub decimals: u8,
@@ -44,6 +51,8 @@ impl Default for GenesisInput {
                 .expect("Current timestamp should be greater than unix epoch")
                 .as_secs() as u32,
             accounts: Some(vec![AccountInput::BasicFungibleFaucet(BasicFungibleFaucetInputs {
+                name: Some("faucet".to_string()),
+                seed: None,
                 auth_scheme: AuthSchemeInput::RpoFalcon512,
                 token_symbol: "POL".to_string(),
                 decimals: 12,
@@ -1,4 +1,5 @@
 use std::{
+    ffi::OsStr,
     fs,
     path::{Path, PathBuf},
 };
@@ -7,7 +8,10 @@ use anyhow::{anyhow, bail, Context, Result};
 pub use inputs::{AccountInput, AuthSchemeInput, GenesisInput};
 use miden_lib::{accounts::faucets::create_basic_fungible_faucet, AuthScheme};
 use miden_node_store::genesis::GenesisState;
-use miden_node_utils::{config::load_config, crypto::get_rpo_random_coin};
+use miden_node_utils::{
+    config::{load_config_with, UnknownKeys},
+    crypto::get_rpo_random_coin,
+};
 use miden_objects::{
     accounts::{Account, AccountData, AuthSecretKey},
     assets::TokenSymbol,
@@ -21,6 +25,7 @@ use tracing::info;
 mod inputs;
 
 const DEFAULT_ACCOUNTS_DIR: &str = "accounts/";
+const ACCOUNT_FILE_EXTENSION: &str = "mac";
 
 // MAKE GENESIS
 // ================================================================================================
@@ -38,7 +43,12 @@ const DEFAULT_ACCOUNTS_DIR: &str = "accounts/";
 /// This function returns a `Result` type. On successful creation of the genesis file, it returns
 /// `Ok(())`. If it fails at any point, due to issues like file existence checks or read/write
 /// operations, it returns an `Err` with a detailed error message.
-pub fn make_genesis(inputs_path: &PathBuf, output_path: &PathBuf, force: &bool) -> Result<()> {
+pub fn make_genesis(
+    inputs_path: &PathBuf,
+    output_path: &PathBuf,
+    force: &bool,
+    unknown_keys: UnknownKeys,
+) -> Result<()> {
     let inputs_path = Path::new(inputs_path);
     let output_path = Path::new(output_path);
 
@@ -73,9 +83,10 @@ pub fn make_genesis(inputs_path: &PathBuf, output_path: &PathBuf, force: &bool)
         },
     };
 
-    let genesis_input: GenesisInput = load_config(inputs_path).map_err(|err| {
-        anyhow!("Failed to load {} genesis input file: {err}", inputs_path.display())
-    })?;
+    let genesis_input: GenesisInput =
+        load_config_with(inputs_path, unknown_keys).map_err(|err| {
+            anyhow!("Failed to load {} genesis input file: {err}", inputs_path.display())
+        })?;
     info!("Genesis input file: {} has successfully been loaded.", inputs_path.display());
 
     let accounts_path = parent_path.join(DEFAULT_ACCOUNTS_DIR);
@@ -93,12 +104,15 @@ pub fn make_genesis(inputs_path: &PathBuf, output_path: &PathBuf, force: &bool)
 
 /// Converts the provided list of account inputs into [Account] objects.
 ///
-/// This function also writes the account data files into the default accounts directory.
+/// This function also writes the account data files into the default accounts directory. With
+/// `force`, account files of previous runs which don't match any of the accounts are removed.
 fn create_accounts(
     accounts: &[AccountInput],
     accounts_path: impl AsRef<Path>,
     force: &bool,
 ) -> Result<Vec<Account>> {
+    let names = account_names(accounts)?;
+
     if accounts_path.as_ref().try_exists()? {
         if !force {
             bail!(
@@ -106,20 +120,23 @@ fn create_accounts(
                 Use the --force flag to overwrite."
             );
         }
-        fs::remove_dir_all(&accounts_path).context("Failed to remove accounts directory")?;
+        remove_stale_account_files(accounts_path.as_ref(), &names)?;
     }
 
     fs::create_dir_all(&accounts_path).context("Failed to create accounts directory")?;
 
     let mut final_accounts = Vec::new();
-    let mut faucet_count = 0;
     let mut rng = ChaCha20Rng::from_seed(rand::random());
 
-    for account in accounts {
+    for (account, name) in accounts.iter().zip(names) {
         // build offchain account data from account inputs
-        let (mut account_data, name) = match account {
+        let mut account_data = match account {
             AccountInput::BasicFungibleFaucet(inputs) => {
                 info!("Creating fungible faucet account...");
+                let mut rng = match inputs.seed {
+                    Some(seed) => ChaCha20Rng::seed_from_u64(seed),
+                    None => ChaCha20Rng::from_seed(rng.gen()),
+                };
                 let (auth_scheme, auth_secret_key) = gen_auth_keys(inputs.auth_scheme, &mut rng)?;
 
                 let storage_mode = inputs.storage_mode.as_str().try_into()?;
@@ -133,18 +150,12 @@ fn create_accounts(
                     auth_scheme,
                 )?;
 
-                let name = format!(
-                    "faucet{}",
-                    (faucet_count > 0).then(|| faucet_count.to_string()).unwrap_or_default()
-                );
-                faucet_count += 1;
-
-                (AccountData::new(account, Some(account_seed), auth_secret_key), name)
+                AccountData::new(account, Some(account_seed), auth_secret_key)
             },
         };
 
         // write account data to file
-        let path = accounts_path.as_ref().join(format!("{name}.mac"));
+        let path = accounts_path.as_ref().join(format!("{name}.{ACCOUNT_FILE_EXTENSION}"));
 
         if !force && matches!(path.try_exists(), Ok(true)) {
             bail!("Failed to generate account file {} because it already exists. Use the --force flag to overwrite.", path.display());
@@ -162,6 +173,69 @@ fn create_accounts(
     Ok(final_accounts)
 }
 
+/// Returns the names of the account files, in the order of the accounts.
+///
+/// Accounts without a declared name are named after their type and index, e.g. `faucet`,
+/// `faucet1`, etc.
+fn account_names(accounts: &[AccountInput]) -> Result<Vec<String>> {
+    let mut names = Vec::with_capacity(accounts.len());
+    let mut faucet_count = 0;
+
+    for account in accounts {
+        let name = match account {
+            AccountInput::BasicFungibleFaucet(inputs) => {
+                let default_name = format!(
+                    "faucet{}",
+                    (faucet_count > 0).then(|| faucet_count.to_string()).unwrap_or_default()
+                );
+                faucet_count += 1;
+
+                inputs.name.clone().unwrap_or(default_name)
+            },
+        };
+
+        if name.is_empty()
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!(
+                "Invalid account name \"{name}\", names may only contain ASCII letters, digits, \
+                '-' and '_'"
+            );
+        }
+        if names.contains(&name) {
+            bail!("Duplicate account name \"{name}\" in genesis inputs");
+        }
+
+        names.push(name);
+    }
+
+    Ok(names)
+}
+
+/// Removes the account files in `accounts_path` which don't correspond to any of `names`.
+fn remove_stale_account_files(accounts_path: &Path, names: &[String]) -> Result<()> {
+    let entries = fs::read_dir(accounts_path).context("Failed to read accounts directory")?;
+
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension() != Some(OsStr::new(ACCOUNT_FILE_EXTENSION)) {
+            continue;
+        }
+
+        let is_declared = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| names.iter().any(|name| name == stem));
+        if !is_declared {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove account file {}", path.display()))?;
+            info!("Stale account file {} has been removed", path.display());
+        }
+    }
+
+    Ok(())
+}
+
 fn gen_auth_keys(
     auth_scheme_input: AuthSchemeInput,
     rng: &mut ChaCha20Rng,
@@ -189,7 +263,7 @@ mod tests {
     use miden_node_store::genesis::GenesisState;
     use miden_objects::{accounts::AccountData, utils::serde::Deserializable};
 
-    use super::make_genesis;
+    use super::{make_genesis, UnknownKeys};
     use crate::DEFAULT_GENESIS_FILE_PATH;
 
     #[test]
@@ -217,7 +291,13 @@ mod tests {
             let genesis_dat_file_path = PathBuf::from(DEFAULT_GENESIS_FILE_PATH);
 
             //  run make_genesis to generate genesis.dat and accounts folder and files
-            make_genesis(&genesis_inputs_file_path, &genesis_dat_file_path, &true).unwrap();
+            make_genesis(
+                &genesis_inputs_file_path,
+                &genesis_dat_file_path,
+                &true,
+                UnknownKeys::Deny,
+            )
+            .unwrap();
 
             let a0_file_path = PathBuf::from("accounts/faucet.mac");
 
@@ -243,4 +323,163 @@ mod tests {
             Ok(())
         });
     }
+
+    #[test]
+    fn test_make_genesis_is_reproducible_with_seeds() {
+        let genesis_inputs_file_path = PathBuf::from("genesis.toml");
+
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                genesis_inputs_file_path.as_path(),
+                r#"
+                version = 1
+                timestamp = 1672531200
+
+                [[accounts]]
+                type = "BasicFungibleFaucet"
+                name = "pol-faucet"
+                seed = 42
+                auth_scheme = "RpoFalcon512"
+                token_symbol = "POL"
+                decimals = 12
+                max_supply = 1000000
+                storage_mode = "public"
+
+                [[accounts]]
+                type = "BasicFungibleFaucet"
+                name = "eth_faucet"
+                seed = 7
+                auth_scheme = "RpoFalcon512"
+                token_symbol = "ETH"
+                decimals = 8
+                max_supply = 1000000
+                storage_mode = "private"
+            "#,
+            )?;
+
+            let genesis_dat_file_path = PathBuf::from(DEFAULT_GENESIS_FILE_PATH);
+            let account_file_paths = [
+                PathBuf::from("accounts/pol-faucet.mac"),
+                PathBuf::from("accounts/eth_faucet.mac"),
+            ];
+            let read_outputs = || {
+                let mut outputs = vec![fs::read(&genesis_dat_file_path).unwrap()];
+                outputs.extend(account_file_paths.iter().map(|path| fs::read(path).unwrap()));
+                outputs
+            };
+
+            make_genesis(
+                &genesis_inputs_file_path,
+                &genesis_dat_file_path,
+                &true,
+                UnknownKeys::Deny,
+            )
+            .unwrap();
+            let first_run = read_outputs();
+
+            // files of previous runs which don't match a declared account are removed
+            jail.create_file("accounts/faucet.mac", "stale")?;
+            jail.create_file("accounts/notes.txt", "unrelated")?;
+
+            make_genesis(
+                &genesis_inputs_file_path,
+                &genesis_dat_file_path,
+                &true,
+                UnknownKeys::Deny,
+            )
+            .unwrap();
+            let second_run = read_outputs();
+
+            assert_eq!(first_run, second_run);
+            assert!(!PathBuf::from("accounts/faucet.mac").exists());
+            assert!(PathBuf::from("accounts/notes.txt").exists());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_make_genesis_rejects_duplicate_names() {
+        let genesis_inputs_file_path = PathBuf::from("genesis.toml");
+
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                genesis_inputs_file_path.as_path(),
+                r#"
+                version = 1
+                timestamp = 1672531200
+
+                [[accounts]]
+                type = "BasicFungibleFaucet"
+                auth_scheme = "RpoFalcon512"
+                token_symbol = "POL"
+                decimals = 12
+                max_supply = 1000000
+                storage_mode = "public"
+
+                [[accounts]]
+                type = "BasicFungibleFaucet"
+                name = "faucet"
+                auth_scheme = "RpoFalcon512"
+                token_symbol = "ETH"
+                decimals = 8
+                max_supply = 1000000
+                storage_mode = "public"
+            "#,
+            )?;
+
+            let genesis_dat_file_path = PathBuf::from(DEFAULT_GENESIS_FILE_PATH);
+
+            let err = make_genesis(
+                &genesis_inputs_file_path,
+                &genesis_dat_file_path,
+                &true,
+                UnknownKeys::Deny,
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains("Duplicate account name \"faucet\""));
+            assert!(!genesis_dat_file_path.exists());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_make_genesis_rejects_unknown_keys() {
+        let genesis_inputs_file_path = PathBuf::from("genesis.toml");
+
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                genesis_inputs_file_path.as_path(),
+                r#"
+                version = 1
+                timestamp = 1672531200
+                timestmap = 1672531200
+            "#,
+            )?;
+
+            let genesis_dat_file_path = PathBuf::from(DEFAULT_GENESIS_FILE_PATH);
+
+            let err = make_genesis(
+                &genesis_inputs_file_path,
+                &genesis_dat_file_path,
+                &true,
+                UnknownKeys::Deny,
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains("`timestmap`: unknown key"), "{err}");
+            assert!(!genesis_dat_file_path.exists());
+
+            make_genesis(
+                &genesis_inputs_file_path,
+                &genesis_dat_file_path,
+                &true,
+                UnknownKeys::Warn,
+            )
+            .unwrap();
+            assert!(genesis_dat_file_path.exists());
+
+            Ok(())
+        });
+    }
 }
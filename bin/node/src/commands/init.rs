@@ -1,26 +1,127 @@
-use std::{fs::File, io::Write, path::PathBuf};
+use std::{
+    fs::{self, File},
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use miden_node_store::config::StoreConfig;
+use miden_node_utils::config::UnknownKeys;
+use miden_objects::assets::TokenSymbol;
 
-use crate::{commands::genesis::GenesisInput, config::NodeConfig};
+use super::{BLOCKSTORE_DIR, DATABASE_FILE, GENESIS_FILE};
+use crate::{
+    commands::{
+        genesis::{AccountInput, GenesisInput},
+        make_genesis,
+    },
+    config::NodeConfig,
+};
+
+/// Default token symbol of the faucet account of the genesis block.
+pub const DEFAULT_FAUCET_TOKEN_SYMBOL: &str = "POL";
 
 // INIT
 // ===================================================================================================
 
-pub fn init_config_files(config_file_path: PathBuf, genesis_file_path: PathBuf) -> Result<()> {
-    let config = NodeConfig::default();
-    let config_as_toml_string = toml::to_string(&config)
+/// Options of the deployment scaffolded by [init_node].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitOptions {
+    /// Store data directory. If set, the store of the configuration is pointed at it, and the
+    /// genesis block is generated in it.
+    pub data_directory: Option<PathBuf>,
+    /// Token symbol of the faucet account of the genesis block, none without faucet account.
+    pub faucet_token_symbol: Option<String>,
+}
+
+/// Generates the node and genesis configuration files, and with a data directory, creates its
+/// layout and generates the genesis block into it.
+///
+/// The genesis accounts are written to the `accounts` directory of the data directory, e.g. the
+/// faucet account file `accounts/faucet.mac`. Nothing is written if any of the files exists.
+pub fn init_node(
+    config_file_path: PathBuf,
+    genesis_file_path: PathBuf,
+    options: InitOptions,
+    unknown_keys: UnknownKeys,
+) -> Result<()> {
+    let store = options.data_directory.as_deref().map(store_config);
+    let config = match &store {
+        Some(store) => NodeConfig::default().with_store(store.clone()),
+        None => NodeConfig::default(),
+    };
+
+    let mut genesis = GenesisInput::default();
+    match &options.faucet_token_symbol {
+        Some(token_symbol) => {
+            TokenSymbol::new(token_symbol)
+                .map_err(|err| anyhow!("Invalid token symbol {token_symbol:?}: {err}"))?;
+            for account in genesis.accounts.iter_mut().flatten() {
+                match account {
+                    AccountInput::BasicFungibleFaucet(inputs) => {
+                        inputs.token_symbol = token_symbol.clone()
+                    },
+                }
+            }
+        },
+        None => genesis.accounts = None,
+    }
+
+    // Checked up front, so that a failed init doesn't leave a partial deployment behind
+    let mut outputs = vec![&config_file_path, &genesis_file_path];
+    outputs.extend(store.as_ref().map(|store| &store.genesis_filepath));
+    for path in outputs {
+        if path.try_exists()? {
+            bail!("Failed to initialize the node because {} already exists", path.display());
+        }
+    }
+
+    init_config_files(&config, &genesis, &config_file_path, &genesis_file_path)?;
+
+    if let Some(store) = store {
+        fs::create_dir_all(&store.blockstore_dir).with_context(|| {
+            format!("Failed to create the block store directory {}", store.blockstore_dir.display())
+        })?;
+        make_genesis(&genesis_file_path, &store.genesis_filepath, &false, unknown_keys)?;
+
+        println!("Genesis file successfully created at: {:?}", store.genesis_filepath);
+        println!(
+            "Start the node with: miden-node start --config {} node",
+            config_file_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the configuration of a store keeping its files in `data_directory`.
+fn store_config(data_directory: &Path) -> StoreConfig {
+    StoreConfig {
+        database_filepath: data_directory.join(DATABASE_FILE),
+        genesis_filepath: data_directory.join(GENESIS_FILE),
+        blockstore_dir: data_directory.join(BLOCKSTORE_DIR),
+        ..Default::default()
+    }
+}
+
+/// Writes the node configuration `config` and the genesis inputs `genesis` to new files.
+fn init_config_files(
+    config: &NodeConfig,
+    genesis: &GenesisInput,
+    config_file_path: &PathBuf,
+    genesis_file_path: &PathBuf,
+) -> Result<()> {
+    let config_as_toml_string = toml::to_string(config)
         .map_err(|err| anyhow!("Failed to serialize default config: {}", err))?;
 
-    write_string_in_file(config_as_toml_string, &config_file_path)?;
+    write_string_in_file(config_as_toml_string, config_file_path)?;
 
     println!("Config file successfully created at: {:?}", config_file_path);
 
-    let genesis = GenesisInput::default();
-    let genesis_as_toml_string = toml::to_string(&genesis)
+    let genesis_as_toml_string = toml::to_string(genesis)
         .map_err(|err| anyhow!("Failed to serialize default config: {}", err))?;
 
-    write_string_in_file(genesis_as_toml_string, &genesis_file_path)?;
+    write_string_in_file(genesis_as_toml_string, genesis_file_path)?;
 
     println!("Genesis config file successfully created at: {:?}", genesis_file_path);
 
@@ -40,3 +141,143 @@ fn write_string_in_file(content: String, path: &PathBuf) -> Result<()> {
 
     Ok(())
 }
+
+// INTERACTIVE INIT
+// ===================================================================================================
+
+/// Asks for the options of the deployment on `output`, reading the answers from `input`.
+///
+/// The `defaults` are used for the empty answers. The data directory is resolved against
+/// `current_dir`.
+pub fn prompt_init_options(
+    defaults: InitOptions,
+    current_dir: &Path,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> Result<InitOptions> {
+    let mut ask = |question: &str, default: &str| -> Result<String> {
+        write!(output, "{question} [{default}]: ")?;
+        output.flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer).context("Failed to read the answer")? == 0 {
+            bail!("The input ended before all the questions were answered");
+        }
+        let answer = match answer.trim() {
+            "" => default,
+            answer => answer,
+        };
+
+        Ok(answer.to_string())
+    };
+
+    let data_directory = defaults.data_directory.as_ref().map_or("none".to_string(), |dir| {
+        dir.strip_prefix(current_dir).unwrap_or(dir).display().to_string()
+    });
+    let data_directory =
+        ask("Data directory, none to only write the configuration", &data_directory)?;
+    let data_directory =
+        (data_directory != "none").then(|| current_dir.join(data_directory.as_str()));
+
+    let create_faucet = ask(
+        "Create a faucet account (yes/no)",
+        if defaults.faucet_token_symbol.is_some() {
+            "yes"
+        } else {
+            "no"
+        },
+    )?;
+    let faucet_token_symbol = match create_faucet.as_str() {
+        "yes" | "y" => Some(ask(
+            "Token symbol of the faucet",
+            defaults.faucet_token_symbol.as_deref().unwrap_or(DEFAULT_FAUCET_TOKEN_SYMBOL),
+        )?),
+        "no" | "n" => None,
+        other => bail!("Invalid answer {other:?}, expected yes or no"),
+    };
+
+    Ok(InitOptions { data_directory, faucet_token_symbol })
+}
+
+// TESTS
+// ===================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_node_utils::config::load_config;
+
+    use super::*;
+
+    #[test]
+    fn empty_answers_keep_the_defaults() {
+        let current_dir = Path::new("/opt/miden");
+        let defaults = InitOptions {
+            data_directory: Some(current_dir.join("data")),
+            faucet_token_symbol: Some(DEFAULT_FAUCET_TOKEN_SYMBOL.to_string()),
+        };
+
+        let mut output = Vec::new();
+        let options =
+            prompt_init_options(defaults.clone(), current_dir, "\n\n\n".as_bytes(), &mut output)
+                .unwrap();
+        assert_eq!(options, defaults);
+        assert!(String::from_utf8(output).unwrap().contains("Data directory"));
+
+        let options = prompt_init_options(
+            defaults.clone(),
+            current_dir,
+            "store\nno\n".as_bytes(),
+            Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            InitOptions {
+                data_directory: Some(current_dir.join("store")),
+                faucet_token_symbol: None,
+            }
+        );
+
+        let options =
+            prompt_init_options(defaults, current_dir, "none\nyes\nETH\n".as_bytes(), Vec::new())
+                .unwrap();
+        assert_eq!(
+            options,
+            InitOptions {
+                data_directory: None,
+                faucet_token_symbol: Some("ETH".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn init_scaffolds_the_data_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("miden-node.toml");
+        let genesis_path = dir.path().join("genesis.toml");
+        let data_directory = dir.path().join("data");
+        let options = InitOptions {
+            data_directory: Some(data_directory.clone()),
+            faucet_token_symbol: None,
+        };
+
+        init_node(config_path.clone(), genesis_path.clone(), options.clone(), UnknownKeys::Deny)
+            .unwrap();
+
+        let config: NodeConfig = load_config(&config_path).unwrap();
+        let (.., store) = config.into_parts();
+        assert_eq!(store.database_filepath, data_directory.join(DATABASE_FILE));
+        assert_eq!(store.genesis_filepath, data_directory.join(GENESIS_FILE));
+        assert_eq!(store.blockstore_dir, data_directory.join(BLOCKSTORE_DIR));
+        assert!(store.genesis_filepath.is_file());
+        assert!(store.blockstore_dir.is_dir());
+
+        // An existing deployment is left untouched
+        let config_file = fs::read(&config_path).unwrap();
+        fs::remove_file(&genesis_path).unwrap();
+        assert!(init_node(config_path.clone(), genesis_path.clone(), options, UnknownKeys::Deny)
+            .is_err());
+        assert!(!genesis_path.exists());
+        assert_eq!(fs::read(&config_path).unwrap(), config_file);
+    }
+}
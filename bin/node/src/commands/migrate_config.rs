@@ -0,0 +1,590 @@
+//! Upgrade of the configuration files written for older releases.
+//!
+//! Every breaking change of the configuration keys increases [CONFIG_VERSION], and adds a
+//! [Migration] of the affected component to [migrations], upgrading its files from the previous
+//! version. Migrations are applied in order of their version, so a file of any older version is
+//! upgraded to the current one.
+
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use miden_node_block_producer::config::BlockProducerConfig;
+use miden_node_rpc::config::RpcConfig;
+use miden_node_store::config::StoreConfig;
+use miden_node_utils::config::{
+    load_config_with, ConfigProblem, ConfigProblemKind, UnknownKeys, CONFIG_VERSION,
+};
+use serde::de::DeserializeOwned;
+use tempfile::NamedTempFile;
+use toml::{Table, Value};
+
+use crate::config::NodeConfig;
+
+const CONFIG_VERSION_KEY: &str = "config_version";
+
+/// Keys of the faucet configuration, as dotted paths in which `*` stands for any array index.
+///
+/// The faucet configuration is defined by the faucet binary, hence its keys are listed here
+/// instead of being checked against its configuration struct.
+const FAUCET_KEYS: &[&str] = &[
+    "config_version",
+    "endpoint.host",
+    "endpoint.port",
+    "node_url",
+    "timeout_ms",
+    "tokens.*.token_symbol",
+    "tokens.*.faucet_account_path",
+    "tokens.*.asset_amount_options",
+    "tokens.*.default_asset_amount",
+    "embed_private_notes",
+    "metrics_endpoint.host",
+    "metrics_endpoint.port",
+];
+
+// MIGRATIONS
+// ================================================================================================
+
+/// Component whose configuration file is migrated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ConfigComponent {
+    Node,
+    Store,
+    Rpc,
+    BlockProducer,
+    Faucet,
+}
+
+impl ConfigComponent {
+    /// Returns the table holding the configuration of the component in the node configuration
+    /// file.
+    fn node_section(self) -> Option<&'static str> {
+        match self {
+            Self::Store => Some("store"),
+            Self::Rpc => Some("rpc"),
+            Self::BlockProducer => Some("block_producer"),
+            Self::Node | Self::Faucet => None,
+        }
+    }
+}
+
+/// Changes of the configuration keys of a component, upgrading its files from `from_version` to
+/// the next version.
+///
+/// The migrations of the store, RPC and block producer also apply to their tables of the node
+/// configuration file.
+pub struct Migration {
+    pub component: ConfigComponent,
+    pub from_version: u32,
+    pub changes: Vec<Change>,
+}
+
+/// A change of the configuration keys, which are dotted paths whose numeric segments index arrays.
+pub enum Change {
+    /// The value of the `from` key moved to the `to` key. Nothing changes if `from` is not set.
+    Move { from: &'static str, to: &'static str },
+    /// The `key` became required, and is set to `value` if it's missing from a table present in
+    /// the file. The `note` explains the value in the report.
+    Default {
+        key: &'static str,
+        value: Value,
+        note: &'static str,
+    },
+}
+
+/// Returns the migrations of all components, by increasing version.
+fn migrations() -> Vec<Migration> {
+    vec![
+        // The faucet dispenses several tokens, each one configured in its own `tokens` table
+        Migration {
+            component: ConfigComponent::Faucet,
+            from_version: 0,
+            changes: vec![
+                Change::Move {
+                    from: "faucet_account_path",
+                    to: "tokens.0.faucet_account_path",
+                },
+                Change::Move {
+                    from: "asset_amount_options",
+                    to: "tokens.0.asset_amount_options",
+                },
+                Change::Default {
+                    key: "tokens.0.token_symbol",
+                    value: Value::String("POL".to_string()),
+                    note: "symbol of the single token of the faucet in earlier releases",
+                },
+            ],
+        },
+    ]
+}
+
+// MIGRATE CONFIG
+// ================================================================================================
+
+/// Upgrades the `input` configuration file of the `component` to the current [CONFIG_VERSION],
+/// writes it to `output` and prints the report of the migration.
+pub fn run_migrate_config(input: &Path, output: &Path, component: ConfigComponent) -> Result<()> {
+    let content =
+        fs::read_to_string(input).with_context(|| format!("Failed to read {}", input.display()))?;
+    let migrated = migrate(component, &content, &migrations(), CONFIG_VERSION)?;
+
+    let mut file = File::options()
+        .write(true)
+        .create_new(true)
+        .open(output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+    file.write_all(migrated.output.as_bytes())
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    for line in &migrated.report {
+        println!("{line}");
+    }
+    println!("Configuration file successfully migrated to: {}", output.display());
+
+    Ok(())
+}
+
+/// An upgraded configuration file, along with the report of its changes.
+#[derive(Debug)]
+struct Migrated {
+    output: String,
+    report: Vec<String>,
+}
+
+/// Upgrades the configuration file of the `component` to the `target` version.
+///
+/// The keys which can't be mapped to the configuration of the target version are removed, and
+/// kept commented out under an `# UNRECOGNIZED` block at the end of the file.
+fn migrate(
+    component: ConfigComponent,
+    input: &str,
+    migrations: &[Migration],
+    target: u32,
+) -> Result<Migrated> {
+    let mut table: Table =
+        toml::from_str(input).context("Failed to parse the configuration file")?;
+    let version = match table.remove(CONFIG_VERSION_KEY) {
+        None => 0,
+        Some(Value::Integer(version)) => {
+            u32::try_from(version).context("Invalid `config_version`")?
+        },
+        Some(value) => bail!("Invalid `config_version` {value}, expected an integer"),
+    };
+    if version > target {
+        bail!(
+            "The configuration file is at version {version}, which is newer than the version \
+             {target} supported by this release"
+        );
+    }
+
+    let mut report = vec![format!("upgrading from version {version} to version {target}")];
+    for from_version in version..target {
+        for migration in
+            migrations.iter().filter(|migration| migration.from_version == from_version)
+        {
+            let prefix = if migration.component == component {
+                None
+            } else if component == ConfigComponent::Node {
+                match migration.component.node_section() {
+                    Some(section) => Some(section),
+                    None => continue,
+                }
+            } else {
+                continue;
+            };
+
+            for change in &migration.changes {
+                apply_change(&mut table, change, prefix, &mut report);
+            }
+        }
+    }
+    table.insert(CONFIG_VERSION_KEY.to_string(), Value::Integer(target.into()));
+
+    let mut unrecognized = Vec::new();
+    for problem in check(component, &table)? {
+        let key = problem.key;
+        match problem.kind {
+            ConfigProblemKind::UnknownKey => {
+                if let Some(value) = remove_value(&mut table, &key) {
+                    report.push(format!("could not map `{key}`, kept under # UNRECOGNIZED"));
+                    unrecognized.push((key, value));
+                }
+            },
+            ConfigProblemKind::MissingKey => report.push(format!(
+                "`{key}` is missing and has no default, it must be set by hand before migrating \
+                 the output again"
+            )),
+            ConfigProblemKind::InvalidValue | ConfigProblemKind::InvalidFile => {
+                report.push(format!("`{key}`: {}, it must be fixed by hand", problem.message))
+            },
+        }
+    }
+
+    let mut output = toml::to_string(&table).context("Failed to serialize the configuration")?;
+    if !unrecognized.is_empty() {
+        output.push_str(
+            "\n# UNRECOGNIZED\n# Keys of the original file which could not be mapped to the \
+             current configuration.\n",
+        );
+        for (key, value) in unrecognized {
+            writeln!(output, "# {key} = {value}").expect("Writing to a string can't fail");
+        }
+    }
+
+    Ok(Migrated { output, report })
+}
+
+/// Applies the `change` to the keys of the `prefix` table, or to the top-level keys.
+fn apply_change(
+    table: &mut Table,
+    change: &Change,
+    prefix: Option<&str>,
+    report: &mut Vec<String>,
+) {
+    let prefixed =
+        |key: &str| prefix.map_or_else(|| key.to_string(), |prefix| format!("{prefix}.{key}"));
+
+    match change {
+        Change::Move { from, to } => {
+            let (from, to) = (prefixed(from), prefixed(to));
+            if value_mut(table, &from).is_none() {
+                return;
+            }
+            if value_mut(table, &to).is_some() {
+                report.push(format!("did not move `{from}` to `{to}`, which is already set"));
+                return;
+            }
+
+            let value = remove_value(table, &from).expect("The value was found above");
+            match insert_value(table, &to, value) {
+                Ok(()) => report.push(format!("moved `{from}` to `{to}`")),
+                Err(value) => {
+                    report.push(format!("could not move `{from}` to `{to}`"));
+                    insert_value(table, &from, value).expect("The value was removed from there");
+                },
+            }
+        },
+        Change::Default { key, value, note } => {
+            let key = prefixed(key);
+            let parent_exists = key
+                .rsplit_once('.')
+                .map_or(true, |(parent, _)| value_mut(table, parent).is_some());
+            if parent_exists
+                && value_mut(table, &key).is_none()
+                && insert_value(table, &key, value.clone()).is_ok()
+            {
+                report.push(format!("set `{key}` to {value}: {note}"));
+            }
+        },
+    }
+}
+
+/// Returns the problems of the configuration of the component.
+fn check(component: ConfigComponent, table: &Table) -> Result<Vec<ConfigProblem>> {
+    match component {
+        ConfigComponent::Node => load_problems::<NodeConfig>(table),
+        ConfigComponent::Store => load_problems::<StoreConfig>(table),
+        ConfigComponent::Rpc => load_problems::<RpcConfig>(table),
+        ConfigComponent::BlockProducer => load_problems::<BlockProducerConfig>(table),
+        ConfigComponent::Faucet => {
+            let mut problems = Vec::new();
+            unknown_faucet_keys(table, None, &mut problems);
+            Ok(problems)
+        },
+    }
+}
+
+/// Returns the problems reported when loading the configuration as `T`.
+fn load_problems<T: DeserializeOwned>(table: &Table) -> Result<Vec<ConfigProblem>> {
+    let mut file = NamedTempFile::new().context("Failed to create a temporary file")?;
+    let content = toml::to_string(table).context("Failed to serialize the configuration")?;
+    file.write_all(content.as_bytes()).context("Failed to write a temporary file")?;
+
+    Ok(load_config_with::<T>(file.path(), UnknownKeys::Deny)
+        .err()
+        .map(|err| err.problems)
+        .unwrap_or_default())
+}
+
+/// Reports the keys of the `table` at the `path` which are not in [FAUCET_KEYS].
+fn unknown_faucet_keys(
+    table: &Table,
+    path: Option<(&str, &str)>,
+    problems: &mut Vec<ConfigProblem>,
+) {
+    for (key, value) in table {
+        let (key_path, key_pattern) = match path {
+            Some((path, pattern)) => (format!("{path}.{key}"), format!("{pattern}.{key}")),
+            None => (key.clone(), key.clone()),
+        };
+        if FAUCET_KEYS.contains(&key_pattern.as_str()) {
+            continue;
+        }
+
+        let nested = format!("{key_pattern}.");
+        if !FAUCET_KEYS.iter().any(|known| known.starts_with(&nested)) {
+            problems.push(ConfigProblem {
+                key: key_path,
+                kind: ConfigProblemKind::UnknownKey,
+                message: "unknown key".to_string(),
+            });
+            continue;
+        }
+
+        match value {
+            Value::Table(table) => {
+                unknown_faucet_keys(table, Some((&key_path, &key_pattern)), problems)
+            },
+            Value::Array(items) => {
+                let item_pattern = format!("{key_pattern}.*");
+                for (index, item) in items.iter().enumerate() {
+                    if let Value::Table(table) = item {
+                        let item_path = format!("{key_path}.{index}");
+                        unknown_faucet_keys(table, Some((&item_path, &item_pattern)), problems);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+// KEY PATHS
+// ================================================================================================
+
+/// Returns the value at the dotted `key` path.
+fn value_mut<'a>(table: &'a mut Table, key: &str) -> Option<&'a mut Value> {
+    let mut segments = key.split('.');
+    let mut value = table.get_mut(segments.next()?)?;
+    for segment in segments {
+        value = match value {
+            Value::Table(table) => table.get_mut(segment)?,
+            Value::Array(items) => items.get_mut(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+
+    Some(value)
+}
+
+/// Returns the table at the dotted `key` path, creating the missing tables along the way. An
+/// index right past the end of an array appends a new table to it.
+fn table_mut<'a>(table: &'a mut Table, key: &str) -> Option<&'a mut Table> {
+    let mut table = table;
+    let mut segments = key.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        let indexed = segments.peek().is_some_and(|next| next.parse::<usize>().is_ok());
+        let value = table.entry(segment).or_insert_with(|| {
+            if indexed {
+                Value::Array(Vec::new())
+            } else {
+                Value::Table(Table::new())
+            }
+        });
+
+        table = match value {
+            Value::Table(table) => table,
+            Value::Array(items) => {
+                let index: usize = segments.next()?.parse().ok()?;
+                if index == items.len() {
+                    items.push(Value::Table(Table::new()));
+                }
+                match items.get_mut(index)? {
+                    Value::Table(table) => table,
+                    _ => return None,
+                }
+            },
+            _ => return None,
+        };
+    }
+
+    Some(table)
+}
+
+/// Removes the value at the dotted `key` path.
+fn remove_value(table: &mut Table, key: &str) -> Option<Value> {
+    let Some((parent, last)) = key.rsplit_once('.') else {
+        return table.remove(key);
+    };
+
+    match value_mut(table, parent)? {
+        Value::Table(table) => table.remove(last),
+        Value::Array(items) => {
+            let index = last.parse::<usize>().ok().filter(|index| *index < items.len())?;
+            Some(items.remove(index))
+        },
+        _ => None,
+    }
+}
+
+/// Sets the missing value at the dotted `key` path, or returns the value back if the path can't
+/// hold it.
+fn insert_value(table: &mut Table, key: &str, value: Value) -> Result<(), Value> {
+    let (parent, last) = match key.rsplit_once('.') {
+        Some((parent, last)) => match table_mut(table, parent) {
+            Some(parent) => (parent, last),
+            None => return Err(value),
+        },
+        None => (table, key),
+    };
+    if parent.contains_key(last) {
+        return Err(value);
+    }
+
+    parent.insert(last.to_string(), value);
+    Ok(())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_node_store::config::StoreConfig;
+    use toml::{Table, Value};
+
+    use super::{migrate, migrations, Change, ConfigComponent, Migration};
+
+    /// Migrations of the store renaming `blocks` in two steps, and making a key required.
+    fn store_migrations() -> Vec<Migration> {
+        vec![
+            Migration {
+                component: ConfigComponent::Store,
+                from_version: 0,
+                changes: vec![Change::Move { from: "blocks", to: "blockstore" }],
+            },
+            Migration {
+                component: ConfigComponent::Store,
+                from_version: 1,
+                changes: vec![
+                    Change::Move { from: "blockstore", to: "blockstore_dir" },
+                    Change::Default {
+                        key: "slow_query_threshold_ms",
+                        value: Value::Integer(1000),
+                        note: "former default",
+                    },
+                ],
+            },
+        ]
+    }
+
+    const STORE_CONFIG: &str = r#"
+        database_filepath = "local.sqlite3"
+        genesis_filepath = "genesis.dat"
+        endpoint = { host = "127.0.0.1", port = 8080 }
+    "#;
+
+    #[test]
+    fn chained_migrations_are_applied_in_order() {
+        let input = format!("{STORE_CONFIG}\nblocks = \"blocks\"");
+        let migrated = migrate(ConfigComponent::Store, &input, &store_migrations(), 2).unwrap();
+
+        let config: StoreConfig = toml::from_str(&migrated.output).unwrap();
+        assert_eq!(config.blockstore_dir.to_str(), Some("blocks"));
+        assert_eq!(config.slow_query_threshold_ms, 1000);
+        assert_eq!(config.config_version, 2);
+        assert_eq!(
+            migrated.report,
+            [
+                "upgrading from version 0 to version 2",
+                "moved `blocks` to `blockstore`",
+                "moved `blockstore` to `blockstore_dir`",
+                "set `slow_query_threshold_ms` to 1000: former default",
+            ]
+        );
+        assert!(!migrated.output.contains("UNRECOGNIZED"), "{}", migrated.output);
+    }
+
+    #[test]
+    fn migrations_start_at_the_version_of_the_file() {
+        // `blocks` was only renamed from version 0, so it's not recognized at version 1
+        let input = format!(
+            "{STORE_CONFIG}\nconfig_version = 1\nblockstore = \"blocks\"\nblocks = \"old\"\n\
+             slow_query_threshold_ms = 200"
+        );
+        let migrated = migrate(ConfigComponent::Store, &input, &store_migrations(), 2).unwrap();
+
+        let config: StoreConfig = toml::from_str(&migrated.output).unwrap();
+        assert_eq!(config.blockstore_dir.to_str(), Some("blocks"));
+        assert_eq!(config.slow_query_threshold_ms, 200);
+        assert!(migrated.output.contains("# blocks = \"old\""), "{}", migrated.output);
+
+        // Files of newer versions are rejected
+        let input = format!("{STORE_CONFIG}\nconfig_version = 3");
+        assert!(migrate(ConfigComponent::Store, &input, &store_migrations(), 2).is_err());
+    }
+
+    #[test]
+    fn unmappable_keys_are_kept_commented_out() {
+        let input = r#"
+            database_filepath = "local.sqlite3"
+            genesis_filepath = "genesis.dat"
+            blocks = "blocks"
+            colour = "blue"
+            endpoint = { host = "127.0.0.1", port = 8080, protocol = "http" }
+        "#;
+        let migrated = migrate(ConfigComponent::Store, input, &store_migrations(), 2).unwrap();
+
+        let (_, unrecognized) = migrated.output.split_once("# UNRECOGNIZED").unwrap();
+        assert!(unrecognized.contains("\n# colour = \"blue\"\n"), "{unrecognized}");
+        assert!(unrecognized.contains("\n# endpoint.protocol = \"http\"\n"), "{unrecognized}");
+        assert!(migrated
+            .report
+            .contains(&"could not map `colour`, kept under # UNRECOGNIZED".to_string()));
+
+        // The unrecognized keys are only comments of a valid configuration file
+        let config: StoreConfig = toml::from_str(&migrated.output).unwrap();
+        assert_eq!(config.endpoint.port, 8080);
+        assert_eq!(config.blockstore_dir.to_str(), Some("blocks"));
+    }
+
+    #[test]
+    fn component_migrations_apply_to_the_node_configuration() {
+        let input = r#"
+            [store]
+            blocks = "blocks"
+            colour = "blue"
+        "#;
+        let migrated = migrate(ConfigComponent::Node, input, &store_migrations(), 2).unwrap();
+
+        let (config, unrecognized) = migrated.output.split_once("# UNRECOGNIZED").unwrap();
+        let config: Table = toml::from_str(config).unwrap();
+        assert_eq!(config["config_version"].as_integer(), Some(2));
+        assert_eq!(config["store"]["blockstore_dir"].as_str(), Some("blocks"));
+        assert_eq!(config["store"]["slow_query_threshold_ms"].as_integer(), Some(1000));
+        assert!(unrecognized.contains("\n# store.colour = \"blue\"\n"), "{unrecognized}");
+        // The other sections are missing, and must be set by hand
+        assert!(migrated.report.iter().any(|line| line.contains("is missing")));
+    }
+
+    #[test]
+    fn single_token_faucet_configuration_is_migrated() {
+        let input = r#"
+            endpoint = { host = "localhost",  port = 8080 }
+            node_url = "http://localhost:57291"
+            timeout_ms = 10000
+            asset_amount_options = [100, 500, 1000]
+            faucet_account_path = "accounts/faucet.mac"
+            colour = "blue"
+        "#;
+        let migrated = migrate(ConfigComponent::Faucet, input, &migrations(), 1).unwrap();
+
+        let (config, unrecognized) = migrated.output.split_once("# UNRECOGNIZED").unwrap();
+        let config: Table = toml::from_str(config).unwrap();
+        assert_eq!(config["config_version"].as_integer(), Some(1));
+        assert_eq!(config["node_url"].as_str(), Some("http://localhost:57291"));
+        assert!(!config.contains_key("faucet_account_path"));
+
+        let tokens = config["tokens"].as_array().unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0]["token_symbol"].as_str(), Some("POL"));
+        assert_eq!(tokens[0]["faucet_account_path"].as_str(), Some("accounts/faucet.mac"));
+        assert_eq!(tokens[0]["asset_amount_options"].as_array().unwrap().len(), 3);
+        assert!(unrecognized.contains("\n# colour = \"blue\"\n"), "{unrecognized}");
+    }
+}
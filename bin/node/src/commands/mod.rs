@@ -1,4 +1,16 @@
+pub mod audit_log;
+pub mod doctor;
+pub mod dry_run;
+pub mod dump_mempool_graph;
+pub mod evict_tx;
 mod genesis;
 pub mod init;
+pub mod migrate_config;
 pub mod start;
+pub mod store;
 pub use genesis::make_genesis;
+
+/// Names of the files of the store data directory, as in the default store configuration.
+const DATABASE_FILE: &str = "miden-store.sqlite3";
+const GENESIS_FILE: &str = "genesis.dat";
+const BLOCKSTORE_DIR: &str = "blocks";
@@ -1,37 +1,252 @@
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+};
+
 use anyhow::{Context, Result};
-use miden_node_block_producer::server::BlockProducer;
-use miden_node_rpc::server::Rpc;
-use miden_node_store::server::Store;
+use miden_node_block_producer::{config::BlockProducerConfig, server::BlockProducer};
+use miden_node_rpc::{config::RpcConfig, server::Rpc};
+use miden_node_store::{config::StoreConfig, server::Store};
+use miden_node_utils::{config::local_url, runtime::spawn_named_in, runtime_info::RuntimeInfo};
 use tokio::task::JoinSet;
+use tracing::info;
 
-use crate::config::NodeConfig;
+use crate::{config::NodeConfig, long_version};
 
 // START
 // ===================================================================================================
 
-pub async fn start_node(config: NodeConfig) -> Result<()> {
-    let (block_producer, rpc, store) = config.into_parts();
+pub async fn start_node(
+    config: NodeConfig,
+    config_path: &Path,
+    runtime_info_path: &Path,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let proving_pool = config.runtime().proving_pool().context("Starting the proving threads")?;
+    let (mut block_producer, mut rpc, store) = config.into_parts();
+    let data_directory = store_data_directory(&store);
 
     let mut join_set = JoinSet::new();
 
     // Start store. The store endpoint is available after loading completes.
     let store = Store::init(store).await.context("Loading store")?;
-    join_set.spawn(async move { store.serve().await.context("Serving store") });
+    let store_addr = store.local_addr();
+    let genesis_commitment = store.genesis_commitment();
+    // The components connect to the bound address, which differs from the configured endpoint
+    // for port 0.
+    block_producer.store_url = local_url(store_addr);
+    rpc.store_url = local_url(store_addr);
+    spawn_named_in(&mut join_set, "miden-store:serve", async move {
+        store.serve().await.context("Serving store")
+    });
 
     // Start block-producer. The block-producer's endpoint is available after loading completes.
-    let block_producer =
-        BlockProducer::init(block_producer).await.context("Loading block-producer")?;
-    join_set.spawn(async move { block_producer.serve().await.context("Serving block-producer") });
+    let block_producer = BlockProducer::init_with_proving_pool(block_producer, proving_pool)
+        .await
+        .context("Loading block-producer")?;
+    let block_producer_addr = block_producer.local_addr();
+    rpc.block_producer_url = local_url(block_producer_addr);
+    spawn_named_in(&mut join_set, "miden-block-producer:serve", async move {
+        block_producer.serve().await.context("Serving block-producer")
+    });
 
     // Start RPC component.
     let rpc = Rpc::init(rpc).await.context("Loading RPC")?;
-    join_set.spawn(async move { rpc.serve().await.context("Serving RPC") });
+    let rpc_addr = rpc.local_addr();
+    spawn_named_in(&mut join_set, "miden-rpc:serve", async move {
+        rpc.serve().await.context("Serving RPC")
+    });
+
+    let info = RuntimeInfo::new("node", &long_version())
+        .with_listener("store", store_addr)
+        .with_listener("block-producer", block_producer_addr)
+        .with_listener("rpc", rpc_addr)
+        .with_data_directory(data_directory)
+        .with_genesis_commitment(genesis_commitment)
+        .with_config_file(config_path)
+        .context("Reading configuration file")?;
+
+    run_until_shutdown(info, runtime_info_path, join_set, shutdown).await
+}
+
+pub async fn start_store(
+    config: StoreConfig,
+    config_path: &Path,
+    runtime_info_path: &Path,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let data_directory = store_data_directory(&config);
+    let store = Store::init(config).await.context("Loading store")?;
+
+    let info = RuntimeInfo::new("store", &long_version())
+        .with_listener("store", store.local_addr())
+        .with_data_directory(data_directory)
+        .with_genesis_commitment(store.genesis_commitment())
+        .with_config_file(config_path)
+        .context("Reading configuration file")?;
+
+    let mut join_set = JoinSet::new();
+    spawn_named_in(&mut join_set, "miden-store:serve", async move {
+        store.serve().await.context("Serving store")
+    });
+
+    run_until_shutdown(info, runtime_info_path, join_set, shutdown).await
+}
+
+pub async fn start_block_producer(
+    config: BlockProducerConfig,
+    config_path: &Path,
+    runtime_info_path: &Path,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let block_producer = BlockProducer::init(config).await.context("Loading block-producer")?;
+
+    let info = RuntimeInfo::new("block-producer", &long_version())
+        .with_listener("block-producer", block_producer.local_addr())
+        .with_config_file(config_path)
+        .context("Reading configuration file")?;
+
+    let mut join_set = JoinSet::new();
+    spawn_named_in(&mut join_set, "miden-block-producer:serve", async move {
+        block_producer.serve().await.context("Serving block-producer")
+    });
+
+    run_until_shutdown(info, runtime_info_path, join_set, shutdown).await
+}
+
+pub async fn start_rpc(
+    config: RpcConfig,
+    config_path: &Path,
+    runtime_info_path: &Path,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let rpc = Rpc::init(config).await.context("Loading RPC")?;
+
+    let info = RuntimeInfo::new("rpc", &long_version())
+        .with_listener("rpc", rpc.local_addr())
+        .with_config_file(config_path)
+        .context("Reading configuration file")?;
+
+    let mut join_set = JoinSet::new();
+    spawn_named_in(&mut join_set, "miden-rpc:serve", async move {
+        rpc.serve().await.context("Serving RPC")
+    });
+
+    run_until_shutdown(info, runtime_info_path, join_set, shutdown).await
+}
 
-    // block on all tasks
-    while let Some(res) = join_set.join_next().await {
-        // For now, if one of the components fails, crash the node
-        res??;
+/// Completes once the process is asked to shut down with `Ctrl-C`.
+pub async fn shutdown_signal() {
+    if tokio::signal::ctrl_c().await.is_err() {
+        // Without signal handling, the components run until they fail
+        std::future::pending::<()>().await;
     }
+}
+
+// HELPERS
+// ===================================================================================================
+
+/// Writes the runtime info file of the started components and prints the startup banner, then
+/// runs the components until one of them fails or `shutdown` completes.
+///
+/// The runtime info file is removed once the components stopped.
+async fn run_until_shutdown(
+    info: RuntimeInfo,
+    runtime_info_path: &Path,
+    mut join_set: JoinSet<Result<()>>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let _runtime_info = info
+        .write(runtime_info_path)
+        .with_context(|| format!("Writing {}", runtime_info_path.display()))?;
+    println!("{info}");
+
+    tokio::select! {
+        result = async {
+            // block on all tasks
+            while let Some(res) = join_set.join_next().await {
+                // For now, if one of the components fails, crash the node
+                res??;
+            }
+            Ok(())
+        } => result,
+        () = shutdown => {
+            info!(component = %info.component, "Shutting down");
+            Ok(())
+        },
+    }
+}
+
+/// Returns the directory holding the store's database.
+fn store_data_directory(config: &StoreConfig) -> PathBuf {
+    match config.database_filepath.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
 
-    Ok(())
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, net::TcpStream, time::Duration};
+
+    use miden_node_store::genesis::GenesisState;
+    use miden_node_utils::{config::Endpoint, runtime_info::RUNTIME_INFO_SCHEMA_VERSION};
+    use miden_objects::utils::Serializable;
+    use tokio::{sync::oneshot, time};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn started_store_writes_its_runtime_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("miden-store.toml");
+        let runtime_info_path = RuntimeInfo::default_path(&config_path);
+        let config = StoreConfig {
+            endpoint: Endpoint { host: "127.0.0.1".to_string(), port: 0 },
+            database_filepath: dir.path().join("miden-store.sqlite3"),
+            genesis_filepath: dir.path().join("genesis.dat"),
+            blockstore_dir: dir.path().join("blocks"),
+            ..Default::default()
+        };
+        fs::write(&config_path, toml::to_string(&config).unwrap()).unwrap();
+        let genesis = GenesisState::new(vec![], 1, 0);
+        fs::write(&config.genesis_filepath, genesis.to_bytes()).unwrap();
+
+        let (shutdown_sender, shutdown) = oneshot::channel::<()>();
+        let store = tokio::spawn({
+            let (config_path, runtime_info_path) = (config_path.clone(), runtime_info_path.clone());
+            async move {
+                let shutdown = async {
+                    let _ = shutdown.await;
+                };
+                start_store(config, &config_path, &runtime_info_path, shutdown).await
+            }
+        });
+
+        while !runtime_info_path.exists() {
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        let info = RuntimeInfo::read(&runtime_info_path).unwrap();
+
+        assert_eq!(info.schema_version, RUNTIME_INFO_SCHEMA_VERSION);
+        assert_eq!(info.component, "store");
+        assert_eq!(info.version.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.pid, std::process::id());
+        assert_eq!(info.data_directory.as_deref(), Some(dir.path()));
+        assert_eq!(info.config_path, Some(fs::canonicalize(&config_path).unwrap()));
+        assert!(info.config_hash.is_some());
+        assert_eq!(info.genesis_commitment, Some(genesis.into_block().unwrap().hash().to_hex()));
+
+        // The listener is reported with the port chosen by the system
+        let store_addr = info.listeners["store"];
+        assert_ne!(store_addr.port(), 0);
+        TcpStream::connect(store_addr).expect("The store must listen on the reported address");
+
+        shutdown_sender.send(()).unwrap();
+        store.await.unwrap().unwrap();
+        assert!(!runtime_info_path.exists(), "The runtime info file must be removed on shutdown");
+    }
 }
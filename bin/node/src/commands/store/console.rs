@@ -0,0 +1,408 @@
+//! Interactive console running [StoreQuery]s against a running store.
+//!
+//! Every line is a query, parsed and run exactly like the `store query` subcommands, or one of the
+//! console commands:
+//!
+//! - `let <name> = <query>` runs the query and binds its result to `$name`. The fields of a bound
+//!   result can be used as arguments of the following queries, e.g. `let h = header` and then
+//!   `notes $h.block_num`.
+//! - `vars` lists the bound results.
+//! - `format pretty|json` changes the format of the results.
+//! - `help` lists the commands, and `exit` or `quit` leave the console.
+//!
+//! The console reads the lines from a line editor with history and completion of the command
+//! names when run in a terminal, and one line after another from the standard input otherwise,
+//! e.g. to run a script of queries.
+
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, IsTerminal, Write},
+};
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use clap::{CommandFactory, Parser, ValueEnum};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Context, Editor, Helper,
+};
+use serde_json::Value;
+
+use super::query::{render, scalar, OutputFormat, StoreQuery, StoreQueryClient};
+
+/// Names of the commands handled by the console itself rather than by [StoreQuery].
+const CONSOLE_COMMANDS: [&str; 6] = ["let", "vars", "format", "help", "exit", "quit"];
+
+const PROMPT: &str = "store> ";
+
+// CONSOLE
+// ================================================================================================
+
+/// A line of the console, parsed by the same parser as the `store query` subcommands.
+#[derive(Parser)]
+#[command(no_binary_name = true, disable_help_subcommand = true, disable_help_flag = true)]
+struct QueryLine {
+    #[command(subcommand)]
+    query: StoreQuery,
+}
+
+/// What the console does after running a line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LineOutcome {
+    /// Prints the output and reads the next line.
+    Output(String),
+    /// Reads the next line.
+    Continue,
+    /// Leaves the console.
+    Exit,
+}
+
+/// State of a console session: the store client, the bound results and the output format.
+pub struct Console {
+    client: StoreQueryClient,
+    variables: BTreeMap<String, Value>,
+    format: OutputFormat,
+}
+
+impl Console {
+    pub fn new(client: StoreQueryClient, format: OutputFormat) -> Self {
+        Self {
+            client,
+            variables: BTreeMap::new(),
+            format,
+        }
+    }
+
+    /// Runs a line of the console.
+    pub async fn run_line(&mut self, line: &str) -> Result<LineOutcome> {
+        let line = line.trim();
+        let Some(command) = line.split_whitespace().next() else {
+            return Ok(LineOutcome::Continue);
+        };
+
+        match command {
+            _ if command.starts_with('#') => Ok(LineOutcome::Continue),
+            "exit" | "quit" => Ok(LineOutcome::Exit),
+            "help" => Ok(LineOutcome::Output(help())),
+            "vars" => {
+                let vars = self
+                    .variables
+                    .iter()
+                    .map(|(name, value)| format!("${name} = {}", render(value, OutputFormat::Json)))
+                    .collect::<Vec<_>>();
+                Ok(LineOutcome::Output(vars.join("\n")))
+            },
+            "format" => {
+                let format = line["format".len()..].trim();
+                self.format = OutputFormat::from_str(format, true)
+                    .map_err(|_| anyhow!("Unknown format {format}, expected pretty or json"))?;
+                Ok(LineOutcome::Continue)
+            },
+            "let" => {
+                let (name, query) = line["let".len()..]
+                    .split_once('=')
+                    .context("Expected `let <name> = <query>`")?;
+                let name = name.trim();
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    bail!("Invalid variable name `{name}`");
+                }
+
+                let value = self.run_query(query).await?;
+                let output = render(&value, self.format);
+                self.variables.insert(name.to_string(), value);
+                Ok(LineOutcome::Output(output))
+            },
+            _ => {
+                let value = self.run_query(line).await?;
+                Ok(LineOutcome::Output(render(&value, self.format)))
+            },
+        }
+    }
+
+    async fn run_query(&mut self, line: &str) -> Result<Value> {
+        let args = line
+            .split_whitespace()
+            .map(|arg| self.substitute(arg))
+            .collect::<Result<Vec<_>>>()?;
+        let QueryLine { query } = QueryLine::try_parse_from(args).map_err(|err| {
+            let message = err.render().to_string();
+            anyhow!("{}", message.trim_start_matches("error: ").trim_end())
+        })?;
+
+        self.client.run(&query).await
+    }
+
+    /// Replaces an argument of the form `$name.field.field` by the value of the field of the bound
+    /// result. Array items are selected by their index.
+    fn substitute(&self, arg: &str) -> Result<String> {
+        let Some(path) = arg.strip_prefix('$') else {
+            return Ok(arg.to_string());
+        };
+
+        let mut fields = path.split('.');
+        let name = fields.next().unwrap_or_default();
+        let mut value =
+            self.variables.get(name).ok_or_else(|| anyhow!("Unknown variable ${name}"))?;
+        for field in fields {
+            value = match value {
+                Value::Array(items) => {
+                    field.parse::<usize>().ok().and_then(|index| items.get(index))
+                },
+                value => value.get(field),
+            }
+            .ok_or_else(|| anyhow!("${name} has no field {field} in {arg}"))?;
+        }
+
+        scalar(value).ok_or_else(|| anyhow!("{arg} is not a single value"))
+    }
+}
+
+fn help() -> String {
+    let mut help = QueryLine::command().help_template("{subcommands}").render_help().to_string();
+    help.push_str(
+        "\nConsole commands:\n  let <name> = <query>  Binds the result of the query to $name\n  \
+         vars                  Lists the bound results\n  \
+         format pretty|json    Changes the format of the results\n  \
+         exit, quit            Leaves the console",
+    );
+    help
+}
+
+/// Returns the names of the queries and console commands.
+fn command_names() -> Vec<String> {
+    QueryLine::command()
+        .get_subcommands()
+        .map(|command| command.get_name().to_string())
+        .chain(CONSOLE_COMMANDS.map(String::from))
+        .collect()
+}
+
+// SESSIONS
+// ================================================================================================
+
+/// Opens a console connected to the store listening on `url`.
+pub async fn run_console(url: &str, format: OutputFormat) -> Result<()> {
+    let client = StoreQueryClient::connect(url).await?;
+    let mut console = Console::new(client, format);
+
+    if io::stdin().is_terminal() {
+        run_interactive(&mut console).await
+    } else {
+        run_script(&mut console, io::stdin().lock(), io::stdout()).await
+    }
+}
+
+/// Runs the lines of `input` one after another, writing their output to `output`.
+///
+/// The errors of a line are written to `output` as well, and don't stop the following lines.
+pub async fn run_script(
+    console: &mut Console,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<()> {
+    for line in input.lines() {
+        let line = line.context("Failed to read the input")?;
+        match console.run_line(&line).await {
+            Ok(LineOutcome::Output(text)) => writeln!(output, "{text}")?,
+            Ok(LineOutcome::Continue) => {},
+            Ok(LineOutcome::Exit) => break,
+            Err(err) => writeln!(output, "error: {err:#}")?,
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_interactive(console: &mut Console) -> Result<()> {
+    let mut editor = Editor::<CommandCompleter, DefaultHistory>::new()?;
+    editor.set_helper(Some(CommandCompleter { names: command_names() }));
+
+    loop {
+        // Reading a line blocks until it's entered
+        let line = match tokio::task::block_in_place(|| editor.readline(PROMPT)) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(err) => return Err(err.into()),
+        };
+        if !line.trim().is_empty() {
+            editor.add_history_entry(line.as_str())?;
+        }
+
+        match console.run_line(&line).await {
+            Ok(LineOutcome::Output(text)) => println!("{text}"),
+            Ok(LineOutcome::Continue) => {},
+            Ok(LineOutcome::Exit) => break,
+            Err(err) => eprintln!("error: {err:#}"),
+        }
+    }
+
+    Ok(())
+}
+
+// COMPLETION
+// ================================================================================================
+
+/// Completes the command name at the start of the line.
+struct CommandCompleter {
+    names: Vec<String>,
+}
+
+impl CommandCompleter {
+    fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let prefix = &line[..pos];
+        let start = prefix.len() - prefix.trim_start().len();
+        if prefix[start..].contains(char::is_whitespace) {
+            return (pos, vec![]);
+        }
+
+        let word = &prefix[start..];
+        let names = self.names.iter().filter(|name| name.starts_with(word)).cloned().collect();
+        (start, names)
+    }
+}
+
+impl Completer for CommandCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+impl Hinter for CommandCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for CommandCompleter {}
+
+impl Validator for CommandCompleter {}
+
+impl Helper for CommandCompleter {}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use miden_node_store::{config::StoreConfig, genesis::GenesisState, server::Store};
+    use miden_node_utils::config::{local_url, Endpoint};
+    use miden_objects::utils::Serializable;
+
+    use super::*;
+
+    /// Starts a store holding the genesis block, and returns a console connected to it.
+    async fn console(dir: &std::path::Path) -> Console {
+        let config = StoreConfig {
+            endpoint: Endpoint { host: "127.0.0.1".to_string(), port: 0 },
+            database_filepath: dir.join("miden-store.sqlite3"),
+            genesis_filepath: dir.join("genesis.dat"),
+            blockstore_dir: dir.join("blocks"),
+            ..Default::default()
+        };
+        fs::write(&config.genesis_filepath, GenesisState::new(vec![], 1, 0).to_bytes()).unwrap();
+        let store = Store::init(config).await.unwrap();
+        let url = local_url(store.local_addr());
+        tokio::spawn(store.serve());
+
+        Console::new(StoreQueryClient::connect(&url).await.unwrap(), OutputFormat::Pretty)
+    }
+
+    async fn run(console: &mut Console, script: &str) -> Vec<String> {
+        let mut output = Vec::new();
+        run_script(console, script.as_bytes(), &mut output).await.unwrap();
+        String::from_utf8(output).unwrap().lines().map(String::from).collect()
+    }
+
+    #[tokio::test]
+    async fn lines_are_parsed_like_the_query_subcommands() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut console = console(dir.path()).await;
+
+        let output = run(&mut console, "format json\nheader 0\nheader 0x0\n\n# comment\n").await;
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0], output[1], "Hex and decimal block numbers must be the same");
+        let header: Value = serde_json::from_str(&output[0]).unwrap();
+        assert_eq!(header["block_num"], 0);
+
+        let output = run(&mut console, "format pretty\nnotes 0").await;
+        assert_eq!(output, ["block_num: 0", "notes:", "  (none)"]);
+
+        // The script stops at `exit`
+        let output = run(&mut console, "exit\nheader 0").await;
+        assert!(output.is_empty());
+    }
+
+    #[tokio::test]
+    async fn results_are_bound_to_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut console = console(dir.path()).await;
+
+        run(&mut console, "let h = header").await;
+        assert_eq!(console.substitute("$h.block_num").unwrap(), "0");
+        assert_eq!(console.substitute("block_num").unwrap(), "block_num");
+
+        let output = run(&mut console, "format json\nlet b = block $h.block_num").await;
+        let block: Value = serde_json::from_str(&output[0]).unwrap();
+        assert_eq!(block["commitment"], console.variables["h"]["commitment"]);
+        assert_eq!(console.substitute("$b.notes").unwrap(), "0");
+
+        let output = run(&mut console, "vars").await;
+        assert_eq!(output.len(), 2);
+        assert!(output[0].starts_with("$b = {"));
+        assert!(output[1].starts_with("$h = {"));
+    }
+
+    #[tokio::test]
+    async fn errors_are_reported_and_the_script_continues() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut console = console(dir.path()).await;
+
+        let script = [
+            "frobnicate 12",
+            "header $missing",
+            "let h = header",
+            "notes $h.no_such_field",
+            "block $h",
+            "header not-a-number",
+            "let = header",
+            "format yaml",
+            "block 1000",
+            "header 0",
+        ];
+        let output = run(&mut console, &script.join("\n")).await;
+        let errors: Vec<_> = output.iter().filter(|line| line.starts_with("error: ")).collect();
+
+        assert!(errors[0].contains("frobnicate"));
+        assert_eq!(errors[1], "error: Unknown variable $missing");
+        assert_eq!(errors[2], "error: $h has no field no_such_field in $h.no_such_field");
+        assert_eq!(errors[3], "error: $h is not a single value");
+        assert!(errors[4].contains("Invalid number not-a-number"));
+        assert_eq!(errors[5], "error: Invalid variable name ``");
+        assert_eq!(errors[6], "error: Unknown format yaml, expected pretty or json");
+        assert!(errors[7].contains("Block 1000 not found"));
+        assert_eq!(errors.len(), 8);
+
+        // The last line still ran
+        let last_error = output.iter().rposition(|line| line.starts_with("error: ")).unwrap();
+        assert!(output[last_error + 1..].contains(&"block_num: 0".to_string()));
+    }
+
+    #[test]
+    fn command_names_are_completed() {
+        let completer = CommandCompleter { names: command_names() };
+
+        assert_eq!(
+            completer.candidates("he", 2),
+            (0, vec!["header".to_string(), "help".to_string()])
+        );
+        assert_eq!(completer.candidates("  nu", 4), (2, vec!["nullifier".to_string()]));
+        assert_eq!(completer.candidates("notes 1", 7), (7, vec![]));
+        assert_eq!(completer.candidates("", 0).1.len(), command_names().len());
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use miden_node_store::{
+    chain_archive::{export_chain, import_chain, ChainArchiveSummary},
+    config::StoreConfig,
+};
+
+use self::query::{render, OutputFormat, StoreQuery, StoreQueryClient};
+use super::{BLOCKSTORE_DIR, DATABASE_FILE, GENESIS_FILE};
+
+pub mod console;
+pub mod query;
+
+// CHAIN ARCHIVE
+// ================================================================================================
+
+/// Exports the chain of the store data in `data_directory` to the archive directory `output`.
+pub async fn run_export_chain(data_directory: &Path, output: &Path) -> Result<()> {
+    let summary = export_chain(store_config(data_directory), output)
+        .await
+        .context("Failed to export the chain")?;
+
+    println!("exported {} blocks to {}", summary.num_blocks, output.display());
+    print_chain_tip(&summary);
+
+    Ok(())
+}
+
+/// Imports the chain of the archive directory `input` into the store data in `data_directory`.
+pub async fn run_import_chain(input: &Path, data_directory: &Path) -> Result<()> {
+    let summary = import_chain(store_config(data_directory), input)
+        .await
+        .context("Failed to import the chain")?;
+
+    println!("imported {} blocks into {}", summary.num_blocks, data_directory.display());
+    print_chain_tip(&summary);
+
+    Ok(())
+}
+
+fn store_config(data_directory: &Path) -> StoreConfig {
+    StoreConfig {
+        database_filepath: data_directory.join(DATABASE_FILE),
+        genesis_filepath: data_directory.join(GENESIS_FILE),
+        blockstore_dir: data_directory.join(BLOCKSTORE_DIR),
+        ..Default::default()
+    }
+}
+
+fn print_chain_tip(summary: &ChainArchiveSummary) {
+    println!("chain tip: block {} ({})", summary.chain_tip, summary.chain_tip_commitment);
+}
+
+// QUERIES
+// ================================================================================================
+
+/// Runs a single query against the store listening on `url`, and prints its result.
+pub async fn run_query(url: &str, query: &StoreQuery, format: OutputFormat) -> Result<()> {
+    let mut client = StoreQueryClient::connect(url).await?;
+    let result = client.run(query).await?;
+    println!("{}", render(&result, format));
+
+    Ok(())
+}
@@ -0,0 +1,369 @@
+use anyhow::{anyhow, Context, Result};
+use clap::{Subcommand, ValueEnum};
+use miden_node_proto::{
+    domain::prefixes::{nullifier_prefix, NULLIFIER_PREFIX_LEN},
+    generated::{
+        requests::{
+            CheckNullifiersByPrefixRequest, GetAccountDetailsRequest, GetAccountStateDeltaRequest,
+            GetBlockByNumberRequest, GetBlockHeaderByNumberRequest,
+        },
+        store::api_client::ApiClient,
+    },
+};
+use miden_objects::{
+    accounts::{delta::AccountDelta, AccountId},
+    block::{Block, BlockHeader},
+    crypto::hash::rpo::RpoDigest,
+    notes::Nullifier,
+    utils::Deserializable,
+};
+use serde_json::{json, Value};
+use tonic::{transport::Channel, Status};
+
+// STORE QUERIES
+// ================================================================================================
+
+/// Read queries against a running store, shared by `store query` and `store console`.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum StoreQuery {
+    /// Prints the header of a block, the latest one if no block number is given
+    Header {
+        #[arg(value_parser = parse_number::<u32>)]
+        block_num: Option<u32>,
+    },
+
+    /// Prints the updated accounts, the number of notes and the nullifiers of a block
+    Block {
+        #[arg(value_parser = parse_number::<u32>)]
+        block_num: u32,
+    },
+
+    /// Lists the notes created by a block
+    Notes {
+        #[arg(value_parser = parse_number::<u32>)]
+        block_num: u32,
+    },
+
+    /// Prints the latest state commitment of an account
+    Account {
+        #[arg(value_parser = parse_account_id)]
+        account_id: AccountId,
+    },
+
+    /// Prints the delta of an account between two blocks, `from_block` excluded
+    Delta {
+        #[arg(value_parser = parse_account_id)]
+        account_id: AccountId,
+
+        #[arg(value_parser = parse_number::<u32>)]
+        from_block: u32,
+
+        #[arg(value_parser = parse_number::<u32>)]
+        to_block: u32,
+    },
+
+    /// Prints whether a nullifier is spent, and the block spending it
+    Nullifier {
+        #[arg(value_parser = parse_nullifier)]
+        nullifier: Nullifier,
+    },
+}
+
+/// Format of the query results.
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One `key: value` line per field
+    #[default]
+    Pretty,
+    /// A single line of JSON
+    Json,
+}
+
+/// Client of the store running the [StoreQuery]s.
+pub struct StoreQueryClient {
+    client: ApiClient<Channel>,
+}
+
+impl StoreQueryClient {
+    /// Connects to the store listening on `url`.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = ApiClient::connect(url.to_string())
+            .await
+            .with_context(|| format!("Failed to connect to the store at {url}"))?;
+
+        Ok(Self { client })
+    }
+
+    /// Runs the query, and returns its result as a JSON object.
+    pub async fn run(&mut self, query: &StoreQuery) -> Result<Value> {
+        match *query {
+            StoreQuery::Header { block_num } => self.header(block_num).await,
+            StoreQuery::Block { block_num } => self.block(block_num).await,
+            StoreQuery::Notes { block_num } => self.notes(block_num).await,
+            StoreQuery::Account { account_id } => self.account(account_id).await,
+            StoreQuery::Delta { account_id, from_block, to_block } => {
+                self.delta(account_id, from_block, to_block).await
+            },
+            StoreQuery::Nullifier { nullifier } => self.nullifier(nullifier).await,
+        }
+    }
+
+    async fn header(&mut self, block_num: Option<u32>) -> Result<Value> {
+        let response = self
+            .client
+            .get_block_header_by_number(GetBlockHeaderByNumberRequest {
+                block_num,
+                include_mmr_proof: None,
+            })
+            .await
+            .map_err(request_error)?
+            .into_inner();
+        let header: BlockHeader = response
+            .block_header
+            .context("The store returned no block header")?
+            .try_into()
+            .context("Invalid block header in the response")?;
+
+        Ok(json!({
+            "block_num": header.block_num(),
+            "commitment": header.hash().to_hex(),
+            "prev_hash": header.prev_hash().to_hex(),
+            "chain_root": header.chain_root().to_hex(),
+            "account_root": header.account_root().to_hex(),
+            "nullifier_root": header.nullifier_root().to_hex(),
+            "note_root": header.note_root().to_hex(),
+            "tx_hash": header.tx_hash().to_hex(),
+            "version": header.version(),
+            "timestamp": header.timestamp(),
+        }))
+    }
+
+    async fn block(&mut self, block_num: u32) -> Result<Value> {
+        let block = self.fetch_block(block_num).await?;
+
+        Ok(json!({
+            "block_num": block.header().block_num(),
+            "commitment": block.hash().to_hex(),
+            "updated_accounts": block
+                .updated_accounts()
+                .iter()
+                .map(|update| update.account_id().to_hex())
+                .collect::<Vec<_>>(),
+            "notes": block.notes().count(),
+            "nullifiers": block.nullifiers().iter().map(Nullifier::to_hex).collect::<Vec<_>>(),
+        }))
+    }
+
+    async fn notes(&mut self, block_num: u32) -> Result<Value> {
+        let block = self.fetch_block(block_num).await?;
+        let notes: Vec<_> = block
+            .notes()
+            .map(|(note_index, note)| {
+                json!({
+                    "note_index": note_index.leaf_index_value(),
+                    "note_id": note.id().to_hex(),
+                    "sender": note.metadata().sender().to_hex(),
+                    "tag": u32::from(note.metadata().tag()),
+                    "note_type": format!("{:?}", note.metadata().note_type()),
+                })
+            })
+            .collect();
+
+        Ok(json!({ "block_num": block_num, "notes": notes }))
+    }
+
+    async fn account(&mut self, account_id: AccountId) -> Result<Value> {
+        let response = self
+            .client
+            .get_account_details(GetAccountDetailsRequest { account_id: Some(account_id.into()) })
+            .await
+            .map_err(request_error)?
+            .into_inner();
+        let details = response.details.context("The store returned no account")?;
+        let summary = details.summary.context("The store returned no account summary")?;
+        let account_hash: RpoDigest = summary
+            .account_hash
+            .context("The store returned no account hash")?
+            .try_into()
+            .context("Invalid account hash in the response")?;
+
+        Ok(json!({
+            "account_id": account_id.to_hex(),
+            "account_hash": account_hash.to_hex(),
+            "block_num": summary.block_num,
+            "public": details.details.is_some(),
+        }))
+    }
+
+    async fn delta(
+        &mut self,
+        account_id: AccountId,
+        from_block: u32,
+        to_block: u32,
+    ) -> Result<Value> {
+        let response = self
+            .client
+            .get_account_state_delta(GetAccountStateDeltaRequest {
+                account_id: Some(account_id.into()),
+                from_block_num: from_block,
+                to_block_num: to_block,
+            })
+            .await
+            .map_err(request_error)?
+            .into_inner();
+        let delta = response
+            .delta
+            .map(|delta| {
+                AccountDelta::read_from_bytes(&delta)
+                    .map(|delta| json!({ "nonce": delta.nonce().map(|nonce| nonce.as_int()) }))
+                    .context("Invalid account delta in the response")
+            })
+            .transpose()?;
+
+        Ok(json!({
+            "account_id": account_id.to_hex(),
+            "from_block": from_block,
+            "to_block": to_block,
+            "delta": delta,
+        }))
+    }
+
+    async fn nullifier(&mut self, nullifier: Nullifier) -> Result<Value> {
+        let mut block_num = None;
+        let mut page = None;
+        loop {
+            let response = self
+                .client
+                .check_nullifiers_by_prefix(CheckNullifiersByPrefixRequest {
+                    prefix_len: NULLIFIER_PREFIX_LEN,
+                    nullifiers: vec![nullifier_prefix(&nullifier)],
+                    page,
+                })
+                .await
+                .map_err(request_error)?
+                .into_inner();
+
+            for update in response.nullifiers {
+                let digest = update.nullifier.context("The store returned no nullifier")?;
+                if Nullifier::try_from(digest)? == nullifier {
+                    block_num = Some(update.block_num);
+                }
+            }
+
+            page = response.next_page;
+            if block_num.is_some() || page.is_none() {
+                break;
+            }
+        }
+
+        Ok(json!({
+            "nullifier": nullifier.to_hex(),
+            "spent": block_num.is_some(),
+            "block_num": block_num,
+        }))
+    }
+
+    async fn fetch_block(&mut self, block_num: u32) -> Result<Block> {
+        let response = self
+            .client
+            .get_block_by_number(GetBlockByNumberRequest { block_num })
+            .await
+            .map_err(request_error)?
+            .into_inner();
+        let block = response.block.ok_or_else(|| anyhow!("Block {block_num} not found"))?;
+
+        Block::read_from_bytes(&block).context("Invalid block in the response")
+    }
+}
+
+fn request_error(status: Status) -> anyhow::Error {
+    anyhow!("Store request failed: {}", status.message())
+}
+
+// OUTPUT
+// ================================================================================================
+
+/// Renders a query result in the given format.
+pub fn render(value: &Value, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => value.to_string(),
+        OutputFormat::Pretty => {
+            let mut output = String::new();
+            render_pretty(&mut output, value, 0);
+            output.trim_end().to_string()
+        },
+    }
+}
+
+fn render_pretty(output: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                if let Some(scalar) = scalar(value) {
+                    output.push_str(&format!("{pad}{key}: {scalar}\n"));
+                } else {
+                    output.push_str(&format!("{pad}{key}:\n"));
+                    render_pretty(output, value, indent + 2);
+                }
+            }
+        },
+        Value::Array(items) if items.is_empty() => output.push_str(&format!("{pad}(none)\n")),
+        Value::Array(items) => {
+            for item in items {
+                if let Some(scalar) = scalar(item) {
+                    output.push_str(&format!("{pad}- {scalar}\n"));
+                } else {
+                    output.push_str(&format!("{pad}-\n"));
+                    render_pretty(output, item, indent + 2);
+                }
+            }
+        },
+        value => output.push_str(&format!("{pad}{}\n", scalar(value).unwrap_or_default())),
+    }
+}
+
+/// Returns the value as a plain string, i.e. without quotes for strings, if it's not an object or
+/// an array.
+pub fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(_) | Value::Array(_) => None,
+        Value::String(string) => Some(string.clone()),
+        Value::Null => Some("-".to_string()),
+        value => Some(value.to_string()),
+    }
+}
+
+// PARSING
+// ================================================================================================
+
+/// Parses a number given in decimal, or in hex with a `0x` prefix.
+pub fn parse_number<T: TryFrom<u64>>(value: &str) -> Result<T> {
+    let number = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .map_err(|err| anyhow!("Invalid number {value}: {err}"))?;
+
+    T::try_from(number).map_err(|_| anyhow!("Number {value} is out of range"))
+}
+
+/// Parses an account ID given in hex with a `0x` prefix, or in decimal.
+pub fn parse_account_id(value: &str) -> Result<AccountId> {
+    let account_id = parse_number::<u64>(value)?;
+
+    AccountId::try_from(account_id).map_err(|err| anyhow!("Invalid account ID {value}: {err}"))
+}
+
+/// Parses a nullifier given in hex, with or without a `0x` prefix.
+pub fn parse_nullifier(value: &str) -> Result<Nullifier> {
+    let hex = if value.starts_with("0x") {
+        value.to_string()
+    } else {
+        format!("0x{value}")
+    };
+    let digest =
+        RpoDigest::try_from(hex.as_str()).map_err(|err| anyhow!("Invalid nullifier: {err}"))?;
+
+    Ok(digest.into())
+}
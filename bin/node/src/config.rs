@@ -1,16 +1,30 @@
-use miden_node_block_producer::config::BlockProducerConfig;
-use miden_node_rpc::config::RpcConfig;
+use miden_node_block_producer::config::{
+    deserialize_expiration_slack, BlockProducerConfig, ProofTimeDistribution,
+};
+use miden_node_rpc::config::{
+    AuditConfig, FreshnessConfig, ResponseCacheConfig, RpcConfig, RpcTransportConfig, TlsConfig,
+};
 use miden_node_store::config::StoreConfig;
-use miden_node_utils::config::Endpoint;
+use miden_node_utils::{
+    config::{Endpoint, CONFIG_VERSION},
+    message_limits::MessageLimits,
+    runtime::RuntimeConfig,
+};
 use serde::{Deserialize, Serialize};
 
 /// Node top-level configuration.
-#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct NodeConfig {
+    /// Version of the layout of the configuration file, see [CONFIG_VERSION].
+    #[serde(default)]
+    config_version: u32,
     block_producer: NormalizedBlockProducerConfig,
     rpc: NormalizedRpcConfig,
     store: StoreConfig,
+    /// Runtime shared by the components, see [RuntimeConfig].
+    #[serde(default)]
+    runtime: RuntimeConfig,
 }
 
 /// A specialized variant of [RpcConfig] with redundant fields within [NodeConfig] removed.
@@ -18,6 +32,18 @@ pub struct NodeConfig {
 #[serde(deny_unknown_fields)]
 struct NormalizedRpcConfig {
     endpoint: Endpoint,
+    #[serde(default)]
+    expose_internal_errors: bool,
+    #[serde(default)]
+    transport: RpcTransportConfig,
+    #[serde(default)]
+    freshness: FreshnessConfig,
+    #[serde(default)]
+    response_cache: ResponseCacheConfig,
+    #[serde(default)]
+    message_limits: MessageLimits,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    audit: Option<AuditConfig>,
 }
 
 /// A specialized variant of [BlockProducerConfig] with redundant fields within [NodeConfig]
@@ -27,54 +53,208 @@ struct NormalizedRpcConfig {
 struct NormalizedBlockProducerConfig {
     endpoint: Endpoint,
     verify_tx_proofs: bool,
+    #[serde(default)]
+    interval_jitter_percent: u8,
+    #[serde(default)]
+    interval_phase_offset_ms: u64,
+    #[serde(default)]
+    min_batch_fill_percent: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_batch_wait_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    block_build_target_percent: Option<u8>,
+    #[serde(default, deserialize_with = "deserialize_expiration_slack")]
+    expiration_slack: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    producer_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    simulated_block_proof_time: Option<ProofTimeDistribution>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    simulated_batch_proof_time: Option<ProofTimeDistribution>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        alias = "simulated_proof_time_seed"
+    )]
+    simulation_seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    transaction_watch_timeout_ms: Option<u64>,
+    #[serde(default)]
+    message_limits: MessageLimits,
+    #[serde(default)]
+    debug_endpoints: bool,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            config_version: CONFIG_VERSION,
+            block_producer: NormalizedBlockProducerConfig::default(),
+            rpc: NormalizedRpcConfig::default(),
+            store: StoreConfig::default(),
+            runtime: RuntimeConfig::default(),
+        }
+    }
 }
 
 impl Default for NormalizedRpcConfig {
     fn default() -> Self {
         // Ensure we stay in sync with the original defaults.
         let RpcConfig {
+            config_version: _,
             endpoint,
             store_url: _,
             block_producer_url: _,
+            expose_internal_errors,
+            transport,
+            freshness,
+            response_cache,
+            message_limits,
+            audit,
+            genesis_commitment: _,
         } = RpcConfig::default();
-        Self { endpoint }
+        Self {
+            endpoint,
+            expose_internal_errors,
+            transport,
+            freshness,
+            response_cache,
+            message_limits,
+            audit,
+        }
     }
 }
 
 impl Default for NormalizedBlockProducerConfig {
     fn default() -> Self {
         // Ensure we stay in sync with the original defaults.
-        let BlockProducerConfig { endpoint, store_url: _, verify_tx_proofs } =
-            BlockProducerConfig::default();
-        Self { endpoint, verify_tx_proofs }
+        let BlockProducerConfig {
+            config_version: _,
+            endpoint,
+            store_url: _,
+            verify_tx_proofs,
+            interval_jitter_percent,
+            interval_phase_offset_ms,
+            min_batch_fill_percent,
+            max_batch_wait_ms,
+            block_build_target_percent,
+            expiration_slack,
+            producer_id,
+            simulated_block_proof_time,
+            simulated_batch_proof_time,
+            simulation_seed,
+            transaction_watch_timeout_ms,
+            block_versions: _,
+            message_limits,
+            debug_endpoints,
+            genesis_commitment: _,
+        } = BlockProducerConfig::default();
+        Self {
+            endpoint,
+            verify_tx_proofs,
+            interval_jitter_percent,
+            interval_phase_offset_ms,
+            min_batch_fill_percent,
+            max_batch_wait_ms,
+            block_build_target_percent,
+            expiration_slack,
+            producer_id,
+            simulated_block_proof_time,
+            simulated_batch_proof_time,
+            simulation_seed,
+            transaction_watch_timeout_ms,
+            message_limits,
+            debug_endpoints,
+        }
     }
 }
 
 impl NodeConfig {
+    /// Returns the configuration of the runtime shared by the components.
+    pub fn runtime(&self) -> RuntimeConfig {
+        self.runtime
+    }
+
+    /// Returns the configuration with the given store configuration.
+    pub fn with_store(self, store: StoreConfig) -> Self {
+        Self { store, ..self }
+    }
+
     pub fn into_parts(self) -> (BlockProducerConfig, RpcConfig, StoreConfig) {
-        let Self { block_producer, rpc, store } = self;
+        let Self {
+            config_version,
+            block_producer,
+            rpc,
+            store,
+            runtime: _,
+        } = self;
 
         let block_producer = BlockProducerConfig {
+            config_version,
             endpoint: block_producer.endpoint,
             store_url: store.endpoint_url(),
             verify_tx_proofs: block_producer.verify_tx_proofs,
+            interval_jitter_percent: block_producer.interval_jitter_percent,
+            interval_phase_offset_ms: block_producer.interval_phase_offset_ms,
+            min_batch_fill_percent: block_producer.min_batch_fill_percent,
+            max_batch_wait_ms: block_producer.max_batch_wait_ms,
+            block_build_target_percent: block_producer.block_build_target_percent,
+            expiration_slack: block_producer.expiration_slack,
+            producer_id: block_producer.producer_id,
+            simulated_block_proof_time: block_producer.simulated_block_proof_time,
+            simulated_batch_proof_time: block_producer.simulated_batch_proof_time,
+            simulation_seed: block_producer.simulation_seed,
+            transaction_watch_timeout_ms: block_producer.transaction_watch_timeout_ms,
+            // The block producer enforces the block versions accepted by the store
+            block_versions: store.block_versions,
+            message_limits: block_producer.message_limits,
+            debug_endpoints: block_producer.debug_endpoints,
+            // The components connect to the store of the node itself
+            genesis_commitment: None,
         };
 
         let rpc = RpcConfig {
+            config_version,
             endpoint: rpc.endpoint,
             store_url: store.endpoint_url(),
             block_producer_url: block_producer.endpoint_url(),
+            expose_internal_errors: rpc.expose_internal_errors,
+            transport: rpc.transport,
+            freshness: rpc.freshness,
+            response_cache: rpc.response_cache,
+            message_limits: rpc.message_limits,
+            audit: rpc.audit,
+            genesis_commitment: None,
         };
 
+        let store = StoreConfig { config_version, ..store };
+
         (block_producer, rpc, store)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use figment::Jail;
-    use miden_node_store::config::StoreConfig;
-    use miden_node_utils::config::{load_config, Endpoint};
+    use miden_node_block_producer::config::{BlockProducerConfig, ProofTimeDistribution, Sigma};
+    use miden_node_rpc::config::{
+        AuditConfig, FreshnessConfig, ResponseCacheConfig, RpcConfig, RpcTransportConfig, TlsConfig,
+    };
+    use miden_node_store::config::{
+        NoteDetailsPolicy, ShardRange, StoreConfig, WriteLimitsConfig,
+        DEFAULT_EXPORT_ROWS_PER_SECOND, DEFAULT_MAX_BLOCK_TIMESTAMP_DRIFT_SECS,
+        DEFAULT_MAX_RESPONSE_SIZE, DEFAULT_SLOW_POOL_WAIT_THRESHOLD_MS,
+        DEFAULT_SLOW_QUERY_THRESHOLD_MS,
+    };
+    use miden_node_utils::{
+        block_versions::BlockVersions,
+        config::{load_config, load_config_with, ConfigProblemKind, Endpoint, UnknownKeys},
+        message_limits::MessageLimits,
+        runtime::{RuntimeConfig, RuntimeConfigError},
+    };
+    use serde::de::DeserializeOwned;
 
     use super::NodeConfig;
     use crate::{
@@ -91,6 +271,8 @@ mod tests {
                     [block_producer]
                     endpoint = { host = "127.0.0.1",  port = 8080 }
                     verify_tx_proofs = true
+                    interval_jitter_percent = 10
+                    simulated_block_proof_time = { distribution = "log_normal", median_ms = 2000, sigma = 0.5, cap_ms = 8000 }
 
                     [rpc]
                     endpoint = { host = "127.0.0.1",  port = 8080 }
@@ -103,35 +285,449 @@ mod tests {
                 "#,
             )?;
 
-            let config: NodeConfig = load_config(NODE_CONFIG_FILE_PATH)?;
+            let config: NodeConfig =
+                load_config(NODE_CONFIG_FILE_PATH).map_err(|err| err.to_string())?;
 
             assert_eq!(
                 config,
                 NodeConfig {
+                    config_version: 0,
                     block_producer: NormalizedBlockProducerConfig {
                         endpoint: Endpoint {
                             host: "127.0.0.1".to_string(),
                             port: 8080,
                         },
-                        verify_tx_proofs: true
+                        verify_tx_proofs: true,
+                        interval_jitter_percent: 10,
+                        interval_phase_offset_ms: 0,
+                        min_batch_fill_percent: 0,
+                        max_batch_wait_ms: None,
+                        block_build_target_percent: None,
+                        expiration_slack: 0,
+                        producer_id: None,
+                        simulated_block_proof_time: Some(ProofTimeDistribution::LogNormal {
+                            median_ms: 2000,
+                            sigma: Sigma(0.5),
+                            cap_ms: 8000,
+                        }),
+                        simulated_batch_proof_time: None,
+                        simulation_seed: None,
+                        transaction_watch_timeout_ms: None,
+                        message_limits: MessageLimits::default(),
+                        debug_endpoints: false,
                     },
                     rpc: NormalizedRpcConfig {
                         endpoint: Endpoint {
                             host: "127.0.0.1".to_string(),
                             port: 8080,
                         },
+                        expose_internal_errors: false,
+                        transport: RpcTransportConfig::default(),
+                        freshness: FreshnessConfig::default(),
+                        response_cache: ResponseCacheConfig::default(),
+                        message_limits: MessageLimits::default(),
+                        audit: None,
                     },
                     store: StoreConfig {
+                        config_version: 0,
                         endpoint: Endpoint {
                             host: "127.0.0.1".to_string(),
                             port: 8080,
                         },
                         database_filepath: "local.sqlite3".into(),
                         genesis_filepath: "genesis.dat".into(),
-                        blockstore_dir: "blocks".into()
+                        blockstore_dir: "blocks".into(),
+                        mode: None,
+                        block_retention_depth: None,
+                        max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+                        note_details: NoteDetailsPolicy::Full,
+                        slow_query_threshold_ms: DEFAULT_SLOW_QUERY_THRESHOLD_MS,
+                        slow_pool_wait_threshold_ms: DEFAULT_SLOW_POOL_WAIT_THRESHOLD_MS,
+                        max_block_timestamp_drift_secs: DEFAULT_MAX_BLOCK_TIMESTAMP_DRIFT_SECS,
+                        skip_block_timestamp_validation: false,
+                        shard_range: ShardRange::default(),
+                        export_rows_per_second: DEFAULT_EXPORT_ROWS_PER_SECOND,
+                        write_limits: WriteLimitsConfig::default(),
+                        block_versions: BlockVersions::default(),
                     },
+                    runtime: RuntimeConfig::default(),
+                }
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rpc_transport_config() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                NODE_CONFIG_FILE_PATH,
+                r#"
+                    [block_producer]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+                    verify_tx_proofs = true
+
+                    [rpc]
+                    endpoint = { host = "0.0.0.0",  port = 8080 }
+
+                    [rpc.transport]
+                    tls = { cert_path = "cert.pem", key_path = "key.pem", reload = true }
+                    http2_keepalive_interval_ms = 30000
+                    http2_keepalive_timeout_ms = 5000
+                    max_concurrent_streams = 128
+                    cors_allowed_origins = ["https://wallet.example"]
+
+                    [store]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+                    database_filepath = "local.sqlite3"
+                    genesis_filepath = "genesis.dat"
+                    blockstore_dir = "blocks"
+                "#,
+            )?;
+
+            let config: NodeConfig =
+                load_config(NODE_CONFIG_FILE_PATH).map_err(|err| err.to_string())?;
+            let (_, rpc, _) = config.into_parts();
+
+            assert_eq!(
+                rpc.transport,
+                RpcTransportConfig {
+                    tls: Some(TlsConfig {
+                        cert_path: "cert.pem".into(),
+                        key_path: "key.pem".into(),
+                        reload: true,
+                    }),
+                    http2_keepalive_interval_ms: Some(30_000),
+                    http2_keepalive_timeout_ms: Some(5_000),
+                    max_concurrent_streams: Some(128),
+                    cors_allowed_origins: Some(vec!["https://wallet.example".to_string()]),
                 }
             );
+            assert_eq!(rpc.transport.http2_keepalive_interval(), Some(Duration::from_secs(30)));
+            assert_eq!(rpc.transport.http2_keepalive_timeout(), Some(Duration::from_secs(5)));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn message_limits_config() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                NODE_CONFIG_FILE_PATH,
+                r#"
+                    [block_producer]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+                    verify_tx_proofs = true
+                    message_limits = { max_message_size = 8388608 }
+
+                    [rpc]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+
+                    [rpc.message_limits]
+                    max_message_size = 8388608
+                    max_note_details_size = 65536
+
+                    [store]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+                    database_filepath = "local.sqlite3"
+                    genesis_filepath = "genesis.dat"
+                    blockstore_dir = "blocks"
+                "#,
+            )?;
+
+            let config: NodeConfig =
+                load_config(NODE_CONFIG_FILE_PATH).map_err(|err| err.to_string())?;
+            let (block_producer, rpc, _) = config.into_parts();
+
+            assert_eq!(block_producer.message_limits.max_message_size(), 8 * 1024 * 1024);
+            assert_eq!(block_producer.message_limits.max_note_details_size(), 256 * 1024);
+            assert_eq!(
+                rpc.message_limits,
+                MessageLimits {
+                    max_message_size: Some(8 * 1024 * 1024),
+                    max_note_details_size: Some(64 * 1024),
+                }
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn audit_config() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                NODE_CONFIG_FILE_PATH,
+                r#"
+                    [block_producer]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+                    verify_tx_proofs = true
+
+                    [rpc]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+                    audit = { directory = "/opt/miden/audit", max_rotated_files = 3 }
+
+                    [store]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+                    database_filepath = "local.sqlite3"
+                    genesis_filepath = "genesis.dat"
+                    blockstore_dir = "blocks"
+                "#,
+            )?;
+
+            let config: NodeConfig =
+                load_config(NODE_CONFIG_FILE_PATH).map_err(|err| err.to_string())?;
+            let (_, rpc, _) = config.into_parts();
+
+            assert_eq!(
+                rpc.audit,
+                Some(AuditConfig {
+                    max_rotated_files: 3,
+                    ..AuditConfig::new("/opt/miden/audit")
+                })
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn simulation_seed_config() {
+        for key in ["simulation_seed", "simulated_proof_time_seed"] {
+            Jail::expect_with(|jail| {
+                jail.create_file(
+                    NODE_CONFIG_FILE_PATH,
+                    &format!(
+                        r#"
+                            [block_producer]
+                            endpoint = {{ host = "127.0.0.1",  port = 8080 }}
+                            verify_tx_proofs = true
+                            {key} = 42
+
+                            [rpc]
+                            endpoint = {{ host = "127.0.0.1",  port = 8080 }}
+
+                            [store]
+                            endpoint = {{ host = "127.0.0.1",  port = 8080 }}
+                            database_filepath = "local.sqlite3"
+                            genesis_filepath = "genesis.dat"
+                            blockstore_dir = "blocks"
+                        "#
+                    ),
+                )?;
+
+                let config: NodeConfig =
+                    load_config(NODE_CONFIG_FILE_PATH).map_err(|err| err.to_string())?;
+                let (block_producer, ..) = config.into_parts();
+
+                // The seed was named after the proof times only, before it seeded the jitter too
+                assert_eq!(block_producer.simulation_seed, Some(42), "{key}");
+
+                Ok(())
+            });
+        }
+    }
+
+    #[test]
+    fn runtime_config() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                NODE_CONFIG_FILE_PATH,
+                r#"
+                    [block_producer]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+                    verify_tx_proofs = true
+
+                    [rpc]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+
+                    [store]
+                    endpoint = { host = "127.0.0.1",  port = 8080 }
+                    database_filepath = "local.sqlite3"
+                    genesis_filepath = "genesis.dat"
+                    blockstore_dir = "blocks"
+
+                    [runtime]
+                    worker_threads = 4
+                    proving_threads = 0
+                "#,
+            )?;
+
+            let config: NodeConfig =
+                load_config(NODE_CONFIG_FILE_PATH).map_err(|err| err.to_string())?;
+            assert_eq!(
+                config.runtime(),
+                RuntimeConfig {
+                    worker_threads: Some(4),
+                    max_blocking_threads: None,
+                    proving_threads: Some(0),
+                }
+            );
+            assert!(matches!(
+                config.runtime().validate(),
+                Err(RuntimeConfigError::NoThreads("proving_threads"))
+            ));
+
+            jail.create_file(
+                NODE_CONFIG_FILE_PATH,
+                &std::fs::read_to_string(NODE_CONFIG_FILE_PATH)
+                    .unwrap()
+                    .replace("proving_threads = 0", "proving_treads = 2"),
+            )?;
+            assert_eq!(
+                problems::<NodeConfig>(UnknownKeys::Deny),
+                [("runtime.proving_treads".to_string(), ConfigProblemKind::UnknownKey)]
+            );
+
+            Ok(())
+        });
+    }
+
+    const MISTAKEN_CONFIG: &str = r#"
+        [block_producer]
+        endpoint = { host = "127.0.0.1", port = 8080, protocol = "http" }
+        store_url = "http://127.0.0.1:8080"
+        verify_tx_proofs = true
+        interval_jitter_percent = "ten"
+        simulated_seed = 1
+
+        [rpc]
+        endpoint = { host = "127.0.0.1", port = 8080 }
+        store_url = "http://127.0.0.1:8080"
+        block_producer_url = "http://127.0.0.1:8080"
+
+        [store]
+        endpoint = { host = "127.0.0.1", port = 8080 }
+        database_filepath = "local.sqlite3"
+        genesis_filepath = "genesis.dat"
+        blockstore = "blocks"
+    "#;
+
+    fn problems<T: DeserializeOwned>(
+        unknown_keys: UnknownKeys,
+    ) -> Vec<(String, ConfigProblemKind)> {
+        let err = load_config_with::<T>(NODE_CONFIG_FILE_PATH, unknown_keys).unwrap_err();
+        err.problems.into_iter().map(|problem| (problem.key, problem.kind)).collect()
+    }
+
+    #[test]
+    fn config_mistakes_are_reported_at_once() {
+        Jail::expect_with(|jail| {
+            jail.create_file(NODE_CONFIG_FILE_PATH, MISTAKEN_CONFIG)?;
+
+            assert_eq!(
+                problems::<NodeConfig>(UnknownKeys::Deny),
+                [
+                    ("block_producer.endpoint.protocol".to_string(), ConfigProblemKind::UnknownKey),
+                    (
+                        "block_producer.interval_jitter_percent".to_string(),
+                        ConfigProblemKind::InvalidValue
+                    ),
+                    ("block_producer.simulated_seed".to_string(), ConfigProblemKind::UnknownKey),
+                    ("store.blockstore".to_string(), ConfigProblemKind::UnknownKey),
+                    ("store.blockstore_dir".to_string(), ConfigProblemKind::MissingKey),
+                ]
+            );
+
+            let err = load_config::<NodeConfig>(NODE_CONFIG_FILE_PATH).unwrap_err().to_string();
+            assert!(err.contains(NODE_CONFIG_FILE_PATH), "{err}");
+            assert!(err.contains("`store.blockstore`: unknown key"), "{err}");
+
+            // Lenient loading only reports the other mistakes
+            assert_eq!(
+                problems::<NodeConfig>(UnknownKeys::Warn),
+                [
+                    (
+                        "block_producer.interval_jitter_percent".to_string(),
+                        ConfigProblemKind::InvalidValue
+                    ),
+                    ("store.blockstore_dir".to_string(), ConfigProblemKind::MissingKey),
+                ]
+            );
+
+            let config = MISTAKEN_CONFIG
+                .replace(r#"interval_jitter_percent = "ten""#, "interval_jitter_percent = 10")
+                .replace("blockstore =", "blockstore_dir =");
+            jail.create_file(NODE_CONFIG_FILE_PATH, &config)?;
+            assert_eq!(
+                problems::<NodeConfig>(UnknownKeys::Deny),
+                [
+                    ("block_producer.endpoint.protocol".to_string(), ConfigProblemKind::UnknownKey),
+                    ("block_producer.simulated_seed".to_string(), ConfigProblemKind::UnknownKey),
+                ]
+            );
+            let config: NodeConfig = load_config_with(NODE_CONFIG_FILE_PATH, UnknownKeys::Warn)
+                .map_err(|err| err.to_string())?;
+            assert_eq!(config.block_producer.interval_jitter_percent, 10);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                NODE_CONFIG_FILE_PATH,
+                &MISTAKEN_CONFIG
+                    .replace(r#"interval_jitter_percent = "ten""#, "expiration_slack = 17")
+                    .replace("blockstore =", "block_retention_depth = 0\nblockstore_dir ="),
+            )?;
+            assert_eq!(
+                problems::<NodeConfig>(UnknownKeys::Warn),
+                [
+                    (
+                        "block_producer.expiration_slack".to_string(),
+                        ConfigProblemKind::InvalidValue
+                    ),
+                    ("store.block_retention_depth".to_string(), ConfigProblemKind::InvalidValue),
+                ]
+            );
+
+            jail.create_file(
+                NODE_CONFIG_FILE_PATH,
+                r#"
+                    endpoint = { host = "127.0.0.1", port = 8080 }
+                    store_url = "http://127.0.0.1:8080"
+                    verify_tx_proofs = true
+                    expiration_slack = 17
+                "#,
+            )?;
+            assert_eq!(
+                problems::<BlockProducerConfig>(UnknownKeys::Deny),
+                [("expiration_slack".to_string(), ConfigProblemKind::InvalidValue)]
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn component_configs_reject_unknown_keys() {
+        fn unknown_keys<T: DeserializeOwned>() -> Vec<String> {
+            problems::<T>(UnknownKeys::Deny)
+                .into_iter()
+                .filter(|(_, kind)| *kind == ConfigProblemKind::UnknownKey)
+                .map(|(key, _)| key)
+                .collect()
+        }
+
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                NODE_CONFIG_FILE_PATH,
+                r#"
+                    endpont = { host = "127.0.0.1", port = 8080 }
+                    endpoint = { host = "127.0.0.1", port = 8080, hots = "localhost" }
+                "#,
+            )?;
+
+            let expected = ["endpoint.hots", "endpont"];
+            assert_eq!(unknown_keys::<BlockProducerConfig>(), expected);
+            assert_eq!(unknown_keys::<RpcConfig>(), expected);
+            assert_eq!(unknown_keys::<StoreConfig>(), expected);
+            assert_eq!(unknown_keys::<NodeConfig>(), ["endpoint", "endpont"]);
 
             Ok(())
         });
@@ -1,12 +1,32 @@
-use std::path::PathBuf;
+use std::{io, path::PathBuf};
 
 use anyhow::{anyhow, Context};
 use clap::{Parser, Subcommand};
-use commands::{init::init_config_files, start::start_node};
-use miden_node_block_producer::server::BlockProducer;
-use miden_node_rpc::server::Rpc;
-use miden_node_store::server::Store;
-use miden_node_utils::{config::load_config, version::LongVersion};
+use commands::{
+    audit_log::run_audit_log,
+    doctor::run_doctor,
+    dry_run::run_dry_run,
+    dump_mempool_graph::run_dump_mempool_graph,
+    evict_tx::{parse_transaction_id, run_evict_tx},
+    init::{init_node, prompt_init_options, InitOptions, DEFAULT_FAUCET_TOKEN_SYMBOL},
+    migrate_config::{run_migrate_config, ConfigComponent},
+    start::{shutdown_signal, start_block_producer, start_node, start_rpc, start_store},
+    store::{
+        console::run_console,
+        query::{OutputFormat, StoreQuery},
+        run_export_chain, run_import_chain, run_query,
+    },
+};
+use miden_node_utils::{
+    config::{
+        load_config_with, Endpoint, UnknownKeys, DEFAULT_BLOCK_PRODUCER_PORT, DEFAULT_STORE_PORT,
+    },
+    runtime_info::RuntimeInfo,
+    version::LongVersion,
+};
+use miden_objects::transaction::TransactionId;
+
+use crate::config::NodeConfig;
 
 mod commands;
 mod config;
@@ -26,17 +46,40 @@ const DEFAULT_GENESIS_INPUTS_PATH: &str = "genesis.toml";
 pub struct Cli {
     #[command(subcommand)]
     pub command: Command,
+
+    /// Log unknown keys of the configuration files as warnings instead of rejecting them
+    #[arg(long, global = true)]
+    pub lenient_config: bool,
+}
+
+impl Cli {
+    /// Returns how the unknown keys of the configuration files are handled.
+    fn unknown_keys(&self) -> UnknownKeys {
+        if self.lenient_config {
+            UnknownKeys::Warn
+        } else {
+            UnknownKeys::Deny
+        }
+    }
 }
 
 #[derive(Subcommand)]
 pub enum Command {
     /// Start the node
+    ///
+    /// Once started, the components write a runtime info file describing the running process in
+    /// JSON, e.g. the addresses their listeners are bound to, which is removed on shutdown.
     Start {
         #[command(subcommand)]
         command: StartCommand,
 
         #[arg(short, long, value_name = "FILE", default_value = NODE_CONFIG_FILE_PATH)]
         config: PathBuf,
+
+        /// Write the runtime info file to this location, instead of next to the configuration
+        /// file with a `runtime.json` extension
+        #[arg(long, value_name = "FILE")]
+        runtime_info: Option<PathBuf>,
     },
 
     /// Generates a genesis file and associated account files based on a specified genesis input
@@ -63,12 +106,94 @@ pub enum Command {
     /// This command creates two files (miden-node.toml and genesis.toml) that provide
     /// configuration details to the node. These files may be modified to change the node
     /// behavior.
+    ///
+    /// With a data directory, the store of the configuration is pointed at it, and the directory
+    /// is scaffolded with the genesis block generated from genesis.toml, along with the account
+    /// files of the genesis accounts, so that the node can be started right away. Nothing is
+    /// written if any of the files already exists.
     Init {
         #[arg(short, long, default_value = NODE_CONFIG_FILE_PATH)]
         config_path: String,
 
         #[arg(short, long, default_value = DEFAULT_GENESIS_INPUTS_PATH)]
         genesis_path: String,
+
+        /// Store data directory to create and generate the genesis block in
+        #[arg(short, long, value_name = "DIR")]
+        data_directory: Option<PathBuf>,
+
+        /// Symbol of the token minted by the faucet account of the genesis block
+        #[arg(short, long, default_value = DEFAULT_FAUCET_TOKEN_SYMBOL)]
+        token_symbol: String,
+
+        /// Generate the genesis block without a faucet account
+        #[arg(long)]
+        no_faucet: bool,
+
+        /// Ask for the data directory and the faucet account, with the other options as defaults
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// Upgrades a configuration file written for an older release
+    ///
+    /// This command moves the keys of the file to their current location, sets the keys which
+    /// became required to their former defaults, and writes the result to a new file. It prints
+    /// every change, along with the keys which could not be mapped, which are kept commented out
+    /// under a `# UNRECOGNIZED` block at the end of the new file. The comments of the original
+    /// file are not kept.
+    MigrateConfig {
+        /// Configuration file to upgrade
+        #[arg(short, long, value_name = "FILE")]
+        input: PathBuf,
+
+        /// Write the upgraded configuration file to this location, which must not exist
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+
+        /// Component the configuration file belongs to
+        #[arg(short, long, value_enum)]
+        component: ConfigComponent,
+    },
+
+    /// Diagnoses common misconfigurations of the node
+    ///
+    /// This command checks the connectivity between the configured components, the genesis file,
+    /// the data directories, the available disk space and the system clock. It prints the outcome
+    /// of every check, and exits with an error if any of them failed.
+    Doctor {
+        #[command(subcommand)]
+        command: StartCommand,
+
+        #[arg(short, long, value_name = "FILE", default_value = NODE_CONFIG_FILE_PATH)]
+        config: PathBuf,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Read the runtime info file of the running components from this location, instead of
+        /// next to the configuration file with a `runtime.json` extension
+        #[arg(long, value_name = "FILE")]
+        runtime_info: Option<PathBuf>,
+    },
+
+    /// Block-producer tools
+    BlockProducer {
+        #[command(subcommand)]
+        command: BlockProducerCommand,
+    },
+
+    /// RPC tools
+    Rpc {
+        #[command(subcommand)]
+        command: RpcCommand,
+    },
+
+    /// Store tools
+    Store {
+        #[command(subcommand)]
+        command: StoreCommand,
     },
 }
 
@@ -80,57 +205,290 @@ pub enum StartCommand {
     Store,
 }
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
+#[derive(Subcommand)]
+pub enum BlockProducerCommand {
+    /// Builds and proves a block from a file of proven transactions, without applying it
+    ///
+    /// This command starts a store on a scratch copy of the given data directory, verifies the
+    /// transactions against it, batches them, and builds and proves a block from the batches. It
+    /// prints the duration of every stage and the resulting block, and exits with an error naming
+    /// the offending transactions or batches if any stage failed. The data directory is left
+    /// untouched.
+    DryRun {
+        /// Store data directory, holding the database, the genesis file and the block store
+        #[arg(short, long, value_name = "DIR")]
+        data_directory: PathBuf,
+
+        /// File of serialized proven transactions, run in the given order
+        #[arg(short, long, value_name = "FILE")]
+        transactions: PathBuf,
+
+        /// Verify the proofs of the transactions
+        #[arg(long)]
+        verify_proofs: bool,
+    },
+
+    /// Prints the dependency graph of the in-flight transactions of a running block producer
+    ///
+    /// This command prints the queued transactions, the transactions being batched and the batches
+    /// waiting for a block, along with the in-flight transactions they depend on, as JSON or in
+    /// the Graphviz DOT language. The block producer must be started with `debug_endpoints`
+    /// enabled.
+    DumpMempoolGraph {
+        /// URL of the block producer
+        #[arg(
+            short,
+            long,
+            default_value_t = Endpoint::localhost(DEFAULT_BLOCK_PRODUCER_PORT).to_string()
+        )]
+        url: String,
+
+        /// Print the graph in the Graphviz DOT language instead of JSON
+        #[arg(long)]
+        dot: bool,
+    },
+
+    /// Evicts a transaction from a running block producer before it's included in a block
+    ///
+    /// This command removes the transaction from the transaction queue, or drops the batch holding
+    /// it and queues the other transactions of the batch again. The eviction is refused if other
+    /// transactions depend on the transaction, unless `--cascade` is given. The command prints the
+    /// evicted transactions, the dropped batches and the queued transactions.
+    EvictTx {
+        /// URL of the block producer
+        #[arg(
+            short,
+            long,
+            default_value_t = Endpoint::localhost(DEFAULT_BLOCK_PRODUCER_PORT).to_string()
+        )]
+        url: String,
+
+        /// ID of the transaction to evict, as a hex string
+        #[arg(short, long, value_name = "ID", value_parser = parse_transaction_id)]
+        tx_id: TransactionId,
+
+        /// Evict the transactions depending on the transaction along with it
+        #[arg(long)]
+        cascade: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RpcCommand {
+    /// Prints the records of the audit log of the submitted transactions
+    ///
+    /// This command reads the current and rotated files of the audit log, and prints their
+    /// records from the oldest to the newest: the time of the submission in milliseconds since the
+    /// Unix epoch, the transaction ID, the address of the peer and the outcome of the submission.
+    AuditLog {
+        /// Directory of the audit log, as configured in the RPC configuration
+        #[arg(short, long, value_name = "DIR")]
+        directory: PathBuf,
+
+        /// Only print the records of this transaction, given as a hex string
+        #[arg(short, long, value_name = "ID", value_parser = parse_transaction_id)]
+        tx_id: Option<TransactionId>,
+
+        /// Print the records as JSON, one per line
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StoreCommand {
+    /// Exports the chain to a directory of flat files, for archival and replay
+    ///
+    /// This command writes the genesis file, one file per block holding the serialized block, and
+    /// a manifest listing the number, commitment, parent commitment and file hash of every block.
+    /// An existing archive of the same chain is extended with the missing blocks. The store must
+    /// not be running, and must not have pruned any block.
+    ExportChain {
+        /// Store data directory, holding the database, the genesis file and the block store
+        #[arg(short, long, value_name = "DIR")]
+        data_directory: PathBuf,
+
+        /// Directory to write the archive to
+        #[arg(short, long, value_name = "DIR")]
+        output: PathBuf,
+    },
+
+    /// Imports a chain exported by `export-chain` into a store data directory
+    ///
+    /// This command replays the archived blocks in order through the store, checking their files
+    /// against the manifest, their parent commitments and their roots. It stops at the first
+    /// invalid block, and an interrupted import resumes after the blocks already imported.
+    ImportChain {
+        /// Directory of the archive
+        #[arg(short, long, value_name = "DIR")]
+        input: PathBuf,
+
+        /// Store data directory, bootstrapped from the archived genesis file if it holds no data
+        #[arg(short, long, value_name = "DIR")]
+        data_directory: PathBuf,
+    },
+
+    /// Runs a read query against a running store and prints its result
+    ///
+    /// Block numbers and account IDs are given in decimal, or in hex with a `0x` prefix.
+    Query {
+        /// URL of the store
+        #[arg(short, long, default_value_t = Endpoint::localhost(DEFAULT_STORE_PORT).to_string())]
+        endpoint: String,
+
+        /// Format of the result
+        #[arg(short, long, value_enum, default_value_t)]
+        format: OutputFormat,
+
+        #[command(subcommand)]
+        query: StoreQuery,
+    },
+
+    /// Opens an interactive console running read queries against a running store
+    ///
+    /// The console accepts the queries of `store query`, and binds their results to variables with
+    /// `let <name> = <query>`, whose fields can be used as arguments of the following queries,
+    /// e.g. `notes $h.block_num`. Type `help` for the list of commands. When the standard
+    /// input isn't a terminal, its lines are run one after another.
+    Console {
+        /// URL of the store
+        #[arg(short, long, default_value_t = Endpoint::localhost(DEFAULT_STORE_PORT).to_string())]
+        endpoint: String,
+
+        /// Format of the results, can be changed with `format pretty|json`
+        #[arg(short, long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
     miden_node_utils::logging::setup_logging()?;
 
     let cli = Cli::parse();
 
+    // The bundled node runs on the runtime configured in its configuration file, the other
+    // commands on the default runtime
+    let node_config = match &cli.command {
+        Command::Start {
+            command: StartCommand::Node,
+            config: config_path,
+            ..
+        } => Some(
+            load_config_with::<NodeConfig>(config_path, cli.unknown_keys())
+                .context("Loading configuration file")?,
+        ),
+        _ => None,
+    };
+    let runtime = node_config
+        .as_ref()
+        .map(NodeConfig::runtime)
+        .unwrap_or_default()
+        .build_runtime()
+        .context("Building the runtime")?;
+
+    runtime.block_on(run(cli, node_config))
+}
+
+/// Runs the command of the `cli`, with the configuration of the bundled node if it was loaded
+/// before building the runtime.
+async fn run(cli: Cli, node_config: Option<NodeConfig>) -> anyhow::Result<()> {
+    let unknown_keys = cli.unknown_keys();
+
     match &cli.command {
-        Command::Start { command, config } => match command {
-            StartCommand::Node => {
-                let config = load_config(config).context("Loading configuration file")?;
-                start_node(config).await
-            },
-            StartCommand::BlockProducer => {
-                let config = load_config(config).context("Loading configuration file")?;
-                BlockProducer::init(config)
-                    .await
-                    .context("Loading block-producer")?
-                    .serve()
-                    .await
-                    .context("Serving block-producer")
-            },
-            StartCommand::Rpc => {
-                let config = load_config(config).context("Loading configuration file")?;
-                Rpc::init(config)
-                    .await
-                    .context("Loading RPC")?
-                    .serve()
-                    .await
-                    .context("Serving RPC")
-            },
-            StartCommand::Store => {
-                let config = load_config(config).context("Loading configuration file")?;
-                Store::init(config)
-                    .await
-                    .context("Loading store")?
-                    .serve()
-                    .await
-                    .context("Serving store")
-            },
+        Command::Start {
+            command,
+            config: config_path,
+            runtime_info,
+        } => {
+            let runtime_info =
+                runtime_info.clone().unwrap_or_else(|| RuntimeInfo::default_path(config_path));
+            match command {
+                StartCommand::Node => {
+                    let config = node_config.expect("The node configuration is loaded by `main`");
+                    start_node(config, config_path, &runtime_info, shutdown_signal()).await
+                },
+                StartCommand::BlockProducer => {
+                    let config = load_config_with(config_path, unknown_keys)
+                        .context("Loading configuration file")?;
+                    start_block_producer(config, config_path, &runtime_info, shutdown_signal())
+                        .await
+                },
+                StartCommand::Rpc => {
+                    let config = load_config_with(config_path, unknown_keys)
+                        .context("Loading configuration file")?;
+                    start_rpc(config, config_path, &runtime_info, shutdown_signal()).await
+                },
+                StartCommand::Store => {
+                    let config = load_config_with(config_path, unknown_keys)
+                        .context("Loading configuration file")?;
+                    start_store(config, config_path, &runtime_info, shutdown_signal()).await
+                },
+            }
         },
         Command::MakeGenesis { output_path, force, inputs_path } => {
-            commands::make_genesis(inputs_path, output_path, force)
+            commands::make_genesis(inputs_path, output_path, force, unknown_keys)
         },
-        Command::Init { config_path, genesis_path } => {
+        Command::Init {
+            config_path,
+            genesis_path,
+            data_directory,
+            token_symbol,
+            no_faucet,
+            interactive,
+        } => {
             let current_dir = std::env::current_dir()
                 .map_err(|err| anyhow!("failed to open current directory: {err}"))?;
 
             let config = current_dir.join(config_path);
             let genesis = current_dir.join(genesis_path);
+            let mut options = InitOptions {
+                data_directory: data_directory.as_ref().map(|dir| current_dir.join(dir)),
+                faucet_token_symbol: (!no_faucet).then(|| token_symbol.clone()),
+            };
+            if *interactive {
+                options =
+                    prompt_init_options(options, &current_dir, io::stdin().lock(), io::stdout())?;
+            }
 
-            init_config_files(config, genesis)
+            init_node(config, genesis, options, unknown_keys)
+        },
+        Command::MigrateConfig { input, output, component } => {
+            run_migrate_config(input, output, *component)
+        },
+        Command::Doctor { command, config, json, runtime_info } => {
+            let runtime_info =
+                runtime_info.clone().unwrap_or_else(|| RuntimeInfo::default_path(config));
+            run_doctor(command, config, &runtime_info, *json, unknown_keys).await
+        },
+        Command::BlockProducer { command } => match command {
+            BlockProducerCommand::DryRun {
+                data_directory,
+                transactions,
+                verify_proofs,
+            } => run_dry_run(data_directory, transactions, *verify_proofs).await,
+            BlockProducerCommand::DumpMempoolGraph { url, dot } => {
+                run_dump_mempool_graph(url, *dot).await
+            },
+            BlockProducerCommand::EvictTx { url, tx_id, cascade } => {
+                run_evict_tx(url, *tx_id, *cascade).await
+            },
+        },
+        Command::Rpc { command } => match command {
+            RpcCommand::AuditLog { directory, tx_id, json } => {
+                run_audit_log(directory, *tx_id, *json)
+            },
+        },
+        Command::Store { command } => match command {
+            StoreCommand::ExportChain { data_directory, output } => {
+                run_export_chain(data_directory, output).await
+            },
+            StoreCommand::ImportChain { input, data_directory } => {
+                run_import_chain(input, data_directory).await
+            },
+            StoreCommand::Query { endpoint, format, query } => {
+                run_query(endpoint, query, *format).await
+            },
+            StoreCommand::Console { endpoint, format } => run_console(endpoint, *format).await,
         },
     }
 }
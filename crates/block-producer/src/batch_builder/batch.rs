@@ -4,6 +4,7 @@ use std::{
 };
 
 use miden_node_proto::domain::notes::NoteAuthenticationInfo;
+use miden_node_utils::notes::validate_output_note;
 use miden_objects::{
     accounts::{delta::AccountUpdateDetails, AccountId},
     batches::BatchNoteTree,
@@ -33,6 +34,9 @@ pub struct TransactionBatch {
     input_notes: Vec<InputNoteCommitment>,
     output_notes_smt: BatchNoteTree,
     output_notes: Vec<OutputNote>,
+    /// The transactions of the batch, kept until it's included in a block so that it can be
+    /// dropped again.
+    transactions: Vec<ProvenTransaction>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -177,6 +181,7 @@ impl TransactionBatch {
             input_notes,
             output_notes_smt,
             output_notes,
+            transactions: txs,
         })
     }
 
@@ -203,6 +208,18 @@ impl TransactionBatch {
         self.updated_accounts.iter()
     }
 
+    /// Returns the IDs of the transactions in this batch.
+    pub fn transaction_ids(&self) -> impl Iterator<Item = TransactionId> + '_ {
+        self.updated_accounts
+            .values()
+            .flat_map(|update| update.transactions.iter().copied())
+    }
+
+    /// Returns the transactions of this batch, in the order in which they were queued.
+    pub fn transactions(&self) -> &[ProvenTransaction] {
+        &self.transactions
+    }
+
     /// Returns input notes list consumed by the transactions in this batch. Any unauthenticated
     /// input notes which have matching output notes within this batch are not included in this
     /// list.
@@ -252,6 +269,13 @@ impl OutputNoteTracker {
                 if output_note_index.insert(note.id(), output_notes.len()).is_some() {
                     return Err(BuildBatchError::DuplicateOutputNote(note.id(), txs.to_vec()));
                 }
+                if let Err(source) = validate_output_note(note) {
+                    return Err(BuildBatchError::InvalidOutputNote {
+                        note_id: note.id(),
+                        source,
+                        txs: txs.to_vec(),
+                    });
+                }
                 output_notes.push(Some(note.clone()));
             }
         }
@@ -301,7 +325,10 @@ mod tests {
     use super::*;
     use crate::test_utils::{
         mock_proven_tx,
-        note::{mock_note, mock_output_note, mock_unauthenticated_note_commitment},
+        note::{
+            mock_invalid_output_notes, mock_note, mock_output_note,
+            mock_unauthenticated_note_commitment,
+        },
     };
 
     #[test]
@@ -353,6 +380,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_invalid_output_notes() {
+        for note in mock_invalid_output_notes() {
+            let mut txs = mock_proven_txs();
+            txs.push(mock_proven_tx(3, vec![], vec![note.clone()]));
+
+            match TransactionBatch::new(txs, Default::default()) {
+                Err(BuildBatchError::InvalidOutputNote { note_id, .. }) => {
+                    assert_eq!(note_id, note.id())
+                },
+                res => panic!("Unexpected result: {res:?}"),
+            }
+        }
+    }
+
     #[test]
     fn test_duplicate_unauthenticated_notes() {
         let mut txs = mock_proven_txs();
@@ -1,20 +1,39 @@
-use std::{cmp::min, collections::BTreeSet, sync::Arc, time::Duration};
+use std::{
+    cmp::min,
+    collections::BTreeSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
-use miden_objects::{notes::NoteId, transaction::OutputNote};
-use tokio::time;
-use tracing::{debug, info, instrument, Span};
-
-use crate::{block_builder::BlockBuilder, ProvenTransaction, SharedRwVec, COMPONENT};
+use miden_objects::{accounts::AccountId, notes::NoteId, transaction::OutputNote, Digest};
+use tracing::{debug, info, info_span, instrument, warn, Instrument, Span};
+
+use crate::{
+    block_budget::BlockBudget,
+    block_builder::BlockBuilder,
+    events::{TransactionEvents, TransactionStatus},
+    proof_time::SimulatedProving,
+    reference_blocks::ReferenceBlocks,
+    ticker::{TickJitter, Ticker},
+    ProvenTransaction, SharedRwVec, COMPONENT,
+};
 
 #[cfg(test)]
 mod tests;
 
 pub mod batch;
 pub use batch::TransactionBatch;
-use miden_node_utils::formatting::{format_array, format_blake3_digest};
+use miden_node_utils::{
+    block_num::BlockNumberExt,
+    formatting::{format_array, format_blake3_digest},
+    runtime::BlockingPool,
+};
 
-use crate::{errors::BuildBatchError, store::Store};
+use crate::{
+    errors::{BuildBatchError, EvictTransactionError},
+    store::Store,
+};
 
 // BATCH BUILDER
 // ================================================================================================
@@ -32,8 +51,33 @@ use crate::{errors::BuildBatchError, store::Store};
 pub trait BatchBuilder: Send + Sync + 'static {
     /// Start proving of a new batch.
     async fn build_batch(&self, txs: Vec<ProvenTransaction>) -> Result<(), BuildBatchError>;
+
+    /// Removes the batches waiting to be included in a block which are picked by `select`, and
+    /// returns them in order.
+    ///
+    /// `select` is given the waiting batches in order, and returns the increasing indices of the
+    /// ones to remove. No batch is added or included in a block while it runs, but the batches
+    /// already taken by a block being built are not given. Implementations which don't keep the
+    /// batches give none.
+    async fn remove_ready_batches(
+        &self,
+        select: BatchSelection<'_>,
+    ) -> Result<Vec<TransactionBatch>, EvictTransactionError> {
+        select(&[]).map(|_| Vec::new())
+    }
+
+    /// Returns the batches waiting to be included in a block, in order. Implementations which don't
+    /// keep the batches return none.
+    async fn ready_batches(&self) -> Vec<TransactionBatch> {
+        Vec::new()
+    }
 }
 
+/// Selection of batches to remove among the batches waiting to be included in a block, see
+/// [BatchBuilder::remove_ready_batches].
+pub type BatchSelection<'a> =
+    Box<dyn FnOnce(&[TransactionBatch]) -> Result<Vec<usize>, EvictTransactionError> + Send + 'a>;
+
 // DEFAULT BATCH BUILDER
 // ================================================================================================
 
@@ -44,6 +88,16 @@ pub struct DefaultBatchBuilderOptions {
 
     /// Maximum number of batches in any given block
     pub max_batches_per_block: usize,
+
+    /// Randomization of the block building schedule
+    pub tick_jitter: TickJitter,
+
+    /// Number of blocks a transaction must remain valid for after the earliest block its batch
+    /// could be included in.
+    ///
+    /// A non-zero slack also drops transactions which could still be included, in exchange for
+    /// not proving batches which may miss their inclusion block.
+    pub expiration_slack: u32,
 }
 
 pub struct DefaultBatchBuilder<S, BB> {
@@ -55,6 +109,21 @@ pub struct DefaultBatchBuilder<S, BB> {
 
     /// Batches ready to be included in a block
     ready_batches: SharedRwVec<TransactionBatch>,
+
+    /// Additional delay applied to every batch to simulate proving
+    simulated_proving: Option<SimulatedProving>,
+
+    /// Status changes of the transactions, published once they are batched
+    events: Arc<TransactionEvents>,
+
+    /// Reference blocks of the transactions, checked against the retention of the store
+    reference_blocks: Arc<ReferenceBlocks>,
+
+    /// Pool the batches are proven on
+    proving_pool: BlockingPool,
+
+    /// Number of batches included in every block
+    block_budget: BlockBudget,
 }
 
 impl<S, BB> DefaultBatchBuilder<S, BB>
@@ -70,21 +139,64 @@ where
         Self {
             store,
             block_builder,
-            options,
             ready_batches: Default::default(),
+            simulated_proving: None,
+            events: Default::default(),
+            reference_blocks: Default::default(),
+            proving_pool: BlockingPool::inline(),
+            block_budget: BlockBudget::fixed(options.max_batches_per_block),
+            options,
         }
     }
 
+    /// Adapts the number of batches of every block, up to `max_batches_per_block`, so that blocks
+    /// are built, proven and applied within `target`, see [BlockBudget].
+    pub fn with_block_build_target(mut self, target: Duration) -> Self {
+        self.block_budget = BlockBudget::adaptive(self.options.max_batches_per_block, target);
+        self
+    }
+
+    /// Delays every batch by a duration sampled by `simulated_proving`, to simulate proving.
+    pub fn with_simulated_proving(mut self, simulated_proving: SimulatedProving) -> Self {
+        self.simulated_proving = Some(simulated_proving);
+        self
+    }
+
+    /// Proves the batches on `proving_pool`, instead of the task building them.
+    pub fn with_proving_pool(mut self, proving_pool: BlockingPool) -> Self {
+        self.proving_pool = proving_pool;
+        self
+    }
+
+    /// Publishes the status changes of the batched transactions to `events`.
+    pub fn with_events(mut self, events: Arc<TransactionEvents>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Rejects the transactions whose reference block recorded in `reference_blocks` would no
+    /// longer be retained by the store once the batch could be included in a block.
+    pub fn with_reference_blocks(mut self, reference_blocks: Arc<ReferenceBlocks>) -> Self {
+        self.reference_blocks = reference_blocks;
+        self
+    }
+
     // BATCH BUILDER STARTER
     // --------------------------------------------------------------------------------------------
     pub async fn run(self: Arc<Self>) {
-        let mut interval = time::interval(self.options.block_frequency);
+        let mut ticker = Ticker::new(self.options.block_frequency, self.options.tick_jitter);
 
-        info!(target: COMPONENT, period_ms = interval.period().as_millis(), "Batch builder started");
+        info!(target: COMPONENT, period_ms = ticker.period().as_millis(), "Batch builder started");
 
         loop {
-            interval.tick().await;
-            self.try_build_block().await;
+            let tick_delay = ticker.tick().await;
+            self.try_build_block()
+                .instrument(info_span!(
+                    target: COMPONENT,
+                    "block_tick",
+                    tick_delay_ms = tick_delay.as_millis()
+                ))
+                .await;
         }
     }
 
@@ -98,15 +210,18 @@ where
         let mut batches_in_block: Vec<TransactionBatch> = {
             let mut locked_ready_batches = self.ready_batches.write().await;
 
-            let num_batches_in_block =
-                min(self.options.max_batches_per_block, locked_ready_batches.len());
+            let num_batches_in_block = min(self.block_budget.batches(), locked_ready_batches.len());
 
             locked_ready_batches.drain(..num_batches_in_block).collect()
         };
 
+        let started_at = Instant::now();
         match self.block_builder.build_block(&batches_in_block).await {
             Ok(_) => {
-                // block successfully built, do nothing
+                self.block_budget.record(batches_in_block.len(), started_at.elapsed());
+
+                // block successfully built, the store may have pruned blocks since
+                self.refresh_retention_depth().await;
             },
             Err(_) => {
                 // Block building failed; add back the batches at the end of the queue
@@ -115,6 +230,43 @@ where
         }
     }
 
+    /// Fetches the number of most recent blocks whose data the store retains, keeping the
+    /// previous one if the store can't be reached.
+    pub async fn refresh_retention_depth(&self) {
+        match self.store.get_block_retention_depth().await {
+            Ok(retention_depth) => self.reference_blocks.set_retention_depth(retention_depth),
+            Err(err) => warn!(
+                target: COMPONENT,
+                %err,
+                "Failed to fetch the block retention depth of the store"
+            ),
+        }
+    }
+
+    /// Returns the earliest block in which a batch built now could be included.
+    ///
+    /// This assumes that every block following the chain tip is filled up with the batches which
+    /// are already waiting in `ready_batches`, so the result is never later than the actual
+    /// inclusion block. Returns `None` if the chain tip could not be fetched from the store.
+    async fn earliest_inclusion_block(&self) -> Option<u32> {
+        let chain_tip = match self.store.get_latest_block_header().await {
+            Ok(header) => header.block_num(),
+            Err(err) => {
+                warn!(
+                    target: COMPONENT,
+                    %err,
+                    "Failed to fetch the chain tip, skipping the transaction expiration check"
+                );
+                return None;
+            },
+        };
+        let batches_ahead = self.ready_batches.read().await.len();
+        let full_blocks_ahead = batches_ahead / self.options.max_batches_per_block.max(1);
+        let full_blocks_ahead = u32::try_from(full_blocks_ahead).unwrap_or(u32::MAX);
+
+        Some(chain_tip.saturating_advance(full_blocks_ahead.saturating_add(1)))
+    }
+
     /// Returns a list of IDs for unauthenticated notes which are not output notes of any ready
     /// transaction batch or the candidate batch itself.
     async fn find_dangling_notes(&self, txs: &[ProvenTransaction]) -> Vec<NoteId> {
@@ -149,13 +301,64 @@ where
     S: Store,
     BB: BlockBuilder,
 {
-    #[instrument(target = "miden-block-producer", skip_all, err, fields(batch_id))]
+    #[instrument(
+        target = "miden-block-producer",
+        skip_all,
+        err,
+        fields(batch_id, proof_time_distribution, simulated_proof_time_ms)
+    )]
     async fn build_batch(&self, txs: Vec<ProvenTransaction>) -> Result<(), BuildBatchError> {
         let num_txs = txs.len();
 
         info!(target: COMPONENT, num_txs, "Building a transaction batch");
         debug!(target: COMPONENT, txs = %format_array(txs.iter().map(|tx| tx.id().to_hex())));
 
+        // make sure that none of the transactions expires before the batch could possibly be
+        // included in a block, so that we don't spend time proving a batch which can't be
+        // committed. The inclusion block is a lower bound, so without slack only transactions
+        // which can't be included are dropped, while the slack also drops the ones which would
+        // only be included if the next blocks are built in time
+        let txs = match self.earliest_inclusion_block().await {
+            Some(inclusion_block) => {
+                let expiration_slack = self.options.expiration_slack;
+                let (txs, expired) = split_dependent_transactions(txs, &[], |tx| {
+                    tx.expiration_block_num()
+                        .expires_before(inclusion_block.saturating_advance(expiration_slack))
+                });
+
+                if !expired.is_empty() {
+                    return Err(BuildBatchError::TransactionsExpired {
+                        inclusion_block,
+                        expiration_slack,
+                        expired,
+                        txs,
+                    });
+                }
+
+                // the block including the batch is proven against the reference blocks of its
+                // transactions, so they must still be retained by the store by then
+                if let Some(retention_depth) = self.reference_blocks.retention_depth() {
+                    let (txs, stale) = split_dependent_transactions(txs, &[], |tx| {
+                        self.reference_blocks.is_stale(tx.id(), inclusion_block)
+                    });
+
+                    if !stale.is_empty() {
+                        return Err(BuildBatchError::StaleReferenceBlock {
+                            inclusion_block,
+                            retention_depth,
+                            stale,
+                            txs,
+                        });
+                    }
+
+                    txs
+                } else {
+                    txs
+                }
+            },
+            None => txs,
+        };
+
         // make sure that all unauthenticated notes in the transactions of the proposed batch
         // have been either created in any of the ready batches (or the batch itself) or are
         // already in the store
@@ -184,7 +387,14 @@ where
             },
         };
 
-        let batch = TransactionBatch::new(txs, found_unauthenticated_notes)?;
+        let tx_ids: Vec<_> = txs.iter().map(ProvenTransaction::id).collect();
+        let batch = self
+            .proving_pool
+            .run(move || TransactionBatch::new(txs, found_unauthenticated_notes))
+            .await?;
+        if let Some(simulated_proving) = &self.simulated_proving {
+            simulated_proving.prove().await;
+        }
 
         info!(target: COMPONENT, "Transaction batch built");
         Span::current().record("batch_id", format_blake3_digest(batch.id()));
@@ -196,7 +406,80 @@ where
         };
 
         info!(target: COMPONENT, num_batches, "Transaction batch added to the batch queue");
+        self.reference_blocks.remove(tx_ids.iter().copied());
+        self.events.publish(tx_ids, &TransactionStatus::Batched);
 
         Ok(())
     }
+
+    async fn remove_ready_batches(
+        &self,
+        select: BatchSelection<'_>,
+    ) -> Result<Vec<TransactionBatch>, EvictTransactionError> {
+        let mut locked_ready_batches = self.ready_batches.write().await;
+        let selected = select(&locked_ready_batches)?;
+
+        // Removing from the last batch, so that the indices of the others don't shift
+        let mut removed: Vec<_> = selected
+            .into_iter()
+            .rev()
+            .map(|index| locked_ready_batches.remove(index))
+            .collect();
+        removed.reverse();
+
+        info!(
+            target: COMPONENT,
+            batches = %format_array(removed.iter().map(|batch| format_blake3_digest(batch.id()))),
+            "Removed batches from the batch queue"
+        );
+
+        Ok(removed)
+    }
+
+    async fn ready_batches(&self) -> Vec<TransactionBatch> {
+        self.ready_batches.read().await.clone()
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Splits `txs` into the transactions which remain valid and the ones which have to be removed.
+///
+/// A transaction is removed if it is selected by `is_removed`, or if it depends on a removed
+/// transaction, either on one of `removed` or on an earlier transaction of `txs`. A transaction
+/// depends on another one if it continues the other transaction's account state, or if it
+/// consumes one of its output notes as an unauthenticated note.
+///
+/// `txs` is expected to be in queue order, i.e. transactions come after the ones they depend on.
+/// The relative order of the transactions is preserved in both returned lists.
+pub(crate) fn split_dependent_transactions(
+    txs: Vec<ProvenTransaction>,
+    removed: &[ProvenTransaction],
+    mut is_removed: impl FnMut(&ProvenTransaction) -> bool,
+) -> (Vec<ProvenTransaction>, Vec<ProvenTransaction>) {
+    let mut removed_states: BTreeSet<(AccountId, Digest)> = BTreeSet::new();
+    let mut removed_notes: BTreeSet<NoteId> = BTreeSet::new();
+    for tx in removed {
+        removed_states.insert((tx.account_id(), tx.account_update().final_state_hash()));
+        removed_notes.extend(tx.output_notes().iter().map(OutputNote::id));
+    }
+
+    let mut kept = Vec::with_capacity(txs.len());
+    let mut dropped = Vec::new();
+    for tx in txs {
+        let depends_on_removed = removed_states
+            .contains(&(tx.account_id(), tx.account_update().init_state_hash()))
+            || tx.get_unauthenticated_notes().any(|note| removed_notes.contains(&note.id()));
+
+        if depends_on_removed || is_removed(&tx) {
+            removed_states.insert((tx.account_id(), tx.account_update().final_state_hash()));
+            removed_notes.extend(tx.output_notes().iter().map(OutputNote::id));
+            dropped.push(tx);
+        } else {
+            kept.push(tx);
+        }
+    }
+
+    (kept, dropped)
 }
@@ -1,14 +1,18 @@
 use std::iter;
 
+use miden_node_utils::block_num::NEVER_EXPIRES;
 use miden_objects::{crypto::merkle::Mmr, Digest};
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, time};
 
 use super::*;
 use crate::{
     block_builder::DefaultBlockBuilder,
     errors::BuildBlockError,
+    reference_blocks::ReferenceBlocks,
+    store::ApplyBlock,
     test_utils::{
-        note::mock_note, MockPrivateAccount, MockProvenTxBuilder, MockStoreSuccessBuilder,
+        block::MockBlockBuilder, note::mock_note, MockPrivateAccount, MockProvenTxBuilder,
+        MockStoreSuccessBuilder,
     },
 };
 // STRUCTS
@@ -33,6 +37,21 @@ impl BlockBuilder for BlockBuilderSuccess {
     }
 }
 
+/// Block builder taking `delay` to build every block.
+#[derive(Default)]
+struct SlowBlockBuilder {
+    inner: BlockBuilderSuccess,
+    delay: Duration,
+}
+
+#[async_trait]
+impl BlockBuilder for SlowBlockBuilder {
+    async fn build_block(&self, batches: &[TransactionBatch]) -> Result<(), BuildBlockError> {
+        time::sleep(self.delay).await;
+        self.inner.build_block(batches).await
+    }
+}
+
 #[derive(Default)]
 struct BlockBuilderFailure;
 
@@ -59,7 +78,12 @@ async fn test_block_size_doesnt_exceed_limit() {
     let batch_builder = Arc::new(DefaultBatchBuilder::new(
         store,
         block_builder.clone(),
-        DefaultBatchBuilderOptions { block_frequency, max_batches_per_block },
+        DefaultBatchBuilderOptions {
+            block_frequency,
+            max_batches_per_block,
+            tick_jitter: Default::default(),
+            expiration_slack: 0,
+        },
     ));
 
     // Add 3 batches in internal queue (remember: 2 batches/block)
@@ -86,6 +110,60 @@ async fn test_block_size_doesnt_exceed_limit() {
     }
 }
 
+/// Tests that blocks taking longer than the target to build shrink the number of batches of the
+/// following blocks
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_slow_blocks_shrink_the_block_budget() {
+    let block_frequency = Duration::from_millis(50);
+
+    let store = Arc::new(MockStoreSuccessBuilder::from_accounts(iter::empty()).build());
+    let block_builder = Arc::new(SlowBlockBuilder {
+        delay: Duration::from_millis(20),
+        ..Default::default()
+    });
+
+    let batch_builder = Arc::new(
+        DefaultBatchBuilder::new(
+            store,
+            block_builder.clone(),
+            DefaultBatchBuilderOptions {
+                block_frequency,
+                max_batches_per_block: 2,
+                tick_jitter: Default::default(),
+                expiration_slack: 0,
+            },
+        )
+        .with_block_build_target(Duration::from_millis(10)),
+    );
+
+    {
+        let mut batch_group =
+            vec![dummy_tx_batch(0, 2), dummy_tx_batch(10, 2), dummy_tx_batch(20, 2)];
+
+        batch_builder.ready_batches.write().await.append(&mut batch_group);
+    }
+
+    tokio::spawn(batch_builder.run());
+
+    // Wait for the first two blocks, however late they are scheduled
+    let sizes = time::timeout(Duration::from_secs(5), async {
+        loop {
+            let batch_groups = block_builder.inner.batch_groups.read().await;
+            if batch_groups.len() >= 2 {
+                break batch_groups.iter().map(Vec::len).collect::<Vec<_>>();
+            }
+            drop(batch_groups);
+            time::sleep(block_frequency / 5).await;
+        }
+    })
+    .await
+    .expect("two blocks must be built");
+
+    // The first block took twice the target, the budget of the next ones is halved
+    assert_eq!(sizes[..2], [2, 1]);
+}
+
 /// Tests that `BlockBuilder::build_block()` is still called when there are no transactions
 #[tokio::test]
 #[miden_node_test_macro::enable_logging]
@@ -99,7 +177,12 @@ async fn test_build_block_called_when_no_batches() {
     let batch_builder = Arc::new(DefaultBatchBuilder::new(
         store,
         block_builder.clone(),
-        DefaultBatchBuilderOptions { block_frequency, max_batches_per_block },
+        DefaultBatchBuilderOptions {
+            block_frequency,
+            max_batches_per_block,
+            tick_jitter: Default::default(),
+            expiration_slack: 0,
+        },
     ));
 
     // start batch builder
@@ -126,7 +209,12 @@ async fn test_batches_added_back_to_queue_on_block_build_failure() {
     let batch_builder = Arc::new(DefaultBatchBuilder::new(
         store,
         block_builder.clone(),
-        DefaultBatchBuilderOptions { block_frequency, max_batches_per_block },
+        DefaultBatchBuilderOptions {
+            block_frequency,
+            max_batches_per_block,
+            tick_jitter: Default::default(),
+            expiration_slack: 0,
+        },
     ));
 
     let internal_ready_batches = batch_builder.ready_batches.clone();
@@ -160,6 +248,8 @@ async fn test_batch_builder_find_dangling_notes() {
         DefaultBatchBuilderOptions {
             block_frequency: Duration::from_millis(20),
             max_batches_per_block: 2,
+            tick_jitter: Default::default(),
+            expiration_slack: 0,
         },
     ));
 
@@ -231,6 +321,8 @@ async fn test_block_builder_no_missing_notes() {
         DefaultBatchBuilderOptions {
             block_frequency: Duration::from_millis(20),
             max_batches_per_block: 2,
+            tick_jitter: Default::default(),
+            expiration_slack: 0,
         },
     ));
 
@@ -283,6 +375,8 @@ async fn test_block_builder_fails_if_notes_are_missing() {
         DefaultBatchBuilderOptions {
             block_frequency: Duration::from_millis(20),
             max_batches_per_block: 2,
+            tick_jitter: Default::default(),
+            expiration_slack: 0,
         },
     ));
 
@@ -313,6 +407,309 @@ async fn test_block_builder_fails_if_notes_are_missing() {
     );
 }
 
+/// Tests that a batch is not built if one of its transactions would expire before the batch could
+/// be included in a block, and that only the unaffected transactions are returned
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_batch_with_expired_transactions_is_not_built() {
+    let accounts: Vec<_> = (1..=3).map(MockPrivateAccount::<3>::from).collect();
+    let store = Arc::new(
+        MockStoreSuccessBuilder::from_accounts(
+            accounts.iter().map(|account| (account.id, account.states[0])),
+        )
+        .initial_block_num(10)
+        .build(),
+    );
+    let batch_builder = Arc::new(DefaultBatchBuilder::new(
+        store,
+        Arc::new(BlockBuilderSuccess::default()),
+        DefaultBatchBuilderOptions {
+            block_frequency: Duration::from_millis(20),
+            max_batches_per_block: 2,
+            tick_jitter: Default::default(),
+            expiration_slack: 0,
+        },
+    ));
+
+    // With 4 batches ahead and 2 batches per block, the earliest block the new batch could be
+    // included in is block 13
+    {
+        let mut batch_group = vec![
+            dummy_tx_batch(10, 1),
+            dummy_tx_batch(20, 1),
+            dummy_tx_batch(30, 1),
+            dummy_tx_batch(40, 1),
+        ];
+
+        batch_builder.ready_batches.write().await.append(&mut batch_group);
+    }
+
+    let note = mock_note(1);
+    let expired_tx = MockProvenTxBuilder::with_account(
+        accounts[0].id,
+        accounts[0].states[0],
+        accounts[0].states[1],
+    )
+    .expiration_block_num(12)
+    .output_notes(vec![OutputNote::Full(note.clone())])
+    .build();
+    let viable_tx = MockProvenTxBuilder::with_account(
+        accounts[1].id,
+        accounts[1].states[0],
+        accounts[1].states[1],
+    )
+    .expiration_block_num(13)
+    .build();
+    let dependent_account_tx = MockProvenTxBuilder::with_account(
+        accounts[0].id,
+        accounts[0].states[1],
+        accounts[0].states[2],
+    )
+    .build();
+    let dependent_note_tx = MockProvenTxBuilder::with_account(
+        accounts[2].id,
+        accounts[2].states[0],
+        accounts[2].states[1],
+    )
+    .unauthenticated_notes(vec![note])
+    .build();
+
+    let txs = vec![
+        expired_tx.clone(),
+        viable_tx.clone(),
+        dependent_account_tx.clone(),
+        dependent_note_tx.clone(),
+    ];
+
+    let result = batch_builder.build_batch(txs).await;
+    assert_eq!(
+        result,
+        Err(BuildBatchError::TransactionsExpired {
+            inclusion_block: 13,
+            expiration_slack: 0,
+            expired: vec![expired_tx, dependent_account_tx, dependent_note_tx],
+            txs: vec![viable_tx.clone()],
+        })
+    );
+    assert_eq!(batch_builder.ready_batches.read().await.len(), 4, "No batch must be built");
+
+    // The remaining transactions can still be batched
+    batch_builder.build_batch(vec![viable_tx]).await.unwrap();
+    assert_eq!(batch_builder.ready_batches.read().await.len(), 5);
+}
+
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_expiration_slack_moves_expiration_boundary() {
+    let accounts: Vec<_> = (1..=2).map(MockPrivateAccount::<3>::from).collect();
+    let store = Arc::new(
+        MockStoreSuccessBuilder::from_accounts(
+            accounts.iter().map(|account| (account.id, account.states[0])),
+        )
+        .initial_block_num(10)
+        .build(),
+    );
+    let batch_builder = Arc::new(DefaultBatchBuilder::new(
+        store,
+        Arc::new(BlockBuilderSuccess::default()),
+        DefaultBatchBuilderOptions {
+            block_frequency: Duration::from_millis(20),
+            max_batches_per_block: 2,
+            tick_jitter: Default::default(),
+            expiration_slack: 2,
+        },
+    ));
+
+    // The earliest inclusion block is 13, as above, so transactions must now remain valid until
+    // block 15
+    {
+        let mut batch_group = vec![
+            dummy_tx_batch(10, 1),
+            dummy_tx_batch(20, 1),
+            dummy_tx_batch(30, 1),
+            dummy_tx_batch(40, 1),
+        ];
+
+        batch_builder.ready_batches.write().await.append(&mut batch_group);
+    }
+
+    let expired_tx = MockProvenTxBuilder::with_account(
+        accounts[0].id,
+        accounts[0].states[0],
+        accounts[0].states[1],
+    )
+    .expiration_block_num(14)
+    .build();
+    let viable_tx = MockProvenTxBuilder::with_account(
+        accounts[1].id,
+        accounts[1].states[0],
+        accounts[1].states[1],
+    )
+    .expiration_block_num(15)
+    .build();
+
+    let result = batch_builder.build_batch(vec![expired_tx.clone(), viable_tx.clone()]).await;
+    assert_eq!(
+        result,
+        Err(BuildBatchError::TransactionsExpired {
+            inclusion_block: 13,
+            expiration_slack: 2,
+            expired: vec![expired_tx],
+            txs: vec![viable_tx.clone()],
+        })
+    );
+
+    batch_builder.build_batch(vec![viable_tx]).await.unwrap();
+    assert_eq!(batch_builder.ready_batches.read().await.len(), 5);
+}
+
+/// Tests that the expiration boundary saturates at the last block number, and that transactions
+/// which never expire are still accepted there
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_expiration_boundary_saturates_at_last_block_number() {
+    let accounts: Vec<_> = (1..=2).map(MockPrivateAccount::<3>::from).collect();
+    let store = Arc::new(
+        MockStoreSuccessBuilder::from_accounts(
+            accounts.iter().map(|account| (account.id, account.states[0])),
+        )
+        .initial_block_num(u32::MAX - 1)
+        .build(),
+    );
+    let batch_builder = Arc::new(DefaultBatchBuilder::new(
+        store,
+        Arc::new(BlockBuilderSuccess::default()),
+        DefaultBatchBuilderOptions {
+            block_frequency: Duration::from_millis(20),
+            max_batches_per_block: 2,
+            tick_jitter: Default::default(),
+            expiration_slack: 2,
+        },
+    ));
+
+    // Two full blocks ahead and the slack would reach past the last block number
+    {
+        let mut batch_group = vec![
+            dummy_tx_batch(10, 1),
+            dummy_tx_batch(20, 1),
+            dummy_tx_batch(30, 1),
+            dummy_tx_batch(40, 1),
+        ];
+
+        batch_builder.ready_batches.write().await.append(&mut batch_group);
+    }
+
+    let expired_tx = MockProvenTxBuilder::with_account(
+        accounts[0].id,
+        accounts[0].states[0],
+        accounts[0].states[1],
+    )
+    .expiration_block_num(u32::MAX - 1)
+    .build();
+    let viable_tx = MockProvenTxBuilder::with_account(
+        accounts[1].id,
+        accounts[1].states[0],
+        accounts[1].states[1],
+    )
+    .expiration_block_num(NEVER_EXPIRES)
+    .build();
+
+    let result = batch_builder.build_batch(vec![expired_tx.clone(), viable_tx.clone()]).await;
+    assert_eq!(
+        result,
+        Err(BuildBatchError::TransactionsExpired {
+            inclusion_block: u32::MAX,
+            expiration_slack: 2,
+            expired: vec![expired_tx],
+            txs: vec![viable_tx.clone()],
+        })
+    );
+
+    batch_builder.build_batch(vec![viable_tx]).await.unwrap();
+    assert_eq!(batch_builder.ready_batches.read().await.len(), 5);
+}
+
+/// Tests that a batch is not built if one of its transactions references a block which the store
+/// would no longer retain once the batch could be included in a block
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_batch_with_stale_reference_blocks_is_not_built() {
+    let accounts: Vec<_> = (1..=2).map(MockPrivateAccount::<3>::from).collect();
+    let store = Arc::new(
+        MockStoreSuccessBuilder::from_accounts(
+            accounts.iter().map(|account| (account.id, account.states[0])),
+        )
+        .block_retention_depth(2)
+        .build(),
+    );
+    let reference_blocks = Arc::new(ReferenceBlocks::default());
+    let batch_builder = DefaultBatchBuilder::new(
+        Arc::clone(&store),
+        Arc::new(BlockBuilderSuccess::default()),
+        DefaultBatchBuilderOptions {
+            block_frequency: Duration::from_millis(20),
+            max_batches_per_block: 2,
+            tick_jitter: Default::default(),
+            expiration_slack: 0,
+        },
+    )
+    .with_reference_blocks(Arc::clone(&reference_blocks));
+    batch_builder.refresh_retention_depth().await;
+
+    let stale_tx = MockProvenTxBuilder::with_account(
+        accounts[0].id,
+        accounts[0].states[0],
+        accounts[0].states[1],
+    )
+    .build();
+    let dependent_tx = MockProvenTxBuilder::with_account(
+        accounts[0].id,
+        accounts[0].states[1],
+        accounts[0].states[2],
+    )
+    .build();
+    let viable_tx = MockProvenTxBuilder::with_account(
+        accounts[1].id,
+        accounts[1].states[0],
+        accounts[1].states[1],
+    )
+    .build();
+    reference_blocks.insert(stale_tx.id(), 1);
+    reference_blocks.insert(dependent_tx.id(), 3);
+
+    // Block 1 is still retained when proposing block 2
+    assert!(!reference_blocks.is_stale(stale_tx.id(), 2));
+
+    // Once blocks 2 and 3 are committed, the batch can't be included before block 4, by which time
+    // block 1 is no longer retained
+    for _ in 0..2 {
+        let block = MockBlockBuilder::new(&store).await.build();
+        store.apply_block(&block, None).await.unwrap();
+    }
+    reference_blocks.insert(viable_tx.id(), 3);
+
+    let txs = vec![stale_tx.clone(), dependent_tx.clone(), viable_tx.clone()];
+    let result = batch_builder.build_batch(txs).await;
+    assert_eq!(
+        result,
+        Err(BuildBatchError::StaleReferenceBlock {
+            inclusion_block: 4,
+            retention_depth: 2,
+            stale: vec![stale_tx, dependent_tx],
+            txs: vec![viable_tx.clone()],
+        })
+    );
+    assert!(batch_builder.ready_batches.read().await.is_empty(), "No batch must be built");
+
+    // The remaining transactions can still be batched
+    batch_builder.build_batch(vec![viable_tx.clone()]).await.unwrap();
+    assert_eq!(batch_builder.ready_batches.read().await.len(), 1);
+    assert!(
+        !reference_blocks.is_stale(viable_tx.id(), u32::MAX),
+        "Batched txs are forgotten"
+    );
+}
+
 // HELPERS
 // ================================================================================================
 
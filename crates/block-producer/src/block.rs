@@ -3,7 +3,7 @@ use std::collections::BTreeMap;
 use miden_node_proto::{
     domain::notes::NoteAuthenticationInfo,
     errors::{ConversionError, MissingFieldHelper},
-    generated::responses::GetBlockInputsResponse,
+    generated::{block::BlockInputsCursor, responses::GetBlockInputsResponse},
     AccountInputRecord, NullifierWitness,
 };
 use miden_objects::{
@@ -37,6 +37,48 @@ pub struct BlockInputs {
     pub found_unauthenticated_notes: NoteAuthenticationInfo,
 }
 
+/// Assembles the pages of the block inputs returned by the store.
+///
+/// The account states and nullifiers of large blocks are returned in several pages, each one
+/// giving the cursor of the next one. All of them must be anchored to the same block.
+#[derive(Debug)]
+pub struct BlockInputsPages {
+    inputs: GetBlockInputsResponse,
+}
+
+impl BlockInputsPages {
+    pub fn new(first_page: GetBlockInputsResponse) -> Self {
+        Self { inputs: first_page }
+    }
+
+    /// Returns the cursor of the next page to fetch, `None` once all the pages were appended.
+    pub fn next(&self) -> Option<BlockInputsCursor> {
+        self.inputs.next
+    }
+
+    /// Appends the next page.
+    pub fn append(&mut self, page: GetBlockInputsResponse) -> Result<(), BlockInputsError> {
+        if page.block_header != self.inputs.block_header || page.mmr_peaks != self.inputs.mmr_peaks
+        {
+            return Err(BlockInputsError::InconsistentPages);
+        }
+
+        self.inputs.account_states.extend(page.account_states);
+        self.inputs.nullifiers.extend(page.nullifiers);
+        self.inputs.next = page.next;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<BlockInputsPages> for BlockInputs {
+    type Error = BlockInputsError;
+
+    fn try_from(pages: BlockInputsPages) -> Result<Self, Self::Error> {
+        pages.inputs.try_into()
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct AccountWitness {
     pub hash: Digest,
@@ -105,3 +147,111 @@ impl TryFrom<GetBlockInputsResponse> for BlockInputs {
         })
     }
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use miden_node_proto::generated::responses::AccountBlockInputRecord;
+    use miden_node_test_utils::{mock_account_id, note::mock_nullifier};
+    use miden_objects::crypto::merkle::Smt;
+
+    use super::*;
+
+    fn block_header(block_num: u32) -> BlockHeader {
+        BlockHeader::new(
+            0,
+            Digest::default(),
+            block_num,
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            1,
+        )
+    }
+
+    /// Returns the block inputs of `num` accounts and as many nullifiers, in a single page.
+    fn block_inputs(num: u8) -> GetBlockInputsResponse {
+        let nullifier_tree = Smt::new();
+
+        GetBlockInputsResponse {
+            block_header: Some(block_header(0).into()),
+            mmr_peaks: vec![],
+            account_states: (0..num)
+                .map(|num| AccountBlockInputRecord {
+                    account_id: Some(mock_account_id(num).into()),
+                    account_hash: Some(Digest::default().into()),
+                    proof: Some(MerklePath::new(vec![]).into()),
+                })
+                .collect(),
+            nullifiers: (0..u32::from(num))
+                .map(|index| {
+                    let nullifier = mock_nullifier(index);
+                    let proof = nullifier_tree.open(&nullifier.inner());
+                    NullifierWitness { nullifier, proof }.into()
+                })
+                .collect(),
+            found_unauthenticated_notes: Some(NoteAuthenticationInfo::default().into()),
+            next: None,
+        }
+    }
+
+    /// Returns the page of `inputs` with the given account states and nullifiers, followed by the
+    /// page starting at `next`.
+    fn page(
+        inputs: &GetBlockInputsResponse,
+        accounts: Range<usize>,
+        nullifiers: Range<usize>,
+        next: Option<(u32, u32)>,
+    ) -> GetBlockInputsResponse {
+        GetBlockInputsResponse {
+            found_unauthenticated_notes: inputs
+                .found_unauthenticated_notes
+                .clone()
+                .filter(|_| accounts.start == 0 && nullifiers.start == 0),
+            account_states: inputs.account_states[accounts].to_vec(),
+            nullifiers: inputs.nullifiers[nullifiers].to_vec(),
+            next: next.map(|(account_offset, nullifier_offset)| BlockInputsCursor {
+                snapshot: 7,
+                account_offset,
+                nullifier_offset,
+            }),
+            ..inputs.clone()
+        }
+    }
+
+    #[test]
+    fn block_inputs_pages_are_assembled() {
+        let inputs = block_inputs(6);
+
+        let mut pages = BlockInputsPages::new(page(&inputs, 0..4, 0..0, Some((4, 0))));
+        for next_page in [page(&inputs, 4..6, 0..2, Some((6, 2))), page(&inputs, 6..6, 2..6, None)]
+        {
+            assert!(pages.next().is_some());
+            pages.append(next_page).unwrap();
+        }
+        assert_eq!(pages.next(), None);
+        assert_eq!(pages.inputs, inputs);
+
+        let assembled = BlockInputs::try_from(pages).unwrap();
+        assert_eq!(assembled.accounts.len(), 6);
+        assert_eq!(assembled.nullifiers.len(), 6);
+    }
+
+    #[test]
+    fn block_inputs_pages_of_other_blocks_are_rejected() {
+        let inputs = block_inputs(6);
+        let mut pages = BlockInputsPages::new(page(&inputs, 0..4, 0..0, Some((4, 0))));
+
+        let mut next_page = page(&inputs, 4..6, 0..6, None);
+        next_page.block_header = Some(block_header(1).into());
+        assert_eq!(pages.append(next_page), Err(BlockInputsError::InconsistentPages));
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use tracing::info;
+
+use crate::COMPONENT;
+
+/// Blocks built faster than this percentage of the target grow the budget.
+const GROW_THRESHOLD_PERCENT: u32 = 75;
+
+/// Number of batches included in every block.
+///
+/// A fixed budget always selects up to the maximum number of batches per block. An adaptive budget
+/// tracks the time taken to build, prove and apply the recent blocks, so that blocks keep up with
+/// the block interval when proving slows down:
+///
+/// - a block taking longer than the target shrinks the budget in proportion to the overshoot, by at
+///   least one batch.
+/// - a block using the whole budget and taking less than 75% of the target grows the budget by one
+///   batch, up to the maximum.
+///
+/// Empty blocks leave the budget unchanged, since their duration doesn't depend on it.
+#[derive(Debug)]
+pub struct BlockBudget {
+    max_batches: usize,
+    /// Target duration of building a block, [None] for a fixed budget.
+    target: Option<Duration>,
+    batches: AtomicUsize,
+}
+
+impl BlockBudget {
+    /// Returns a budget of `max_batches` batches per block.
+    pub fn fixed(max_batches: usize) -> Self {
+        Self {
+            max_batches,
+            target: None,
+            batches: AtomicUsize::new(max_batches),
+        }
+    }
+
+    /// Returns a budget of at most `max_batches` batches per block, adapted so that blocks are
+    /// built within `target`.
+    pub fn adaptive(max_batches: usize, target: Duration) -> Self {
+        Self {
+            target: Some(target),
+            ..Self::fixed(max_batches)
+        }
+    }
+
+    /// Returns the number of batches of the next block.
+    pub fn batches(&self) -> usize {
+        self.batches.load(Ordering::Relaxed)
+    }
+
+    /// Records that a block of `num_batches` batches was built in `duration`, and adapts the
+    /// budget of the next blocks.
+    pub fn record(&self, num_batches: usize, duration: Duration) {
+        let Some(target) = self.target else {
+            return;
+        };
+        if num_batches == 0 {
+            return;
+        }
+
+        let budget = self.batches();
+        let next_budget = if duration > target {
+            // Scaled down by the overshoot, in at least one batch
+            let scaled = num_batches as f64 * target.as_secs_f64() / duration.as_secs_f64();
+            (scaled as usize).min(budget - 1).max(1)
+        } else if num_batches >= budget && duration < target * GROW_THRESHOLD_PERCENT / 100 {
+            (budget + 1).min(self.max_batches)
+        } else {
+            budget
+        };
+
+        if next_budget != budget {
+            info!(
+                target: COMPONENT,
+                num_batches,
+                duration_ms = duration.as_millis(),
+                target_ms = target.as_millis(),
+                budget = next_budget,
+                "Adapted the number of batches per block"
+            );
+            self.batches.store(next_budget, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGET: Duration = Duration::from_secs(8);
+
+    #[test]
+    fn fixed_budgets_are_not_adapted() {
+        let budget = BlockBudget::fixed(4);
+        budget.record(4, TARGET * 10);
+        assert_eq!(budget.batches(), 4);
+    }
+
+    #[test]
+    fn slow_blocks_shrink_the_budget() {
+        let budget = BlockBudget::adaptive(8, TARGET);
+
+        // Twice as long as the target halves the budget
+        budget.record(8, TARGET * 2);
+        assert_eq!(budget.batches(), 4);
+
+        // Slightly slow blocks remove one batch
+        budget.record(4, TARGET + Duration::from_millis(1));
+        assert_eq!(budget.batches(), 3);
+
+        // At least one batch is kept
+        budget.record(3, TARGET * 100);
+        assert_eq!(budget.batches(), 1);
+        budget.record(1, TARGET * 100);
+        assert_eq!(budget.batches(), 1);
+
+        // Empty blocks don't depend on the budget
+        let budget = BlockBudget::adaptive(8, TARGET);
+        budget.record(0, TARGET * 2);
+        assert_eq!(budget.batches(), 8);
+    }
+
+    #[test]
+    fn fast_full_blocks_grow_the_budget() {
+        let budget = BlockBudget::adaptive(4, TARGET);
+        budget.record(4, TARGET * 2);
+        assert_eq!(budget.batches(), 2);
+
+        // Blocks which don't use the whole budget, or which are close to the target, don't tell
+        // whether more batches would fit
+        budget.record(1, TARGET / 10);
+        budget.record(2, TARGET * 9 / 10);
+        assert_eq!(budget.batches(), 2);
+
+        budget.record(2, TARGET / 2);
+        assert_eq!(budget.batches(), 3);
+        budget.record(3, TARGET / 2);
+        budget.record(4, TARGET / 2);
+        assert_eq!(budget.batches(), 4, "The budget is capped at the maximum");
+    }
+}
@@ -1,7 +1,16 @@
-use std::{collections::BTreeSet, sync::Arc};
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
-use miden_node_utils::formatting::{format_array, format_blake3_digest};
+use miden_node_proto::generated::block as proto;
+use miden_node_utils::{
+    block_versions::BlockVersions,
+    formatting::{format_array, format_blake3_digest},
+    runtime::BlockingPool,
+};
 use miden_objects::{
     accounts::AccountId,
     block::Block,
@@ -13,6 +22,8 @@ use tracing::{debug, info, instrument};
 use crate::{
     batch_builder::batch::TransactionBatch,
     errors::BuildBlockError,
+    events::{TransactionEvents, TransactionStatus},
+    proof_time::SimulatedProving,
     store::{ApplyBlock, Store},
     COMPONENT,
 };
@@ -37,11 +48,49 @@ pub trait BlockBuilder: Send + Sync + 'static {
     async fn build_block(&self, batches: &[TransactionBatch]) -> Result<(), BuildBlockError>;
 }
 
+// BLOCK STAGE DURATIONS
+// =================================================================================================
+
+/// Durations of the stages of building a block, reported to the store alongside the block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockStageDurations {
+    /// Fetching the block inputs from the store.
+    pub inputs_fetch: Duration,
+    /// Computing the block witness and assembling the block.
+    pub propose: Duration,
+    /// Proving the block, including any simulated proving time.
+    pub prove: Duration,
+    /// Time from the start of the block building until the block is sent to the store.
+    pub build: Duration,
+}
+
+impl From<BlockStageDurations> for proto::BlockStageDurations {
+    fn from(durations: BlockStageDurations) -> Self {
+        let micros = |duration: Duration| u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+
+        Self {
+            inputs_fetch_us: micros(durations.inputs_fetch),
+            propose_us: micros(durations.propose),
+            prove_us: micros(durations.prove),
+            build_us: micros(durations.build),
+        }
+    }
+}
+
+// DEFAULT BLOCK BUILDER
+// =================================================================================================
+
 #[derive(Debug)]
 pub struct DefaultBlockBuilder<S, A> {
     store: Arc<S>,
     state_view: Arc<A>,
-    block_kernel: BlockProver,
+    block_kernel: Arc<BlockProver>,
+    /// Pool the blocks are proven on
+    proving_pool: BlockingPool,
+    /// Additional delay applied to every block to simulate proving
+    simulated_proving: Option<SimulatedProving>,
+    /// Status changes of the transactions, published once they are committed
+    events: Arc<TransactionEvents>,
 }
 
 impl<S, A> DefaultBlockBuilder<S, A>
@@ -53,9 +102,37 @@ where
         Self {
             store,
             state_view,
-            block_kernel: BlockProver::new(),
+            block_kernel: Arc::new(BlockProver::new()),
+            proving_pool: BlockingPool::inline(),
+            simulated_proving: None,
+            events: Default::default(),
         }
     }
+
+    /// Delays every block by a duration sampled by `simulated_proving`, to simulate proving.
+    pub fn with_simulated_proving(mut self, simulated_proving: SimulatedProving) -> Self {
+        self.simulated_proving = Some(simulated_proving);
+        self
+    }
+
+    /// Publishes the status changes of the committed transactions to `events`.
+    pub fn with_events(mut self, events: Arc<TransactionEvents>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Stamps the blocks with the versions supported at their height, see [BlockVersions].
+    pub fn with_block_versions(mut self, block_versions: BlockVersions) -> Self {
+        let block_kernel = Arc::unwrap_or_clone(self.block_kernel);
+        self.block_kernel = Arc::new(block_kernel.with_block_versions(block_versions));
+        self
+    }
+
+    /// Proves the blocks on `proving_pool`, instead of the task building them.
+    pub fn with_proving_pool(mut self, proving_pool: BlockingPool) -> Self {
+        self.proving_pool = proving_pool;
+        self
+    }
 }
 
 #[async_trait]
@@ -64,8 +141,14 @@ where
     S: Store,
     A: ApplyBlock,
 {
-    #[instrument(target = "miden-block-producer", skip_all, err)]
+    #[instrument(
+        target = "miden-block-producer",
+        skip_all,
+        err,
+        fields(proof_time_distribution, simulated_proof_time_ms)
+    )]
     async fn build_block(&self, batches: &[TransactionBatch]) -> Result<(), BuildBlockError> {
+        let started_at = Instant::now();
         info!(
             target: COMPONENT,
             num_batches = batches.len(),
@@ -101,6 +184,7 @@ where
             .collect();
 
         // Request information needed for block building from the store
+        let inputs_fetch_started_at = Instant::now();
         let block_inputs = self
             .store
             .get_block_inputs(
@@ -109,6 +193,7 @@ where
                 dangling_notes.iter(),
             )
             .await?;
+        let inputs_fetch = inputs_fetch_started_at.elapsed();
 
         let missing_notes: Vec<_> = dangling_notes
             .difference(&block_inputs.found_unauthenticated_notes.note_ids())
@@ -120,7 +205,14 @@ where
 
         let (block_header_witness, updated_accounts) = BlockWitness::new(block_inputs, batches)?;
 
-        let new_block_header = self.block_kernel.prove(block_header_witness)?;
+        let prove_started_at = Instant::now();
+        let block_kernel = Arc::clone(&self.block_kernel);
+        let new_block_header =
+            self.proving_pool.run(move || block_kernel.prove(block_header_witness)).await?;
+        if let Some(simulated_proving) = &self.simulated_proving {
+            simulated_proving.prove().await;
+        }
+        let prove = prove_started_at.elapsed();
         let block_num = new_block_header.block_num();
 
         // TODO: return an error?
@@ -130,12 +222,33 @@ where
 
         let block_hash = block.hash();
 
-        info!(target: COMPONENT, block_num, %block_hash, "block built");
+        // Everything but fetching the inputs and proving is attributed to proposing the block
+        let build = started_at.elapsed();
+        let stage_durations = BlockStageDurations {
+            inputs_fetch,
+            propose: build.saturating_sub(inputs_fetch + prove),
+            prove,
+            build,
+        };
+
+        info!(
+            target: COMPONENT,
+            block_num,
+            %block_hash,
+            ?inputs_fetch,
+            ?prove,
+            ?build,
+            "block built"
+        );
         debug!(target: COMPONENT, ?block);
 
-        self.state_view.apply_block(&block).await?;
+        self.state_view.apply_block(&block, Some(stage_durations)).await?;
 
         info!(target: COMPONENT, block_num, %block_hash, "block committed");
+        self.events.publish(
+            batches.iter().flat_map(TransactionBatch::transaction_ids),
+            &TransactionStatus::Committed { block_num, block_commitment: block_hash },
+        );
 
         Ok(())
     }
@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, BTreeSet};
 
+use miden_node_utils::block_num::BlockNumberExt;
 use miden_objects::{
     accounts::{delta::AccountUpdateDetails, AccountId},
     block::BlockAccountUpdate,
@@ -39,6 +40,10 @@ impl BlockWitness {
         if batches.len() > MAX_BATCHES_PER_BLOCK {
             return Err(BuildBlockError::TooManyBatchesInBlock(batches.len()));
         }
+        let prev_block_num = block_inputs.block_header.block_num();
+        if prev_block_num.child().is_none() {
+            return Err(BuildBlockError::LastBlockReached(prev_block_num));
+        }
         Self::validate_nullifiers(&block_inputs, batches)?;
 
         let batch_created_notes_roots = batches
@@ -132,6 +137,14 @@ impl BlockWitness {
         ))
     }
 
+    /// Returns the number of the block built from this witness.
+    pub(super) fn block_num(&self) -> u32 {
+        self.prev_header
+            .block_num()
+            .child()
+            .expect("the previous block number was checked when creating the witness")
+    }
+
     /// Converts [`BlockWitness`] into inputs to the block kernel program
     pub(super) fn into_program_inputs(
         self,
@@ -242,7 +255,7 @@ impl BlockWitness {
                 advice_stack.extend(self.prev_header.nullifier_root());
 
                 // append nullifier value (`[block_num, 0, 0, 0]`)
-                let block_num = self.prev_header.block_num() + 1;
+                let block_num = self.block_num();
                 advice_stack.extend([block_num.into(), ZERO, ZERO, ZERO]);
 
                 for nullifier in self.produced_nullifiers.keys() {
@@ -1,6 +1,7 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use miden_lib::transaction::TransactionKernel;
+use miden_node_utils::block_versions::BlockVersions;
 use miden_objects::{assembly::Assembler, block::compute_tx_hash, BlockHeader, Digest};
 use miden_processor::{execute, DefaultHost, ExecutionOptions, MemAdviceProvider, Program};
 use miden_stdlib::StdLibrary;
@@ -27,9 +28,11 @@ mod tests;
 
 const BLOCK_KERNEL_MASM: &str = include_str!("asm/block_kernel.masm");
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct BlockProver {
     kernel: Program,
+    /// Versions of the proven blocks at every height
+    block_versions: BlockVersions,
 }
 
 impl BlockProver {
@@ -44,25 +47,38 @@ impl BlockProver {
                 .expect("failed to load account update program")
         };
 
-        Self { kernel: account_program }
+        Self {
+            kernel: account_program,
+            block_versions: BlockVersions::default(),
+        }
+    }
+
+    /// Sets the versions of the blocks proven from now on.
+    pub fn with_block_versions(mut self, block_versions: BlockVersions) -> Self {
+        self.block_versions = block_versions;
+        self
     }
 
     // Note: this will eventually all be done in the VM, and also return an `ExecutionProof`
     pub fn prove(&self, witness: BlockWitness) -> Result<BlockHeader, BuildBlockError> {
         let prev_hash = witness.prev_header.hash();
-        let block_num = witness.prev_header.block_num() + 1;
-        let version = witness.prev_header.version();
+        let block_num = witness.block_num();
+        let version = self.block_versions.next_version(block_num, witness.prev_header.version())?;
+        let prev_timestamp = witness.prev_header.timestamp();
 
         let tx_hash = compute_tx_hash(witness.transactions());
         let (account_root, note_root, nullifier_root, chain_root) = self.compute_roots(witness)?;
 
         let proof_hash = Digest::default();
-        let timestamp = SystemTime::now()
+        let now: u32 = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("today is expected to be after 1970")
             .as_secs()
             .try_into()
             .expect("timestamp must fit in a `u32`");
+        // The store requires block timestamps to increase strictly, even for blocks built within
+        // the same second
+        let timestamp = now.max(prev_timestamp + 1);
 
         Ok(BlockHeader::new(
             version,
@@ -1,8 +1,11 @@
 // block builder tests (higher level)
 // `apply_block()` is called
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use miden_node_utils::block_versions::{
+    BlockVersionRange, BlockVersionUpgrade, BlockVersions, UnsupportedBlockVersion,
+};
 use miden_objects::{
     accounts::{account_id::testing::ACCOUNT_ID_OFF_CHAIN_SENDER, AccountId},
     Digest, Felt,
@@ -11,7 +14,8 @@ use miden_objects::{
 use crate::{
     batch_builder::TransactionBatch,
     block_builder::{BlockBuilder, BuildBlockError, DefaultBlockBuilder},
-    test_utils::{MockProvenTxBuilder, MockStoreFailure, MockStoreSuccessBuilder},
+    proof_time::{ProofTimeDistribution, ProofTimeSampler, SimulatedProving},
+    test_utils::{FaultyStore, MockProvenTxBuilder, MockStoreSuccessBuilder, StoreCall},
 };
 
 /// Tests that `build_block()` succeeds when the transaction batches are not empty
@@ -71,12 +75,140 @@ async fn test_apply_block_called_empty_batches() {
 #[tokio::test]
 #[miden_node_test_macro::enable_logging]
 async fn test_build_block_failure() {
-    let store = Arc::new(MockStoreFailure);
+    let store = Arc::new(FaultyStore::new(
+        MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build(),
+    ));
+    store.fail_always(StoreCall::GetBlockInputs);
 
     let block_builder = DefaultBlockBuilder::new(store.clone(), store.clone());
 
     let result = block_builder.build_block(&Vec::new()).await;
 
-    // Ensure that the store's `apply_block()` was called
+    // Ensure that the store's `apply_block()` was not called
     assert!(matches!(result, Err(BuildBlockError::GetBlockInputsFailed(_))));
+    assert_eq!(store.calls(), [StoreCall::GetBlockInputs]);
+}
+
+/// Tests that `build_block()` fails when `apply_block()` fails, and succeeds once the store
+/// recovers
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_apply_block_failure() {
+    let store = Arc::new(FaultyStore::new(
+        MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build(),
+    ));
+    store.fail_next(StoreCall::ApplyBlock, 1);
+
+    let block_builder = DefaultBlockBuilder::new(store.clone(), store.clone());
+
+    let result = block_builder.build_block(&Vec::new()).await;
+    assert!(matches!(result, Err(BuildBlockError::ApplyBlockFailed(_))));
+    assert_eq!(*store.inner().num_apply_block_called.read().await, 0);
+
+    block_builder.build_block(&Vec::new()).await.unwrap();
+    assert_eq!(*store.inner().num_apply_block_called.read().await, 1);
+
+    assert_eq!(
+        store.calls(),
+        [
+            StoreCall::GetBlockInputs,
+            StoreCall::ApplyBlock,
+            StoreCall::GetBlockInputs,
+            StoreCall::ApplyBlock
+        ]
+    );
+}
+
+/// Tests that the latency of the store is attributed to fetching the block inputs
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_store_latency_attributed_to_inputs_fetch() {
+    let store = Arc::new(FaultyStore::new(
+        MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build(),
+    ));
+    store.delay(StoreCall::GetBlockInputs, Duration::from_millis(20));
+
+    let block_builder = DefaultBlockBuilder::new(store.clone(), store.clone());
+
+    block_builder.build_block(&Vec::new()).await.unwrap();
+
+    let applied_stage_durations = store.inner().applied_stage_durations.read().await;
+    let durations = applied_stage_durations[0].expect("stage durations should be reported");
+    assert!(durations.inputs_fetch >= Duration::from_millis(20));
+}
+
+/// Tests that the durations of the stages of building a block are reported to the store
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_stage_durations_reported_to_store() {
+    let account_id = AccountId::new_unchecked(Felt::new(ACCOUNT_ID_OFF_CHAIN_SENDER));
+    let account_hash: Digest =
+        [Felt::new(1u64), Felt::new(1u64), Felt::new(1u64), Felt::new(1u64)].into();
+    let store = Arc::new(
+        MockStoreSuccessBuilder::from_accounts(std::iter::once((account_id, account_hash))).build(),
+    );
+
+    let simulated_proving = SimulatedProving::new(
+        ProofTimeSampler::new(ProofTimeDistribution::Constant { value_ms: 20 }, 0).unwrap(),
+    );
+    let block_builder = DefaultBlockBuilder::new(store.clone(), store.clone())
+        .with_simulated_proving(simulated_proving);
+
+    for _ in 0..3 {
+        block_builder.build_block(&Vec::new()).await.unwrap();
+    }
+
+    let applied_stage_durations = store.applied_stage_durations.read().await;
+    assert_eq!(applied_stage_durations.len(), 3);
+    for durations in applied_stage_durations.iter() {
+        let durations = durations.expect("stage durations should be reported");
+
+        assert!(durations.inputs_fetch > Duration::ZERO);
+        assert!(durations.propose > Duration::ZERO);
+        assert!(durations.prove >= Duration::from_millis(20));
+        assert_eq!(durations.inputs_fetch + durations.propose + durations.prove, durations.build);
+    }
+}
+
+/// Tests that the produced blocks switch to the upgraded version at the activation block, and
+/// that no block is built on top of a block whose version isn't supported
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_block_version_upgrade() {
+    let store = Arc::new(MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build());
+    let block_versions = BlockVersions {
+        supported: BlockVersionRange { min: 0, max: 1 },
+        upgrade: Some(BlockVersionUpgrade {
+            activation_block: 3,
+            supported: BlockVersionRange { min: 2, max: 2 },
+        }),
+    };
+    let block_builder =
+        DefaultBlockBuilder::new(store.clone(), store.clone()).with_block_versions(block_versions);
+
+    for _ in 0..3 {
+        block_builder.build_block(&Vec::new()).await.unwrap();
+    }
+
+    let versions: Vec<_> = store
+        .block_headers
+        .read()
+        .await
+        .values()
+        .map(|header| (header.block_num(), header.version()))
+        .collect();
+    assert_eq!(versions, [(1, 0), (2, 0), (3, 2), (4, 2)]);
+
+    // A block producer unaware of the upgrade refuses to build on top of the upgraded chain
+    let block_builder = DefaultBlockBuilder::new(store.clone(), store.clone());
+    let err = block_builder.build_block(&Vec::new()).await.unwrap_err();
+    assert_eq!(
+        err,
+        BuildBlockError::UnsupportedBlockVersion(UnsupportedBlockVersion {
+            block_num: 5,
+            version: 2,
+            supported: BlockVersionRange::default(),
+        })
+    );
+    assert_eq!(*store.num_apply_block_called.read().await, 3);
 }
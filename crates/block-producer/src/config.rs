@@ -1,7 +1,21 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    time::Duration,
+};
 
-use miden_node_utils::config::{Endpoint, DEFAULT_BLOCK_PRODUCER_PORT, DEFAULT_STORE_PORT};
-use serde::{Deserialize, Serialize};
+use miden_node_utils::{
+    block_versions::BlockVersions,
+    config::{Endpoint, CONFIG_VERSION, DEFAULT_BLOCK_PRODUCER_PORT, DEFAULT_STORE_PORT},
+    message_limits::MessageLimits,
+};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+
+pub use crate::proof_time::{ProofTimeDistribution, Sigma};
+use crate::{
+    proof_time::{ProofTimeSampler, SimulatedProving},
+    ticker::TickJitter,
+    MAX_EXPIRATION_SLACK, SERVER_BLOCK_FREQUENCY, SERVER_TRANSACTION_WATCH_TIMEOUT,
+};
 
 // Main config
 // ================================================================================================
@@ -10,6 +24,12 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct BlockProducerConfig {
+    /// Version of the layout of a standalone block producer configuration file, see
+    /// [CONFIG_VERSION]. Within the node configuration file, the version of the node configuration
+    /// applies.
+    #[serde(default, skip_serializing)]
+    pub config_version: u32,
+
     pub endpoint: Endpoint,
 
     /// Store gRPC endpoint in the format `http://<host>[:<port>]`.
@@ -22,19 +42,195 @@ pub struct BlockProducerConfig {
     /// verification may take ~15ms/proof. This is OK when all transactions are forwarded to the
     /// block producer from the RPC component as transaction proofs are also verified there.
     pub verify_tx_proofs: bool,
+
+    /// Maximum random offset of every block and batch building tick from its regular schedule, as
+    /// a percentage of the respective interval. Capped at 50.
+    ///
+    /// Useful to avoid synchronized load spikes when several nodes share the same hosts.
+    #[serde(default)]
+    pub interval_jitter_percent: u8,
+
+    /// Fixed delay of the block and batch building schedules, in milliseconds.
+    #[serde(default)]
+    pub interval_phase_offset_ms: u64,
+
+    /// Minimum number of transactions of a batch, as a percentage of the maximum batch size.
+    /// Capped at 100.
+    ///
+    /// Avoids proving batches of a single transaction at low traffic. Smaller batches are built
+    /// once their oldest transaction waited for `max_batch_wait_ms`.
+    #[serde(default)]
+    pub min_batch_fill_percent: u8,
+
+    /// Maximum time a transaction waits for a batch to reach the minimum fill, in milliseconds.
+    /// Defaults to the block interval.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_batch_wait_ms: Option<u64>,
+
+    /// Target time to build, prove and apply a block, as a percentage of the block interval.
+    /// Capped at 100.
+    ///
+    /// Once set, the number of batches of every block adapts to the durations of the recent
+    /// blocks, so that blocks keep up with the block interval when proving slows down. Blocks
+    /// include up to the maximum number of batches if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_build_target_percent: Option<u8>,
+
+    /// Number of blocks a transaction must remain valid for after the earliest block its batch
+    /// could be included in. At most 16.
+    ///
+    /// Transactions expiring earlier are dropped instead of being batched, leaving a margin for
+    /// blocks which take longer than expected to be built and proven. This trades inclusions for
+    /// proving time: a non-zero slack also drops transactions which the next blocks could still
+    /// have included, while without slack only the transactions which can't be included are.
+    #[serde(default, deserialize_with = "deserialize_expiration_slack")]
+    pub expiration_slack: u32,
+
+    /// Identifier of this block producer, reported to the store along with every block.
+    ///
+    /// Only informational, a random identifier is generated at startup if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub producer_id: Option<String>,
+
+    /// Distribution of an additional delay applied to every block, to simulate proving times.
+    ///
+    /// Intended for capacity simulations on test networks, no delay is applied if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulated_block_proof_time: Option<ProofTimeDistribution>,
+
+    /// Distribution of an additional delay applied to every batch, to simulate proving times.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulated_batch_proof_time: Option<ProofTimeDistribution>,
+
+    /// Seed of the simulated proof times and of the jitter of the block and batch building
+    /// schedules. A random seed is used if not set, and logged at startup so that the proof times
+    /// and schedules of any run can be reproduced. Formerly `simulated_proof_time_seed`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        alias = "simulated_proof_time_seed"
+    )]
+    pub simulation_seed: Option<u64>,
+
+    /// Maximum time for which `SubmitAndWatchProvenTransaction` follows a transaction, in
+    /// milliseconds. Defaults to 60 seconds.
+    ///
+    /// The stream is closed with a `still_pending` status once it elapses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_watch_timeout_ms: Option<u64>,
+
+    /// Versions of the produced blocks, and their scheduled upgrade, see [BlockVersions]. Must
+    /// match the versions configured for the store.
+    #[serde(default)]
+    pub block_versions: BlockVersions,
+
+    /// Size limits of the gRPC messages of the block producer and of the notes of the submitted
+    /// transactions, see [MessageLimits].
+    #[serde(default)]
+    pub message_limits: MessageLimits,
+
+    /// Enables the debugging endpoints, e.g. `DumpMempoolGraph`.
+    ///
+    /// They expose the in-flight transactions and are expensive on large queues, so they are
+    /// disabled by default and only meant for development and incident investigation.
+    #[serde(default)]
+    pub debug_endpoints: bool,
+
+    /// Expected commitment of the genesis block of the store, as a hex string.
+    ///
+    /// The block producer refuses to start if the genesis block of its store differs, e.g. if it
+    /// is pointed at the store of another network. Not checked if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis_commitment: Option<String>,
 }
 
 impl BlockProducerConfig {
     pub fn endpoint_url(&self) -> String {
         self.endpoint.to_string()
     }
+
+    /// Returns the randomization to apply to the block and batch building schedules
+    /// respectively, drawing the offsets from `seed`.
+    pub(crate) fn tick_jitters(&self, seed: u64) -> (TickJitter, TickJitter) {
+        // Distinct from the streams of the simulated proof times, see `simulated_proving`
+        let tick_jitter = |seed| TickJitter {
+            percent: self.interval_jitter_percent,
+            phase_offset: Duration::from_millis(self.interval_phase_offset_ms),
+            seed,
+        };
+
+        (tick_jitter(seed.wrapping_add(2)), tick_jitter(seed.wrapping_add(3)))
+    }
+
+    /// Returns the minimum number of transactions of a batch of at most `batch_size` transactions.
+    pub(crate) fn min_batch_size(&self, batch_size: usize) -> usize {
+        let percent = usize::from(self.min_batch_fill_percent.min(100));
+        (batch_size * percent).div_ceil(100)
+    }
+
+    /// Returns the maximum time a transaction waits for a batch to reach the minimum fill.
+    pub(crate) fn max_batch_wait(&self) -> Duration {
+        self.max_batch_wait_ms.map_or(SERVER_BLOCK_FREQUENCY, Duration::from_millis)
+    }
+
+    /// Returns the target time to build a block, if the number of batches per block is adaptive.
+    pub(crate) fn block_build_target(&self) -> Option<Duration> {
+        self.block_build_target_percent
+            .map(|percent| SERVER_BLOCK_FREQUENCY * u32::from(percent.min(100)) / 100)
+    }
+
+    /// Returns the maximum time for which a submitted transaction is watched.
+    pub(crate) fn transaction_watch_timeout(&self) -> Duration {
+        self.transaction_watch_timeout_ms
+            .map_or(SERVER_TRANSACTION_WATCH_TIMEOUT, Duration::from_millis)
+    }
+
+    /// Returns the simulated proving of blocks and batches respectively, if configured, drawing
+    /// the proof times from `seed`.
+    pub(crate) fn simulated_proving(
+        &self,
+        seed: u64,
+    ) -> std::io::Result<(Option<SimulatedProving>, Option<SimulatedProving>)> {
+        // Blocks and batches draw from distinct streams of the same seed
+        let block_seed = seed;
+        let batch_seed = seed.wrapping_add(1);
+
+        let simulated_proving = |distribution: &Option<ProofTimeDistribution>, seed| {
+            distribution
+                .clone()
+                .map(|distribution| ProofTimeSampler::new(distribution, seed))
+                .transpose()
+                .map(|sampler| sampler.map(SimulatedProving::new))
+        };
+
+        Ok((
+            simulated_proving(&self.simulated_block_proof_time, block_seed)?,
+            simulated_proving(&self.simulated_batch_proof_time, batch_seed)?,
+        ))
+    }
 }
 
 impl Display for BlockProducerConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "{{ endpoint: \"{}\", store_url: \"{}\" }}",
-            self.endpoint, self.store_url
+            "{{ endpoint: \"{}\", store_url: \"{}\", interval_jitter_percent: {}, interval_phase_offset_ms: {}, min_batch_fill_percent: {}, max_batch_wait_ms: {:?}, block_build_target_percent: {:?}, expiration_slack: {}, producer_id: {:?}, simulated_block_proof_time: {:?}, simulated_batch_proof_time: {:?}, simulation_seed: {:?}, transaction_watch_timeout_ms: {:?}, block_versions: {}, message_limits: {:?}, debug_endpoints: {}, genesis_commitment: {:?} }}",
+            self.endpoint,
+            self.store_url,
+            self.interval_jitter_percent,
+            self.interval_phase_offset_ms,
+            self.min_batch_fill_percent,
+            self.max_batch_wait_ms,
+            self.block_build_target_percent,
+            self.expiration_slack,
+            self.producer_id,
+            self.simulated_block_proof_time,
+            self.simulated_batch_proof_time,
+            self.simulation_seed,
+            self.transaction_watch_timeout_ms,
+            self.block_versions,
+            self.message_limits,
+            self.debug_endpoints,
+            self.genesis_commitment
         ))
     }
 }
@@ -42,9 +238,44 @@ impl Display for BlockProducerConfig {
 impl Default for BlockProducerConfig {
     fn default() -> Self {
         Self {
+            config_version: CONFIG_VERSION,
             endpoint: Endpoint::localhost(DEFAULT_BLOCK_PRODUCER_PORT),
             store_url: Endpoint::localhost(DEFAULT_STORE_PORT).to_string(),
             verify_tx_proofs: true,
+            interval_jitter_percent: 0,
+            interval_phase_offset_ms: 0,
+            min_batch_fill_percent: 0,
+            max_batch_wait_ms: None,
+            block_build_target_percent: None,
+            expiration_slack: 0,
+            producer_id: None,
+            simulated_block_proof_time: None,
+            simulated_batch_proof_time: None,
+            simulation_seed: None,
+            transaction_watch_timeout_ms: None,
+            block_versions: BlockVersions::default(),
+            message_limits: MessageLimits::default(),
+            debug_endpoints: false,
+            genesis_commitment: None,
         }
     }
 }
+
+// HELPERS
+// ================================================================================================
+
+/// Deserializes an expiration slack, rejecting slacks above 16 blocks.
+///
+/// Shared with the configurations embedding the block producer configuration.
+pub fn deserialize_expiration_slack<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u32, D::Error> {
+    let expiration_slack = u32::deserialize(deserializer)?;
+    if expiration_slack > MAX_EXPIRATION_SLACK {
+        return Err(D::Error::custom(format!(
+            "expiration slack must be at most {MAX_EXPIRATION_SLACK} blocks, got {expiration_slack}"
+        )));
+    }
+
+    Ok(expiration_slack)
+}
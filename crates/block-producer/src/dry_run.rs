@@ -0,0 +1,362 @@
+//! Dry runs of the block production pipeline.
+//!
+//! A dry run verifies a list of proven transactions against the state of a store, groups them
+//! into batches, and builds and proves a block from the batches, exactly like the block producer
+//! does. The block is never applied to the store, so the same transactions can be run repeatedly
+//! against the same data to validate changes to the pipeline and compare their performance.
+//!
+//! Since the block is not applied, the transactions must all fit in a single block.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use miden_node_proto::generated::store::api_client as store_client;
+use miden_node_utils::{
+    formatting::{format_array, format_blake3_digest},
+    request_id::connect_with_request_ids,
+};
+use miden_objects::{
+    block::Block,
+    notes::NoteId,
+    transaction::{OutputNote, TransactionId},
+    Digest,
+};
+use thiserror::Error;
+use tracing::info;
+
+pub use crate::block_builder::BlockStageDurations;
+use crate::{
+    batch_builder::batch::{BatchId, TransactionBatch},
+    block_builder::{BlockBuilder, DefaultBlockBuilder},
+    errors::{ApplyBlockError, BuildBatchError, BuildBlockError, VerifyTxError},
+    state_view::DefaultStateView,
+    store::{ApplyBlock, DefaultStore, Store},
+    txqueue::TransactionValidator,
+    ProvenTransaction, COMPONENT, SERVER_BATCH_SIZE, SERVER_MAX_BATCHES_PER_BLOCK,
+};
+
+// DRY RUN OPTIONS
+// ================================================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DryRunOptions {
+    /// Number of transactions per batch. The transactions are batched in the given order.
+    pub batch_size: usize,
+    /// Maximum number of batches in the block.
+    pub max_batches_per_block: usize,
+    /// Enables the verification of the transaction proofs.
+    pub verify_tx_proofs: bool,
+}
+
+impl Default for DryRunOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: SERVER_BATCH_SIZE,
+            max_batches_per_block: SERVER_MAX_BATCHES_PER_BLOCK,
+            verify_tx_proofs: true,
+        }
+    }
+}
+
+// DRY RUN REPORT
+// ================================================================================================
+
+/// Outcome of a dry run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunReport {
+    /// Time spent verifying the transactions against the store.
+    pub verify_txs: Duration,
+    pub batches: Vec<BatchReport>,
+    pub block: BlockReport,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchReport {
+    pub id: BatchId,
+    pub num_txs: usize,
+    /// Time spent building the batch, including fetching the unauthenticated notes.
+    pub build: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockReport {
+    pub block_num: u32,
+    pub block_hash: Digest,
+    pub stage_durations: BlockStageDurations,
+}
+
+// DRY RUN ERROR
+// ================================================================================================
+
+#[derive(Debug, Error)]
+pub enum DryRunError {
+    #[error("Failed to connect to the store: {0}")]
+    StoreConnectionFailed(#[from] tonic::transport::Error),
+    #[error("Too many batches for a single block: {num_batches}, limit: {limit}")]
+    TooManyBatches { num_batches: usize, limit: usize },
+    #[error("Transaction {tx_id} failed verification: {source}")]
+    VerifyTxFailed {
+        tx_id: TransactionId,
+        source: VerifyTxError,
+    },
+    #[error("Failed to build a batch of transactions {}: {source}", format_array(.tx_ids.iter().map(TransactionId::to_hex)))]
+    BuildBatchFailed {
+        tx_ids: Vec<TransactionId>,
+        source: BuildBatchError,
+    },
+    #[error(
+        "Failed to build a block of batches {}: {source}",
+        format_array(.batch_ids.iter().copied().map(format_blake3_digest))
+    )]
+    BuildBlockFailed {
+        batch_ids: Vec<BatchId>,
+        source: BuildBlockError,
+    },
+}
+
+// DRY RUN
+// ================================================================================================
+
+/// Runs the transactions through the block production pipeline, against the store at
+/// `store_url`, without applying the resulting block.
+pub async fn dry_run(
+    store_url: String,
+    txs: Vec<ProvenTransaction>,
+    options: DryRunOptions,
+) -> Result<DryRunReport, DryRunError> {
+    let store = store_client::ApiClient::new(connect_with_request_ids(store_url).await?);
+    let store = Arc::new(DefaultStore::new(store));
+
+    run(store, txs, options).await
+}
+
+async fn run<S: Store>(
+    store: Arc<S>,
+    txs: Vec<ProvenTransaction>,
+    options: DryRunOptions,
+) -> Result<DryRunReport, DryRunError> {
+    let num_batches = txs.len().div_ceil(options.batch_size.max(1));
+    if num_batches > options.max_batches_per_block {
+        return Err(DryRunError::TooManyBatches {
+            num_batches,
+            limit: options.max_batches_per_block,
+        });
+    }
+
+    info!(target: COMPONENT, num_txs = txs.len(), num_batches, "Starting dry run");
+
+    // Verify the transactions, tracking them as in-flight so that they can depend on each other
+    let started_at = Instant::now();
+    let state_view = DefaultStateView::new(Arc::clone(&store), options.verify_tx_proofs);
+    for tx in &txs {
+        state_view
+            .verify_tx(tx)
+            .await
+            .map_err(|source| DryRunError::VerifyTxFailed { tx_id: tx.id(), source })?;
+    }
+    let verify_txs = started_at.elapsed();
+
+    let mut batches = Vec::with_capacity(num_batches);
+    let mut batch_reports = Vec::with_capacity(num_batches);
+    for txs in txs.chunks(options.batch_size.max(1)) {
+        let started_at = Instant::now();
+        let batch = build_batch(&*store, &batches, txs.to_vec()).await.map_err(|source| {
+            DryRunError::BuildBatchFailed {
+                tx_ids: txs.iter().map(ProvenTransaction::id).collect(),
+                source,
+            }
+        })?;
+
+        batch_reports.push(BatchReport {
+            id: batch.id(),
+            num_txs: txs.len(),
+            build: started_at.elapsed(),
+        });
+        batches.push(batch);
+    }
+
+    // The block builder hands the block over to the recorder instead of the store
+    let recorder = Arc::new(BlockRecorder::default());
+    DefaultBlockBuilder::new(store, Arc::clone(&recorder))
+        .build_block(&batches)
+        .await
+        .map_err(|source| DryRunError::BuildBlockFailed {
+            batch_ids: batches.iter().map(TransactionBatch::id).collect(),
+            source,
+        })?;
+    let block = recorder.block.lock().expect("lock poisoned").expect("block was built");
+
+    info!(
+        target: COMPONENT,
+        block_num = block.block_num,
+        block_hash = %block.block_hash,
+        "Dry run completed"
+    );
+
+    Ok(DryRunReport {
+        verify_txs,
+        batches: batch_reports,
+        block,
+    })
+}
+
+/// Builds a batch of the `txs`, whose unauthenticated notes are either created by the previous
+/// `batches` or by the `txs` themselves, or authenticated by the store.
+async fn build_batch<S: Store>(
+    store: &S,
+    batches: &[TransactionBatch],
+    txs: Vec<ProvenTransaction>,
+) -> Result<TransactionBatch, BuildBatchError> {
+    let output_notes: Vec<NoteId> = batches
+        .iter()
+        .flat_map(|batch| batch.output_notes().iter().map(OutputNote::id))
+        .chain(txs.iter().flat_map(|tx| tx.output_notes().iter().map(OutputNote::id)))
+        .collect();
+    let dangling_notes: Vec<NoteId> = txs
+        .iter()
+        .flat_map(|tx| tx.get_unauthenticated_notes().map(|note| note.id()))
+        .filter(|note_id| !output_notes.contains(note_id))
+        .collect();
+
+    let found_unauthenticated_notes = if dangling_notes.is_empty() {
+        Default::default()
+    } else {
+        match store.get_note_authentication_info(dangling_notes.iter()).await {
+            Ok(stored_notes) => stored_notes,
+            Err(err) => return Err(BuildBatchError::NotePathsError(err, txs)),
+        }
+    };
+
+    TransactionBatch::new(txs, found_unauthenticated_notes)
+}
+
+/// Records the block built by the block builder instead of applying it.
+#[derive(Debug, Default)]
+struct BlockRecorder {
+    block: Mutex<Option<BlockReport>>,
+}
+
+#[async_trait]
+impl ApplyBlock for BlockRecorder {
+    async fn apply_block(
+        &self,
+        block: &Block,
+        stage_durations: Option<BlockStageDurations>,
+    ) -> Result<(), ApplyBlockError> {
+        *self.block.lock().expect("lock poisoned") = Some(BlockReport {
+            block_num: block.header().block_num(),
+            block_hash: block.hash(),
+            stage_durations: stage_durations.unwrap_or_default(),
+        });
+
+        Ok(())
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{Felt, ZERO};
+
+    use super::*;
+    use crate::test_utils::{MockPrivateAccount, MockProvenTxBuilder, MockStoreSuccessBuilder};
+
+    #[tokio::test]
+    #[miden_node_test_macro::enable_logging]
+    async fn dry_run_builds_block_without_applying_it() {
+        let accounts: Vec<MockPrivateAccount<3>> =
+            (0..3).map(|index| MockPrivateAccount::from(index)).collect();
+        let store = Arc::new(
+            MockStoreSuccessBuilder::from_accounts(
+                accounts.iter().map(|account| (account.id, account.states[0])),
+            )
+            .build(),
+        );
+        let account_root = store.account_root().await;
+
+        // The second transaction of the first account depends on its first one
+        let txs = vec![
+            MockProvenTxBuilder::with_account(
+                accounts[0].id,
+                accounts[0].states[0],
+                accounts[0].states[1],
+            )
+            .build(),
+            MockProvenTxBuilder::with_account(
+                accounts[1].id,
+                accounts[1].states[0],
+                accounts[1].states[1],
+            )
+            .build(),
+            MockProvenTxBuilder::with_account(
+                accounts[0].id,
+                accounts[0].states[1],
+                accounts[0].states[2],
+            )
+            .build(),
+        ];
+        let options = DryRunOptions {
+            batch_size: 2,
+            max_batches_per_block: 4,
+            verify_tx_proofs: false,
+        };
+
+        let report = run(Arc::clone(&store), txs, options).await.unwrap();
+
+        assert_eq!(report.batches.iter().map(|batch| batch.num_txs).collect::<Vec<_>>(), [2, 1]);
+        assert!(report.block.stage_durations.build > Duration::ZERO);
+        assert_ne!(report.block.block_hash, Digest::default());
+
+        // Nothing was applied to the store
+        assert_eq!(*store.num_apply_block_called.read().await, 0);
+        assert_eq!(store.account_root().await, account_root);
+    }
+
+    #[tokio::test]
+    #[miden_node_test_macro::enable_logging]
+    async fn dry_run_reports_offending_transactions() {
+        let account = MockPrivateAccount::<3>::from(0);
+        let store = Arc::new(
+            MockStoreSuccessBuilder::from_accounts(std::iter::once((
+                account.id,
+                account.states[0],
+            )))
+            .build(),
+        );
+
+        // The transaction starts from an unknown account state
+        let unknown_state = [Felt::new(42), ZERO, ZERO, ZERO].into();
+        let tx =
+            MockProvenTxBuilder::with_account(account.id, unknown_state, account.states[1]).build();
+        let tx_id = tx.id();
+
+        let options = DryRunOptions {
+            verify_tx_proofs: false,
+            ..Default::default()
+        };
+        let err = run(store, vec![tx], options).await.unwrap_err();
+        assert!(
+            matches!(err, DryRunError::VerifyTxFailed { tx_id: failed, .. } if failed == tx_id),
+            "{err}"
+        );
+
+        // Transactions which don't fit in a block are rejected upfront
+        let options = DryRunOptions {
+            batch_size: 1,
+            max_batches_per_block: 1,
+            verify_tx_proofs: false,
+        };
+        let store = Arc::new(MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build());
+        let txs = vec![
+            MockProvenTxBuilder::with_account_index(0).build(),
+            MockProvenTxBuilder::with_account_index(1).build(),
+        ];
+        let err = run(store, txs, options).await.unwrap_err();
+        assert!(matches!(err, DryRunError::TooManyBatches { num_batches: 2, limit: 1 }));
+    }
+}
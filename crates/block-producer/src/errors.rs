@@ -1,5 +1,9 @@
-use miden_node_proto::errors::ConversionError;
-use miden_node_utils::formatting::format_opt;
+use miden_node_proto::errors::{ConversionError, ErrorChainHelper};
+use miden_node_utils::{
+    block_versions::UnsupportedBlockVersion,
+    formatting::{format_array, format_opt},
+    notes::NoteValidationError,
+};
 use miden_objects::{
     accounts::AccountId,
     crypto::merkle::{MerkleError, MmrError},
@@ -11,6 +15,8 @@ use miden_objects::{
 use miden_processor::ExecutionError;
 use thiserror::Error;
 
+use crate::COMPONENT;
+
 // Transaction verification errors
 // =================================================================================================
 
@@ -34,6 +40,13 @@ pub enum VerifyTxError {
         current_account_hash: Option<Digest>,
     },
 
+    /// The transaction's reference block is not part of the chain, e.g. because the transaction
+    /// was executed against another network
+    #[error(
+        "Transaction reference block {block_ref} is not part of the chain (chain tip: {chain_tip})"
+    )]
+    UnknownReferenceBlock { block_ref: Digest, chain_tip: u32 },
+
     /// Failed to retrieve transaction inputs from the store
     ///
     /// TODO: Make this an "internal error". Q: Should we have a single `InternalError` enum for
@@ -58,6 +71,57 @@ pub enum AddTransactionError {
     VerificationFailed(#[from] VerifyTxError),
 }
 
+impl From<AddTransactionError> for tonic::Status {
+    fn from(err: AddTransactionError) -> Self {
+        let status = match err {
+            // The transaction was built against an outdated account state, the submitter has to
+            // resync the account before retrying
+            AddTransactionError::VerificationFailed(
+                VerifyTxError::IncorrectAccountInitialHash { .. },
+            ) => tonic::Status::failed_precondition(format!("{err:?}")),
+
+            _ => tonic::Status::invalid_argument(format!("{err:?}")),
+        };
+
+        status.with_error_source(COMPONENT, &err)
+    }
+}
+
+// Transaction eviction errors
+// =================================================================================================
+
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum EvictTransactionError {
+    #[error("Transaction {} is neither queued nor waiting in a batch", .0.to_hex())]
+    NotFound(TransactionId),
+    #[error(
+        "Transaction {} has in-flight transactions depending on it, which are only evicted with \
+         cascade: {}",
+        .tx_id.to_hex(),
+        format_array(.dependents.iter().map(|tx_id| tx_id.to_hex()))
+    )]
+    HasDependents {
+        tx_id: TransactionId,
+        dependents: Vec<TransactionId>,
+    },
+    #[error("Transactions are being batched, retry once the batches are built")]
+    BatchInProgress,
+}
+
+impl From<EvictTransactionError> for tonic::Status {
+    fn from(err: EvictTransactionError) -> Self {
+        let status = match err {
+            EvictTransactionError::NotFound(_) => tonic::Status::not_found(err.to_string()),
+            EvictTransactionError::HasDependents { .. } => {
+                tonic::Status::failed_precondition(err.to_string())
+            },
+            EvictTransactionError::BatchInProgress => tonic::Status::unavailable(err.to_string()),
+        };
+
+        status.with_error_source(COMPONENT, &err)
+    }
+}
+
 // Batch building errors
 // =================================================================================================
 
@@ -99,6 +163,13 @@ pub enum BuildBatchError {
     #[error("Duplicated transaction output note ID in the batch: {0}")]
     DuplicateOutputNote(NoteId, Vec<ProvenTransaction>),
 
+    #[error("Invalid transaction output note {note_id}: {source}")]
+    InvalidOutputNote {
+        note_id: NoteId,
+        source: NoteValidationError,
+        txs: Vec<ProvenTransaction>,
+    },
+
     #[error("Unauthenticated transaction notes not found in the store: {0:?}")]
     UnauthenticatedNotesNotFound(Vec<NoteId>, Vec<ProvenTransaction>),
 
@@ -116,6 +187,35 @@ pub enum BuildBatchError {
         error: AccountDeltaError,
         txs: Vec<ProvenTransaction>,
     },
+
+    /// Some transactions would expire before the batch could be included in a block.
+    ///
+    /// Only the remaining, still viable, transactions are returned to the transaction queue.
+    #[error(
+        "Transactions {} would expire before the batch could be included in block {inclusion_block}, with an expiration slack of {expiration_slack} blocks",
+        format_array(.expired.iter().map(|tx| tx.id().to_hex()))
+    )]
+    TransactionsExpired {
+        inclusion_block: u32,
+        expiration_slack: u32,
+        expired: Vec<ProvenTransaction>,
+        txs: Vec<ProvenTransaction>,
+    },
+
+    /// Some transactions reference blocks which the store would no longer retain by the time the
+    /// batch could be included in a block.
+    ///
+    /// Only the remaining, still viable, transactions are returned to the transaction queue.
+    #[error(
+        "Transactions {} reference blocks older than the {retention_depth} blocks retained before block {inclusion_block}",
+        format_array(.stale.iter().map(|tx| tx.id().to_hex()))
+    )]
+    StaleReferenceBlock {
+        inclusion_block: u32,
+        retention_depth: u32,
+        stale: Vec<ProvenTransaction>,
+        txs: Vec<ProvenTransaction>,
+    },
 }
 
 impl BuildBatchError {
@@ -128,9 +228,12 @@ impl BuildBatchError {
             BuildBatchError::NotePathsError(_, txs) => txs,
             BuildBatchError::DuplicateUnauthenticatedNote(_, txs) => txs,
             BuildBatchError::DuplicateOutputNote(_, txs) => txs,
+            BuildBatchError::InvalidOutputNote { txs, .. } => txs,
             BuildBatchError::UnauthenticatedNotesNotFound(_, txs) => txs,
             BuildBatchError::NoteHashesMismatch { txs, .. } => txs,
             BuildBatchError::AccountUpdateError { txs, .. } => txs,
+            BuildBatchError::TransactionsExpired { txs, .. } => txs,
+            BuildBatchError::StaleReferenceBlock { txs, .. } => txs,
         }
     }
 }
@@ -160,6 +263,10 @@ pub enum BlockInputsError {
     MmrPeaksError(#[from] MmrError),
     #[error("gRPC client failed with error: {0}")]
     GrpcClientError(String),
+    #[error("pages of the block inputs are anchored to different blocks")]
+    InconsistentPages,
+    #[error("the store's snapshot of the block inputs expired before all pages were fetched")]
+    SnapshotExpired,
 }
 
 // Note paths errors
@@ -174,6 +281,45 @@ pub enum NotePathsError {
     GrpcClientError(String),
 }
 
+// Block header errors
+// =================================================================================================
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum BlockHeaderError {
+    #[error("failed to parse protobuf message: {0}")]
+    ConversionError(#[from] ConversionError),
+    #[error("gRPC client failed with error: {0}")]
+    GrpcClientError(String),
+}
+
+// Data availability errors
+// =================================================================================================
+
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum DataAvailabilityError {
+    #[error("gRPC client failed with error: {0}")]
+    GrpcClientError(String),
+}
+
+// Account state errors
+// =================================================================================================
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum AccountStateError {
+    #[error("failed to parse protobuf message: {0}")]
+    ConversionError(#[from] ConversionError),
+    #[error("gRPC client failed with error: {0}")]
+    GrpcClientError(String),
+}
+
+impl From<AccountStateError> for tonic::Status {
+    fn from(err: AccountStateError) -> Self {
+        tonic::Status::unavailable(err.to_string()).with_error_source(COMPONENT, &err)
+    }
+}
+
 // Block applying errors
 // =================================================================================================
 
@@ -206,6 +352,10 @@ pub enum BuildBlockError {
     UnauthenticatedNotesNotFound(Vec<NoteId>),
     #[error("too many batches in block. Got: {0}, max: {MAX_BATCHES_PER_BLOCK}")]
     TooManyBatchesInBlock(usize),
+    #[error("no block can follow block {0}, the last block number")]
+    LastBlockReached(u32),
+    #[error("cannot build block: {0}")]
+    UnsupportedBlockVersion(#[from] UnsupportedBlockVersion),
     #[error("Failed to merge transaction delta into account {account_id}: {error}")]
     AccountUpdateError {
         account_id: AccountId,
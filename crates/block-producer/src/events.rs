@@ -0,0 +1,104 @@
+use miden_objects::{transaction::TransactionId, Digest};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+use crate::{COMPONENT, TRANSACTION_EVENTS_CAPACITY};
+
+// TRANSACTION STATUS
+// ================================================================================================
+
+/// Progress of a queued transaction through the block production pipeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// The transaction was included in a batch.
+    Batched,
+    /// The transaction was dropped from the pipeline, and will never be committed.
+    Rejected { reason: String },
+    /// The transaction was committed in a block.
+    Committed { block_num: u32, block_commitment: Digest },
+}
+
+impl TransactionStatus {
+    /// Returns whether no other status follows this one.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Self::Batched)
+    }
+}
+
+// TRANSACTION EVENTS
+// ================================================================================================
+
+/// Broadcasts the status changes of the queued transactions to their subscribers.
+///
+/// Events are only buffered for the live subscriptions, and dropped if there is none. A
+/// subscription falling more than [TRANSACTION_EVENTS_CAPACITY] events behind misses the oldest
+/// ones.
+#[derive(Debug)]
+pub struct TransactionEvents {
+    sender: broadcast::Sender<(TransactionId, TransactionStatus)>,
+}
+
+impl Default for TransactionEvents {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(TRANSACTION_EVENTS_CAPACITY);
+
+        Self { sender }
+    }
+}
+
+impl TransactionEvents {
+    /// Returns a subscription to the status changes of the transaction.
+    ///
+    /// Only the events published after this call are received, so the subscription must be taken
+    /// before the transaction is queued. The subscription ends when it is dropped.
+    pub fn subscribe(&self, tx_id: TransactionId) -> TransactionSubscription {
+        TransactionSubscription { tx_id, receiver: self.sender.subscribe() }
+    }
+
+    /// Publishes the new `status` of the transactions.
+    pub fn publish(
+        &self,
+        tx_ids: impl IntoIterator<Item = TransactionId>,
+        status: &TransactionStatus,
+    ) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+
+        for tx_id in tx_ids {
+            // Sending only fails if all subscriptions were dropped in the meantime
+            let _ = self.sender.send((tx_id, status.clone()));
+        }
+    }
+}
+
+/// Subscription to the status changes of a single transaction.
+#[derive(Debug)]
+pub struct TransactionSubscription {
+    tx_id: TransactionId,
+    receiver: broadcast::Receiver<(TransactionId, TransactionStatus)>,
+}
+
+impl TransactionSubscription {
+    /// Returns the next status of the transaction.
+    ///
+    /// Never completes once the events were dropped.
+    pub async fn next(&mut self) -> TransactionStatus {
+        loop {
+            match self.receiver.recv().await {
+                Ok((tx_id, status)) if tx_id == self.tx_id => return status,
+                Ok(_) => {},
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(
+                        target: COMPONENT,
+                        tx_id = %self.tx_id.to_hex(),
+                        skipped,
+                        "Transaction subscription lagged behind"
+                    );
+                },
+                // The block production pipeline was dropped, no other status will follow
+                Err(RecvError::Closed) => std::future::pending().await,
+            }
+        }
+    }
+}
@@ -8,14 +8,20 @@ use tokio::sync::RwLock;
 pub mod test_utils;
 
 mod batch_builder;
+mod block_budget;
 mod block_builder;
 mod errors;
+mod events;
+mod proof_time;
+mod reference_blocks;
 mod state_view;
 mod store;
+mod ticker;
 mod txqueue;
 
 pub mod block;
 pub mod config;
+pub mod dry_run;
 pub mod server;
 
 // TYPE ALIASES
@@ -41,3 +47,28 @@ const SERVER_BUILD_BATCH_FREQUENCY: Duration = Duration::from_secs(2);
 
 /// Maximum number of batches per block
 const SERVER_MAX_BATCHES_PER_BLOCK: usize = 4;
+
+/// Time for which deterministic rejections of transactions are remembered
+const SERVER_REJECTION_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Maximum number of remembered rejections of transactions
+const SERVER_REJECTION_CACHE_CAPACITY: usize = 4096;
+
+/// Maximum number of transaction events buffered for the slowest subscription
+const TRANSACTION_EVENTS_CAPACITY: usize = 4096;
+
+/// Maximum time for which a submitted transaction is watched, if it isn't configured
+const SERVER_TRANSACTION_WATCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Maximum number of blocks of expiration slack
+const MAX_EXPIRATION_SLACK: u32 = 16;
+
+/// Maximum number of submissions of a block to the store, if it is unreachable
+const APPLY_BLOCK_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay between the submissions of a block to the store
+const APPLY_BLOCK_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Maximum number of times the block inputs are fetched, if the store's snapshot of their pages
+/// expires before all of them are fetched
+const BLOCK_INPUTS_MAX_ATTEMPTS: u32 = 2;
@@ -0,0 +1,314 @@
+use std::{
+    cmp::Ordering,
+    f64::consts::PI,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::PathBuf,
+    sync::Mutex,
+    time::Duration,
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tokio::time;
+use tracing::Span;
+
+// DISTRIBUTION
+// ================================================================================================
+
+/// Distribution of the simulated proving durations of blocks or batches.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum ProofTimeDistribution {
+    /// Durations uniformly sampled in `min_ms..=max_ms`.
+    Uniform { min_ms: u64, max_ms: u64 },
+
+    /// The same duration for every proof.
+    Constant { value_ms: u64 },
+
+    /// Log-normally distributed durations, with `sigma` being the standard deviation of the
+    /// duration's logarithm. Durations are capped at `cap_ms` to cut the long tail.
+    LogNormal {
+        median_ms: u64,
+        sigma: Sigma,
+        cap_ms: u64,
+    },
+
+    /// Durations replayed from a file containing one duration in milliseconds per line, e.g.
+    /// recorded from the telemetry of a production node. The replay wraps around at the end of the
+    /// file.
+    Replay { file: PathBuf },
+}
+
+impl ProofTimeDistribution {
+    /// Returns the name of the distribution, as used in the configuration.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Uniform { .. } => "uniform",
+            Self::Constant { .. } => "constant",
+            Self::LogNormal { .. } => "log_normal",
+            Self::Replay { .. } => "replay",
+        }
+    }
+}
+
+/// Shape parameter of a log-normal distribution.
+///
+/// Wraps a float to provide the total ordering required by the configuration types.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Sigma(pub f64);
+
+impl PartialEq for Sigma {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Sigma {}
+
+impl PartialOrd for Sigma {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Sigma {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hash for Sigma {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+// SAMPLER
+// ================================================================================================
+
+/// Draws proving durations from a [ProofTimeDistribution].
+#[derive(Debug)]
+pub struct ProofTimeSampler {
+    distribution: ProofTimeDistribution,
+    rng: StdRng,
+    /// Durations loaded from the file of a [ProofTimeDistribution::Replay].
+    replay: Vec<Duration>,
+    /// Index of the next replayed duration.
+    next_replay: usize,
+}
+
+impl ProofTimeSampler {
+    /// Returns a sampler for the given distribution, drawing the same durations for the same
+    /// `seed`.
+    ///
+    /// # Errors
+    /// Returns an error if the distribution parameters are invalid, or if the replay file can't be
+    /// loaded.
+    pub fn new(distribution: ProofTimeDistribution, seed: u64) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidInput, msg);
+
+        let replay = match &distribution {
+            ProofTimeDistribution::Uniform { min_ms, max_ms } if min_ms > max_ms => {
+                return Err(invalid(format!(
+                    "uniform proof time distribution has min_ms {min_ms} above max_ms {max_ms}"
+                )));
+            },
+            ProofTimeDistribution::LogNormal { sigma, .. } if sigma.0.is_nan() || sigma.0 < 0.0 => {
+                return Err(invalid(format!(
+                    "log-normal proof time distribution has invalid sigma {}",
+                    sigma.0
+                )));
+            },
+            ProofTimeDistribution::Replay { file } => {
+                let replay = load_replay_file(&fs::read_to_string(file)?).map_err(|err| {
+                    invalid(format!("invalid proof time replay file {}: {err}", file.display()))
+                })?;
+                if replay.is_empty() {
+                    return Err(invalid(format!(
+                        "proof time replay file {} is empty",
+                        file.display()
+                    )));
+                }
+                replay
+            },
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            distribution,
+            rng: StdRng::seed_from_u64(seed),
+            replay,
+            next_replay: 0,
+        })
+    }
+
+    pub fn distribution(&self) -> &ProofTimeDistribution {
+        &self.distribution
+    }
+
+    /// Returns the next proving duration.
+    pub fn sample(&mut self) -> Duration {
+        match &self.distribution {
+            ProofTimeDistribution::Uniform { min_ms, max_ms } => {
+                Duration::from_millis(self.rng.gen_range(*min_ms..=*max_ms))
+            },
+            ProofTimeDistribution::Constant { value_ms } => Duration::from_millis(*value_ms),
+            ProofTimeDistribution::LogNormal { median_ms, sigma, cap_ms } => {
+                // Box-Muller transform of two uniform samples into a standard normal sample
+                let u1: f64 = 1.0 - self.rng.gen::<f64>();
+                let u2: f64 = self.rng.gen();
+                let normal = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+
+                let duration_ms = *median_ms as f64 * (sigma.0 * normal).exp();
+                Duration::from_millis((duration_ms as u64).min(*cap_ms))
+            },
+            ProofTimeDistribution::Replay { .. } => {
+                let duration = self.replay[self.next_replay];
+                self.next_replay = (self.next_replay + 1) % self.replay.len();
+                duration
+            },
+        }
+    }
+}
+
+/// Parses one duration in milliseconds per line, ignoring empty lines.
+fn load_replay_file(content: &str) -> Result<Vec<Duration>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            line.trim()
+                .parse()
+                .map(Duration::from_millis)
+                .map_err(|err| format!("line {}: {err}", index + 1))
+        })
+        .collect()
+}
+
+// SIMULATED PROVING
+// ================================================================================================
+
+/// Simulates the time taken by proving, by waiting for a duration drawn from a distribution.
+#[derive(Debug)]
+pub struct SimulatedProving {
+    sampler: Mutex<ProofTimeSampler>,
+}
+
+impl SimulatedProving {
+    pub fn new(sampler: ProofTimeSampler) -> Self {
+        Self { sampler: Mutex::new(sampler) }
+    }
+
+    /// Waits for the next sampled proving duration.
+    ///
+    /// The duration and the kind of distribution are recorded in the `simulated_proof_time_ms` and
+    /// `proof_time_distribution` fields of the current span.
+    pub async fn prove(&self) {
+        let (kind, duration) = {
+            let mut sampler = self.sampler.lock().expect("proof time sampler lock poisoned");
+            (sampler.distribution().kind(), sampler.sample())
+        };
+
+        let span = Span::current();
+        span.record("proof_time_distribution", kind);
+        span.record("simulated_proof_time_ms", duration.as_millis());
+
+        time::sleep(duration).await;
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    const NUM_SAMPLES: usize = 10_000;
+
+    fn samples(distribution: ProofTimeDistribution) -> Vec<Duration> {
+        seeded_samples(distribution, 42)
+    }
+
+    fn seeded_samples(distribution: ProofTimeDistribution, seed: u64) -> Vec<Duration> {
+        let mut sampler = ProofTimeSampler::new(distribution, seed).unwrap();
+        (0..NUM_SAMPLES).map(|_| sampler.sample()).collect()
+    }
+
+    #[test]
+    fn uniform_samples_are_within_bounds() {
+        let samples = samples(ProofTimeDistribution::Uniform { min_ms: 100, max_ms: 200 });
+
+        assert!(samples.iter().all(|d| (100..=200).contains(&d.as_millis())));
+        assert_eq!(samples.iter().min(), Some(&Duration::from_millis(100)));
+        assert_eq!(samples.iter().max(), Some(&Duration::from_millis(200)));
+
+        let invalid = ProofTimeDistribution::Uniform { min_ms: 200, max_ms: 100 };
+        assert!(ProofTimeSampler::new(invalid, 0).is_err());
+    }
+
+    #[test]
+    fn constant_samples_are_constant() {
+        let samples = samples(ProofTimeDistribution::Constant { value_ms: 150 });
+
+        assert!(samples.iter().all(|d| *d == Duration::from_millis(150)));
+    }
+
+    #[test]
+    fn log_normal_samples_are_capped_around_the_median() {
+        let mut samples = samples(ProofTimeDistribution::LogNormal {
+            median_ms: 1_000,
+            sigma: Sigma(0.5),
+            cap_ms: 3_000,
+        });
+
+        assert!(samples.iter().all(|d| *d <= Duration::from_millis(3_000)));
+        // The tail above e^(2 * sigma) times the median is ~2% of the samples
+        assert!(samples.iter().any(|d| *d == Duration::from_millis(3_000)));
+
+        samples.sort();
+        let median = samples[NUM_SAMPLES / 2].as_millis();
+        assert!((950..=1_050).contains(&median), "median {median}ms");
+    }
+
+    #[test]
+    fn replay_wraps_around() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "100\n\n250\n 50 ").unwrap();
+
+        let distribution = ProofTimeDistribution::Replay { file: file.path().to_path_buf() };
+        let mut sampler = ProofTimeSampler::new(distribution, 0).unwrap();
+
+        let samples: Vec<_> = (0..7).map(|_| sampler.sample().as_millis()).collect();
+        assert_eq!(samples, [100, 250, 50, 100, 250, 50, 100]);
+    }
+
+    #[test]
+    fn invalid_replay_files_are_rejected() {
+        for content in ["", "100\nabc\n", "-5"] {
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            write!(file, "{content}").unwrap();
+
+            let distribution = ProofTimeDistribution::Replay { file: file.path().to_path_buf() };
+            assert!(ProofTimeSampler::new(distribution, 0).is_err(), "{content:?}");
+        }
+    }
+
+    #[test]
+    fn seeded_samplers_are_deterministic() {
+        let distribution = ProofTimeDistribution::LogNormal {
+            median_ms: 500,
+            sigma: Sigma(1.0),
+            cap_ms: 10_000,
+        };
+
+        assert_eq!(samples(distribution.clone()), samples(distribution.clone()));
+        assert_ne!(seeded_samples(distribution.clone(), 1), seeded_samples(distribution, 2));
+    }
+}
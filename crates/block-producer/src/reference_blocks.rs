@@ -0,0 +1,71 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use miden_node_utils::block_num::BlockNumberExt;
+use miden_objects::transaction::TransactionId;
+
+// REFERENCE BLOCKS
+// ================================================================================================
+
+/// Reference blocks of the transactions waiting to be batched, checked against the number of
+/// recent blocks whose data the store retains.
+///
+/// Blocks are proven against the data of the reference blocks of their transactions, so a
+/// transaction can't be included in a block once the store pruned the data of its reference
+/// block. Transactions are recorded once verified, and forgotten once batched or reverted.
+#[derive(Debug, Default)]
+pub struct ReferenceBlocks {
+    /// Reference block of every recorded transaction
+    reference_blocks: Mutex<HashMap<TransactionId, u32>>,
+
+    /// Number of most recent blocks whose data the store retains, `None` if it retains all blocks
+    retention_depth: Mutex<Option<u32>>,
+}
+
+impl ReferenceBlocks {
+    /// Records the reference block of a verified transaction.
+    pub fn insert(&self, tx_id: TransactionId, block_num: u32) {
+        self.reference_blocks
+            .lock()
+            .expect("Reference blocks lock poisoned")
+            .insert(tx_id, block_num);
+    }
+
+    /// Forgets the reference blocks of the given transactions.
+    pub fn remove(&self, tx_ids: impl IntoIterator<Item = TransactionId>) {
+        let mut reference_blocks =
+            self.reference_blocks.lock().expect("Reference blocks lock poisoned");
+        for tx_id in tx_ids {
+            reference_blocks.remove(&tx_id);
+        }
+    }
+
+    /// Returns the number of most recent blocks whose data the store retains, `None` if it
+    /// retains all blocks.
+    pub fn retention_depth(&self) -> Option<u32> {
+        *self.retention_depth.lock().expect("Retention depth lock poisoned")
+    }
+
+    /// Updates the number of most recent blocks whose data the store retains.
+    pub fn set_retention_depth(&self, retention_depth: Option<u32>) {
+        *self.retention_depth.lock().expect("Retention depth lock poisoned") = retention_depth;
+    }
+
+    /// Returns `true` if the data of the transaction's reference block would no longer be retained
+    /// when proposing `inclusion_block`, i.e. if the reference block isn't one of the
+    /// `retention_depth` blocks preceding it.
+    ///
+    /// Transactions whose reference block isn't recorded are never stale.
+    pub fn is_stale(&self, tx_id: TransactionId, inclusion_block: u32) -> bool {
+        let Some(retention_depth) = self.retention_depth() else {
+            return false;
+        };
+
+        self.reference_blocks
+            .lock()
+            .expect("Reference blocks lock poisoned")
+            .get(&tx_id)
+            .is_some_and(|block_num| {
+                block_num.saturating_advance(retention_depth) < inclusion_block
+            })
+    }
+}
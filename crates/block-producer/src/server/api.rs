@@ -1,16 +1,37 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use miden_node_proto::generated::{
-    block_producer::api_server, requests::SubmitProvenTransactionRequest,
-    responses::SubmitProvenTransactionResponse,
+    block_producer::api_server,
+    requests::{
+        DumpMempoolGraphRequest, EvictTransactionRequest, GetAccountExpectedStateRequest,
+        GetSupportedBlockVersionsRequest, GraphFormat, SubmitProvenTransactionRequest,
+    },
+    responses::{
+        submit_and_watch_proven_transaction_response::Status as WatchStatus,
+        DumpMempoolGraphResponse, EvictTransactionResponse, GetAccountExpectedStateResponse,
+        GetSupportedBlockVersionsResponse, SubmitAndWatchProvenTransactionResponse,
+        SubmitProvenTransactionResponse, TransactionBatched, TransactionCommitted,
+        TransactionRejected, TransactionStillPending,
+    },
 };
-use miden_node_utils::formatting::{format_input_notes, format_output_notes};
-use miden_objects::{transaction::ProvenTransaction, utils::serde::Deserializable};
+use miden_node_utils::{
+    block_versions::BlockVersions,
+    formatting::{format_input_notes, format_output_notes},
+    message_limits::MessageLimits,
+};
+use miden_objects::{
+    accounts::AccountId,
+    transaction::{ProvenTransaction, TransactionId},
+    utils::serde::Deserializable,
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::Status;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, Instrument};
 
 use crate::{
     batch_builder::BatchBuilder,
+    events::{TransactionEvents, TransactionStatus, TransactionSubscription},
     txqueue::{TransactionQueue, TransactionValidator},
     COMPONENT,
 };
@@ -20,11 +41,50 @@ use crate::{
 
 pub struct BlockProducerApi<BB, TV> {
     queue: Arc<TransactionQueue<BB, TV>>,
+    /// Status changes of the queued transactions, streamed to the clients watching them
+    events: Arc<TransactionEvents>,
+    /// Maximum time for which a submitted transaction is watched
+    watch_timeout: Duration,
+    /// Versions of the produced blocks, reported to the operators
+    block_versions: BlockVersions,
+    /// Limits on the size of the notes of the submitted transactions
+    message_limits: MessageLimits,
+    /// Whether the debugging endpoints are served
+    debug_endpoints: bool,
 }
 
 impl<BB, TV> BlockProducerApi<BB, TV> {
-    pub fn new(queue: Arc<TransactionQueue<BB, TV>>) -> Self {
-        Self { queue }
+    pub fn new(
+        queue: Arc<TransactionQueue<BB, TV>>,
+        events: Arc<TransactionEvents>,
+        watch_timeout: Duration,
+    ) -> Self {
+        Self {
+            queue,
+            events,
+            watch_timeout,
+            block_versions: BlockVersions::default(),
+            message_limits: MessageLimits::default(),
+            debug_endpoints: false,
+        }
+    }
+
+    /// Sets the versions of the produced blocks reported by `GetSupportedBlockVersions`.
+    pub fn with_block_versions(mut self, block_versions: BlockVersions) -> Self {
+        self.block_versions = block_versions;
+        self
+    }
+
+    /// Sets the limits on the size of the notes of the submitted transactions.
+    pub fn with_message_limits(mut self, message_limits: MessageLimits) -> Self {
+        self.message_limits = message_limits;
+        self
+    }
+
+    /// Serves the debugging endpoints, e.g. `DumpMempoolGraph`, if `enabled`.
+    pub fn with_debug_endpoints(mut self, enabled: bool) -> Self {
+        self.debug_endpoints = enabled;
+        self
     }
 }
 
@@ -44,31 +104,424 @@ where
         &self,
         request: tonic::Request<SubmitProvenTransactionRequest>,
     ) -> Result<tonic::Response<SubmitProvenTransactionResponse>, Status> {
+        let tx = read_transaction(request.into_inner(), &self.message_limits)?;
+
+        let block_height = self.queue.add_transaction(tx).await?;
+
+        Ok(tonic::Response::new(SubmitProvenTransactionResponse { block_height }))
+    }
+
+    type SubmitAndWatchProvenTransactionStream =
+        ReceiverStream<Result<SubmitAndWatchProvenTransactionResponse, Status>>;
+
+    #[instrument(
+        target = "miden-block-producer",
+        name = "block_producer:submit_and_watch_proven_transaction",
+        skip_all,
+        err
+    )]
+    async fn submit_and_watch_proven_transaction(
+        &self,
+        request: tonic::Request<SubmitProvenTransactionRequest>,
+    ) -> Result<tonic::Response<Self::SubmitAndWatchProvenTransactionStream>, Status> {
+        let tx = read_transaction(request.into_inner(), &self.message_limits)?;
+
+        // Subscribing first, so that no status change is missed
+        let subscription = self.events.subscribe(tx.id());
+        let block_height = self.queue.add_transaction(tx).await?;
+
+        let (sender, receiver) = mpsc::channel(WATCH_STREAM_BUFFER);
+        let submitted = WatchStatus::Submitted(SubmitProvenTransactionResponse { block_height });
+        sender
+            .try_send(Ok(SubmitAndWatchProvenTransactionResponse { status: Some(submitted) }))
+            .expect("The stream buffer is empty");
+
+        tokio::spawn(watch_transaction(subscription, sender, self.watch_timeout).in_current_span());
+
+        Ok(tonic::Response::new(ReceiverStream::new(receiver)))
+    }
+
+    #[instrument(
+        target = "miden-block-producer",
+        name = "block_producer:get_account_expected_state",
+        skip_all,
+        err
+    )]
+    async fn get_account_expected_state(
+        &self,
+        request: tonic::Request<GetAccountExpectedStateRequest>,
+    ) -> Result<tonic::Response<GetAccountExpectedStateResponse>, Status> {
         let request = request.into_inner();
         debug!(target: COMPONENT, ?request);
 
-        let tx = ProvenTransaction::read_from_bytes(&request.transaction)
-            .map_err(|_| Status::invalid_argument("Invalid transaction"))?;
-
-        info!(
-            target: COMPONENT,
-            tx_id = %tx.id().to_hex(),
-            account_id = %tx.account_id().to_hex(),
-            initial_account_hash = %tx.account_update().init_state_hash(),
-            final_account_hash = %tx.account_update().final_state_hash(),
-            input_notes = %format_input_notes(tx.input_notes()),
-            output_notes = %format_output_notes(tx.output_notes()),
-            block_ref = %tx.block_ref(),
-            "Deserialized transaction"
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(Status::invalid_argument("account_id is missing"))?
+            .try_into()
+            .map_err(|err| Status::invalid_argument(format!("Invalid account id: {err}")))?;
+
+        let expected = self.queue.get_expected_account_state(account_id).await?;
+
+        Ok(tonic::Response::new(GetAccountExpectedStateResponse {
+            account_hash: expected.account_hash.map(Into::into),
+            in_flight: expected.in_flight,
+            block_height: expected.block_height,
+        }))
+    }
+
+    #[instrument(
+        target = "miden-block-producer",
+        name = "block_producer:get_supported_block_versions",
+        skip_all,
+        err
+    )]
+    async fn get_supported_block_versions(
+        &self,
+        _request: tonic::Request<GetSupportedBlockVersionsRequest>,
+    ) -> Result<tonic::Response<GetSupportedBlockVersionsResponse>, Status> {
+        Ok(tonic::Response::new(self.block_versions.into()))
+    }
+
+    #[instrument(
+        target = "miden-block-producer",
+        name = "block_producer:evict_transaction",
+        skip_all,
+        err
+    )]
+    async fn evict_transaction(
+        &self,
+        request: tonic::Request<EvictTransactionRequest>,
+    ) -> Result<tonic::Response<EvictTransactionResponse>, Status> {
+        let request = request.into_inner();
+        debug!(target: COMPONENT, ?request);
+
+        let tx_id: TransactionId = request
+            .transaction_id
+            .ok_or(Status::invalid_argument("transaction_id is missing"))?
+            .try_into()
+            .map_err(|err| Status::invalid_argument(format!("Invalid transaction id: {err}")))?;
+
+        let eviction = self.queue.evict_transaction(tx_id, request.cascade).await?;
+
+        Ok(tonic::Response::new(EvictTransactionResponse {
+            evicted_transactions: eviction.evicted_transactions.iter().map(Into::into).collect(),
+            dropped_batches: eviction
+                .dropped_batches
+                .iter()
+                .map(|batch_id| batch_id.to_vec())
+                .collect(),
+            requeued_transactions: eviction.requeued_transactions.iter().map(Into::into).collect(),
+        }))
+    }
+
+    #[instrument(
+        target = "miden-block-producer",
+        name = "block_producer:dump_mempool_graph",
+        skip_all,
+        err
+    )]
+    async fn dump_mempool_graph(
+        &self,
+        request: tonic::Request<DumpMempoolGraphRequest>,
+    ) -> Result<tonic::Response<DumpMempoolGraphResponse>, Status> {
+        let request = request.into_inner();
+        debug!(target: COMPONENT, ?request);
+
+        if !self.debug_endpoints {
+            return Err(Status::failed_precondition(
+                "Debugging endpoints are disabled, see `debug_endpoints` of the block producer \
+                 configuration",
+            ));
+        }
+
+        let graph = self.queue.mempool_graph().await;
+        let graph = match request.format() {
+            GraphFormat::Json => graph.to_json(),
+            GraphFormat::Dot => graph.to_dot(),
+        };
+
+        Ok(tonic::Response::new(DumpMempoolGraphResponse { graph }))
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Number of messages of a watch stream buffered for a slow client.
+const WATCH_STREAM_BUFFER: usize = 4;
+
+/// Deserializes the transaction of the request, and checks the size of its notes.
+fn read_transaction(
+    request: SubmitProvenTransactionRequest,
+    message_limits: &MessageLimits,
+) -> Result<ProvenTransaction, Status> {
+    debug!(target: COMPONENT, ?request);
+
+    let tx = ProvenTransaction::read_from_bytes(&request.transaction)
+        .map_err(|_| Status::invalid_argument("Invalid transaction"))?;
+    message_limits.check_output_notes(tx.output_notes()).map_err(|err| {
+        Status::invalid_argument(format!("Invalid transaction {}: {err}", tx.id()))
+    })?;
+
+    info!(
+        target: COMPONENT,
+        tx_id = %tx.id().to_hex(),
+        account_id = %tx.account_id().to_hex(),
+        initial_account_hash = %tx.account_update().init_state_hash(),
+        final_account_hash = %tx.account_update().final_state_hash(),
+        input_notes = %format_input_notes(tx.input_notes()),
+        output_notes = %format_output_notes(tx.output_notes()),
+        block_ref = %tx.block_ref(),
+        "Deserialized transaction"
+    );
+    debug!(target: COMPONENT, proof = ?tx.proof());
+
+    Ok(tx)
+}
+
+/// Streams the status changes of a submitted transaction to the `sender`, until a final status or
+/// the `timeout`.
+///
+/// Stops as soon as the client disconnects, dropping the subscription.
+async fn watch_transaction(
+    mut subscription: TransactionSubscription,
+    sender: mpsc::Sender<Result<SubmitAndWatchProvenTransactionResponse, Status>>,
+    timeout: Duration,
+) {
+    let deadline = tokio::time::sleep(timeout);
+    tokio::pin!(deadline);
+
+    loop {
+        let status = tokio::select! {
+            status = subscription.next() => Some(status),
+            _ = &mut deadline => None,
+            _ = sender.closed() => {
+                debug!(target: COMPONENT, "Transaction watcher disconnected");
+                return;
+            },
+        };
+        let Some(status) = status else {
+            debug!(target: COMPONENT, "Transaction still pending at the watch timeout");
+            let status = WatchStatus::StillPending(TransactionStillPending {});
+            let response = SubmitAndWatchProvenTransactionResponse { status: Some(status) };
+            let _ = sender.send(Ok(response)).await;
+            return;
+        };
+
+        let is_final = status.is_final();
+        let status = match status {
+            TransactionStatus::Batched => WatchStatus::Batched(TransactionBatched {}),
+            TransactionStatus::Rejected { reason } => {
+                WatchStatus::Rejected(TransactionRejected { reason })
+            },
+            TransactionStatus::Committed { block_num, block_commitment } => {
+                WatchStatus::Committed(TransactionCommitted {
+                    block_num,
+                    block_commitment: Some(block_commitment.into()),
+                })
+            },
+        };
+
+        let response = SubmitAndWatchProvenTransactionResponse { status: Some(status) };
+        if sender.send(Ok(response)).await.is_err() || is_final {
+            return;
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use miden_node_test_utils::note::mock_output_note;
+    use miden_node_utils::block_versions::{BlockVersionRange, BlockVersionUpgrade};
+    use miden_objects::{utils::Serializable, Digest};
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::{
+        batch_builder::{DefaultBatchBuilder, DefaultBatchBuilderOptions},
+        block_builder::DefaultBlockBuilder,
+        state_view::DefaultStateView,
+        test_utils::{
+            MockPrivateAccount, MockProvenTxBuilder, MockStoreSuccess, MockStoreSuccessBuilder,
+        },
+        txqueue::TransactionQueueOptions,
+    };
+
+    type TestBatchBuilder = DefaultBatchBuilder<
+        MockStoreSuccess,
+        DefaultBlockBuilder<MockStoreSuccess, DefaultStateView<MockStoreSuccess>>,
+    >;
+    type TestQueue = TransactionQueue<TestBatchBuilder, DefaultStateView<MockStoreSuccess>>;
+    type TestApi = BlockProducerApi<TestBatchBuilder, DefaultStateView<MockStoreSuccess>>;
+
+    /// Returns the API of a block producer on top of the `store`, along with its queue and batch
+    /// builder.
+    ///
+    /// The batches and blocks are only built once the queue and the batch builder are run.
+    fn block_producer(
+        store: Arc<MockStoreSuccess>,
+        watch_timeout: Duration,
+    ) -> (TestApi, Arc<TestQueue>, Arc<TestBatchBuilder>) {
+        let events = Arc::new(TransactionEvents::default());
+        let state_view = Arc::new(DefaultStateView::new(Arc::clone(&store), false));
+        let block_builder = DefaultBlockBuilder::new(Arc::clone(&store), Arc::clone(&state_view))
+            .with_events(Arc::clone(&events));
+        let batch_builder = Arc::new(
+            DefaultBatchBuilder::new(
+                store,
+                Arc::new(block_builder),
+                DefaultBatchBuilderOptions {
+                    block_frequency: Duration::from_millis(20),
+                    max_batches_per_block: 2,
+                    tick_jitter: Default::default(),
+                    expiration_slack: 0,
+                },
+            )
+            .with_events(Arc::clone(&events)),
+        );
+        let queue = Arc::new(
+            TransactionQueue::new(
+                state_view,
+                Arc::clone(&batch_builder),
+                TransactionQueueOptions {
+                    build_batch_frequency: Duration::from_millis(10),
+                    batch_size: 2,
+                    min_batch_size: 0,
+                    max_batch_wait: Duration::ZERO,
+                    tick_jitter: Default::default(),
+                    rejection_cache_ttl: Duration::ZERO,
+                    rejection_cache_capacity: 0,
+                },
+            )
+            .with_events(Arc::clone(&events)),
         );
-        debug!(target: COMPONENT, proof = ?tx.proof());
 
-        let block_height = self
-            .queue
-            .add_transaction(tx)
+        (
+            BlockProducerApi::new(Arc::clone(&queue), events, watch_timeout),
+            queue,
+            batch_builder,
+        )
+    }
+
+    /// Submits the transaction, and returns all the statuses of its watch stream.
+    async fn submit_and_watch(api: &TestApi, tx: &ProvenTransaction) -> Vec<WatchStatus> {
+        let request = SubmitProvenTransactionRequest { transaction: tx.to_bytes() };
+        let stream = api
+            .submit_and_watch_proven_transaction(tonic::Request::new(request))
             .await
-            .map_err(|err| Status::invalid_argument(format!("{:?}", err)))?;
+            .unwrap()
+            .into_inner();
 
-        Ok(tonic::Response::new(SubmitProvenTransactionResponse { block_height }))
+        tokio::time::timeout(
+            Duration::from_secs(30),
+            stream.map(|response| response.unwrap().status.unwrap()).collect(),
+        )
+        .await
+        .expect("The watch stream must end")
+    }
+
+    #[tokio::test]
+    #[miden_node_test_macro::enable_logging]
+    async fn watched_transaction_is_followed_until_committed() {
+        let account: MockPrivateAccount<3> = MockPrivateAccount::from(1);
+        let store = Arc::new(
+            MockStoreSuccessBuilder::from_accounts(iter::once((account.id, account.states[0])))
+                .build(),
+        );
+        let (api, queue, batch_builder) =
+            block_producer(Arc::clone(&store), Duration::from_secs(30));
+        tokio::spawn(queue.run());
+        tokio::spawn(batch_builder.run());
+
+        let tx =
+            MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1])
+                .build();
+        let statuses = submit_and_watch(&api, &tx).await;
+
+        assert_eq!(statuses.len(), 3, "{statuses:?}");
+        assert!(matches!(statuses[0], WatchStatus::Submitted(_)));
+        assert_eq!(statuses[1], WatchStatus::Batched(TransactionBatched {}));
+        let WatchStatus::Committed(TransactionCommitted { block_num, block_commitment }) =
+            &statuses[2]
+        else {
+            panic!("The transaction must be committed, got {:?}", statuses[2]);
+        };
+        let block_commitment: Digest = block_commitment.as_ref().unwrap().try_into().unwrap();
+        let chain_mmr = store.chain_mmr.read().await;
+        assert!((0..chain_mmr.forest()).any(|pos| chain_mmr.get(pos).unwrap() == block_commitment));
+        assert!(*block_num > 0);
+    }
+
+    #[tokio::test]
+    #[miden_node_test_macro::enable_logging]
+    async fn watched_transaction_is_still_pending_at_the_timeout() {
+        let account: MockPrivateAccount<3> = MockPrivateAccount::from(1);
+        let store = Arc::new(
+            MockStoreSuccessBuilder::from_accounts(iter::once((account.id, account.states[0])))
+                .build(),
+        );
+        // Nothing is batched, since the queue isn't run
+        let (api, ..) = block_producer(store, Duration::from_millis(50));
+
+        let tx =
+            MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1])
+                .build();
+        let statuses = submit_and_watch(&api, &tx).await;
+
+        assert_eq!(statuses.len(), 2, "{statuses:?}");
+        assert!(matches!(statuses[0], WatchStatus::Submitted(_)));
+        assert_eq!(statuses[1], WatchStatus::StillPending(TransactionStillPending {}));
+    }
+
+    #[tokio::test]
+    async fn supported_block_versions_are_reported() {
+        let store = Arc::new(MockStoreSuccessBuilder::from_accounts(iter::empty()).build());
+        let block_versions = BlockVersions {
+            supported: BlockVersionRange { min: 0, max: 1 },
+            upgrade: Some(BlockVersionUpgrade {
+                activation_block: 100,
+                supported: BlockVersionRange { min: 1, max: 2 },
+            }),
+        };
+        let (api, ..) = block_producer(store, Duration::from_secs(30));
+        let api = api.with_block_versions(block_versions);
+
+        let response = api
+            .get_supported_block_versions(tonic::Request::new(GetSupportedBlockVersionsRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(BlockVersions::try_from(response).unwrap(), block_versions);
+    }
+
+    #[tokio::test]
+    async fn transactions_with_oversized_notes_are_rejected() {
+        let account: MockPrivateAccount<3> = MockPrivateAccount::from(1);
+        let store = Arc::new(
+            MockStoreSuccessBuilder::from_accounts(iter::once((account.id, account.states[0])))
+                .build(),
+        );
+        let (api, ..) = block_producer(store, Duration::from_secs(30));
+        let api = api.with_message_limits(MessageLimits {
+            max_note_details_size: Some(16),
+            ..Default::default()
+        });
+
+        let note = mock_output_note(1);
+        let tx =
+            MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1])
+                .output_notes(vec![note.clone()])
+                .build();
+        let request = SubmitProvenTransactionRequest { transaction: tx.to_bytes() };
+        let status = api.submit_proven_transaction(tonic::Request::new(request)).await.unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().contains(&note.id().to_string()), "{status}");
+        assert!(status.message().contains("maximum of 16 bytes"), "{status}");
     }
 }
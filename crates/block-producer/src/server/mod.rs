@@ -1,7 +1,13 @@
-use std::{net::ToSocketAddrs, sync::Arc};
+use std::{net::SocketAddr, sync::Arc};
 
 use miden_node_proto::generated::{block_producer::api_server, store::api_client as store_client};
-use miden_node_utils::errors::ApiError;
+use miden_node_utils::{
+    errors::ApiError,
+    genesis::check_genesis_commitment,
+    request_id::{connect_with_request_ids, RequestIdLayer},
+    runtime::BlockingPool,
+    tasks::TaskSupervisor,
+};
 use tokio::net::TcpListener;
 use tokio_stream::wrappers::TcpListenerStream;
 use tracing::info;
@@ -10,11 +16,13 @@ use crate::{
     batch_builder::{DefaultBatchBuilder, DefaultBatchBuilderOptions},
     block_builder::DefaultBlockBuilder,
     config::BlockProducerConfig,
+    events::TransactionEvents,
+    reference_blocks::ReferenceBlocks,
     state_view::DefaultStateView,
     store::DefaultStore,
     txqueue::{TransactionQueue, TransactionQueueOptions},
     COMPONENT, SERVER_BATCH_SIZE, SERVER_BLOCK_FREQUENCY, SERVER_BUILD_BATCH_FREQUENCY,
-    SERVER_MAX_BATCHES_PER_BLOCK,
+    SERVER_MAX_BATCHES_PER_BLOCK, SERVER_REJECTION_CACHE_CAPACITY, SERVER_REJECTION_CACHE_TTL,
 };
 
 pub mod api;
@@ -36,6 +44,9 @@ type Api = api::BlockProducerApi<
 pub struct BlockProducer {
     api_service: api_server::ApiServer<Api>,
     listener: TcpListener,
+    local_addr: SocketAddr,
+    /// Supervises the transaction queue and the batch builder.
+    supervisor: TaskSupervisor,
 }
 
 impl BlockProducer {
@@ -43,65 +54,164 @@ impl BlockProducer {
     /// endpoint without serving the API yet. Incoming requests will be queued until
     /// [`serve`](Self::serve) is called.
     pub async fn init(config: BlockProducerConfig) -> Result<Self, ApiError> {
+        Self::init_with_proving_pool(config, BlockingPool::inline()).await
+    }
+
+    /// Initializes the block-producer like [`init`](Self::init), proving the batches and blocks
+    /// on `proving_pool`.
+    pub async fn init_with_proving_pool(
+        config: BlockProducerConfig,
+        proving_pool: BlockingPool,
+    ) -> Result<Self, ApiError> {
         info!(target: COMPONENT, %config, "Initializing server");
 
-        let store = Arc::new(DefaultStore::new(
-            store_client::ApiClient::connect(config.store_url.to_string())
+        // Random identifiers tell apart the instances without a configured one
+        let producer_id = config
+            .producer_id
+            .clone()
+            .unwrap_or_else(|| format!("{:016x}", rand::random::<u64>()));
+        info!(target: COMPONENT, %producer_id, "Reporting blocks to the store");
+
+        let max_message_size = config.message_limits.max_message_size();
+        let store_client = store_client::ApiClient::new(
+            connect_with_request_ids(config.store_url.to_string())
                 .await
                 .map_err(|err| ApiError::DatabaseConnectionFailed(err.to_string()))?,
-        ));
-        let state_view =
-            Arc::new(DefaultStateView::new(Arc::clone(&store), config.verify_tx_proofs));
+        )
+        .max_decoding_message_size(max_message_size)
+        .max_encoding_message_size(max_message_size);
+        let store = DefaultStore::new(store_client).with_producer_id(producer_id);
+        if let Some(expected) = &config.genesis_commitment {
+            let genesis_commitment = store.get_genesis_commitment().await.map_err(|err| {
+                ApiError::ApiInitialisationFailed(format!(
+                    "Failed to read the genesis block of the store: {err}"
+                ))
+            })?;
+            check_genesis_commitment(Some(expected), genesis_commitment)
+                .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?;
+            info!(target: COMPONENT, %expected, "Genesis block commitment of the store checked");
+        }
+        let store = Arc::new(store);
+        let reference_blocks = Arc::new(ReferenceBlocks::default());
+        let state_view = Arc::new(
+            DefaultStateView::new(Arc::clone(&store), config.verify_tx_proofs)
+                .with_reference_blocks(Arc::clone(&reference_blocks)),
+        );
+
+        let simulation_seed = config.simulation_seed.unwrap_or_else(rand::random);
+        let (simulated_block_proving, simulated_batch_proving) =
+            config.simulated_proving(simulation_seed)?;
+        if simulated_block_proving.is_some() || simulated_batch_proving.is_some() {
+            info!(target: COMPONENT, simulation_seed, "Simulating proof times");
+        }
+        let (block_tick_jitter, batch_tick_jitter) = config.tick_jitters(simulation_seed);
+        if config.interval_jitter_percent > 0 {
+            info!(target: COMPONENT, simulation_seed, "Jittering the block and batch schedules");
+        }
+        let events = Arc::new(TransactionEvents::default());
+
+        let mut block_builder =
+            DefaultBlockBuilder::new(Arc::clone(&store), Arc::clone(&state_view))
+                .with_events(Arc::clone(&events))
+                .with_block_versions(config.block_versions)
+                .with_proving_pool(proving_pool.clone());
+        if let Some(simulated_proving) = simulated_block_proving {
+            block_builder = block_builder.with_simulated_proving(simulated_proving);
+        }
 
-        let block_builder = DefaultBlockBuilder::new(Arc::clone(&store), Arc::clone(&state_view));
         let batch_builder_options = DefaultBatchBuilderOptions {
             block_frequency: SERVER_BLOCK_FREQUENCY,
             max_batches_per_block: SERVER_MAX_BATCHES_PER_BLOCK,
+            tick_jitter: block_tick_jitter,
+            expiration_slack: config.expiration_slack,
         };
-        let batch_builder = Arc::new(DefaultBatchBuilder::new(
+        let mut batch_builder = DefaultBatchBuilder::new(
             Arc::clone(&store),
             Arc::new(block_builder),
             batch_builder_options,
-        ));
+        )
+        .with_events(Arc::clone(&events))
+        .with_reference_blocks(reference_blocks)
+        .with_proving_pool(proving_pool);
+        if let Some(simulated_proving) = simulated_batch_proving {
+            batch_builder = batch_builder.with_simulated_proving(simulated_proving);
+        }
+        if let Some(target) = config.block_build_target() {
+            batch_builder = batch_builder.with_block_build_target(target);
+        }
+        batch_builder.refresh_retention_depth().await;
+        let batch_builder = Arc::new(batch_builder);
 
         let transaction_queue_options = TransactionQueueOptions {
             build_batch_frequency: SERVER_BUILD_BATCH_FREQUENCY,
             batch_size: SERVER_BATCH_SIZE,
+            min_batch_size: config.min_batch_size(SERVER_BATCH_SIZE),
+            max_batch_wait: config.max_batch_wait(),
+            tick_jitter: batch_tick_jitter,
+            rejection_cache_ttl: SERVER_REJECTION_CACHE_TTL,
+            rejection_cache_capacity: SERVER_REJECTION_CACHE_CAPACITY,
         };
-        let queue = Arc::new(TransactionQueue::new(
-            state_view,
-            Arc::clone(&batch_builder),
-            transaction_queue_options,
-        ));
-
-        let api_service =
-            api_server::ApiServer::new(api::BlockProducerApi::new(Arc::clone(&queue)));
-
-        tokio::spawn(async move { queue.run().await });
-        tokio::spawn(async move { batch_builder.run().await });
-
-        let addr = config
-            .endpoint
-            .to_socket_addrs()
-            .map_err(ApiError::EndpointToSocketFailed)?
-            .next()
-            .ok_or_else(|| ApiError::AddressResolutionFailed(config.endpoint.to_string()))?;
-
-        let listener = TcpListener::bind(addr).await?;
-
-        info!(target: COMPONENT, "Server initialized");
+        let queue = Arc::new(
+            TransactionQueue::new(
+                state_view,
+                Arc::clone(&batch_builder),
+                transaction_queue_options,
+            )
+            .with_events(Arc::clone(&events)),
+        );
+
+        let api_service = api_server::ApiServer::new(
+            api::BlockProducerApi::new(
+                Arc::clone(&queue),
+                events,
+                config.transaction_watch_timeout(),
+            )
+            .with_block_versions(config.block_versions)
+            .with_message_limits(config.message_limits)
+            .with_debug_endpoints(config.debug_endpoints),
+        )
+        .max_decoding_message_size(max_message_size)
+        .max_encoding_message_size(max_message_size);
+
+        let supervisor = TaskSupervisor::new(COMPONENT);
+        supervisor.spawn_supervised("transaction_queue", queue.run());
+        supervisor.spawn_supervised("batch_builder", batch_builder.run());
+
+        let listener = config.endpoint.bind().await?;
+        let local_addr = listener.local_addr()?;
+
+        info!(target: COMPONENT, %local_addr, "Server initialized");
+
+        Ok(Self {
+            api_service,
+            listener,
+            local_addr,
+            supervisor,
+        })
+    }
 
-        Ok(Self { api_service, listener })
+    /// Returns the address the block producer listens on, with the port chosen by the system if
+    /// the endpoint's port is 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
     }
 
     /// Serves the block-producers's RPC API.
     ///
+    /// The server shuts down if the transaction queue or the batch builder panics.
+    ///
     /// Note: this blocks until the server dies.
     pub async fn serve(self) -> Result<(), ApiError> {
         tonic::transport::Server::builder()
+            .layer(RequestIdLayer)
             .add_service(self.api_service)
-            .serve_with_incoming(TcpListenerStream::new(self.listener))
+            .serve_with_incoming_shutdown(
+                TcpListenerStream::new(self.listener),
+                self.supervisor.wait_for_failure(),
+            )
             .await
-            .map_err(ApiError::ApiServeFailed)
+            .map_err(ApiError::ApiServeFailed)?;
+
+        Ok(self.supervisor.check()?)
     }
 }
@@ -74,6 +74,25 @@ impl InflightAccountStates {
         Ok(())
     }
 
+    /// Reverts the latest state transition of the given account, which must result in
+    /// `final_state`. Returns an error if the account's latest inflight state doesn't match.
+    ///
+    /// In other words, if an account has state transitions `a->b->c->d` then calling `revert(d)`
+    /// would leave behind `a->b->c`, while `revert(c)` would fail.
+    pub fn revert(&mut self, id: AccountId, final_state: Digest) -> Result<(), ()> {
+        let states = self.0.get_mut(&id).ok_or(())?;
+        if states.back() != Some(&final_state) {
+            return Err(());
+        }
+
+        states.pop_back();
+        if states.is_empty() {
+            self.0.remove(&id);
+        }
+
+        Ok(())
+    }
+
     /// The latest value of the given account.
     pub fn get(&self, id: AccountId) -> Option<&Digest> {
         self.0.get(&id).and_then(|states| states.back())
@@ -120,4 +139,28 @@ mod tests {
         // Check that cleanup is performed.
         assert!(uut.0.is_empty());
     }
+
+    #[test]
+    fn account_states_revert_from_latest() {
+        let account: AccountId = AccountId::new_unchecked(Felt::new(10));
+        const ONE: Digest = Digest::new([Felt::new(1), Felt::new(1), Felt::new(1), Felt::new(1)]);
+        const TWO: Digest = Digest::new([Felt::new(2), Felt::new(2), Felt::new(2), Felt::new(2)]);
+        let mut uut = InflightAccountStates::default();
+
+        assert!(uut.verify_and_add(account, Digest::default(), ONE).is_ok());
+        assert!(uut.verify_and_add(account, ONE, TWO).is_ok());
+
+        // Only the latest state can be reverted.
+        assert!(uut.revert(account, ONE).is_err());
+        assert!(uut.revert(account, TWO).is_ok());
+        assert_eq!(uut.get(account), Some(&ONE));
+
+        // The reverted transition can be re-added.
+        assert!(uut.verify_and_add(account, ONE, TWO).is_ok());
+        assert!(uut.revert(account, TWO).is_ok());
+        assert!(uut.revert(account, ONE).is_ok());
+
+        // Check that cleanup is performed.
+        assert!(uut.0.is_empty());
+    }
 }
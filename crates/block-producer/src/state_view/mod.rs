@@ -3,6 +3,7 @@ use std::{collections::BTreeSet, sync::Arc};
 use async_trait::async_trait;
 use miden_node_utils::formatting::format_array;
 use miden_objects::{
+    accounts::AccountId,
     block::Block,
     notes::{NoteId, Nullifier},
     transaction::OutputNote,
@@ -10,13 +11,15 @@ use miden_objects::{
 };
 use miden_tx::TransactionVerifier;
 use tokio::sync::RwLock;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 
 use self::account_state::InflightAccountStates;
 use crate::{
-    errors::VerifyTxError,
+    block_builder::BlockStageDurations,
+    errors::{AccountStateError, VerifyTxError},
+    reference_blocks::ReferenceBlocks,
     store::{ApplyBlock, ApplyBlockError, Store, TransactionInputs},
-    txqueue::TransactionValidator,
+    txqueue::{ExpectedAccountState, TransactionValidator},
     ProvenTransaction, COMPONENT,
 };
 
@@ -39,6 +42,9 @@ pub struct DefaultStateView<S> {
 
     /// The output notes of transactions currently in the block production pipeline.
     notes_in_flight: Arc<RwLock<BTreeSet<NoteId>>>,
+
+    /// The reference blocks of the verified transactions, until they are batched or reverted.
+    reference_blocks: Arc<ReferenceBlocks>,
 }
 
 impl<S> DefaultStateView<S>
@@ -52,8 +58,15 @@ where
             accounts_in_flight: Default::default(),
             nullifiers_in_flight: Default::default(),
             notes_in_flight: Default::default(),
+            reference_blocks: Default::default(),
         }
     }
+
+    /// Records the reference blocks of the verified transactions in `reference_blocks`.
+    pub fn with_reference_blocks(mut self, reference_blocks: Arc<ReferenceBlocks>) -> Self {
+        self.reference_blocks = reference_blocks;
+        self
+    }
 }
 
 #[async_trait]
@@ -93,6 +106,7 @@ where
         let mut tx_inputs = self.store.get_tx_inputs(candidate_tx).await?;
 
         let current_block_height = tx_inputs.current_block_height;
+        let reference_block_num = tx_inputs.reference_block_num;
 
         // The latest inflight account state takes precedence since this is the current block being
         // constructed.
@@ -129,8 +143,71 @@ where
             locked_notes_in_flight.extend(candidate_tx.output_notes().iter().map(OutputNote::id));
         }
 
+        if let Some(reference_block_num) = reference_block_num {
+            self.reference_blocks.insert(candidate_tx.id(), reference_block_num);
+        }
+
         Ok(current_block_height)
     }
+
+    #[instrument(target = "miden-block-producer", skip_all)]
+    async fn revert_transactions(&self, txs: &[ProvenTransaction]) {
+        let mut locked_accounts_in_flight = self.accounts_in_flight.write().await;
+        let mut locked_nullifiers_in_flight = self.nullifiers_in_flight.write().await;
+        let mut locked_notes_in_flight = self.notes_in_flight.write().await;
+
+        // Revert in reverse order, so that the account states are unwound from the latest one
+        for tx in txs.iter().rev() {
+            let reverted = locked_accounts_in_flight
+                .revert(tx.account_id(), tx.account_update().final_state_hash());
+            if reverted.is_err() {
+                warn!(
+                    target: COMPONENT,
+                    tx_id = %tx.id().to_hex(),
+                    account_id = %tx.account_id().to_hex(),
+                    "Reverted transaction is not the latest in-flight update of its account"
+                );
+            }
+
+            for nullifier in tx.get_nullifiers() {
+                locked_nullifiers_in_flight.remove(&nullifier);
+            }
+            for note in tx.output_notes().iter() {
+                locked_notes_in_flight.remove(&note.id());
+            }
+        }
+
+        self.reference_blocks.remove(txs.iter().map(ProvenTransaction::id));
+    }
+
+    #[instrument(target = "miden-block-producer", skip_all, err)]
+    async fn get_expected_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<ExpectedAccountState, AccountStateError> {
+        // Hold the lock while querying the store, so that the account can't become in-flight in
+        // the meantime
+        let locked_accounts_in_flight = self.accounts_in_flight.read().await;
+
+        let committed = self.store.get_committed_account_state(account_id).await?;
+
+        let expected = match locked_accounts_in_flight.get(account_id) {
+            Some(final_state) => ExpectedAccountState {
+                account_hash: Some(*final_state),
+                in_flight: true,
+                block_height: committed.block_num,
+            },
+            None => ExpectedAccountState {
+                account_hash: committed.account_hash,
+                in_flight: false,
+                block_height: committed.block_num,
+            },
+        };
+
+        debug!(target: COMPONENT, account_id = %account_id.to_hex(), ?expected);
+
+        Ok(expected)
+    }
 }
 
 #[async_trait]
@@ -139,8 +216,12 @@ where
     S: Store,
 {
     #[instrument(target = "miden-block-producer", skip_all, err)]
-    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
-        self.store.apply_block(block).await?;
+    async fn apply_block(
+        &self,
+        block: &Block,
+        stage_durations: Option<BlockStageDurations>,
+    ) -> Result<(), ApplyBlockError> {
+        self.store.apply_block(block, stage_durations).await?;
 
         let mut locked_accounts_in_flight = self.accounts_in_flight.write().await;
         let mut locked_nullifiers_in_flight = self.nullifiers_in_flight.write().await;
@@ -232,6 +313,14 @@ fn ensure_tx_inputs_constraints(
 ) -> Result<Vec<NoteId>, VerifyTxError> {
     debug!(target: COMPONENT, %tx_inputs);
 
+    // the transaction must have been executed against a block of this chain
+    if tx_inputs.reference_block_num.is_none() {
+        return Err(VerifyTxError::UnknownReferenceBlock {
+            block_ref: candidate_tx.block_ref(),
+            chain_tip: tx_inputs.current_block_height,
+        });
+    }
+
     match tx_inputs.account_hash {
         // if the account is present in the Store, make sure that the account state hash
         // from the received transaction is the same as the one from the Store
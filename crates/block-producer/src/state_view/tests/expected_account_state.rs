@@ -0,0 +1,127 @@
+//! Requirements for `get_expected_account_state()`:
+//!
+//! EAS1: the final state of the latest in-flight transaction of the account is returned
+//! EAS2: the committed state is returned when the account has no in-flight transactions
+//! EAS3: transactions built on top of the returned state can be chained before being committed
+
+use std::iter;
+
+use miden_objects::{accounts::delta::AccountUpdateDetails, block::BlockAccountUpdate};
+
+use super::*;
+use crate::test_utils::{block::MockBlockBuilder, MockStoreSuccessBuilder};
+
+fn account_update(account: &MockPrivateAccount<3>, tx: &ProvenTransaction) -> BlockAccountUpdate {
+    BlockAccountUpdate::new(
+        account.id,
+        tx.account_update().final_state_hash(),
+        AccountUpdateDetails::Private,
+        vec![tx.id()],
+    )
+}
+
+/// Tests requirements EAS1, EAS2 and EAS3
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_expected_account_state_chains_transactions() {
+    let account: MockPrivateAccount<3> = MockPrivateAccount::from(0);
+
+    let store = Arc::new(
+        MockStoreSuccessBuilder::from_accounts(iter::once((account.id, account.states[0]))).build(),
+    );
+    let state_view = DefaultStateView::new(store.clone(), false);
+
+    let expected = state_view.get_expected_account_state(account.id).await.unwrap();
+    assert_eq!(
+        expected,
+        ExpectedAccountState {
+            account_hash: Some(account.states[0]),
+            in_flight: false,
+            block_height: 1,
+        }
+    );
+
+    // Build the first transaction on top of the committed state
+    let tx1 = MockProvenTxBuilder::with_account(
+        account.id,
+        expected.account_hash.unwrap(),
+        account.states[1],
+    )
+    .build();
+    assert_eq!(state_view.verify_tx(&tx1).await, Ok(0));
+
+    // Chain the second transaction on top of the in-flight one
+    let expected = state_view.get_expected_account_state(account.id).await.unwrap();
+    assert_eq!(
+        expected,
+        ExpectedAccountState {
+            account_hash: Some(account.states[1]),
+            in_flight: true,
+            block_height: 1,
+        }
+    );
+
+    let tx2 = MockProvenTxBuilder::with_account(
+        account.id,
+        expected.account_hash.unwrap(),
+        account.states[2],
+    )
+    .build();
+    assert_eq!(state_view.verify_tx(&tx2).await, Ok(0));
+
+    // Both transactions are committed in consecutive blocks
+    let block1 = MockBlockBuilder::new(&store)
+        .await
+        .account_updates(vec![account_update(&account, &tx1)])
+        .build();
+    state_view.apply_block(&block1, None).await.unwrap();
+
+    let expected = state_view.get_expected_account_state(account.id).await.unwrap();
+    assert_eq!(
+        expected,
+        ExpectedAccountState {
+            account_hash: Some(account.states[2]),
+            in_flight: true,
+            block_height: 2,
+        }
+    );
+
+    let block2 = MockBlockBuilder::new(&store)
+        .await
+        .account_updates(vec![account_update(&account, &tx2)])
+        .build();
+    state_view.apply_block(&block2, None).await.unwrap();
+
+    assert_eq!(block2.header().block_num(), block1.header().block_num() + 1);
+    assert_eq!(*store.num_apply_block_called.read().await, 2);
+
+    let expected = state_view.get_expected_account_state(account.id).await.unwrap();
+    assert_eq!(
+        expected,
+        ExpectedAccountState {
+            account_hash: Some(account.states[2]),
+            in_flight: false,
+            block_height: 3,
+        }
+    );
+}
+
+/// Tests requirement EAS2 for accounts which don't exist yet
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_expected_account_state_of_unknown_account() {
+    let account: MockPrivateAccount<3> = MockPrivateAccount::from(0);
+
+    let store = Arc::new(MockStoreSuccessBuilder::from_accounts(iter::empty()).build());
+    let state_view = DefaultStateView::new(store, false);
+
+    let expected = state_view.get_expected_account_state(account.id).await.unwrap();
+    assert_eq!(
+        expected,
+        ExpectedAccountState {
+            account_hash: None,
+            in_flight: false,
+            block_height: 1,
+        }
+    );
+}
@@ -1,28 +1,14 @@
-use miden_objects::{Hasher, EMPTY_WORD, ZERO};
+use miden_node_test_utils::{MockPrivateAccount, MockProvenTxBuilder};
 
 use super::*;
-use crate::test_utils::{MockPrivateAccount, MockProvenTxBuilder};
 
 mod apply_block;
+mod expected_account_state;
 mod verify_tx;
 
 // HELPERS
 // -------------------------------------------------------------------------------------------------
 
-pub fn nullifier_by_index(index: u32) -> Nullifier {
-    Nullifier::new(
-        Hasher::hash(&index.to_be_bytes()),
-        Hasher::hash(
-            &[index.to_be_bytes(), index.to_be_bytes()]
-                .into_iter()
-                .flatten()
-                .collect::<Vec<_>>(),
-        ),
-        EMPTY_WORD.into(),
-        [ZERO, ZERO, ZERO, index.into()],
-    )
-}
-
 /// Returns `num` transactions, and the corresponding account they modify.
 /// The transactions each consume a single different note
 pub fn get_txs_and_accounts(
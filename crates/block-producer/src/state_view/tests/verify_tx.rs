@@ -9,14 +9,19 @@
 //! VT4: In each block, at most 1 transaction is allowed to modify any given account
 //! VT5: `verify_tx(tx)` must fail if a previous transaction, not yet in the block, consumed a note
 //!      that `tx` is also consuming
+//! VT6: `verify_tx(tx)` must fail before querying the store if a transaction of the block being
+//!      applied consumed a note that `tx` is also consuming
 
 use std::iter;
 
-use miden_objects::notes::Note;
+use miden_node_test_utils::note::{mock_note, mock_nullifier};
+use miden_objects::{
+    accounts::delta::AccountUpdateDetails, block::BlockAccountUpdate, notes::Note, ONE,
+};
 use tokio::task::JoinSet;
 
 use super::*;
-use crate::test_utils::{block::MockBlockBuilder, note::mock_note, MockStoreSuccessBuilder};
+use crate::test_utils::{block::MockBlockBuilder, FaultyStore, MockStoreSuccessBuilder, StoreCall};
 
 /// Tests the happy path where 3 transactions who modify different accounts and consume different
 /// notes all verify successfully
@@ -134,7 +139,7 @@ async fn test_verify_tx_vt2() {
 async fn test_verify_tx_vt3() {
     let account: MockPrivateAccount<3> = MockPrivateAccount::from(1);
 
-    let nullifier_in_store = nullifier_by_index(0);
+    let nullifier_in_store = mock_nullifier(0);
 
     // Notice: `consumed_note_in_store` is added to the store
     let store = Arc::new(
@@ -191,7 +196,7 @@ async fn test_verify_tx_vt4() {
 async fn test_verify_tx_vt5() {
     let account_1: MockPrivateAccount<3> = MockPrivateAccount::from(1);
     let account_2: MockPrivateAccount<3> = MockPrivateAccount::from(2);
-    let nullifier_in_both_txs = nullifier_by_index(0);
+    let nullifier_in_both_txs = mock_nullifier(0);
 
     // Notice: `consumed_note_in_both_txs` is NOT in the store
     let store = Arc::new(
@@ -227,6 +232,75 @@ async fn test_verify_tx_vt5() {
     );
 }
 
+/// Verifies requirement VT6
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_verify_tx_vt6() {
+    let account_1: MockPrivateAccount<3> = MockPrivateAccount::from(1);
+    let account_2: MockPrivateAccount<3> = MockPrivateAccount::from(2);
+    let nullifier_in_both_txs = mock_nullifier(0);
+
+    let store = Arc::new(FaultyStore::new(
+        MockStoreSuccessBuilder::from_accounts(
+            [account_1, account_2]
+                .into_iter()
+                .map(|account| (account.id, account.states[0])),
+        )
+        .build(),
+    ));
+
+    let tx1 =
+        MockProvenTxBuilder::with_account(account_1.id, account_1.states[0], account_1.states[1])
+            .nullifiers(vec![nullifier_in_both_txs])
+            .build();
+    let tx2 =
+        MockProvenTxBuilder::with_account(account_2.id, account_2.states[0], account_2.states[1])
+            .nullifiers(vec![nullifier_in_both_txs])
+            .build();
+
+    let state_view = Arc::new(DefaultStateView::new(store.clone(), false));
+    state_view.verify_tx(&tx1).await.unwrap();
+
+    let block = MockBlockBuilder::new(store.inner())
+        .await
+        .account_updates(vec![BlockAccountUpdate::new(
+            account_1.id,
+            account_1.states[1],
+            AccountUpdateDetails::Private,
+            vec![tx1.id()],
+        )])
+        .produced_nullifiers(vec![nullifier_in_both_txs])
+        .build();
+
+    // Keep the block in progress while tx2 is submitted
+    let apply_block_gate = store.hold(StoreCall::ApplyBlock);
+    let apply_block = tokio::spawn({
+        let state_view = Arc::clone(&state_view);
+        async move { state_view.apply_block(&block, None).await }
+    });
+    apply_block_gate.entered().await;
+
+    let verify_tx2_result = state_view.verify_tx(&tx2).await;
+    assert_eq!(
+        verify_tx2_result,
+        Err(VerifyTxError::InputNotesAlreadyConsumed(vec![nullifier_in_both_txs]))
+    );
+    assert_eq!(
+        store.calls(),
+        [StoreCall::GetTxInputs, StoreCall::ApplyBlock],
+        "tx2 must be rejected without querying the store"
+    );
+
+    // Once the block is applied, the note is consumed in the store
+    apply_block_gate.release();
+    apply_block.await.unwrap().unwrap();
+    let verify_tx2_result = state_view.verify_tx(&tx2).await;
+    assert_eq!(
+        verify_tx2_result,
+        Err(VerifyTxError::InputNotesAlreadyConsumed(vec![nullifier_in_both_txs]))
+    );
+}
+
 /// Tests that `verify_tx()` succeeds when the unauthenticated input note found in the in-flight
 /// notes
 #[tokio::test]
@@ -295,7 +369,7 @@ async fn test_verify_tx_stored_unauthenticated_notes() {
     let output_notes = dangling_notes.into_iter().map(OutputNote::Full).collect();
     let block = MockBlockBuilder::new(&store).await.created_notes(vec![output_notes]).build();
 
-    store.apply_block(&block).await.unwrap();
+    store.apply_block(&block, None).await.unwrap();
 
     let verify_tx1_result = state_view.verify_tx(&tx1).await;
     assert_eq!(
@@ -304,3 +378,58 @@ async fn test_verify_tx_stored_unauthenticated_notes() {
         "Dangling unauthenticated notes must be found in the store after block applying"
     );
 }
+
+/// Tests that `verify_tx()` rejects transactions executed against a block of another chain
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_verify_tx_reference_block_from_another_chain() {
+    let account: MockPrivateAccount<3> = MockPrivateAccount::from(1);
+    let store = Arc::new(
+        MockStoreSuccessBuilder::from_accounts(iter::once((account.id, account.states[0]))).build(),
+    );
+
+    let foreign_block_ref = Digest::from([ONE, ONE, ONE, ONE]);
+    let tx = MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1])
+        .block_ref(foreign_block_ref)
+        .build();
+
+    let state_view = DefaultStateView::new(store, false);
+
+    let verify_tx_result = state_view.verify_tx(&tx).await;
+    assert_eq!(
+        verify_tx_result,
+        Err(VerifyTxError::UnknownReferenceBlock {
+            block_ref: foreign_block_ref,
+            chain_tip: 0
+        })
+    );
+}
+
+/// Tests that `verify_tx()` only accepts transactions referencing a block once the block is
+/// committed
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_verify_tx_reference_block_beyond_chain_tip() {
+    let account: MockPrivateAccount<3> = MockPrivateAccount::from(1);
+    let store = Arc::new(
+        MockStoreSuccessBuilder::from_accounts(iter::once((account.id, account.states[0]))).build(),
+    );
+
+    let next_block = MockBlockBuilder::new(&store).await.build();
+    let tx = MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1])
+        .block_ref(next_block.hash())
+        .build();
+
+    let state_view = DefaultStateView::new(Arc::clone(&store), false);
+
+    let verify_tx_result = state_view.verify_tx(&tx).await;
+    assert!(
+        matches!(verify_tx_result, Err(VerifyTxError::UnknownReferenceBlock { block_ref, .. }) if block_ref == next_block.hash()),
+        "A block which is not committed yet must not be a valid reference block"
+    );
+
+    store.apply_block(&next_block, None).await.unwrap();
+
+    let verify_tx_result = state_view.verify_tx(&tx).await;
+    assert_eq!(verify_tx_result, Ok(0));
+}
@@ -2,6 +2,7 @@ use std::{
     collections::BTreeMap,
     fmt::{Display, Formatter},
     num::NonZeroU32,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use async_trait::async_trait;
@@ -10,30 +11,45 @@ use miden_node_proto::{
     domain::notes::NoteAuthenticationInfo,
     errors::{ConversionError, MissingFieldHelper},
     generated::{
+        account::AccountSummary,
+        block::BlockProducerMetadata,
         digest,
         requests::{
-            ApplyBlockRequest, GetBlockInputsRequest, GetNoteAuthenticationInfoRequest,
+            ApplyBlockRequest, GetAccountCommitmentsRequest, GetBlockHeaderByNumberRequest,
+            GetBlockInputsRequest, GetDataAvailabilityRequest, GetNoteAuthenticationInfoRequest,
             GetTransactionInputsRequest,
         },
-        responses::{GetTransactionInputsResponse, NullifierTransactionInputRecord},
+        responses::{
+            GetBlockHeaderByNumberResponse, GetTransactionInputsResponse,
+            NullifierTransactionInputRecord,
+        },
         store::api_client as store_client,
     },
     AccountState,
 };
-use miden_node_utils::formatting::format_opt;
+use miden_node_utils::{formatting::format_opt, request_id::RequestIdChannel};
 use miden_objects::{
     accounts::AccountId,
     block::Block,
     notes::{NoteId, Nullifier},
     utils::Serializable,
-    Digest,
+    BlockHeader, Digest,
 };
 use miden_processor::crypto::RpoDigest;
-use tonic::transport::Channel;
-use tracing::{debug, info, instrument};
+use tonic::{Code, Status};
+use tracing::{debug, info, instrument, warn};
 
-pub use crate::errors::{ApplyBlockError, BlockInputsError, TxInputsError};
-use crate::{block::BlockInputs, errors::NotePathsError, ProvenTransaction, COMPONENT};
+pub use crate::errors::{
+    AccountStateError, ApplyBlockError, BlockHeaderError, BlockInputsError, DataAvailabilityError,
+    TxInputsError,
+};
+use crate::{
+    block::{BlockInputs, BlockInputsPages},
+    block_builder::BlockStageDurations,
+    errors::NotePathsError,
+    ProvenTransaction, APPLY_BLOCK_MAX_ATTEMPTS, APPLY_BLOCK_RETRY_DELAY,
+    BLOCK_INPUTS_MAX_ATTEMPTS, COMPONENT,
+};
 
 // STORE TRAIT
 // ================================================================================================
@@ -62,11 +78,42 @@ pub trait Store: ApplyBlock {
         &self,
         notes: impl Iterator<Item = &NoteId> + Send,
     ) -> Result<NoteAuthenticationInfo, NotePathsError>;
+
+    /// Returns the header of the latest block known to the store, i.e. the current chain tip.
+    async fn get_latest_block_header(&self) -> Result<BlockHeader, BlockHeaderError>;
+
+    /// Returns the committed state of an account, together with the current chain tip.
+    async fn get_committed_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<CommittedAccountState, AccountStateError>;
+
+    /// Returns the number of most recent blocks whose data the store retains, `None` if the data
+    /// of old blocks is not pruned.
+    async fn get_block_retention_depth(&self) -> Result<Option<u32>, DataAvailabilityError>;
 }
 
 #[async_trait]
 pub trait ApplyBlock: Send + Sync + 'static {
-    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError>;
+    /// Applies the block to the store, reporting the durations of the stages of building it if
+    /// they were measured.
+    async fn apply_block(
+        &self,
+        block: &Block,
+        stage_durations: Option<BlockStageDurations>,
+    ) -> Result<(), ApplyBlockError>;
+}
+
+// COMMITTED ACCOUNT STATE
+// ================================================================================================
+
+/// State of an account as of the latest block committed to the store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommittedAccountState {
+    /// Hash of the account state, `None` if the account is not in the store.
+    pub account_hash: Option<Digest>,
+    /// Number of the latest block.
+    pub block_num: u32,
 }
 
 // TRANSACTION INPUTS
@@ -87,6 +134,8 @@ pub struct TransactionInputs {
     pub missing_unauthenticated_notes: Vec<NoteId>,
     /// The current block height
     pub current_block_height: u32,
+    /// Number of the transaction's reference block, `None` if the block is not part of the chain
+    pub reference_block_num: Option<u32>,
 }
 
 impl Display for TransactionInputs {
@@ -104,10 +153,11 @@ impl Display for TransactionInputs {
         };
 
         f.write_fmt(format_args!(
-            "{{ account_id: {}, account_hash: {}, nullifiers: {} }}",
+            "{{ account_id: {}, account_hash: {}, nullifiers: {}, reference_block_num: {} }}",
             self.account_id,
             format_opt(self.account_hash.as_ref()),
-            nullifiers
+            nullifiers,
+            format_opt(self.reference_block_num.as_ref())
         ))
     }
 }
@@ -147,6 +197,7 @@ impl TryFrom<GetTransactionInputsResponse> for TransactionInputs {
             nullifiers,
             missing_unauthenticated_notes,
             current_block_height,
+            reference_block_num: response.reference_block_num,
         })
     }
 }
@@ -155,30 +206,138 @@ impl TryFrom<GetTransactionInputsResponse> for TransactionInputs {
 // ================================================================================================
 
 pub struct DefaultStore {
-    store: store_client::ApiClient<Channel>,
+    store: store_client::ApiClient<RequestIdChannel>,
+    /// Identifier reported to the store along with the applied blocks.
+    producer_id: Option<String>,
 }
 
 impl DefaultStore {
     /// TODO: this should probably take store connection string and create a connection internally
-    pub fn new(store: store_client::ApiClient<Channel>) -> Self {
-        Self { store }
+    pub fn new(store: store_client::ApiClient<RequestIdChannel>) -> Self {
+        Self { store, producer_id: None }
     }
-}
 
-#[async_trait]
-impl ApplyBlock for DefaultStore {
-    #[instrument(target = "miden-block-producer", skip_all, err)]
-    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
-        let request = tonic::Request::new(ApplyBlockRequest { block: block.to_bytes() });
+    /// Reports the identifier and the submission attempt along with every applied block.
+    pub fn with_producer_id(mut self, producer_id: String) -> Self {
+        self.producer_id = Some(producer_id);
+        self
+    }
+
+    /// Returns the commitment of the genesis block of the store.
+    pub async fn get_genesis_commitment(&self) -> Result<Digest, BlockHeaderError> {
+        Ok(self.get_block_header(Some(0)).await?.hash())
+    }
 
-        let _ = self
+    /// Returns the header of the block `block_num`, or of the latest block if [None].
+    async fn get_block_header(
+        &self,
+        block_num: Option<u32>,
+    ) -> Result<BlockHeader, BlockHeaderError> {
+        let request = tonic::Request::new(GetBlockHeaderByNumberRequest {
+            block_num,
+            include_mmr_proof: None,
+        });
+
+        let store_response = self
+            .store
+            .clone()
+            .get_block_header_by_number(request)
+            .await
+            .map_err(|err| BlockHeaderError::GrpcClientError(err.message().to_string()))?
+            .into_inner();
+
+        let block_header = store_response
+            .block_header
+            .ok_or(GetBlockHeaderByNumberResponse::missing_field("block_header"))?
+            .try_into()?;
+
+        Ok(block_header)
+    }
+
+    /// Fetches all the pages of the block inputs of `request`.
+    async fn fetch_block_inputs_pages(
+        &self,
+        request: GetBlockInputsRequest,
+    ) -> Result<BlockInputsPages, BlockInputsError> {
+        let grpc_error =
+            |status: Status| BlockInputsError::GrpcClientError(status.message().into());
+
+        let first_page = self
             .store
             .clone()
-            .apply_block(request)
+            .get_block_inputs(tonic::Request::new(request))
             .await
-            .map_err(|status| ApplyBlockError::GrpcClientError(status.message().to_string()))?;
+            .map_err(grpc_error)?
+            .into_inner();
+
+        let mut pages = BlockInputsPages::new(first_page);
+        while let Some(cursor) = pages.next() {
+            let request = tonic::Request::new(GetBlockInputsRequest {
+                cursor: Some(cursor),
+                ..Default::default()
+            });
+            let page = match self.store.clone().get_block_inputs(request).await {
+                Ok(page) => page.into_inner(),
+                Err(status) if status.code() == Code::NotFound => {
+                    return Err(BlockInputsError::SnapshotExpired)
+                },
+                Err(status) => return Err(grpc_error(status)),
+            };
+            pages.append(page)?;
+        }
 
-        Ok(())
+        Ok(pages)
+    }
+}
+
+#[async_trait]
+impl ApplyBlock for DefaultStore {
+    #[instrument(target = "miden-block-producer", skip_all, err)]
+    async fn apply_block(
+        &self,
+        block: &Block,
+        stage_durations: Option<BlockStageDurations>,
+    ) -> Result<(), ApplyBlockError> {
+        let block_data = block.to_bytes();
+        let produced_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX));
+
+        // The store ignores resubmissions of committed blocks, so submissions whose outcome is
+        // unknown can be retried
+        let mut attempt = 1;
+        loop {
+            let request = tonic::Request::new(ApplyBlockRequest {
+                block: block_data.clone(),
+                stage_durations: stage_durations.map(Into::into),
+                producer: self.producer_id.clone().map(|producer_id| BlockProducerMetadata {
+                    producer_id,
+                    attempt,
+                    produced_at,
+                }),
+            });
+
+            match self.store.clone().apply_block(request).await {
+                Ok(_) => return Ok(()),
+                Err(status)
+                    if attempt < APPLY_BLOCK_MAX_ATTEMPTS
+                        && matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded) =>
+                {
+                    warn!(
+                        target: COMPONENT,
+                        block_num = block.header().block_num(),
+                        attempt,
+                        %status,
+                        "Failed to submit the block, retrying"
+                    );
+                    tokio::time::sleep(APPLY_BLOCK_RETRY_DELAY).await;
+                    attempt += 1;
+                },
+                Err(status) => {
+                    return Err(ApplyBlockError::GrpcClientError(status.message().to_string()))
+                },
+            }
+        }
     }
 }
 
@@ -196,6 +355,7 @@ impl Store for DefaultStore {
                 .get_unauthenticated_notes()
                 .map(|note| note.id().into())
                 .collect(),
+            reference_block: Some(proven_tx.block_ref().into()),
         };
 
         info!(target: COMPONENT, tx_id = %proven_tx.id().to_hex());
@@ -233,21 +393,23 @@ impl Store for DefaultStore {
         produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
         notes: impl Iterator<Item = &NoteId> + Send,
     ) -> Result<BlockInputs, BlockInputsError> {
-        let request = tonic::Request::new(GetBlockInputsRequest {
+        let request = GetBlockInputsRequest {
             account_ids: updated_accounts.map(Into::into).collect(),
             nullifiers: produced_nullifiers.map(digest::Digest::from).collect(),
             unauthenticated_notes: notes.map(digest::Digest::from).collect(),
-        });
-
-        let store_response = self
-            .store
-            .clone()
-            .get_block_inputs(request)
-            .await
-            .map_err(|err| BlockInputsError::GrpcClientError(err.message().to_string()))?
-            .into_inner();
+            cursor: None,
+        };
 
-        Ok(store_response.try_into()?)
+        let mut attempt = 1;
+        loop {
+            match self.fetch_block_inputs_pages(request.clone()).await {
+                Err(BlockInputsError::SnapshotExpired) if attempt < BLOCK_INPUTS_MAX_ATTEMPTS => {
+                    warn!(target: COMPONENT, attempt, "Block inputs snapshot expired, refetching");
+                    attempt += 1;
+                },
+                pages => return pages?.try_into(),
+            }
+        }
     }
 
     async fn get_note_authentication_info(
@@ -273,4 +435,57 @@ impl Store for DefaultStore {
 
         Ok(note_authentication_info)
     }
+
+    async fn get_latest_block_header(&self) -> Result<BlockHeader, BlockHeaderError> {
+        self.get_block_header(None).await
+    }
+
+    #[instrument(target = "miden-block-producer", skip_all, err)]
+    async fn get_committed_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<CommittedAccountState, AccountStateError> {
+        // Only the commitment is needed, so the account proof and details aren't requested
+        let request = tonic::Request::new(GetAccountCommitmentsRequest {
+            account_ids: vec![account_id.into()],
+        });
+
+        let store_response = self
+            .store
+            .clone()
+            .get_account_commitments(request)
+            .await
+            .map_err(|err| AccountStateError::GrpcClientError(err.message().to_string()))?
+            .into_inner();
+
+        // Accounts which are not in the store are omitted from the response
+        let account_hash = store_response
+            .accounts
+            .into_iter()
+            .next()
+            .map(|summary| -> Result<Digest, ConversionError> {
+                summary
+                    .account_hash
+                    .ok_or(AccountSummary::missing_field("account_hash"))?
+                    .try_into()
+            })
+            .transpose()?;
+
+        Ok(CommittedAccountState {
+            account_hash,
+            block_num: store_response.block_num,
+        })
+    }
+    #[instrument(target = "miden-block-producer", skip_all, err)]
+    async fn get_block_retention_depth(&self) -> Result<Option<u32>, DataAvailabilityError> {
+        let store_response = self
+            .store
+            .clone()
+            .get_data_availability(tonic::Request::new(GetDataAvailabilityRequest {}))
+            .await
+            .map_err(|err| DataAvailabilityError::GrpcClientError(err.message().to_string()))?
+            .into_inner();
+
+        Ok(store_response.block_retention_depth)
+    }
 }
@@ -1,10 +1,12 @@
 use std::iter;
 
+pub(crate) use miden_node_test_utils::block::{
+    flatten_output_notes, note_created_smt_from_note_batches,
+};
 use miden_objects::{
-    block::{Block, BlockAccountUpdate, BlockNoteIndex, BlockNoteTree, NoteBatch},
+    block::{Block, BlockAccountUpdate, NoteBatch},
     crypto::merkle::{Mmr, SimpleSmt},
     notes::Nullifier,
-    transaction::OutputNote,
     BlockHeader, Digest, ACCOUNT_TREE_DEPTH,
 };
 
@@ -177,25 +179,6 @@ impl MockBlockBuilder {
     }
 }
 
-pub(crate) fn flatten_output_notes<'a>(
-    batches: impl Iterator<Item = &'a NoteBatch>,
-) -> impl Iterator<Item = (BlockNoteIndex, &'a OutputNote)> {
-    batches.enumerate().flat_map(|(batch_idx, batch)| {
-        batch.iter().enumerate().map(move |(note_idx_in_batch, note)| {
-            (BlockNoteIndex::new(batch_idx, note_idx_in_batch).unwrap(), note)
-        })
-    })
-}
-
-pub(crate) fn note_created_smt_from_note_batches<'a>(
-    batches: impl Iterator<Item = &'a NoteBatch>,
-) -> BlockNoteTree {
-    let note_leaf_iterator =
-        flatten_output_notes(batches).map(|(index, note)| (index, note.id(), *note.metadata()));
-
-    BlockNoteTree::with_entries(note_leaf_iterator).unwrap()
-}
-
 pub(crate) fn block_output_notes<'a>(
     batches: impl Iterator<Item = &'a TransactionBatch> + Clone,
 ) -> impl Iterator<Item = &'a NoteBatch> + Clone {
@@ -0,0 +1,245 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use miden_node_proto::domain::notes::NoteAuthenticationInfo;
+use miden_objects::{
+    accounts::AccountId,
+    block::Block,
+    notes::{NoteId, Nullifier},
+    BlockHeader,
+};
+use tokio::sync::Notify;
+
+use crate::{
+    block::BlockInputs,
+    block_builder::BlockStageDurations,
+    errors::NotePathsError,
+    store::{
+        AccountStateError, ApplyBlock, ApplyBlockError, BlockHeaderError, BlockInputsError,
+        CommittedAccountState, DataAvailabilityError, Store, TransactionInputs, TxInputsError,
+    },
+    ProvenTransaction,
+};
+
+/// A store method called by the block producer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StoreCall {
+    GetTxInputs,
+    GetBlockInputs,
+    GetNoteAuthenticationInfo,
+    GetLatestBlockHeader,
+    GetCommittedAccountState,
+    GetBlockRetentionDepth,
+    ApplyBlock,
+}
+
+/// Failures injected into the calls to a store method.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Failures {
+    #[default]
+    None,
+    /// The given number of upcoming calls fail.
+    Next(usize),
+    Always,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Faults {
+    failures: Failures,
+    latency: Duration,
+    gate: Option<Arc<CallGate>>,
+}
+
+/// Holds the calls to a store method until the test releases them, see [FaultyStore::hold].
+#[derive(Debug, Default)]
+pub struct CallGate {
+    entered: Notify,
+    released: Notify,
+}
+
+impl CallGate {
+    /// Waits until a call reached the gate.
+    pub async fn entered(&self) {
+        self.entered.notified().await;
+    }
+
+    /// Lets one held call proceed, or the next one if no call is held yet.
+    pub fn release(&self) {
+        self.released.notify_one();
+    }
+}
+
+/// Wraps a store, injecting failures and latencies into the calls to its methods.
+///
+/// Every call is recorded, including the failed ones, so that tests can assert on the requests
+/// the block producer made. Calls which don't fail are served by the wrapped store.
+#[derive(Debug)]
+pub struct FaultyStore<S> {
+    inner: S,
+    faults: Mutex<BTreeMap<StoreCall, Faults>>,
+    calls: Mutex<Vec<StoreCall>>,
+}
+
+impl<S> FaultyStore<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            faults: Mutex::default(),
+            calls: Mutex::default(),
+        }
+    }
+
+    /// Returns the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Fails the next `num_calls` calls to the method `call`.
+    pub fn fail_next(&self, call: StoreCall, num_calls: usize) {
+        self.faults.lock().unwrap().entry(call).or_default().failures = Failures::Next(num_calls);
+    }
+
+    /// Fails every call to the method `call`.
+    pub fn fail_always(&self, call: StoreCall) {
+        self.faults.lock().unwrap().entry(call).or_default().failures = Failures::Always;
+    }
+
+    /// Delays every call to the method `call` by `latency`, whether it fails or not.
+    pub fn delay(&self, call: StoreCall, latency: Duration) {
+        self.faults.lock().unwrap().entry(call).or_default().latency = latency;
+    }
+
+    /// Holds every call to the method `call` until released through the returned gate, before
+    /// its latency and failure are applied.
+    pub fn hold(&self, call: StoreCall) -> Arc<CallGate> {
+        let gate = Arc::new(CallGate::default());
+        self.faults.lock().unwrap().entry(call).or_default().gate = Some(Arc::clone(&gate));
+        gate
+    }
+
+    /// Returns the calls made so far, in order.
+    pub fn calls(&self) -> Vec<StoreCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Records the call, waits for its gate to be released, applies its latency, and returns an
+    /// error message if it must fail.
+    async fn enter(&self, call: StoreCall) -> Result<(), String> {
+        self.calls.lock().unwrap().push(call);
+
+        let gate = self.faults.lock().unwrap().get(&call).and_then(|faults| faults.gate.clone());
+        if let Some(gate) = gate {
+            gate.entered.notify_one();
+            gate.released.notified().await;
+        }
+
+        let (latency, fail) = {
+            let mut faults = self.faults.lock().unwrap();
+            let faults = faults.entry(call).or_default();
+            let fail = match faults.failures {
+                Failures::None | Failures::Next(0) => false,
+                Failures::Next(remaining) => {
+                    faults.failures = Failures::Next(remaining - 1);
+                    true
+                },
+                Failures::Always => true,
+            };
+
+            (faults.latency, fail)
+        };
+
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+
+        if fail {
+            Err(format!("injected failure of {call:?}"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl<S: ApplyBlock> ApplyBlock for FaultyStore<S> {
+    async fn apply_block(
+        &self,
+        block: &Block,
+        stage_durations: Option<BlockStageDurations>,
+    ) -> Result<(), ApplyBlockError> {
+        self.enter(StoreCall::ApplyBlock)
+            .await
+            .map_err(ApplyBlockError::GrpcClientError)?;
+
+        self.inner.apply_block(block, stage_durations).await
+    }
+}
+
+#[async_trait]
+impl<S: Store> Store for FaultyStore<S> {
+    async fn get_tx_inputs(
+        &self,
+        proven_tx: &ProvenTransaction,
+    ) -> Result<TransactionInputs, TxInputsError> {
+        self.enter(StoreCall::GetTxInputs)
+            .await
+            .map_err(TxInputsError::GrpcClientError)?;
+
+        self.inner.get_tx_inputs(proven_tx).await
+    }
+
+    async fn get_block_inputs(
+        &self,
+        updated_accounts: impl Iterator<Item = AccountId> + Send,
+        produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<BlockInputs, BlockInputsError> {
+        self.enter(StoreCall::GetBlockInputs)
+            .await
+            .map_err(BlockInputsError::GrpcClientError)?;
+
+        self.inner.get_block_inputs(updated_accounts, produced_nullifiers, notes).await
+    }
+
+    async fn get_note_authentication_info(
+        &self,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        self.enter(StoreCall::GetNoteAuthenticationInfo)
+            .await
+            .map_err(NotePathsError::GrpcClientError)?;
+
+        self.inner.get_note_authentication_info(notes).await
+    }
+
+    async fn get_latest_block_header(&self) -> Result<BlockHeader, BlockHeaderError> {
+        self.enter(StoreCall::GetLatestBlockHeader)
+            .await
+            .map_err(BlockHeaderError::GrpcClientError)?;
+
+        self.inner.get_latest_block_header().await
+    }
+
+    async fn get_committed_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<CommittedAccountState, AccountStateError> {
+        self.enter(StoreCall::GetCommittedAccountState)
+            .await
+            .map_err(AccountStateError::GrpcClientError)?;
+
+        self.inner.get_committed_account_state(account_id).await
+    }
+
+    async fn get_block_retention_depth(&self) -> Result<Option<u32>, DataAvailabilityError> {
+        self.enter(StoreCall::GetBlockRetentionDepth)
+            .await
+            .map_err(DataAvailabilityError::GrpcClientError)?;
+
+        self.inner.get_block_retention_depth().await
+    }
+}
@@ -1,22 +1,17 @@
 use std::sync::Arc;
 
+pub use miden_node_test_utils::{
+    mock_account_id, mock_proven_tx, note, MockPrivateAccount, MockProvenTxBuilder,
+};
 use miden_objects::{accounts::AccountId, Digest};
 use tokio::sync::RwLock;
 
-mod proven_tx;
-
-pub use proven_tx::{mock_proven_tx, MockProvenTxBuilder};
-
+mod faulty_store;
 mod store;
 
-pub use store::{MockStoreFailure, MockStoreSuccess, MockStoreSuccessBuilder};
-
-mod account;
-
-pub use account::{mock_account_id, MockPrivateAccount};
+pub use faulty_store::{FaultyStore, StoreCall};
+pub use store::{MockStoreSuccess, MockStoreSuccessBuilder};
 
 pub mod block;
 
 pub mod batch;
-
-pub mod note;
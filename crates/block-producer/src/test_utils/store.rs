@@ -17,9 +17,11 @@ use super::*;
 use crate::{
     batch_builder::TransactionBatch,
     block::{AccountWitness, BlockInputs},
+    block_builder::BlockStageDurations,
     errors::NotePathsError,
     store::{
-        ApplyBlock, ApplyBlockError, BlockInputsError, Store, TransactionInputs, TxInputsError,
+        AccountStateError, ApplyBlock, ApplyBlockError, BlockHeaderError, BlockInputsError,
+        CommittedAccountState, DataAvailabilityError, Store, TransactionInputs, TxInputsError,
     },
     test_utils::block::{
         block_output_notes, flatten_output_notes, note_created_smt_from_note_batches,
@@ -35,6 +37,7 @@ pub struct MockStoreSuccessBuilder {
     produced_nullifiers: Option<BTreeSet<Digest>>,
     chain_mmr: Option<Mmr>,
     block_num: Option<u32>,
+    block_retention_depth: Option<u32>,
 }
 
 impl MockStoreSuccessBuilder {
@@ -55,6 +58,7 @@ impl MockStoreSuccessBuilder {
             produced_nullifiers: None,
             chain_mmr: None,
             block_num: None,
+            block_retention_depth: None,
         }
     }
 
@@ -71,6 +75,7 @@ impl MockStoreSuccessBuilder {
             produced_nullifiers: None,
             chain_mmr: None,
             block_num: None,
+            block_retention_depth: None,
         }
     }
 
@@ -98,6 +103,12 @@ impl MockStoreSuccessBuilder {
         self
     }
 
+    pub fn block_retention_depth(mut self, retention_depth: u32) -> Self {
+        self.block_retention_depth = Some(retention_depth);
+
+        self
+    }
+
     pub fn build(self) -> MockStoreSuccess {
         let block_num = self.block_num.unwrap_or(1);
         let accounts_smt = self.accounts.unwrap_or(SimpleSmt::new().unwrap());
@@ -154,7 +165,9 @@ impl MockStoreSuccessBuilder {
                 initial_block_header,
             )]))),
             num_apply_block_called: Default::default(),
+            applied_stage_durations: Default::default(),
             notes: Arc::new(RwLock::new(notes)),
+            block_retention_depth: self.block_retention_depth,
         }
     }
 }
@@ -175,8 +188,14 @@ pub struct MockStoreSuccess {
     /// The number of times `apply_block()` was called
     pub num_apply_block_called: Arc<RwLock<u32>>,
 
+    /// The stage durations reported with every applied block
+    pub applied_stage_durations: Arc<RwLock<Vec<Option<BlockStageDurations>>>>,
+
     /// Maps note id -> note inclusion proof for all created notes
     pub notes: Arc<RwLock<BTreeMap<NoteId, NoteInclusionProof>>>,
+
+    /// Number of most recent blocks whose data is retained, `None` if all blocks are retained
+    pub block_retention_depth: Option<u32>,
 }
 
 impl MockStoreSuccess {
@@ -189,7 +208,11 @@ impl MockStoreSuccess {
 
 #[async_trait]
 impl ApplyBlock for MockStoreSuccess {
-    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+    async fn apply_block(
+        &self,
+        block: &Block,
+        stage_durations: Option<BlockStageDurations>,
+    ) -> Result<(), ApplyBlockError> {
         // Intentionally, we take and hold both locks, to prevent calls to `get_tx_inputs()` from
         // going through while we're updating the store's data structure
         let mut locked_accounts = self.accounts.write().await;
@@ -237,6 +260,7 @@ impl ApplyBlock for MockStoreSuccess {
 
         // update num_apply_block_called
         *self.num_apply_block_called.write().await += 1;
+        self.applied_stage_durations.write().await.push(stage_durations);
 
         Ok(())
     }
@@ -281,12 +305,25 @@ impl Store for MockStoreSuccess {
             })
             .collect();
 
+        // Mock transactions reference the default digest unless built with a reference block,
+        // which resolves to the initial block of the mock chain
+        let locked_headers = self.block_headers.read().await;
+        let reference_block_num = if proven_tx.block_ref() == Digest::default() {
+            locked_headers.keys().next().copied()
+        } else {
+            locked_headers
+                .values()
+                .find(|header| header.hash() == proven_tx.block_ref())
+                .map(BlockHeader::block_num)
+        };
+
         Ok(TransactionInputs {
             account_id: proven_tx.account_id(),
             account_hash,
             nullifiers,
             missing_unauthenticated_notes,
             current_block_height: 0,
+            reference_block_num,
         })
     }
 
@@ -384,40 +421,30 @@ impl Store for MockStoreSuccess {
 
         Ok(NoteAuthenticationInfo { block_proofs, note_proofs })
     }
-}
 
-#[derive(Default)]
-pub struct MockStoreFailure;
+    async fn get_latest_block_header(&self) -> Result<BlockHeader, BlockHeaderError> {
+        let locked_headers = self.block_headers.read().await;
 
-#[async_trait]
-impl ApplyBlock for MockStoreFailure {
-    async fn apply_block(&self, _block: &Block) -> Result<(), ApplyBlockError> {
-        Err(ApplyBlockError::GrpcClientError(String::new()))
+        Ok(*locked_headers.iter().max_by_key(|(block_num, _)| *block_num).unwrap().1)
     }
-}
 
-#[async_trait]
-impl Store for MockStoreFailure {
-    async fn get_tx_inputs(
+    async fn get_committed_account_state(
         &self,
-        _proven_tx: &ProvenTransaction,
-    ) -> Result<TransactionInputs, TxInputsError> {
-        Err(TxInputsError::Dummy)
-    }
+        account_id: AccountId,
+    ) -> Result<CommittedAccountState, AccountStateError> {
+        let locked_accounts = self.accounts.read().await;
+        let locked_headers = self.block_headers.read().await;
 
-    async fn get_block_inputs(
-        &self,
-        _updated_accounts: impl Iterator<Item = AccountId> + Send,
-        _produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
-        _notes: impl Iterator<Item = &NoteId> + Send,
-    ) -> Result<BlockInputs, BlockInputsError> {
-        Err(BlockInputsError::GrpcClientError(String::new()))
+        let account_hash = locked_accounts.get_leaf(&account_id.into());
+        let block_num = *locked_headers.keys().max().unwrap();
+
+        Ok(CommittedAccountState {
+            account_hash: (account_hash != EMPTY_WORD).then(|| account_hash.into()),
+            block_num,
+        })
     }
 
-    async fn get_note_authentication_info(
-        &self,
-        _notes: impl Iterator<Item = &NoteId> + Send,
-    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
-        Err(NotePathsError::GrpcClientError(String::new()))
+    async fn get_block_retention_depth(&self) -> Result<Option<u32>, DataAvailabilityError> {
+        Ok(self.block_retention_depth)
     }
 }
@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tokio::time::{self, Instant, Interval};
+
+/// Upper bound for [TickJitter::percent], so that consecutive ticks can never swap order.
+const MAX_JITTER_PERCENT: u8 = 50;
+
+/// Randomization of the ticks of a [Ticker].
+///
+/// This allows desynchronizing block producers which share the same infrastructure, and would
+/// otherwise all build their batches and blocks at the same instants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TickJitter {
+    /// Maximum random offset of every tick from its slot in the schedule, as a percentage of the
+    /// period. Values above 50 are capped at 50.
+    pub percent: u8,
+
+    /// Fixed delay of the whole schedule.
+    pub phase_offset: Duration,
+
+    /// Seed of the random offsets, so that the schedule of a run can be reproduced.
+    pub seed: u64,
+}
+
+/// A periodic timer which fires at a random offset around a fixed schedule.
+///
+/// The offsets are not cumulative: every tick is jittered around its own slot, so the average
+/// period over many ticks matches the configured period. Without jitter, the ticker behaves
+/// exactly like [tokio::time::interval], i.e. the first tick completes immediately.
+pub struct Ticker {
+    interval: Interval,
+    /// Maximum offset of a tick from its slot.
+    max_offset: Duration,
+    rng: StdRng,
+}
+
+impl Ticker {
+    pub fn new(period: Duration, jitter: TickJitter) -> Self {
+        let max_offset = period * u32::from(jitter.percent.min(MAX_JITTER_PERCENT)) / 100;
+
+        // The underlying interval fires at the earliest possible time of every slot, the random
+        // delay applied on every tick is therefore in `0..=2 * max_offset`
+        let start = Instant::now() + jitter.phase_offset;
+        let interval = time::interval_at(start, period);
+
+        Self {
+            interval,
+            max_offset,
+            rng: StdRng::seed_from_u64(jitter.seed),
+        }
+    }
+
+    /// Returns the configured period of the ticker.
+    pub fn period(&self) -> Duration {
+        self.interval.period()
+    }
+
+    /// Completes at the next tick, and returns the random delay which was applied to it.
+    pub async fn tick(&mut self) -> Duration {
+        self.interval.tick().await;
+
+        if self.max_offset.is_zero() {
+            return Duration::ZERO;
+        }
+
+        // Timers have a millisecond resolution, so there is no point in a finer delay
+        let max_delay_ms = (self.max_offset * 2).as_millis() as u64;
+        let delay = Duration::from_millis(self.rng.gen_range(0..=max_delay_ms));
+        time::sleep(delay).await;
+
+        delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD: Duration = Duration::from_millis(100);
+
+    fn seeded_ticker(jitter: TickJitter) -> Ticker {
+        Ticker::new(PERIOD, TickJitter { seed: 42, ..jitter })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn zero_jitter_matches_interval() {
+        let mut ticker = seeded_ticker(TickJitter::default());
+        let mut interval = time::interval(PERIOD);
+
+        for _ in 0..100 {
+            let delay = ticker.tick().await;
+            let ticked_at = Instant::now();
+
+            assert_eq!(delay, Duration::ZERO);
+            assert_eq!(interval.tick().await, ticked_at);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn jitter_preserves_average_period() {
+        const NUM_TICKS: u32 = 10_000;
+        let jitter = TickJitter { percent: 20, ..Default::default() };
+        let max_offset = PERIOD / 5;
+
+        let mut ticker = seeded_ticker(jitter);
+        let start = Instant::now();
+
+        let mut total_delay = Duration::ZERO;
+        let mut previous_tick = None;
+        for tick in 0..NUM_TICKS {
+            let delay = ticker.tick().await;
+            let ticked_at = Instant::now();
+            total_delay += delay;
+
+            // Every tick stays within its slot
+            assert!(delay <= 2 * max_offset);
+            assert_eq!(ticked_at, start + PERIOD * tick + delay);
+
+            // Ticks never come closer than the period minus twice the maximum offset
+            if let Some(previous_tick) = previous_tick {
+                assert!(ticked_at - previous_tick >= PERIOD - 2 * max_offset);
+            }
+            previous_tick = Some(ticked_at);
+        }
+
+        // The mean delay converges to the maximum offset, i.e. the ticks are jittered around
+        // `start + max_offset + k * PERIOD`
+        let mean_delay = total_delay / NUM_TICKS;
+        let tolerance = max_offset / 20;
+        assert!(
+            mean_delay.abs_diff(max_offset) < tolerance,
+            "mean delay {mean_delay:?} differs from {max_offset:?}"
+        );
+
+        // The schedule doesn't drift
+        let elapsed = Instant::now() - start;
+        assert!(elapsed <= PERIOD * (NUM_TICKS - 1) + 2 * max_offset);
+        assert!(elapsed >= PERIOD * (NUM_TICKS - 1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn phase_offset_delays_the_schedule() {
+        let phase_offset = Duration::from_millis(30);
+        let mut ticker = seeded_ticker(TickJitter { phase_offset, ..Default::default() });
+        let start = Instant::now();
+
+        for tick in 0..10 {
+            ticker.tick().await;
+            assert_eq!(Instant::now(), start + phase_offset + PERIOD * tick);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn offsets_are_drawn_from_the_seed() {
+        let jitter = TickJitter { percent: 20, ..Default::default() };
+        let delays = |seed| async move {
+            let mut ticker = Ticker::new(PERIOD, TickJitter { seed, ..jitter });
+            let mut delays = Vec::new();
+            for _ in 0..10 {
+                delays.push(ticker.tick().await);
+            }
+            delays
+        };
+
+        assert_eq!(delays(1).await, delays(1).await);
+        assert_ne!(delays(1).await, delays(2).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn jitter_is_capped() {
+        let mut ticker = seeded_ticker(TickJitter { percent: 200, ..Default::default() });
+
+        assert_eq!(ticker.max_offset, PERIOD / 2);
+        for _ in 0..100 {
+            assert!(ticker.tick().await <= PERIOD);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Dependency graph of the in-flight transactions and batches, for debugging.
+//!
+//! A transaction depends on another in-flight transaction if it continues the other transaction's
+//! account state, or if it consumes one of its output notes as an unauthenticated note. When the
+//! production of blocks stalls, the graph shows which transactions and batches are waiting on
+//! which, e.g. a long chain of queued transactions behind a batch which keeps failing.
+//!
+//! The graph is a snapshot of the transaction queue and of the batches waiting for a block. It
+//! doesn't include the batches taken by the block being built.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+use miden_node_utils::formatting::format_blake3_digest;
+use miden_objects::{accounts::AccountId, notes::NoteId, transaction::TransactionId, Digest};
+use serde_json::{json, Value};
+
+use crate::{
+    batch_builder::{batch::BatchId, TransactionBatch},
+    ProvenTransaction,
+};
+
+// MEMPOOL GRAPH
+// ================================================================================================
+
+/// Stage of the block production pipeline an in-flight transaction is at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionNodeStatus {
+    /// Waiting in the queue to be batched.
+    Queued,
+    /// Part of a batch being built.
+    Batching,
+    /// Part of a batch waiting for a block.
+    Batched,
+}
+
+impl fmt::Display for TransactionNodeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Queued => "queued",
+            Self::Batching => "batching",
+            Self::Batched => "batched",
+        })
+    }
+}
+
+/// An in-flight transaction, and the in-flight transactions it depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionNode {
+    pub id: TransactionId,
+    /// Account updated by the transaction, unknown for the transactions of the batches being
+    /// built.
+    pub account_id: Option<AccountId>,
+    pub status: TransactionNodeStatus,
+    /// The batch holding the transaction, if it waits for a block.
+    pub batch: Option<BatchId>,
+    /// The in-flight transactions it depends on. The dependencies on the transactions of the
+    /// batches being built are unknown.
+    pub dependencies: Vec<TransactionId>,
+}
+
+/// A batch waiting for a block, and the earlier batches it depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchNode {
+    pub id: BatchId,
+    pub transactions: Vec<TransactionId>,
+    /// The batches holding transactions the transactions of the batch depend on.
+    pub dependencies: Vec<BatchId>,
+}
+
+/// Dependency graph of the in-flight transactions and batches, see the [module docs](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MempoolGraph {
+    /// The transactions in queue order: the batched transactions, the queued transactions, and
+    /// the transactions being batched.
+    pub transactions: Vec<TransactionNode>,
+    /// The batches waiting for a block, in order.
+    pub batches: Vec<BatchNode>,
+}
+
+impl MempoolGraph {
+    /// Returns the graph of the `batches` waiting for a block and of the `queued` transactions,
+    /// given in queue order, along with the transactions of the batches being built.
+    pub fn new(
+        batches: &[TransactionBatch],
+        queued: &[ProvenTransaction],
+        batching: impl IntoIterator<Item = TransactionId>,
+    ) -> Self {
+        // In-flight transactions producing every account state and note
+        let mut state_producers: BTreeMap<(AccountId, Digest), TransactionId> = BTreeMap::new();
+        let mut note_producers: BTreeMap<NoteId, TransactionId> = BTreeMap::new();
+
+        let batched = batches
+            .iter()
+            .flat_map(|batch| batch.transactions().iter().map(|tx| (tx, Some(batch.id()))));
+        let queued = queued.iter().map(|tx| (tx, None));
+
+        let mut transactions = Vec::new();
+        for (tx, batch) in batched.chain(queued) {
+            let state_producer =
+                state_producers.get(&(tx.account_id(), tx.account_update().init_state_hash()));
+            let consumed_note_producers =
+                tx.get_unauthenticated_notes().filter_map(|note| note_producers.get(&note.id()));
+            let mut dependencies = Vec::new();
+            for tx_id in state_producer.into_iter().chain(consumed_note_producers) {
+                if !dependencies.contains(tx_id) {
+                    dependencies.push(*tx_id);
+                }
+            }
+
+            state_producers
+                .insert((tx.account_id(), tx.account_update().final_state_hash()), tx.id());
+            note_producers.extend(tx.output_notes().iter().map(|note| (note.id(), tx.id())));
+
+            let status = match batch {
+                Some(_) => TransactionNodeStatus::Batched,
+                None => TransactionNodeStatus::Queued,
+            };
+            transactions.push(TransactionNode {
+                id: tx.id(),
+                account_id: Some(tx.account_id()),
+                status,
+                batch,
+                dependencies,
+            });
+        }
+
+        let batch_of: BTreeMap<TransactionId, BatchId> = transactions
+            .iter()
+            .filter_map(|tx| tx.batch.map(|batch| (tx.id, batch)))
+            .collect();
+        let batches = batches
+            .iter()
+            .map(|batch| {
+                let mut dependencies = Vec::new();
+                let batch_transactions =
+                    transactions.iter().filter(|tx| tx.batch == Some(batch.id()));
+                for tx_id in batch_transactions.flat_map(|tx| &tx.dependencies) {
+                    let dependency = batch_of[tx_id];
+                    if dependency != batch.id() && !dependencies.contains(&dependency) {
+                        dependencies.push(dependency);
+                    }
+                }
+
+                BatchNode {
+                    id: batch.id(),
+                    transactions: batch.transactions().iter().map(ProvenTransaction::id).collect(),
+                    dependencies,
+                }
+            })
+            .collect();
+
+        transactions.extend(batching.into_iter().map(|id| TransactionNode {
+            id,
+            account_id: None,
+            status: TransactionNodeStatus::Batching,
+            batch: None,
+            dependencies: Vec::new(),
+        }));
+
+        Self { transactions, batches }
+    }
+
+    /// Returns the graph as a JSON document, with the IDs as hex strings.
+    pub fn to_json(&self) -> String {
+        let transactions: Vec<Value> = self
+            .transactions
+            .iter()
+            .map(|tx| {
+                let dependencies: Vec<_> =
+                    tx.dependencies.iter().map(TransactionId::to_hex).collect();
+                json!({
+                    "id": tx.id.to_hex(),
+                    "account_id": tx.account_id.map(|account_id| account_id.to_hex()),
+                    "status": tx.status.to_string(),
+                    "batch": tx.batch.map(format_blake3_digest),
+                    "dependencies": dependencies,
+                })
+            })
+            .collect();
+        let batches: Vec<Value> = self
+            .batches
+            .iter()
+            .map(|batch| {
+                let transactions: Vec<_> =
+                    batch.transactions.iter().map(TransactionId::to_hex).collect();
+                let dependencies: Vec<_> =
+                    batch.dependencies.iter().copied().map(format_blake3_digest).collect();
+                json!({
+                    "id": format_blake3_digest(batch.id),
+                    "transactions": transactions,
+                    "dependencies": dependencies,
+                })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&json!({ "transactions": transactions, "batches": batches }))
+            .expect("the graph should serialize to JSON")
+    }
+
+    /// Returns the graph in the Graphviz DOT language.
+    ///
+    /// The transactions are the nodes, labeled with the prefix of their ID and their status, and
+    /// grouped in a cluster per batch. The edges go from the transactions to the transactions they
+    /// depend on.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph mempool {\n    rankdir=LR;\n    node [shape=box];\n");
+
+        let node = |tx: &TransactionNode| {
+            let id = tx.id.to_hex();
+            format!("\"{id}\" [label=\"{}\\n{}\"];", short_id(&id), tx.status)
+        };
+        for batch in &self.batches {
+            let id = format_blake3_digest(batch.id);
+            writeln!(dot, "    subgraph \"cluster_{id}\" {{").unwrap();
+            writeln!(dot, "        label=\"batch {}\";", short_id(&id)).unwrap();
+            for tx in self.transactions.iter().filter(|tx| tx.batch == Some(batch.id)) {
+                writeln!(dot, "        {}", node(tx)).unwrap();
+            }
+            dot.push_str("    }\n");
+        }
+        for tx in self.transactions.iter().filter(|tx| tx.batch.is_none()) {
+            writeln!(dot, "    {}", node(tx)).unwrap();
+        }
+        for tx in &self.transactions {
+            for dependency in &tx.dependencies {
+                writeln!(dot, "    \"{}\" -> \"{}\";", tx.id.to_hex(), dependency.to_hex())
+                    .unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Returns the prefix of a hex ID shown in the labels of the DOT graph.
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(10)]
+}
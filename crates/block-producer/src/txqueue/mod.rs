@@ -1,19 +1,43 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use async_trait::async_trait;
-use miden_objects::MAX_OUTPUT_NOTES_PER_BATCH;
-use tokio::{sync::RwLock, time};
-use tracing::{debug, info, info_span, instrument, Instrument};
+use miden_node_utils::{
+    block_num::BlockNumberExt,
+    formatting::{format_array, format_blake3_digest},
+    runtime::spawn_named,
+};
+use miden_objects::{
+    accounts::AccountId, transaction::TransactionId, Digest, MAX_OUTPUT_NOTES_PER_BATCH,
+};
+use tokio::{sync::RwLock, time::Instant};
+use tracing::{debug, field, info, info_span, instrument, Instrument, Span};
 
+use self::rejections::RejectionCache;
 use crate::{
-    batch_builder::BatchBuilder,
-    errors::{AddTransactionError, VerifyTxError},
+    batch_builder::{
+        batch::BatchId, split_dependent_transactions, BatchBuilder, BatchSelection,
+        TransactionBatch,
+    },
+    errors::{
+        AccountStateError, AddTransactionError, BuildBatchError, EvictTransactionError,
+        VerifyTxError,
+    },
+    events::{TransactionEvents, TransactionStatus},
+    ticker::{TickJitter, Ticker},
     ProvenTransaction, SharedRwVec, COMPONENT,
 };
 
+mod graph;
+mod rejections;
 #[cfg(test)]
 mod tests;
 
+pub use graph::{BatchNode, MempoolGraph, TransactionNode, TransactionNodeStatus};
+
 // TRANSACTION VALIDATOR
 // ================================================================================================
 
@@ -32,6 +56,47 @@ pub trait TransactionValidator: Send + Sync + 'static {
     /// - Track the necessary state of the transaction until it is committed to the `store`, to
     ///   perform the check above.
     async fn verify_tx(&self, tx: &ProvenTransaction) -> Result<u32, VerifyTxError>;
+
+    /// Stops tracking `txs`, which were dropped from the block production pipeline before being
+    /// committed.
+    ///
+    /// Transactions are given in the order in which they were verified, and dependent
+    /// transactions are always dropped together with the transactions they depend on.
+    async fn revert_transactions(&self, txs: &[ProvenTransaction]);
+
+    /// Returns the state the account is expected to have once the in-flight transactions are
+    /// committed, i.e. the final state of its latest in-flight transaction, or its committed state
+    /// if it has none.
+    async fn get_expected_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<ExpectedAccountState, AccountStateError>;
+}
+
+/// State of an account as expected by the block producer.
+///
+/// This is only a hint: in-flight transactions can still be dropped from the block production
+/// pipeline, in which case transactions building upon their final state will be rejected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpectedAccountState {
+    /// Expected hash of the account state, `None` if the account is unknown.
+    pub account_hash: Option<Digest>,
+    /// Whether `account_hash` is the final state of an in-flight transaction.
+    pub in_flight: bool,
+    /// The current block height.
+    pub block_height: u32,
+}
+
+/// Transactions and batches affected by the eviction of a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Eviction {
+    /// The evicted transaction, followed by the evicted transactions depending on it.
+    pub evicted_transactions: Vec<TransactionId>,
+    /// The batches which were waiting for a block, dropped as they held evicted transactions or
+    /// depended on the transactions of other dropped batches.
+    pub dropped_batches: Vec<BatchId>,
+    /// The transactions of the dropped batches which were not evicted, queued again.
+    pub requeued_transactions: Vec<TransactionId>,
 }
 
 // TRANSACTION QUEUE
@@ -43,10 +108,34 @@ pub struct TransactionQueueOptions {
 
     /// The size of a batch
     pub batch_size: usize,
+
+    /// Minimum number of transactions of a batch. Smaller batches are only built once their oldest
+    /// transaction has been queued for `max_batch_wait`.
+    pub min_batch_size: usize,
+
+    /// Maximum time a transaction waits in the queue for a batch to reach `min_batch_size`
+    pub max_batch_wait: Duration,
+
+    /// Randomization of the batch building schedule
+    pub tick_jitter: TickJitter,
+
+    /// Time for which deterministic rejections of transactions are remembered
+    pub rejection_cache_ttl: Duration,
+
+    /// Maximum number of remembered rejections, zero disables the rejection cache
+    pub rejection_cache_capacity: usize,
 }
 
 pub struct TransactionQueue<BB, TV> {
     ready_queue: SharedRwVec<ProvenTransaction>,
+    /// Time at which the transactions were queued, kept until they are successfully batched or
+    /// dropped, so transactions added back to the queue keep their age.
+    queued_at: Arc<Mutex<HashMap<TransactionId, Instant>>>,
+    /// Recent deterministic rejections, answering repeated submissions of rejected transactions
+    /// without verifying them again.
+    rejections: RejectionCache,
+    /// Status changes of the transactions, published once they are dropped from the queue
+    events: Arc<TransactionEvents>,
     tx_validator: Arc<TV>,
     batch_builder: Arc<BB>,
     options: TransactionQueueOptions,
@@ -64,26 +153,67 @@ where
     ) -> Self {
         Self {
             ready_queue: Arc::new(RwLock::new(Vec::new())),
+            queued_at: Default::default(),
+            rejections: RejectionCache::new(
+                options.rejection_cache_ttl,
+                options.rejection_cache_capacity,
+            ),
+            events: Default::default(),
             tx_validator,
             batch_builder,
             options,
         }
     }
 
+    /// Publishes the status changes of the dropped transactions to `events`.
+    pub fn with_events(mut self, events: Arc<TransactionEvents>) -> Self {
+        self.events = events;
+        self
+    }
+
     pub async fn run(self: Arc<Self>) {
-        let mut interval = time::interval(self.options.build_batch_frequency);
+        let mut ticker = Ticker::new(self.options.build_batch_frequency, self.options.tick_jitter);
 
-        info!(target: COMPONENT, period_ms = interval.period().as_millis(), "Transaction queue started");
+        info!(target: COMPONENT, period_ms = ticker.period().as_millis(), "Transaction queue started");
 
         loop {
-            interval.tick().await;
-            self.try_build_batches().await;
+            let tick_delay = ticker.tick().await;
+            self.try_build_batches()
+                .instrument(info_span!(
+                    target: COMPONENT,
+                    "batch_tick",
+                    tick_delay_ms = tick_delay.as_millis()
+                ))
+                .await;
         }
     }
 
+    /// Returns how long the oldest transaction of the queue has been waiting to be batched, or
+    /// `None` if the queue is empty.
+    pub async fn oldest_transaction_age(&self) -> Option<Duration> {
+        let locked_ready_queue = self.ready_queue.read().await;
+        let queued_at = self.queued_at.lock().expect("Queued times lock poisoned");
+
+        locked_ready_queue
+            .iter()
+            .filter_map(|tx| queued_at.get(&tx.id()))
+            .min()
+            .map(Instant::elapsed)
+    }
+
     /// Divides the queue in groups to be batched; those that failed are appended back on the queue
     #[instrument(target = "miden-block-producer", skip_all)]
     async fn try_build_batches(&self) {
+        // Batches are not built until enough transactions are queued, unless the oldest one waited
+        // for too long
+        let oldest_transaction_age = self.oldest_transaction_age().await;
+        if oldest_transaction_age.is_some_and(|age| age < self.options.max_batch_wait)
+            && self.ready_queue.read().await.len() < self.options.min_batch_size
+        {
+            debug!(target: COMPONENT, "Not enough transactions queued for a batch");
+            return;
+        }
+
         let mut txs: Vec<ProvenTransaction> = {
             let mut locked_ready_queue = self.ready_queue.write().await;
 
@@ -122,14 +252,105 @@ where
                 batch.push(tx)
             }
 
+            // The last batch can be too small, its transactions wait for more transactions unless
+            // the oldest one waited for too long already
+            if txs.is_empty()
+                && batch.len() < self.options.min_batch_size
+                && !self.waited_too_long(&batch[0])
+            {
+                debug!(target: COMPONENT, num_txs = batch.len(), "Batch too small, requeuing");
+                let mut locked_ready_queue = self.ready_queue.write().await;
+                batch
+                    .into_iter()
+                    .enumerate()
+                    .for_each(|(i, tx)| locked_ready_queue.insert(i, tx));
+                break;
+            }
+
             let ready_queue = self.ready_queue.clone();
+            let queued_at = self.queued_at.clone();
+            let tx_validator = self.tx_validator.clone();
             let batch_builder = self.batch_builder.clone();
+            let events = self.events.clone();
+            let tx_ids: Vec<_> = batch.iter().map(ProvenTransaction::id).collect();
 
-            tokio::spawn(
+            spawn_named(
+                &format!("{COMPONENT}:build_batch"),
                 async move {
                     match batch_builder.build_batch(batch).await {
                         Ok(_) => {
-                            // batch was successfully built, do nothing
+                            // batch was successfully built, its txs are no longer queued
+                            forget_queued_at(&queued_at, tx_ids);
+                        },
+                        Err(BuildBatchError::TransactionsExpired {
+                            inclusion_block,
+                            expiration_slack,
+                            expired,
+                            txs,
+                        }) => {
+                            let reason = format!(
+                                "Transaction expires before block {}",
+                                inclusion_block.saturating_advance(expiration_slack)
+                            );
+                            let dependent_reason =
+                                "Transaction depends on an expired transaction".into();
+                            let rejection = Rejection {
+                                rejected: expired,
+                                txs,
+                                reason,
+                                dependent_reason,
+                            };
+                            let reverted = reject_transactions(
+                                &ready_queue,
+                                &queued_at,
+                                &*tx_validator,
+                                &events,
+                                rejection,
+                            )
+                            .await;
+                            info!(
+                                target: COMPONENT,
+                                txs = %format_array(reverted.iter().map(TransactionId::to_hex)),
+                                inclusion_block,
+                                expiration_slack,
+                                "Dropped expired transactions"
+                            );
+                        },
+                        Err(BuildBatchError::StaleReferenceBlock {
+                            inclusion_block,
+                            retention_depth,
+                            stale,
+                            txs,
+                        }) => {
+                            let reason = format!(
+                                "Stale reference block: the store only retains the \
+                                 {retention_depth} blocks before block {inclusion_block}, rebuild \
+                                 the transaction against a newer block"
+                            );
+                            let dependent_reason =
+                                "Transaction depends on a transaction with a stale reference block"
+                                    .into();
+                            let rejection = Rejection {
+                                rejected: stale,
+                                txs,
+                                reason,
+                                dependent_reason,
+                            };
+                            let reverted = reject_transactions(
+                                &ready_queue,
+                                &queued_at,
+                                &*tx_validator,
+                                &events,
+                                rejection,
+                            )
+                            .await;
+                            info!(
+                                target: COMPONENT,
+                                txs = %format_array(reverted.iter().map(TransactionId::to_hex)),
+                                inclusion_block,
+                                retention_depth,
+                                "Dropped transactions with stale reference blocks"
+                            );
                         },
                         Err(e) => {
                             // batch building failed, add txs back to the beginning of the queue
@@ -146,23 +367,51 @@ where
         }
     }
 
+    /// Returns `true` if `tx` has been queued for at least `max_batch_wait`.
+    fn waited_too_long(&self, tx: &ProvenTransaction) -> bool {
+        let queued_at = self.queued_at.lock().expect("Queued times lock poisoned");
+        queued_at
+            .get(&tx.id())
+            .map_or(true, |queued_at| queued_at.elapsed() >= self.options.max_batch_wait)
+    }
+
     /// Queues `tx` to be added in a batch and subsequently into a block and returns the current
     /// block height.
     ///
     /// This method will validate the `tx` and ensure it is valid w.r.t. the rollup state, and the
-    /// current in-flight transactions.
-    #[instrument(target = "miden-block-producer", skip_all, err)]
+    /// current in-flight transactions. Transactions which were rejected recently for a reason
+    /// not depending on the in-flight transactions are rejected again without being verified.
+    #[instrument(
+        target = "miden-block-producer",
+        skip_all,
+        err,
+        fields(cached_rejection = field::Empty)
+    )]
     pub async fn add_transaction(&self, tx: ProvenTransaction) -> Result<u32, AddTransactionError> {
         info!(target: COMPONENT, tx_id = %tx.id().to_hex(), account_id = %tx.account_id().to_hex());
 
-        let block_height = self
-            .tx_validator
-            .verify_tx(&tx)
-            .await
-            .map_err(AddTransactionError::VerificationFailed)?;
+        if let Some(err) = self.rejections.get(tx.id()) {
+            Span::current().record("cached_rejection", true);
+            return Err(AddTransactionError::VerificationFailed(err));
+        }
+
+        let block_height = match self.tx_validator.verify_tx(&tx).await {
+            Ok(block_height) => block_height,
+            Err(err) => {
+                let cached = self.rejections.insert(tx.id(), &err);
+                Span::current().record("cached_rejection", false);
+                debug!(target: COMPONENT, cached, "Transaction rejected");
+                return Err(AddTransactionError::VerificationFailed(err));
+            },
+        };
+        self.rejections.observe_chain_tip(block_height);
 
         let queue_len = {
             let mut queue_write_guard = self.ready_queue.write().await;
+            self.queued_at
+                .lock()
+                .expect("Queued times lock poisoned")
+                .insert(tx.id(), Instant::now());
             queue_write_guard.push(tx);
             queue_write_guard.len()
         };
@@ -171,4 +420,206 @@ where
 
         Ok(block_height)
     }
+
+    /// Evicts the transaction from the block production pipeline before it's included in a block,
+    /// e.g. a transaction which keeps failing the batches it's part of.
+    ///
+    /// The transactions depending on it are evicted along with it if `cascade` is set, otherwise
+    /// the eviction is refused if there is any. The batches waiting for a block can't be split,
+    /// hence the batch holding the transaction is dropped, along with the later batches depending
+    /// on the transactions of a dropped batch, and the transactions of the dropped batches which
+    /// are not evicted are queued again.
+    #[instrument(
+        target = "miden-block-producer",
+        skip_all,
+        err,
+        fields(tx_id = %tx_id.to_hex(), cascade)
+    )]
+    pub async fn evict_transaction(
+        &self,
+        tx_id: TransactionId,
+        cascade: bool,
+    ) -> Result<Eviction, EvictTransactionError> {
+        let mut locked_ready_queue = self.ready_queue.write().await;
+
+        // The transactions being batched are neither queued nor waiting in a batch, so their
+        // dependencies on the evicted transaction can't be checked
+        {
+            let queued_at = self.queued_at.lock().expect("Queued times lock poisoned");
+            let queued: BTreeSet<_> =
+                locked_ready_queue.iter().map(ProvenTransaction::id).collect();
+            if queued_at.keys().any(|tx_id| !queued.contains(tx_id)) {
+                return Err(EvictTransactionError::BatchInProgress);
+            }
+        }
+
+        let mut split = None;
+        let select: BatchSelection<'_> = Box::new(|batches: &[TransactionBatch]| {
+            let selected = select_dropped_batches(batches, tx_id);
+            let pipeline = selected
+                .iter()
+                .flat_map(|&index| batches[index].transactions().iter().cloned())
+                .chain(locked_ready_queue.iter().cloned())
+                .collect();
+            let (kept, evicted) =
+                split_dependent_transactions(pipeline, &[], |tx| tx.id() == tx_id);
+
+            if evicted.is_empty() {
+                return Err(EvictTransactionError::NotFound(tx_id));
+            }
+            if !cascade && evicted.len() > 1 {
+                let dependents = evicted[1..].iter().map(ProvenTransaction::id).collect();
+                return Err(EvictTransactionError::HasDependents { tx_id, dependents });
+            }
+
+            split = Some((kept, evicted));
+            Ok(selected)
+        });
+        let dropped_batches = self.batch_builder.remove_ready_batches(select).await?;
+        let (kept, evicted) = split.expect("The eviction was not refused");
+
+        // The requeued transactions were queued long ago already, so they are batched again
+        // without waiting for more transactions
+        *locked_ready_queue = kept;
+        self.tx_validator.revert_transactions(&evicted).await;
+        forget_queued_at(&self.queued_at, evicted.iter().map(ProvenTransaction::id));
+
+        let evicted_transactions: Vec<_> = evicted.iter().map(ProvenTransaction::id).collect();
+        let requeued_transactions: Vec<_> = dropped_batches
+            .iter()
+            .flat_map(|batch| batch.transactions().iter().map(ProvenTransaction::id))
+            .filter(|tx_id| !evicted_transactions.contains(tx_id))
+            .collect();
+        let dropped_batches: Vec<_> = dropped_batches.iter().map(TransactionBatch::id).collect();
+        info!(
+            target: COMPONENT,
+            txs = %format_array(evicted_transactions.iter().map(TransactionId::to_hex)),
+            batches = %format_array(dropped_batches.iter().copied().map(format_blake3_digest)),
+            requeued = %format_array(requeued_transactions.iter().map(TransactionId::to_hex)),
+            "Evicted transactions"
+        );
+
+        let reason = "Transaction evicted by the operator".into();
+        self.events.publish([tx_id], &TransactionStatus::Rejected { reason });
+        let reason = "Transaction depends on an evicted transaction".into();
+        self.events.publish(
+            evicted_transactions[1..].iter().copied(),
+            &TransactionStatus::Rejected { reason },
+        );
+
+        Ok(Eviction {
+            evicted_transactions,
+            dropped_batches,
+            requeued_transactions,
+        })
+    }
+
+    /// Returns the dependency graph of the queued transactions, of the batches waiting for a
+    /// block, and of the transactions being batched.
+    pub async fn mempool_graph(&self) -> MempoolGraph {
+        let locked_ready_queue = self.ready_queue.read().await;
+        let batches = self.batch_builder.ready_batches().await;
+
+        // The transactions being batched are neither queued nor waiting in a batch
+        let batching: BTreeSet<_> = {
+            let known: BTreeSet<_> = locked_ready_queue
+                .iter()
+                .chain(batches.iter().flat_map(TransactionBatch::transactions))
+                .map(ProvenTransaction::id)
+                .collect();
+            let queued_at = self.queued_at.lock().expect("Queued times lock poisoned");
+            queued_at.keys().filter(|tx_id| !known.contains(tx_id)).copied().collect()
+        };
+
+        MempoolGraph::new(&batches, &locked_ready_queue, batching)
+    }
+
+    /// Returns the state the account is expected to have once the queued transactions are
+    /// committed.
+    pub async fn get_expected_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<ExpectedAccountState, AccountStateError> {
+        self.tx_validator.get_expected_account_state(account_id).await
+    }
+}
+
+/// Returns the increasing indices of the batches to drop to evict the transaction: the batch
+/// holding it, and the later batches depending on the transactions of a dropped batch.
+fn select_dropped_batches(batches: &[TransactionBatch], tx_id: TransactionId) -> Vec<usize> {
+    let Some(first) = batches
+        .iter()
+        .position(|batch| batch.transactions().iter().any(|tx| tx.id() == tx_id))
+    else {
+        return Vec::new();
+    };
+
+    let mut selected = vec![first];
+    let mut dropped_txs = batches[first].transactions().to_vec();
+    for (index, batch) in batches.iter().enumerate().skip(first + 1) {
+        let (_, dependents) =
+            split_dependent_transactions(batch.transactions().to_vec(), &dropped_txs, |_| false);
+        if !dependents.is_empty() {
+            selected.push(index);
+            dropped_txs.extend_from_slice(batch.transactions());
+        }
+    }
+
+    selected
+}
+
+/// Transactions of a batch which can no longer be committed, see [reject_transactions].
+struct Rejection {
+    /// The transactions which can't be committed, followed by the ones of the batch depending on
+    /// them
+    rejected: Vec<ProvenTransaction>,
+    /// The remaining, still viable, transactions of the batch
+    txs: Vec<ProvenTransaction>,
+    /// Reason published for the rejected transactions of the batch
+    reason: String,
+    /// Reason published for the queued transactions depending on the rejected ones
+    dependent_reason: String,
+}
+
+/// Drops the rejected transactions of a batch together with the queued transactions depending on
+/// them, and adds the remaining transactions of the batch back to the beginning of the queue.
+///
+/// Returns the IDs of the dropped transactions.
+async fn reject_transactions<TV: TransactionValidator>(
+    ready_queue: &SharedRwVec<ProvenTransaction>,
+    queued_at: &Mutex<HashMap<TransactionId, Instant>>,
+    tx_validator: &TV,
+    events: &TransactionEvents,
+    rejection: Rejection,
+) -> Vec<TransactionId> {
+    let Rejection { rejected, txs, reason, dependent_reason } = rejection;
+
+    let mut locked_ready_queue = ready_queue.write().await;
+    let queued = locked_ready_queue.drain(..).collect();
+    let (queued, dependents) = split_dependent_transactions(queued, &rejected, |_| false);
+
+    let rejected_ids: Vec<_> = rejected.iter().map(ProvenTransaction::id).collect();
+    let dependent_ids: Vec<_> = dependents.iter().map(ProvenTransaction::id).collect();
+    let reverted: Vec<_> = rejected.into_iter().chain(dependents).collect();
+    tx_validator.revert_transactions(&reverted).await;
+    forget_queued_at(queued_at, reverted.iter().map(ProvenTransaction::id));
+
+    events.publish(rejected_ids.iter().copied(), &TransactionStatus::Rejected { reason });
+    let reason = dependent_reason;
+    events.publish(dependent_ids.iter().copied(), &TransactionStatus::Rejected { reason });
+
+    *locked_ready_queue = txs.into_iter().chain(queued).collect();
+
+    rejected_ids.into_iter().chain(dependent_ids).collect()
+}
+
+/// Stops tracking the time at which the given transactions were queued.
+fn forget_queued_at(
+    queued_at: &Mutex<HashMap<TransactionId, Instant>>,
+    tx_ids: impl IntoIterator<Item = TransactionId>,
+) {
+    let mut queued_at = queued_at.lock().expect("Queued times lock poisoned");
+    for tx_id in tx_ids {
+        queued_at.remove(&tx_id);
+    }
 }
@@ -0,0 +1,154 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::Duration,
+};
+
+use miden_objects::{transaction::TransactionId, Digest};
+use tokio::time::Instant;
+
+use crate::errors::VerifyTxError;
+
+// REJECTION CACHE
+// ================================================================================================
+
+/// Remembers the deterministic rejections of transactions for a short time, so that repeated
+/// submissions of a rejected transaction are rejected without verifying it again.
+///
+/// Only rejections which don't depend on the in-flight transactions are cached. All entries are
+/// dropped once the chain tip advances, since a committed block can make a rejected transaction
+/// valid, e.g. by committing its reference block. The least recently used entries are evicted once
+/// `capacity` transactions are cached.
+pub struct RejectionCache {
+    ttl: Duration,
+    capacity: usize,
+    inner: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    /// Highest chain tip observed by the cache.
+    chain_tip: u32,
+    by_id: HashMap<TransactionId, Entry>,
+    /// Cached transactions, by their last use.
+    by_last_use: BTreeMap<u64, TransactionId>,
+    next_use: u64,
+}
+
+struct Entry {
+    rejection: Rejection,
+    rejected_at: Instant,
+    last_use: u64,
+}
+
+/// A deterministic rejection of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rejection {
+    InvalidProof,
+    UnknownReferenceBlock { block_ref: Digest, chain_tip: u32 },
+}
+
+impl Rejection {
+    /// Returns the rejection of `err`, or `None` if the error isn't deterministic.
+    fn from_error(err: &VerifyTxError) -> Option<Self> {
+        match err {
+            VerifyTxError::InvalidTransactionProof(_) => Some(Self::InvalidProof),
+            VerifyTxError::UnknownReferenceBlock { block_ref, chain_tip } => {
+                Some(Self::UnknownReferenceBlock {
+                    block_ref: *block_ref,
+                    chain_tip: *chain_tip,
+                })
+            },
+            // The other errors depend on the in-flight transactions, or on the availability of
+            // the store
+            _ => None,
+        }
+    }
+
+    fn into_error(self, tx_id: TransactionId) -> VerifyTxError {
+        match self {
+            Self::InvalidProof => VerifyTxError::InvalidTransactionProof(tx_id),
+            Self::UnknownReferenceBlock { block_ref, chain_tip } => {
+                VerifyTxError::UnknownReferenceBlock { block_ref, chain_tip }
+            },
+        }
+    }
+}
+
+impl RejectionCache {
+    /// Creates a cache remembering up to `capacity` rejections for `ttl` each. A zero `capacity`
+    /// disables the cache.
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self { ttl, capacity, inner: Default::default() }
+    }
+
+    /// Returns the cached rejection of the transaction, if any.
+    pub fn get(&self, tx_id: TransactionId) -> Option<VerifyTxError> {
+        let mut entries = self.inner.lock().expect("Rejection cache lock poisoned");
+        let Entries { by_id, by_last_use, next_use, .. } = &mut *entries;
+
+        let entry = by_id.get_mut(&tx_id)?;
+        if entry.rejected_at.elapsed() >= self.ttl {
+            by_last_use.remove(&entry.last_use);
+            by_id.remove(&tx_id);
+            return None;
+        }
+
+        by_last_use.remove(&entry.last_use);
+        entry.last_use = *next_use;
+        by_last_use.insert(*next_use, tx_id);
+        *next_use += 1;
+
+        Some(entry.rejection.into_error(tx_id))
+    }
+
+    /// Caches the rejection of the transaction by `err`, unless the error isn't deterministic.
+    ///
+    /// Returns whether the rejection was cached.
+    pub fn insert(&self, tx_id: TransactionId, err: &VerifyTxError) -> bool {
+        let Some(rejection) = Rejection::from_error(err) else {
+            return false;
+        };
+        if self.capacity == 0 {
+            return false;
+        }
+
+        if let Rejection::UnknownReferenceBlock { chain_tip, .. } = rejection {
+            self.observe_chain_tip(chain_tip);
+        }
+
+        let mut entries = self.inner.lock().expect("Rejection cache lock poisoned");
+        let Entries { by_id, by_last_use, next_use, .. } = &mut *entries;
+
+        if let Some(previous) = by_id.remove(&tx_id) {
+            by_last_use.remove(&previous.last_use);
+        }
+        while by_id.len() >= self.capacity {
+            let (_, evicted) = by_last_use.pop_first().expect("Cached transactions are tracked");
+            by_id.remove(&evicted);
+        }
+
+        by_id.insert(
+            tx_id,
+            Entry {
+                rejection,
+                rejected_at: Instant::now(),
+                last_use: *next_use,
+            },
+        );
+        by_last_use.insert(*next_use, tx_id);
+        *next_use += 1;
+
+        true
+    }
+
+    /// Drops all cached rejections if `chain_tip` is beyond the highest chain tip observed so far.
+    pub fn observe_chain_tip(&self, chain_tip: u32) {
+        let mut entries = self.inner.lock().expect("Rejection cache lock poisoned");
+        if chain_tip > entries.chain_tip {
+            entries.chain_tip = chain_tip;
+            entries.by_id.clear();
+            entries.by_last_use.clear();
+        }
+    }
+}
@@ -1,7 +1,25 @@
-use tokio::sync::mpsc::{self, error::TryRecvError};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use miden_node_test_utils::{MockPrivateAccount, MockProvenTxBuilder};
+use miden_objects::ONE;
+use tokio::{
+    sync::mpsc::{self, error::TryRecvError},
+    time,
+};
 
 use super::*;
-use crate::{errors::BuildBatchError, test_utils::MockProvenTxBuilder, TransactionBatch};
+use crate::{
+    batch_builder::{
+        split_dependent_transactions, DefaultBatchBuilder, DefaultBatchBuilderOptions,
+    },
+    block_builder::DefaultBlockBuilder,
+    errors::{BuildBatchError, TxInputsError},
+    reference_blocks::ReferenceBlocks,
+    state_view::DefaultStateView,
+    store::{ApplyBlock, Store},
+    test_utils::{block::MockBlockBuilder, MockStoreSuccessBuilder},
+    TransactionBatch,
+};
 
 // STRUCTS
 // ================================================================================================
@@ -14,6 +32,15 @@ impl TransactionValidator for TransactionValidatorSuccess {
     async fn verify_tx(&self, _tx: &ProvenTransaction) -> Result<u32, VerifyTxError> {
         Ok(0)
     }
+
+    async fn revert_transactions(&self, _txs: &[ProvenTransaction]) {}
+
+    async fn get_expected_account_state(
+        &self,
+        _account_id: AccountId,
+    ) -> Result<ExpectedAccountState, AccountStateError> {
+        Ok(ExpectedAccountState::default())
+    }
 }
 
 /// All transactions fail to verify
@@ -24,6 +51,83 @@ impl TransactionValidator for TransactionValidatorFailure {
     async fn verify_tx(&self, tx: &ProvenTransaction) -> Result<u32, VerifyTxError> {
         Err(VerifyTxError::InvalidTransactionProof(tx.id()))
     }
+
+    async fn revert_transactions(&self, _txs: &[ProvenTransaction]) {}
+
+    async fn get_expected_account_state(
+        &self,
+        _account_id: AccountId,
+    ) -> Result<ExpectedAccountState, AccountStateError> {
+        Ok(ExpectedAccountState::default())
+    }
+}
+
+/// All transactions verify successfully, reverted transactions are recorded in `reverted`
+#[derive(Default)]
+struct TransactionValidatorRecording {
+    reverted: SharedRwVec<ProvenTransaction>,
+}
+
+#[async_trait]
+impl TransactionValidator for TransactionValidatorRecording {
+    async fn verify_tx(&self, _tx: &ProvenTransaction) -> Result<u32, VerifyTxError> {
+        Ok(0)
+    }
+
+    async fn revert_transactions(&self, txs: &[ProvenTransaction]) {
+        self.reverted.write().await.extend_from_slice(txs);
+    }
+
+    async fn get_expected_account_state(
+        &self,
+        _account_id: AccountId,
+    ) -> Result<ExpectedAccountState, AccountStateError> {
+        Ok(ExpectedAccountState::default())
+    }
+}
+
+/// Rejects the transactions for which `reject` returns an error, and accepts the others at
+/// `block_height`. Verifications are counted in `verifications`
+struct TransactionValidatorCounting {
+    reject: Box<dyn Fn(&ProvenTransaction) -> Option<VerifyTxError> + Send + Sync>,
+    block_height: AtomicU32,
+    verifications: AtomicUsize,
+}
+
+impl TransactionValidatorCounting {
+    fn new(
+        reject: impl Fn(&ProvenTransaction) -> Option<VerifyTxError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            reject: Box::new(reject),
+            block_height: AtomicU32::new(0),
+            verifications: AtomicUsize::new(0),
+        }
+    }
+
+    fn verifications(&self) -> usize {
+        self.verifications.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl TransactionValidator for TransactionValidatorCounting {
+    async fn verify_tx(&self, tx: &ProvenTransaction) -> Result<u32, VerifyTxError> {
+        self.verifications.fetch_add(1, Ordering::Relaxed);
+        match (self.reject)(tx) {
+            Some(err) => Err(err),
+            None => Ok(self.block_height.load(Ordering::Relaxed)),
+        }
+    }
+
+    async fn revert_transactions(&self, _txs: &[ProvenTransaction]) {}
+
+    async fn get_expected_account_state(
+        &self,
+        _account_id: AccountId,
+    ) -> Result<ExpectedAccountState, AccountStateError> {
+        Ok(ExpectedAccountState::default())
+    }
 }
 
 /// Records all batches built in `ready_batches`
@@ -61,6 +165,110 @@ impl BatchBuilder for BatchBuilderFailure {
     }
 }
 
+/// Fails to build batches containing transactions which expire before `inclusion_block`
+struct BatchBuilderExpiration {
+    inclusion_block: u32,
+}
+
+#[async_trait]
+impl BatchBuilder for BatchBuilderExpiration {
+    async fn build_batch(&self, txs: Vec<ProvenTransaction>) -> Result<(), BuildBatchError> {
+        let (txs, expired) = split_dependent_transactions(txs, &[], |tx| {
+            tx.expiration_block_num() < self.inclusion_block
+        });
+
+        if expired.is_empty() {
+            Ok(())
+        } else {
+            Err(BuildBatchError::TransactionsExpired {
+                inclusion_block: self.inclusion_block,
+                expiration_slack: 0,
+                expired,
+                txs,
+            })
+        }
+    }
+}
+
+/// Keeps the built batches in `ready_batches` until they are removed
+#[derive(Default)]
+struct BatchBuilderHolding {
+    ready_batches: SharedRwVec<TransactionBatch>,
+}
+
+#[async_trait]
+impl BatchBuilder for BatchBuilderHolding {
+    async fn build_batch(&self, txs: Vec<ProvenTransaction>) -> Result<(), BuildBatchError> {
+        let batch = TransactionBatch::new(txs, Default::default())
+            .expect("Tx batch building should have succeeded");
+        self.ready_batches.write().await.push(batch);
+
+        Ok(())
+    }
+
+    async fn remove_ready_batches(
+        &self,
+        select: BatchSelection<'_>,
+    ) -> Result<Vec<TransactionBatch>, EvictTransactionError> {
+        let mut locked_ready_batches = self.ready_batches.write().await;
+        let selected = select(&locked_ready_batches)?;
+
+        let mut removed: Vec<_> = selected
+            .into_iter()
+            .rev()
+            .map(|index| locked_ready_batches.remove(index))
+            .collect();
+        removed.reverse();
+
+        Ok(removed)
+    }
+
+    async fn ready_batches(&self) -> Vec<TransactionBatch> {
+        self.ready_batches.read().await.clone()
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Returns a queue verifying transactions with `tx_validator`, which remembers rejections for
+/// `rejection_cache_ttl`
+fn queue_with_rejection_cache(
+    tx_validator: Arc<TransactionValidatorCounting>,
+    rejection_cache_ttl: Duration,
+) -> TransactionQueue<BatchBuilderFailure, TransactionValidatorCounting> {
+    TransactionQueue::new(
+        tx_validator,
+        Arc::new(BatchBuilderFailure),
+        TransactionQueueOptions {
+            build_batch_frequency: Duration::from_millis(5),
+            batch_size: 2,
+            min_batch_size: 0,
+            max_batch_wait: Duration::ZERO,
+            tick_jitter: Default::default(),
+            rejection_cache_ttl,
+            rejection_cache_capacity: 16,
+        },
+    )
+}
+
+/// Returns a queue holding the built batches, which records the reverted transactions
+fn queue_holding_batches() -> TransactionQueue<BatchBuilderHolding, TransactionValidatorRecording> {
+    TransactionQueue::new(
+        Arc::new(TransactionValidatorRecording::default()),
+        Arc::new(BatchBuilderHolding::default()),
+        TransactionQueueOptions {
+            build_batch_frequency: Duration::from_millis(5),
+            batch_size: 2,
+            min_batch_size: 0,
+            max_batch_wait: Duration::ZERO,
+            tick_jitter: Default::default(),
+            rejection_cache_ttl: Duration::ZERO,
+            rejection_cache_capacity: 0,
+        },
+    )
+}
+
 // TESTS
 // ================================================================================================
 
@@ -76,7 +284,15 @@ async fn test_build_batch_success() {
     let tx_queue = Arc::new(TransactionQueue::new(
         Arc::new(TransactionValidatorSuccess),
         Arc::new(BatchBuilderSuccess::new(sender)),
-        TransactionQueueOptions { build_batch_frequency, batch_size },
+        TransactionQueueOptions {
+            build_batch_frequency,
+            batch_size,
+            min_batch_size: 0,
+            max_batch_wait: Duration::ZERO,
+            tick_jitter: Default::default(),
+            rejection_cache_ttl: Duration::ZERO,
+            rejection_cache_capacity: 0,
+        },
     ));
 
     // Starts the transaction queue task.
@@ -169,7 +385,15 @@ async fn test_tx_verify_failure() {
     let tx_queue = Arc::new(TransactionQueue::new(
         Arc::new(TransactionValidatorFailure),
         batch_builder.clone(),
-        TransactionQueueOptions { build_batch_frequency, batch_size },
+        TransactionQueueOptions {
+            build_batch_frequency,
+            batch_size,
+            min_batch_size: 0,
+            max_batch_wait: Duration::ZERO,
+            tick_jitter: Default::default(),
+            rejection_cache_ttl: Duration::ZERO,
+            rejection_cache_capacity: 0,
+        },
     ));
 
     // Start the queue
@@ -202,7 +426,15 @@ async fn test_build_batch_failure() {
     let tx_queue = TransactionQueue::new(
         Arc::new(TransactionValidatorSuccess),
         batch_builder.clone(),
-        TransactionQueueOptions { build_batch_frequency, batch_size },
+        TransactionQueueOptions {
+            build_batch_frequency,
+            batch_size,
+            min_batch_size: 0,
+            max_batch_wait: Duration::ZERO,
+            tick_jitter: Default::default(),
+            rejection_cache_ttl: Duration::ZERO,
+            rejection_cache_capacity: 0,
+        },
     );
 
     let internal_ready_queue = tx_queue.ready_queue.clone();
@@ -223,3 +455,507 @@ async fn test_build_batch_failure() {
 
     assert_eq!(internal_ready_queue.read().await.len(), 3);
 }
+
+/// Tests that expired transactions are reverted together with the queued transactions depending on
+/// them, while the unaffected transactions are added back to the ready queue
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_expired_transactions_are_reverted() {
+    let tx_validator = Arc::new(TransactionValidatorRecording::default());
+    let events = Arc::new(TransactionEvents::default());
+    let tx_queue = TransactionQueue::new(
+        tx_validator.clone(),
+        Arc::new(BatchBuilderExpiration { inclusion_block: 10 }),
+        TransactionQueueOptions {
+            build_batch_frequency: Duration::from_millis(30),
+            batch_size: 2,
+            min_batch_size: 0,
+            max_batch_wait: Duration::ZERO,
+            tick_jitter: Default::default(),
+            rejection_cache_ttl: Duration::ZERO,
+            rejection_cache_capacity: 0,
+        },
+    )
+    .with_events(Arc::clone(&events));
+
+    let account = MockPrivateAccount::<3>::from(0);
+    let expired_tx =
+        MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1])
+            .expiration_block_num(9)
+            .build();
+    let viable_tx = MockProvenTxBuilder::with_account_index(1).build();
+    let dependent_tx =
+        MockProvenTxBuilder::with_account(account.id, account.states[1], account.states[2]).build();
+    let unrelated_tx = MockProvenTxBuilder::with_account_index(2).build();
+
+    for tx in [expired_tx.clone(), viable_tx.clone()] {
+        tx_queue.add_transaction(tx).await.unwrap();
+    }
+
+    // Spawns the batch building task, which only runs once this task yields, after the following
+    // transactions have been queued
+    tx_queue.try_build_batches().await;
+
+    // The dependent transaction is the last one to be rejected
+    let mut subscription = events.subscribe(dependent_tx.id());
+    for tx in [dependent_tx.clone(), unrelated_tx.clone()] {
+        tx_queue.add_transaction(tx).await.unwrap();
+    }
+
+    assert!(matches!(subscription.next().await, TransactionStatus::Rejected { .. }));
+    assert_eq!(*tx_validator.reverted.read().await, vec![expired_tx, dependent_tx]);
+    assert_eq!(*tx_queue.ready_queue.read().await, vec![viable_tx, unrelated_tx]);
+}
+
+/// Tests that transactions whose reference block aged past the retention of the store are reverted,
+/// instead of poisoning the batch of the other transactions
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_stale_reference_blocks_are_reverted() {
+    let accounts: Vec<_> = (1..=2).map(MockPrivateAccount::<3>::from).collect();
+    let store = Arc::new(
+        MockStoreSuccessBuilder::from_accounts(
+            accounts.iter().map(|account| (account.id, account.states[0])),
+        )
+        .block_retention_depth(2)
+        .build(),
+    );
+    let reference_blocks = Arc::new(ReferenceBlocks::default());
+    let state_view = Arc::new(
+        DefaultStateView::new(Arc::clone(&store), false)
+            .with_reference_blocks(Arc::clone(&reference_blocks)),
+    );
+    let batch_builder = DefaultBatchBuilder::new(
+        Arc::clone(&store),
+        Arc::new(DefaultBlockBuilder::new(Arc::clone(&store), Arc::clone(&state_view))),
+        DefaultBatchBuilderOptions {
+            block_frequency: Duration::from_millis(20),
+            max_batches_per_block: 2,
+            tick_jitter: Default::default(),
+            expiration_slack: 0,
+        },
+    )
+    .with_reference_blocks(reference_blocks);
+    batch_builder.refresh_retention_depth().await;
+
+    let events = Arc::new(TransactionEvents::default());
+    let tx_queue = TransactionQueue::new(
+        Arc::clone(&state_view),
+        Arc::new(batch_builder),
+        TransactionQueueOptions {
+            build_batch_frequency: Duration::from_millis(30),
+            batch_size: 2,
+            min_batch_size: 0,
+            max_batch_wait: Duration::ZERO,
+            tick_jitter: Default::default(),
+            rejection_cache_ttl: Duration::ZERO,
+            rejection_cache_capacity: 0,
+        },
+    )
+    .with_events(Arc::clone(&events));
+
+    // References the initial block of the mock chain, block 1
+    let stale_tx = MockProvenTxBuilder::with_account(
+        accounts[0].id,
+        accounts[0].states[0],
+        accounts[0].states[1],
+    )
+    .build();
+    let mut subscription = events.subscribe(stale_tx.id());
+    tx_queue.add_transaction(stale_tx).await.unwrap();
+
+    // Blocks 2 and 3 are committed while the transaction waits, so its batch can't be included
+    // before block 4, by which time block 1 is no longer retained
+    for _ in 0..2 {
+        let block = MockBlockBuilder::new(&store).await.build();
+        state_view.apply_block(&block, None).await.unwrap();
+    }
+
+    let chain_tip = store.get_latest_block_header().await.unwrap();
+    let viable_tx = MockProvenTxBuilder::with_account(
+        accounts[1].id,
+        accounts[1].states[0],
+        accounts[1].states[1],
+    )
+    .block_ref(chain_tip.hash())
+    .build();
+    tx_queue.add_transaction(viable_tx.clone()).await.unwrap();
+
+    tx_queue.try_build_batches().await;
+
+    let reason = "Stale reference block: the store only retains the 2 blocks before block 4, \
+                  rebuild the transaction against a newer block"
+        .to_string();
+    assert_eq!(subscription.next().await, TransactionStatus::Rejected { reason });
+    assert_eq!(*tx_queue.ready_queue.read().await, vec![viable_tx]);
+
+    let expected = tx_queue.get_expected_account_state(accounts[0].id).await.unwrap();
+    assert!(!expected.in_flight, "Reverted transactions must no longer be in flight");
+}
+
+/// Tests that batches below the minimum size are only built once their oldest transaction waited
+/// for `max_batch_wait`, while full batches are built right away
+#[tokio::test(start_paused = true)]
+#[miden_node_test_macro::enable_logging]
+async fn test_min_batch_size_is_bounded_by_max_wait() {
+    let build_batch_frequency = Duration::from_millis(5);
+    let max_batch_wait = Duration::from_millis(20);
+    let batch_size = 3;
+    let (sender, mut receiver) = mpsc::unbounded_channel::<TransactionBatch>();
+
+    let tx_queue = Arc::new(TransactionQueue::new(
+        Arc::new(TransactionValidatorSuccess),
+        Arc::new(BatchBuilderSuccess::new(sender)),
+        TransactionQueueOptions {
+            build_batch_frequency,
+            batch_size,
+            min_batch_size: batch_size,
+            max_batch_wait,
+            tick_jitter: Default::default(),
+            rejection_cache_ttl: Duration::ZERO,
+            rejection_cache_capacity: 0,
+        },
+    ));
+
+    tokio::spawn(tx_queue.clone().run());
+    assert_eq!(tx_queue.oldest_transaction_age().await, None);
+
+    // a lone transaction waits for more transactions until the last tick before the bound
+    let tx = MockProvenTxBuilder::with_account_index(0).build();
+    tx_queue.add_transaction(tx.clone()).await.unwrap();
+
+    tokio::time::advance(max_batch_wait - Duration::from_millis(1)).await;
+    assert_eq!(Err(TryRecvError::Empty), receiver.try_recv(), "Batch below the minimum size");
+    assert_eq!(
+        tx_queue.oldest_transaction_age().await,
+        Some(max_batch_wait - Duration::from_millis(1))
+    );
+
+    // and is batched on the first tick after waiting for `max_batch_wait`
+    tokio::time::advance(Duration::from_millis(1)).await;
+    let batch = receiver.try_recv().expect("Transaction waited for the maximum time");
+    let expected = TransactionBatch::new(vec![tx], Default::default()).expect("Valid transactions");
+    assert_eq!(expected, batch);
+    assert_eq!(tx_queue.oldest_transaction_age().await, None);
+
+    // a full batch doesn't wait, the remaining transaction does
+    let mut txs = Vec::new();
+    for i in 0..(batch_size + 1) {
+        let tx = MockProvenTxBuilder::with_account_index(i as u32).build();
+        tx_queue.add_transaction(tx.clone()).await.unwrap();
+        txs.push(tx);
+    }
+
+    tokio::time::advance(build_batch_frequency).await;
+    let batch = receiver.try_recv().expect("Full batch is built on the next tick");
+    let expected = TransactionBatch::new(txs[..batch_size].to_vec(), Default::default())
+        .expect("Valid transactions");
+    assert_eq!(expected, batch);
+    assert_eq!(Err(TryRecvError::Empty), receiver.try_recv(), "Remaining transaction waits");
+    assert_eq!(tx_queue.ready_queue.read().await.len(), 1);
+
+    // the remaining transaction keeps its age while waiting
+    tokio::time::advance(max_batch_wait - build_batch_frequency).await;
+    let batch = receiver.try_recv().expect("Transaction waited for the maximum time");
+    let expected = TransactionBatch::new(txs[batch_size..].to_vec(), Default::default())
+        .expect("Valid transactions");
+    assert_eq!(expected, batch);
+}
+
+/// Tests that repeated submissions of a transaction with an invalid proof are rejected without
+/// verifying the transaction again, while transient failures are never cached
+#[tokio::test(start_paused = true)]
+#[miden_node_test_macro::enable_logging]
+async fn test_deterministic_rejections_are_cached() {
+    let invalid_tx = MockProvenTxBuilder::with_account_index(0).build();
+    let unlucky_tx = MockProvenTxBuilder::with_account_index(1).build();
+
+    let invalid_tx_id = invalid_tx.id();
+    let tx_validator = Arc::new(TransactionValidatorCounting::new(move |tx| {
+        if tx.id() == invalid_tx_id {
+            Some(VerifyTxError::InvalidTransactionProof(tx.id()))
+        } else {
+            Some(VerifyTxError::StoreConnectionFailed(TxInputsError::GrpcClientError(
+                "store unavailable".to_string(),
+            )))
+        }
+    }));
+    let tx_queue = queue_with_rejection_cache(tx_validator.clone(), Duration::from_secs(30));
+
+    for _ in 0..3 {
+        let result = tx_queue.add_transaction(invalid_tx.clone()).await;
+        assert_eq!(
+            result,
+            Err(AddTransactionError::VerificationFailed(VerifyTxError::InvalidTransactionProof(
+                invalid_tx.id()
+            )))
+        );
+    }
+    assert_eq!(
+        tx_validator.verifications(),
+        1,
+        "Repeated submissions are served from the cache"
+    );
+
+    for attempt in 1..=3 {
+        let result = tx_queue.add_transaction(unlucky_tx.clone()).await;
+        assert!(matches!(
+            result,
+            Err(AddTransactionError::VerificationFailed(VerifyTxError::StoreConnectionFailed(_)))
+        ));
+        assert_eq!(tx_validator.verifications(), 1 + attempt, "Transient failures aren't cached");
+    }
+}
+
+/// Tests that cached rejections are forgotten after their TTL
+#[tokio::test(start_paused = true)]
+#[miden_node_test_macro::enable_logging]
+async fn test_cached_rejections_expire() {
+    let rejection_cache_ttl = Duration::from_millis(100);
+    let tx = MockProvenTxBuilder::with_account_index(0).build();
+
+    let tx_validator = Arc::new(TransactionValidatorCounting::new(|tx| {
+        Some(VerifyTxError::InvalidTransactionProof(tx.id()))
+    }));
+    let tx_queue = queue_with_rejection_cache(tx_validator.clone(), rejection_cache_ttl);
+
+    tx_queue.add_transaction(tx.clone()).await.unwrap_err();
+    assert_eq!(tx_validator.verifications(), 1);
+
+    time::advance(rejection_cache_ttl - Duration::from_millis(1)).await;
+    tx_queue.add_transaction(tx.clone()).await.unwrap_err();
+    assert_eq!(tx_validator.verifications(), 1, "Rejection is still cached");
+
+    time::advance(Duration::from_millis(1)).await;
+    tx_queue.add_transaction(tx.clone()).await.unwrap_err();
+    assert_eq!(tx_validator.verifications(), 2, "Expired rejection is verified again");
+
+    tx_queue.add_transaction(tx).await.unwrap_err();
+    assert_eq!(tx_validator.verifications(), 2, "New rejection is cached");
+}
+
+/// Tests that cached rejections are forgotten once the chain tip advances, since the rejected
+/// transaction's reference block can be committed in the meantime
+#[tokio::test(start_paused = true)]
+#[miden_node_test_macro::enable_logging]
+async fn test_cached_rejections_are_dropped_when_chain_tip_advances() {
+    let block_ref = Digest::from([ONE, ONE, ONE, ONE]);
+    let early_tx = MockProvenTxBuilder::with_account_index(0).block_ref(block_ref).build();
+    let other_tx = MockProvenTxBuilder::with_account_index(1).build();
+
+    let tx_validator = Arc::new(TransactionValidatorCounting::new(move |tx| {
+        (tx.block_ref() == block_ref)
+            .then_some(VerifyTxError::UnknownReferenceBlock { block_ref, chain_tip: 0 })
+    }));
+    let tx_queue = queue_with_rejection_cache(tx_validator.clone(), Duration::from_secs(30));
+
+    tx_queue.add_transaction(early_tx.clone()).await.unwrap_err();
+    tx_queue.add_transaction(early_tx.clone()).await.unwrap_err();
+    assert_eq!(tx_validator.verifications(), 1);
+
+    // Transactions accepted at the same chain tip keep the rejection cached
+    tx_queue.add_transaction(other_tx.clone()).await.unwrap();
+    tx_queue.add_transaction(early_tx.clone()).await.unwrap_err();
+    assert_eq!(tx_validator.verifications(), 2);
+
+    tx_validator.block_height.store(1, Ordering::Relaxed);
+    tx_queue.add_transaction(other_tx).await.unwrap();
+    tx_queue.add_transaction(early_tx).await.unwrap_err();
+    assert_eq!(
+        tx_validator.verifications(),
+        4,
+        "Rejection is verified again at the new chain tip"
+    );
+}
+
+/// Tests that a queued transaction is only evicted along with the transactions depending on it if
+/// the eviction cascades
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_evict_queued_transaction() {
+    let events = Arc::new(TransactionEvents::default());
+    let tx_queue = queue_holding_batches().with_events(events.clone());
+
+    let account = MockPrivateAccount::<3>::from(0);
+    let txs: Vec<_> = (0..2)
+        .map(|i| {
+            MockProvenTxBuilder::with_account(account.id, account.states[i], account.states[i + 1])
+                .build()
+        })
+        .collect();
+    let unrelated_tx = MockProvenTxBuilder::with_account_index(1).build();
+    for tx in txs.iter().chain([&unrelated_tx]) {
+        tx_queue.add_transaction(tx.clone()).await.unwrap();
+    }
+
+    let result = tx_queue.evict_transaction(txs[0].id(), false).await;
+    assert_eq!(
+        result,
+        Err(EvictTransactionError::HasDependents {
+            tx_id: txs[0].id(),
+            dependents: vec![txs[1].id()],
+        })
+    );
+    assert_eq!(tx_queue.ready_queue.read().await.len(), 3, "Refused eviction keeps the queue");
+    assert!(tx_queue.tx_validator.reverted.read().await.is_empty());
+
+    let eviction = tx_queue.evict_transaction(unrelated_tx.id(), false).await.unwrap();
+    assert_eq!(eviction.evicted_transactions, vec![unrelated_tx.id()]);
+
+    let mut subscription = events.subscribe(txs[1].id());
+    let eviction = tx_queue.evict_transaction(txs[0].id(), true).await.unwrap();
+    assert_eq!(
+        eviction,
+        Eviction {
+            evicted_transactions: vec![txs[0].id(), txs[1].id()],
+            ..Default::default()
+        }
+    );
+    assert!(tx_queue.ready_queue.read().await.is_empty());
+    assert_eq!(
+        *tx_queue.tx_validator.reverted.read().await,
+        vec![unrelated_tx, txs[0].clone(), txs[1].clone()]
+    );
+    assert!(matches!(subscription.next().await, TransactionStatus::Rejected { .. }));
+    assert_eq!(
+        tx_queue.oldest_transaction_age().await,
+        None,
+        "Evicted transactions are forgotten"
+    );
+}
+
+/// Tests that evicting a batched transaction drops its batch along with the later batches
+/// depending on it, and queues the remaining transactions of the dropped batches again
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_evict_batched_transaction() {
+    let tx_queue = queue_holding_batches();
+
+    let account = MockPrivateAccount::<4>::from(0);
+    let account_txs: Vec<_> = (0..3)
+        .map(|i| {
+            MockProvenTxBuilder::with_account(account.id, account.states[i], account.states[i + 1])
+                .build()
+        })
+        .collect();
+    let neighbour_tx = MockProvenTxBuilder::with_account_index(1).build();
+    let unrelated_tx = MockProvenTxBuilder::with_account_index(2).build();
+
+    let batch_builder = &tx_queue.batch_builder;
+    for txs in [
+        vec![account_txs[0].clone(), neighbour_tx.clone()],
+        vec![account_txs[1].clone()],
+        vec![unrelated_tx.clone()],
+    ] {
+        batch_builder.build_batch(txs).await.unwrap();
+    }
+    tx_queue.add_transaction(account_txs[2].clone()).await.unwrap();
+    let batch_ids: Vec<_> = batch_builder
+        .ready_batches
+        .read()
+        .await
+        .iter()
+        .map(TransactionBatch::id)
+        .collect();
+
+    let result = tx_queue.evict_transaction(account_txs[0].id(), false).await;
+    assert_eq!(
+        result,
+        Err(EvictTransactionError::HasDependents {
+            tx_id: account_txs[0].id(),
+            dependents: vec![account_txs[1].id(), account_txs[2].id()],
+        })
+    );
+    assert_eq!(
+        batch_builder.ready_batches.read().await.len(),
+        3,
+        "Refused eviction keeps batches"
+    );
+
+    let eviction = tx_queue.evict_transaction(account_txs[0].id(), true).await.unwrap();
+    assert_eq!(
+        eviction,
+        Eviction {
+            evicted_transactions: account_txs.iter().map(ProvenTransaction::id).collect(),
+            dropped_batches: batch_ids[..2].to_vec(),
+            requeued_transactions: vec![neighbour_tx.id()],
+        }
+    );
+    assert_eq!(*tx_queue.ready_queue.read().await, vec![neighbour_tx]);
+    assert_eq!(*tx_queue.tx_validator.reverted.read().await, account_txs);
+
+    let eviction = tx_queue.evict_transaction(unrelated_tx.id(), false).await.unwrap();
+    assert_eq!(eviction.dropped_batches, vec![batch_ids[2]]);
+    assert!(batch_builder.ready_batches.read().await.is_empty());
+
+    let result = tx_queue.evict_transaction(unrelated_tx.id(), false).await;
+    assert_eq!(result, Err(EvictTransactionError::NotFound(unrelated_tx.id())));
+}
+
+/// Tests that the mempool graph links the in-flight transactions to the transactions they depend
+/// on, and the batches to the batches they depend on
+#[tokio::test]
+#[miden_node_test_macro::enable_logging]
+async fn test_mempool_graph() {
+    let tx_queue = queue_holding_batches();
+
+    let account = MockPrivateAccount::<4>::from(0);
+    let account_txs: Vec<_> = (0..3)
+        .map(|i| {
+            MockProvenTxBuilder::with_account(account.id, account.states[i], account.states[i + 1])
+                .build()
+        })
+        .collect();
+    let unrelated_tx = MockProvenTxBuilder::with_account_index(1).build();
+    let batching_tx = MockProvenTxBuilder::with_account_index(2).build();
+
+    let batch_builder = &tx_queue.batch_builder;
+    for txs in [vec![account_txs[0].clone()], vec![account_txs[1].clone(), unrelated_tx.clone()]] {
+        batch_builder.build_batch(txs).await.unwrap();
+    }
+    tx_queue.add_transaction(account_txs[2].clone()).await.unwrap();
+    tx_queue.queued_at.lock().unwrap().insert(batching_tx.id(), Instant::now());
+    let batch_ids: Vec<_> = batch_builder
+        .ready_batches
+        .read()
+        .await
+        .iter()
+        .map(TransactionBatch::id)
+        .collect();
+
+    let graph = tx_queue.mempool_graph().await;
+    let nodes: Vec<_> = graph
+        .transactions
+        .iter()
+        .map(|tx| (tx.id, tx.status, tx.batch, tx.dependencies.clone()))
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            (account_txs[0].id(), TransactionNodeStatus::Batched, Some(batch_ids[0]), vec![]),
+            (
+                account_txs[1].id(),
+                TransactionNodeStatus::Batched,
+                Some(batch_ids[1]),
+                vec![account_txs[0].id()]
+            ),
+            (unrelated_tx.id(), TransactionNodeStatus::Batched, Some(batch_ids[1]), vec![]),
+            (
+                account_txs[2].id(),
+                TransactionNodeStatus::Queued,
+                None,
+                vec![account_txs[1].id()]
+            ),
+            (batching_tx.id(), TransactionNodeStatus::Batching, None, vec![]),
+        ]
+    );
+    assert!(graph.batches[0].dependencies.is_empty());
+    assert_eq!(graph.batches[1].dependencies, vec![batch_ids[0]]);
+
+    let edge =
+        format!("\"{}\" -> \"{}\";", account_txs[2].id().to_hex(), account_txs[1].id().to_hex());
+    assert!(graph.to_dot().contains(&edge));
+    let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+    assert_eq!(json["transactions"][3]["status"], "queued");
+    assert_eq!(json["batches"][1]["dependencies"][0], format_blake3_digest(batch_ids[0]));
+}
@@ -2,20 +2,24 @@ use std::fmt::{Debug, Display, Formatter};
 
 use miden_node_utils::formatting::format_opt;
 use miden_objects::{
-    accounts::{Account, AccountHeader, AccountId},
+    accounts::{Account, AccountHeader, AccountId, AccountStorageHeader},
     crypto::{hash::rpo::RpoDigest, merkle::MerklePath},
-    utils::Serializable,
-    Digest,
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+    BlockHeader, Digest, ACCOUNT_TREE_DEPTH,
 };
 
+use super::merkle::{compress_merkle_path, decompress_merkle_path};
 use crate::{
-    errors::{ConversionError, MissingFieldHelper},
+    errors::{AccountProofBundleError, ConversionError, MissingFieldHelper},
     generated::{
         account::{
             AccountHeader as AccountHeaderPb, AccountId as AccountIdPb,
-            AccountInfo as AccountInfoPb, AccountSummary as AccountSummaryPb,
+            AccountInfo as AccountInfoPb, AccountProofBundle as AccountProofBundlePb,
+            AccountStorageMode as AccountStorageModePb, AccountSummary as AccountSummaryPb,
+        },
+        responses::{
+            AccountBlockInputRecord, AccountProofsResponse, AccountTransactionInputRecord,
         },
-        responses::{AccountBlockInputRecord, AccountTransactionInputRecord},
     },
 };
 
@@ -80,14 +84,25 @@ pub struct AccountSummary {
     pub account_id: AccountId,
     pub account_hash: RpoDigest,
     pub block_num: u32,
+    /// Whether the full details of the account are stored, which is only the case for public
+    /// accounts.
+    pub details_available: bool,
 }
 
 impl From<&AccountSummary> for AccountSummaryPb {
     fn from(update: &AccountSummary) -> Self {
+        let storage_mode = if update.account_id.is_public() {
+            AccountStorageModePb::Public
+        } else {
+            AccountStorageModePb::Private
+        };
+
         Self {
             account_id: Some(update.account_id.into()),
             account_hash: Some(update.account_hash.into()),
             block_num: update.block_num,
+            storage_mode: storage_mode.into(),
+            details_available: update.details_available,
         }
     }
 }
@@ -216,3 +231,379 @@ impl TryFrom<AccountTransactionInputRecord> for AccountState {
         Ok(Self { account_id, account_hash })
     }
 }
+
+// ACCOUNT PROOF BUNDLE
+// ================================================================================================
+
+/// Version of the native encoding of [AccountProofBundle].
+const ACCOUNT_PROOF_BUNDLE_VERSION: u8 = 0;
+
+/// Proof of the state of an account at a block, bundled into a single value which clients can
+/// pass through to their proving pipelines.
+///
+/// The bundle refers to its block by number and commitment instead of embedding its header, and
+/// [AccountProofBundle::verify] checks it against a header known to the client. Its native
+/// encoding starts with a version byte, and omits the roots of empty subtrees from the account
+/// proof, as [CompressedMerklePath](crate::generated::merkle::CompressedMerklePath) does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountProofBundle {
+    /// Number of the block whose account root the proof opens.
+    pub block_num: u32,
+    /// Commitment to the block.
+    pub block_commitment: Digest,
+    pub account_id: AccountId,
+    pub account_hash: Digest,
+    /// Authentication path from the account root of the block to the account.
+    pub account_proof: MerklePath,
+    /// Storage header of public accounts.
+    pub storage_header: Option<AccountStorageHeader>,
+    /// Code commitment of public accounts.
+    pub code_commitment: Option<Digest>,
+}
+
+impl AccountProofBundle {
+    /// Bundles an account proof returned by `GetAccountProofs` for the block `block_num`, whose
+    /// commitment is `block_commitment`.
+    ///
+    /// The storage header and code commitment are only bundled if the response holds the state
+    /// header of the account.
+    pub fn from_proofs_response(
+        block_num: u32,
+        block_commitment: Digest,
+        response: &AccountProofsResponse,
+    ) -> Result<Self, ConversionError> {
+        let state_header = response.state_header.as_ref();
+        let storage_header = state_header
+            .map(|state_header| AccountStorageHeader::read_from_bytes(&state_header.storage_header))
+            .transpose()?;
+        let code_commitment = state_header
+            .and_then(|state_header| state_header.header)
+            .map(|header| {
+                header
+                    .code_commitment
+                    .ok_or(AccountHeaderPb::missing_field(stringify!(code_commitment)))?
+                    .try_into()
+            })
+            .transpose()?;
+
+        Ok(Self {
+            block_num,
+            block_commitment,
+            account_id: response
+                .account_id
+                .ok_or(AccountProofsResponse::missing_field(stringify!(account_id)))?
+                .try_into()?,
+            account_hash: response
+                .account_hash
+                .ok_or(AccountProofsResponse::missing_field(stringify!(account_hash)))?
+                .try_into()?,
+            account_proof: response
+                .account_proof
+                .as_ref()
+                .ok_or(AccountProofsResponse::missing_field(stringify!(account_proof)))?
+                .try_into()?,
+            storage_header,
+            code_commitment,
+        })
+    }
+
+    /// Checks that the bundle refers to the block of `header`, and that its account proof opens
+    /// the account root of the block to the account hash.
+    pub fn verify(&self, header: &BlockHeader) -> Result<(), AccountProofBundleError> {
+        if header.block_num() != self.block_num || header.hash() != self.block_commitment {
+            return Err(AccountProofBundleError::BlockMismatch {
+                bundle_block_num: self.block_num,
+                bundle_commitment: self.block_commitment,
+                block_num: header.block_num(),
+                commitment: header.hash(),
+            });
+        }
+
+        if self.account_proof.depth() != ACCOUNT_TREE_DEPTH {
+            return Err(AccountProofBundleError::InvalidProofDepth(self.account_proof.depth()));
+        }
+
+        let account_root = self
+            .account_proof
+            .compute_root(self.account_id.into(), self.account_hash)
+            .map_err(|_| AccountProofBundleError::InvalidAccountProof(self.account_id))?;
+        if account_root != header.account_root() {
+            return Err(AccountProofBundleError::InvalidAccountProof(self.account_id));
+        }
+
+        Ok(())
+    }
+}
+
+impl Serializable for AccountProofBundle {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        let (empty_nodes, siblings) = compress_merkle_path(&self.account_proof);
+
+        target.write_u8(ACCOUNT_PROOF_BUNDLE_VERSION);
+        target.write_u32(self.block_num);
+        target.write(self.block_commitment);
+        target.write(self.account_id);
+        target.write(self.account_hash);
+        target.write_u8(self.account_proof.depth());
+        target.write_u64(empty_nodes);
+        target.write_many(siblings);
+
+        target.write_bool(self.storage_header.is_some());
+        if let Some(storage_header) = &self.storage_header {
+            target.write(storage_header);
+        }
+        target.write_bool(self.code_commitment.is_some());
+        if let Some(code_commitment) = self.code_commitment {
+            target.write(code_commitment);
+        }
+    }
+}
+
+impl Deserializable for AccountProofBundle {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let version = source.read_u8()?;
+        if version != ACCOUNT_PROOF_BUNDLE_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported account proof bundle version {version}"
+            )));
+        }
+
+        let block_num = source.read_u32()?;
+        let block_commitment = source.read()?;
+        let account_id = source.read()?;
+        let account_hash = source.read()?;
+
+        let depth = source.read_u8()?;
+        let empty_nodes = source.read_u64()?;
+        let num_siblings = u32::from(depth).saturating_sub(empty_nodes.count_ones());
+        let siblings = source.read_many(num_siblings as usize)?;
+        let account_proof = decompress_merkle_path(depth, empty_nodes, siblings)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+
+        let storage_header = source.read_bool()?.then(|| source.read()).transpose()?;
+        let code_commitment = source.read_bool()?.then(|| source.read()).transpose()?;
+
+        Ok(Self {
+            block_num,
+            block_commitment,
+            account_id,
+            account_hash,
+            account_proof,
+            storage_header,
+            code_commitment,
+        })
+    }
+}
+
+impl From<&AccountProofBundle> for AccountProofBundlePb {
+    fn from(bundle: &AccountProofBundle) -> Self {
+        Self {
+            block_num: bundle.block_num,
+            block_commitment: Some(bundle.block_commitment.into()),
+            account_id: Some(bundle.account_id.into()),
+            account_hash: Some(bundle.account_hash.into()),
+            account_proof: Some((&bundle.account_proof).into()),
+            storage_header: bundle.storage_header.as_ref().map(Serializable::to_bytes),
+            code_commitment: bundle.code_commitment.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<AccountProofBundlePb> for AccountProofBundle {
+    type Error = ConversionError;
+
+    fn try_from(bundle: AccountProofBundlePb) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_num: bundle.block_num,
+            block_commitment: bundle
+                .block_commitment
+                .ok_or(AccountProofBundlePb::missing_field(stringify!(block_commitment)))?
+                .try_into()?,
+            account_id: bundle
+                .account_id
+                .ok_or(AccountProofBundlePb::missing_field(stringify!(account_id)))?
+                .try_into()?,
+            account_hash: bundle
+                .account_hash
+                .ok_or(AccountProofBundlePb::missing_field(stringify!(account_hash)))?
+                .try_into()?,
+            account_proof: bundle
+                .account_proof
+                .as_ref()
+                .ok_or(AccountProofBundlePb::missing_field(stringify!(account_proof)))?
+                .try_into()?,
+            storage_header: bundle
+                .storage_header
+                .map(|storage_header| AccountStorageHeader::read_from_bytes(&storage_header))
+                .transpose()?,
+            code_commitment: bundle.code_commitment.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{
+        accounts::{
+            account_id::testing::{
+                ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN,
+                ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_ON_CHAIN,
+            },
+            AccountStorage, StorageSlot,
+        },
+        crypto::merkle::{LeafIndex, SimpleSmt},
+        Felt, ZERO,
+    };
+
+    use super::*;
+    use crate::generated::responses::AccountStateHeader;
+
+    fn digest(value: u64) -> Digest {
+        Digest::new([Felt::new(value), ZERO, ZERO, ZERO])
+    }
+
+    /// Returns the header of a block whose account tree holds a private and a public account,
+    /// along with the bundle of the proof of the public account.
+    fn block_with_bundle() -> (BlockHeader, AccountProofBundle) {
+        let account_id =
+            AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_ON_CHAIN).unwrap();
+        let account_tree = SimpleSmt::<ACCOUNT_TREE_DEPTH>::with_leaves([
+            (ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN, digest(1).into()),
+            (account_id.into(), digest(2).into()),
+        ])
+        .unwrap();
+
+        let header = BlockHeader::new(
+            1,
+            digest(3),
+            7,
+            Digest::default(),
+            account_tree.root(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            0,
+        );
+        let storage = AccountStorage::new(vec![StorageSlot::Value(digest(4).into())]).unwrap();
+        let bundle = AccountProofBundle {
+            block_num: header.block_num(),
+            block_commitment: header.hash(),
+            account_id,
+            account_hash: digest(2),
+            account_proof: account_tree.open(&LeafIndex::new_max_depth(account_id.into())).path,
+            storage_header: Some(storage.get_header()),
+            code_commitment: Some(digest(5)),
+        };
+
+        (header, bundle)
+    }
+
+    #[test]
+    fn bundles_round_trip_through_the_native_and_proto_encodings() {
+        let (_, bundle) = block_with_bundle();
+        let private_bundle = AccountProofBundle {
+            storage_header: None,
+            code_commitment: None,
+            ..bundle.clone()
+        };
+
+        for bundle in [bundle, private_bundle] {
+            let bytes = bundle.to_bytes();
+            assert_eq!(AccountProofBundle::read_from_bytes(&bytes).unwrap(), bundle);
+            let proto = AccountProofBundlePb::from(&bundle);
+            assert_eq!(AccountProofBundle::try_from(proto), Ok(bundle.clone()));
+
+            // The roots of the empty subtrees are left out of the encoding
+            assert!(bytes.len() < bundle.account_proof.depth() as usize * 32);
+        }
+    }
+
+    #[test]
+    fn bundles_are_built_from_proofs_responses() {
+        let (_, bundle) = block_with_bundle();
+        let account_header = AccountHeaderPb {
+            vault_root: Some(digest(6).into()),
+            storage_commitment: Some(digest(7).into()),
+            code_commitment: bundle.code_commitment.map(Into::into),
+            nonce: 1,
+        };
+        let response = AccountProofsResponse {
+            account_id: Some(bundle.account_id.into()),
+            account_hash: Some(bundle.account_hash.into()),
+            account_proof: Some((&bundle.account_proof).into()),
+            state_header: Some(AccountStateHeader {
+                header: Some(account_header),
+                storage_header: bundle.storage_header.as_ref().unwrap().to_bytes(),
+                account_code: None,
+            }),
+            ..Default::default()
+        };
+
+        let bundled = AccountProofBundle::from_proofs_response(
+            bundle.block_num,
+            bundle.block_commitment,
+            &response,
+        );
+        assert_eq!(bundled, Ok(bundle.clone()));
+
+        // Responses without state header only bundle the account proof
+        let response = AccountProofsResponse { state_header: None, ..response };
+        let bundled = AccountProofBundle::from_proofs_response(
+            bundle.block_num,
+            bundle.block_commitment,
+            &response,
+        );
+        assert_eq!(
+            bundled,
+            Ok(AccountProofBundle {
+                storage_header: None,
+                code_commitment: None,
+                ..bundle
+            })
+        );
+    }
+
+    #[test]
+    fn bundles_are_verified_against_the_header_of_their_block() {
+        let (header, bundle) = block_with_bundle();
+        assert_eq!(bundle.verify(&header), Ok(()));
+
+        let other_block = AccountProofBundle { block_num: 8, ..bundle.clone() };
+        assert!(matches!(
+            other_block.verify(&header),
+            Err(AccountProofBundleError::BlockMismatch { bundle_block_num: 8, block_num: 7, .. })
+        ));
+
+        let other_hash = AccountProofBundle {
+            account_hash: digest(1),
+            ..bundle.clone()
+        };
+        assert_eq!(
+            other_hash.verify(&header),
+            Err(AccountProofBundleError::InvalidAccountProof(bundle.account_id))
+        );
+
+        let short_proof = AccountProofBundle {
+            account_proof: bundle.account_proof.nodes()[1..].iter().copied().collect(),
+            ..bundle
+        };
+        assert_eq!(
+            short_proof.verify(&header),
+            Err(AccountProofBundleError::InvalidProofDepth(ACCOUNT_TREE_DEPTH - 1))
+        );
+    }
+
+    #[test]
+    fn bundles_of_unknown_versions_are_rejected() {
+        let (_, bundle) = block_with_bundle();
+        let mut bytes = bundle.to_bytes();
+        bytes[0] = ACCOUNT_PROOF_BUNDLE_VERSION + 1;
+
+        assert!(AccountProofBundle::read_from_bytes(&bytes).is_err());
+    }
+}
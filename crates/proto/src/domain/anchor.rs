@@ -0,0 +1,153 @@
+//! Chain anchors of the read responses.
+//!
+//! Every read response of the store carries the [ChainAnchor] of the chain tip it was computed
+//! against. Responses with the same anchor are consistent with each other, e.g. a nullifier
+//! reported as unspent by one of them is not reported as spent by another. Clients combining the
+//! results of several reads check that they agree before using them together:
+//!
+//! ```ignore
+//! let notes = client.get_notes_by_id(notes_request).await?.into_inner();
+//! let nullifiers = client.check_nullifiers(nullifiers_request).await?.into_inner();
+//! if !same_anchor(&notes, &nullifiers) {
+//!     // A block was applied in-between, retry the reads
+//! }
+//! ```
+
+use miden_objects::crypto::hash::rpo::RpoDigest;
+
+use crate::{
+    errors::{ConversionError, MissingFieldHelper},
+    generated::{
+        block::ChainAnchor,
+        responses::{
+            CheckNotesConsumedResponse, CheckNullifiersByPrefixResponse, CheckNullifiersResponse,
+            GetAccountDetailsResponse, GetAccountProofsResponse, GetAccountStateDeltaResponse,
+            GetAccountStorageHeaderResponse, GetAccountWitnessesResponse, GetBlockByNumberResponse,
+            GetBlockHeaderByCommitmentResponse, GetBlockHeaderByNumberResponse,
+            GetBlockHeadersRangeResponse, GetBlockNoteFiltersResponse, GetDataAvailabilityResponse,
+            GetNoteTagStatisticsResponse, GetNotesByIdResponse, GetNotesByRecipientResponse,
+            SyncAccountCompactResponse, SyncNoteResponse, SyncStateResponse,
+        },
+    },
+};
+
+// CHAIN ANCHOR
+// ================================================================================================
+
+impl ChainAnchor {
+    /// Returns the anchor of the block `block_num` with the given commitment.
+    pub fn new(block_num: u32, block_commitment: RpoDigest) -> Self {
+        Self {
+            block_num,
+            block_commitment: Some(block_commitment.into()),
+        }
+    }
+
+    /// Returns the commitment to the anchor's block.
+    pub fn commitment(&self) -> Result<RpoDigest, ConversionError> {
+        self.block_commitment
+            .ok_or(ChainAnchor::missing_field(stringify!(block_commitment)))?
+            .try_into()
+    }
+}
+
+// ANCHORED RESPONSES
+// ================================================================================================
+
+/// A response computed against a chain tip.
+pub trait Anchored {
+    /// Returns the anchor of the response, [None] if the server didn't set it.
+    fn anchor(&self) -> Option<&ChainAnchor>;
+}
+
+macro_rules! impl_anchored {
+    ($($response:ty),+ $(,)?) => {
+        $(
+            impl Anchored for $response {
+                fn anchor(&self) -> Option<&ChainAnchor> {
+                    self.anchor.as_ref()
+                }
+            }
+        )+
+    };
+}
+
+impl_anchored!(
+    CheckNotesConsumedResponse,
+    CheckNullifiersResponse,
+    CheckNullifiersByPrefixResponse,
+    GetAccountDetailsResponse,
+    GetAccountProofsResponse,
+    GetAccountStateDeltaResponse,
+    GetAccountStorageHeaderResponse,
+    GetAccountWitnessesResponse,
+    GetBlockByNumberResponse,
+    GetBlockNoteFiltersResponse,
+    GetBlockHeaderByCommitmentResponse,
+    GetBlockHeaderByNumberResponse,
+    GetBlockHeadersRangeResponse,
+    GetDataAvailabilityResponse,
+    GetNoteTagStatisticsResponse,
+    GetNotesByIdResponse,
+    GetNotesByRecipientResponse,
+    SyncAccountCompactResponse,
+    SyncNoteResponse,
+    SyncStateResponse,
+);
+
+/// Returns whether both responses were computed against the same chain tip.
+///
+/// Responses without an anchor are never considered consistent.
+pub fn same_anchor(a: &impl Anchored, b: &impl Anchored) -> bool {
+    match (a.anchor(), b.anchor()) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{Felt, ZERO};
+
+    use super::*;
+
+    fn anchor(block_num: u32) -> ChainAnchor {
+        ChainAnchor::new(block_num, [Felt::new(block_num.into()), ZERO, ZERO, ZERO].into())
+    }
+
+    #[test]
+    fn responses_with_equal_anchors_are_consistent() {
+        let notes = GetNotesByIdResponse {
+            anchor: Some(anchor(5)),
+            ..Default::default()
+        };
+        let nullifiers = CheckNullifiersResponse {
+            anchor: Some(anchor(5)),
+            ..Default::default()
+        };
+        assert!(same_anchor(&notes, &nullifiers));
+        assert_eq!(
+            nullifiers.anchor().unwrap().commitment().unwrap(),
+            notes.anchor.unwrap().commitment().unwrap()
+        );
+
+        let nullifiers = CheckNullifiersResponse {
+            anchor: Some(anchor(6)),
+            ..Default::default()
+        };
+        assert!(!same_anchor(&notes, &nullifiers));
+
+        // Anchors must agree on the commitment as well
+        let nullifiers = CheckNullifiersResponse {
+            anchor: Some(ChainAnchor::new(5, RpoDigest::default())),
+            ..Default::default()
+        };
+        assert!(!same_anchor(&notes, &nullifiers));
+
+        let unanchored = CheckNullifiersResponse::default();
+        assert!(!same_anchor(&unanchored, &unanchored));
+    }
+}
@@ -0,0 +1,122 @@
+//! Historical data served by the nodes.
+//!
+//! Nodes can prune the data of old blocks, and skip storing the details of some public notes.
+//! Clients ask a node which historical data it serves with `GetDataAvailability`, and route the
+//! queries it can't answer to an archive node:
+//!
+//! ```ignore
+//! let availability = client.get_data_availability(GetDataAvailabilityRequest {}).await?;
+//! let client = if availability.get_ref().serves_block(block_num) {
+//!     client
+//! } else {
+//!     archive_client
+//! };
+//! let block = client.get_block_by_number(GetBlockByNumberRequest { block_num }).await?;
+//! ```
+
+use miden_objects::{crypto::hash::rpo::RpoDigest, GENESIS_BLOCK};
+
+use crate::generated::{
+    digest::Digest,
+    note::{NoteDetailsAvailability, NoteDetailsPolicy},
+    responses::GetDataAvailabilityResponse,
+};
+
+impl GetDataAvailabilityResponse {
+    /// Returns whether the node serves the full data of the block with `GetBlockByNumber`, if the
+    /// block is part of its chain.
+    pub fn serves_block(&self, block_num: u32) -> bool {
+        block_num == GENESIS_BLOCK || block_num >= self.oldest_block_data
+    }
+
+    /// Returns whether the node returns the state deltas of public accounts since the block known
+    /// to the client with `GetAccountProofs`.
+    pub fn serves_account_deltas_since(&self, known_block_num: u32) -> bool {
+        known_block_num >= self.oldest_account_delta_block
+    }
+
+    /// Returns whether the node stores the details of the public notes with the script and tag,
+    /// if they were created after its note details policy was set.
+    ///
+    /// Returns `false` if the node didn't report its policy, or reported an unknown one.
+    pub fn stores_note_details(&self, script_root: &RpoDigest, tag: u32) -> bool {
+        self.note_details
+            .as_ref()
+            .is_some_and(|note_details| note_details.stores(script_root, tag))
+    }
+}
+
+impl NoteDetailsAvailability {
+    /// Returns whether the details of the public notes with the script and tag are stored.
+    ///
+    /// Returns `false` if the policy is unknown.
+    pub fn stores(&self, script_root: &RpoDigest, tag: u32) -> bool {
+        match NoteDetailsPolicy::try_from(self.policy) {
+            Ok(NoteDetailsPolicy::Full) => true,
+            Ok(NoteDetailsPolicy::MetadataOnly) | Err(_) => false,
+            Ok(NoteDetailsPolicy::Selective) => {
+                let script_root = Digest::from(script_root);
+                self.script_roots.contains(&script_root)
+                    || self.tags.iter().any(|range| (range.from..=range.to).contains(&tag))
+            },
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{Felt, ZERO};
+
+    use super::*;
+    use crate::generated::note::NoteTagRange;
+
+    fn availability(policy: NoteDetailsPolicy) -> GetDataAvailabilityResponse {
+        GetDataAvailabilityResponse {
+            oldest_block_data: 10,
+            oldest_account_delta_block: 9,
+            note_details: Some(NoteDetailsAvailability {
+                policy: policy.into(),
+                script_roots: vec![Digest::from([Felt::new(1), ZERO, ZERO, ZERO])],
+                tags: vec![NoteTagRange { from: 100, to: 200 }],
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pruned_blocks_and_deltas_are_not_served() {
+        let availability = availability(NoteDetailsPolicy::Full);
+
+        assert!(availability.serves_block(GENESIS_BLOCK));
+        assert!(!availability.serves_block(9));
+        assert!(availability.serves_block(10));
+
+        assert!(!availability.serves_account_deltas_since(8));
+        assert!(availability.serves_account_deltas_since(9));
+    }
+
+    #[test]
+    fn note_details_follow_the_policy() {
+        let script_root = RpoDigest::from([Felt::new(1), ZERO, ZERO, ZERO]);
+        let other_root = RpoDigest::default();
+
+        let full = availability(NoteDetailsPolicy::Full);
+        assert!(full.stores_note_details(&other_root, 0));
+
+        let metadata_only = availability(NoteDetailsPolicy::MetadataOnly);
+        assert!(!metadata_only.stores_note_details(&script_root, 150));
+
+        let selective = availability(NoteDetailsPolicy::Selective);
+        assert!(selective.stores_note_details(&script_root, 0));
+        assert!(selective.stores_note_details(&other_root, 200));
+        assert!(!selective.stores_note_details(&other_root, 201));
+
+        let mut unknown = availability(NoteDetailsPolicy::Full);
+        unknown.note_details.as_mut().unwrap().policy = 42;
+        assert!(!unknown.stores_note_details(&script_root, 150));
+        assert!(!GetDataAvailabilityResponse::default().stores_note_details(&script_root, 150));
+    }
+}
@@ -1,8 +1,9 @@
+use miden_node_utils::block_versions::{BlockVersionRange, BlockVersionUpgrade, BlockVersions};
 use miden_objects::{crypto::merkle::MerklePath, BlockHeader};
 
 use crate::{
     errors::{ConversionError, MissingFieldHelper},
-    generated::block as proto,
+    generated::{block as proto, responses::GetSupportedBlockVersionsResponse},
 };
 
 // BLOCK HEADER
@@ -121,3 +122,58 @@ impl TryFrom<proto::BlockInclusionProof> for BlockInclusionProof {
         Ok(result)
     }
 }
+
+// BLOCK VERSIONS
+// ================================================================================================
+
+impl From<BlockVersionRange> for proto::BlockVersionRange {
+    fn from(range: BlockVersionRange) -> Self {
+        Self { min: range.min, max: range.max }
+    }
+}
+
+impl From<proto::BlockVersionRange> for BlockVersionRange {
+    fn from(range: proto::BlockVersionRange) -> Self {
+        Self { min: range.min, max: range.max }
+    }
+}
+
+impl From<BlockVersions> for GetSupportedBlockVersionsResponse {
+    fn from(versions: BlockVersions) -> Self {
+        Self {
+            supported: Some(versions.supported.into()),
+            upgrade: versions.upgrade.map(|upgrade| proto::BlockVersionUpgrade {
+                activation_block: upgrade.activation_block,
+                supported: Some(upgrade.supported.into()),
+            }),
+        }
+    }
+}
+
+impl TryFrom<GetSupportedBlockVersionsResponse> for BlockVersions {
+    type Error = ConversionError;
+
+    fn try_from(value: GetSupportedBlockVersionsResponse) -> Result<Self, Self::Error> {
+        let upgrade = value
+            .upgrade
+            .map(|upgrade| {
+                let supported = upgrade
+                    .supported
+                    .ok_or(proto::BlockVersionUpgrade::missing_field(stringify!(supported)))?;
+
+                Ok::<_, ConversionError>(BlockVersionUpgrade {
+                    activation_block: upgrade.activation_block,
+                    supported: supported.into(),
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            supported: value
+                .supported
+                .ok_or(GetSupportedBlockVersionsResponse::missing_field(stringify!(supported)))?
+                .into(),
+            upgrade,
+        })
+    }
+}
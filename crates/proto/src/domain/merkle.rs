@@ -1,5 +1,7 @@
+use std::cmp::Ordering;
+
 use miden_objects::{
-    crypto::merkle::{LeafIndex, MerklePath, MmrDelta, SmtLeaf, SmtProof},
+    crypto::merkle::{EmptySubtreeRoots, LeafIndex, MerklePath, MmrDelta, SmtLeaf, SmtProof},
     Digest, Word,
 };
 
@@ -33,6 +35,115 @@ impl TryFrom<&generated::merkle::MerklePath> for MerklePath {
     }
 }
 
+// COMPRESSED MERKLE PATH
+// ================================================================================================
+
+/// Number of nodes of a path which can be marked as empty subtree roots, i.e. the number of bits
+/// of [generated::merkle::CompressedMerklePath::empty_nodes].
+const MAX_COMPRESSED_NODES: usize = u64::BITS as usize;
+
+/// Returns the root of the empty subtree whose sibling is the node at `index` of a path of `depth`
+/// nodes, the first node being the sibling of the leaf.
+fn empty_path_node(depth: usize, index: usize) -> Digest {
+    *EmptySubtreeRoots::entry(depth as u8, (depth - index) as u8)
+}
+
+/// Splits `path` into the bitmask of its nodes which are roots of empty subtrees, and its other
+/// nodes, as in [generated::merkle::CompressedMerklePath].
+pub(crate) fn compress_merkle_path(path: &MerklePath) -> (u64, Vec<Digest>) {
+    let depth = path.depth() as usize;
+
+    let mut empty_nodes = 0u64;
+    let mut siblings = Vec::new();
+    for (index, node) in path.nodes().iter().enumerate() {
+        // Only the first nodes can be marked in the mask, the others are always included
+        if index < MAX_COMPRESSED_NODES && *node == empty_path_node(depth, index) {
+            empty_nodes |= 1 << index;
+        } else {
+            siblings.push(*node);
+        }
+    }
+
+    (empty_nodes, siblings)
+}
+
+/// Rebuilds a path of `depth` nodes from the bitmask of its nodes which are roots of empty
+/// subtrees, and its other nodes, see [compress_merkle_path].
+pub(crate) fn decompress_merkle_path(
+    depth: u8,
+    empty_nodes: u64,
+    siblings: Vec<Digest>,
+) -> Result<MerklePath, ConversionError> {
+    let depth = depth as usize;
+
+    // Bits beyond the path can't be interpreted
+    let valid_bits = match depth {
+        d if d >= MAX_COMPRESSED_NODES => u64::MAX,
+        d => (1 << d) - 1,
+    };
+    if empty_nodes & !valid_bits != 0 {
+        return Err(ConversionError::TooMuchData {
+            expected: depth,
+            got: (u64::BITS - empty_nodes.leading_zeros()) as usize,
+        });
+    }
+
+    let expected = depth - empty_nodes.count_ones() as usize;
+    let got = siblings.len();
+    match got.cmp(&expected) {
+        Ordering::Greater => return Err(ConversionError::TooMuchData { expected, got }),
+        Ordering::Less => return Err(ConversionError::InsufficientData { expected, got }),
+        Ordering::Equal => {},
+    }
+
+    let mut siblings = siblings.into_iter();
+    Ok((0..depth)
+        .map(|index| {
+            if index < MAX_COMPRESSED_NODES && empty_nodes & (1 << index) != 0 {
+                empty_path_node(depth, index)
+            } else {
+                siblings.next().expect("number of siblings was checked")
+            }
+        })
+        .collect())
+}
+
+impl From<&MerklePath> for generated::merkle::CompressedMerklePath {
+    fn from(value: &MerklePath) -> Self {
+        let (empty_nodes, siblings) = compress_merkle_path(value);
+
+        Self {
+            depth: value.depth().into(),
+            empty_nodes,
+            siblings: convert(siblings),
+        }
+    }
+}
+
+impl From<MerklePath> for generated::merkle::CompressedMerklePath {
+    fn from(value: MerklePath) -> Self {
+        (&value).into()
+    }
+}
+
+impl TryFrom<&generated::merkle::CompressedMerklePath> for MerklePath {
+    type Error = ConversionError;
+
+    fn try_from(value: &generated::merkle::CompressedMerklePath) -> Result<Self, Self::Error> {
+        let siblings: Vec<Digest> = try_convert(&value.siblings)?;
+
+        decompress_merkle_path(value.depth.try_into()?, value.empty_nodes, siblings)
+    }
+}
+
+impl TryFrom<generated::merkle::CompressedMerklePath> for MerklePath {
+    type Error = ConversionError;
+
+    fn try_from(value: generated::merkle::CompressedMerklePath) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
+
 // MMR DELTA
 // ================================================================================================
 
@@ -162,3 +273,116 @@ impl From<SmtProof> for generated::smt::SmtOpening {
         }
     }
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::Felt;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Builds a path of `depth` nodes, the nodes at the indices set in `empty` being roots of empty
+    /// subtrees.
+    fn path_with_empty_nodes(depth: usize, empty: &[bool], nodes: &[[u64; 4]]) -> MerklePath {
+        (0..depth)
+            .map(|index| {
+                if empty[index] {
+                    empty_path_node(depth, index)
+                } else {
+                    Digest::new(nodes[index].map(Felt::new))
+                }
+            })
+            .collect()
+    }
+
+    fn round_trip(path: &MerklePath) -> generated::merkle::CompressedMerklePath {
+        let compressed = generated::merkle::CompressedMerklePath::from(path);
+        assert_eq!(&MerklePath::try_from(&compressed).unwrap(), path);
+        compressed
+    }
+
+    #[test]
+    fn fully_empty_path_is_compressed_to_the_mask() {
+        let path = path_with_empty_nodes(20, &[true; 20], &[]);
+
+        let compressed = round_trip(&path);
+        assert_eq!(compressed.depth, 20);
+        assert_eq!(compressed.empty_nodes, (1 << 20) - 1);
+        assert!(compressed.siblings.is_empty());
+    }
+
+    #[test]
+    fn dense_path_keeps_all_nodes() {
+        let nodes: Vec<_> = (1..=20).map(|n| [n, 0, 0, 0]).collect();
+        let path = path_with_empty_nodes(20, &[false; 20], &nodes);
+
+        let compressed = round_trip(&path);
+        assert_eq!(compressed.empty_nodes, 0);
+        assert_eq!(compressed.siblings.len(), 20);
+    }
+
+    #[test]
+    fn empty_path_round_trips() {
+        let compressed = round_trip(&MerklePath::new(vec![]));
+        assert_eq!(compressed.depth, 0);
+        assert_eq!(compressed.empty_nodes, 0);
+    }
+
+    #[test]
+    fn nodes_beyond_the_mask_are_kept() {
+        let depth = MAX_COMPRESSED_NODES + 8;
+        let path = path_with_empty_nodes(depth, &vec![true; depth], &[]);
+
+        let compressed = round_trip(&path);
+        assert_eq!(compressed.empty_nodes, u64::MAX);
+        assert_eq!(compressed.siblings.len(), 8);
+    }
+
+    #[test]
+    fn malformed_paths_are_rejected() {
+        let path = path_with_empty_nodes(4, &[true, false, true, false], &[[1, 2, 3, 4]; 4]);
+        let compressed = generated::merkle::CompressedMerklePath::from(&path);
+
+        // Mask bit beyond the depth of the path
+        let mut invalid = compressed.clone();
+        invalid.empty_nodes |= 1 << 4;
+        assert!(MerklePath::try_from(&invalid).is_err());
+
+        // Missing sibling
+        let mut invalid = compressed.clone();
+        invalid.siblings.pop();
+        assert_eq!(
+            MerklePath::try_from(&invalid),
+            Err(ConversionError::InsufficientData { expected: 2, got: 1 })
+        );
+
+        // Extra sibling
+        let mut invalid = compressed;
+        invalid.siblings.push(Digest::default().into());
+        assert_eq!(
+            MerklePath::try_from(&invalid),
+            Err(ConversionError::TooMuchData { expected: 2, got: 3 })
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn compressed_paths_round_trip(
+            (depth, empty, nodes) in (0..=80usize).prop_flat_map(|depth| (
+                Just(depth),
+                prop::collection::vec(any::<bool>(), depth),
+                prop::collection::vec(any::<[u64; 4]>(), depth),
+            ))
+        ) {
+            let path = path_with_empty_nodes(depth, &empty, &nodes);
+            let compressed = round_trip(&path);
+
+            let num_empty = empty.iter().take(MAX_COMPRESSED_NODES).filter(|empty| **empty).count();
+            prop_assert_eq!(compressed.depth as usize, depth);
+            prop_assert_eq!(compressed.siblings.len(), depth - num_empty);
+        }
+    }
+}
@@ -1,10 +1,15 @@
 pub mod accounts;
+pub mod anchor;
+pub mod availability;
 pub mod blocks;
 pub mod digest;
 pub mod merkle;
+pub mod note_filters;
 pub mod notes;
 pub mod nullifiers;
+pub mod prefixes;
 pub mod transactions;
+pub mod truncation;
 
 // UTILITIES
 // ================================================================================================
@@ -0,0 +1,162 @@
+//! Bloom filters of the note tags created in a block.
+//!
+//! For every block, the store computes a Bloom filter of the tags of the notes created in it.
+//! Clients download the filters of a range of blocks, test them against the tags they track, and
+//! only request the notes of the blocks whose filter may contain one of their tags. A filter never
+//! misses a tag of its block, but can match tags which are not in the block.
+//!
+//! The store and the clients must test the tags the exact same way, so the layout below is part of
+//! the API, and every component testing a filter uses [NoteTagFilter].
+//!
+//! # Layout
+//!
+//! A filter is an array of bytes, whose bits are numbered from the least significant bit of the
+//! first byte: bit `i` is bit `i % 8` of byte `i / 8`. It has [NOTE_FILTER_BITS_PER_TAG] bits per
+//! distinct tag of the block, rounded up to whole bytes, and at least [MIN_NOTE_FILTER_LEN] bytes.
+//! The filter of a block without notes is empty and matches no tag.
+//!
+//! A tag sets the [NOTE_FILTER_NUM_HASHES] bits `(h1 + i * h2) % num_bits` for `i` in
+//! `0..NOTE_FILTER_NUM_HASHES`, with wrapping `u64` arithmetic, where `h1` is the [splitmix64]
+//! hash of the tag as a `u64`, and `h2` is the [splitmix64] hash of `h1` with its least
+//! significant bit set.
+//!
+//! [splitmix64]: https://prng.di.unimi.it/splitmix64.c
+
+use std::collections::BTreeSet;
+
+/// Number of bits of the filters per distinct tag, for a false positive rate of about 1%.
+pub const NOTE_FILTER_BITS_PER_TAG: usize = 10;
+
+/// Number of bits set by every tag.
+pub const NOTE_FILTER_NUM_HASHES: u64 = 7;
+
+/// Minimum length in bytes of the filters of the blocks with notes.
+pub const MIN_NOTE_FILTER_LEN: usize = 8;
+
+// NOTE TAG FILTER
+// ================================================================================================
+
+/// Bloom filter of the note tags created in a block, see the [module docs](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NoteTagFilter {
+    bits: Vec<u8>,
+}
+
+impl NoteTagFilter {
+    /// Returns the filter of the given tags.
+    pub fn from_tags(tags: impl IntoIterator<Item = u32>) -> Self {
+        let tags: BTreeSet<u32> = tags.into_iter().collect();
+        if tags.is_empty() {
+            return Self::default();
+        }
+
+        let len = (tags.len() * NOTE_FILTER_BITS_PER_TAG).div_ceil(8).max(MIN_NOTE_FILTER_LEN);
+        let mut filter = Self { bits: vec![0; len] };
+        for tag in tags {
+            for index in filter.bit_indices(tag) {
+                filter.bits[index / 8] |= 1 << (index % 8);
+            }
+        }
+
+        filter
+    }
+
+    /// Returns the filter of the given bytes, as returned by [NoteTagFilter::as_bytes].
+    pub fn from_bytes(bits: Vec<u8>) -> Self {
+        Self { bits }
+    }
+
+    /// Returns the bytes of the filter.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+
+    /// Returns the bytes of the filter.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bits
+    }
+
+    /// Returns `true` if the block of the filter may contain a note with the given tag, `false` if
+    /// it certainly doesn't.
+    pub fn may_contain(&self, tag: u32) -> bool {
+        !self.bits.is_empty()
+            && self
+                .bit_indices(tag)
+                .all(|index| self.bits[index / 8] & (1 << (index % 8)) != 0)
+    }
+
+    /// Returns the indices of the bits set by the tag.
+    fn bit_indices(&self, tag: u32) -> impl Iterator<Item = usize> {
+        let num_bits = self.bits.len() as u64 * 8;
+        let h1 = splitmix64(u64::from(tag));
+        let h2 = splitmix64(h1) | 1;
+
+        (0..NOTE_FILTER_NUM_HASHES)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits) as usize)
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Returns the output of the splitmix64 generator for the given state.
+fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_contain_their_tags() {
+        let tags: Vec<u32> = (0..1000).map(|i| i * 7919).collect();
+        let filter = NoteTagFilter::from_tags(tags.iter().copied());
+        assert_eq!(filter.as_bytes().len(), 1250);
+
+        assert!(tags.iter().all(|tag| filter.may_contain(*tag)));
+
+        // About 1% of the other tags match
+        let false_positives =
+            (0..10_000).map(|i| i * 7919 + 1).filter(|tag| filter.may_contain(*tag)).count();
+        assert!(false_positives < 300, "{false_positives} false positives");
+    }
+
+    #[test]
+    fn filters_are_sized_by_distinct_tags() {
+        assert_eq!(NoteTagFilter::from_tags([]), NoteTagFilter::default());
+        assert!(!NoteTagFilter::default().may_contain(0));
+
+        let filter = NoteTagFilter::from_tags([1, 1, 1, 2]);
+        assert_eq!(filter.as_bytes().len(), MIN_NOTE_FILTER_LEN);
+        assert!(filter.may_contain(1));
+        assert!(filter.may_contain(2));
+
+        let filter = NoteTagFilter::from_bytes(filter.into_bytes());
+        assert!(filter.may_contain(1));
+    }
+
+    #[test]
+    fn filter_layout_is_stable() {
+        // Computed from the layout documented in the module, changing the layout must break it
+        assert_eq!(splitmix64(0), 0xe220_a839_7b1d_cdaf);
+
+        let filter = NoteTagFilter::from_tags([0]);
+        let h1 = splitmix64(0);
+        let h2 = splitmix64(h1) | 1;
+        let expected: BTreeSet<u64> = (0..NOTE_FILTER_NUM_HASHES)
+            .map(|i| h1.wrapping_add(i.wrapping_mul(h2)) % 64)
+            .collect();
+        let set: BTreeSet<u64> = (0..64)
+            .filter(|index| filter.as_bytes()[index / 8] & (1 << (index % 8)) != 0)
+            .map(|index| index as u64)
+            .collect();
+        assert_eq!(set, expected);
+    }
+}
@@ -0,0 +1,164 @@
+//! Bit layouts of the nullifier prefixes and note tag prefixes.
+//!
+//! Clients don't reveal the exact nullifiers and note tags they are interested in, only their
+//! prefixes, which the store matches against the prefixes it computed when the block was applied.
+//! The store and the clients must therefore extract the prefixes the exact same way: a prefix
+//! computed from the wrong bits doesn't fail, it silently matches nothing. The functions of this
+//! module are the definition of these layouts, and every component computing a prefix uses them.
+//!
+//! # Nullifier prefixes
+//!
+//! A nullifier is a word of four field elements. Its prefix is the [NULLIFIER_PREFIX_LEN] most
+//! significant bits of the canonical `u64` value of its most significant element, i.e. the
+//! element at index 3:
+//!
+//! ```text
+//! element 3 (canonical u64): [ prefix: bits 63..48 | bits 47..0 ]
+//! elements 0 to 2:           not part of the prefix
+//! ```
+//!
+//! # Note tags
+//!
+//! A note tag is a `u32`. Its prefix of length `n` is its `n` most significant bits, and its most
+//! significant bit is the execution mode of the note: cleared for the notes executed by the
+//! network, set for the local notes.
+//!
+//! ```text
+//! tag: [ execution mode: bit 31 | bits 30..0 ]
+//! ```
+
+use miden_objects::notes::Nullifier;
+
+/// Number of bits of the nullifier prefixes.
+pub const NULLIFIER_PREFIX_LEN: u32 = 16;
+
+/// Number of bits of the note tags.
+pub const NOTE_TAG_LEN: u32 = u32::BITS;
+
+/// Position of the execution mode bit of the note tags.
+pub const NOTE_TAG_EXECUTION_MODE_BIT: u32 = NOTE_TAG_LEN - 1;
+
+// NULLIFIER PREFIXES
+// ================================================================================================
+
+/// Returns the prefix of the nullifier: the [NULLIFIER_PREFIX_LEN] most significant bits of its
+/// most significant element.
+pub fn nullifier_prefix(nullifier: &Nullifier) -> u32 {
+    (nullifier.most_significant_felt().as_int() >> (u64::BITS - NULLIFIER_PREFIX_LEN)) as u32
+}
+
+/// Returns `true` if `prefix` fits in [NULLIFIER_PREFIX_LEN] bits, i.e. if some nullifier can
+/// have it as prefix.
+pub fn is_nullifier_prefix(prefix: u32) -> bool {
+    prefix >> NULLIFIER_PREFIX_LEN == 0
+}
+
+// NOTE TAGS
+// ================================================================================================
+
+/// Returns the `prefix_len` most significant bits of the note tag.
+///
+/// # Panics
+/// Panics if `prefix_len` is greater than [NOTE_TAG_LEN].
+pub fn note_tag_prefix(tag: u32, prefix_len: u32) -> u32 {
+    assert!(prefix_len <= NOTE_TAG_LEN, "Note tag prefixes are at most {NOTE_TAG_LEN} bits");
+    // Shifting a `u32` by 32 bits overflows, the empty prefix is handled separately
+    tag.checked_shr(note_tag_prefix_shift(prefix_len)).unwrap_or(0)
+}
+
+/// Returns the number of bits the note tags are shifted right by to get their `prefix_len` bits
+/// prefix.
+pub fn note_tag_prefix_shift(prefix_len: u32) -> u32 {
+    NOTE_TAG_LEN - prefix_len
+}
+
+/// Returns `true` if the note tag is the one of a note executed by the network, i.e. if its
+/// execution mode bit is cleared.
+pub fn is_network_note_tag(tag: u32) -> bool {
+    tag >> NOTE_TAG_EXECUTION_MODE_BIT == 0
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{crypto::hash::rpo::RpoDigest, Felt, ZERO};
+
+    use super::*;
+
+    fn nullifier(elements: [u64; 4]) -> Nullifier {
+        RpoDigest::new(elements.map(Felt::new)).into()
+    }
+
+    // The vectors below are computed by hand from the layouts documented in the module, changing
+    // the layouts must break them.
+
+    #[test]
+    fn nullifier_prefixes_are_the_high_bits_of_the_most_significant_element() {
+        let vectors = [
+            ([0, 0, 0, 0], 0x0000),
+            ([0, 0, 0, 0x1234_5678_9abc_def0], 0x1234),
+            ([0, 0, 0, 0x0001_0000_0000_0000], 0x0001),
+            ([0, 0, 0, 0x0000_ffff_ffff_ffff], 0x0000),
+            ([0, 0, 0, 0x8000_0000_0000_0000], 0x8000),
+            // The largest canonical value of a field element
+            ([0, 0, 0, 0xffff_ffff_0000_0000], 0xffff),
+            // The other elements are not part of the prefix
+            ([0xffff_ffff_0000_0000, 0xabcd_0000_0000_0000, 0x4321_0000_0000_0000, 0], 0x0000),
+            ([0xabcd_0000_0000_0000, 0, 0, 0x5a5a_0000_0000_0001], 0x5a5a),
+        ];
+
+        for (elements, prefix) in vectors {
+            assert_eq!(nullifier_prefix(&nullifier(elements)), prefix, "{elements:x?}");
+        }
+    }
+
+    #[test]
+    fn nullifier_prefixes_fit_in_their_length() {
+        let nullifier: Nullifier = RpoDigest::new([ZERO, ZERO, ZERO, -Felt::new(1)]).into();
+        assert_eq!(nullifier_prefix(&nullifier), 0xffff);
+        assert!(is_nullifier_prefix(nullifier_prefix(&nullifier)));
+
+        assert!(is_nullifier_prefix(0));
+        assert!(is_nullifier_prefix(0xffff));
+        assert!(!is_nullifier_prefix(0x1_0000));
+        assert!(!is_nullifier_prefix(u32::MAX));
+    }
+
+    #[test]
+    fn note_tag_prefixes_are_the_high_bits_of_the_tag() {
+        let vectors = [
+            (0x1234_5678, 0, 0x0),
+            (0x1234_5678, 1, 0x0),
+            (0x1234_5678, 4, 0x1),
+            (0x1234_5678, 8, 0x12),
+            (0x1234_5678, 16, 0x1234),
+            (0x1234_5678, 32, 0x1234_5678),
+            (0x8000_0000, 1, 0x1),
+            (0xffff_ffff, 0, 0x0),
+            (0xffff_ffff, 16, 0xffff),
+            (0x0000_ffff, 16, 0x0000),
+        ];
+
+        for (tag, prefix_len, prefix) in vectors {
+            assert_eq!(note_tag_prefix(tag, prefix_len), prefix, "{tag:#x} / {prefix_len}");
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn note_tag_prefixes_are_at_most_32_bits() {
+        note_tag_prefix(0, 33);
+    }
+
+    #[test]
+    fn network_note_tags_have_the_execution_mode_bit_cleared() {
+        assert!(is_network_note_tag(0x0000_0000));
+        assert!(is_network_note_tag(0x4000_0000));
+        assert!(is_network_note_tag(0x7fff_ffff));
+        assert!(!is_network_note_tag(0x8000_0000));
+        assert!(!is_network_note_tag(0xc000_0000));
+        assert!(!is_network_note_tag(0xffff_ffff));
+    }
+}
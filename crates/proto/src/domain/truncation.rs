@@ -0,0 +1,187 @@
+//! Continuation of truncated list responses.
+//!
+//! The store truncates the lists of some responses to keep them within its response size limit,
+//! and sets their `truncated` flag. The items which were left out are fetched by repeating the
+//! request for them only:
+//!
+//! ```ignore
+//! let mut next_request = Some(request);
+//! while let Some(request) = next_request {
+//!     let response = client.get_notes_by_id(request.clone()).await?.into_inner();
+//!     next_request = request.continuation(&response);
+//!     notes.extend(response.notes);
+//! }
+//! ```
+
+use crate::generated::{
+    account::AccountId,
+    requests::{GetAccountProofsRequest, GetNotesByIdRequest},
+    responses::{GetAccountProofsResponse, GetNotesByIdResponse},
+};
+
+impl GetNotesByIdRequest {
+    /// Returns the request for the notes left out of the truncated `response` to this request, or
+    /// [None] if the response is complete.
+    pub fn continuation(&self, response: &GetNotesByIdResponse) -> Option<Self> {
+        if !response.truncated {
+            return None;
+        }
+
+        let note_ids = self
+            .note_ids
+            .iter()
+            .filter(|note_id| {
+                response.notes.iter().all(|note| note.note_id.as_ref() != Some(*note_id))
+            })
+            .copied()
+            .collect();
+
+        Some(Self { note_ids, ..*self })
+    }
+}
+
+impl GetAccountProofsRequest {
+    /// Returns the request for the proofs left out of the truncated `response` to this request,
+    /// or [None] if the response is complete.
+    ///
+    /// The proofs of a continuation can be at a later block than the ones of the previous
+    /// response. The code commitments in excess of the remaining accounts are dropped, which can
+    /// only make the store return codes already known to the caller.
+    pub fn continuation(&self, response: &GetAccountProofsResponse) -> Option<Self> {
+        if !response.truncated {
+            return None;
+        }
+
+        let is_remaining = |account_id: &AccountId| {
+            response
+                .account_proofs
+                .iter()
+                .all(|proof| proof.account_id.as_ref() != Some(account_id))
+        };
+
+        let account_ids: Vec<_> = self.account_ids.iter().copied().filter(is_remaining).collect();
+        let known_blocks = self
+            .known_blocks
+            .iter()
+            .filter(|known_block| known_block.account_id.as_ref().is_some_and(is_remaining))
+            .copied()
+            .collect();
+        let code_commitments =
+            self.code_commitments.iter().take(account_ids.len()).copied().collect();
+
+        Some(Self {
+            account_ids,
+            include_headers: self.include_headers,
+            code_commitments,
+            known_blocks,
+            bundle_proofs: self.bundle_proofs,
+        })
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::{
+        digest::Digest, note::Note, requests::KnownAccountBlock, responses::AccountProofsResponse,
+    };
+
+    fn digest(n: u64) -> Digest {
+        Digest { d0: n, d1: 0, d2: 0, d3: 0 }
+    }
+
+    /// Answers with at most two notes per response, and nothing for unknown notes.
+    fn get_notes_by_id(request: &GetNotesByIdRequest) -> GetNotesByIdResponse {
+        let mut notes: Vec<_> = request
+            .note_ids
+            .iter()
+            .filter(|note_id| note_id.d0 < 100)
+            .map(|note_id| Note {
+                note_id: Some(*note_id),
+                ..Default::default()
+            })
+            .collect();
+        let truncated = notes.len() > 2;
+        notes.truncate(2);
+
+        GetNotesByIdResponse { notes, truncated, anchor: None }
+    }
+
+    #[test]
+    fn continuations_fetch_every_note() {
+        let request = GetNotesByIdRequest {
+            note_ids: [1, 2, 3, 500, 4, 5].map(digest).to_vec(),
+            compress_merkle_paths: true,
+        };
+
+        let mut notes = Vec::new();
+        let mut requests = 0;
+        let mut next_request = Some(request);
+        while let Some(request) = next_request {
+            let response = get_notes_by_id(&request);
+            next_request = request.continuation(&response);
+            if let Some(next_request) = &next_request {
+                assert!(next_request.compress_merkle_paths);
+            }
+            notes.extend(response.notes);
+            requests += 1;
+        }
+
+        assert_eq!(requests, 3);
+        assert_eq!(
+            notes.iter().map(|note| note.note_id.unwrap().d0).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn account_proofs_continuation_keeps_remaining_accounts() {
+        let request = GetAccountProofsRequest {
+            account_ids: [1, 2, 3].map(|id| AccountId { id }).to_vec(),
+            include_headers: Some(true),
+            code_commitments: [7, 8, 9].map(digest).to_vec(),
+            known_blocks: vec![
+                KnownAccountBlock {
+                    account_id: Some(AccountId { id: 1 }),
+                    block_num: 5,
+                },
+                KnownAccountBlock {
+                    account_id: Some(AccountId { id: 3 }),
+                    block_num: 6,
+                },
+            ],
+            bundle_proofs: Some(true),
+        };
+
+        let response = GetAccountProofsResponse {
+            block_num: 10,
+            account_proofs: vec![AccountProofsResponse {
+                account_id: Some(AccountId { id: 1 }),
+                ..Default::default()
+            }],
+            truncated: true,
+            anchor: None,
+        };
+
+        let continuation = request.continuation(&response).unwrap();
+        assert_eq!(
+            continuation,
+            GetAccountProofsRequest {
+                account_ids: [2, 3].map(|id| AccountId { id }).to_vec(),
+                include_headers: Some(true),
+                code_commitments: [7, 8].map(digest).to_vec(),
+                known_blocks: vec![KnownAccountBlock {
+                    account_id: Some(AccountId { id: 3 }),
+                    block_num: 6
+                }],
+                bundle_proofs: Some(true),
+            }
+        );
+
+        let response = GetAccountProofsResponse { truncated: false, ..response };
+        assert_eq!(request.continuation(&response), None);
+    }
+}
@@ -1,7 +1,16 @@
-use std::{any::type_name, num::TryFromIntError};
+use std::{any::type_name, error::Error, num::TryFromIntError};
 
-use miden_objects::crypto::merkle::{SmtLeafError, SmtProofError};
+use miden_objects::{
+    accounts::AccountId,
+    crypto::merkle::{SmtLeafError, SmtProofError},
+    utils::DeserializationError,
+    Digest, ACCOUNT_TREE_DEPTH,
+};
+use prost::Message;
 use thiserror::Error;
+use tonic::{codegen::Bytes, Status};
+
+use crate::generated::error::{ErrorDetails, ErrorLayer};
 
 #[derive(Debug, Clone, PartialEq, Error)]
 pub enum ConversionError {
@@ -15,6 +24,8 @@ pub enum ConversionError {
     SmtProofError(#[from] SmtProofError),
     #[error("Integer conversion error: {0}")]
     TryFromIntError(#[from] TryFromIntError),
+    #[error("Deserialization error: {0}")]
+    DeserializationError(#[from] DeserializationError),
     #[error("Too much data, expected {expected}, got {got}")]
     TooMuchData { expected: usize, got: usize },
     #[error("Not enough data, expected {expected}, got {got}")]
@@ -42,3 +53,175 @@ impl<T: prost::Message> MissingFieldHelper for T {
         }
     }
 }
+
+// ACCOUNT PROOF BUNDLE ERROR
+// ================================================================================================
+
+/// Inconsistency of an [AccountProofBundle](crate::domain::accounts::AccountProofBundle) with the
+/// header of its block.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AccountProofBundleError {
+    #[error(
+        "Bundle refers to block {bundle_block_num} ({bundle_commitment}), but the header is of \
+         block {block_num} ({commitment})"
+    )]
+    BlockMismatch {
+        bundle_block_num: u32,
+        bundle_commitment: Digest,
+        block_num: u32,
+        commitment: Digest,
+    },
+    #[error("Account proof has {0} nodes, expected {ACCOUNT_TREE_DEPTH}")]
+    InvalidProofDepth(u8),
+    #[error("Proof of account {0} doesn't open the account root of its block")]
+    InvalidAccountProof(AccountId),
+}
+
+// ERROR CHAINS
+// ================================================================================================
+
+impl ErrorLayer {
+    /// Returns the layer reporting `err` in `component`, along with the messages of its sources.
+    pub fn from_error<E: Error + 'static>(component: &str, err: &E) -> Self {
+        let mut sources = Vec::new();
+        let mut source = err.source();
+        while let Some(err) = source {
+            sources.push(err.to_string());
+            source = err.source();
+        }
+
+        Self {
+            component: component.to_string(),
+            kind: error_kind(err),
+            message: err.to_string(),
+            sources,
+        }
+    }
+}
+
+/// Helpers to build and inspect the error chain recorded in the details of a [Status].
+pub trait ErrorChainHelper: Sized {
+    /// Records `err` as the origin of the error chain, replacing the details of the status.
+    fn with_error_source<E: Error + 'static>(self, component: &str, err: &E) -> Status;
+
+    /// Appends a layer to the error chain, keeping the layers recorded by upstream components.
+    fn with_error_layer(self, layer: ErrorLayer) -> Status;
+
+    /// Returns the layers of the error chain, from the origin of the error to the component which
+    /// returned the status.
+    ///
+    /// Statuses without valid error details have an empty chain.
+    fn error_chain(&self) -> Vec<ErrorLayer>;
+}
+
+impl ErrorChainHelper for Status {
+    fn with_error_source<E: Error + 'static>(self, component: &str, err: &E) -> Status {
+        with_error_chain(self, vec![ErrorLayer::from_error(component, err)])
+    }
+
+    fn with_error_layer(self, layer: ErrorLayer) -> Status {
+        let mut layers = self.error_chain();
+        layers.push(layer);
+        with_error_chain(self, layers)
+    }
+
+    fn error_chain(&self) -> Vec<ErrorLayer> {
+        ErrorDetails::decode(self.details())
+            .map(|details| details.layers)
+            .unwrap_or_default()
+    }
+}
+
+fn with_error_chain(status: Status, layers: Vec<ErrorLayer>) -> Status {
+    let details = ErrorDetails { layers }.encode_to_vec();
+    Status::with_details_and_metadata(
+        status.code(),
+        status.message(),
+        Bytes::from(details),
+        status.metadata().clone(),
+    )
+}
+
+/// Returns the kind of the error as `<type>::<variant>`, or just `<type>` for errors which are not
+/// enums.
+fn error_kind<E: Error>(err: &E) -> String {
+    let type_name = type_name::<E>();
+    let type_name = type_name.split('<').next().unwrap_or(type_name);
+    let type_name = type_name.rsplit("::").next().unwrap_or(type_name);
+
+    // The `Debug` representation of an enum starts with the name of the variant
+    let debug = format!("{err:?}");
+    let variant: String = debug.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+
+    if variant.is_empty() || variant == type_name {
+        type_name.to_string()
+    } else {
+        format!("{type_name}::{variant}")
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use tonic::Code;
+
+    use super::*;
+
+    #[derive(Debug, Error)]
+    enum StorageError {
+        #[error("I/O error: {0}")]
+        IoError(#[from] io::Error),
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Failed to load block {0}")]
+    struct LoadBlockError(u32, #[source] StorageError);
+
+    #[test]
+    fn error_chain_is_built_from_error_sources() {
+        let err = StorageError::IoError(io::Error::other("disk on fire"));
+        let status = Status::internal(err.to_string()).with_error_source("miden-store", &err);
+
+        assert_eq!(status.code(), Code::Internal);
+        assert_eq!(status.message(), "I/O error: disk on fire");
+        assert_eq!(
+            status.error_chain(),
+            [ErrorLayer {
+                component: "miden-store".to_string(),
+                kind: "StorageError::IoError".to_string(),
+                message: "I/O error: disk on fire".to_string(),
+                sources: vec!["disk on fire".to_string()],
+            }]
+        );
+
+        let err = LoadBlockError(7, err);
+        let layer = ErrorLayer::from_error("miden-store", &err);
+        assert_eq!(layer.kind, "LoadBlockError");
+        assert_eq!(layer.sources, ["I/O error: disk on fire", "disk on fire"]);
+    }
+
+    #[test]
+    fn proxies_append_their_layer() {
+        let err = StorageError::IoError(io::Error::other("disk on fire"));
+        let status = Status::internal(err.to_string())
+            .with_error_source("miden-store", &err)
+            .with_error_layer(ErrorLayer {
+                component: "miden-rpc".to_string(),
+                kind: "UpstreamError".to_string(),
+                ..Default::default()
+            });
+
+        assert_eq!(status.message(), "I/O error: disk on fire");
+        let components: Vec<_> =
+            status.error_chain().into_iter().map(|layer| layer.component).collect();
+        assert_eq!(components, ["miden-store", "miden-rpc"]);
+
+        // Statuses without details start a new chain
+        let status = Status::not_found("Missing").with_error_layer(ErrorLayer::default());
+        assert_eq!(status.error_chain().len(), 1);
+    }
+}
@@ -16,6 +16,13 @@ pub struct AccountSummary {
     pub account_hash: ::core::option::Option<super::digest::Digest>,
     #[prost(uint32, tag = "3")]
     pub block_num: u32,
+    /// Storage mode of the account, as encoded in its ID.
+    #[prost(enumeration = "AccountStorageMode", tag = "4")]
+    pub storage_mode: i32,
+    /// Whether the full details of the account are stored by the node, and can be fetched with
+    /// `GetAccountDetails`. Only public accounts have their details stored.
+    #[prost(bool, tag = "5")]
+    pub details_available: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AccountInfo {
@@ -39,3 +46,102 @@ pub struct AccountHeader {
     #[prost(uint64, tag = "4")]
     pub nonce: u64,
 }
+/// Layout metadata of a slot of an account's storage, without its value.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct AccountStorageSlotHeader {
+    /// Index of the slot in the account's storage.
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    /// Whether the slot holds a storage map rather than a single value.
+    #[prost(bool, tag = "2")]
+    pub is_map: bool,
+    /// Hash of the value of a value slot, or root of the storage map of a map slot.
+    #[prost(message, optional, tag = "3")]
+    pub commitment: ::core::option::Option<super::digest::Digest>,
+    /// Number of entries of the storage map, for map slots only.
+    #[prost(uint64, optional, tag = "4")]
+    pub map_entry_count: ::core::option::Option<u64>,
+}
+/// An asset of an account's vault.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccountVaultAsset {
+    /// Key of the asset in the vault, by which the assets are ordered.
+    #[prost(message, optional, tag = "1")]
+    pub vault_key: ::core::option::Option<super::digest::Digest>,
+    /// ID of the faucet which issued the asset.
+    #[prost(message, optional, tag = "2")]
+    pub faucet_id: ::core::option::Option<AccountId>,
+    /// Amount of a fungible asset, unset for non-fungible assets.
+    #[prost(uint64, optional, tag = "3")]
+    pub amount: ::core::option::Option<u64>,
+    /// The asset encoded using miden native format.
+    #[prost(bytes = "vec", tag = "4")]
+    pub asset: ::prost::alloc::vec::Vec<u8>,
+}
+/// Position of an account delta in an export, deltas are exported in the order of their block and
+/// then of their account ID.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct AccountDeltaCursor {
+    /// Number of the block of the delta.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// ID of the account of the delta.
+    #[prost(message, optional, tag = "2")]
+    pub account_id: ::core::option::Option<AccountId>,
+}
+/// Proof of the state of an account at a block, bundled for clients embedding it in their proofs.
+/// `GetAccountProofs` returns it encoded using miden native format, see `AccountProofBundle` in
+/// `miden-node-proto`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccountProofBundle {
+    /// Number of the block whose account root the proof opens.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Commitment to the block.
+    #[prost(message, optional, tag = "2")]
+    pub block_commitment: ::core::option::Option<super::digest::Digest>,
+    /// Account ID.
+    #[prost(message, optional, tag = "3")]
+    pub account_id: ::core::option::Option<AccountId>,
+    /// Account hash.
+    #[prost(message, optional, tag = "4")]
+    pub account_hash: ::core::option::Option<super::digest::Digest>,
+    /// Authentication path from the `account_root` of the block header to the account.
+    #[prost(message, optional, tag = "5")]
+    pub account_proof: ::core::option::Option<super::merkle::CompressedMerklePath>,
+    /// Storage header of public accounts, encoded using miden native format.
+    #[prost(bytes = "vec", optional, tag = "6")]
+    pub storage_header: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Code commitment of public accounts.
+    #[prost(message, optional, tag = "7")]
+    pub code_commitment: ::core::option::Option<super::digest::Digest>,
+}
+/// Where the state of an account is stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AccountStorageMode {
+    /// Only the commitment to the account's state is stored on-chain.
+    Private = 0,
+    /// The full state of the account is stored on-chain.
+    Public = 1,
+}
+impl AccountStorageMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Private => "ACCOUNT_STORAGE_MODE_PRIVATE",
+            Self::Public => "ACCOUNT_STORAGE_MODE_PUBLIC",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ACCOUNT_STORAGE_MODE_PRIVATE" => Some(Self::Private),
+            "ACCOUNT_STORAGE_MODE_PUBLIC" => Some(Self::Public),
+            _ => None,
+        }
+    }
+}
@@ -35,6 +35,19 @@ pub struct BlockHeader {
     #[prost(fixed32, tag = "11")]
     pub timestamp: u32,
 }
+/// The chain tip a response was computed against.
+///
+/// All the data of a response reflects the chain state as of this block. Responses with the same
+/// anchor are mutually consistent.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ChainAnchor {
+    /// Number of the latest block at the time of the read.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Commitment to the latest block at the time of the read.
+    #[prost(message, optional, tag = "2")]
+    pub block_commitment: ::core::option::Option<super::digest::Digest>,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct BlockInclusionProof {
     #[prost(message, optional, tag = "1")]
@@ -45,3 +58,110 @@ pub struct BlockInclusionProof {
     #[prost(fixed32, tag = "3")]
     pub chain_length: u32,
 }
+/// Durations of the stages of building a block in the block producer, in microseconds.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BlockStageDurations {
+    /// Fetching the block inputs from the store.
+    #[prost(uint64, tag = "1")]
+    pub inputs_fetch_us: u64,
+    /// Computing the block witness and assembling the block.
+    #[prost(uint64, tag = "2")]
+    pub propose_us: u64,
+    /// Proving the block, including any simulated proving time.
+    #[prost(uint64, tag = "3")]
+    pub prove_us: u64,
+    /// Time from the start of the block building until the block was sent to the store.
+    #[prost(uint64, tag = "4")]
+    pub build_us: u64,
+}
+/// Durations of the production stages of a committed block, in microseconds.
+///
+/// The stages are only known for the blocks built by a block producer which reported them.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BlockProductionStats {
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    #[prost(uint64, optional, tag = "2")]
+    pub inputs_fetch_us: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "3")]
+    pub propose_us: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "4")]
+    pub prove_us: ::core::option::Option<u64>,
+    /// Applying the block to the store.
+    #[prost(uint64, optional, tag = "5")]
+    pub commit_us: ::core::option::Option<u64>,
+    /// Time from the start of the block building until the block was committed.
+    #[prost(uint64, optional, tag = "6")]
+    pub total_us: ::core::option::Option<u64>,
+}
+/// Metadata of the submission of a block by a block producer.
+///
+/// Purely informational, the store applies blocks the same way whether they carry it or not.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockProducerMetadata {
+    /// Identifier of the block producer instance which built the block.
+    #[prost(string, tag = "1")]
+    pub producer_id: ::prost::alloc::string::String,
+    /// Submission attempt of the block, starting at 1.
+    #[prost(uint32, tag = "2")]
+    pub attempt: u32,
+    /// Time the block producer first submitted the block, in milliseconds since the UNIX epoch.
+    #[prost(fixed64, tag = "3")]
+    pub produced_at: u64,
+}
+/// Latest submission of a committed block, as reported by its block producer.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockProvenance {
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    #[prost(message, optional, tag = "2")]
+    pub producer: ::core::option::Option<BlockProducerMetadata>,
+    /// Time the store received the submission, in milliseconds since the UNIX epoch.
+    #[prost(fixed64, tag = "3")]
+    pub received_at: u64,
+}
+/// Percentiles of the duration of a block production stage over a range of blocks, in microseconds.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StageDurationPercentiles {
+    /// Name of the stage, one of `inputs_fetch`, `propose`, `prove`, `commit` and `total`.
+    #[prost(string, tag = "1")]
+    pub stage: ::prost::alloc::string::String,
+    /// Number of blocks for which the duration of the stage is known.
+    #[prost(uint32, tag = "2")]
+    pub count: u32,
+    #[prost(uint64, tag = "3")]
+    pub p50_us: u64,
+    #[prost(uint64, tag = "4")]
+    pub p95_us: u64,
+}
+/// Position in a paginated `GetBlockInputs` response.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BlockInputsCursor {
+    /// Opaque token of the snapshot of the block inputs held by the store.
+    #[prost(fixed64, tag = "1")]
+    pub snapshot: u64,
+    /// Number of account states returned by the previous pages.
+    #[prost(uint32, tag = "2")]
+    pub account_offset: u32,
+    /// Number of nullifiers returned by the previous pages.
+    #[prost(uint32, tag = "3")]
+    pub nullifier_offset: u32,
+}
+/// Inclusive range of block versions.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BlockVersionRange {
+    #[prost(fixed32, tag = "1")]
+    pub min: u32,
+    #[prost(fixed32, tag = "2")]
+    pub max: u32,
+}
+/// Upgrade of the block versions supported by a node, scheduled at a given block.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BlockVersionUpgrade {
+    /// Number of the first block supporting the upgraded versions.
+    #[prost(fixed32, tag = "1")]
+    pub activation_block: u32,
+    /// Versions supported from the activation block onwards.
+    #[prost(message, optional, tag = "2")]
+    pub supported: ::core::option::Option<BlockVersionRange>,
+}
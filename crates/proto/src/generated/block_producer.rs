@@ -90,6 +90,149 @@ pub mod api_client {
             self.inner = self.inner.max_encoding_message_size(limit);
             self
         }
+        pub async fn dump_mempool_graph(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::DumpMempoolGraphRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::DumpMempoolGraphResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/block_producer.Api/DumpMempoolGraph",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("block_producer.Api", "DumpMempoolGraph"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn evict_transaction(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::EvictTransactionRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::EvictTransactionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/block_producer.Api/EvictTransaction",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("block_producer.Api", "EvictTransaction"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_account_expected_state(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetAccountExpectedStateRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountExpectedStateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/block_producer.Api/GetAccountExpectedState",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("block_producer.Api", "GetAccountExpectedState"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_supported_block_versions(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetSupportedBlockVersionsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetSupportedBlockVersionsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/block_producer.Api/GetSupportedBlockVersions",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("block_producer.Api", "GetSupportedBlockVersions"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn submit_and_watch_proven_transaction(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::SubmitProvenTransactionRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<
+                tonic::codec::Streaming<
+                    super::super::responses::SubmitAndWatchProvenTransactionResponse,
+                >,
+            >,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/block_producer.Api/SubmitAndWatchProvenTransaction",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "block_producer.Api",
+                        "SubmitAndWatchProvenTransaction",
+                    ),
+                );
+            self.inner.server_streaming(req, path, codec).await
+        }
         pub async fn submit_proven_transaction(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -133,6 +276,56 @@ pub mod api_server {
     /// Generated trait containing gRPC methods that should be implemented for use with ApiServer.
     #[async_trait]
     pub trait Api: std::marker::Send + std::marker::Sync + 'static {
+        async fn dump_mempool_graph(
+            &self,
+            request: tonic::Request<super::super::requests::DumpMempoolGraphRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::DumpMempoolGraphResponse>,
+            tonic::Status,
+        >;
+        async fn evict_transaction(
+            &self,
+            request: tonic::Request<super::super::requests::EvictTransactionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::EvictTransactionResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_expected_state(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetAccountExpectedStateRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountExpectedStateResponse>,
+            tonic::Status,
+        >;
+        async fn get_supported_block_versions(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetSupportedBlockVersionsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetSupportedBlockVersionsResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the SubmitAndWatchProvenTransaction method.
+        type SubmitAndWatchProvenTransactionStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<
+                    super::super::responses::SubmitAndWatchProvenTransactionResponse,
+                    tonic::Status,
+                >,
+            >
+            + std::marker::Send
+            + 'static;
+        async fn submit_and_watch_proven_transaction(
+            &self,
+            request: tonic::Request<
+                super::super::requests::SubmitProvenTransactionRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<Self::SubmitAndWatchProvenTransactionStream>,
+            tonic::Status,
+        >;
         async fn submit_proven_transaction(
             &self,
             request: tonic::Request<
@@ -219,6 +412,253 @@ pub mod api_server {
         }
         fn call(&mut self, req: http::Request<B>) -> Self::Future {
             match req.uri().path() {
+                "/block_producer.Api/DumpMempoolGraph" => {
+                    #[allow(non_camel_case_types)]
+                    struct DumpMempoolGraphSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::DumpMempoolGraphRequest,
+                    > for DumpMempoolGraphSvc<T> {
+                        type Response = super::super::responses::DumpMempoolGraphResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::DumpMempoolGraphRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::dump_mempool_graph(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DumpMempoolGraphSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/block_producer.Api/EvictTransaction" => {
+                    #[allow(non_camel_case_types)]
+                    struct EvictTransactionSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::EvictTransactionRequest,
+                    > for EvictTransactionSvc<T> {
+                        type Response = super::super::responses::EvictTransactionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::EvictTransactionRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::evict_transaction(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = EvictTransactionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/block_producer.Api/GetAccountExpectedState" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountExpectedStateSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountExpectedStateRequest,
+                    > for GetAccountExpectedStateSvc<T> {
+                        type Response = super::super::responses::GetAccountExpectedStateResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountExpectedStateRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_expected_state(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountExpectedStateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/block_producer.Api/GetSupportedBlockVersions" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSupportedBlockVersionsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetSupportedBlockVersionsRequest,
+                    > for GetSupportedBlockVersionsSvc<T> {
+                        type Response = super::super::responses::GetSupportedBlockVersionsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetSupportedBlockVersionsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_supported_block_versions(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetSupportedBlockVersionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/block_producer.Api/SubmitAndWatchProvenTransaction" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubmitAndWatchProvenTransactionSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::ServerStreamingService<
+                        super::super::requests::SubmitProvenTransactionRequest,
+                    > for SubmitAndWatchProvenTransactionSvc<T> {
+                        type Response = super::super::responses::SubmitAndWatchProvenTransactionResponse;
+                        type ResponseStream = T::SubmitAndWatchProvenTransactionStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::SubmitProvenTransactionRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::submit_and_watch_proven_transaction(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SubmitAndWatchProvenTransactionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/block_producer.Api/SubmitProvenTransaction" => {
                     #[allow(non_camel_case_types)]
                     struct SubmitProvenTransactionSvc<T: Api>(pub Arc<T>);
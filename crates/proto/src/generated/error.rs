@@ -0,0 +1,25 @@
+// This file is @generated by prost-build.
+/// Details attached to the gRPC statuses returned by the node components.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ErrorDetails {
+    /// Layers of the error chain, from the component where the error originated to the component
+    /// which returned the status. Components proxying an upstream error append their own layer.
+    #[prost(message, repeated, tag = "1")]
+    pub layers: ::prost::alloc::vec::Vec<ErrorLayer>,
+}
+/// The error reported by a single component.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ErrorLayer {
+    /// Name of the component, e.g. `miden-store`.
+    #[prost(string, tag = "1")]
+    pub component: ::prost::alloc::string::String,
+    /// Kind of the error, e.g. `DatabaseError::SqliteError`.
+    #[prost(string, tag = "2")]
+    pub kind: ::prost::alloc::string::String,
+    /// Error message.
+    #[prost(string, tag = "3")]
+    pub message: ::prost::alloc::string::String,
+    /// Messages of the errors which caused this error, from the outermost to the innermost.
+    #[prost(string, repeated, tag = "4")]
+    pub sources: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
@@ -4,3 +4,17 @@ pub struct MerklePath {
     #[prost(message, repeated, tag = "1")]
     pub siblings: ::prost::alloc::vec::Vec<super::digest::Digest>,
 }
+/// A Merkle path in which the roots of empty subtrees are omitted.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CompressedMerklePath {
+    /// Number of nodes in the decompressed path.
+    #[prost(uint32, tag = "1")]
+    pub depth: u32,
+    /// Bitmask of the nodes which are roots of empty subtrees, the least significant bit
+    /// corresponding to the first node of the path, i.e. the sibling of the leaf.
+    #[prost(fixed64, tag = "2")]
+    pub empty_nodes: u64,
+    /// Nodes of the path which are not roots of empty subtrees, ordered from the leaf to the root.
+    #[prost(message, repeated, tag = "3")]
+    pub siblings: ::prost::alloc::vec::Vec<super::digest::Digest>,
+}
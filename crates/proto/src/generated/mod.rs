@@ -4,6 +4,7 @@ pub mod account;
 pub mod block;
 pub mod block_producer;
 pub mod digest;
+pub mod error;
 pub mod merkle;
 pub mod mmr;
 pub mod note;
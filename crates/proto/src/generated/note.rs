@@ -28,6 +28,58 @@ pub struct Note {
     /// details contain the `Note` in a serialized format.
     #[prost(bytes = "vec", optional, tag = "6")]
     pub details: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Set instead of `merkle_path` if the request asked for compressed Merkle paths.
+    #[prost(message, optional, tag = "7")]
+    pub compressed_merkle_path: ::core::option::Option<
+        super::merkle::CompressedMerklePath,
+    >,
+    /// Set if the note is public but its details are not retained by this node, as configured by
+    /// its data availability policy.
+    #[prost(bool, tag = "8")]
+    pub details_not_retained: bool,
+    /// The transaction which created the note. Unknown if its sender updated its account with
+    /// several transactions in the block, or for older notes whose block data was pruned.
+    #[prost(message, optional, tag = "9")]
+    pub created_by_tx: ::core::option::Option<super::transaction::TransactionId>,
+}
+/// Position of a note in the chain.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct NotePosition {
+    /// Number of the block containing the note.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Index of the note in the block, as in `Note.note_index`.
+    #[prost(uint32, tag = "2")]
+    pub note_index: u32,
+}
+/// Position of a nullifier in the results of `CheckNullifiersByPrefix`, which are ordered by block
+/// number and then by nullifier.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct NullifierPosition {
+    /// Number of the block consuming the nullifier.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// The nullifier.
+    #[prost(message, optional, tag = "2")]
+    pub nullifier: ::core::option::Option<super::digest::Digest>,
+}
+/// Consumption status of a note.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct NoteConsumption {
+    /// ID of the note.
+    #[prost(message, optional, tag = "1")]
+    pub note_id: ::core::option::Option<super::digest::Digest>,
+    /// Number of the block which created the note.
+    #[prost(fixed32, tag = "2")]
+    pub block_num: u32,
+    /// Nullifier of the note. Unset for private notes, whose nullifier and consumption are unknown
+    /// to the node.
+    #[prost(message, optional, tag = "3")]
+    pub nullifier: ::core::option::Option<super::digest::Digest>,
+    /// Number of the block which consumed the note. Unset if the note is private, or wasn't
+    /// consumed as of the anchored block.
+    #[prost(fixed32, optional, tag = "4")]
+    pub consumed_block_num: ::core::option::Option<u32>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NoteInclusionInBlockProof {
@@ -50,6 +102,53 @@ pub struct NoteSyncRecord {
     pub metadata: ::core::option::Option<NoteMetadata>,
     #[prost(message, optional, tag = "4")]
     pub merkle_path: ::core::option::Option<super::merkle::MerklePath>,
+    /// Set instead of `merkle_path` if the request asked for compressed Merkle paths.
+    #[prost(message, optional, tag = "5")]
+    pub compressed_merkle_path: ::core::option::Option<
+        super::merkle::CompressedMerklePath,
+    >,
+}
+/// Inclusive range of note tags.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct NoteTagRange {
+    #[prost(fixed32, tag = "1")]
+    pub from: u32,
+    #[prost(fixed32, tag = "2")]
+    pub to: u32,
+}
+/// Details of public notes stored by a node. The policy only applies to the notes of the blocks
+/// applied while it was set, the notes of older blocks can be stored under another policy.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NoteDetailsAvailability {
+    #[prost(enumeration = "NoteDetailsPolicy", tag = "1")]
+    pub policy: i32,
+    /// Roots of the note scripts whose notes are stored, with the `SELECTIVE` policy.
+    #[prost(message, repeated, tag = "2")]
+    pub script_roots: ::prost::alloc::vec::Vec<super::digest::Digest>,
+    /// Ranges of the tags of the notes which are stored, with the `SELECTIVE` policy.
+    #[prost(message, repeated, tag = "3")]
+    pub tags: ::prost::alloc::vec::Vec<NoteTagRange>,
+}
+/// Number of notes whose tags share a prefix.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct NoteTagBucket {
+    /// Most significant bits shared by the tags of the bucket's notes.
+    #[prost(fixed32, tag = "1")]
+    pub prefix: u32,
+    /// Number of notes in the bucket.
+    #[prost(uint32, tag = "2")]
+    pub num_notes: u32,
+}
+/// Bloom filter of the tags of the notes created in a block.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockNoteFilter {
+    /// Number of the block.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Bits of the filter, empty for blocks without notes. The layout of the filter and the hashing
+    /// of the tags are described in `miden_node_proto::domain::note_filters`.
+    #[prost(bytes = "vec", tag = "2")]
+    pub filter: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NoteAuthenticationInfo {
@@ -60,3 +159,36 @@ pub struct NoteAuthenticationInfo {
     #[prost(message, repeated, tag = "2")]
     pub block_proofs: ::prost::alloc::vec::Vec<super::block::BlockInclusionProof>,
 }
+/// Which details of public notes are stored by a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NoteDetailsPolicy {
+    /// The details of all public notes are stored.
+    Full = 0,
+    /// The details of public notes are never stored.
+    MetadataOnly = 1,
+    /// The details of public notes are only stored for selected note scripts and tags.
+    Selective = 2,
+}
+impl NoteDetailsPolicy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Full => "NOTE_DETAILS_POLICY_FULL",
+            Self::MetadataOnly => "NOTE_DETAILS_POLICY_METADATA_ONLY",
+            Self::Selective => "NOTE_DETAILS_POLICY_SELECTIVE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NOTE_DETAILS_POLICY_FULL" => Some(Self::Full),
+            "NOTE_DETAILS_POLICY_METADATA_ONLY" => Some(Self::MetadataOnly),
+            "NOTE_DETAILS_POLICY_SELECTIVE" => Some(Self::Selective),
+            _ => None,
+        }
+    }
+}
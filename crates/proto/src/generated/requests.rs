@@ -3,6 +3,19 @@
 pub struct ApplyBlockRequest {
     #[prost(bytes = "vec", tag = "1")]
     pub block: ::prost::alloc::vec::Vec<u8>,
+    /// Durations of the stages of building the block, if measured by the block producer.
+    #[prost(message, optional, tag = "2")]
+    pub stage_durations: ::core::option::Option<super::block::BlockStageDurations>,
+    /// Identity of the block producer and submission attempt, if reported by the block producer.
+    #[prost(message, optional, tag = "3")]
+    pub producer: ::core::option::Option<super::block::BlockProducerMetadata>,
+}
+/// Returns whether the specified notes were consumed, and by which block.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckNotesConsumedRequest {
+    /// IDs of the notes to check.
+    #[prost(message, repeated, tag = "1")]
+    pub note_ids: ::prost::alloc::vec::Vec<super::digest::Digest>,
 }
 /// Returns a list of nullifiers that match the specified prefixes and are recorded in the node.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -11,9 +24,13 @@ pub struct CheckNullifiersByPrefixRequest {
     #[prost(uint32, tag = "1")]
     pub prefix_len: u32,
     /// List of nullifiers to check. Each nullifier is specified by its prefix with length equal
-    /// to prefix_len
+    /// to prefix_len: the prefix_len high bits of the canonical value of its most significant
+    /// element, i.e. the element at index 3.
     #[prost(uint32, repeated, tag = "2")]
     pub nullifiers: ::prost::alloc::vec::Vec<u32>,
+    /// Position of the last nullifier of the previous page, unset for the first page.
+    #[prost(message, optional, tag = "3")]
+    pub page: ::core::option::Option<super::note::NullifierPosition>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CheckNullifiersRequest {
@@ -35,6 +52,59 @@ pub struct GetBlockHeaderByNumberRequest {
     #[prost(bool, optional, tag = "2")]
     pub include_mmr_proof: ::core::option::Option<bool>,
 }
+/// Returns the block header with the given commitment.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetBlockHeaderByCommitmentRequest {
+    /// The commitment (hash) of the target block.
+    #[prost(message, optional, tag = "1")]
+    pub block_commitment: ::core::option::Option<super::digest::Digest>,
+    /// Whether or not to return authentication data for the block header.
+    #[prost(bool, optional, tag = "2")]
+    pub include_mmr_proof: ::core::option::Option<bool>,
+}
+/// Returns the consecutive block headers of a range, with the MMR delta to catch up with them.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetBlockHeadersRangeRequest {
+    /// First block of the range.
+    #[prost(fixed32, tag = "1")]
+    pub from_block: u32,
+    /// Last block of the range, included.
+    #[prost(fixed32, tag = "2")]
+    pub to_block: u32,
+    /// Whether or not to return the MMR delta extending a partial MMR of the blocks before
+    /// `from_block` to the blocks before the last returned header.
+    #[prost(bool, tag = "3")]
+    pub include_mmr_delta: bool,
+}
+/// Returns the durations of the production stages of a range of blocks.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetBlockProductionStatsRequest {
+    /// First block of the range.
+    #[prost(fixed32, tag = "1")]
+    pub block_from: u32,
+    /// Last block of the range, included.
+    #[prost(fixed32, tag = "2")]
+    pub block_to: u32,
+}
+/// Returns aggregate statistics of a range of blocks.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetChainStatsRequest {
+    /// First block of the range.
+    #[prost(fixed32, tag = "1")]
+    pub from_block: u32,
+    /// Last block of the range, included.
+    #[prost(fixed32, tag = "2")]
+    pub to_block: u32,
+}
+/// Returns the state of the database connection pool of the store.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetDatabasePoolStatsRequest {}
+/// Returns the historical data the node can serve.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetDataAvailabilityRequest {}
+/// Returns the versions of the blocks supported by the component.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetSupportedBlockVersionsRequest {}
 /// State synchronization request.
 ///
 /// Specifies state updates the client is interested in. The server will return the first block which
@@ -57,10 +127,14 @@ pub struct SyncStateRequest {
     /// Specifies the tags which the client is interested in.
     #[prost(fixed32, repeated, tag = "3")]
     pub note_tags: ::prost::alloc::vec::Vec<u32>,
-    /// Determines the nullifiers the client is interested in by specifying the 16high bits of the
-    /// target nullifier.
+    /// Determines the nullifiers the client is interested in by specifying the 16 high bits of the
+    /// target nullifier: bits 63..48 of the canonical value of its most significant element, i.e.
+    /// the element at index 3. Prefixes of more than 16 bits are rejected.
     #[prost(uint32, repeated, tag = "4")]
     pub nullifiers: ::prost::alloc::vec::Vec<u32>,
+    /// Whether to return the Merkle paths of the notes in compressed form.
+    #[prost(bool, tag = "5")]
+    pub compress_merkle_paths: bool,
 }
 /// Note synchronization request.
 ///
@@ -75,6 +149,23 @@ pub struct SyncNoteRequest {
     /// Specifies the tags which the client is interested in.
     #[prost(fixed32, repeated, tag = "2")]
     pub note_tags: ::prost::alloc::vec::Vec<u32>,
+    /// Whether to return the Merkle paths of the notes in compressed form.
+    #[prost(bool, tag = "3")]
+    pub compress_merkle_paths: bool,
+}
+/// Compact synchronization of a single account.
+///
+/// Returns the latest state of the account and its merged delta since the block known by the
+/// client, instead of the account's update in every block in-between. Meant for accounts updated in
+/// many blocks, e.g. faucets.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SyncAccountCompactRequest {
+    /// ID of the account.
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+    /// Last block known by the client. The delta starts from the next block.
+    #[prost(fixed32, tag = "2")]
+    pub known_block: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetBlockInputsRequest {
@@ -87,6 +178,10 @@ pub struct GetBlockInputsRequest {
     /// Array of note IDs to be checked for existence in the database.
     #[prost(message, repeated, tag = "3")]
     pub unauthenticated_notes: ::prost::alloc::vec::Vec<super::digest::Digest>,
+    /// Cursor returned by the previous page of a paginated response, to fetch its next page. The
+    /// other fields are ignored when set.
+    #[prost(message, optional, tag = "4")]
+    pub cursor: ::core::option::Option<super::block::BlockInputsCursor>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetTransactionInputsRequest {
@@ -96,6 +191,9 @@ pub struct GetTransactionInputsRequest {
     pub nullifiers: ::prost::alloc::vec::Vec<super::digest::Digest>,
     #[prost(message, repeated, tag = "3")]
     pub unauthenticated_notes: ::prost::alloc::vec::Vec<super::digest::Digest>,
+    /// Hash of the block the transaction was executed against.
+    #[prost(message, optional, tag = "4")]
+    pub reference_block: ::core::option::Option<super::digest::Digest>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SubmitProvenTransactionRequest {
@@ -108,6 +206,51 @@ pub struct GetNotesByIdRequest {
     /// List of NoteId's to be queried from the database
     #[prost(message, repeated, tag = "1")]
     pub note_ids: ::prost::alloc::vec::Vec<super::digest::Digest>,
+    /// Whether to return the Merkle paths of the notes in compressed form.
+    #[prost(bool, tag = "2")]
+    pub compress_merkle_paths: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNotesByRecipientRequest {
+    /// Recipient digests of the queried notes.
+    #[prost(message, repeated, tag = "1")]
+    pub recipients: ::prost::alloc::vec::Vec<super::digest::Digest>,
+    /// Block number from which the notes are returned.
+    #[prost(fixed32, tag = "2")]
+    pub from_block: u32,
+    /// Position of the last note of the previous page, unset for the first page.
+    #[prost(message, optional, tag = "3")]
+    pub page: ::core::option::Option<super::note::NotePosition>,
+    /// Whether to return the Merkle paths of the notes in compressed form.
+    #[prost(bool, tag = "4")]
+    pub compress_merkle_paths: bool,
+}
+/// Returns the number of notes per tag prefix in a range of blocks.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetNoteTagStatisticsRequest {
+    /// First block of the range.
+    #[prost(fixed32, tag = "1")]
+    pub from_block: u32,
+    /// Last block of the range, included.
+    #[prost(fixed32, tag = "2")]
+    pub to_block: u32,
+    /// Number of most significant bits of the tags the notes are grouped by: 8, 16 or 32.
+    #[prost(uint32, tag = "3")]
+    pub prefix_len: u32,
+    /// Whether to leave out the notes whose tags are meant for network execution, i.e. whose most
+    /// significant bit is cleared.
+    #[prost(bool, tag = "4")]
+    pub exclude_network_notes: bool,
+}
+/// Returns the Bloom filters of the note tags of a range of blocks.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetBlockNoteFiltersRequest {
+    /// First block of the range.
+    #[prost(fixed32, tag = "1")]
+    pub from_block: u32,
+    /// Last block of the range, included.
+    #[prost(fixed32, tag = "2")]
+    pub to_block: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetNoteAuthenticationInfoRequest {
@@ -121,6 +264,55 @@ pub struct ListNullifiersRequest {}
 pub struct ListAccountsRequest {}
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct ListNotesRequest {}
+/// Returns the latest commitments of the specified accounts, without their details.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountCommitmentsRequest {
+    /// List of account IDs to get the commitments of.
+    #[prost(message, repeated, tag = "1")]
+    pub account_ids: ::prost::alloc::vec::Vec<super::account::AccountId>,
+}
+/// Returns the witnesses of the specified accounts against the account tree of a recent block.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountWitnessesRequest {
+    /// List of account IDs to get the witnesses of.
+    #[prost(message, repeated, tag = "1")]
+    pub account_ids: ::prost::alloc::vec::Vec<super::account::AccountId>,
+    /// Number of the block whose account tree the witnesses are against, the chain tip if not set.
+    /// Only the most recent blocks are supported.
+    #[prost(fixed32, optional, tag = "2")]
+    pub block_num: ::core::option::Option<u32>,
+}
+/// Returns which of the specified transactions were committed in a block.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterKnownTransactionsRequest {
+    /// List of transaction IDs to look up.
+    #[prost(message, repeated, tag = "1")]
+    pub transaction_ids: ::prost::alloc::vec::Vec<super::transaction::TransactionId>,
+}
+/// Streams the updates of the specified accounts by the blocks applied from now on.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeAccountUpdatesRequest {
+    /// List of account IDs to follow.
+    #[prost(message, repeated, tag = "1")]
+    pub account_ids: ::prost::alloc::vec::Vec<super::account::AccountId>,
+}
+/// Streams the state deltas of public accounts in the blocks `from_block..=to_block`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportAccountDeltasRequest {
+    /// List of account IDs to export.
+    #[prost(message, repeated, tag = "1")]
+    pub account_ids: ::prost::alloc::vec::Vec<super::account::AccountId>,
+    /// First block of the range.
+    #[prost(fixed32, tag = "2")]
+    pub from_block: u32,
+    /// Last block of the range.
+    #[prost(fixed32, tag = "3")]
+    pub to_block: u32,
+    /// Cursor of the last received delta, to resume an interrupted export. Unset to start from
+    /// `from_block`.
+    #[prost(message, optional, tag = "4")]
+    pub cursor: ::core::option::Option<super::account::AccountDeltaCursor>,
+}
 /// Returns the latest state of an account with the specified ID.
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct GetAccountDetailsRequest {
@@ -128,6 +320,23 @@ pub struct GetAccountDetailsRequest {
     #[prost(message, optional, tag = "1")]
     pub account_id: ::core::option::Option<super::account::AccountId>,
 }
+/// Returns the storage layout of a public account, without the stored values.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAccountStorageHeaderRequest {
+    /// ID of the public account.
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+}
+/// Returns a page of the assets in the vault of a public account.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAccountVaultAssetsRequest {
+    /// ID of the public account.
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+    /// Vault key of the last asset of the previous page, unset for the first page.
+    #[prost(message, optional, tag = "2")]
+    pub page: ::core::option::Option<super::digest::Digest>,
+}
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct GetBlockByNumberRequest {
     /// The block number of the target block.
@@ -148,6 +357,31 @@ pub struct GetAccountStateDeltaRequest {
     #[prost(fixed32, tag = "3")]
     pub to_block_num: u32,
 }
+/// Returns the dependency graph of the in-flight transactions and batches of the block producer.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DumpMempoolGraphRequest {
+    /// Format of the returned graph.
+    #[prost(enumeration = "GraphFormat", tag = "1")]
+    pub format: i32,
+}
+/// Evicts a transaction from the block production pipeline before it's included in a block.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct EvictTransactionRequest {
+    /// ID of the transaction to evict.
+    #[prost(message, optional, tag = "1")]
+    pub transaction_id: ::core::option::Option<super::transaction::TransactionId>,
+    /// Whether to also evict the in-flight transactions depending on it. Without it, the eviction is
+    /// refused if there is any.
+    #[prost(bool, tag = "2")]
+    pub cascade: bool,
+}
+/// Returns the state an account is expected to have once its in-flight transactions are committed.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAccountExpectedStateRequest {
+    /// ID of the account for which the expected state is requested.
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetAccountProofsRequest {
     /// List of account IDs to get states.
@@ -162,4 +396,51 @@ pub struct GetAccountProofsRequest {
     /// all requested accounts.
     #[prost(message, repeated, tag = "3")]
     pub code_commitments: ::prost::alloc::vec::Vec<super::digest::Digest>,
+    /// Block numbers of the latest account states known to the caller. For each of these accounts,
+    /// the response includes the delta of the public account's state since the known block.
+    #[prost(message, repeated, tag = "4")]
+    pub known_blocks: ::prost::alloc::vec::Vec<KnownAccountBlock>,
+    /// Optional flag to return every proof as an `AccountProofBundle`, in the `bundle` field of its
+    /// response. `false` by default.
+    #[prost(bool, optional, tag = "5")]
+    pub bundle_proofs: ::core::option::Option<bool>,
+}
+/// Block number of the latest state of an account known to the caller.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct KnownAccountBlock {
+    /// ID of the account, which must be one of the requested accounts.
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+    /// Block number of the account state known to the caller.
+    #[prost(fixed32, tag = "2")]
+    pub block_num: u32,
+}
+/// Format of a dumped graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum GraphFormat {
+    /// JSON document.
+    Json = 0,
+    /// Graphviz DOT language.
+    Dot = 1,
+}
+impl GraphFormat {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Json => "GRAPH_FORMAT_JSON",
+            Self::Dot => "GRAPH_FORMAT_DOT",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "GRAPH_FORMAT_JSON" => Some(Self::Json),
+            "GRAPH_FORMAT_DOT" => Some(Self::Dot),
+            _ => None,
+        }
+    }
 }
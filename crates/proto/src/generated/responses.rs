@@ -2,16 +2,36 @@
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct ApplyBlockResponse {}
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckNotesConsumedResponse {
+    /// Consumption status of the requested notes known to the node, in no particular order. Notes
+    /// which are unknown to the node are left out.
+    #[prost(message, repeated, tag = "1")]
+    pub notes: ::prost::alloc::vec::Vec<super::note::NoteConsumption>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "2")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CheckNullifiersResponse {
     /// Each requested nullifier has its corresponding nullifier proof at the same position.
     #[prost(message, repeated, tag = "1")]
     pub proofs: ::prost::alloc::vec::Vec<super::smt::SmtOpening>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "2")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CheckNullifiersByPrefixResponse {
-    /// List of nullifiers matching the prefixes specified in the request.
+    /// List of nullifiers matching the prefixes specified in the request, ordered by block number
+    /// and then by nullifier.
     #[prost(message, repeated, tag = "1")]
     pub nullifiers: ::prost::alloc::vec::Vec<NullifierUpdate>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "2")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+    /// Position to request the next page from, unset if there are no more nullifiers.
+    #[prost(message, optional, tag = "3")]
+    pub next_page: ::core::option::Option<super::note::NullifierPosition>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetBlockHeaderByNumberResponse {
@@ -24,6 +44,153 @@ pub struct GetBlockHeaderByNumberResponse {
     /// Current chain length
     #[prost(fixed32, optional, tag = "3")]
     pub chain_length: ::core::option::Option<u32>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "4")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetBlockHeaderByCommitmentResponse {
+    /// The requested block header
+    #[prost(message, optional, tag = "1")]
+    pub block_header: ::core::option::Option<super::block::BlockHeader>,
+    /// Number of the requested block
+    #[prost(fixed32, tag = "2")]
+    pub block_num: u32,
+    /// Merkle path to verify the block's inclusion in the MMR at the returned `chain_length`
+    #[prost(message, optional, tag = "3")]
+    pub mmr_path: ::core::option::Option<super::merkle::MerklePath>,
+    /// Current chain length
+    #[prost(fixed32, optional, tag = "4")]
+    pub chain_length: ::core::option::Option<u32>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "5")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetBlockHeadersRangeResponse {
+    /// Headers of the blocks of the range which are part of the chain, in order. The range is
+    /// truncated to the chain tip.
+    #[prost(message, repeated, tag = "1")]
+    pub block_headers: ::prost::alloc::vec::Vec<super::block::BlockHeader>,
+    /// MMR delta extending a partial MMR of the blocks before `from_block` to the blocks before the
+    /// last returned header, if requested. The chain root of the last returned header authenticates
+    /// the extended MMR, and the header itself is then added to it.
+    #[prost(message, optional, tag = "2")]
+    pub mmr_delta: ::core::option::Option<super::mmr::MmrDelta>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "3")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetBlockProductionStatsResponse {
+    /// Stage durations of the blocks of the requested range which are part of the chain, in order
+    #[prost(message, repeated, tag = "1")]
+    pub blocks: ::prost::alloc::vec::Vec<super::block::BlockProductionStats>,
+    /// Percentiles of the durations of every stage over the returned blocks
+    #[prost(message, repeated, tag = "2")]
+    pub percentiles: ::prost::alloc::vec::Vec<super::block::StageDurationPercentiles>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetChainStatsResponse {
+    /// First block of the range.
+    #[prost(fixed32, tag = "1")]
+    pub from_block: u32,
+    /// Last block of the range, the chain tip if the requested range goes beyond it.
+    #[prost(fixed32, tag = "2")]
+    pub to_block: u32,
+    /// Number of blocks of the range.
+    #[prost(uint32, tag = "3")]
+    pub num_blocks: u32,
+    /// Number of transactions of the blocks of the range.
+    #[prost(uint64, tag = "4")]
+    pub num_transactions: u64,
+    /// Number of notes created by the blocks of the range.
+    #[prost(uint64, tag = "5")]
+    pub num_notes: u64,
+    /// Number of nullifiers created by the blocks of the range.
+    #[prost(uint64, tag = "6")]
+    pub num_nullifiers: u64,
+    /// Number of accounts updated for the first time by the blocks of the range.
+    #[prost(uint64, tag = "7")]
+    pub num_new_accounts: u64,
+    /// Average duration between two consecutive blocks of the range in milliseconds, unset for a
+    /// range of a single block.
+    #[prost(uint64, optional, tag = "8")]
+    pub average_block_interval_ms: ::core::option::Option<u64>,
+}
+/// Number of waits for a database connection in a duration bucket.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct PoolWaitBucket {
+    /// Upper bound of the bucket in milliseconds, included, unset for the last, unbounded, bucket.
+    #[prost(uint64, optional, tag = "1")]
+    pub upper_bound_ms: ::core::option::Option<u64>,
+    /// Number of waits in the bucket, excluding the waits of the previous buckets.
+    #[prost(uint64, tag = "2")]
+    pub count: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDatabasePoolStatsResponse {
+    /// Maximum number of connections of the pool.
+    #[prost(uint32, tag = "1")]
+    pub max_size: u32,
+    /// Number of open connections.
+    #[prost(uint32, tag = "2")]
+    pub size: u32,
+    /// Number of connections running a query.
+    #[prost(uint32, tag = "3")]
+    pub in_use: u32,
+    /// Number of queries waiting for a connection.
+    #[prost(uint32, tag = "4")]
+    pub waiting: u32,
+    /// Histogram of the waits for a connection since the store started, ordered by upper bound.
+    #[prost(message, repeated, tag = "5")]
+    pub wait_buckets: ::prost::alloc::vec::Vec<PoolWaitBucket>,
+    /// Number of waits for a connection since the store started.
+    #[prost(uint64, tag = "6")]
+    pub num_waits: u64,
+    /// Sum of the waits for a connection since the store started, in microseconds.
+    #[prost(uint64, tag = "7")]
+    pub total_wait_us: u64,
+    /// Number of waits longer than the `slow_pool_wait_threshold_ms` of the store configuration.
+    #[prost(uint64, tag = "8")]
+    pub num_slow_waits: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDataAvailabilityResponse {
+    /// Oldest block whose full data is served by `GetBlockByNumber`, looked up in the block store.
+    /// The data of the genesis block is always served. Greater than the chain tip if the data of no
+    /// other block is served.
+    #[prost(fixed32, tag = "1")]
+    pub oldest_block_data: u32,
+    /// Number of most recent blocks whose full data is retained, unset if the data of old blocks is
+    /// not pruned
+    #[prost(fixed32, optional, tag = "2")]
+    pub block_retention_depth: ::core::option::Option<u32>,
+    /// Oldest block known to the client since which `GetAccountProofs` and `SyncAccountCompact`
+    /// return the state deltas of public accounts. Clients catching up on frequently updated
+    /// accounts should prefer `SyncAccountCompact` to following their updates with `SyncState`.
+    #[prost(fixed32, tag = "3")]
+    pub oldest_account_delta_block: u32,
+    /// Details of public notes stored by the node, served by `GetNotesById`
+    #[prost(message, optional, tag = "4")]
+    pub note_details: ::core::option::Option<super::note::NoteDetailsAvailability>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "5")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+    /// Which historical data the node keeps. Nodes predating the store modes report the archive
+    /// mode, whether or not they prune data: the fields above tell which data they serve.
+    #[prost(enumeration = "StoreMode", tag = "6")]
+    pub mode: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetSupportedBlockVersionsResponse {
+    /// Versions supported by the blocks preceding the scheduled upgrade, or by all the blocks if no
+    /// upgrade is scheduled.
+    #[prost(message, optional, tag = "1")]
+    pub supported: ::core::option::Option<super::block::BlockVersionRange>,
+    /// Upgrade of the supported versions, unset if none is scheduled.
+    #[prost(message, optional, tag = "2")]
+    pub upgrade: ::core::option::Option<super::block::BlockVersionUpgrade>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct NullifierUpdate {
@@ -56,6 +223,9 @@ pub struct SyncStateResponse {
     /// List of nullifiers created between `request.block_num + 1` and `response.block_header.block_num`
     #[prost(message, repeated, tag = "8")]
     pub nullifiers: ::prost::alloc::vec::Vec<NullifierUpdate>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "9")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SyncNoteResponse {
@@ -74,6 +244,28 @@ pub struct SyncNoteResponse {
     /// List of all notes together with the Merkle paths from `response.block_header.note_root`
     #[prost(message, repeated, tag = "4")]
     pub notes: ::prost::alloc::vec::Vec<super::note::NoteSyncRecord>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "5")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncAccountCompactResponse {
+    /// Latest commitment to the account's state.
+    #[prost(message, optional, tag = "1")]
+    pub account_hash: ::core::option::Option<super::digest::Digest>,
+    /// Block of the latest update of the account.
+    #[prost(fixed32, tag = "2")]
+    pub last_update_block: u32,
+    /// Number of blocks updating the account after the known block, which the delta replaces.
+    #[prost(uint32, tag = "3")]
+    pub skipped_updates: u32,
+    /// Delta of the account's state from the known block (exclusive) up to the chain tip
+    /// (inclusive). Only set for public accounts.
+    #[prost(message, optional, tag = "4")]
+    pub delta: ::core::option::Option<AccountStateDeltaInfo>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "5")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
 }
 /// An account returned as a response to the GetBlockInputs
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -107,11 +299,15 @@ pub struct GetBlockInputsResponse {
     /// The requested nullifiers and their authentication paths
     #[prost(message, repeated, tag = "4")]
     pub nullifiers: ::prost::alloc::vec::Vec<NullifierBlockInputRecord>,
-    /// The list of requested notes which were found in the database
+    /// The list of requested notes which were found in the database, only set on the first page
     #[prost(message, optional, tag = "5")]
     pub found_unauthenticated_notes: ::core::option::Option<
         super::note::NoteAuthenticationInfo,
     >,
+    /// Cursor of the next page if the account states and nullifiers didn't fit in this response.
+    /// All the pages are read from the same snapshot, anchored to the same block.
+    #[prost(message, optional, tag = "6")]
+    pub next: ::core::option::Option<super::block::BlockInputsCursor>,
 }
 /// An account returned as a response to the GetTransactionInputs
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
@@ -141,6 +337,9 @@ pub struct GetTransactionInputsResponse {
     pub missing_unauthenticated_notes: ::prost::alloc::vec::Vec<super::digest::Digest>,
     #[prost(fixed32, tag = "4")]
     pub block_height: u32,
+    /// Number of the requested reference block, unset if the block is not part of the chain.
+    #[prost(fixed32, optional, tag = "5")]
+    pub reference_block_num: ::core::option::Option<u32>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct SubmitProvenTransactionResponse {
@@ -148,11 +347,99 @@ pub struct SubmitProvenTransactionResponse {
     #[prost(fixed32, tag = "1")]
     pub block_height: u32,
 }
+/// Message of the stream following a submitted transaction through the block production pipeline.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubmitAndWatchProvenTransactionResponse {
+    #[prost(
+        oneof = "submit_and_watch_proven_transaction_response::Status",
+        tags = "1, 2, 3, 4, 5"
+    )]
+    pub status: ::core::option::Option<
+        submit_and_watch_proven_transaction_response::Status,
+    >,
+}
+/// Nested message and enum types in `SubmitAndWatchProvenTransactionResponse`.
+pub mod submit_and_watch_proven_transaction_response {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Status {
+        /// The transaction was accepted, always the first message of the stream.
+        #[prost(message, tag = "1")]
+        Submitted(super::SubmitProvenTransactionResponse),
+        /// The transaction was included in a batch.
+        #[prost(message, tag = "2")]
+        Batched(super::TransactionBatched),
+        /// The transaction was dropped and will never be committed. Ends the stream.
+        #[prost(message, tag = "3")]
+        Rejected(super::TransactionRejected),
+        /// The transaction was committed. Ends the stream.
+        #[prost(message, tag = "4")]
+        Committed(super::TransactionCommitted),
+        /// The transaction is still pending after the watch timeout of the node. Ends the stream.
+        #[prost(message, tag = "5")]
+        StillPending(super::TransactionStillPending),
+    }
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct TransactionBatched {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransactionRejected {
+    /// Reason of the rejection.
+    #[prost(string, tag = "1")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct TransactionCommitted {
+    /// Number of the block which committed the transaction.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Commitment of the block which committed the transaction.
+    #[prost(message, optional, tag = "2")]
+    pub block_commitment: ::core::option::Option<super::digest::Digest>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct TransactionStillPending {}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetNotesByIdResponse {
     /// Lists Note's returned by the database
     #[prost(message, repeated, tag = "1")]
     pub notes: ::prost::alloc::vec::Vec<super::note::Note>,
+    /// Whether notes were left out to keep the response within the store's size limit. The notes
+    /// which were left out are fetched by requesting them again.
+    #[prost(bool, tag = "2")]
+    pub truncated: bool,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "3")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNotesByRecipientResponse {
+    /// Public notes sent to the requested recipients, in chain order.
+    #[prost(message, repeated, tag = "1")]
+    pub notes: ::prost::alloc::vec::Vec<super::note::Note>,
+    /// Position to request the next page from, unset if there are no more notes.
+    #[prost(message, optional, tag = "2")]
+    pub next_page: ::core::option::Option<super::note::NotePosition>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "3")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNoteTagStatisticsResponse {
+    /// Number of notes of the non-empty tag buckets, ordered by prefix.
+    #[prost(message, repeated, tag = "1")]
+    pub buckets: ::prost::alloc::vec::Vec<super::note::NoteTagBucket>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "2")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetBlockNoteFiltersResponse {
+    /// Filters of the blocks of the range up to the chain tip, ordered by block number.
+    #[prost(message, repeated, tag = "1")]
+    pub filters: ::prost::alloc::vec::Vec<super::note::BlockNoteFilter>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "2")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetNoteAuthenticationInfoResponse {
@@ -178,22 +465,191 @@ pub struct ListNotesResponse {
     pub notes: ::prost::alloc::vec::Vec<super::note::Note>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountCommitmentsResponse {
+    /// Number of the latest block, as of which the commitments are returned.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Commitments of the requested accounts, with the block of their last update. Accounts not in
+    /// the store are omitted.
+    #[prost(message, repeated, tag = "2")]
+    pub accounts: ::prost::alloc::vec::Vec<super::account::AccountSummary>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountWitnessesResponse {
+    /// Number of the block whose account tree the witnesses are against.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Witnesses of the requested accounts, in the requested order. Accounts not in the tree have
+    /// a witness of their empty leaf.
+    #[prost(message, repeated, tag = "2")]
+    pub witnesses: ::prost::alloc::vec::Vec<AccountBlockInputRecord>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "3")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterKnownTransactionsResponse {
+    /// Number of the latest block, as of which the transactions are looked up.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// The requested transactions which were committed, with the block they were committed in.
+    /// Transactions not committed yet are omitted.
+    #[prost(message, repeated, tag = "2")]
+    pub transactions: ::prost::alloc::vec::Vec<super::transaction::TransactionSummary>,
+}
+/// Delta of the state of an account by a block, streamed by `ExportAccountDeltas`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportAccountDeltasResponse {
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+    /// Number of the block which updated the account.
+    #[prost(fixed32, tag = "2")]
+    pub block_num: u32,
+    /// Delta of the account state by the block, encoded using miden native format.
+    #[prost(bytes = "vec", tag = "3")]
+    pub delta: ::prost::alloc::vec::Vec<u8>,
+    /// Cursor to resume the export after this delta.
+    #[prost(message, optional, tag = "4")]
+    pub cursor: ::core::option::Option<super::account::AccountDeltaCursor>,
+}
+/// Message of the stream following the updates of accounts.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeAccountUpdatesResponse {
+    #[prost(oneof = "subscribe_account_updates_response::Update", tags = "1, 2")]
+    pub update: ::core::option::Option<subscribe_account_updates_response::Update>,
+}
+/// Nested message and enum types in `SubscribeAccountUpdatesResponse`.
+pub mod subscribe_account_updates_response {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Update {
+        /// Latest commitments of the followed accounts, always the first message of the stream.
+        #[prost(message, tag = "1")]
+        Snapshot(super::GetAccountCommitmentsResponse),
+        /// Updates of the followed accounts by a block applied after the snapshot.
+        #[prost(message, tag = "2")]
+        Block(super::AccountBlockUpdates),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccountBlockUpdates {
+    /// Number of the block which updated the accounts.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Updates of the followed accounts by the block.
+    #[prost(message, repeated, tag = "2")]
+    pub updates: ::prost::alloc::vec::Vec<AccountBlockUpdate>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccountBlockUpdate {
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+    /// Commitment of the account state after the block.
+    #[prost(message, optional, tag = "2")]
+    pub account_hash: ::core::option::Option<super::digest::Digest>,
+    /// Delta of the state of a public account by the block, encoded using miden native format.
+    /// Unset for private accounts, and for new public accounts whose details are requested with
+    /// `GetAccountDetails`.
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub delta: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetAccountDetailsResponse {
     /// Account info (with details for public accounts)
     #[prost(message, optional, tag = "1")]
     pub details: ::core::option::Option<super::account::AccountInfo>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "2")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountStorageHeaderResponse {
+    /// Header of the latest block, as of which the storage layout is returned
+    #[prost(message, optional, tag = "1")]
+    pub block_header: ::core::option::Option<super::block::BlockHeader>,
+    /// Commitment to the account's storage
+    #[prost(message, optional, tag = "2")]
+    pub storage_commitment: ::core::option::Option<super::digest::Digest>,
+    /// Headers of the account's storage slots, ordered by index
+    #[prost(message, repeated, tag = "3")]
+    pub slots: ::prost::alloc::vec::Vec<super::account::AccountStorageSlotHeader>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "4")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountVaultAssetsResponse {
+    /// Number of the latest block, as of which the assets are returned
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Root of the account's vault. Pages read at different roots may miss or repeat assets.
+    #[prost(message, optional, tag = "2")]
+    pub vault_root: ::core::option::Option<super::digest::Digest>,
+    /// Assets of the page, ordered by vault key
+    #[prost(message, repeated, tag = "3")]
+    pub assets: ::prost::alloc::vec::Vec<super::account::AccountVaultAsset>,
+    /// Vault key to request the next page from, unset if there are no more assets.
+    #[prost(message, optional, tag = "4")]
+    pub next_page: ::core::option::Option<super::digest::Digest>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "5")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetBlockByNumberResponse {
     /// The requested `Block` data encoded using miden native format
     #[prost(bytes = "vec", optional, tag = "1")]
     pub block: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "2")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+    /// The latest submission of the block, only known for blocks submitted with producer metadata.
+    #[prost(message, optional, tag = "3")]
+    pub provenance: ::core::option::Option<super::block::BlockProvenance>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetAccountStateDeltaResponse {
     /// The calculated `AccountStateDelta` encoded using miden native format
     #[prost(bytes = "vec", optional, tag = "1")]
     pub delta: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "2")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DumpMempoolGraphResponse {
+    /// The graph, in the requested format.
+    #[prost(string, tag = "1")]
+    pub graph: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EvictTransactionResponse {
+    /// The evicted transaction, followed by the evicted transactions depending on it.
+    #[prost(message, repeated, tag = "1")]
+    pub evicted_transactions: ::prost::alloc::vec::Vec<
+        super::transaction::TransactionId,
+    >,
+    /// IDs of the batches which were waiting for a block, dropped as they held evicted transactions
+    /// or depended on the transactions of other dropped batches.
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub dropped_batches: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Transactions of the dropped batches which were not evicted, and were queued again.
+    #[prost(message, repeated, tag = "3")]
+    pub requeued_transactions: ::prost::alloc::vec::Vec<
+        super::transaction::TransactionId,
+    >,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAccountExpectedStateResponse {
+    /// Expected state hash of the account. Unset if the account is neither committed nor in-flight.
+    #[prost(message, optional, tag = "1")]
+    pub account_hash: ::core::option::Option<super::digest::Digest>,
+    /// Whether the hash is the final state of an in-flight transaction, or the committed state of
+    /// the account otherwise.
+    #[prost(bool, tag = "2")]
+    pub in_flight: bool,
+    /// The current chain tip.
+    #[prost(fixed32, tag = "3")]
+    pub block_height: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetAccountProofsResponse {
@@ -203,6 +659,13 @@ pub struct GetAccountProofsResponse {
     /// List of account state infos for the requested account keys.
     #[prost(message, repeated, tag = "2")]
     pub account_proofs: ::prost::alloc::vec::Vec<AccountProofsResponse>,
+    /// Whether proofs were left out to keep the response within the store's size limit. The proofs
+    /// which were left out are fetched by requesting the remaining accounts again.
+    #[prost(bool, tag = "3")]
+    pub truncated: bool,
+    /// The chain tip the response was computed against.
+    #[prost(message, optional, tag = "4")]
+    pub anchor: ::core::option::Option<super::block::ChainAnchor>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AccountProofsResponse {
@@ -218,6 +681,38 @@ pub struct AccountProofsResponse {
     /// State header for public accounts. Filled only if `include_headers` flag is set to `true`.
     #[prost(message, optional, tag = "4")]
     pub state_header: ::core::option::Option<AccountStateHeader>,
+    /// Changes of the public account's state since the block known to the caller. Filled only if
+    /// the request contained a known block for the account.
+    #[prost(message, optional, tag = "5")]
+    pub state_delta: ::core::option::Option<AccountStateDeltaInfo>,
+    /// The proof of the account's state as an `AccountProofBundle` encoded using miden native
+    /// format, replacing `account_hash`, `account_proof` and `state_header`. Filled only if the
+    /// request set `bundle_proofs`.
+    #[prost(bytes = "vec", optional, tag = "6")]
+    pub bundle: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccountStateDeltaInfo {
+    #[prost(oneof = "account_state_delta_info::Data", tags = "1, 2, 3")]
+    pub data: ::core::option::Option<account_state_delta_info::Data>,
+}
+/// Nested message and enum types in `AccountStateDeltaInfo`.
+pub mod account_state_delta_info {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Data {
+        /// Delta of the account state from the known block (exclusive) up to the response's
+        /// `block_num` (inclusive), encoded using miden native format.
+        #[prost(bytes, tag = "1")]
+        Delta(::prost::alloc::vec::Vec<u8>),
+        /// The known block is older than the account history retained by the node, the full account
+        /// details must be requested instead.
+        #[prost(bool, tag = "2")]
+        TooOld(bool),
+        /// The delta exceeds the response size limit, the full account details must be requested
+        /// instead.
+        #[prost(bool, tag = "3")]
+        FetchDetails(bool),
+    }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AccountStateHeader {
@@ -232,3 +727,33 @@ pub struct AccountStateHeader {
     #[prost(bytes = "vec", optional, tag = "3")]
     pub account_code: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
+/// Historical data kept by a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum StoreMode {
+    /// All historical data is kept: the data of all blocks, the details of all public notes and the
+    /// deltas of all public accounts.
+    Archive = 0,
+    /// Only the data needed to serve the current state and the recent history is kept.
+    Pruned = 1,
+}
+impl StoreMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Archive => "STORE_MODE_ARCHIVE",
+            Self::Pruned => "STORE_MODE_PRUNED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "STORE_MODE_ARCHIVE" => Some(Self::Archive),
+            "STORE_MODE_PRUNED" => Some(Self::Pruned),
+            _ => None,
+        }
+    }
+}
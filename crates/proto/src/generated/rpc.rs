@@ -90,6 +90,32 @@ pub mod api_client {
             self.inner = self.inner.max_encoding_message_size(limit);
             self
         }
+        pub async fn check_notes_consumed(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::CheckNotesConsumedRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::CheckNotesConsumedResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/CheckNotesConsumed",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "CheckNotesConsumed"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn check_nullifiers(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -164,6 +190,32 @@ pub mod api_client {
             req.extensions_mut().insert(GrpcMethod::new("rpc.Api", "GetAccountDetails"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_account_expected_state(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetAccountExpectedStateRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountExpectedStateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/GetAccountExpectedState",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "GetAccountExpectedState"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_account_proofs(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -213,6 +265,84 @@ pub mod api_client {
                 .insert(GrpcMethod::new("rpc.Api", "GetAccountStateDelta"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_account_storage_header(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetAccountStorageHeaderRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountStorageHeaderResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/GetAccountStorageHeader",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "GetAccountStorageHeader"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_account_vault_assets(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetAccountVaultAssetsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountVaultAssetsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/GetAccountVaultAssets",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "GetAccountVaultAssets"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_account_witnesses(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetAccountWitnessesRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountWitnessesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/GetAccountWitnesses",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "GetAccountWitnesses"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_block_by_number(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -236,6 +366,58 @@ pub mod api_client {
             req.extensions_mut().insert(GrpcMethod::new("rpc.Api", "GetBlockByNumber"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_block_note_filters(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetBlockNoteFiltersRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockNoteFiltersResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/GetBlockNoteFilters",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "GetBlockNoteFilters"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_block_header_by_commitment(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetBlockHeaderByCommitmentRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockHeaderByCommitmentResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/GetBlockHeaderByCommitment",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "GetBlockHeaderByCommitment"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_block_header_by_number(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -262,11 +444,13 @@ pub mod api_client {
                 .insert(GrpcMethod::new("rpc.Api", "GetBlockHeaderByNumber"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_notes_by_id(
+        pub async fn get_block_headers_range(
             &mut self,
-            request: impl tonic::IntoRequest<super::super::requests::GetNotesByIdRequest>,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetBlockHeadersRangeRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetNotesByIdResponse>,
+            tonic::Response<super::super::responses::GetBlockHeadersRangeResponse>,
             tonic::Status,
         > {
             self.inner
@@ -278,18 +462,21 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rpc.Api/GetNotesById");
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/GetBlockHeadersRange",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("rpc.Api", "GetNotesById"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "GetBlockHeadersRange"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn submit_proven_transaction(
+        pub async fn get_data_availability(
             &mut self,
             request: impl tonic::IntoRequest<
-                super::super::requests::SubmitProvenTransactionRequest,
+                super::super::requests::GetDataAvailabilityRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::SubmitProvenTransactionResponse>,
+            tonic::Response<super::super::responses::GetDataAvailabilityResponse>,
             tonic::Status,
         > {
             self.inner
@@ -302,18 +489,20 @@ pub mod api_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rpc.Api/SubmitProvenTransaction",
+                "/rpc.Api/GetDataAvailability",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("rpc.Api", "SubmitProvenTransaction"));
+                .insert(GrpcMethod::new("rpc.Api", "GetDataAvailability"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn sync_notes(
+        pub async fn get_note_tag_statistics(
             &mut self,
-            request: impl tonic::IntoRequest<super::super::requests::SyncNoteRequest>,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetNoteTagStatisticsRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::SyncNoteResponse>,
+            tonic::Response<super::super::responses::GetNoteTagStatisticsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -325,16 +514,19 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rpc.Api/SyncNotes");
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/GetNoteTagStatistics",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("rpc.Api", "SyncNotes"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "GetNoteTagStatistics"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn sync_state(
+        pub async fn get_notes_by_id(
             &mut self,
-            request: impl tonic::IntoRequest<super::super::requests::SyncStateRequest>,
+            request: impl tonic::IntoRequest<super::super::requests::GetNotesByIdRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::SyncStateResponse>,
+            tonic::Response<super::super::responses::GetNotesByIdResponse>,
             tonic::Status,
         > {
             self.inner
@@ -346,47 +538,213 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rpc.Api/SyncState");
+            let path = http::uri::PathAndQuery::from_static("/rpc.Api/GetNotesById");
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("rpc.Api", "SyncState"));
+            req.extensions_mut().insert(GrpcMethod::new("rpc.Api", "GetNotesById"));
             self.inner.unary(req, path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod api_server {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value,
-    )]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with ApiServer.
-    #[async_trait]
-    pub trait Api: std::marker::Send + std::marker::Sync + 'static {
-        async fn check_nullifiers(
-            &self,
-            request: tonic::Request<super::super::requests::CheckNullifiersRequest>,
+        pub async fn get_notes_by_recipient(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetNotesByRecipientRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::CheckNullifiersResponse>,
+            tonic::Response<super::super::responses::GetNotesByRecipientResponse>,
             tonic::Status,
-        >;
-        async fn check_nullifiers_by_prefix(
-            &self,
-            request: tonic::Request<
-                super::super::requests::CheckNullifiersByPrefixRequest,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/GetNotesByRecipient",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "GetNotesByRecipient"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn submit_and_watch_proven_transaction(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::SubmitProvenTransactionRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::CheckNullifiersByPrefixResponse>,
+            tonic::Response<
+                tonic::codec::Streaming<
+                    super::super::responses::SubmitAndWatchProvenTransactionResponse,
+                >,
+            >,
             tonic::Status,
-        >;
-        async fn get_account_details(
-            &self,
-            request: tonic::Request<super::super::requests::GetAccountDetailsRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/SubmitAndWatchProvenTransaction",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "SubmitAndWatchProvenTransaction"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn submit_proven_transaction(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::SubmitProvenTransactionRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetAccountDetailsResponse>,
+            tonic::Response<super::super::responses::SubmitProvenTransactionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/SubmitProvenTransaction",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "SubmitProvenTransaction"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn sync_account_compact(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::SyncAccountCompactRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncAccountCompactResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rpc.Api/SyncAccountCompact",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rpc.Api", "SyncAccountCompact"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn sync_notes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::requests::SyncNoteRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncNoteResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rpc.Api/SyncNotes");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("rpc.Api", "SyncNotes"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn sync_state(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::requests::SyncStateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncStateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rpc.Api/SyncState");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("rpc.Api", "SyncState"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod api_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ApiServer.
+    #[async_trait]
+    pub trait Api: std::marker::Send + std::marker::Sync + 'static {
+        async fn check_notes_consumed(
+            &self,
+            request: tonic::Request<super::super::requests::CheckNotesConsumedRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::CheckNotesConsumedResponse>,
+            tonic::Status,
+        >;
+        async fn check_nullifiers(
+            &self,
+            request: tonic::Request<super::super::requests::CheckNullifiersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::CheckNullifiersResponse>,
+            tonic::Status,
+        >;
+        async fn check_nullifiers_by_prefix(
+            &self,
+            request: tonic::Request<
+                super::super::requests::CheckNullifiersByPrefixRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::CheckNullifiersByPrefixResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_details(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountDetailsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountDetailsResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_expected_state(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetAccountExpectedStateRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountExpectedStateResponse>,
             tonic::Status,
         >;
         async fn get_account_proofs(
@@ -403,6 +761,29 @@ pub mod api_server {
             tonic::Response<super::super::responses::GetAccountStateDeltaResponse>,
             tonic::Status,
         >;
+        async fn get_account_storage_header(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetAccountStorageHeaderRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountStorageHeaderResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_vault_assets(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountVaultAssetsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountVaultAssetsResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_witnesses(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountWitnessesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountWitnessesResponse>,
+            tonic::Status,
+        >;
         async fn get_block_by_number(
             &self,
             request: tonic::Request<super::super::requests::GetBlockByNumberRequest>,
@@ -410,6 +791,22 @@ pub mod api_server {
             tonic::Response<super::super::responses::GetBlockByNumberResponse>,
             tonic::Status,
         >;
+        async fn get_block_note_filters(
+            &self,
+            request: tonic::Request<super::super::requests::GetBlockNoteFiltersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockNoteFiltersResponse>,
+            tonic::Status,
+        >;
+        async fn get_block_header_by_commitment(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetBlockHeaderByCommitmentRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockHeaderByCommitmentResponse>,
+            tonic::Status,
+        >;
         async fn get_block_header_by_number(
             &self,
             request: tonic::Request<
@@ -419,6 +816,27 @@ pub mod api_server {
             tonic::Response<super::super::responses::GetBlockHeaderByNumberResponse>,
             tonic::Status,
         >;
+        async fn get_block_headers_range(
+            &self,
+            request: tonic::Request<super::super::requests::GetBlockHeadersRangeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockHeadersRangeResponse>,
+            tonic::Status,
+        >;
+        async fn get_data_availability(
+            &self,
+            request: tonic::Request<super::super::requests::GetDataAvailabilityRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetDataAvailabilityResponse>,
+            tonic::Status,
+        >;
+        async fn get_note_tag_statistics(
+            &self,
+            request: tonic::Request<super::super::requests::GetNoteTagStatisticsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNoteTagStatisticsResponse>,
+            tonic::Status,
+        >;
         async fn get_notes_by_id(
             &self,
             request: tonic::Request<super::super::requests::GetNotesByIdRequest>,
@@ -426,6 +844,31 @@ pub mod api_server {
             tonic::Response<super::super::responses::GetNotesByIdResponse>,
             tonic::Status,
         >;
+        async fn get_notes_by_recipient(
+            &self,
+            request: tonic::Request<super::super::requests::GetNotesByRecipientRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNotesByRecipientResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the SubmitAndWatchProvenTransaction method.
+        type SubmitAndWatchProvenTransactionStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<
+                    super::super::responses::SubmitAndWatchProvenTransactionResponse,
+                    tonic::Status,
+                >,
+            >
+            + std::marker::Send
+            + 'static;
+        async fn submit_and_watch_proven_transaction(
+            &self,
+            request: tonic::Request<
+                super::super::requests::SubmitProvenTransactionRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<Self::SubmitAndWatchProvenTransactionStream>,
+            tonic::Status,
+        >;
         async fn submit_proven_transaction(
             &self,
             request: tonic::Request<
@@ -435,6 +878,13 @@ pub mod api_server {
             tonic::Response<super::super::responses::SubmitProvenTransactionResponse>,
             tonic::Status,
         >;
+        async fn sync_account_compact(
+            &self,
+            request: tonic::Request<super::super::requests::SyncAccountCompactRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncAccountCompactResponse>,
+            tonic::Status,
+        >;
         async fn sync_notes(
             &self,
             request: tonic::Request<super::super::requests::SyncNoteRequest>,
@@ -526,15 +976,450 @@ pub mod api_server {
         }
         fn call(&mut self, req: http::Request<B>) -> Self::Future {
             match req.uri().path() {
+                "/rpc.Api/CheckNotesConsumed" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckNotesConsumedSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::CheckNotesConsumedRequest,
+                    > for CheckNotesConsumedSvc<T> {
+                        type Response = super::super::responses::CheckNotesConsumedResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::CheckNotesConsumedRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::check_notes_consumed(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckNotesConsumedSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/rpc.Api/CheckNullifiers" => {
                     #[allow(non_camel_case_types)]
-                    struct CheckNullifiersSvc<T: Api>(pub Arc<T>);
+                    struct CheckNullifiersSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::CheckNullifiersRequest,
+                    > for CheckNullifiersSvc<T> {
+                        type Response = super::super::responses::CheckNullifiersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::CheckNullifiersRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::check_nullifiers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckNullifiersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/CheckNullifiersByPrefix" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckNullifiersByPrefixSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::CheckNullifiersByPrefixRequest,
+                    > for CheckNullifiersByPrefixSvc<T> {
+                        type Response = super::super::responses::CheckNullifiersByPrefixResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::CheckNullifiersByPrefixRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::check_nullifiers_by_prefix(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckNullifiersByPrefixSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/GetAccountDetails" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountDetailsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountDetailsRequest,
+                    > for GetAccountDetailsSvc<T> {
+                        type Response = super::super::responses::GetAccountDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountDetailsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_details(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountDetailsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/GetAccountExpectedState" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountExpectedStateSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountExpectedStateRequest,
+                    > for GetAccountExpectedStateSvc<T> {
+                        type Response = super::super::responses::GetAccountExpectedStateResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountExpectedStateRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_expected_state(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountExpectedStateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/GetAccountProofs" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountProofsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountProofsRequest,
+                    > for GetAccountProofsSvc<T> {
+                        type Response = super::super::responses::GetAccountProofsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountProofsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_proofs(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountProofsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/GetAccountStateDelta" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountStateDeltaSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountStateDeltaRequest,
+                    > for GetAccountStateDeltaSvc<T> {
+                        type Response = super::super::responses::GetAccountStateDeltaResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountStateDeltaRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_state_delta(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountStateDeltaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/GetAccountStorageHeader" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountStorageHeaderSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountStorageHeaderRequest,
+                    > for GetAccountStorageHeaderSvc<T> {
+                        type Response = super::super::responses::GetAccountStorageHeaderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountStorageHeaderRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_storage_header(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountStorageHeaderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/GetAccountVaultAssets" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountVaultAssetsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountVaultAssetsRequest,
+                    > for GetAccountVaultAssetsSvc<T> {
+                        type Response = super::super::responses::GetAccountVaultAssetsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountVaultAssetsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_vault_assets(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountVaultAssetsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/GetAccountWitnesses" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountWitnessesSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::CheckNullifiersRequest,
-                    > for CheckNullifiersSvc<T> {
-                        type Response = super::super::responses::CheckNullifiersResponse;
+                        super::super::requests::GetAccountWitnessesRequest,
+                    > for GetAccountWitnessesSvc<T> {
+                        type Response = super::super::responses::GetAccountWitnessesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -542,12 +1427,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::CheckNullifiersRequest,
+                                super::super::requests::GetAccountWitnessesRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::check_nullifiers(&inner, request).await
+                                <T as Api>::get_account_witnesses(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -558,7 +1443,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CheckNullifiersSvc(inner);
+                        let method = GetAccountWitnessesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -574,15 +1459,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/rpc.Api/CheckNullifiersByPrefix" => {
+                "/rpc.Api/GetBlockByNumber" => {
                     #[allow(non_camel_case_types)]
-                    struct CheckNullifiersByPrefixSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockByNumberSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::CheckNullifiersByPrefixRequest,
-                    > for CheckNullifiersByPrefixSvc<T> {
-                        type Response = super::super::responses::CheckNullifiersByPrefixResponse;
+                        super::super::requests::GetBlockByNumberRequest,
+                    > for GetBlockByNumberSvc<T> {
+                        type Response = super::super::responses::GetBlockByNumberResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -590,13 +1475,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::CheckNullifiersByPrefixRequest,
+                                super::super::requests::GetBlockByNumberRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::check_nullifiers_by_prefix(&inner, request)
-                                    .await
+                                <T as Api>::get_block_by_number(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -607,7 +1491,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CheckNullifiersByPrefixSvc(inner);
+                        let method = GetBlockByNumberSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -623,15 +1507,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/rpc.Api/GetAccountDetails" => {
+                "/rpc.Api/GetBlockNoteFilters" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAccountDetailsSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockNoteFiltersSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetAccountDetailsRequest,
-                    > for GetAccountDetailsSvc<T> {
-                        type Response = super::super::responses::GetAccountDetailsResponse;
+                        super::super::requests::GetBlockNoteFiltersRequest,
+                    > for GetBlockNoteFiltersSvc<T> {
+                        type Response = super::super::responses::GetBlockNoteFiltersResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -639,12 +1523,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetAccountDetailsRequest,
+                                super::super::requests::GetBlockNoteFiltersRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_account_details(&inner, request).await
+                                <T as Api>::get_block_note_filters(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -655,7 +1539,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetAccountDetailsSvc(inner);
+                        let method = GetBlockNoteFiltersSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -671,15 +1555,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/rpc.Api/GetAccountProofs" => {
+                "/rpc.Api/GetBlockHeaderByCommitment" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAccountProofsSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockHeaderByCommitmentSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetAccountProofsRequest,
-                    > for GetAccountProofsSvc<T> {
-                        type Response = super::super::responses::GetAccountProofsResponse;
+                        super::super::requests::GetBlockHeaderByCommitmentRequest,
+                    > for GetBlockHeaderByCommitmentSvc<T> {
+                        type Response = super::super::responses::GetBlockHeaderByCommitmentResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -687,12 +1571,13 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetAccountProofsRequest,
+                                super::super::requests::GetBlockHeaderByCommitmentRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_account_proofs(&inner, request).await
+                                <T as Api>::get_block_header_by_commitment(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -703,7 +1588,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetAccountProofsSvc(inner);
+                        let method = GetBlockHeaderByCommitmentSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -719,15 +1604,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/rpc.Api/GetAccountStateDelta" => {
+                "/rpc.Api/GetBlockHeaderByNumber" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAccountStateDeltaSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockHeaderByNumberSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetAccountStateDeltaRequest,
-                    > for GetAccountStateDeltaSvc<T> {
-                        type Response = super::super::responses::GetAccountStateDeltaResponse;
+                        super::super::requests::GetBlockHeaderByNumberRequest,
+                    > for GetBlockHeaderByNumberSvc<T> {
+                        type Response = super::super::responses::GetBlockHeaderByNumberResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -735,12 +1620,13 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetAccountStateDeltaRequest,
+                                super::super::requests::GetBlockHeaderByNumberRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_account_state_delta(&inner, request).await
+                                <T as Api>::get_block_header_by_number(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -751,7 +1637,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetAccountStateDeltaSvc(inner);
+                        let method = GetBlockHeaderByNumberSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -767,15 +1653,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/rpc.Api/GetBlockByNumber" => {
+                "/rpc.Api/GetBlockHeadersRange" => {
                     #[allow(non_camel_case_types)]
-                    struct GetBlockByNumberSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockHeadersRangeSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetBlockByNumberRequest,
-                    > for GetBlockByNumberSvc<T> {
-                        type Response = super::super::responses::GetBlockByNumberResponse;
+                        super::super::requests::GetBlockHeadersRangeRequest,
+                    > for GetBlockHeadersRangeSvc<T> {
+                        type Response = super::super::responses::GetBlockHeadersRangeResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -783,12 +1669,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetBlockByNumberRequest,
+                                super::super::requests::GetBlockHeadersRangeRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_block_by_number(&inner, request).await
+                                <T as Api>::get_block_headers_range(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -799,7 +1685,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetBlockByNumberSvc(inner);
+                        let method = GetBlockHeadersRangeSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -815,15 +1701,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/rpc.Api/GetBlockHeaderByNumber" => {
+                "/rpc.Api/GetDataAvailability" => {
                     #[allow(non_camel_case_types)]
-                    struct GetBlockHeaderByNumberSvc<T: Api>(pub Arc<T>);
+                    struct GetDataAvailabilitySvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetBlockHeaderByNumberRequest,
-                    > for GetBlockHeaderByNumberSvc<T> {
-                        type Response = super::super::responses::GetBlockHeaderByNumberResponse;
+                        super::super::requests::GetDataAvailabilityRequest,
+                    > for GetDataAvailabilitySvc<T> {
+                        type Response = super::super::responses::GetDataAvailabilityResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -831,13 +1717,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetBlockHeaderByNumberRequest,
+                                super::super::requests::GetDataAvailabilityRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_block_header_by_number(&inner, request)
-                                    .await
+                                <T as Api>::get_data_availability(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -848,7 +1733,55 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetBlockHeaderByNumberSvc(inner);
+                        let method = GetDataAvailabilitySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/GetNoteTagStatistics" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetNoteTagStatisticsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetNoteTagStatisticsRequest,
+                    > for GetNoteTagStatisticsSvc<T> {
+                        type Response = super::super::responses::GetNoteTagStatisticsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetNoteTagStatisticsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_note_tag_statistics(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetNoteTagStatisticsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -912,6 +1845,107 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
+                "/rpc.Api/GetNotesByRecipient" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetNotesByRecipientSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetNotesByRecipientRequest,
+                    > for GetNotesByRecipientSvc<T> {
+                        type Response = super::super::responses::GetNotesByRecipientResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetNotesByRecipientRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_notes_by_recipient(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetNotesByRecipientSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rpc.Api/SubmitAndWatchProvenTransaction" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubmitAndWatchProvenTransactionSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::ServerStreamingService<
+                        super::super::requests::SubmitProvenTransactionRequest,
+                    > for SubmitAndWatchProvenTransactionSvc<T> {
+                        type Response = super::super::responses::SubmitAndWatchProvenTransactionResponse;
+                        type ResponseStream = T::SubmitAndWatchProvenTransactionStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::SubmitProvenTransactionRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::submit_and_watch_proven_transaction(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SubmitAndWatchProvenTransactionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/rpc.Api/SubmitProvenTransaction" => {
                     #[allow(non_camel_case_types)]
                     struct SubmitProvenTransactionSvc<T: Api>(pub Arc<T>);
@@ -960,6 +1994,54 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
+                "/rpc.Api/SyncAccountCompact" => {
+                    #[allow(non_camel_case_types)]
+                    struct SyncAccountCompactSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::SyncAccountCompactRequest,
+                    > for SyncAccountCompactSvc<T> {
+                        type Response = super::super::responses::SyncAccountCompactResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::SyncAccountCompactRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::sync_account_compact(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SyncAccountCompactSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/rpc.Api/SyncNotes" => {
                     #[allow(non_camel_case_types)]
                     struct SyncNotesSvc<T: Api>(pub Arc<T>);
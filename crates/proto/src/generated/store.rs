@@ -111,6 +111,32 @@ pub mod api_client {
             req.extensions_mut().insert(GrpcMethod::new("store.Api", "ApplyBlock"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn check_notes_consumed(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::CheckNotesConsumedRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::CheckNotesConsumedResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/CheckNotesConsumed",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "CheckNotesConsumed"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn check_nullifiers(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -162,6 +188,88 @@ pub mod api_client {
                 .insert(GrpcMethod::new("store.Api", "CheckNullifiersByPrefix"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn export_account_deltas(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::ExportAccountDeltasRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<
+                tonic::codec::Streaming<
+                    super::super::responses::ExportAccountDeltasResponse,
+                >,
+            >,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/ExportAccountDeltas",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "ExportAccountDeltas"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn filter_known_transactions(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::FilterKnownTransactionsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::FilterKnownTransactionsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/FilterKnownTransactions",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "FilterKnownTransactions"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_account_commitments(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetAccountCommitmentsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountCommitmentsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetAccountCommitments",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetAccountCommitments"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_account_details(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -240,13 +348,13 @@ pub mod api_client {
                 .insert(GrpcMethod::new("store.Api", "GetAccountStateDelta"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_block_by_number(
+        pub async fn get_account_storage_header(
             &mut self,
             request: impl tonic::IntoRequest<
-                super::super::requests::GetBlockByNumberRequest,
+                super::super::requests::GetAccountStorageHeaderRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetBlockByNumberResponse>,
+            tonic::Response<super::super::responses::GetAccountStorageHeaderResponse>,
             tonic::Status,
         > {
             self.inner
@@ -259,20 +367,20 @@ pub mod api_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/store.Api/GetBlockByNumber",
+                "/store.Api/GetAccountStorageHeader",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("store.Api", "GetBlockByNumber"));
+                .insert(GrpcMethod::new("store.Api", "GetAccountStorageHeader"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_block_header_by_number(
+        pub async fn get_account_vault_assets(
             &mut self,
             request: impl tonic::IntoRequest<
-                super::super::requests::GetBlockHeaderByNumberRequest,
+                super::super::requests::GetAccountVaultAssetsRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetBlockHeaderByNumberResponse>,
+            tonic::Response<super::super::responses::GetAccountVaultAssetsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -285,20 +393,20 @@ pub mod api_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/store.Api/GetBlockHeaderByNumber",
+                "/store.Api/GetAccountVaultAssets",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("store.Api", "GetBlockHeaderByNumber"));
+                .insert(GrpcMethod::new("store.Api", "GetAccountVaultAssets"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_block_inputs(
+        pub async fn get_account_witnesses(
             &mut self,
             request: impl tonic::IntoRequest<
-                super::super::requests::GetBlockInputsRequest,
+                super::super::requests::GetAccountWitnessesRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetBlockInputsResponse>,
+            tonic::Response<super::super::responses::GetAccountWitnessesResponse>,
             tonic::Status,
         > {
             self.inner
@@ -310,18 +418,21 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/store.Api/GetBlockInputs");
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetAccountWitnesses",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("store.Api", "GetBlockInputs"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetAccountWitnesses"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_note_authentication_info(
+        pub async fn get_block_by_number(
             &mut self,
             request: impl tonic::IntoRequest<
-                super::super::requests::GetNoteAuthenticationInfoRequest,
+                super::super::requests::GetBlockByNumberRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetNoteAuthenticationInfoResponse>,
+            tonic::Response<super::super::responses::GetBlockByNumberResponse>,
             tonic::Status,
         > {
             self.inner
@@ -334,18 +445,20 @@ pub mod api_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/store.Api/GetNoteAuthenticationInfo",
+                "/store.Api/GetBlockByNumber",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("store.Api", "GetNoteAuthenticationInfo"));
+                .insert(GrpcMethod::new("store.Api", "GetBlockByNumber"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_notes_by_id(
+        pub async fn get_block_note_filters(
             &mut self,
-            request: impl tonic::IntoRequest<super::super::requests::GetNotesByIdRequest>,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetBlockNoteFiltersRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetNotesByIdResponse>,
+            tonic::Response<super::super::responses::GetBlockNoteFiltersResponse>,
             tonic::Status,
         > {
             self.inner
@@ -357,18 +470,21 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/store.Api/GetNotesById");
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetBlockNoteFilters",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("store.Api", "GetNotesById"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetBlockNoteFilters"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_transaction_inputs(
+        pub async fn get_block_header_by_commitment(
             &mut self,
             request: impl tonic::IntoRequest<
-                super::super::requests::GetTransactionInputsRequest,
+                super::super::requests::GetBlockHeaderByCommitmentRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetTransactionInputsResponse>,
+            tonic::Response<super::super::responses::GetBlockHeaderByCommitmentResponse>,
             tonic::Status,
         > {
             self.inner
@@ -381,18 +497,20 @@ pub mod api_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/store.Api/GetTransactionInputs",
+                "/store.Api/GetBlockHeaderByCommitment",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("store.Api", "GetTransactionInputs"));
+                .insert(GrpcMethod::new("store.Api", "GetBlockHeaderByCommitment"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_accounts(
+        pub async fn get_block_header_by_number(
             &mut self,
-            request: impl tonic::IntoRequest<super::super::requests::ListAccountsRequest>,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetBlockHeaderByNumberRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::ListAccountsResponse>,
+            tonic::Response<super::super::responses::GetBlockHeaderByNumberResponse>,
             tonic::Status,
         > {
             self.inner
@@ -404,16 +522,21 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/store.Api/ListAccounts");
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetBlockHeaderByNumber",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("store.Api", "ListAccounts"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetBlockHeaderByNumber"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_notes(
+        pub async fn get_block_headers_range(
             &mut self,
-            request: impl tonic::IntoRequest<super::super::requests::ListNotesRequest>,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetBlockHeadersRangeRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::ListNotesResponse>,
+            tonic::Response<super::super::responses::GetBlockHeadersRangeResponse>,
             tonic::Status,
         > {
             self.inner
@@ -425,18 +548,21 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/store.Api/ListNotes");
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetBlockHeadersRange",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("store.Api", "ListNotes"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetBlockHeadersRange"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_nullifiers(
+        pub async fn get_block_inputs(
             &mut self,
             request: impl tonic::IntoRequest<
-                super::super::requests::ListNullifiersRequest,
+                super::super::requests::GetBlockInputsRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::ListNullifiersResponse>,
+            tonic::Response<super::super::responses::GetBlockInputsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -448,16 +574,18 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/store.Api/ListNullifiers");
+            let path = http::uri::PathAndQuery::from_static("/store.Api/GetBlockInputs");
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("store.Api", "ListNullifiers"));
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "GetBlockInputs"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn sync_notes(
+        pub async fn get_block_production_stats(
             &mut self,
-            request: impl tonic::IntoRequest<super::super::requests::SyncNoteRequest>,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetBlockProductionStatsRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::SyncNoteResponse>,
+            tonic::Response<super::super::responses::GetBlockProductionStatsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -469,16 +597,21 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/store.Api/SyncNotes");
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetBlockProductionStats",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("store.Api", "SyncNotes"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetBlockProductionStats"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn sync_state(
+        pub async fn get_chain_stats(
             &mut self,
-            request: impl tonic::IntoRequest<super::super::requests::SyncStateRequest>,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetChainStatsRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::SyncStateResponse>,
+            tonic::Response<super::super::responses::GetChainStatsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -490,237 +623,1538 @@ pub mod api_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/store.Api/SyncState");
+            let path = http::uri::PathAndQuery::from_static("/store.Api/GetChainStats");
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("store.Api", "SyncState"));
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "GetChainStats"));
             self.inner.unary(req, path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod api_server {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value,
-    )]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with ApiServer.
-    #[async_trait]
-    pub trait Api: std::marker::Send + std::marker::Sync + 'static {
-        async fn apply_block(
-            &self,
-            request: tonic::Request<super::super::requests::ApplyBlockRequest>,
+        pub async fn get_data_availability(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetDataAvailabilityRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::ApplyBlockResponse>,
+            tonic::Response<super::super::responses::GetDataAvailabilityResponse>,
             tonic::Status,
-        >;
-        async fn check_nullifiers(
-            &self,
-            request: tonic::Request<super::super::requests::CheckNullifiersRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetDataAvailability",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetDataAvailability"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_database_pool_stats(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetDatabasePoolStatsRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::CheckNullifiersResponse>,
+            tonic::Response<super::super::responses::GetDatabasePoolStatsResponse>,
             tonic::Status,
-        >;
-        async fn check_nullifiers_by_prefix(
-            &self,
-            request: tonic::Request<
-                super::super::requests::CheckNullifiersByPrefixRequest,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetDatabasePoolStats",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetDatabasePoolStats"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_note_authentication_info(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetNoteAuthenticationInfoRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::CheckNullifiersByPrefixResponse>,
+            tonic::Response<super::super::responses::GetNoteAuthenticationInfoResponse>,
             tonic::Status,
-        >;
-        async fn get_account_details(
-            &self,
-            request: tonic::Request<super::super::requests::GetAccountDetailsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetAccountDetailsResponse>,
-            tonic::Status,
-        >;
-        async fn get_account_proofs(
-            &self,
-            request: tonic::Request<super::super::requests::GetAccountProofsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetAccountProofsResponse>,
-            tonic::Status,
-        >;
-        async fn get_account_state_delta(
-            &self,
-            request: tonic::Request<super::super::requests::GetAccountStateDeltaRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetAccountStateDeltaResponse>,
-            tonic::Status,
-        >;
-        async fn get_block_by_number(
-            &self,
-            request: tonic::Request<super::super::requests::GetBlockByNumberRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetBlockByNumberResponse>,
-            tonic::Status,
-        >;
-        async fn get_block_header_by_number(
-            &self,
-            request: tonic::Request<
-                super::super::requests::GetBlockHeaderByNumberRequest,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetNoteAuthenticationInfo",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetNoteAuthenticationInfo"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_note_tag_statistics(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetNoteTagStatisticsRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetBlockHeaderByNumberResponse>,
+            tonic::Response<super::super::responses::GetNoteTagStatisticsResponse>,
             tonic::Status,
-        >;
-        async fn get_block_inputs(
-            &self,
-            request: tonic::Request<super::super::requests::GetBlockInputsRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetNoteTagStatistics",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetNoteTagStatistics"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_notes_by_id(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::requests::GetNotesByIdRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetBlockInputsResponse>,
+            tonic::Response<super::super::responses::GetNotesByIdResponse>,
             tonic::Status,
-        >;
-        async fn get_note_authentication_info(
-            &self,
-            request: tonic::Request<
-                super::super::requests::GetNoteAuthenticationInfoRequest,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/store.Api/GetNotesById");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "GetNotesById"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_notes_by_recipient(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetNotesByRecipientRequest,
             >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetNoteAuthenticationInfoResponse>,
+            tonic::Response<super::super::responses::GetNotesByRecipientResponse>,
             tonic::Status,
-        >;
-        async fn get_notes_by_id(
-            &self,
-            request: tonic::Request<super::super::requests::GetNotesByIdRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetNotesByRecipient",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetNotesByRecipient"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_supported_block_versions(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetSupportedBlockVersionsRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetNotesByIdResponse>,
+            tonic::Response<super::super::responses::GetSupportedBlockVersionsResponse>,
             tonic::Status,
-        >;
-        async fn get_transaction_inputs(
-            &self,
-            request: tonic::Request<super::super::requests::GetTransactionInputsRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetSupportedBlockVersions",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetSupportedBlockVersions"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_transaction_inputs(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetTransactionInputsRequest,
+            >,
         ) -> std::result::Result<
             tonic::Response<super::super::responses::GetTransactionInputsResponse>,
             tonic::Status,
-        >;
-        async fn list_accounts(
-            &self,
-            request: tonic::Request<super::super::requests::ListAccountsRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetTransactionInputs",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetTransactionInputs"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_accounts(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::requests::ListAccountsRequest>,
         ) -> std::result::Result<
             tonic::Response<super::super::responses::ListAccountsResponse>,
             tonic::Status,
-        >;
-        async fn list_notes(
-            &self,
-            request: tonic::Request<super::super::requests::ListNotesRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/store.Api/ListAccounts");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "ListAccounts"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_notes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::requests::ListNotesRequest>,
         ) -> std::result::Result<
             tonic::Response<super::super::responses::ListNotesResponse>,
             tonic::Status,
-        >;
-        async fn list_nullifiers(
-            &self,
-            request: tonic::Request<super::super::requests::ListNullifiersRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/store.Api/ListNotes");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "ListNotes"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_nullifiers(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::ListNullifiersRequest,
+            >,
         ) -> std::result::Result<
             tonic::Response<super::super::responses::ListNullifiersResponse>,
             tonic::Status,
-        >;
-        async fn sync_notes(
-            &self,
-            request: tonic::Request<super::super::requests::SyncNoteRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::super::responses::SyncNoteResponse>,
-            tonic::Status,
-        >;
-        async fn sync_state(
-            &self,
-            request: tonic::Request<super::super::requests::SyncStateRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/store.Api/ListNullifiers");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "ListNullifiers"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn subscribe_account_updates(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::SubscribeAccountUpdatesRequest,
+            >,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::SyncStateResponse>,
+            tonic::Response<
+                tonic::codec::Streaming<
+                    super::super::responses::SubscribeAccountUpdatesResponse,
+                >,
+            >,
             tonic::Status,
-        >;
-    }
-    #[derive(Debug)]
-    pub struct ApiServer<T> {
-        inner: Arc<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-        max_decoding_message_size: Option<usize>,
-        max_encoding_message_size: Option<usize>,
-    }
-    impl<T> ApiServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-                max_decoding_message_size: None,
-                max_encoding_message_size: None,
-            }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
-        }
-        /// Enable decompressing requests with the given encoding.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.accept_compression_encodings.enable(encoding);
-            self
-        }
-        /// Compress responses with the given encoding, if the client supports it.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.send_compression_encodings.enable(encoding);
-            self
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/SubscribeAccountUpdates",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "SubscribeAccountUpdates"));
+            self.inner.server_streaming(req, path, codec).await
         }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.max_decoding_message_size = Some(limit);
-            self
+        pub async fn sync_account_compact(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::SyncAccountCompactRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncAccountCompactResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/SyncAccountCompact",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "SyncAccountCompact"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.max_encoding_message_size = Some(limit);
-            self
+        pub async fn sync_notes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::requests::SyncNoteRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncNoteResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/store.Api/SyncNotes");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "SyncNotes"));
+            self.inner.unary(req, path, codec).await
         }
-    }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for ApiServer<T>
-    where
-        T: Api,
-        B: Body + std::marker::Send + 'static,
-        B::Error: Into<StdError> + std::marker::Send + 'static,
-    {
-        type Response = http::Response<tonic::body::BoxBody>;
-        type Error = std::convert::Infallible;
-        type Future = BoxFuture<Self::Response, Self::Error>;
-        fn poll_ready(
+        pub async fn sync_state(
             &mut self,
-            _cx: &mut Context<'_>,
-        ) -> Poll<std::result::Result<(), Self::Error>> {
-            Poll::Ready(Ok(()))
+            request: impl tonic::IntoRequest<super::super::requests::SyncStateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncStateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/store.Api/SyncState");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "SyncState"));
+            self.inner.unary(req, path, codec).await
         }
-        fn call(&mut self, req: http::Request<B>) -> Self::Future {
-            match req.uri().path() {
-                "/store.Api/ApplyBlock" => {
+    }
+}
+/// Generated server implementations.
+pub mod api_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ApiServer.
+    #[async_trait]
+    pub trait Api: std::marker::Send + std::marker::Sync + 'static {
+        async fn apply_block(
+            &self,
+            request: tonic::Request<super::super::requests::ApplyBlockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::ApplyBlockResponse>,
+            tonic::Status,
+        >;
+        async fn check_notes_consumed(
+            &self,
+            request: tonic::Request<super::super::requests::CheckNotesConsumedRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::CheckNotesConsumedResponse>,
+            tonic::Status,
+        >;
+        async fn check_nullifiers(
+            &self,
+            request: tonic::Request<super::super::requests::CheckNullifiersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::CheckNullifiersResponse>,
+            tonic::Status,
+        >;
+        async fn check_nullifiers_by_prefix(
+            &self,
+            request: tonic::Request<
+                super::super::requests::CheckNullifiersByPrefixRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::CheckNullifiersByPrefixResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the ExportAccountDeltas method.
+        type ExportAccountDeltasStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<
+                    super::super::responses::ExportAccountDeltasResponse,
+                    tonic::Status,
+                >,
+            >
+            + std::marker::Send
+            + 'static;
+        async fn export_account_deltas(
+            &self,
+            request: tonic::Request<super::super::requests::ExportAccountDeltasRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::ExportAccountDeltasStream>,
+            tonic::Status,
+        >;
+        async fn filter_known_transactions(
+            &self,
+            request: tonic::Request<
+                super::super::requests::FilterKnownTransactionsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::FilterKnownTransactionsResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_commitments(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountCommitmentsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountCommitmentsResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_details(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountDetailsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountDetailsResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_proofs(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountProofsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountProofsResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_state_delta(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountStateDeltaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountStateDeltaResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_storage_header(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetAccountStorageHeaderRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountStorageHeaderResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_vault_assets(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountVaultAssetsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountVaultAssetsResponse>,
+            tonic::Status,
+        >;
+        async fn get_account_witnesses(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountWitnessesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountWitnessesResponse>,
+            tonic::Status,
+        >;
+        async fn get_block_by_number(
+            &self,
+            request: tonic::Request<super::super::requests::GetBlockByNumberRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockByNumberResponse>,
+            tonic::Status,
+        >;
+        async fn get_block_note_filters(
+            &self,
+            request: tonic::Request<super::super::requests::GetBlockNoteFiltersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockNoteFiltersResponse>,
+            tonic::Status,
+        >;
+        async fn get_block_header_by_commitment(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetBlockHeaderByCommitmentRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockHeaderByCommitmentResponse>,
+            tonic::Status,
+        >;
+        async fn get_block_header_by_number(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetBlockHeaderByNumberRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockHeaderByNumberResponse>,
+            tonic::Status,
+        >;
+        async fn get_block_headers_range(
+            &self,
+            request: tonic::Request<super::super::requests::GetBlockHeadersRangeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockHeadersRangeResponse>,
+            tonic::Status,
+        >;
+        async fn get_block_inputs(
+            &self,
+            request: tonic::Request<super::super::requests::GetBlockInputsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockInputsResponse>,
+            tonic::Status,
+        >;
+        async fn get_block_production_stats(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetBlockProductionStatsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetBlockProductionStatsResponse>,
+            tonic::Status,
+        >;
+        async fn get_chain_stats(
+            &self,
+            request: tonic::Request<super::super::requests::GetChainStatsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetChainStatsResponse>,
+            tonic::Status,
+        >;
+        async fn get_data_availability(
+            &self,
+            request: tonic::Request<super::super::requests::GetDataAvailabilityRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetDataAvailabilityResponse>,
+            tonic::Status,
+        >;
+        async fn get_database_pool_stats(
+            &self,
+            request: tonic::Request<super::super::requests::GetDatabasePoolStatsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetDatabasePoolStatsResponse>,
+            tonic::Status,
+        >;
+        async fn get_note_authentication_info(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetNoteAuthenticationInfoRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNoteAuthenticationInfoResponse>,
+            tonic::Status,
+        >;
+        async fn get_note_tag_statistics(
+            &self,
+            request: tonic::Request<super::super::requests::GetNoteTagStatisticsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNoteTagStatisticsResponse>,
+            tonic::Status,
+        >;
+        async fn get_notes_by_id(
+            &self,
+            request: tonic::Request<super::super::requests::GetNotesByIdRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNotesByIdResponse>,
+            tonic::Status,
+        >;
+        async fn get_notes_by_recipient(
+            &self,
+            request: tonic::Request<super::super::requests::GetNotesByRecipientRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNotesByRecipientResponse>,
+            tonic::Status,
+        >;
+        async fn get_supported_block_versions(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetSupportedBlockVersionsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetSupportedBlockVersionsResponse>,
+            tonic::Status,
+        >;
+        async fn get_transaction_inputs(
+            &self,
+            request: tonic::Request<super::super::requests::GetTransactionInputsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetTransactionInputsResponse>,
+            tonic::Status,
+        >;
+        async fn list_accounts(
+            &self,
+            request: tonic::Request<super::super::requests::ListAccountsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::ListAccountsResponse>,
+            tonic::Status,
+        >;
+        async fn list_notes(
+            &self,
+            request: tonic::Request<super::super::requests::ListNotesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::ListNotesResponse>,
+            tonic::Status,
+        >;
+        async fn list_nullifiers(
+            &self,
+            request: tonic::Request<super::super::requests::ListNullifiersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::ListNullifiersResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the SubscribeAccountUpdates method.
+        type SubscribeAccountUpdatesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<
+                    super::super::responses::SubscribeAccountUpdatesResponse,
+                    tonic::Status,
+                >,
+            >
+            + std::marker::Send
+            + 'static;
+        async fn subscribe_account_updates(
+            &self,
+            request: tonic::Request<
+                super::super::requests::SubscribeAccountUpdatesRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<Self::SubscribeAccountUpdatesStream>,
+            tonic::Status,
+        >;
+        async fn sync_account_compact(
+            &self,
+            request: tonic::Request<super::super::requests::SyncAccountCompactRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncAccountCompactResponse>,
+            tonic::Status,
+        >;
+        async fn sync_notes(
+            &self,
+            request: tonic::Request<super::super::requests::SyncNoteRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncNoteResponse>,
+            tonic::Status,
+        >;
+        async fn sync_state(
+            &self,
+            request: tonic::Request<super::super::requests::SyncStateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::SyncStateResponse>,
+            tonic::Status,
+        >;
+    }
+    #[derive(Debug)]
+    pub struct ApiServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> ApiServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ApiServer<T>
+    where
+        T: Api,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/store.Api/ApplyBlock" => {
+                    #[allow(non_camel_case_types)]
+                    struct ApplyBlockSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::ApplyBlockRequest,
+                    > for ApplyBlockSvc<T> {
+                        type Response = super::super::responses::ApplyBlockResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::ApplyBlockRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::apply_block(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ApplyBlockSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/CheckNotesConsumed" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckNotesConsumedSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::CheckNotesConsumedRequest,
+                    > for CheckNotesConsumedSvc<T> {
+                        type Response = super::super::responses::CheckNotesConsumedResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::CheckNotesConsumedRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::check_notes_consumed(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckNotesConsumedSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/CheckNullifiers" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckNullifiersSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::CheckNullifiersRequest,
+                    > for CheckNullifiersSvc<T> {
+                        type Response = super::super::responses::CheckNullifiersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::CheckNullifiersRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::check_nullifiers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckNullifiersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/CheckNullifiersByPrefix" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckNullifiersByPrefixSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::CheckNullifiersByPrefixRequest,
+                    > for CheckNullifiersByPrefixSvc<T> {
+                        type Response = super::super::responses::CheckNullifiersByPrefixResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::CheckNullifiersByPrefixRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::check_nullifiers_by_prefix(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckNullifiersByPrefixSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/ExportAccountDeltas" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExportAccountDeltasSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::ServerStreamingService<
+                        super::super::requests::ExportAccountDeltasRequest,
+                    > for ExportAccountDeltasSvc<T> {
+                        type Response = super::super::responses::ExportAccountDeltasResponse;
+                        type ResponseStream = T::ExportAccountDeltasStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::ExportAccountDeltasRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::export_account_deltas(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ExportAccountDeltasSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/FilterKnownTransactions" => {
+                    #[allow(non_camel_case_types)]
+                    struct FilterKnownTransactionsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::FilterKnownTransactionsRequest,
+                    > for FilterKnownTransactionsSvc<T> {
+                        type Response = super::super::responses::FilterKnownTransactionsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::FilterKnownTransactionsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::filter_known_transactions(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FilterKnownTransactionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountCommitments" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountCommitmentsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountCommitmentsRequest,
+                    > for GetAccountCommitmentsSvc<T> {
+                        type Response = super::super::responses::GetAccountCommitmentsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountCommitmentsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_commitments(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountCommitmentsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountDetails" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountDetailsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountDetailsRequest,
+                    > for GetAccountDetailsSvc<T> {
+                        type Response = super::super::responses::GetAccountDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountDetailsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_details(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountDetailsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountProofs" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountProofsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountProofsRequest,
+                    > for GetAccountProofsSvc<T> {
+                        type Response = super::super::responses::GetAccountProofsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountProofsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_proofs(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountProofsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountStateDelta" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountStateDeltaSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountStateDeltaRequest,
+                    > for GetAccountStateDeltaSvc<T> {
+                        type Response = super::super::responses::GetAccountStateDeltaResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountStateDeltaRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_state_delta(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountStateDeltaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountStorageHeader" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountStorageHeaderSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountStorageHeaderRequest,
+                    > for GetAccountStorageHeaderSvc<T> {
+                        type Response = super::super::responses::GetAccountStorageHeaderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountStorageHeaderRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_storage_header(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountStorageHeaderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountVaultAssets" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountVaultAssetsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountVaultAssetsRequest,
+                    > for GetAccountVaultAssetsSvc<T> {
+                        type Response = super::super::responses::GetAccountVaultAssetsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountVaultAssetsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_vault_assets(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountVaultAssetsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountWitnesses" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountWitnessesSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountWitnessesRequest,
+                    > for GetAccountWitnessesSvc<T> {
+                        type Response = super::super::responses::GetAccountWitnessesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountWitnessesRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_witnesses(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountWitnessesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetBlockByNumber" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetBlockByNumberSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetBlockByNumberRequest,
+                    > for GetBlockByNumberSvc<T> {
+                        type Response = super::super::responses::GetBlockByNumberResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetBlockByNumberRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_block_by_number(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetBlockByNumberSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetBlockNoteFilters" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetBlockNoteFiltersSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetBlockNoteFiltersRequest,
+                    > for GetBlockNoteFiltersSvc<T> {
+                        type Response = super::super::responses::GetBlockNoteFiltersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetBlockNoteFiltersRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_block_note_filters(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetBlockNoteFiltersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetBlockHeaderByCommitment" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetBlockHeaderByCommitmentSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetBlockHeaderByCommitmentRequest,
+                    > for GetBlockHeaderByCommitmentSvc<T> {
+                        type Response = super::super::responses::GetBlockHeaderByCommitmentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetBlockHeaderByCommitmentRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_block_header_by_commitment(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetBlockHeaderByCommitmentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetBlockHeaderByNumber" => {
                     #[allow(non_camel_case_types)]
-                    struct ApplyBlockSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockHeaderByNumberSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::ApplyBlockRequest,
-                    > for ApplyBlockSvc<T> {
-                        type Response = super::super::responses::ApplyBlockResponse;
+                        super::super::requests::GetBlockHeaderByNumberRequest,
+                    > for GetBlockHeaderByNumberSvc<T> {
+                        type Response = super::super::responses::GetBlockHeaderByNumberResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -728,12 +2162,13 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::ApplyBlockRequest,
+                                super::super::requests::GetBlockHeaderByNumberRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::apply_block(&inner, request).await
+                                <T as Api>::get_block_header_by_number(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -744,7 +2179,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ApplyBlockSvc(inner);
+                        let method = GetBlockHeaderByNumberSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -760,15 +2195,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/CheckNullifiers" => {
+                "/store.Api/GetBlockHeadersRange" => {
                     #[allow(non_camel_case_types)]
-                    struct CheckNullifiersSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockHeadersRangeSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::CheckNullifiersRequest,
-                    > for CheckNullifiersSvc<T> {
-                        type Response = super::super::responses::CheckNullifiersResponse;
+                        super::super::requests::GetBlockHeadersRangeRequest,
+                    > for GetBlockHeadersRangeSvc<T> {
+                        type Response = super::super::responses::GetBlockHeadersRangeResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -776,12 +2211,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::CheckNullifiersRequest,
+                                super::super::requests::GetBlockHeadersRangeRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::check_nullifiers(&inner, request).await
+                                <T as Api>::get_block_headers_range(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -792,7 +2227,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CheckNullifiersSvc(inner);
+                        let method = GetBlockHeadersRangeSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -808,15 +2243,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/CheckNullifiersByPrefix" => {
+                "/store.Api/GetBlockInputs" => {
                     #[allow(non_camel_case_types)]
-                    struct CheckNullifiersByPrefixSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockInputsSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::CheckNullifiersByPrefixRequest,
-                    > for CheckNullifiersByPrefixSvc<T> {
-                        type Response = super::super::responses::CheckNullifiersByPrefixResponse;
+                        super::super::requests::GetBlockInputsRequest,
+                    > for GetBlockInputsSvc<T> {
+                        type Response = super::super::responses::GetBlockInputsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -824,13 +2259,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::CheckNullifiersByPrefixRequest,
+                                super::super::requests::GetBlockInputsRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::check_nullifiers_by_prefix(&inner, request)
-                                    .await
+                                <T as Api>::get_block_inputs(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -841,7 +2275,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CheckNullifiersByPrefixSvc(inner);
+                        let method = GetBlockInputsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -857,15 +2291,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetAccountDetails" => {
+                "/store.Api/GetBlockProductionStats" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAccountDetailsSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockProductionStatsSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetAccountDetailsRequest,
-                    > for GetAccountDetailsSvc<T> {
-                        type Response = super::super::responses::GetAccountDetailsResponse;
+                        super::super::requests::GetBlockProductionStatsRequest,
+                    > for GetBlockProductionStatsSvc<T> {
+                        type Response = super::super::responses::GetBlockProductionStatsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -873,12 +2307,13 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetAccountDetailsRequest,
+                                super::super::requests::GetBlockProductionStatsRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_account_details(&inner, request).await
+                                <T as Api>::get_block_production_stats(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -889,7 +2324,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetAccountDetailsSvc(inner);
+                        let method = GetBlockProductionStatsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -905,15 +2340,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetAccountProofs" => {
+                "/store.Api/GetChainStats" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAccountProofsSvc<T: Api>(pub Arc<T>);
+                    struct GetChainStatsSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetAccountProofsRequest,
-                    > for GetAccountProofsSvc<T> {
-                        type Response = super::super::responses::GetAccountProofsResponse;
+                        super::super::requests::GetChainStatsRequest,
+                    > for GetChainStatsSvc<T> {
+                        type Response = super::super::responses::GetChainStatsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -921,12 +2356,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetAccountProofsRequest,
+                                super::super::requests::GetChainStatsRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_account_proofs(&inner, request).await
+                                <T as Api>::get_chain_stats(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -937,7 +2372,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetAccountProofsSvc(inner);
+                        let method = GetChainStatsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -953,15 +2388,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetAccountStateDelta" => {
+                "/store.Api/GetDataAvailability" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAccountStateDeltaSvc<T: Api>(pub Arc<T>);
+                    struct GetDataAvailabilitySvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetAccountStateDeltaRequest,
-                    > for GetAccountStateDeltaSvc<T> {
-                        type Response = super::super::responses::GetAccountStateDeltaResponse;
+                        super::super::requests::GetDataAvailabilityRequest,
+                    > for GetDataAvailabilitySvc<T> {
+                        type Response = super::super::responses::GetDataAvailabilityResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -969,12 +2404,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetAccountStateDeltaRequest,
+                                super::super::requests::GetDataAvailabilityRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_account_state_delta(&inner, request).await
+                                <T as Api>::get_data_availability(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -985,7 +2420,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetAccountStateDeltaSvc(inner);
+                        let method = GetDataAvailabilitySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1001,15 +2436,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetBlockByNumber" => {
+                "/store.Api/GetDatabasePoolStats" => {
                     #[allow(non_camel_case_types)]
-                    struct GetBlockByNumberSvc<T: Api>(pub Arc<T>);
+                    struct GetDatabasePoolStatsSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetBlockByNumberRequest,
-                    > for GetBlockByNumberSvc<T> {
-                        type Response = super::super::responses::GetBlockByNumberResponse;
+                        super::super::requests::GetDatabasePoolStatsRequest,
+                    > for GetDatabasePoolStatsSvc<T> {
+                        type Response = super::super::responses::GetDatabasePoolStatsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1017,12 +2452,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetBlockByNumberRequest,
+                                super::super::requests::GetDatabasePoolStatsRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_block_by_number(&inner, request).await
+                                <T as Api>::get_database_pool_stats(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1033,7 +2468,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetBlockByNumberSvc(inner);
+                        let method = GetDatabasePoolStatsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1049,15 +2484,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetBlockHeaderByNumber" => {
+                "/store.Api/GetNoteAuthenticationInfo" => {
                     #[allow(non_camel_case_types)]
-                    struct GetBlockHeaderByNumberSvc<T: Api>(pub Arc<T>);
+                    struct GetNoteAuthenticationInfoSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetBlockHeaderByNumberRequest,
-                    > for GetBlockHeaderByNumberSvc<T> {
-                        type Response = super::super::responses::GetBlockHeaderByNumberResponse;
+                        super::super::requests::GetNoteAuthenticationInfoRequest,
+                    > for GetNoteAuthenticationInfoSvc<T> {
+                        type Response = super::super::responses::GetNoteAuthenticationInfoResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1065,12 +2500,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetBlockHeaderByNumberRequest,
+                                super::super::requests::GetNoteAuthenticationInfoRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_block_header_by_number(&inner, request)
+                                <T as Api>::get_note_authentication_info(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -1082,7 +2517,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetBlockHeaderByNumberSvc(inner);
+                        let method = GetNoteAuthenticationInfoSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1098,15 +2533,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetBlockInputs" => {
+                "/store.Api/GetNoteTagStatistics" => {
                     #[allow(non_camel_case_types)]
-                    struct GetBlockInputsSvc<T: Api>(pub Arc<T>);
+                    struct GetNoteTagStatisticsSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetBlockInputsRequest,
-                    > for GetBlockInputsSvc<T> {
-                        type Response = super::super::responses::GetBlockInputsResponse;
+                        super::super::requests::GetNoteTagStatisticsRequest,
+                    > for GetNoteTagStatisticsSvc<T> {
+                        type Response = super::super::responses::GetNoteTagStatisticsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1114,12 +2549,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetBlockInputsRequest,
+                                super::super::requests::GetNoteTagStatisticsRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_block_inputs(&inner, request).await
+                                <T as Api>::get_note_tag_statistics(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1130,7 +2565,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetBlockInputsSvc(inner);
+                        let method = GetNoteTagStatisticsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1146,15 +2581,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetNoteAuthenticationInfo" => {
+                "/store.Api/GetNotesById" => {
                     #[allow(non_camel_case_types)]
-                    struct GetNoteAuthenticationInfoSvc<T: Api>(pub Arc<T>);
+                    struct GetNotesByIdSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetNoteAuthenticationInfoRequest,
-                    > for GetNoteAuthenticationInfoSvc<T> {
-                        type Response = super::super::responses::GetNoteAuthenticationInfoResponse;
+                        super::super::requests::GetNotesByIdRequest,
+                    > for GetNotesByIdSvc<T> {
+                        type Response = super::super::responses::GetNotesByIdResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1162,13 +2597,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetNoteAuthenticationInfoRequest,
+                                super::super::requests::GetNotesByIdRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_note_authentication_info(&inner, request)
-                                    .await
+                                <T as Api>::get_notes_by_id(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1179,7 +2613,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetNoteAuthenticationInfoSvc(inner);
+                        let method = GetNotesByIdSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1195,15 +2629,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetNotesById" => {
+                "/store.Api/GetNotesByRecipient" => {
                     #[allow(non_camel_case_types)]
-                    struct GetNotesByIdSvc<T: Api>(pub Arc<T>);
+                    struct GetNotesByRecipientSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetNotesByIdRequest,
-                    > for GetNotesByIdSvc<T> {
-                        type Response = super::super::responses::GetNotesByIdResponse;
+                        super::super::requests::GetNotesByRecipientRequest,
+                    > for GetNotesByRecipientSvc<T> {
+                        type Response = super::super::responses::GetNotesByRecipientResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1211,12 +2645,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetNotesByIdRequest,
+                                super::super::requests::GetNotesByRecipientRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_notes_by_id(&inner, request).await
+                                <T as Api>::get_notes_by_recipient(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1227,7 +2661,56 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetNotesByIdSvc(inner);
+                        let method = GetNotesByRecipientSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetSupportedBlockVersions" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSupportedBlockVersionsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetSupportedBlockVersionsRequest,
+                    > for GetSupportedBlockVersionsSvc<T> {
+                        type Response = super::super::responses::GetSupportedBlockVersionsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetSupportedBlockVersionsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_supported_block_versions(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetSupportedBlockVersionsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1435,6 +2918,103 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
+                "/store.Api/SubscribeAccountUpdates" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubscribeAccountUpdatesSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::ServerStreamingService<
+                        super::super::requests::SubscribeAccountUpdatesRequest,
+                    > for SubscribeAccountUpdatesSvc<T> {
+                        type Response = super::super::responses::SubscribeAccountUpdatesResponse;
+                        type ResponseStream = T::SubscribeAccountUpdatesStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::SubscribeAccountUpdatesRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::subscribe_account_updates(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SubscribeAccountUpdatesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/SyncAccountCompact" => {
+                    #[allow(non_camel_case_types)]
+                    struct SyncAccountCompactSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::SyncAccountCompactRequest,
+                    > for SyncAccountCompactSvc<T> {
+                        type Response = super::super::responses::SyncAccountCompactResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::SyncAccountCompactRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::sync_account_compact(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SyncAccountCompactSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/store.Api/SyncNotes" => {
                     #[allow(non_camel_case_types)]
                     struct SyncNotesSvc<T: Api>(pub Arc<T>);
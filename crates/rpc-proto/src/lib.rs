@@ -6,6 +6,9 @@ extern crate std;
 mod proto_files;
 pub use proto_files::PROTO_FILES;
 
+#[cfg(feature = "std")]
+pub mod schema;
+
 /// Writes the RPC protobuf file into `target_dir`.
 #[cfg(feature = "std")]
 pub fn write_proto(target_dir: &std::path::Path) -> Result<(), std::string::String> {
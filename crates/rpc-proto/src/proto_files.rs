@@ -4,6 +4,7 @@ pub const PROTO_FILES: &[(&str, &str)] = &[
     ("block.proto", include_str!("../proto/block.proto")),
     ("block_producer.proto", include_str!("../proto/block_producer.proto")),
     ("digest.proto", include_str!("../proto/digest.proto")),
+    ("error.proto", include_str!("../proto/error.proto")),
     ("merkle.proto", include_str!("../proto/merkle.proto")),
     ("mmr.proto", include_str!("../proto/mmr.proto")),
     ("note.proto", include_str!("../proto/note.proto")),
@@ -0,0 +1,1038 @@
+//! Structural description of the protobuf schema, used to check the wire compatibility of the node
+//! and its clients.
+//!
+//! A [Schema] only records what matters on the wire: the field numbers, types and labels of the
+//! messages, the values of the enums and the methods of the services. Comments, formatting and the
+//! order of the declarations don't change it, so its [fingerprint](Schema::fingerprint) only
+//! changes when the schema does.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter, Write},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::PROTO_FILES;
+
+/// Types which are not messages or enums declared in the schema.
+const SCALAR_TYPES: &[&str] = &[
+    "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+    "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+// SCHEMA
+// ================================================================================================
+
+/// The wire-relevant structure of a set of protobuf files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    /// Messages, enums and services, by fully qualified name.
+    pub definitions: BTreeMap<String, Definition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Definition {
+    /// Fields of a message, by field number.
+    Message(BTreeMap<u32, Field>),
+    /// Names of the values of an enum, by value.
+    Enum(BTreeMap<i32, String>),
+    /// Methods of a service, by name.
+    Service(BTreeMap<String, Method>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub label: Label,
+    /// Scalar type, `map<K, V>` or fully qualified name of a message or enum.
+    pub ty: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Label {
+    Singular,
+    Optional,
+    Repeated,
+    /// Member of the named `oneof`.
+    Oneof(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Method {
+    /// Fully qualified request type, prefixed with `stream ` for streaming requests.
+    pub request: String,
+    /// Fully qualified response type, prefixed with `stream ` for streaming responses.
+    pub response: String,
+}
+
+impl Schema {
+    /// Returns the schema of the protobuf files embedded in this crate.
+    pub fn embedded() -> Self {
+        Self::parse(PROTO_FILES).expect("Embedded protobuf files should be valid")
+    }
+
+    /// Parses the given `(file name, file contents)` protobuf files.
+    pub fn parse(files: &[(&str, &str)]) -> Result<Self, SchemaError> {
+        let mut declarations = Vec::new();
+        for (file_name, contents) in files {
+            Parser::new(contents)
+                .parse_file(&mut declarations)
+                .map_err(|message| SchemaError { file: file_name.to_string(), message })?;
+        }
+
+        // Type names are resolved once all the definitions are known, as they can be used before
+        // they are declared
+        let names: Vec<String> = declarations.iter().map(|decl| decl.name.clone()).collect();
+        let definitions = declarations
+            .into_iter()
+            .map(|decl| (decl.name, decl.definition.resolve(&decl.scope, &names)))
+            .collect();
+
+        Ok(Self { definitions })
+    }
+
+    /// Parses a schema in the canonical format produced by its [Display] implementation.
+    pub fn from_canonical(canonical: &str) -> Result<Self, SchemaError> {
+        let error = |line: usize, message: &str| SchemaError {
+            file: "canonical schema".to_string(),
+            message: format!("line {}: {message}", line + 1),
+        };
+
+        let mut definitions = BTreeMap::new();
+        let mut current: Option<(String, Definition)> = None;
+        for (line_num, line) in canonical.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            if !line.starts_with(' ') {
+                let definition = match words.as_slice() {
+                    ["message", _] => Definition::Message(BTreeMap::new()),
+                    ["enum", _] => Definition::Enum(BTreeMap::new()),
+                    ["service", _] => Definition::Service(BTreeMap::new()),
+                    _ => return Err(error(line_num, "expected a definition")),
+                };
+                definitions.extend(current.replace((words[1].to_string(), definition)));
+                continue;
+            }
+
+            let Some((_, definition)) = current.as_mut() else {
+                return Err(error(line_num, "member outside of a definition"));
+            };
+            match (definition, words.as_slice()) {
+                (Definition::Message(fields), [number, label, ty, name]) => {
+                    let number = number.parse().map_err(|_| error(line_num, "invalid number"))?;
+                    let label = Label::from_canonical(label)
+                        .ok_or_else(|| error(line_num, "invalid label"))?;
+                    let field = Field {
+                        name: name.to_string(),
+                        label,
+                        ty: ty.to_string(),
+                    };
+                    fields.insert(number, field);
+                },
+                (Definition::Enum(values), [value, name]) => {
+                    let value = value.parse().map_err(|_| error(line_num, "invalid value"))?;
+                    values.insert(value, name.to_string());
+                },
+                (Definition::Service(methods), ["rpc", name, request, response]) => {
+                    let method = Method {
+                        request: request.replace(':', " "),
+                        response: response.replace(':', " "),
+                    };
+                    methods.insert(name.to_string(), method);
+                },
+                _ => return Err(error(line_num, "invalid member")),
+            }
+        }
+        definitions.extend(current);
+
+        Ok(Self { definitions })
+    }
+
+    /// Returns a fingerprint of the schema, which only changes when the schema does.
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.to_string())
+    }
+
+    /// Returns the changes from this schema to `other`.
+    pub fn diff(&self, other: &Schema) -> Vec<Change> {
+        let mut changes = Vec::new();
+
+        for (name, old) in &self.definitions {
+            match other.definitions.get(name) {
+                None => changes.push(Change::removed(name.clone(), old.kind().to_string())),
+                Some(new) => old.diff(name, new, &mut changes),
+            }
+        }
+        for (name, new) in &other.definitions {
+            if !self.definitions.contains_key(name) {
+                changes.push(Change::added(name.clone(), new.kind().to_string()));
+            }
+        }
+
+        changes
+    }
+}
+
+/// Formats the schema in its canonical format, one line per definition and member.
+impl Display for Schema {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (name, definition) in &self.definitions {
+            writeln!(f, "{} {name}", definition.kind())?;
+            match definition {
+                Definition::Message(fields) => {
+                    for (number, field) in fields {
+                        writeln!(f, "    {number} {}", field)?;
+                    }
+                },
+                Definition::Enum(values) => {
+                    for (value, name) in values {
+                        writeln!(f, "    {value} {name}")?;
+                    }
+                },
+                Definition::Service(methods) => {
+                    for (name, method) in methods {
+                        writeln!(
+                            f,
+                            "    rpc {name} {} {}",
+                            method.request.replace(' ', ":"),
+                            method.response.replace(' ', ":")
+                        )?;
+                    }
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Definition {
+    fn kind(&self) -> &'static str {
+        match self {
+            Definition::Message(_) => "message",
+            Definition::Enum(_) => "enum",
+            Definition::Service(_) => "service",
+        }
+    }
+
+    /// Returns the fingerprint of the definition.
+    pub fn fingerprint(&self) -> String {
+        let mut schema = Schema::default();
+        schema.definitions.insert(String::new(), self.clone());
+        schema.fingerprint()
+    }
+
+    fn diff(&self, name: &str, new: &Definition, changes: &mut Vec<Change>) {
+        match (self, new) {
+            (Definition::Message(old), Definition::Message(new)) => {
+                diff_members(name, old, new, changes, |path, old, new, changes| {
+                    if old.label != new.label || old.ty != new.ty {
+                        // Optional and singular fields have the same encoding, but a repeated
+                        // field or a oneof member doesn't decode like any other label
+                        let breaking = old.ty != new.ty
+                            || !matches!(
+                                (&old.label, &new.label),
+                                (
+                                    Label::Singular | Label::Optional,
+                                    Label::Singular | Label::Optional
+                                )
+                            );
+                        changes.push(Change::modified(path, format!("{old} -> {new}"), breaking));
+                    } else if old.name != new.name {
+                        // Fields are identified by their number on the wire
+                        changes.push(Change::modified(path, format!("{old} -> {new}"), false));
+                    }
+                });
+            },
+            (Definition::Enum(old), Definition::Enum(new)) => {
+                diff_members(name, old, new, changes, |path, old, new, changes| {
+                    // Enum values are identified by their number on the wire
+                    if old != new {
+                        changes.push(Change::modified(path, format!("{old} -> {new}"), false));
+                    }
+                });
+            },
+            (Definition::Service(old), Definition::Service(new)) => {
+                diff_members(name, old, new, changes, |path, old, new, changes| {
+                    if old != new {
+                        changes.push(Change::modified(path, format!("{old} -> {new}"), true));
+                    }
+                });
+            },
+            (old, new) => changes.push(Change::modified(
+                name.to_string(),
+                format!("{} -> {}", old.kind(), new.kind()),
+                true,
+            )),
+        }
+    }
+
+    fn resolve(self, scope: &str, names: &[String]) -> Self {
+        match self {
+            Definition::Message(fields) => Definition::Message(
+                fields
+                    .into_iter()
+                    .map(|(number, field)| {
+                        let ty = resolve_type(&field.ty, scope, names);
+                        (number, Field { ty, ..field })
+                    })
+                    .collect(),
+            ),
+            Definition::Enum(values) => Definition::Enum(values),
+            Definition::Service(methods) => Definition::Service(
+                methods
+                    .into_iter()
+                    .map(|(name, method)| {
+                        let method = Method {
+                            request: resolve_type(&method.request, scope, names),
+                            response: resolve_type(&method.response, scope, names),
+                        };
+                        (name, method)
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.label, self.ty, self.name)
+    }
+}
+
+impl Label {
+    fn from_canonical(label: &str) -> Option<Self> {
+        match label {
+            "singular" => Some(Label::Singular),
+            "optional" => Some(Label::Optional),
+            "repeated" => Some(Label::Repeated),
+            _ => label.strip_prefix("oneof:").map(|name| Label::Oneof(name.to_string())),
+        }
+    }
+}
+
+impl Display for Label {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Label::Singular => f.write_str("singular"),
+            Label::Optional => f.write_str("optional"),
+            Label::Repeated => f.write_str("repeated"),
+            Label::Oneof(name) => write!(f, "oneof:{name}"),
+        }
+    }
+}
+
+impl Display for Method {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({}) returns ({})", self.request, self.response)
+    }
+}
+
+/// Error raised when a protobuf file can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    pub file: String,
+    pub message: String,
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file, self.message)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+// CHANGES
+// ================================================================================================
+
+/// A change between two versions of a [Schema].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// Name of the changed definition, followed by the changed member if any.
+    pub path: String,
+    pub details: String,
+    /// Whether peers using the previous schema can fail to decode the messages of the new one, or
+    /// to call its methods.
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl Change {
+    fn added(path: String, details: String) -> Self {
+        Self {
+            kind: ChangeKind::Added,
+            path,
+            details,
+            breaking: false,
+        }
+    }
+
+    fn removed(path: String, details: String) -> Self {
+        Self {
+            kind: ChangeKind::Removed,
+            path,
+            details,
+            breaking: true,
+        }
+    }
+
+    fn modified(path: String, details: String, breaking: bool) -> Self {
+        Self {
+            kind: ChangeKind::Modified,
+            path,
+            details,
+            breaking,
+        }
+    }
+}
+
+/// Formats the change as a line of the compatibility log.
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let breaking = if self.breaking { "breaking" } else { "non-breaking" };
+        let kind = match self.kind {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Modified => "modified",
+        };
+        write!(f, "{breaking} {kind} {} {}", self.path, self.details)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Records the added and removed members of a definition, and the changes of the others.
+fn diff_members<K: Ord + Display, V: Display>(
+    name: &str,
+    old: &BTreeMap<K, V>,
+    new: &BTreeMap<K, V>,
+    changes: &mut Vec<Change>,
+    diff_member: impl Fn(String, &V, &V, &mut Vec<Change>),
+) {
+    for (key, old_member) in old {
+        let path = format!("{name}.{key}");
+        match new.get(key) {
+            None => changes.push(Change::removed(path, old_member.to_string())),
+            Some(new_member) => diff_member(path, old_member, new_member, changes),
+        }
+    }
+    for (key, new_member) in new {
+        if !old.contains_key(key) {
+            changes.push(Change::added(format!("{name}.{key}"), new_member.to_string()));
+        }
+    }
+}
+
+/// Returns the fully qualified name of the type `ty` used in `scope`, following the protobuf
+/// scoping rules: the innermost enclosing scope declaring the type wins.
+fn resolve_type(ty: &str, scope: &str, names: &[String]) -> String {
+    if let Some(inner) = ty.strip_prefix("stream ") {
+        return format!("stream {}", resolve_type(inner, scope, names));
+    }
+    if let Some(types) = ty.strip_prefix("map<").and_then(|ty| ty.strip_suffix('>')) {
+        let (key, value) = types.split_once(',').unwrap_or((types, ""));
+        return format!("map<{key},{}>", resolve_type(value, scope, names));
+    }
+    if let Some(absolute) = ty.strip_prefix('.') {
+        return absolute.to_string();
+    }
+    if SCALAR_TYPES.contains(&ty) {
+        return ty.to_string();
+    }
+
+    let mut scope = scope;
+    loop {
+        let candidate = if scope.is_empty() {
+            ty.to_string()
+        } else {
+            format!("{scope}.{ty}")
+        };
+        if names.contains(&candidate) {
+            return candidate;
+        }
+        if scope.is_empty() {
+            // Unknown types are kept as written, e.g. types of files missing from the schema
+            return ty.to_string();
+        }
+        scope = scope.rsplit_once('.').map_or("", |(parent, _)| parent);
+    }
+}
+
+/// Returns the FNV-1a hash of `text` as an hexadecimal string.
+fn fingerprint(text: &str) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let hash = text
+        .bytes()
+        .fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+
+    let mut fingerprint = String::with_capacity(16);
+    write!(fingerprint, "{hash:016x}").expect("Writing to a string can't fail");
+    fingerprint
+}
+
+// PARSER
+// ================================================================================================
+
+/// A definition before its type names are resolved.
+struct Declaration {
+    name: String,
+    /// Scope in which the type names of the definition are resolved.
+    scope: String,
+    definition: Definition,
+}
+
+/// Minimal parser of the protobuf language, which only extracts the wire-relevant declarations.
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+    package: String,
+}
+
+impl<'a> Parser<'a> {
+    fn new(contents: &'a str) -> Self {
+        Self {
+            tokens: tokenize(contents),
+            position: 0,
+            package: String::new(),
+        }
+    }
+
+    fn parse_file(mut self, declarations: &mut Vec<Declaration>) -> Result<(), String> {
+        while let Some(token) = self.next() {
+            match token {
+                "syntax" | "import" | "option" => self.skip_statement()?,
+                "package" => {
+                    self.package = self.expect_name()?.to_string();
+                    self.expect(";")?;
+                },
+                "message" => self.parse_message(&self.package.clone(), declarations)?,
+                "enum" => self.parse_enum(&self.package.clone(), declarations)?,
+                "service" => self.parse_service(declarations)?,
+                ";" => {},
+                token => return Err(format!("unexpected `{token}`")),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_message(
+        &mut self,
+        scope: &str,
+        declarations: &mut Vec<Declaration>,
+    ) -> Result<(), String> {
+        let name = qualify(scope, self.expect_name()?);
+        self.expect("{")?;
+
+        let mut fields = BTreeMap::new();
+        let mut oneof = None;
+        loop {
+            match self.next().ok_or("unexpected end of file")? {
+                "}" if oneof.is_some() => oneof = None,
+                "}" => break,
+                "message" => self.parse_message(&name, declarations)?,
+                "enum" => self.parse_enum(&name, declarations)?,
+                "oneof" => {
+                    oneof = Some(self.expect_name()?.to_string());
+                    self.expect("{")?;
+                },
+                "option" | "reserved" | "extensions" => self.skip_statement()?,
+                ";" => {},
+                token => {
+                    let (label, ty) = match token {
+                        "optional" => (Label::Optional, self.parse_type()?),
+                        "repeated" => (Label::Repeated, self.parse_type()?),
+                        _ => {
+                            self.position -= 1;
+                            let label = oneof.clone().map_or(Label::Singular, Label::Oneof);
+                            (label, self.parse_type()?)
+                        },
+                    };
+                    let field_name = self.expect_name()?.to_string();
+                    self.expect("=")?;
+                    let number = self.expect_name()?;
+                    let number =
+                        number.parse().map_err(|_| format!("invalid field number `{number}`"))?;
+                    self.skip_statement()?;
+
+                    let field = Field { name: field_name, label, ty };
+                    if fields.insert(number, field).is_some() {
+                        return Err(format!("field number {number} of {name} is used twice"));
+                    }
+                },
+            }
+        }
+
+        declarations.push(Declaration {
+            scope: name.clone(),
+            name,
+            definition: Definition::Message(fields),
+        });
+        Ok(())
+    }
+
+    fn parse_enum(
+        &mut self,
+        scope: &str,
+        declarations: &mut Vec<Declaration>,
+    ) -> Result<(), String> {
+        let name = qualify(scope, self.expect_name()?);
+        self.expect("{")?;
+
+        let mut values = BTreeMap::new();
+        loop {
+            match self.next().ok_or("unexpected end of file")? {
+                "}" => break,
+                "option" | "reserved" => self.skip_statement()?,
+                ";" => {},
+                value_name => {
+                    self.expect("=")?;
+                    let mut value = self.expect_name()?.to_string();
+                    if value == "-" {
+                        value.push_str(self.expect_name()?);
+                    }
+                    let value =
+                        value.parse().map_err(|_| format!("invalid enum value `{value}`"))?;
+                    self.skip_statement()?;
+                    values.insert(value, value_name.to_string());
+                },
+            }
+        }
+
+        declarations.push(Declaration {
+            name,
+            scope: scope.to_string(),
+            definition: Definition::Enum(values),
+        });
+        Ok(())
+    }
+
+    fn parse_service(&mut self, declarations: &mut Vec<Declaration>) -> Result<(), String> {
+        let name = self.expect_name()?;
+        let name = qualify(&self.package, name);
+        self.expect("{")?;
+
+        let mut methods = BTreeMap::new();
+        loop {
+            match self.next().ok_or("unexpected end of file")? {
+                "}" => break,
+                "option" => self.skip_statement()?,
+                ";" => {},
+                "rpc" => {
+                    let method_name = self.expect_name()?.to_string();
+                    let request = self.parse_method_type()?;
+                    self.expect("returns")?;
+                    let response = self.parse_method_type()?;
+                    if self.peek() == Some("{") {
+                        self.skip_block()?;
+                    } else {
+                        self.expect(";")?;
+                    }
+                    methods.insert(method_name, Method { request, response });
+                },
+                token => return Err(format!("unexpected `{token}` in service {name}")),
+            }
+        }
+
+        declarations.push(Declaration {
+            name,
+            scope: self.package.clone(),
+            definition: Definition::Service(methods),
+        });
+        Ok(())
+    }
+
+    fn parse_type(&mut self) -> Result<String, String> {
+        let ty = self.expect_name()?;
+        if ty != "map" {
+            return Ok(ty.to_string());
+        }
+
+        self.expect("<")?;
+        let key = self.expect_name()?;
+        self.expect(",")?;
+        let value = self.expect_name()?;
+        self.expect(">")?;
+        Ok(format!("map<{key},{value}>"))
+    }
+
+    fn parse_method_type(&mut self) -> Result<String, String> {
+        self.expect("(")?;
+        let mut ty = self.expect_name()?.to_string();
+        if ty == "stream" {
+            ty = format!("stream {}", self.expect_name()?);
+        }
+        self.expect(")")?;
+        Ok(ty)
+    }
+
+    /// Skips the tokens up to the end of the current statement, including option blocks.
+    fn skip_statement(&mut self) -> Result<(), String> {
+        loop {
+            match self.next().ok_or("unexpected end of file")? {
+                ";" => return Ok(()),
+                "{" => {
+                    self.position -= 1;
+                    return self.skip_block();
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn skip_block(&mut self) -> Result<(), String> {
+        self.expect("{")?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next().ok_or("unexpected end of file")? {
+                "{" => depth += 1,
+                "}" => depth -= 1,
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.tokens.get(self.position).copied();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected `{expected}`, found `{token}`")),
+            None => Err(format!("expected `{expected}`, found end of file")),
+        }
+    }
+
+    fn expect_name(&mut self) -> Result<&'a str, String> {
+        match self.next() {
+            Some(token) if !is_symbol(token) => Ok(token),
+            Some(token) => Err(format!("expected a name, found `{token}`")),
+            None => Err("expected a name, found end of file".to_string()),
+        }
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+fn is_symbol(token: &str) -> bool {
+    token.len() == 1 && "{}()<>[]=;,-".contains(token)
+}
+
+/// Splits protobuf source into names, string literals and symbols, dropping the comments.
+fn tokenize(source: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_whitespace() {
+            rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len())
+        } else if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            rest.find("*/").map_or(rest.len(), |end| end + 2)
+        } else if c == '"' || c == '\'' {
+            let end = rest[1..].find(c).map_or(rest.len(), |end| end + 2);
+            tokens.push(&rest[..end]);
+            end
+        } else if is_symbol(&rest[..c.len_utf8()]) {
+            tokens.push(&rest[..1]);
+            1
+        } else {
+            let end = rest
+                .find(|c: char| {
+                    c.is_whitespace() || c == '"' || c == '\'' || is_symbol(&c.to_string())
+                })
+                .unwrap_or(rest.len());
+            let end = rest[..end].find("//").or(rest[..end].find("/*")).unwrap_or(end);
+            tokens.push(&rest[..end]);
+            end
+        };
+        rest = &rest[len..];
+    }
+
+    tokens
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::Path, vec};
+
+    use super::*;
+
+    const UPDATE_BASELINE_COMMAND: &str = "UPDATE_PROTO_BASELINE=1 cargo test -p miden-rpc-proto";
+
+    /// Checks that the embedded schema matches the committed baseline, and that the compatibility
+    /// log has an entry for it. Both are updated by running the tests with `UPDATE_PROTO_BASELINE`
+    /// set to `1`.
+    #[test]
+    fn embedded_schema_matches_baseline() {
+        let schema_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("schema");
+        let baseline_path = schema_dir.join("baseline.txt");
+        let log_path = schema_dir.join("compatibility.log");
+
+        let schema = Schema::embedded();
+        let fingerprint = schema.fingerprint();
+        let baseline =
+            Schema::from_canonical(&fs::read_to_string(&baseline_path).unwrap()).unwrap();
+        let changes = baseline.diff(&schema);
+
+        if !changes.is_empty() && env::var("UPDATE_PROTO_BASELINE").is_ok_and(|var| var == "1") {
+            let mut log = fs::read_to_string(&log_path).unwrap();
+            write!(log, "\nversion {fingerprint}\n").unwrap();
+            for change in &changes {
+                writeln!(log, "    {change}").unwrap();
+            }
+            fs::write(&log_path, log).unwrap();
+
+            let baseline = format!(
+                "# Canonical protobuf schema, generated by `{UPDATE_BASELINE_COMMAND}`.\n\
+                 # fingerprint: {fingerprint}\n{schema}"
+            );
+            fs::write(&baseline_path, baseline).unwrap();
+            return;
+        }
+
+        let changes: Vec<String> = changes.iter().map(|change| format!("    {change}")).collect();
+        assert!(
+            changes.is_empty(),
+            "The protobuf schema differs from the baseline:\n{}\nRun `{UPDATE_BASELINE_COMMAND}` \
+             to update the baseline and the compatibility log.",
+            changes.join("\n")
+        );
+
+        let log = fs::read_to_string(&log_path).unwrap();
+        let last_version = log.lines().filter_map(|line| line.strip_prefix("version ")).next_back();
+        assert_eq!(
+            last_version,
+            Some(fingerprint.as_str()),
+            "The compatibility log has no entry for the current schema"
+        );
+    }
+
+    fn schema(source: &str) -> Schema {
+        Schema::parse(&[("test.proto", source)]).unwrap()
+    }
+
+    fn changes(old: &str, new: &str) -> Vec<String> {
+        schema(old).diff(&schema(new)).iter().map(ToString::to_string).collect()
+    }
+
+    const BASE: &str = "
+        syntax = \"proto3\";
+        package test;
+
+        import \"digest.proto\";
+
+        // A message.
+        message Request {
+            fixed32 block_num = 1;
+            optional bool include_proof = 2;
+            repeated digest.Digest ids = 3;
+            oneof data {
+                bytes raw = 4;
+                Note note = 5;
+            }
+        }
+
+        message Note { uint32 tag = 1; }
+
+        enum Kind {
+            UNKNOWN = 0;
+            PUBLIC = 1;
+        }
+
+        service Api {
+            rpc Get(Request) returns (Note) {}
+        }
+    ";
+
+    #[test]
+    fn schema_ignores_comments_and_formatting() {
+        let reformatted = "
+            syntax = 'proto3'; package test; import 'digest.proto';
+            service Api { rpc Get ( Request ) returns ( Note ); }
+            /* Declarations can be reordered */
+            enum Kind { PUBLIC = 1; UNKNOWN = 0; }
+            message Note {
+                uint32 tag = 1; // The tag
+            }
+            message Request {
+                oneof data { Note note = 5; bytes raw = 4; }
+                repeated digest.Digest ids = 3;
+                optional bool include_proof = 2;
+                fixed32 block_num = 1;
+            }
+        ";
+
+        assert_eq!(schema(BASE), schema(reformatted));
+        assert_eq!(schema(BASE).fingerprint(), schema(reformatted).fingerprint());
+        assert_ne!(schema(BASE).fingerprint(), Schema::default().fingerprint());
+    }
+
+    #[test]
+    fn canonical_format_roundtrips() {
+        let schema = schema(BASE);
+        let Definition::Message(fields) = &schema.definitions["test.Request"] else {
+            panic!("Request is a message");
+        };
+        assert_eq!(fields[&5].ty, "test.Note");
+        assert_eq!(fields[&5].label, Label::Oneof("data".to_string()));
+
+        assert_eq!(Schema::from_canonical(&schema.to_string()).unwrap(), schema);
+    }
+
+    #[test]
+    fn additions_are_not_breaking() {
+        let new = BASE
+            .replace("uint32 tag = 1;", "uint32 tag = 1; optional string memo = 2;")
+            .replace("PUBLIC = 1;", "PUBLIC = 1; PRIVATE = 2;")
+            .replace("rpc Get", "rpc Put(Note) returns (Note); rpc Get")
+            .replace("message Note", "message Empty {} message Note");
+
+        assert_eq!(
+            changes(BASE, &new),
+            vec![
+                "non-breaking added test.Api.Put (test.Note) returns (test.Note)",
+                "non-breaking added test.Kind.2 PRIVATE",
+                "non-breaking added test.Note.2 optional string memo",
+                "non-breaking added test.Empty message",
+            ]
+        );
+    }
+
+    #[test]
+    fn removals_are_breaking() {
+        let new = BASE
+            .replace("optional bool include_proof = 2;", "")
+            .replace("PUBLIC = 1;", "")
+            .replace("rpc Get(Request) returns (Note) {}", "");
+
+        assert_eq!(
+            changes(BASE, &new),
+            vec![
+                "breaking removed test.Api.Get (test.Request) returns (test.Note)",
+                "breaking removed test.Kind.1 PUBLIC",
+                "breaking removed test.Request.2 optional bool include_proof",
+            ]
+        );
+        assert_eq!(
+            changes(BASE, &BASE.replace("enum Kind", "enum Other")),
+            vec!["breaking removed test.Kind enum", "non-breaking added test.Other enum",]
+        );
+    }
+
+    #[test]
+    fn renames_are_not_breaking() {
+        let new = BASE.replace("block_num", "block_number").replace("PUBLIC", "PUB");
+
+        assert_eq!(
+            changes(BASE, &new),
+            vec![
+                "non-breaking modified test.Kind.1 PUBLIC -> PUB",
+                "non-breaking modified test.Request.1 singular fixed32 block_num -> singular fixed32 block_number",
+            ]
+        );
+    }
+
+    #[test]
+    fn encoding_changes_are_breaking() {
+        let cases = [
+            // Type changes
+            ("fixed32 block_num = 1;", "uint32 block_num = 1;", true),
+            ("uint32 tag = 1;", "Request tag = 1;", true),
+            // Label changes
+            ("fixed32 block_num = 1;", "optional fixed32 block_num = 1;", false),
+            ("optional bool include_proof = 2;", "bool include_proof = 2;", false),
+            ("fixed32 block_num = 1;", "repeated fixed32 block_num = 1;", true),
+            ("repeated digest.Digest ids = 3;", "digest.Digest ids = 3;", true),
+            ("Note note = 5;", "} Note note = 5; oneof other {", true),
+            // Method changes
+            ("returns (Note)", "returns (Request)", true),
+            ("returns (Note)", "returns (stream Note)", true),
+        ];
+
+        for (old, new, breaking) in cases {
+            let changes = schema(BASE).diff(&schema(&BASE.replace(old, new)));
+            assert_eq!(changes.len(), 1, "{old} -> {new}: {changes:?}");
+            assert_eq!(changes[0].kind, ChangeKind::Modified, "{old} -> {new}");
+            assert_eq!(changes[0].breaking, breaking, "{old} -> {new}");
+        }
+
+        let new = BASE.replace("message Note { uint32 tag = 1; }", "enum Note { TAG = 1; }");
+        assert_eq!(changes(BASE, &new), vec!["breaking modified test.Note message -> enum"]);
+    }
+
+    #[test]
+    fn nested_types_are_resolved_in_their_scope() {
+        let schema = schema(
+            "
+            package test;
+            message Outer {
+                message Inner { Kind kind = 1; }
+                enum Kind { A = 0; }
+                Inner inner = 1;
+                map<string, Inner> inners = 2;
+                .test.Other other = 3;
+            }
+            message Other { Outer.Inner inner = 1; }
+            ",
+        );
+
+        let field_types = |name: &str| match &schema.definitions[name] {
+            Definition::Message(fields) => {
+                fields.values().map(|field| field.ty.clone()).collect::<Vec<_>>()
+            },
+            _ => panic!("{name} is a message"),
+        };
+        assert_eq!(
+            field_types("test.Outer"),
+            ["test.Outer.Inner", "map<string,test.Outer.Inner>", "test.Other"]
+        );
+        assert_eq!(field_types("test.Outer.Inner"), ["test.Outer.Kind"]);
+        assert_eq!(field_types("test.Other"), ["test.Outer.Inner"]);
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        let err = Schema::parse(&[("bad.proto", "message Foo { uint32 a = 1; uint32 b = 1; }")])
+            .unwrap_err();
+        assert_eq!(err.file, "bad.proto");
+
+        assert!(Schema::parse(&[("bad.proto", "message Foo { uint32 a = 1;")]).is_err());
+        assert!(Schema::parse(&[("bad.proto", "message { }")]).is_err());
+    }
+}
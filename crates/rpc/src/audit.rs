@@ -0,0 +1,303 @@
+//! Audit log of the transactions submitted to the RPC.
+//!
+//! Operators investigating spam or disputes need a durable record of the submitted transactions,
+//! independent of the retention of their tracing backend. When enabled, the RPC appends one
+//! [AuditRecord] per submission to the `audit.log` file of the configured directory, as a line of
+//! JSON. Once the file exceeds the maximum size, it is renamed to `audit.log.1`, the previously
+//! rotated files are shifted to `audit.log.2`, `audit.log.3`, etc., and the files beyond the
+//! maximum number of rotated files are deleted.
+//!
+//! The records are read back with [find_records], e.g. by the `rpc audit-log` command of the node.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use miden_objects::transaction::TransactionId;
+use serde::{Deserialize, Serialize};
+use tonic::Status;
+use tracing::warn;
+
+use crate::{config::AuditConfig, COMPONENT};
+
+/// Name of the file the records are appended to.
+const AUDIT_FILE: &str = "audit.log";
+
+// AUDIT RECORD
+// ================================================================================================
+
+/// Outcome of the submission of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Time of the submission, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// ID of the transaction as a hex string, unset if the transaction couldn't be deserialized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    /// Address of the peer which submitted the transaction, if known. Behind a reverse proxy, this
+    /// is the address of the proxy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer: Option<SocketAddr>,
+    /// Whether the transaction was accepted by the block producer.
+    pub accepted: bool,
+    /// gRPC code of the rejection, unset for accepted transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Message of the rejection, unset for accepted transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl AuditRecord {
+    /// Returns the record of a submission by `peer` which resulted in `result`.
+    pub fn new<T>(
+        transaction_id: Option<TransactionId>,
+        peer: Option<SocketAddr>,
+        result: &Result<T, Status>,
+    ) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+        let (code, message) = match result {
+            Ok(_) => (None, None),
+            Err(status) => {
+                (Some(format!("{:?}", status.code())), Some(status.message().to_string()))
+            },
+        };
+
+        Self {
+            timestamp_ms,
+            transaction_id: transaction_id.map(|tx_id| tx_id.to_hex()),
+            peer,
+            accepted: result.is_ok(),
+            code,
+            message,
+        }
+    }
+}
+
+// AUDIT LOG
+// ================================================================================================
+
+/// Rotating files the [AuditRecord]s are appended to, see the [module docs](self).
+///
+/// Records are written synchronously by the request handlers, so that they are on disk once the
+/// response is sent. Appending a line is short compared to the verification of the transaction.
+pub struct AuditLog {
+    config: AuditConfig,
+    current: Mutex<CurrentFile>,
+}
+
+/// The file records are currently appended to.
+struct CurrentFile {
+    file: File,
+    size: u64,
+}
+
+impl AuditLog {
+    /// Opens the audit log in the configured directory, creating the directory if needed.
+    pub fn open(config: AuditConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.directory)?;
+        let current = CurrentFile::open(&config.directory)?;
+
+        Ok(Self { config, current: Mutex::new(current) })
+    }
+
+    /// Appends the record to the log.
+    ///
+    /// Failures are logged rather than returned, so that they don't fail the submission.
+    pub fn record(&self, record: &AuditRecord) {
+        if let Err(err) = self.append(record) {
+            warn!(
+                target: COMPONENT,
+                %err,
+                transaction_id = ?record.transaction_id,
+                "Failed to write to the audit log"
+            );
+        }
+    }
+
+    fn append(&self, record: &AuditRecord) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut current = self.current.lock().expect("audit log lock should not be poisoned");
+        if current.size > 0 && current.size + line.len() as u64 > self.config.max_file_size {
+            *current = self.rotate()?;
+        }
+
+        current.file.write_all(&line)?;
+        current.size += line.len() as u64;
+
+        Ok(())
+    }
+
+    /// Shifts the rotated files, moves the current file to `audit.log.1` and returns a new current
+    /// file.
+    fn rotate(&self) -> io::Result<CurrentFile> {
+        let directory = &self.config.directory;
+        let max_rotated_files = self.config.max_rotated_files;
+
+        remove_if_exists(&rotated_path(directory, max_rotated_files.max(1)))?;
+        for index in (1..max_rotated_files).rev() {
+            let path = rotated_path(directory, index);
+            if path.exists() {
+                fs::rename(path, rotated_path(directory, index + 1))?;
+            }
+        }
+
+        let current_path = directory.join(AUDIT_FILE);
+        if max_rotated_files == 0 {
+            remove_if_exists(&current_path)?;
+        } else {
+            fs::rename(current_path, rotated_path(directory, 1))?;
+        }
+
+        CurrentFile::open(directory)
+    }
+}
+
+impl CurrentFile {
+    fn open(directory: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(directory.join(AUDIT_FILE))?;
+        let size = file.metadata()?.len();
+
+        Ok(Self { file, size })
+    }
+}
+
+// AUDIT LOG QUERIES
+// ================================================================================================
+
+/// Returns the records of the audit log in `directory`, from the oldest to the newest, optionally
+/// only those of the transaction with the given hex ID.
+///
+/// Lines which can't be parsed, e.g. a line cut short by a crash, are skipped.
+pub fn find_records(
+    directory: &Path,
+    transaction_id: Option<&str>,
+) -> io::Result<Vec<AuditRecord>> {
+    let mut rotated_files = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let name = entry?.file_name();
+        let index = name.to_str().and_then(|name| {
+            name.strip_prefix(AUDIT_FILE)?.strip_prefix('.')?.parse::<usize>().ok()
+        });
+        if let Some(index) = index {
+            rotated_files.push(index);
+        }
+    }
+    rotated_files.sort_unstable();
+
+    let files = rotated_files
+        .into_iter()
+        .rev()
+        .map(|index| rotated_path(directory, index))
+        .chain([directory.join(AUDIT_FILE)]);
+
+    let mut records = Vec::new();
+    for path in files {
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        for line in BufReader::new(file).lines() {
+            let Ok(record) = serde_json::from_str::<AuditRecord>(&line?) else {
+                continue;
+            };
+            if transaction_id.is_none() || record.transaction_id.as_deref() == transaction_id {
+                records.push(record);
+            }
+        }
+    }
+
+    Ok(records)
+}
+
+// HELPERS
+// ================================================================================================
+
+fn rotated_path(directory: &Path, index: usize) -> PathBuf {
+    directory.join(format!("{AUDIT_FILE}.{index}"))
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(transaction_id: &str, accepted: bool) -> AuditRecord {
+        AuditRecord {
+            timestamp_ms: 1,
+            transaction_id: Some(transaction_id.to_string()),
+            peer: Some("127.0.0.1:1234".parse().unwrap()),
+            accepted,
+            code: (!accepted).then(|| "InvalidArgument".to_string()),
+            message: (!accepted).then(|| "Invalid proof".to_string()),
+        }
+    }
+
+    #[test]
+    fn records_are_found_by_transaction_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::open(AuditConfig::new(dir.path())).unwrap();
+
+        log.record(&record("0x01", false));
+        log.record(&record("0x02", true));
+        log.record(&record("0x01", true));
+
+        let records = find_records(dir.path(), Some("0x01")).unwrap();
+        assert_eq!(records, [record("0x01", false), record("0x01", true)]);
+        assert_eq!(find_records(dir.path(), None).unwrap().len(), 3);
+
+        // Records of previous runs are kept
+        drop(log);
+        let log = AuditLog::open(AuditConfig::new(dir.path())).unwrap();
+        log.record(&record("0x01", true));
+        assert_eq!(find_records(dir.path(), Some("0x01")).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn files_are_rotated_and_the_oldest_deleted() {
+        let dir = tempfile::tempdir().unwrap();
+        let line_len = serde_json::to_vec(&record("0x00", true)).unwrap().len() as u64 + 1;
+        let config = AuditConfig {
+            // Two records per file
+            max_file_size: 2 * line_len,
+            max_rotated_files: 2,
+            ..AuditConfig::new(dir.path())
+        };
+        let log = AuditLog::open(config).unwrap();
+
+        for index in 0..7 {
+            log.record(&record(&format!("0x{index:02}"), true));
+        }
+
+        assert!(dir.path().join("audit.log.2").exists());
+        assert!(!dir.path().join("audit.log.3").exists());
+
+        // The first file was deleted, the records of the others are in order
+        let transaction_ids: Vec<_> = find_records(dir.path(), None)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.transaction_id.unwrap())
+            .collect();
+        assert_eq!(transaction_ids, ["0x02", "0x03", "0x04", "0x05", "0x06"]);
+    }
+}
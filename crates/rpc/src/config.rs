@@ -1,7 +1,15 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    path::PathBuf,
+    time::Duration,
+};
 
-use miden_node_utils::config::{
-    Endpoint, DEFAULT_BLOCK_PRODUCER_PORT, DEFAULT_NODE_RPC_PORT, DEFAULT_STORE_PORT,
+use miden_node_utils::{
+    config::{
+        Endpoint, CONFIG_VERSION, DEFAULT_BLOCK_PRODUCER_PORT, DEFAULT_NODE_RPC_PORT,
+        DEFAULT_STORE_PORT,
+    },
+    message_limits::MessageLimits,
 };
 use serde::{Deserialize, Serialize};
 
@@ -11,11 +19,43 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct RpcConfig {
+    /// Version of the layout of a standalone RPC configuration file, see [CONFIG_VERSION]. Within
+    /// the node configuration file, the version of the node configuration applies.
+    #[serde(default, skip_serializing)]
+    pub config_version: u32,
     pub endpoint: Endpoint,
     /// Store gRPC endpoint in the format `http://<host>[:<port>]`.
     pub store_url: String,
     /// Block producer gRPC endpoint in the format `http://<host>[:<port>]`.
     pub block_producer_url: String,
+    /// Whether to return the messages and sources of internal errors to clients.
+    ///
+    /// They can contain file paths and other details of the node's environment, hence this should
+    /// only be enabled for development.
+    #[serde(default)]
+    pub expose_internal_errors: bool,
+    /// Transport settings of the RPC listener, for operators exposing it without a proxy.
+    #[serde(default)]
+    pub transport: RpcTransportConfig,
+    /// When the node is reported to the clients as syncing, see [FreshnessConfig].
+    #[serde(default)]
+    pub freshness: FreshnessConfig,
+    /// In-memory cache of the immutable responses of the store, see [ResponseCacheConfig].
+    #[serde(default)]
+    pub response_cache: ResponseCacheConfig,
+    /// Size limits of the gRPC messages of the RPC, to the clients and to the other components,
+    /// and of the notes of the submitted transactions, see [MessageLimits].
+    #[serde(default)]
+    pub message_limits: MessageLimits,
+    /// Audit log of the submitted transactions, disabled if unset, see [AuditConfig].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit: Option<AuditConfig>,
+    /// Expected commitment of the genesis block of the store, as a hex string.
+    ///
+    /// The RPC refuses to start if the genesis block of its store differs, e.g. if a testnet RPC
+    /// is pointed at a devnet store. Not checked if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis_commitment: Option<String>,
 }
 
 impl RpcConfig {
@@ -27,8 +67,8 @@ impl RpcConfig {
 impl Display for RpcConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "{{ endpoint: \"{}\", store_url: \"{}\", block_producer_url: \"{}\" }}",
-            self.endpoint, self.store_url, self.block_producer_url
+            "{{ endpoint: \"{}\", store_url: \"{}\", block_producer_url: \"{}\", expose_internal_errors: {}, transport: {:?}, freshness: {:?}, response_cache: {:?}, message_limits: {:?}, audit: {:?}, genesis_commitment: {:?} }}",
+            self.endpoint, self.store_url, self.block_producer_url, self.expose_internal_errors, self.transport, self.freshness, self.response_cache, self.message_limits, self.audit, self.genesis_commitment
         ))
     }
 }
@@ -36,12 +76,200 @@ impl Display for RpcConfig {
 impl Default for RpcConfig {
     fn default() -> Self {
         Self {
+            config_version: CONFIG_VERSION,
             endpoint: Endpoint {
                 host: "0.0.0.0".to_string(),
                 port: DEFAULT_NODE_RPC_PORT,
             },
             store_url: Endpoint::localhost(DEFAULT_STORE_PORT).to_string(),
             block_producer_url: Endpoint::localhost(DEFAULT_BLOCK_PRODUCER_PORT).to_string(),
+            expose_internal_errors: false,
+            transport: RpcTransportConfig::default(),
+            freshness: FreshnessConfig::default(),
+            response_cache: ResponseCacheConfig::default(),
+            message_limits: MessageLimits::default(),
+            audit: None,
+            genesis_commitment: None,
+        }
+    }
+}
+
+// Transport config
+// ================================================================================================
+
+/// Transport settings of the RPC listener.
+///
+/// All the settings are optional, and the defaults keep the behavior of tonic: plaintext
+/// connections, no HTTP/2 keepalive pings, no limit of concurrent streams, and `grpc-web` requests
+/// accepted from any origin.
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcTransportConfig {
+    /// Terminates TLS on the RPC listener with the given certificate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    /// Interval in milliseconds of the HTTP/2 keepalive pings sent on idle connections, e.g. to
+    /// detect mobile clients which silently dropped their connection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http2_keepalive_interval_ms: Option<u64>,
+    /// Duration in milliseconds after which a connection is closed if a keepalive ping isn't
+    /// acknowledged. Only applies if `http2_keepalive_interval_ms` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http2_keepalive_timeout_ms: Option<u64>,
+    /// Maximum number of concurrent streams per connection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_streams: Option<u32>,
+    /// Origins allowed to send `grpc-web` requests from a browser, e.g. `https://wallet.example`.
+    /// Requests from other origins are rejected. All origins are allowed if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors_allowed_origins: Option<Vec<String>>,
+}
+
+impl RpcTransportConfig {
+    pub fn http2_keepalive_interval(&self) -> Option<Duration> {
+        self.http2_keepalive_interval_ms.map(Duration::from_millis)
+    }
+
+    pub fn http2_keepalive_timeout(&self) -> Option<Duration> {
+        self.http2_keepalive_timeout_ms.map(Duration::from_millis)
+    }
+}
+
+/// Certificate of the RPC, reloaded when its files change.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// PEM file of the certificate chain, starting with the certificate of the RPC.
+    pub cert_path: PathBuf,
+    /// PEM file of the private key of the certificate.
+    pub key_path: PathBuf,
+    /// Whether to check the files for changes and serve new connections with the rotated
+    /// certificate, without restarting the RPC.
+    #[serde(default)]
+    pub reload: bool,
+}
+
+// Freshness config
+// ================================================================================================
+
+/// Default expected interval in milliseconds between two blocks, matching the block producer's.
+pub const DEFAULT_EXPECTED_BLOCK_INTERVAL_MS: u64 = 10_000;
+
+/// Default number of expected block intervals without a new block after which the node is
+/// reported as syncing.
+pub const DEFAULT_STALE_BLOCK_INTERVALS: u32 = 6;
+
+/// When the node is reported to the clients as syncing, i.e. behind the chain.
+///
+/// The node is syncing if its latest block was applied more than `stale_block_intervals` times
+/// `expected_block_interval_ms` ago, or if its store is syncing blocks. Chains only holding their
+/// genesis block are never considered stale.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FreshnessConfig {
+    /// Expected interval in milliseconds between two blocks.
+    #[serde(default = "default_expected_block_interval_ms")]
+    pub expected_block_interval_ms: u64,
+    /// Number of expected block intervals without a new block after which the node is reported
+    /// as syncing.
+    #[serde(default = "default_stale_block_intervals")]
+    pub stale_block_intervals: u32,
+}
+
+impl FreshnessConfig {
+    /// Returns the time without a new block after which the node is reported as syncing.
+    pub fn max_staleness(&self) -> Duration {
+        Duration::from_millis(self.expected_block_interval_ms) * self.stale_block_intervals
+    }
+}
+
+impl Default for FreshnessConfig {
+    fn default() -> Self {
+        Self {
+            expected_block_interval_ms: DEFAULT_EXPECTED_BLOCK_INTERVAL_MS,
+            stale_block_intervals: DEFAULT_STALE_BLOCK_INTERVALS,
+        }
+    }
+}
+
+fn default_expected_block_interval_ms() -> u64 {
+    DEFAULT_EXPECTED_BLOCK_INTERVAL_MS
+}
+
+fn default_stale_block_intervals() -> u32 {
+    DEFAULT_STALE_BLOCK_INTERVALS
+}
+
+// Response cache config
+// ================================================================================================
+
+/// Default maximum size in bytes of the cached responses.
+pub const DEFAULT_RESPONSE_CACHE_MAX_SIZE: usize = 16 * 1024 * 1024;
+
+/// In-memory cache of the store's responses which can't change once returned: the block headers
+/// requested by number without MMR proof, and the notes requested by ID.
+///
+/// Absorbs the traffic of explorers requesting the same blocks and notes over and over. The least
+/// recently used responses are evicted once their encoded size exceeds `max_size`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseCacheConfig {
+    /// Maximum size in bytes of the cached responses, zero disables the cache.
+    #[serde(default = "default_response_cache_max_size")]
+    pub max_size: usize,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_size: DEFAULT_RESPONSE_CACHE_MAX_SIZE,
+        }
+    }
+}
+
+fn default_response_cache_max_size() -> usize {
+    DEFAULT_RESPONSE_CACHE_MAX_SIZE
+}
+
+// Audit config
+// ================================================================================================
+
+/// Default size in bytes after which the current audit log file is rotated.
+pub const DEFAULT_AUDIT_MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Default number of rotated audit log files kept.
+pub const DEFAULT_AUDIT_MAX_ROTATED_FILES: usize = 10;
+
+/// Audit log recording the ID, peer address, time and acceptance of every submitted transaction,
+/// see [AuditLog](crate::audit::AuditLog).
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditConfig {
+    /// Directory of the audit log files, created if missing.
+    pub directory: PathBuf,
+    /// Size in bytes after which the current file is rotated.
+    #[serde(default = "default_audit_max_file_size")]
+    pub max_file_size: u64,
+    /// Number of rotated files kept, the oldest ones are deleted.
+    #[serde(default = "default_audit_max_rotated_files")]
+    pub max_rotated_files: usize,
+}
+
+impl AuditConfig {
+    /// Returns the configuration of an audit log in `directory`, with the default rotation.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            max_file_size: DEFAULT_AUDIT_MAX_FILE_SIZE,
+            max_rotated_files: DEFAULT_AUDIT_MAX_ROTATED_FILES,
         }
     }
 }
+
+fn default_audit_max_file_size() -> u64 {
+    DEFAULT_AUDIT_MAX_FILE_SIZE
+}
+
+fn default_audit_max_rotated_files() -> usize {
+    DEFAULT_AUDIT_MAX_ROTATED_FILES
+}
@@ -1,63 +1,288 @@
+use std::{net::SocketAddr, pin::Pin, time::Duration};
+
+use miden_node_block_producer::COMPONENT as BLOCK_PRODUCER_COMPONENT;
 use miden_node_proto::{
+    domain::anchor::Anchored,
+    errors::ErrorChainHelper,
     generated::{
         block_producer::api_client as block_producer_client,
+        error::{ErrorDetails, ErrorLayer},
         requests::{
-            CheckNullifiersByPrefixRequest, CheckNullifiersRequest, GetAccountDetailsRequest,
-            GetAccountProofsRequest, GetAccountStateDeltaRequest, GetBlockByNumberRequest,
-            GetBlockHeaderByNumberRequest, GetNotesByIdRequest, SubmitProvenTransactionRequest,
-            SyncNoteRequest, SyncStateRequest,
+            CheckNotesConsumedRequest, CheckNullifiersByPrefixRequest, CheckNullifiersRequest,
+            GetAccountDetailsRequest, GetAccountExpectedStateRequest, GetAccountProofsRequest,
+            GetAccountStateDeltaRequest, GetAccountStorageHeaderRequest,
+            GetAccountVaultAssetsRequest, GetAccountWitnessesRequest, GetBlockByNumberRequest,
+            GetBlockHeaderByCommitmentRequest, GetBlockHeaderByNumberRequest,
+            GetBlockHeadersRangeRequest, GetBlockNoteFiltersRequest, GetDataAvailabilityRequest,
+            GetNoteTagStatisticsRequest, GetNotesByIdRequest, GetNotesByRecipientRequest,
+            SubmitProvenTransactionRequest, SyncAccountCompactRequest, SyncNoteRequest,
+            SyncStateRequest,
         },
         responses::{
-            CheckNullifiersByPrefixResponse, CheckNullifiersResponse, GetAccountDetailsResponse,
-            GetAccountProofsResponse, GetAccountStateDeltaResponse, GetBlockByNumberResponse,
-            GetBlockHeaderByNumberResponse, GetNotesByIdResponse, SubmitProvenTransactionResponse,
-            SyncNoteResponse, SyncStateResponse,
+            CheckNotesConsumedResponse, CheckNullifiersByPrefixResponse, CheckNullifiersResponse,
+            GetAccountDetailsResponse, GetAccountExpectedStateResponse, GetAccountProofsResponse,
+            GetAccountStateDeltaResponse, GetAccountStorageHeaderResponse,
+            GetAccountVaultAssetsResponse, GetAccountWitnessesResponse, GetBlockByNumberResponse,
+            GetBlockHeaderByCommitmentResponse, GetBlockHeaderByNumberResponse,
+            GetBlockHeadersRangeResponse, GetBlockNoteFiltersResponse, GetDataAvailabilityResponse,
+            GetNoteTagStatisticsResponse, GetNotesByIdResponse, GetNotesByRecipientResponse,
+            SubmitAndWatchProvenTransactionResponse, SubmitProvenTransactionResponse,
+            SyncAccountCompactResponse, SyncNoteResponse, SyncStateResponse,
         },
         rpc::api_server,
         store::api_client as store_client,
     },
     try_convert,
 };
+use miden_node_store::{
+    server::{
+        sharding::attach_shard_keys,
+        sync_params::{
+            check_nullifier_prefixes, normalize_sync_note_request, normalize_sync_state_request,
+        },
+    },
+    COMPONENT as STORE_COMPONENT, MAX_ACCOUNT_WITNESSES, MAX_NOTE_RECIPIENTS,
+};
+use miden_node_utils::{
+    message_limits::MessageLimits,
+    request_id::{connect_with_request_ids, RequestIdChannel},
+};
 use miden_objects::{
-    accounts::AccountId, crypto::hash::rpo::RpoDigest, transaction::ProvenTransaction,
-    utils::serde::Deserializable, Digest, MAX_NUM_FOREIGN_ACCOUNTS, MIN_PROOF_SECURITY_LEVEL,
+    accounts::AccountId,
+    crypto::hash::rpo::RpoDigest,
+    transaction::{ProvenTransaction, TransactionId},
+    utils::serde::Deserializable,
+    BlockHeader, Digest, MAX_NUM_FOREIGN_ACCOUNTS, MIN_PROOF_SECURITY_LEVEL,
 };
 use miden_tx::TransactionVerifier;
-use tonic::{
-    transport::{Channel, Error},
-    Request, Response, Status,
-};
+use prost::Message;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{transport::Error, Code, Request, Response, Status};
 use tracing::{debug, info, instrument};
 
-use crate::{config::RpcConfig, COMPONENT};
+use super::{cache::ResponseCache, rate_limit::RateLimiter};
+use crate::{
+    audit::{AuditLog, AuditRecord},
+    config::RpcConfig,
+    COMPONENT,
+};
+
+/// Maximum number of `GetAccountExpectedState` requests served per second, across all clients.
+const EXPECTED_STATE_REQUESTS_PER_SECOND: u32 = 100;
+
+/// Maximum number of `GetNoteTagStatistics` requests served per second, across all clients.
+///
+/// The statistics are aggregated over all the notes of a range of blocks.
+const NOTE_TAG_STATISTICS_REQUESTS_PER_SECOND: u32 = 10;
 
 // RPC API
 // ================================================================================================
 
 pub struct RpcApi {
-    store: store_client::ApiClient<Channel>,
-    block_producer: block_producer_client::ApiClient<Channel>,
+    store: store_client::ApiClient<RequestIdChannel>,
+    block_producer: block_producer_client::ApiClient<RequestIdChannel>,
+    expected_state_limiter: RateLimiter,
+    note_tag_statistics_limiter: RateLimiter,
+    expose_internal_errors: bool,
+    /// Limits on the size of the notes of the submitted transactions
+    message_limits: MessageLimits,
+    /// Records the submitted transactions, if enabled
+    audit_log: Option<AuditLog>,
+    /// Answers the requests for immutable data, if enabled
+    cache: Option<ResponseCache>,
 }
 
 impl RpcApi {
     pub(super) async fn from_config(config: &RpcConfig) -> Result<Self, Error> {
-        let store = store_client::ApiClient::connect(config.store_url.clone()).await?;
+        let max_message_size = config.message_limits.max_message_size();
+
+        let store =
+            store_client::ApiClient::new(connect_with_request_ids(config.store_url.clone()).await?)
+                .max_decoding_message_size(max_message_size)
+                .max_encoding_message_size(max_message_size);
         info!(target: COMPONENT, store_endpoint = config.store_url, "Store client initialized");
 
-        let block_producer =
-            block_producer_client::ApiClient::connect(config.block_producer_url.clone()).await?;
+        let block_producer = block_producer_client::ApiClient::new(
+            connect_with_request_ids(config.block_producer_url.clone()).await?,
+        )
+        .max_decoding_message_size(max_message_size)
+        .max_encoding_message_size(max_message_size);
         info!(
             target: COMPONENT,
             block_producer_endpoint = config.block_producer_url,
             "Block producer client initialized",
         );
 
-        Ok(Self { store, block_producer })
+        let expected_state_limiter =
+            RateLimiter::new(EXPECTED_STATE_REQUESTS_PER_SECOND, Duration::from_secs(1));
+        let note_tag_statistics_limiter =
+            RateLimiter::new(NOTE_TAG_STATISTICS_REQUESTS_PER_SECOND, Duration::from_secs(1));
+
+        Ok(Self {
+            store,
+            block_producer,
+            expected_state_limiter,
+            note_tag_statistics_limiter,
+            expose_internal_errors: config.expose_internal_errors,
+            message_limits: config.message_limits,
+            audit_log: None,
+            cache: (config.response_cache.max_size > 0)
+                .then(|| ResponseCache::new(config.response_cache.max_size)),
+        })
+    }
+
+    /// Returns the commitment of the genesis block of the store.
+    pub(super) async fn genesis_commitment(&self) -> Result<Digest, Status> {
+        let request = GetBlockHeaderByNumberRequest {
+            block_num: Some(0),
+            include_mmr_proof: None,
+        };
+        let header = self
+            .store
+            .clone()
+            .get_block_header_by_number(request)
+            .await?
+            .into_inner()
+            .block_header
+            .ok_or_else(|| Status::internal("The store returned no genesis block header"))?;
+        let header = BlockHeader::try_from(header)
+            .map_err(|err| Status::internal(format!("Invalid genesis block header: {err}")))?;
+
+        Ok(header.hash())
+    }
+
+    /// Records the outcome of every transaction submission to the given audit log.
+    pub(super) fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Records the outcome of the submission of a transaction by `peer` in the audit log, if
+    /// enabled.
+    fn audit<T>(
+        &self,
+        tx_id: Option<TransactionId>,
+        peer: Option<SocketAddr>,
+        result: &Result<T, Status>,
+    ) {
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record(&AuditRecord::new(tx_id, peer, result));
+        }
+    }
+
+    /// Forwards a response of the store, anchoring the cached responses to its chain tip and
+    /// recording the freshness of the chain it reports.
+    fn store_response<T: Anchored>(
+        &self,
+        result: Result<Response<T>, Status>,
+    ) -> Result<Response<T>, Status> {
+        let response = result.map_err(|err| self.upstream_error(STORE_COMPONENT, err))?;
+        if let Some(cache) = &self.cache {
+            if let Some(anchor) = response.get_ref().anchor() {
+                cache.observe_anchor(anchor);
+            }
+            cache.observe_freshness(response.metadata());
+        }
+
+        Ok(response)
+    }
+
+    /// Returns a response served from the `cache`, with the freshness last reported by the store.
+    fn cached_response<T>(cache: &ResponseCache, message: T) -> Response<T> {
+        let mut response = Response::new(message);
+        cache.report_freshness(response.metadata_mut());
+        response
+    }
+
+    /// Appends the RPC layer to the error chain of a status returned by an upstream component.
+    ///
+    /// See [upstream_error].
+    fn upstream_error(&self, upstream: &str, status: Status) -> Status {
+        upstream_error(self.expose_internal_errors, upstream, status)
     }
 }
 
+/// Appends the RPC layer to the error chain of a status returned by an upstream component.
+///
+/// Unless `expose_internal_errors` is set, the messages and sources of internal errors are
+/// removed, as they can contain details of the node's environment. The components and kinds of the
+/// errors are kept.
+fn upstream_error(expose_internal_errors: bool, upstream: &str, status: Status) -> Status {
+    let layer = ErrorLayer {
+        component: COMPONENT.to_string(),
+        kind: "UpstreamError".to_string(),
+        message: format!("Request to {upstream} failed"),
+        sources: Vec::new(),
+    };
+    let status = status.with_error_layer(layer);
+
+    if expose_internal_errors
+        || !matches!(status.code(), Code::Internal | Code::Unknown | Code::DataLoss)
+    {
+        return status;
+    }
+
+    let layers = status
+        .error_chain()
+        .into_iter()
+        .map(|layer| ErrorLayer {
+            component: layer.component,
+            kind: layer.kind,
+            ..Default::default()
+        })
+        .collect();
+    let details = ErrorDetails { layers }.encode_to_vec();
+
+    Status::with_details(status.code(), "Internal error", details.into())
+}
+
+/// Deserializes the transaction of the request.
+fn read_transaction(request: &SubmitProvenTransactionRequest) -> Result<ProvenTransaction, Status> {
+    ProvenTransaction::read_from_bytes(&request.transaction)
+        .map_err(|err| Status::invalid_argument(format!("Invalid transaction: {err}")))
+}
+
+/// Verifies the size of the notes and the proof of a transaction.
+fn verify_transaction(
+    tx: &ProvenTransaction,
+    message_limits: &MessageLimits,
+) -> Result<(), Status> {
+    // Checked before the proof, which is much more expensive to verify
+    message_limits.check_output_notes(tx.output_notes()).map_err(|err| {
+        Status::invalid_argument(format!("Invalid transaction {}: {err}", tx.id()))
+    })?;
+
+    let tx_verifier = TransactionVerifier::new(MIN_PROOF_SECURITY_LEVEL);
+
+    tx_verifier.verify(tx.clone()).map_err(|err| {
+        Status::invalid_argument(format!("Invalid proof for transaction {}: {err}", tx.id()))
+    })
+}
+
 #[tonic::async_trait]
 impl api_server::Api for RpcApi {
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:check_notes_consumed",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn check_notes_consumed(
+        &self,
+        request: Request<CheckNotesConsumedRequest>,
+    ) -> Result<Response<CheckNotesConsumedResponse>, Status> {
+        debug!(target: COMPONENT, request = ?request.get_ref());
+
+        // Validation checking for correct NoteId's
+        let note_ids = request.get_ref().note_ids.clone();
+
+        let _: Vec<RpoDigest> = try_convert(note_ids)
+            .map_err(|err| Status::invalid_argument(format!("Invalid NoteId: {}", err)))?;
+
+        self.store_response(self.store.clone().check_notes_consumed(request).await)
+    }
+
     #[instrument(
         target = "miden-rpc",
         name = "rpc:check_nullifiers",
@@ -78,7 +303,7 @@ impl api_server::Api for RpcApi {
                 .or(Err(Status::invalid_argument("Digest field is not in the modulus range")))?;
         }
 
-        self.store.clone().check_nullifiers(request).await
+        self.store_response(self.store.clone().check_nullifiers(request).await)
     }
 
     #[instrument(
@@ -94,7 +319,10 @@ impl api_server::Api for RpcApi {
     ) -> Result<Response<CheckNullifiersByPrefixResponse>, Status> {
         debug!(target: COMPONENT, request = ?request.get_ref());
 
-        self.store.clone().check_nullifiers_by_prefix(request).await
+        let CheckNullifiersByPrefixRequest { prefix_len, nullifiers, .. } = request.get_ref();
+        check_nullifier_prefixes(*prefix_len, nullifiers)?;
+
+        self.store_response(self.store.clone().check_nullifiers_by_prefix(request).await)
     }
 
     #[instrument(
@@ -110,39 +338,143 @@ impl api_server::Api for RpcApi {
     ) -> Result<Response<GetBlockHeaderByNumberResponse>, Status> {
         info!(target: COMPONENT, request = ?request.get_ref());
 
-        self.store.clone().get_block_header_by_number(request).await
+        // Headers are immutable, but MMR proofs depend on the chain length
+        let block_num = request.get_ref().block_num;
+        let include_mmr_proof = request.get_ref().include_mmr_proof.unwrap_or_default();
+        if let (Some(cache), Some(block_num), false) = (&self.cache, block_num, include_mmr_proof) {
+            if let Some(response) = cache.get_block_header(block_num) {
+                return Ok(Self::cached_response(cache, response));
+            }
+        }
+
+        let response =
+            self.store_response(self.store.clone().get_block_header_by_number(request).await)?;
+        if let (Some(cache), Some(block_num)) = (&self.cache, block_num) {
+            cache.insert_block_header(block_num, response.get_ref());
+        }
+
+        Ok(response)
+    }
+
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_block_headers_range",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_block_headers_range(
+        &self,
+        request: Request<GetBlockHeadersRangeRequest>,
+    ) -> Result<Response<GetBlockHeadersRangeResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        self.store_response(self.store.clone().get_block_headers_range(request).await)
+    }
+
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_block_header_by_commitment",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_block_header_by_commitment(
+        &self,
+        request: Request<GetBlockHeaderByCommitmentRequest>,
+    ) -> Result<Response<GetBlockHeaderByCommitmentResponse>, Status> {
+        info!(target: COMPONENT, request = ?request.get_ref());
+
+        self.store_response(self.store.clone().get_block_header_by_commitment(request).await)
+    }
+
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_data_availability",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_data_availability(
+        &self,
+        request: Request<GetDataAvailabilityRequest>,
+    ) -> Result<Response<GetDataAvailabilityResponse>, Status> {
+        info!(target: COMPONENT, request = ?request.get_ref());
+
+        self.store_response(self.store.clone().get_data_availability(request).await)
     }
 
     #[instrument(
         target = "miden-rpc",
         name = "rpc:sync_state",
         skip_all,
+        fields(
+            account_ids,
+            unique_account_ids,
+            note_tags,
+            unique_note_tags,
+            nullifier_prefixes,
+            unique_nullifier_prefixes
+        ),
         ret(level = "debug"),
         err
     )]
     async fn sync_state(
         &self,
-        request: Request<SyncStateRequest>,
+        mut request: Request<SyncStateRequest>,
     ) -> Result<Response<SyncStateResponse>, Status> {
         debug!(target: COMPONENT, request = ?request.get_ref());
 
-        self.store.clone().sync_state(request).await
+        // Forward the deduplicated parameters to the store
+        normalize_sync_state_request(request.get_mut())?;
+        let account_ids: Vec<u64> =
+            request.get_ref().account_ids.iter().map(|account_id| account_id.id).collect();
+        attach_shard_keys(&mut request, account_ids);
+
+        self.store_response(self.store.clone().sync_state(request).await)
     }
 
     #[instrument(
         target = "miden-rpc",
         name = "rpc:sync_notes",
         skip_all,
+        fields(note_tags, unique_note_tags),
         ret(level = "debug"),
         err
     )]
     async fn sync_notes(
         &self,
-        request: Request<SyncNoteRequest>,
+        mut request: Request<SyncNoteRequest>,
     ) -> Result<Response<SyncNoteResponse>, Status> {
         debug!(target: COMPONENT, request = ?request.get_ref());
 
-        self.store.clone().sync_notes(request).await
+        normalize_sync_note_request(request.get_mut())?;
+
+        self.store_response(self.store.clone().sync_notes(request).await)
+    }
+
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:sync_account_compact",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn sync_account_compact(
+        &self,
+        request: Request<SyncAccountCompactRequest>,
+    ) -> Result<Response<SyncAccountCompactResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let account_ids = request.account_id.map(|account_id| account_id.id);
+        let mut request = Request::new(request);
+        attach_shard_keys(&mut request, account_ids);
+
+        self.store_response(self.store.clone().sync_account_compact(request).await)
     }
 
     #[instrument(
@@ -161,10 +493,73 @@ impl api_server::Api for RpcApi {
         // Validation checking for correct NoteId's
         let note_ids = request.get_ref().note_ids.clone();
 
-        let _: Vec<RpoDigest> = try_convert(note_ids)
+        let note_ids: Vec<RpoDigest> = try_convert(note_ids)
             .map_err(|err| Status::invalid_argument(format!("Invalid NoteId: {}", err)))?;
 
-        self.store.clone().get_notes_by_id(request).await
+        let compressed = request.get_ref().compress_merkle_paths;
+        if let Some(cache) = &self.cache {
+            let max_response_size = self.message_limits.max_message_size();
+            if let Some(response) = cache.get_notes(&note_ids, compressed, max_response_size) {
+                return Ok(Self::cached_response(cache, response));
+            }
+        }
+
+        let response = self.store_response(self.store.clone().get_notes_by_id(request).await)?;
+        if let Some(cache) = &self.cache {
+            cache.insert_notes(response.get_ref(), compressed);
+        }
+
+        Ok(response)
+    }
+
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_notes_by_recipient",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_notes_by_recipient(
+        &self,
+        request: Request<GetNotesByRecipientRequest>,
+    ) -> Result<Response<GetNotesByRecipientResponse>, Status> {
+        debug!(target: COMPONENT, request = ?request.get_ref());
+
+        let recipients = &request.get_ref().recipients;
+        if recipients.len() > MAX_NOTE_RECIPIENTS {
+            return Err(Status::invalid_argument(format!(
+                "Too many recipients requested: {}, limit: {MAX_NOTE_RECIPIENTS}",
+                recipients.len()
+            )));
+        }
+
+        let _: Vec<RpoDigest> = try_convert(recipients.clone())
+            .map_err(|err| Status::invalid_argument(format!("Invalid recipient: {}", err)))?;
+
+        self.store_response(self.store.clone().get_notes_by_recipient(request).await)
+    }
+
+    /// Returns the number of notes per tag prefix in a range of blocks.
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_note_tag_statistics",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_note_tag_statistics(
+        &self,
+        request: Request<GetNoteTagStatisticsRequest>,
+    ) -> Result<Response<GetNoteTagStatisticsResponse>, Status> {
+        debug!(target: COMPONENT, request = ?request.get_ref());
+
+        if !self.note_tag_statistics_limiter.try_acquire() {
+            return Err(Status::resource_exhausted(
+                "Too many note tag statistics requests, retry later",
+            ));
+        }
+
+        self.store_response(self.store.clone().get_note_tag_statistics(request).await)
     }
 
     #[instrument(target = "miden-rpc", name = "rpc:submit_proven_transaction", skip_all, err)]
@@ -174,18 +569,72 @@ impl api_server::Api for RpcApi {
     ) -> Result<Response<SubmitProvenTransactionResponse>, Status> {
         debug!(target: COMPONENT, request = ?request.get_ref());
 
+        let peer = request.remote_addr();
         let request = request.into_inner();
+        let tx = read_transaction(&request);
+        let tx_id = tx.as_ref().ok().map(ProvenTransaction::id);
 
-        let tx = ProvenTransaction::read_from_bytes(&request.transaction)
-            .map_err(|err| Status::invalid_argument(format!("Invalid transaction: {err}")))?;
+        let result = async {
+            verify_transaction(&tx?, &self.message_limits)?;
 
-        let tx_verifier = TransactionVerifier::new(MIN_PROOF_SECURITY_LEVEL);
+            self.block_producer
+                .clone()
+                .submit_proven_transaction(request)
+                .await
+                .map_err(|err| self.upstream_error(BLOCK_PRODUCER_COMPONENT, err))
+        }
+        .await;
+
+        self.audit(tx_id, peer, &result);
+        result
+    }
 
-        tx_verifier.verify(tx.clone()).map_err(|err| {
-            Status::invalid_argument(format!("Invalid proof for transaction {}: {err}", tx.id()))
-        })?;
+    type SubmitAndWatchProvenTransactionStream =
+        Pin<Box<dyn Stream<Item = Result<SubmitAndWatchProvenTransactionResponse, Status>> + Send>>;
 
-        self.block_producer.clone().submit_proven_transaction(request).await
+    /// Submits a transaction, and streams its status changes from the block producer.
+    ///
+    /// Dropping the stream of a disconnected client cancels the upstream stream, which ends the
+    /// subscription of the block producer.
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:submit_and_watch_proven_transaction",
+        skip_all,
+        err
+    )]
+    async fn submit_and_watch_proven_transaction(
+        &self,
+        request: Request<SubmitProvenTransactionRequest>,
+    ) -> Result<Response<Self::SubmitAndWatchProvenTransactionStream>, Status> {
+        debug!(target: COMPONENT, request = ?request.get_ref());
+
+        let peer = request.remote_addr();
+        let request = request.into_inner();
+        let tx = read_transaction(&request);
+        let tx_id = tx.as_ref().ok().map(ProvenTransaction::id);
+
+        let result = async {
+            verify_transaction(&tx?, &self.message_limits)?;
+
+            self.block_producer
+                .clone()
+                .submit_and_watch_proven_transaction(request)
+                .await
+                .map_err(|err| self.upstream_error(BLOCK_PRODUCER_COMPONENT, err))
+        }
+        .await;
+
+        self.audit(tx_id, peer, &result);
+        let updates = result?.into_inner();
+
+        let expose_internal_errors = self.expose_internal_errors;
+        let updates = updates.map(move |update| {
+            update.map_err(|err| {
+                upstream_error(expose_internal_errors, BLOCK_PRODUCER_COMPONENT, err)
+            })
+        });
+
+        Ok(Response::new(Box::pin(updates)))
     }
 
     /// Returns details for public (public) account by id.
@@ -198,19 +647,107 @@ impl api_server::Api for RpcApi {
     )]
     async fn get_account_details(
         &self,
-        request: Request<GetAccountDetailsRequest>,
+        mut request: Request<GetAccountDetailsRequest>,
     ) -> std::result::Result<Response<GetAccountDetailsResponse>, Status> {
         debug!(target: COMPONENT, request = ?request.get_ref());
 
         // Validating account using conversion:
-        let _account_id: AccountId = request
+        let account_id: AccountId = request
+            .get_ref()
+            .account_id
+            .ok_or(Status::invalid_argument("account_id is missing"))?
+            .try_into()
+            .map_err(|err| Status::invalid_argument(format!("Invalid account id: {err}")))?;
+        attach_shard_keys(&mut request, [u64::from(account_id)]);
+
+        self.store_response(self.store.clone().get_account_details(request).await)
+    }
+
+    /// Returns the storage layout of a public account, without the stored values.
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_account_storage_header",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_storage_header(
+        &self,
+        mut request: Request<GetAccountStorageHeaderRequest>,
+    ) -> Result<Response<GetAccountStorageHeaderResponse>, Status> {
+        debug!(target: COMPONENT, request = ?request.get_ref());
+
+        let account_id: AccountId = request
+            .get_ref()
+            .account_id
+            .ok_or(Status::invalid_argument("account_id is missing"))?
+            .try_into()
+            .map_err(|err| Status::invalid_argument(format!("Invalid account id: {err}")))?;
+        // The storage of private accounts is not known to the node
+        if !account_id.is_public() {
+            return Err(Status::not_found(format!("Account {account_id} is private")));
+        }
+        attach_shard_keys(&mut request, [u64::from(account_id)]);
+
+        self.store_response(self.store.clone().get_account_storage_header(request).await)
+    }
+
+    /// Returns a page of the assets in the vault of a public account.
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_account_vault_assets",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_vault_assets(
+        &self,
+        mut request: Request<GetAccountVaultAssetsRequest>,
+    ) -> Result<Response<GetAccountVaultAssetsResponse>, Status> {
+        debug!(target: COMPONENT, request = ?request.get_ref());
+
+        let account_id: AccountId = request
             .get_ref()
             .account_id
             .ok_or(Status::invalid_argument("account_id is missing"))?
             .try_into()
             .map_err(|err| Status::invalid_argument(format!("Invalid account id: {err}")))?;
+        // The vault of private accounts is not known to the node
+        if !account_id.is_public() {
+            return Err(Status::not_found(format!("Account {account_id} is private")));
+        }
+        attach_shard_keys(&mut request, [u64::from(account_id)]);
+
+        self.store
+            .clone()
+            .get_account_vault_assets(request)
+            .await
+            .map_err(|err| self.upstream_error(STORE_COMPONENT, err))
+    }
+
+    /// Returns the witnesses of accounts against the account tree of a recent block.
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_account_witnesses",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_witnesses(
+        &self,
+        request: Request<GetAccountWitnessesRequest>,
+    ) -> Result<Response<GetAccountWitnessesResponse>, Status> {
+        debug!(target: COMPONENT, request = ?request.get_ref());
+
+        let account_ids = &request.get_ref().account_ids;
+        if account_ids.len() > MAX_ACCOUNT_WITNESSES {
+            return Err(Status::invalid_argument(format!(
+                "Too many accounts requested: {}, limit: {MAX_ACCOUNT_WITNESSES}",
+                account_ids.len()
+            )));
+        }
 
-        self.store.clone().get_account_details(request).await
+        self.store_response(self.store.clone().get_account_witnesses(request).await)
     }
 
     #[instrument(
@@ -228,7 +765,26 @@ impl api_server::Api for RpcApi {
 
         debug!(target: COMPONENT, ?request);
 
-        self.store.clone().get_block_by_number(request).await
+        self.store_response(self.store.clone().get_block_by_number(request).await)
+    }
+
+    /// Returns the Bloom filters of the note tags of a range of blocks.
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_block_note_filters",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_block_note_filters(
+        &self,
+        request: Request<GetBlockNoteFiltersRequest>,
+    ) -> Result<Response<GetBlockNoteFiltersResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        self.store_response(self.store.clone().get_block_note_filters(request).await)
     }
 
     #[instrument(
@@ -246,7 +802,11 @@ impl api_server::Api for RpcApi {
 
         debug!(target: COMPONENT, ?request);
 
-        self.store.clone().get_account_state_delta(request).await
+        let account_ids = request.account_id.map(|account_id| account_id.id);
+        let mut request = Request::new(request);
+        attach_shard_keys(&mut request, account_ids);
+
+        self.store_response(self.store.clone().get_account_state_delta(request).await)
     }
 
     #[instrument(
@@ -271,6 +831,304 @@ impl api_server::Api for RpcApi {
             )));
         }
 
-        self.store.clone().get_account_proofs(request).await
+        let account_ids: Vec<u64> =
+            request.account_ids.iter().map(|account_id| account_id.id).collect();
+        let mut request = Request::new(request);
+        attach_shard_keys(&mut request, account_ids);
+
+        self.store_response(self.store.clone().get_account_proofs(request).await)
+    }
+
+    /// Returns the state an account is expected to have once its in-flight transactions are
+    /// committed, allowing to build a transaction on top of another one before it is committed.
+    ///
+    /// The returned state is advisory: in-flight transactions can still be dropped by the block
+    /// producer, in which case transactions built on top of them are rejected as well.
+    #[instrument(
+        target = "miden-rpc",
+        name = "rpc:get_account_expected_state",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_expected_state(
+        &self,
+        request: Request<GetAccountExpectedStateRequest>,
+    ) -> Result<Response<GetAccountExpectedStateResponse>, Status> {
+        debug!(target: COMPONENT, request = ?request.get_ref());
+
+        if !self.expected_state_limiter.try_acquire() {
+            return Err(Status::resource_exhausted(
+                "Too many expected account state requests, retry later",
+            ));
+        }
+
+        let _account_id: AccountId = request
+            .get_ref()
+            .account_id
+            .ok_or(Status::invalid_argument("account_id is missing"))?
+            .try_into()
+            .map_err(|err| Status::invalid_argument(format!("Invalid account id: {err}")))?;
+
+        self.block_producer
+            .clone()
+            .get_account_expected_state(request)
+            .await
+            .map_err(|err| self.upstream_error(BLOCK_PRODUCER_COMPONENT, err))
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::Path,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use miden_node_proto::generated::rpc::api_server::Api;
+    use miden_node_store::{
+        config::{
+            NoteDetailsPolicy, ShardRange, StoreConfig, WriteLimitsConfig,
+            DEFAULT_EXPORT_ROWS_PER_SECOND, DEFAULT_MAX_BLOCK_TIMESTAMP_DRIFT_SECS,
+            DEFAULT_MAX_RESPONSE_SIZE, DEFAULT_SLOW_POOL_WAIT_THRESHOLD_MS,
+            DEFAULT_SLOW_QUERY_THRESHOLD_MS,
+        },
+        genesis::GenesisState,
+        server::Store,
+    };
+    use miden_node_utils::{
+        block_versions::BlockVersions,
+        config::{local_url, Endpoint, CONFIG_VERSION},
+        freshness::{ChainFreshness, STALENESS_HEADER, SYNCING_HEADER},
+        request_id::{with_request_id, RequestIdPropagator, REQUEST_ID_HEADER},
+    };
+    use miden_objects::utils::Serializable;
+    use tonic::{service::interceptor::InterceptedService, transport::Endpoint as ChannelEndpoint};
+
+    use super::*;
+    use crate::{audit::find_records, config::AuditConfig};
+
+    /// Starts a store whose genesis block can't be read from the block store.
+    async fn start_faulty_store(dir: &Path) -> store_client::ApiClient<RequestIdChannel> {
+        start_faulty_store_with_freshness(dir).await.0
+    }
+
+    /// Starts a store like [start_faulty_store], returning the freshness of its chain as well.
+    async fn start_faulty_store_with_freshness(
+        dir: &Path,
+    ) -> (store_client::ApiClient<RequestIdChannel>, ChainFreshness) {
+        let config = StoreConfig {
+            config_version: CONFIG_VERSION,
+            endpoint: Endpoint { host: "127.0.0.1".to_string(), port: 0 },
+            database_filepath: dir.join("miden-store.sqlite3"),
+            genesis_filepath: dir.join("genesis.dat"),
+            blockstore_dir: dir.join("blocks"),
+            mode: None,
+            block_retention_depth: None,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            note_details: NoteDetailsPolicy::Full,
+            slow_query_threshold_ms: DEFAULT_SLOW_QUERY_THRESHOLD_MS,
+            slow_pool_wait_threshold_ms: DEFAULT_SLOW_POOL_WAIT_THRESHOLD_MS,
+            max_block_timestamp_drift_secs: DEFAULT_MAX_BLOCK_TIMESTAMP_DRIFT_SECS,
+            skip_block_timestamp_validation: false,
+            shard_range: ShardRange::default(),
+            export_rows_per_second: DEFAULT_EXPORT_ROWS_PER_SECOND,
+            write_limits: WriteLimitsConfig::default(),
+            block_versions: BlockVersions::default(),
+        };
+        fs::write(&config.genesis_filepath, GenesisState::new(vec![], 1, 0).to_bytes()).unwrap();
+
+        let store = Store::init(config.clone()).await.unwrap();
+        let store_url = local_url(store.local_addr());
+        let freshness = store.freshness().clone();
+        tokio::spawn(store.serve());
+
+        // The genesis block is the only block file, replacing it by a directory makes reading it
+        // fail with an I/O error
+        let epoch_dir = fs::read_dir(&config.blockstore_dir).unwrap().next().unwrap().unwrap();
+        let block_file = fs::read_dir(epoch_dir.path()).unwrap().next().unwrap().unwrap();
+        fs::remove_file(block_file.path()).unwrap();
+        fs::create_dir(block_file.path()).unwrap();
+
+        let store =
+            store_client::ApiClient::new(connect_with_request_ids(store_url).await.unwrap());
+
+        (store, freshness)
+    }
+
+    fn rpc_api(
+        store: store_client::ApiClient<RequestIdChannel>,
+        expose_internal_errors: bool,
+    ) -> RpcApi {
+        // The block producer is never reached
+        let block_producer = block_producer_client::ApiClient::new(InterceptedService::new(
+            ChannelEndpoint::from_static("http://127.0.0.1:1").connect_lazy(),
+            RequestIdPropagator,
+        ));
+
+        RpcApi {
+            store,
+            block_producer,
+            expected_state_limiter: RateLimiter::new(
+                EXPECTED_STATE_REQUESTS_PER_SECOND,
+                Duration::from_secs(1),
+            ),
+            note_tag_statistics_limiter: RateLimiter::new(
+                NOTE_TAG_STATISTICS_REQUESTS_PER_SECOND,
+                Duration::from_secs(1),
+            ),
+            expose_internal_errors,
+            message_limits: MessageLimits::default(),
+            audit_log: None,
+            cache: None,
+        }
+    }
+
+    async fn get_genesis_block(rpc: &RpcApi) -> Status {
+        rpc.get_block_by_number(Request::new(GetBlockByNumberRequest { block_num: 0 }))
+            .await
+            .unwrap_err()
+    }
+
+    #[tokio::test]
+    async fn store_errors_are_chained_through_the_rpc() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = start_faulty_store(dir.path()).await;
+
+        let err = get_genesis_block(&rpc_api(store.clone(), true)).await;
+        assert_eq!(err.code(), Code::Internal);
+
+        let chain = err.error_chain();
+        let components: Vec<_> = chain.iter().map(|layer| layer.component.as_str()).collect();
+        assert_eq!(components, ["miden-store", "miden-rpc"]);
+        assert_eq!(chain[0].kind, "GetBlockError::IoError");
+        assert_eq!(chain[0].message, err.message());
+        assert_eq!(chain[0].sources.len(), 1);
+        assert_eq!(chain[1].kind, "UpstreamError");
+
+        // By default, only the components and kinds of internal errors are returned
+        let err = get_genesis_block(&rpc_api(store, false)).await;
+        assert_eq!(err.code(), Code::Internal);
+        assert_eq!(err.message(), "Internal error");
+
+        let chain = err.error_chain();
+        let kinds: Vec<_> = chain.iter().map(|layer| layer.kind.as_str()).collect();
+        assert_eq!(kinds, ["GetBlockError::IoError", "UpstreamError"]);
+        assert!(chain.iter().all(|layer| layer.message.is_empty() && layer.sources.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn genesis_commitment_is_read_from_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let rpc = rpc_api(start_faulty_store(dir.path()).await, true);
+
+        // The header is read from the database, not from the block store
+        let genesis = GenesisState::new(vec![], 1, 0).into_block().unwrap();
+        assert_eq!(rpc.genesis_commitment().await.unwrap(), genesis.header().hash());
+    }
+
+    #[tokio::test]
+    async fn block_headers_are_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let rpc = RpcApi {
+            cache: Some(ResponseCache::new(1024)),
+            ..rpc_api(start_faulty_store(dir.path()).await, true)
+        };
+        let request = |include_mmr_proof| {
+            Request::new(GetBlockHeaderByNumberRequest {
+                block_num: Some(0),
+                include_mmr_proof: Some(include_mmr_proof),
+            })
+        };
+
+        let response = rpc.get_block_header_by_number(request(true)).await.unwrap().into_inner();
+        assert!(response.mmr_path.is_some());
+
+        // The header is cached, along with the anchor of the response
+        let cached = rpc.cache.as_ref().unwrap().get_block_header(0).unwrap();
+        assert_eq!(cached.block_header, response.block_header);
+        assert_eq!(cached.anchor, response.anchor);
+        let served = rpc.get_block_header_by_number(request(false)).await.unwrap().into_inner();
+        assert_eq!(served, cached);
+
+        // Requests for MMR proofs are still answered by the store
+        let response = rpc.get_block_header_by_number(request(true)).await.unwrap().into_inner();
+        assert!(response.mmr_path.is_some());
+    }
+
+    #[tokio::test]
+    async fn cached_responses_report_the_freshness_of_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, freshness) = start_faulty_store_with_freshness(dir.path()).await;
+        let rpc = RpcApi {
+            cache: Some(ResponseCache::new(1024)),
+            ..rpc_api(store, true)
+        };
+        let request = || {
+            Request::new(GetBlockHeaderByNumberRequest {
+                block_num: Some(0),
+                include_mmr_proof: Some(false),
+            })
+        };
+
+        // The store applied its latest block an hour ago, and is syncing its blocks
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        freshness.set_last_block_applied_at(Some(now - 3600));
+        let _sync = freshness.start_sync();
+
+        let response = rpc.get_block_header_by_number(request()).await.unwrap();
+        assert!(rpc.cache.as_ref().unwrap().get_block_header(0).is_some());
+        let cached = rpc.get_block_header_by_number(request()).await.unwrap();
+        assert_eq!(cached.get_ref(), response.get_ref());
+
+        // The cached response is as stale as the store, and is flagged as such by the RPC
+        for response in [response, cached] {
+            let header = |name| response.metadata().get(name).unwrap().to_str().unwrap();
+            assert!(header(STALENESS_HEADER).parse::<u64>().unwrap() >= 3600);
+            assert_eq!(header(SYNCING_HEADER), "true");
+        }
+    }
+
+    #[tokio::test]
+    async fn request_ids_are_propagated_to_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let rpc = rpc_api(start_faulty_store(dir.path()).await, true);
+
+        // The store echoes the ID of the request served by the RPC, even for failed requests
+        let err = with_request_id("fixed-id".to_string(), get_genesis_block(&rpc)).await;
+        let request_id = err.metadata().get(REQUEST_ID_HEADER).unwrap();
+        assert_eq!(request_id.to_str().unwrap(), "fixed-id");
+
+        // Requests made on behalf of no request are assigned an ID by the store
+        let err = get_genesis_block(&rpc).await;
+        let request_id = err.metadata().get(REQUEST_ID_HEADER).unwrap();
+        assert_ne!(request_id.to_str().unwrap(), "fixed-id");
+    }
+
+    #[tokio::test]
+    async fn rejected_submissions_are_audited() {
+        let dir = tempfile::tempdir().unwrap();
+        // The store is never reached
+        let store = store_client::ApiClient::new(InterceptedService::new(
+            ChannelEndpoint::from_static("http://127.0.0.1:1").connect_lazy(),
+            RequestIdPropagator,
+        ));
+        let audit_log = AuditLog::open(AuditConfig::new(dir.path())).unwrap();
+        let rpc = rpc_api(store, false).with_audit_log(audit_log);
+
+        let request = SubmitProvenTransactionRequest { transaction: vec![1, 2, 3] };
+        let err = rpc.submit_proven_transaction(Request::new(request)).await.unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let records = find_records(dir.path(), None).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].transaction_id, None);
+        assert!(!records[0].accepted);
+        assert_eq!(records[0].code.as_deref(), Some("InvalidArgument"));
+        assert_eq!(records[0].message.as_deref(), Some(err.message()));
     }
 }
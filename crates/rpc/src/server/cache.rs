@@ -0,0 +1,376 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+    time::Instant,
+};
+
+use miden_node_proto::generated::{
+    block::{BlockHeader, ChainAnchor},
+    note::Note,
+    responses::{GetBlockHeaderByNumberResponse, GetNotesByIdResponse},
+};
+use miden_node_utils::freshness::{STALENESS_HEADER, SYNCING_HEADER};
+use miden_objects::Digest;
+use prost::Message;
+use tonic::metadata::{MetadataMap, MetadataValue};
+
+// RESPONSE CACHE
+// ================================================================================================
+
+/// Caches the parts of the store's responses which can't change once returned, so that repeated
+/// requests for the same blocks and notes are answered without reaching the store.
+///
+/// The block headers requested by number are cached, but not their MMR proofs, which depend on
+/// the chain length. The notes requested by ID are cached once committed, along with their
+/// inclusion proofs, and the details the store returned at the time.
+///
+/// The cached responses are valid against any later chain tip. They are anchored to the latest
+/// chain tip observed in the responses of the store, so that they can be combined with the other
+/// reads of a client, and report the freshness of the chain last reported by the store. The least
+/// recently used entries are evicted once their encoded size exceeds `max_size` bytes.
+pub struct ResponseCache {
+    max_size: usize,
+    inner: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    /// Latest chain tip observed in the responses of the store.
+    anchor: Option<ChainAnchor>,
+    /// Freshness of the chain reported in the latest response of the store.
+    freshness: Option<ObservedFreshness>,
+    by_key: BTreeMap<CacheKey, Entry>,
+    /// Cached keys, by their last use.
+    by_last_use: BTreeMap<u64, CacheKey>,
+    next_use: u64,
+    /// Encoded size of the cached values.
+    size: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CacheKey {
+    BlockHeader(u32),
+    /// Notes are cached separately with their compressed and uncompressed Merkle paths.
+    Note {
+        note_id: Digest,
+        compressed: bool,
+    },
+}
+
+/// Freshness headers of a response of the store, see [miden_node_utils::freshness].
+#[derive(Debug, Clone, Copy)]
+struct ObservedFreshness {
+    /// Seconds since the latest block was applied, [None] while the chain only holds its genesis
+    /// block.
+    staleness: Option<u64>,
+    syncing: bool,
+    observed_at: Instant,
+}
+
+enum CachedValue {
+    BlockHeader(BlockHeader),
+    Note(Note),
+}
+
+impl CachedValue {
+    fn size(&self) -> usize {
+        match self {
+            Self::BlockHeader(header) => header.encoded_len(),
+            Self::Note(note) => note.encoded_len(),
+        }
+    }
+}
+
+struct Entry {
+    value: CachedValue,
+    last_use: u64,
+}
+
+impl ResponseCache {
+    /// Creates a cache holding up to `max_size` bytes of responses.
+    pub fn new(max_size: usize) -> Self {
+        Self { max_size, inner: Default::default() }
+    }
+
+    /// Records the chain tip of a response of the store, anchoring the cached responses to it if
+    /// it is the latest one.
+    pub fn observe_anchor(&self, anchor: &ChainAnchor) {
+        let mut entries = self.inner.lock().expect("Response cache lock poisoned");
+        if entries
+            .anchor
+            .as_ref()
+            .map_or(true, |latest| anchor.block_num >= latest.block_num)
+        {
+            entries.anchor = Some(anchor.clone());
+        }
+    }
+
+    /// Records the freshness of the chain reported in the headers of a response of the store.
+    pub fn observe_freshness(&self, metadata: &MetadataMap) {
+        let header = |name| metadata.get(name).and_then(|value| value.to_str().ok());
+        let freshness = ObservedFreshness {
+            staleness: header(STALENESS_HEADER).and_then(|staleness| staleness.parse().ok()),
+            syncing: header(SYNCING_HEADER) == Some("true"),
+            observed_at: Instant::now(),
+        };
+
+        self.inner.lock().expect("Response cache lock poisoned").freshness = Some(freshness);
+    }
+
+    /// Sets the freshness headers of a cached response, as the store would have.
+    ///
+    /// The staleness last reported by the store is increased by the time elapsed since, so that
+    /// the cached responses of a node falling behind are flagged as such by the
+    /// [SyncingFlagLayer](miden_node_utils::freshness::SyncingFlagLayer).
+    pub fn report_freshness(&self, metadata: &mut MetadataMap) {
+        let entries = self.inner.lock().expect("Response cache lock poisoned");
+        let Some(freshness) = entries.freshness else {
+            return;
+        };
+
+        if let Some(staleness) = freshness.staleness {
+            let staleness = staleness.saturating_add(freshness.observed_at.elapsed().as_secs());
+            metadata.insert(STALENESS_HEADER, MetadataValue::from(staleness));
+        }
+        if freshness.syncing {
+            metadata.insert(SYNCING_HEADER, MetadataValue::from_static("true"));
+        }
+    }
+
+    /// Returns the cached header of block `block_num`, without MMR proof.
+    pub fn get_block_header(&self, block_num: u32) -> Option<GetBlockHeaderByNumberResponse> {
+        let mut entries = self.inner.lock().expect("Response cache lock poisoned");
+        let CachedValue::BlockHeader(header) = entries.get(CacheKey::BlockHeader(block_num))?
+        else {
+            unreachable!("Block headers are cached under their block number");
+        };
+        let block_header = Some(header.clone());
+
+        Some(GetBlockHeaderByNumberResponse {
+            block_header,
+            mmr_path: None,
+            chain_length: None,
+            anchor: entries.anchor.clone(),
+        })
+    }
+
+    /// Caches the block header of a response of the store to a request for block `block_num`.
+    pub fn insert_block_header(&self, block_num: u32, response: &GetBlockHeaderByNumberResponse) {
+        let Some(header) = &response.block_header else {
+            return;
+        };
+
+        self.insert(CacheKey::BlockHeader(block_num), CachedValue::BlockHeader(header.clone()));
+    }
+
+    /// Returns the cached notes `note_ids`, in the requested order, if all of them are cached.
+    ///
+    /// Like the store, the notes beyond `max_response_size` bytes are left out of the response,
+    /// which is then marked as truncated.
+    pub fn get_notes(
+        &self,
+        note_ids: &[Digest],
+        compressed: bool,
+        max_response_size: usize,
+    ) -> Option<GetNotesByIdResponse> {
+        let mut entries = self.inner.lock().expect("Response cache lock poisoned");
+
+        let keys: BTreeSet<_> = note_ids
+            .iter()
+            .map(|note_id| CacheKey::Note { note_id: *note_id, compressed })
+            .collect();
+        if keys.is_empty() || !keys.iter().all(|key| entries.by_key.contains_key(key)) {
+            return None;
+        }
+
+        let mut notes = Vec::new();
+        let mut truncated = false;
+        let mut response_size = 0;
+        let mut seen = BTreeSet::new();
+        for note_id in note_ids {
+            if !seen.insert(note_id) {
+                continue;
+            }
+            let key = CacheKey::Note { note_id: *note_id, compressed };
+            let CachedValue::Note(note) = entries.get(key).expect("All the notes are cached")
+            else {
+                unreachable!("Notes are cached under their ID");
+            };
+
+            response_size += note.encoded_len();
+            if response_size > max_response_size && !notes.is_empty() {
+                truncated = true;
+                break;
+            }
+            notes.push(note.clone());
+        }
+
+        Some(GetNotesByIdResponse {
+            notes,
+            truncated,
+            anchor: entries.anchor.clone(),
+        })
+    }
+
+    /// Caches the notes of a response of the store to a request for notes by ID.
+    pub fn insert_notes(&self, response: &GetNotesByIdResponse, compressed: bool) {
+        for note in &response.notes {
+            let Some(note_id) = note.note_id.as_ref().and_then(|id| Digest::try_from(id).ok())
+            else {
+                continue;
+            };
+
+            self.insert(CacheKey::Note { note_id, compressed }, CachedValue::Note(note.clone()));
+        }
+    }
+
+    fn insert(&self, key: CacheKey, value: CachedValue) {
+        let value_size = value.size();
+        if value_size > self.max_size {
+            return;
+        }
+
+        let mut entries = self.inner.lock().expect("Response cache lock poisoned");
+        entries.remove(key);
+        while entries.size + value_size > self.max_size {
+            let (_, evicted) = entries.by_last_use.pop_first().expect("Cached keys are tracked");
+            entries.remove(evicted);
+        }
+
+        let last_use = entries.next_use;
+        entries.next_use += 1;
+        entries.size += value_size;
+        entries.by_last_use.insert(last_use, key);
+        entries.by_key.insert(key, Entry { value, last_use });
+    }
+}
+
+impl Entries {
+    /// Returns the value cached under `key`, marking it as the most recently used.
+    fn get(&mut self, key: CacheKey) -> Option<&CachedValue> {
+        let Self { by_key, by_last_use, next_use, .. } = self;
+
+        let entry = by_key.get_mut(&key)?;
+        by_last_use.remove(&entry.last_use);
+        entry.last_use = *next_use;
+        by_last_use.insert(*next_use, key);
+        *next_use += 1;
+
+        Some(&entry.value)
+    }
+
+    fn remove(&mut self, key: CacheKey) {
+        if let Some(entry) = self.by_key.remove(&key) {
+            self.by_last_use.remove(&entry.last_use);
+            self.size -= entry.value.size();
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::Felt;
+
+    use super::*;
+
+    fn note_id(seed: u64) -> Digest {
+        Digest::new([Felt::new(seed), Felt::new(0), Felt::new(0), Felt::new(0)])
+    }
+
+    fn note(seed: u64) -> Note {
+        Note {
+            block_num: 1,
+            note_index: seed as u32,
+            note_id: Some(note_id(seed).into()),
+            details: Some(vec![0; 100]),
+            ..Default::default()
+        }
+    }
+
+    fn notes_response(seeds: &[u64]) -> GetNotesByIdResponse {
+        GetNotesByIdResponse {
+            notes: seeds.iter().copied().map(note).collect(),
+            truncated: false,
+            anchor: Some(ChainAnchor::new(5, note_id(5))),
+        }
+    }
+
+    #[test]
+    fn notes_are_served_if_all_of_them_are_cached() {
+        let cache = ResponseCache::new(1024 * 1024);
+        let response = notes_response(&[1, 2]);
+        cache.observe_anchor(response.anchor.as_ref().unwrap());
+        cache.insert_notes(&response, false);
+
+        let cached = cache.get_notes(&[note_id(2), note_id(1), note_id(2)], false, usize::MAX);
+        assert_eq!(
+            cached,
+            Some(GetNotesByIdResponse {
+                notes: vec![note(2), note(1)],
+                truncated: false,
+                anchor: response.anchor.clone(),
+            })
+        );
+
+        // Notes missing from the cache, or cached with the other form of Merkle paths, are read
+        // from the store
+        assert_eq!(cache.get_notes(&[note_id(1), note_id(3)], false, usize::MAX), None);
+        assert_eq!(cache.get_notes(&[note_id(1)], true, usize::MAX), None);
+
+        // The cached responses are anchored to the latest chain tip
+        let anchor = ChainAnchor::new(8, note_id(8));
+        cache.observe_anchor(&anchor);
+        cache.observe_anchor(response.anchor.as_ref().unwrap());
+        let cached = cache.get_notes(&[note_id(1)], false, usize::MAX).unwrap();
+        assert_eq!(cached.anchor, Some(anchor));
+
+        // Responses are truncated to the maximum response size
+        let max_response_size = note(1).encoded_len() + 1;
+        let cached = cache.get_notes(&[note_id(1), note_id(2)], false, max_response_size).unwrap();
+        assert_eq!(cached.notes, [note(1)]);
+        assert!(cached.truncated);
+    }
+
+    #[test]
+    fn cached_responses_report_the_last_freshness_of_the_store() {
+        let cache = ResponseCache::new(1024);
+        let report = || {
+            let mut metadata = MetadataMap::new();
+            cache.report_freshness(&mut metadata);
+            let header = |name| metadata.get(name).map(|value| value.to_str().unwrap().to_owned());
+            (header(STALENESS_HEADER), header(SYNCING_HEADER))
+        };
+        assert_eq!(report(), (None, None));
+
+        let mut metadata = MetadataMap::new();
+        metadata.insert(STALENESS_HEADER, MetadataValue::from(3600u64));
+        metadata.insert(SYNCING_HEADER, MetadataValue::from_static("true"));
+        cache.observe_freshness(&metadata);
+        let (staleness, syncing) = report();
+        assert!(staleness.unwrap().parse::<u64>().unwrap() >= 3600);
+        assert_eq!(syncing.as_deref(), Some("true"));
+
+        // A chain only holding its genesis block has no staleness
+        cache.observe_freshness(&MetadataMap::new());
+        assert_eq!(report(), (None, None));
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted() {
+        let note_size = note(1).encoded_len();
+        let cache = ResponseCache::new(2 * note_size);
+        cache.insert_notes(&notes_response(&[1, 2]), false);
+
+        // Using the first note makes the second one the least recently used
+        assert!(cache.get_notes(&[note_id(1)], false, usize::MAX).is_some());
+        cache.insert_notes(&notes_response(&[3]), false);
+
+        assert!(cache.get_notes(&[note_id(1)], false, usize::MAX).is_some());
+        assert!(cache.get_notes(&[note_id(2)], false, usize::MAX).is_none());
+        assert!(cache.get_notes(&[note_id(3)], false, usize::MAX).is_some());
+        assert_eq!(cache.inner.lock().unwrap().size, 2 * note_size);
+    }
+}
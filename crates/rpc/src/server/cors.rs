@@ -0,0 +1,124 @@
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tonic::codegen::{
+    http::{self, header::ORIGIN, StatusCode},
+    BoxFuture, Service,
+};
+use tower::Layer;
+
+/// Layer rejecting the browser requests from origins which aren't allowed.
+///
+/// `tonic_web` answers the CORS requests of any origin, so the allowed origins are enforced in
+/// front of it: requests with an `Origin` header which isn't listed, including the preflight
+/// requests, are rejected with `403 Forbidden`. Requests without an `Origin` header, i.e. not made
+/// by a browser, are always served. All requests are served if no origin list is set.
+#[derive(Debug, Clone, Default)]
+pub struct OriginFilterLayer {
+    allowed_origins: Option<Arc<BTreeSet<String>>>,
+}
+
+impl OriginFilterLayer {
+    pub fn new(allowed_origins: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_origins: allowed_origins.map(|origins| Arc::new(origins.into_iter().collect())),
+        }
+    }
+}
+
+impl<S> Layer<S> for OriginFilterLayer {
+    type Service = OriginFilter<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        OriginFilter {
+            inner,
+            allowed_origins: self.allowed_origins.clone(),
+        }
+    }
+}
+
+/// Service rejecting the browser requests from origins which aren't allowed, see
+/// [OriginFilterLayer].
+#[derive(Debug, Clone)]
+pub struct OriginFilter<S> {
+    inner: S,
+    allowed_origins: Option<Arc<BTreeSet<String>>>,
+}
+
+impl<S> OriginFilter<S> {
+    fn is_allowed<B>(&self, request: &http::Request<B>) -> bool {
+        let (Some(allowed_origins), Some(origin)) =
+            (&self.allowed_origins, request.headers().get(ORIGIN))
+        else {
+            return true;
+        };
+
+        origin.to_str().is_ok_and(|origin| allowed_origins.contains(origin))
+    }
+}
+
+impl<S, B, ResBody> Service<http::Request<B>> for OriginFilter<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    ResBody: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        if !self.is_allowed(&request) {
+            let mut response = http::Response::new(ResBody::default());
+            *response.status_mut() = StatusCode::FORBIDDEN;
+            return Box::pin(async move { Ok(response) });
+        }
+
+        Box::pin(self.inner.call(request))
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    async fn status(allowed_origins: Option<&[&str]>, origin: Option<&str>) -> StatusCode {
+        let allowed_origins =
+            allowed_origins.map(|origins| origins.iter().map(ToString::to_string).collect());
+        let service = OriginFilterLayer::new(allowed_origins).layer(service_fn(
+            |_request: http::Request<()>| async { Ok::<_, Infallible>(http::Response::new(())) },
+        ));
+
+        let mut request = http::Request::new(());
+        if let Some(origin) = origin {
+            request.headers_mut().insert(ORIGIN, origin.parse().unwrap());
+        }
+
+        service.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn only_allowed_origins_are_served() {
+        let allowed = Some(["https://wallet.example"].as_slice());
+
+        assert_eq!(status(allowed, Some("https://wallet.example")).await, StatusCode::OK);
+        assert_eq!(status(allowed, Some("https://evil.example")).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(allowed, None).await, StatusCode::OK);
+
+        assert_eq!(status(None, Some("https://evil.example")).await, StatusCode::OK);
+    }
+}
@@ -1,15 +1,28 @@
-use std::net::ToSocketAddrs;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use api::RpcApi;
+use cors::OriginFilterLayer;
 use miden_node_proto::generated::rpc::api_server;
-use miden_node_utils::errors::ApiError;
+use miden_node_utils::{
+    errors::ApiError, freshness::SyncingFlagLayer, genesis::check_genesis_commitment,
+    request_id::RequestIdLayer, runtime::spawn_named,
+};
+use tls::{tls_incoming, ReloadingCertificate};
 use tokio::net::TcpListener;
 use tokio_stream::wrappers::TcpListenerStream;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::{config::RpcConfig, COMPONENT};
+use crate::{
+    audit::AuditLog,
+    config::{RpcConfig, RpcTransportConfig},
+    COMPONENT,
+};
 
 mod api;
+mod cache;
+mod cors;
+mod rate_limit;
+mod tls;
 
 /// Represents an initialized rpc component where the RPC connection is open, but not yet actively
 /// responding to requests.
@@ -20,40 +33,125 @@ mod api;
 pub struct Rpc {
     api_service: api_server::ApiServer<RpcApi>,
     listener: TcpListener,
+    local_addr: SocketAddr,
+    transport: RpcTransportConfig,
+    certificate: Option<Arc<ReloadingCertificate>>,
+    /// Time without a new block after which the node is reported as syncing.
+    max_staleness: Duration,
 }
 
 impl Rpc {
     pub async fn init(config: RpcConfig) -> Result<Self, ApiError> {
         info!(target: COMPONENT, %config, "Initializing server");
 
-        let api = api::RpcApi::from_config(&config)
+        let mut api = api::RpcApi::from_config(&config)
             .await
             .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?;
-        let api_service = api_server::ApiServer::new(api);
+        if let Some(expected) = &config.genesis_commitment {
+            let genesis_commitment = api.genesis_commitment().await.map_err(|status| {
+                ApiError::ApiInitialisationFailed(format!(
+                    "Failed to read the genesis block of the store: {}",
+                    status.message()
+                ))
+            })?;
+            check_genesis_commitment(Some(expected), genesis_commitment)
+                .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?;
+            info!(target: COMPONENT, %expected, "Genesis block commitment of the store checked");
+        }
+        if let Some(audit) = config.audit.clone() {
+            let directory = audit.directory.clone();
+            let audit_log = AuditLog::open(audit).map_err(|err| {
+                ApiError::ApiInitialisationFailed(format!(
+                    "Failed to open the audit log in {}: {err}",
+                    directory.display()
+                ))
+            })?;
+            info!(target: COMPONENT, directory = %directory.display(), "Audit log opened");
+            api = api.with_audit_log(audit_log);
+        }
+        let max_message_size = config.message_limits.max_message_size();
+        let api_service = api_server::ApiServer::new(api)
+            .max_decoding_message_size(max_message_size)
+            .max_encoding_message_size(max_message_size);
 
-        let addr = config
-            .endpoint
-            .to_socket_addrs()
-            .map_err(ApiError::EndpointToSocketFailed)?
-            .next()
-            .ok_or_else(|| ApiError::AddressResolutionFailed(config.endpoint.to_string()))?;
+        // Invalid certificates are reported before binding the listener
+        let certificate = config
+            .transport
+            .tls
+            .clone()
+            .map(ReloadingCertificate::load)
+            .transpose()
+            .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?
+            .map(Arc::new);
 
-        let listener = TcpListener::bind(addr).await?;
+        let listener = config.endpoint.bind().await?;
+        let local_addr = listener.local_addr()?;
 
-        info!(target: COMPONENT, "Server initialized");
+        if certificate.is_none() && !local_addr.ip().is_loopback() {
+            warn!(
+                target: COMPONENT,
+                %local_addr,
+                "RPC serves plaintext connections on a public interface, configure \
+                 `rpc.transport.tls` or terminate TLS in a reverse proxy"
+            );
+        }
 
-        Ok(Self { api_service, listener })
+        info!(target: COMPONENT, %local_addr, "Server initialized");
+
+        Ok(Self {
+            api_service,
+            listener,
+            local_addr,
+            transport: config.transport,
+            certificate,
+            max_staleness: config.freshness.max_staleness(),
+        })
+    }
+
+    /// Returns the address the RPC listens on, with the port chosen by the system if the
+    /// endpoint's port is 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
     }
 
     /// Serves the RPC API.
     ///
     /// Note: this blocks until the server dies.
     pub async fn serve(self) -> Result<(), ApiError> {
-        tonic::transport::Server::builder()
+        let router = tonic::transport::Server::builder()
             .accept_http1(true)
-            .add_service(tonic_web::enable(self.api_service))
-            .serve_with_incoming(TcpListenerStream::new(self.listener))
+            .http2_keepalive_interval(self.transport.http2_keepalive_interval())
+            .http2_keepalive_timeout(self.transport.http2_keepalive_timeout())
+            .max_concurrent_streams(self.transport.max_concurrent_streams)
+            .layer(RequestIdLayer)
+            .layer(SyncingFlagLayer::new(self.max_staleness))
+            .layer(OriginFilterLayer::new(self.transport.cors_allowed_origins))
+            .add_service(tonic_web::enable(self.api_service));
+
+        let Some(certificate) = self.certificate else {
+            return router
+                .serve_with_incoming(TcpListenerStream::new(self.listener))
+                .await
+                .map_err(ApiError::ApiServeFailed);
+        };
+
+        let acceptor = Arc::clone(&certificate)
+            .acceptor()
+            .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?;
+        let watcher =
+            self.transport.tls.is_some_and(|tls| tls.reload).then(|| {
+                spawn_named(&format!("{COMPONENT}:certificate_watch"), certificate.watch())
+            });
+
+        let result = router
+            .serve_with_incoming(tls_incoming(self.listener, acceptor))
             .await
-            .map_err(ApiError::ApiServeFailed)
+            .map_err(ApiError::ApiServeFailed);
+
+        if let Some(watcher) = watcher {
+            watcher.abort();
+        }
+
+        result
     }
 }
@@ -0,0 +1,61 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// A token bucket limiting the rate at which requests are served.
+///
+/// The bucket holds up to `capacity` tokens, and is refilled with one token every
+/// `refill_period`. Every served request takes one token from the bucket.
+pub struct RateLimiter {
+    capacity: u32,
+    refill_period: Duration,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: u32,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Returns a limiter serving bursts of up to `capacity` requests, and `capacity` requests per
+    /// `period` on average.
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        assert!(capacity > 0, "rate limiter capacity must be positive");
+
+        Self {
+            capacity,
+            refill_period: period / capacity,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token from the bucket, returns `false` if the request has to be rejected.
+    pub fn try_acquire(&self) -> bool {
+        let mut bucket = self.bucket.lock().expect("rate limiter lock poisoned");
+
+        let elapsed = bucket.last_refill.elapsed();
+        let refills = (elapsed.as_nanos() / self.refill_period.as_nanos().max(1))
+            .min(u128::from(self.capacity)) as u32;
+        if refills > 0 {
+            bucket.tokens = (bucket.tokens + refills).min(self.capacity);
+            // A full bucket doesn't accumulate the time elapsed since it was filled
+            bucket.last_refill = if bucket.tokens == self.capacity {
+                Instant::now()
+            } else {
+                bucket.last_refill + self.refill_period * refills
+            };
+        }
+
+        if bucket.tokens == 0 {
+            return false;
+        }
+
+        bucket.tokens -= 1;
+        true
+    }
+}
@@ -0,0 +1,351 @@
+//! TLS termination of the RPC listener.
+//!
+//! The certificate is served through a [ReloadingCertificate], which swaps it in place when its
+//! files change, so that new connections get the rotated certificate without rebinding the
+//! listener. Established connections keep the certificate of their handshake.
+
+use std::{
+    fmt::{Debug, Formatter},
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use miden_node_utils::runtime::spawn_named;
+use thiserror::Error;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::{
+    rustls::{
+        self,
+        crypto::{ring, CryptoProvider},
+        pki_types::{CertificateDer, PrivateKeyDer},
+        server::{ClientHello, ResolvesServerCert},
+        sign::CertifiedKey,
+        ServerConfig,
+    },
+    server::TlsStream,
+    TlsAcceptor,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, info, warn};
+
+use crate::{config::TlsConfig, COMPONENT};
+
+/// Interval at which the certificate files are checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Number of connections which completed their handshake, waiting to be served.
+const HANDSHAKEN_CONNECTIONS_BUFFER: usize = 64;
+
+// ERRORS
+// ================================================================================================
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("failed to read {path}: {source}")]
+    ReadFailed { path: PathBuf, source: io::Error },
+    #[error("no certificate found in {0}")]
+    MissingCertificate(PathBuf),
+    #[error("no private key found in {0}")]
+    MissingPrivateKey(PathBuf),
+    #[error("invalid private key in {path}: {source}")]
+    InvalidPrivateKey { path: PathBuf, source: rustls::Error },
+    #[error("invalid TLS configuration: {0}")]
+    InvalidConfig(#[from] rustls::Error),
+}
+
+// RELOADING CERTIFICATE
+// ================================================================================================
+
+/// Certificate of the RPC, along with the content of the files it was loaded from.
+struct LoadedCertificate {
+    files: (Vec<u8>, Vec<u8>),
+    certified_key: Arc<CertifiedKey>,
+}
+
+/// Certificate of the RPC, which can be reloaded from its files while it is served.
+pub struct ReloadingCertificate {
+    config: TlsConfig,
+    provider: Arc<CryptoProvider>,
+    loaded: RwLock<LoadedCertificate>,
+}
+
+impl ReloadingCertificate {
+    /// Loads the certificate and private key files of the configuration.
+    ///
+    /// # Errors
+    /// Returns an error if a file can't be read, or doesn't hold a valid certificate or key.
+    pub fn load(config: TlsConfig) -> Result<Self, TlsError> {
+        let provider = Arc::new(ring::default_provider());
+        let loaded = load_certificate(&config, &provider)?;
+
+        Ok(Self {
+            config,
+            provider,
+            loaded: RwLock::new(loaded),
+        })
+    }
+
+    /// Reloads the certificate if its files changed since it was last loaded.
+    ///
+    /// Returns `true` if the certificate was replaced. The current certificate is kept if the new
+    /// files are invalid, e.g. while they are being written.
+    ///
+    /// # Errors
+    /// Returns an error if the files changed, but don't hold a valid certificate and key.
+    pub fn reload(&self) -> Result<bool, TlsError> {
+        let files = read_files(&self.config)?;
+        if files == self.loaded.read().expect("the lock is never poisoned").files {
+            return Ok(false);
+        }
+
+        let certified_key = certified_key(&self.config, &files, &self.provider)?;
+        *self.loaded.write().expect("the lock is never poisoned") =
+            LoadedCertificate { files, certified_key };
+
+        Ok(true)
+    }
+
+    /// Checks the certificate files for changes every [RELOAD_INTERVAL], until the task is
+    /// aborted.
+    pub async fn watch(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match self.reload() {
+                Ok(true) => info!(
+                    target: COMPONENT,
+                    cert_path = %self.config.cert_path.display(),
+                    "TLS certificate reloaded"
+                ),
+                Ok(false) => {},
+                Err(err) => warn!(
+                    target: COMPONENT,
+                    %err,
+                    "Failed to reload the TLS certificate, keeping the current one"
+                ),
+            }
+        }
+    }
+
+    /// Returns an acceptor of TLS connections serving this certificate.
+    ///
+    /// Both HTTP/2 and HTTP/1.1 are negotiated, the latter for `grpc-web` clients.
+    pub fn acceptor(self: Arc<Self>) -> Result<TlsAcceptor, TlsError> {
+        let provider = Arc::clone(&self.provider);
+        let mut config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_cert_resolver(self);
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+impl Debug for ReloadingCertificate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReloadingCertificate").field("config", &self.config).finish()
+    }
+}
+
+impl ResolvesServerCert for ReloadingCertificate {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(Arc::clone(
+            &self.loaded.read().expect("the lock is never poisoned").certified_key,
+        ))
+    }
+}
+
+fn read_files(config: &TlsConfig) -> Result<(Vec<u8>, Vec<u8>), TlsError> {
+    let read = |path: &Path| {
+        std::fs::read(path)
+            .map_err(|source| TlsError::ReadFailed { path: path.to_path_buf(), source })
+    };
+
+    Ok((read(&config.cert_path)?, read(&config.key_path)?))
+}
+
+fn load_certificate(
+    config: &TlsConfig,
+    provider: &CryptoProvider,
+) -> Result<LoadedCertificate, TlsError> {
+    let files = read_files(config)?;
+    let certified_key = certified_key(config, &files, provider)?;
+
+    Ok(LoadedCertificate { files, certified_key })
+}
+
+/// Parses the PEM encoded certificate chain and private key.
+fn certified_key(
+    config: &TlsConfig,
+    (cert_pem, key_pem): &(Vec<u8>, Vec<u8>),
+    provider: &CryptoProvider,
+) -> Result<Arc<CertifiedKey>, TlsError> {
+    let read_failed =
+        |path: &Path, source| TlsError::ReadFailed { path: path.to_path_buf(), source };
+
+    let cert_chain = rustls_pemfile::certs(&mut cert_pem.as_slice())
+        .collect::<Result<Vec<CertificateDer<'static>>, _>>()
+        .map_err(|source| read_failed(&config.cert_path, source))?;
+    if cert_chain.is_empty() {
+        return Err(TlsError::MissingCertificate(config.cert_path.clone()));
+    }
+
+    let key: PrivateKeyDer<'static> = rustls_pemfile::private_key(&mut key_pem.as_slice())
+        .map_err(|source| read_failed(&config.key_path, source))?
+        .ok_or_else(|| TlsError::MissingPrivateKey(config.key_path.clone()))?;
+    let key = provider
+        .key_provider
+        .load_private_key(key)
+        .map_err(|source| TlsError::InvalidPrivateKey { path: config.key_path.clone(), source })?;
+
+    Ok(Arc::new(CertifiedKey::new(cert_chain, key)))
+}
+
+// INCOMING CONNECTIONS
+// ================================================================================================
+
+/// Returns the TLS connections accepted by the listener.
+///
+/// Every handshake runs in its own task, so that slow or failing clients don't hold back the
+/// others. Connections failing their handshake are dropped.
+pub fn tls_incoming(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+) -> ReceiverStream<io::Result<TlsStream<TcpStream>>> {
+    let (sender, receiver) = mpsc::channel(HANDSHAKEN_CONNECTIONS_BUFFER);
+
+    spawn_named(&format!("{COMPONENT}:tls_accept"), async move {
+        loop {
+            let (stream, peer_addr) = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        if sender.send(Err(err)).await.is_err() {
+                            return;
+                        }
+                        continue;
+                    },
+                },
+                _ = sender.closed() => return,
+            };
+
+            let acceptor = acceptor.clone();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                match acceptor.accept(stream).await {
+                    Ok(stream) => {
+                        let _ = sender.send(Ok(stream)).await;
+                    },
+                    Err(err) => debug!(target: COMPONENT, %peer_addr, %err, "TLS handshake failed"),
+                }
+            });
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use tokio_rustls::{
+        rustls::{pki_types::ServerName, ClientConfig, RootCertStore},
+        TlsConnector,
+    };
+    use tokio_stream::StreamExt;
+
+    use super::*;
+
+    /// Writes a new self-signed certificate for `localhost` to the files of the configuration,
+    /// returning it.
+    fn write_certificate(config: &TlsConfig) -> CertificateDer<'static> {
+        let rcgen::CertifiedKey { cert, key_pair } =
+            rcgen::generate_simple_self_signed(["localhost".to_string()]).unwrap();
+        std::fs::write(&config.cert_path, cert.pem()).unwrap();
+        std::fs::write(&config.key_path, key_pair.serialize_pem()).unwrap();
+
+        cert.der().clone()
+    }
+
+    /// Connects to the listener, trusting the `trusted` certificate, and returns the certificate
+    /// served by the listener.
+    async fn handshake(
+        listener_addr: std::net::SocketAddr,
+        trusted: &CertificateDer<'static>,
+    ) -> io::Result<CertificateDer<'static>> {
+        let mut roots = RootCertStore::empty();
+        roots.add(trusted.clone()).unwrap();
+        let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        let stream = TcpStream::connect(listener_addr).await?;
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let stream = TlsConnector::from(Arc::new(config)).connect(server_name, stream).await?;
+
+        Ok(stream.get_ref().1.peer_certificates().unwrap()[0].clone())
+    }
+
+    fn tls_config(dir: &Path) -> TlsConfig {
+        TlsConfig {
+            cert_path: dir.join("cert.pem"),
+            key_path: dir.join("key.pem"),
+            reload: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn rotated_certificates_are_served_without_rebinding() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = tls_config(dir.path());
+        let first = write_certificate(&config);
+
+        let certificate = Arc::new(ReloadingCertificate::load(config.clone()).unwrap());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listener_addr = listener.local_addr().unwrap();
+        let mut incoming = tls_incoming(listener, Arc::clone(&certificate).acceptor().unwrap());
+
+        assert_eq!(handshake(listener_addr, &first).await.unwrap(), first);
+        assert!(incoming.next().await.unwrap().is_ok());
+        assert!(!certificate.reload().unwrap(), "The files didn't change");
+
+        let second = write_certificate(&config);
+        assert!(certificate.reload().unwrap());
+        assert_eq!(handshake(listener_addr, &second).await.unwrap(), second);
+        assert!(incoming.next().await.unwrap().is_ok());
+
+        // Clients trusting only the previous certificate are rejected
+        assert!(handshake(listener_addr, &first).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn invalid_certificate_files_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = tls_config(dir.path());
+
+        let err = ReloadingCertificate::load(config.clone()).unwrap_err();
+        assert!(matches!(err, TlsError::ReadFailed { path, .. } if path == config.cert_path));
+
+        write_certificate(&config);
+        std::fs::write(&config.key_path, "not a key").unwrap();
+        let err = ReloadingCertificate::load(config.clone()).unwrap_err();
+        assert!(matches!(err, TlsError::MissingPrivateKey(path) if path == config.key_path));
+
+        // A broken rotation keeps the current certificate
+        write_certificate(&config);
+        let certificate = ReloadingCertificate::load(config.clone()).unwrap();
+        std::fs::write(&config.cert_path, "").unwrap();
+        assert!(matches!(certificate.reload(), Err(TlsError::MissingCertificate(_))));
+    }
+}
@@ -0,0 +1,243 @@
+//! Account SMT, along with its revisions at the most recent blocks.
+//!
+//! Block builders and external verifiers need witnesses of several accounts against the same
+//! account root, but a block can be applied in-between their requests. Instead of keeping copies
+//! of the whole tree, every applied block records the values its update replaced, i.e. the
+//! previous values of the leaves of its updated accounts and of their ancestors. The witnesses
+//! against the tree of a recent block are the witnesses against the latest tree, with the nodes
+//! updated since then replaced by their values at that block.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use miden_objects::{
+    crypto::{
+        hash::rpo::{Rpo256, RpoDigest},
+        merkle::{
+            LeafIndex, MerkleError, MerklePath, MutationSet, NodeIndex, SimpleSmt, ValuePath,
+        },
+    },
+    Word, ACCOUNT_TREE_DEPTH,
+};
+
+use crate::types::BlockNumber;
+
+/// Account SMT, with the revisions of the [ACCOUNT_TREE_REVISIONS](crate::ACCOUNT_TREE_REVISIONS)
+/// most recent blocks.
+#[derive(Debug, Clone)]
+pub struct AccountTree {
+    tree: SimpleSmt<ACCOUNT_TREE_DEPTH>,
+    /// Number of the block the tree is at.
+    block_num: BlockNumber,
+    /// Values replaced by the most recent blocks, in ascending block number order.
+    revisions: VecDeque<Revision>,
+    max_revisions: usize,
+}
+
+/// Values of the nodes updated by a block, before the block.
+#[derive(Debug, Clone)]
+struct Revision {
+    block_num: BlockNumber,
+    previous_nodes: BTreeMap<NodeIndex, RpoDigest>,
+}
+
+/// Update of the account tree by a block, see [AccountTree::compute_update].
+pub struct AccountTreeUpdate {
+    mutations: MutationSet<ACCOUNT_TREE_DEPTH, LeafIndex<ACCOUNT_TREE_DEPTH>, Word>,
+    previous_nodes: BTreeMap<NodeIndex, RpoDigest>,
+}
+
+impl AccountTreeUpdate {
+    /// Returns the root of the tree after the update.
+    pub fn root(&self) -> RpoDigest {
+        self.mutations.root()
+    }
+}
+
+impl AccountTree {
+    /// Wraps the account tree of block `block_num`, retaining the revisions of up to
+    /// `max_revisions` blocks applied from now on.
+    pub fn new(
+        tree: SimpleSmt<ACCOUNT_TREE_DEPTH>,
+        block_num: BlockNumber,
+        max_revisions: usize,
+    ) -> Self {
+        Self {
+            tree,
+            block_num,
+            revisions: VecDeque::new(),
+            max_revisions,
+        }
+    }
+
+    /// Returns the root of the latest tree.
+    pub fn root(&self) -> RpoDigest {
+        self.tree.root()
+    }
+
+    /// Returns the witness of the leaf in the latest tree.
+    pub fn open(&self, leaf_index: &LeafIndex<ACCOUNT_TREE_DEPTH>) -> ValuePath {
+        self.tree.open(leaf_index)
+    }
+
+    /// Returns the oldest block whose tree is retained.
+    pub fn oldest_block_num(&self) -> BlockNumber {
+        self.revisions.front().map_or(self.block_num, |revision| revision.block_num - 1)
+    }
+
+    /// Returns the witness of the leaf in the tree of block `block_num`, or [None] if the tree of
+    /// this block is not retained.
+    pub fn open_at(
+        &self,
+        leaf_index: &LeafIndex<ACCOUNT_TREE_DEPTH>,
+        block_num: BlockNumber,
+    ) -> Option<ValuePath> {
+        if block_num > self.block_num || block_num < self.oldest_block_num() {
+            return None;
+        }
+
+        let opening = self.tree.open(leaf_index);
+        if block_num == self.block_num {
+            return Some(opening);
+        }
+
+        // The value of a node at `block_num` was recorded by the first later block updating it
+        let later_revisions: Vec<_> = self
+            .revisions
+            .iter()
+            .filter(|revision| revision.block_num > block_num)
+            .collect();
+        let node_at = |index: NodeIndex, latest: RpoDigest| {
+            later_revisions
+                .iter()
+                .find_map(|revision| revision.previous_nodes.get(&index))
+                .copied()
+                .unwrap_or(latest)
+        };
+
+        let mut index = leaf_node_index(leaf_index);
+        let value = node_at(index, opening.value);
+        let mut path = Vec::with_capacity(opening.path.len());
+        for sibling in opening.path.iter() {
+            path.push(node_at(index.sibling(), *sibling));
+            index.move_up();
+        }
+
+        Some(ValuePath { value, path: MerklePath::new(path) })
+    }
+
+    /// Computes the update of the tree by the next block, setting the given leaves.
+    pub fn compute_update(
+        &self,
+        leaves: impl IntoIterator<Item = (LeafIndex<ACCOUNT_TREE_DEPTH>, Word)>,
+    ) -> AccountTreeUpdate {
+        let leaves: Vec<_> = leaves.into_iter().collect();
+
+        // Records the values of the updated leaves and of their ancestors, computing them from the
+        // witnesses of the leaves
+        let mut previous_nodes = BTreeMap::new();
+        for (leaf_index, _) in &leaves {
+            let opening = self.tree.open(leaf_index);
+            let mut index = leaf_node_index(leaf_index);
+            let mut node = opening.value;
+            for sibling in opening.path.iter() {
+                previous_nodes.insert(index, node);
+                node = if index.is_value_odd() {
+                    Rpo256::merge(&[*sibling, node])
+                } else {
+                    Rpo256::merge(&[node, *sibling])
+                };
+                index.move_up();
+            }
+        }
+
+        AccountTreeUpdate {
+            mutations: self.tree.compute_mutations(leaves),
+            previous_nodes,
+        }
+    }
+
+    /// Applies the update of the tree by the next block, retaining the tree before the update.
+    pub fn apply_update(&mut self, update: AccountTreeUpdate) -> Result<(), MerkleError> {
+        self.tree.apply_mutations(update.mutations)?;
+        self.block_num += 1;
+
+        if self.max_revisions > 0 {
+            if self.revisions.len() == self.max_revisions {
+                self.revisions.pop_front();
+            }
+            self.revisions.push_back(Revision {
+                block_num: self.block_num,
+                previous_nodes: update.previous_nodes,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the index of the node of the leaf.
+fn leaf_node_index(leaf_index: &LeafIndex<ACCOUNT_TREE_DEPTH>) -> NodeIndex {
+    NodeIndex::new(ACCOUNT_TREE_DEPTH, leaf_index.value())
+        .expect("leaf indices are within the tree depth")
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{Felt, ZERO};
+
+    use super::*;
+
+    fn leaf(value: u64) -> Word {
+        [Felt::new(value), ZERO, ZERO, ZERO]
+    }
+
+    #[test]
+    fn witnesses_of_recent_blocks_match_their_trees() {
+        let leaves = |values: &[(u64, u64)]| -> Vec<_> {
+            values
+                .iter()
+                .map(|(index, value)| (LeafIndex::new_max_depth(*index), leaf(*value)))
+                .collect()
+        };
+        let blocks = [
+            leaves(&[(1, 10), (u64::MAX, 20)]),
+            leaves(&[(1, 11), (2, 30)]),
+            leaves(&[]),
+            leaves(&[(2, 31), (3, 40)]),
+        ];
+
+        // Trees of the successive blocks, starting from the genesis tree
+        let genesis = SimpleSmt::with_leaves([(0, leaf(1))]).unwrap();
+        let mut trees = vec![genesis.clone()];
+        let mut account_tree = AccountTree::new(genesis, 0, 3);
+        for (block_num, block) in blocks.iter().enumerate() {
+            let mut tree = trees.last().unwrap().clone();
+            for (index, value) in block {
+                tree.insert(*index, *value);
+            }
+
+            let update = account_tree.compute_update(block.clone());
+            assert_eq!(update.root(), tree.root());
+            account_tree.apply_update(update).unwrap();
+            assert_eq!(account_tree.block_num, block_num as BlockNumber + 1);
+            trees.push(tree);
+        }
+
+        // Only the trees of the 3 most recent blocks before the latest one are retained
+        assert_eq!(account_tree.oldest_block_num(), 1);
+        for index in [0, 1, 2, 3, 4, u64::MAX] {
+            let leaf_index = LeafIndex::new_max_depth(index);
+            assert!(account_tree.open_at(&leaf_index, 0).is_none());
+            assert!(account_tree.open_at(&leaf_index, 5).is_none());
+            for block_num in 1..=4 {
+                let expected = trees[block_num].open(&leaf_index);
+                let witness = account_tree.open_at(&leaf_index, block_num as BlockNumber).unwrap();
+                assert_eq!(witness.value, expected.value);
+                assert_eq!(witness.path, expected.path);
+            }
+        }
+    }
+}
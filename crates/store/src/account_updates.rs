@@ -0,0 +1,170 @@
+//! Notifications of the account updates of the committed blocks.
+//!
+//! Indexers mirroring a few public accounts subscribe to their updates with
+//! `SubscribeAccountUpdates`, instead of polling the accounts every block. The store publishes the
+//! account updates of every block once committed, and every subscription filters its accounts.
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use miden_objects::block::BlockAccountUpdate;
+use thiserror::Error;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{
+    types::{AccountId, BlockNumber},
+    ACCOUNT_UPDATES_CAPACITY,
+};
+
+// ACCOUNT UPDATE EVENTS
+// ================================================================================================
+
+/// Account updates of an applied block.
+#[derive(Debug)]
+pub struct BlockAccountUpdates {
+    pub block_num: BlockNumber,
+    pub updates: Vec<BlockAccountUpdate>,
+}
+
+/// Broadcasts the account updates of the applied blocks to their subscribers.
+///
+/// Updates are only buffered for the live subscriptions, and dropped if there is none. A
+/// subscription falling more than [ACCOUNT_UPDATES_CAPACITY] blocks behind is ended.
+#[derive(Debug)]
+pub struct AccountUpdateEvents {
+    sender: broadcast::Sender<Arc<BlockAccountUpdates>>,
+}
+
+impl Default for AccountUpdateEvents {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(ACCOUNT_UPDATES_CAPACITY);
+
+        Self { sender }
+    }
+}
+
+impl AccountUpdateEvents {
+    /// Returns a subscription to the updates of the accounts by the blocks published from now on.
+    ///
+    /// The subscription ends when it is dropped.
+    pub fn subscribe(&self, account_ids: BTreeSet<AccountId>) -> AccountUpdatesSubscription {
+        AccountUpdatesSubscription {
+            account_ids,
+            receiver: self.sender.subscribe(),
+        }
+    }
+
+    /// Publishes the account updates of an applied block.
+    pub fn publish(&self, updates: BlockAccountUpdates) {
+        // Sending only fails if there is no subscription
+        let _ = self.sender.send(Arc::new(updates));
+    }
+}
+
+/// Subscription to the updates of a set of accounts.
+#[derive(Debug)]
+pub struct AccountUpdatesSubscription {
+    account_ids: BTreeSet<AccountId>,
+    receiver: broadcast::Receiver<Arc<BlockAccountUpdates>>,
+}
+
+impl AccountUpdatesSubscription {
+    /// Returns the updates of the subscribed accounts by the next block updating any of them,
+    /// along with the number of that block.
+    ///
+    /// # Errors
+    /// Returns an error if the subscription fell too far behind, after which it must be dropped.
+    pub async fn next(
+        &mut self,
+    ) -> Result<(BlockNumber, Vec<BlockAccountUpdate>), AccountSubscriptionError> {
+        loop {
+            let block = match self.receiver.recv().await {
+                Ok(block) => block,
+                Err(RecvError::Lagged(skipped)) => {
+                    return Err(AccountSubscriptionError::Lagged(skipped))
+                },
+                Err(RecvError::Closed) => return Err(AccountSubscriptionError::Closed),
+            };
+
+            let updates: Vec<_> = block
+                .updates
+                .iter()
+                .filter(|update| self.account_ids.contains(&AccountId::from(update.account_id())))
+                .cloned()
+                .collect();
+            if !updates.is_empty() {
+                return Ok((block.block_num, updates));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AccountSubscriptionError {
+    #[error("subscription fell {0} blocks behind")]
+    Lagged(u64),
+    #[error("no more blocks are applied")]
+    Closed,
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_node_test_utils::mock_account_id;
+    use miden_objects::{accounts::delta::AccountUpdateDetails, Digest};
+
+    use super::*;
+
+    fn account_ids(nums: &[u8]) -> BTreeSet<AccountId> {
+        nums.iter().map(|num| mock_account_id(*num).into()).collect()
+    }
+
+    fn updates(block_num: BlockNumber, account_ids: &[u8]) -> BlockAccountUpdates {
+        let updates = account_ids
+            .iter()
+            .map(|num| {
+                BlockAccountUpdate::new(
+                    mock_account_id(*num),
+                    Digest::default(),
+                    AccountUpdateDetails::Private,
+                    vec![],
+                )
+            })
+            .collect();
+
+        BlockAccountUpdates { block_num, updates }
+    }
+
+    #[tokio::test]
+    async fn subscriptions_only_receive_their_accounts() {
+        let events = AccountUpdateEvents::default();
+        let mut subscription = events.subscribe(account_ids(&[1, 2]));
+
+        events.publish(updates(1, &[3]));
+        events.publish(updates(2, &[1, 3]));
+        events.publish(updates(3, &[2, 1]));
+
+        let (block_num, updates) = subscription.next().await.unwrap();
+        assert_eq!(block_num, 2);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].account_id(), mock_account_id(1));
+
+        let (block_num, updates) = subscription.next().await.unwrap();
+        assert_eq!(block_num, 3);
+        let account_ids: Vec<_> = updates.iter().map(BlockAccountUpdate::account_id).collect();
+        assert_eq!(account_ids, [mock_account_id(2), mock_account_id(1)]);
+    }
+
+    #[tokio::test]
+    async fn lagging_subscriptions_are_ended() {
+        let events = AccountUpdateEvents::default();
+        let mut subscription = events.subscribe(account_ids(&[1]));
+
+        for block_num in 0..=ACCOUNT_UPDATES_CAPACITY as u32 {
+            events.publish(updates(block_num, &[1]));
+        }
+
+        assert_eq!(subscription.next().await, Err(AccountSubscriptionError::Lagged(1)));
+    }
+}
@@ -0,0 +1,257 @@
+//! Utilities to describe the stored accounts.
+
+use miden_node_proto::generated::account::{
+    AccountStorageSlotHeader as AccountStorageSlotHeaderPb,
+    AccountVaultAsset as AccountVaultAssetPb,
+};
+use miden_objects::{
+    accounts::{AccountStorage, StorageSlot},
+    assets::{Asset, AssetVault},
+    crypto::hash::rpo::{Rpo256, RpoDigest},
+    utils::Serializable,
+};
+
+// STORAGE SLOT HEADER
+// ================================================================================================
+
+/// Layout metadata of a slot of an account's storage, without its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageSlotHeader {
+    /// Index of the slot in the account's storage.
+    pub index: u8,
+    pub kind: StorageSlotKind,
+}
+
+/// Type of a storage slot, with the commitment to its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageSlotKind {
+    /// A single value, committed to by its hash.
+    Value { commitment: RpoDigest },
+    /// A storage map, committed to by its root.
+    Map { root: RpoDigest, entry_count: usize },
+}
+
+impl From<StorageSlotHeader> for AccountStorageSlotHeaderPb {
+    fn from(header: StorageSlotHeader) -> Self {
+        let (is_map, commitment, map_entry_count) = match header.kind {
+            StorageSlotKind::Value { commitment } => (false, commitment, None),
+            StorageSlotKind::Map { root, entry_count } => (true, root, Some(entry_count as u64)),
+        };
+
+        Self {
+            index: header.index.into(),
+            is_map,
+            commitment: Some(commitment.into()),
+            map_entry_count,
+        }
+    }
+}
+
+/// Returns the headers of the slots of the account storage, ordered by index.
+pub fn storage_slot_headers(storage: &AccountStorage) -> Vec<StorageSlotHeader> {
+    storage
+        .slots()
+        .iter()
+        .enumerate()
+        .map(|(index, slot)| {
+            let kind = match slot {
+                StorageSlot::Value(value) => {
+                    StorageSlotKind::Value { commitment: Rpo256::hash_elements(value) }
+                },
+                StorageSlot::Map(map) => StorageSlotKind::Map {
+                    root: map.root(),
+                    entry_count: map.entries().count(),
+                },
+            };
+
+            StorageSlotHeader {
+                index: index.try_into().expect("account storage has at most 255 slots"),
+                kind,
+            }
+        })
+        .collect()
+}
+
+// VAULT ASSETS
+// ================================================================================================
+
+/// An asset of an account's vault, along with its key in the vault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VaultAsset {
+    pub vault_key: RpoDigest,
+    pub asset: Asset,
+}
+
+impl From<VaultAsset> for AccountVaultAssetPb {
+    fn from(VaultAsset { vault_key, asset }: VaultAsset) -> Self {
+        let amount = match asset {
+            Asset::Fungible(asset) => Some(asset.amount()),
+            Asset::NonFungible(_) => None,
+        };
+
+        Self {
+            vault_key: Some(vault_key.into()),
+            faucet_id: Some(asset.faucet_id().into()),
+            amount,
+            asset: asset.to_bytes(),
+        }
+    }
+}
+
+/// Returns up to `limit` assets of the vault ordered by vault key, starting after the key `after`.
+pub fn vault_assets_page(
+    vault: &AssetVault,
+    after: Option<RpoDigest>,
+    limit: usize,
+) -> Vec<VaultAsset> {
+    let mut assets: Vec<_> = vault
+        .assets()
+        .map(|asset| VaultAsset {
+            vault_key: asset.vault_key().into(),
+            asset,
+        })
+        .filter(|asset| after.map_or(true, |after| asset.vault_key > after))
+        .collect();
+    assets.sort_unstable_by_key(|asset| asset.vault_key);
+    assets.truncate(limit);
+
+    assets
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{
+        accounts::{
+            account_id::testing::{
+                ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN_1,
+                ACCOUNT_ID_NON_FUNGIBLE_FAUCET_ON_CHAIN,
+            },
+            AccountId, AccountStorageDelta, StorageMap, StorageMapDelta,
+        },
+        assets::{FungibleAsset, NonFungibleAsset, NonFungibleAssetDetails},
+        Felt, Word,
+    };
+
+    use super::*;
+
+    fn word(n: u64) -> Word {
+        [Felt::new(n), Felt::new(0), Felt::new(0), Felt::new(0)]
+    }
+
+    #[test]
+    fn slot_headers_follow_storage_updates() {
+        let map = StorageMap::with_entries([(word(1).into(), word(10))]).unwrap();
+        let mut storage = AccountStorage::new(vec![
+            StorageSlot::Value(word(5)),
+            StorageSlot::Map(map.clone()),
+            StorageSlot::empty_value(),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            storage_slot_headers(&storage),
+            [
+                StorageSlotHeader {
+                    index: 0,
+                    kind: StorageSlotKind::Value {
+                        commitment: Rpo256::hash_elements(&word(5))
+                    },
+                },
+                StorageSlotHeader {
+                    index: 1,
+                    kind: StorageSlotKind::Map { root: map.root(), entry_count: 1 },
+                },
+                StorageSlotHeader {
+                    index: 2,
+                    kind: StorageSlotKind::Value {
+                        commitment: Rpo256::hash_elements(&Word::default()),
+                    },
+                },
+            ]
+        );
+
+        // Entries added to the map are counted, and updated values change the commitments
+        let map_delta = StorageMapDelta::from_iters([], [(word(2), word(20)), (word(3), word(30))]);
+        let delta = AccountStorageDelta::from_iters([], [(2, word(7))], [(1, map_delta)]);
+        storage.apply_delta(&delta).unwrap();
+
+        let headers = storage_slot_headers(&storage);
+        assert_eq!(headers.len(), 3);
+        assert_eq!(
+            headers[0].kind,
+            StorageSlotKind::Value {
+                commitment: Rpo256::hash_elements(&word(5))
+            }
+        );
+        assert!(matches!(
+            headers[1].kind,
+            StorageSlotKind::Map { root, entry_count: 3 } if root != map.root()
+        ));
+        assert_eq!(
+            headers[2].kind,
+            StorageSlotKind::Value {
+                commitment: Rpo256::hash_elements(&word(7))
+            }
+        );
+
+        // Removed entries are not counted anymore
+        let map_delta = StorageMapDelta::from_iters([word(1)], []);
+        let delta = AccountStorageDelta::from_iters([], [], [(1, map_delta)]);
+        storage.apply_delta(&delta).unwrap();
+        assert!(matches!(
+            storage_slot_headers(&storage)[1].kind,
+            StorageSlotKind::Map { entry_count: 2, .. }
+        ));
+
+        // Map slots are reported as maps in the protobuf representation
+        let proto: Vec<AccountStorageSlotHeaderPb> =
+            storage_slot_headers(&storage).into_iter().map(Into::into).collect();
+        assert_eq!(
+            proto.iter().map(|slot| (slot.is_map, slot.map_entry_count)).collect::<Vec<_>>(),
+            [(false, None), (true, Some(2)), (false, None)]
+        );
+    }
+
+    #[test]
+    fn vault_assets_are_paged_by_vault_key() {
+        let faucet = |id| AccountId::try_from(id).unwrap();
+        let non_fungible = |data: u8| -> Asset {
+            let details = NonFungibleAssetDetails::new(
+                faucet(ACCOUNT_ID_NON_FUNGIBLE_FAUCET_ON_CHAIN),
+                vec![data],
+            )
+            .unwrap();
+            NonFungibleAsset::new(&details).unwrap().into()
+        };
+        let vault = AssetVault::new(&[
+            FungibleAsset::new(faucet(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN), 100)
+                .unwrap()
+                .into(),
+            FungibleAsset::new(faucet(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN_1), 5)
+                .unwrap()
+                .into(),
+            non_fungible(1),
+            non_fungible(2),
+        ])
+        .unwrap();
+
+        let all = vault_assets_page(&vault, None, 10);
+        assert_eq!(all.len(), 4);
+        assert!(all.windows(2).all(|pair| pair[0].vault_key < pair[1].vault_key));
+
+        // Pages resume after the key of the last asset of the previous page
+        let first = vault_assets_page(&vault, None, 3);
+        let second = vault_assets_page(&vault, Some(first[2].vault_key), 3);
+        assert_eq!([first, second].concat(), all);
+        assert!(vault_assets_page(&vault, Some(all[3].vault_key), 3).is_empty());
+
+        // Only fungible assets have an amount
+        let proto: Vec<AccountVaultAssetPb> = all.into_iter().map(Into::into).collect();
+        let mut amounts: Vec<_> = proto.iter().map(|asset| asset.amount).collect();
+        amounts.sort_unstable();
+        assert_eq!(amounts, [None, None, Some(5), Some(100)]);
+    }
+}
@@ -0,0 +1,67 @@
+//! Provenance of the committed blocks.
+//!
+//! The block producer reports its identity and the submission attempt along with every block. The
+//! store keeps the latest submission of every block, to debug blocks submitted several times, e.g.
+//! retried after a timeout or submitted by concurrent block producers. The provenance has no
+//! consensus meaning, and blocks applied without it, e.g. by stress-test seeding, have none.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use miden_node_proto::generated::block::{
+    BlockProducerMetadata, BlockProvenance as BlockProvenancePb,
+};
+
+use crate::{types::BlockNumber, MAX_PRODUCER_ID_LEN};
+
+// BLOCK PROVENANCE
+// ================================================================================================
+
+/// Submission of a committed block by a block producer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockProvenance {
+    pub block_num: BlockNumber,
+    /// Identifier of the block producer instance, truncated to [MAX_PRODUCER_ID_LEN] characters.
+    pub producer_id: String,
+    /// Submission attempt, starting at 1.
+    pub attempt: u32,
+    /// Time the block producer first submitted the block, in milliseconds since the UNIX epoch.
+    pub produced_at: u64,
+    /// Time the store received the submission, in milliseconds since the UNIX epoch.
+    pub received_at: u64,
+}
+
+impl BlockProvenance {
+    /// Records the submission of the block `block_num` with the given metadata, received at
+    /// `received_at`.
+    pub fn new(
+        block_num: BlockNumber,
+        producer: BlockProducerMetadata,
+        received_at: SystemTime,
+    ) -> Self {
+        let received_at = received_at
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX));
+
+        Self {
+            block_num,
+            producer_id: producer.producer_id.chars().take(MAX_PRODUCER_ID_LEN).collect(),
+            attempt: producer.attempt,
+            produced_at: producer.produced_at,
+            received_at,
+        }
+    }
+}
+
+impl From<BlockProvenance> for BlockProvenancePb {
+    fn from(provenance: BlockProvenance) -> Self {
+        Self {
+            block_num: provenance.block_num,
+            producer: Some(BlockProducerMetadata {
+                producer_id: provenance.producer_id,
+                attempt: provenance.attempt,
+                produced_at: provenance.produced_at,
+            }),
+            received_at: provenance.received_at,
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Durations of the production stages of the committed blocks.
+//!
+//! The block producer reports how long it took to fetch the inputs of a block, to propose it and
+//! to prove it. The store adds the time it took to commit the block, and keeps the durations of
+//! every block for the analysis of their trends over periods longer than the retention of traces.
+
+use std::time::Duration;
+
+use miden_node_proto::generated::block::{
+    BlockProductionStats as BlockProductionStatsPb, BlockStageDurations,
+    StageDurationPercentiles as StageDurationPercentilesPb,
+};
+
+use crate::types::BlockNumber;
+
+/// Names of the block production stages, in the order of the percentiles of a
+/// [StageDurationPercentiles] list.
+pub const BLOCK_PRODUCTION_STAGES: [&str; 5] =
+    ["inputs_fetch", "propose", "prove", "commit", "total"];
+
+// BLOCK PRODUCTION STATS
+// ================================================================================================
+
+/// Durations of the production stages of a block, in microseconds.
+///
+/// The stages measured by the block producer are [None] for blocks it didn't report them for,
+/// e.g. blocks applied by other tools, and all stages are [None] for blocks committed before the
+/// durations were recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockProductionStats {
+    pub block_num: BlockNumber,
+    pub inputs_fetch_us: Option<u64>,
+    pub propose_us: Option<u64>,
+    pub prove_us: Option<u64>,
+    pub commit_us: Option<u64>,
+    pub total_us: Option<u64>,
+}
+
+impl BlockProductionStats {
+    /// Combines the stage durations reported by the block producer, if any, with the time it took
+    /// to commit the block.
+    pub fn new(
+        block_num: BlockNumber,
+        stage_durations: Option<BlockStageDurations>,
+        commit: Duration,
+    ) -> Self {
+        let commit_us = u64::try_from(commit.as_micros()).unwrap_or(u64::MAX);
+
+        Self {
+            block_num,
+            inputs_fetch_us: stage_durations.map(|durations| durations.inputs_fetch_us),
+            propose_us: stage_durations.map(|durations| durations.propose_us),
+            prove_us: stage_durations.map(|durations| durations.prove_us),
+            commit_us: Some(commit_us),
+            total_us: stage_durations.map(|durations| durations.build_us.saturating_add(commit_us)),
+        }
+    }
+
+    /// Returns the durations of the stages, in the order of [BLOCK_PRODUCTION_STAGES].
+    fn stages(&self) -> [Option<u64>; 5] {
+        [
+            self.inputs_fetch_us,
+            self.propose_us,
+            self.prove_us,
+            self.commit_us,
+            self.total_us,
+        ]
+    }
+}
+
+impl From<BlockProductionStats> for BlockProductionStatsPb {
+    fn from(stats: BlockProductionStats) -> Self {
+        Self {
+            block_num: stats.block_num,
+            inputs_fetch_us: stats.inputs_fetch_us,
+            propose_us: stats.propose_us,
+            prove_us: stats.prove_us,
+            commit_us: stats.commit_us,
+            total_us: stats.total_us,
+        }
+    }
+}
+
+// STAGE DURATION PERCENTILES
+// ================================================================================================
+
+/// Percentiles of the duration of a block production stage, in microseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageDurationPercentiles {
+    pub stage: &'static str,
+    /// Number of blocks for which the duration of the stage is known.
+    pub count: usize,
+    pub p50_us: u64,
+    pub p95_us: u64,
+}
+
+impl StageDurationPercentiles {
+    /// Computes the percentiles of the durations of every stage over the given blocks.
+    ///
+    /// Blocks for which the duration of a stage is unknown are left out of the percentiles of the
+    /// stage. The percentiles of stages without any known duration are zero.
+    pub fn from_stats(stats: &[BlockProductionStats]) -> Vec<Self> {
+        BLOCK_PRODUCTION_STAGES
+            .into_iter()
+            .enumerate()
+            .map(|(index, stage)| {
+                let mut durations: Vec<u64> =
+                    stats.iter().filter_map(|stats| stats.stages()[index]).collect();
+                durations.sort_unstable();
+
+                Self {
+                    stage,
+                    count: durations.len(),
+                    p50_us: percentile(&durations, 50),
+                    p95_us: percentile(&durations, 95),
+                }
+            })
+            .collect()
+    }
+}
+
+impl From<StageDurationPercentiles> for StageDurationPercentilesPb {
+    fn from(percentiles: StageDurationPercentiles) -> Self {
+        Self {
+            stage: percentiles.stage.to_string(),
+            count: percentiles.count.try_into().unwrap_or(u32::MAX),
+            p50_us: percentiles.p50_us,
+            p95_us: percentiles.p95_us,
+        }
+    }
+}
+
+/// Returns the nearest-rank percentile of the sorted values, or zero if there are none.
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_combine_reported_durations_with_commit() {
+        let reported = BlockStageDurations {
+            inputs_fetch_us: 10,
+            propose_us: 20,
+            prove_us: 30,
+            build_us: 61,
+        };
+
+        let stats = BlockProductionStats::new(7, Some(reported), Duration::from_micros(5));
+        assert_eq!(
+            stats,
+            BlockProductionStats {
+                block_num: 7,
+                inputs_fetch_us: Some(10),
+                propose_us: Some(20),
+                prove_us: Some(30),
+                commit_us: Some(5),
+                total_us: Some(66),
+            }
+        );
+
+        // Blocks applied without reported durations only know their commit time
+        let stats = BlockProductionStats::new(8, None, Duration::from_micros(5));
+        assert_eq!(
+            stats,
+            BlockProductionStats {
+                block_num: 8,
+                commit_us: Some(5),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn percentiles_skip_unknown_durations() {
+        let mut stats: Vec<_> = (1..=100)
+            .map(|prove_us| BlockProductionStats {
+                block_num: prove_us as BlockNumber,
+                prove_us: Some(prove_us),
+                commit_us: Some(1),
+                ..Default::default()
+            })
+            .collect();
+        stats.push(BlockProductionStats { block_num: 101, ..Default::default() });
+
+        let percentiles = StageDurationPercentiles::from_stats(&stats);
+        assert_eq!(
+            percentiles.iter().map(|percentiles| percentiles.stage).collect::<Vec<_>>(),
+            BLOCK_PRODUCTION_STAGES
+        );
+
+        let prove = percentiles[2];
+        assert_eq!((prove.count, prove.p50_us, prove.p95_us), (100, 50, 95));
+        let commit = percentiles[3];
+        assert_eq!((commit.count, commit.p50_us, commit.p95_us), (100, 1, 1));
+        let inputs_fetch = percentiles[0];
+        assert_eq!((inputs_fetch.count, inputs_fetch.p50_us, inputs_fetch.p95_us), (0, 0, 0));
+
+        assert_eq!(percentile(&[3], 95), 3);
+        assert_eq!(percentile(&[1, 2], 50), 1);
+        assert_eq!(percentile(&[1, 2], 95), 2);
+    }
+}
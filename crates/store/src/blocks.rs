@@ -1,15 +1,53 @@
-use std::{io::ErrorKind, path::PathBuf};
+use std::{
+    ffi::OsStr,
+    io::ErrorKind,
+    path::PathBuf,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use miden_objects::GENESIS_BLOCK;
+use tokio::io::AsyncWriteExt;
+
+use crate::types::BlockNumber;
+
+/// Name of the file recording the height below which blocks have been pruned.
+const PRUNED_BELOW_FILE: &str = "pruned_below";
+
+/// Summary of the work done by [BlockStore::prune_below].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PrunedBlocks {
+    /// Number of block files removed.
+    pub num_blocks: usize,
+    /// Total size of the removed block files, in bytes.
+    pub freed_bytes: u64,
+}
 
 #[derive(Debug)]
 pub struct BlockStore {
     store_dir: PathBuf,
+    /// Blocks in the range `1..pruned_below` are no longer served by this store. The genesis block
+    /// is never pruned.
+    pruned_below: AtomicU32,
 }
 
 impl BlockStore {
     pub async fn new(store_dir: PathBuf) -> Result<Self, std::io::Error> {
         tokio::fs::create_dir_all(&store_dir).await?;
 
-        Ok(Self { store_dir })
+        let pruned_below = match tokio::fs::read_to_string(store_dir.join(PRUNED_BELOW_FILE)).await
+        {
+            Ok(data) => data
+                .trim()
+                .parse()
+                .map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == ErrorKind::NotFound => 0,
+            Err(err) => return Err(err),
+        };
+
+        Ok(Self {
+            store_dir,
+            pruned_below: AtomicU32::new(pruned_below),
+        })
     }
 
     pub async fn load_block(&self, block_num: u32) -> Result<Option<Vec<u8>>, std::io::Error> {
@@ -38,6 +76,116 @@ impl BlockStore {
         std::fs::write(block_path, data)
     }
 
+    /// Returns `true` if the data of the specified block has been pruned from this store.
+    pub fn is_pruned(&self, block_num: BlockNumber) -> bool {
+        block_num != GENESIS_BLOCK && block_num < self.pruned_below.load(Ordering::Acquire)
+    }
+
+    /// Returns the number below which the data of blocks has been pruned, see [Self::is_pruned].
+    pub fn pruned_below(&self) -> BlockNumber {
+        self.pruned_below.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of the oldest block whose data is stored, besides the genesis block, or
+    /// [None] if there is none.
+    ///
+    /// The blocks are looked up on disk, so the result reflects the files actually retained, e.g.
+    /// after the operator removed files by hand. Files left behind by an interrupted pruning run
+    /// are ignored, as their blocks are no longer served.
+    pub async fn oldest_block(&self) -> Result<Option<BlockNumber>, std::io::Error> {
+        let pruned_below = self.pruned_below();
+
+        let mut epochs = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.store_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if let Some(epoch) = parse_epoch_dir_name(&entry.file_name()) {
+                epochs.push((epoch, entry.path()));
+            }
+        }
+        epochs.sort_unstable();
+
+        // The first epoch holding a block is usually the one of the pruning height, or of the
+        // genesis block if pruning is disabled, so only one or two epochs are read
+        for (_, epoch_path) in epochs {
+            let mut oldest: Option<BlockNumber> = None;
+            let mut blocks = tokio::fs::read_dir(epoch_path).await?;
+            while let Some(entry) = blocks.next_entry().await? {
+                let Some(block_num) = parse_block_file_name(&entry.file_name()) else {
+                    continue;
+                };
+                if block_num != GENESIS_BLOCK && block_num >= pruned_below {
+                    oldest = Some(oldest.map_or(block_num, |oldest| oldest.min(block_num)));
+                }
+            }
+
+            if oldest.is_some() {
+                return Ok(oldest);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Removes the files of all blocks below `block_num`, except for the genesis block.
+    ///
+    /// The new pruning height is persisted before any file is removed, so an interrupted run only
+    /// leaves behind files of blocks which are already reported as pruned. Every call sweeps all
+    /// the blocks below the recorded height, which cleans up after such an interrupted run.
+    pub async fn prune_below(
+        &self,
+        block_num: BlockNumber,
+    ) -> Result<PrunedBlocks, std::io::Error> {
+        let pruned_below = self.pruned_below.load(Ordering::Acquire);
+        if block_num > pruned_below {
+            self.persist_pruned_below(block_num).await?;
+            self.pruned_below.store(block_num, Ordering::Release);
+        }
+        let pruned_below = pruned_below.max(block_num);
+
+        let mut pruned = PrunedBlocks::default();
+        if pruned_below <= GENESIS_BLOCK + 1 {
+            return Ok(pruned);
+        }
+
+        let mut epochs = tokio::fs::read_dir(&self.store_dir).await?;
+        while let Some(epoch_entry) = epochs.next_entry().await? {
+            let Some(epoch) = parse_epoch_dir_name(&epoch_entry.file_name()) else {
+                continue;
+            };
+            if epoch > (pruned_below - 1) >> 16 {
+                continue;
+            }
+
+            let mut blocks = tokio::fs::read_dir(epoch_entry.path()).await?;
+            while let Some(block_entry) = blocks.next_entry().await? {
+                let Some(block_num) = parse_block_file_name(&block_entry.file_name()) else {
+                    continue;
+                };
+                if block_num == GENESIS_BLOCK || block_num >= pruned_below {
+                    continue;
+                }
+
+                let size = block_entry.metadata().await?.len();
+                match tokio::fs::remove_file(block_entry.path()).await {
+                    Ok(()) => {
+                        pruned.num_blocks += 1;
+                        pruned.freed_bytes += size;
+                    },
+                    Err(err) if err.kind() == ErrorKind::NotFound => {},
+                    Err(err) => return Err(err),
+                }
+            }
+
+            // Epochs which are entirely below the pruning height are empty now, unless they
+            // contain the genesis block, in which case the removal fails and is ignored.
+            if epoch < pruned_below >> 16 {
+                let _ = tokio::fs::remove_dir(epoch_entry.path()).await;
+            }
+        }
+
+        Ok(pruned)
+    }
+
     // HELPER FUNCTIONS
     // --------------------------------------------------------------------------------------------
 
@@ -53,4 +201,143 @@ impl BlockStore {
 
         Ok((epoch_path.to_path_buf(), block_path))
     }
+
+    /// Atomically replaces the recorded pruning height.
+    async fn persist_pruned_below(&self, block_num: BlockNumber) -> Result<(), std::io::Error> {
+        let tmp_path = self.store_dir.join(format!("{PRUNED_BELOW_FILE}.tmp"));
+
+        let mut file = tokio::fs::File::create(&tmp_path).await?;
+        file.write_all(block_num.to_string().as_bytes()).await?;
+        file.sync_all().await?;
+
+        tokio::fs::rename(tmp_path, self.store_dir.join(PRUNED_BELOW_FILE)).await
+    }
+}
+
+/// Parses the epoch of an epoch directory name, i.e. `{epoch:04x}`.
+fn parse_epoch_dir_name(name: &OsStr) -> Option<u32> {
+    let name = name.to_str()?;
+    if name.len() != 4 {
+        return None;
+    }
+    u32::from_str_radix(name, 16).ok()
+}
+
+/// Parses the block number of a block file name, i.e. `block_{block_num:08x}.dat`.
+fn parse_block_file_name(name: &OsStr) -> Option<BlockNumber> {
+    let block_num = name.to_str()?.strip_prefix("block_")?.strip_suffix(".dat")?;
+    u32::from_str_radix(block_num, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    async fn stored_blocks(block_store: &BlockStore, max_block_num: u32) -> BTreeSet<u32> {
+        let mut stored = BTreeSet::new();
+        for block_num in 0..=max_block_num {
+            if block_store.load_block(block_num).await.unwrap().is_some() {
+                stored.insert(block_num);
+            }
+        }
+        stored
+    }
+
+    #[tokio::test]
+    async fn prune_below_keeps_genesis_and_recent_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let block_store = BlockStore::new(dir.path().to_path_buf()).await.unwrap();
+
+        for block_num in 0..=20 {
+            block_store.save_block(block_num, &[block_num as u8; 100]).await.unwrap();
+        }
+
+        let pruned = block_store.prune_below(15).await.unwrap();
+        assert_eq!(pruned, PrunedBlocks { num_blocks: 14, freed_bytes: 1400 });
+
+        let expected: BTreeSet<u32> = [0].into_iter().chain(15..=20).collect();
+        assert_eq!(stored_blocks(&block_store, 20).await, expected);
+
+        assert!(!block_store.is_pruned(GENESIS_BLOCK));
+        assert!(block_store.is_pruned(1));
+        assert!(block_store.is_pruned(14));
+        assert!(!block_store.is_pruned(15));
+
+        // Pruning again at the same height is a no-op
+        let pruned = block_store.prune_below(15).await.unwrap();
+        assert_eq!(pruned, PrunedBlocks::default());
+    }
+
+    #[tokio::test]
+    async fn prune_below_removes_fully_pruned_epochs() {
+        let dir = tempfile::tempdir().unwrap();
+        let block_store = BlockStore::new(dir.path().to_path_buf()).await.unwrap();
+
+        let first_in_epoch_1 = 1 << 16;
+        let first_in_epoch_2 = 2 << 16;
+        for block_num in [0, 1, first_in_epoch_1, first_in_epoch_2, first_in_epoch_2 + 1] {
+            block_store.save_block(block_num, &[1, 2, 3]).await.unwrap();
+        }
+
+        let pruned = block_store.prune_below(first_in_epoch_2 + 1).await.unwrap();
+        assert_eq!(pruned, PrunedBlocks { num_blocks: 3, freed_bytes: 9 });
+
+        assert!(dir.path().join("0000").exists(), "epoch of the genesis block must be kept");
+        assert!(!dir.path().join("0001").exists());
+        assert!(dir.path().join("0002").exists());
+        assert!(block_store.load_block(first_in_epoch_2 + 1).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn pruning_height_survives_restart_and_interrupted_runs_are_resumed() {
+        let dir = tempfile::tempdir().unwrap();
+        let block_store = BlockStore::new(dir.path().to_path_buf()).await.unwrap();
+
+        for block_num in 0..=10 {
+            block_store.save_block(block_num, &[0; 10]).await.unwrap();
+        }
+
+        // Simulate a crash after the pruning height was recorded, but before any block file was
+        // removed.
+        block_store.persist_pruned_below(5).await.unwrap();
+        drop(block_store);
+
+        let block_store = BlockStore::new(dir.path().to_path_buf()).await.unwrap();
+        assert!(block_store.is_pruned(4));
+        assert!(!block_store.is_pruned(5));
+
+        // A lower height doesn't move the recorded height back, but still sweeps leftover files
+        let pruned = block_store.prune_below(2).await.unwrap();
+        assert_eq!(pruned, PrunedBlocks { num_blocks: 4, freed_bytes: 40 });
+        assert!(block_store.is_pruned(4));
+
+        let expected: BTreeSet<u32> = [0].into_iter().chain(5..=10).collect();
+        assert_eq!(stored_blocks(&block_store, 10).await, expected);
+    }
+
+    #[tokio::test]
+    async fn oldest_block_is_looked_up_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let block_store = BlockStore::new(dir.path().to_path_buf()).await.unwrap();
+
+        block_store.save_block(GENESIS_BLOCK, &[0; 10]).await.unwrap();
+        assert_eq!(block_store.oldest_block().await.unwrap(), None);
+
+        let first_in_epoch_1 = 1 << 16;
+        for block_num in [first_in_epoch_1 + 2, 3, first_in_epoch_1, 7] {
+            block_store.save_block(block_num, &[0; 10]).await.unwrap();
+        }
+        assert_eq!(block_store.oldest_block().await.unwrap(), Some(3));
+
+        // Files of pruned blocks are ignored, even if they were not removed yet
+        block_store.persist_pruned_below(5).await.unwrap();
+        block_store.pruned_below.store(5, Ordering::Release);
+        assert_eq!(block_store.oldest_block().await.unwrap(), Some(7));
+
+        // Missing files are skipped, whatever the pruning height
+        tokio::fs::remove_file(block_store.block_path(7)).await.unwrap();
+        assert_eq!(block_store.oldest_block().await.unwrap(), Some(first_in_epoch_1));
+    }
 }
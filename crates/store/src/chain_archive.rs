@@ -0,0 +1,530 @@
+//! Export and import of the chain as a directory of flat files.
+//!
+//! An archive holds everything needed to rebuild a store from scratch, in a format which doesn't
+//! depend on the store's database:
+//!
+//! - `genesis.dat`: the genesis file the store was bootstrapped from.
+//! - `blocks/<block number>.block`: the serialized [Block] of every block, genesis included, as
+//!   found in the block store. Block numbers are zero-padded to 10 digits.
+//! - `manifest.tsv`: the index of the blocks, with one tab-separated line per block, in order: the
+//!   block number, the block commitment, the commitment of the parent block and the BLAKE3 hash of
+//!   the block file. Commitments and hashes are hex-encoded.
+//!
+//! Importing an archive replays its blocks through [State::apply_block], so every block is checked
+//! to extend the previous one and its roots are recomputed: a tampered block stops the import at
+//! that block.
+//!
+//! Both directions are resumable. An interrupted export appends the blocks missing from the
+//! manifest, and an interrupted import applies the blocks above the chain tip of its store.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use miden_node_utils::{block_num::BlockNumberExt, formatting::format_blake3_digest};
+use miden_objects::{
+    block::Block,
+    crypto::hash::{blake::Blake3_256, rpo::RpoDigest},
+    utils::Deserializable,
+    BlockHeader, GENESIS_BLOCK,
+};
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+use crate::{
+    blocks::BlockStore,
+    config::StoreConfig,
+    db::Db,
+    errors::ChainArchiveError,
+    note_details::NoteDetailsFilter,
+    state::{State, TimestampValidation},
+    types::BlockNumber,
+    write_limits::WriteLimits,
+    COMPONENT,
+};
+
+const GENESIS_FILE: &str = "genesis.dat";
+const BLOCKS_DIR: &str = "blocks";
+const MANIFEST_FILE: &str = "manifest.tsv";
+const MANIFEST_HEADER: &str = "block_num\tcommitment\tparent_commitment\tfile_hash";
+
+/// Interval between two progress reports of an export or import.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+// MANIFEST
+// ================================================================================================
+
+/// A line of the manifest of an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub block_num: BlockNumber,
+    pub commitment: RpoDigest,
+    pub parent_commitment: RpoDigest,
+    /// Hex-encoded BLAKE3 hash of the block file.
+    pub file_hash: String,
+}
+
+impl ManifestEntry {
+    fn new(header: &BlockHeader, block_data: &[u8]) -> Self {
+        Self {
+            block_num: header.block_num(),
+            commitment: header.hash(),
+            parent_commitment: header.prev_hash(),
+            file_hash: file_hash(block_data),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\n",
+            self.block_num,
+            self.commitment.to_hex(),
+            self.parent_commitment.to_hex(),
+            self.file_hash
+        )
+    }
+
+    fn parse(line_num: usize, line: &str) -> Result<Self, ChainArchiveError> {
+        let invalid =
+            |reason: String| ChainArchiveError::InvalidManifest { line: line_num, reason };
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [block_num, commitment, parent_commitment, file_hash] = fields[..] else {
+            return Err(invalid(format!("expected 4 fields, got {}", fields.len())));
+        };
+
+        let digest = |field: &str| {
+            RpoDigest::try_from(field).map_err(|err| invalid(format!("invalid digest: {err}")))
+        };
+
+        Ok(Self {
+            block_num: block_num
+                .parse()
+                .map_err(|err| invalid(format!("invalid block number: {err}")))?,
+            commitment: digest(commitment)?,
+            parent_commitment: digest(parent_commitment)?,
+            file_hash: file_hash.to_string(),
+        })
+    }
+}
+
+/// Reads the manifest at `path`, or returns no entries if there is none.
+///
+/// The entries must list the blocks in order, starting at the genesis block. A trailing line
+/// without a line break, left behind by an interrupted export, is ignored.
+async fn read_manifest(path: &Path) -> Result<Vec<ManifestEntry>, ChainArchiveError> {
+    let manifest = match tokio::fs::read_to_string(path).await {
+        Ok(manifest) => manifest,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut lines: Vec<&str> = manifest.split_inclusive('\n').collect();
+    if lines.last().is_some_and(|line| !line.ends_with('\n')) {
+        lines.pop();
+    }
+
+    let mut entries = Vec::with_capacity(lines.len().saturating_sub(1));
+    for (index, line) in lines.into_iter().enumerate() {
+        let line = line.trim_end_matches('\n');
+        if index == 0 {
+            if line != MANIFEST_HEADER {
+                return Err(ChainArchiveError::InvalidManifest {
+                    line: 1,
+                    reason: format!("expected the header `{MANIFEST_HEADER}`"),
+                });
+            }
+            continue;
+        }
+
+        let entry = ManifestEntry::parse(index + 1, line)?;
+        if entry.block_num as usize != entries.len() {
+            return Err(ChainArchiveError::InvalidManifest {
+                line: index + 1,
+                reason: format!("expected block {}, got block {}", entries.len(), entry.block_num),
+            });
+        }
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn file_hash(data: &[u8]) -> String {
+    format_blake3_digest(Blake3_256::hash(data))
+}
+
+fn block_file_path(archive_dir: &Path, block_num: BlockNumber) -> PathBuf {
+    archive_dir.join(BLOCKS_DIR).join(format!("{block_num:010}.block"))
+}
+
+// EXPORT
+// ================================================================================================
+
+/// Outcome of an export or import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainArchiveSummary {
+    /// Latest block of the archive, or of the store after an import.
+    pub chain_tip: BlockNumber,
+    pub chain_tip_commitment: RpoDigest,
+    /// Number of blocks exported or imported by this run, excluding the ones of a previous run.
+    pub num_blocks: u32,
+}
+
+/// Exports the chain of the store configured by `config` to the archive at `archive_dir`.
+///
+/// If the archive already holds blocks of the same chain, only the newer blocks are exported. The
+/// store must not be running, and must not have pruned any block.
+pub async fn export_chain(
+    config: StoreConfig,
+    archive_dir: &Path,
+) -> Result<ChainArchiveSummary, ChainArchiveError> {
+    let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await?);
+    let db = Db::setup(config.clone(), Arc::clone(&block_store)).await?;
+    let chain_tip = db
+        .select_block_header_by_block_num(None)
+        .await?
+        .ok_or(ChainArchiveError::MissingBlock(GENESIS_BLOCK))?;
+
+    tokio::fs::create_dir_all(archive_dir.join(BLOCKS_DIR)).await?;
+
+    let genesis = tokio::fs::read(&config.genesis_filepath).await?;
+    let archive_genesis = archive_dir.join(GENESIS_FILE);
+    match tokio::fs::read(&archive_genesis).await {
+        Ok(archived) if archived != genesis => return Err(ChainArchiveError::GenesisMismatch),
+        Ok(_) => {},
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            tokio::fs::write(&archive_genesis, &genesis).await?;
+        },
+        Err(err) => return Err(err.into()),
+    }
+
+    // Resume after the last exported block, if it is part of the store's chain
+    let manifest_path = archive_dir.join(MANIFEST_FILE);
+    let entries = read_manifest(&manifest_path).await?;
+    if let Some(last) = entries.last() {
+        let header = db.select_block_header_by_block_num(Some(last.block_num)).await?;
+        if header.map(|header| header.hash()) != Some(last.commitment) {
+            return Err(ChainArchiveError::ChainMismatch(last.block_num));
+        }
+    }
+
+    // The manifest is rewritten to drop a partially written line
+    let mut manifest = tokio::fs::File::create(&manifest_path).await?;
+    manifest.write_all(format!("{MANIFEST_HEADER}\n").as_bytes()).await?;
+    for entry in &entries {
+        manifest.write_all(entry.to_line().as_bytes()).await?;
+    }
+
+    // Nothing is left to export once the last block number was exported
+    let first_block = entries.last().map_or(Some(GENESIS_BLOCK), |last| last.block_num.child());
+    let blocks = first_block.map_or(1..=0, |first_block| first_block..=chain_tip.block_num());
+    let mut progress = Progress::new("Exporting", *blocks.start(), *blocks.end());
+    for block_num in blocks {
+        let block_data = block_store
+            .load_block(block_num)
+            .await?
+            .ok_or(ChainArchiveError::MissingBlock(block_num))?;
+
+        // The block store may hold blocks which failed to be applied, the database has the final
+        // say on the chain
+        let header = db
+            .select_block_header_by_block_num(Some(block_num))
+            .await?
+            .ok_or(ChainArchiveError::MissingBlock(block_num))?;
+        let block = Block::read_from_bytes(&block_data)
+            .map_err(|source| ChainArchiveError::InvalidBlockFile { block_num, source })?;
+        if block.hash() != header.hash() {
+            return Err(ChainArchiveError::CommitmentMismatch {
+                block_num,
+                expected: header.hash(),
+                actual: block.hash(),
+            });
+        }
+
+        // Block files are written in full before being listed in the manifest
+        let block_path = block_file_path(archive_dir, block_num);
+        let partial_path = block_path.with_extension("partial");
+        tokio::fs::write(&partial_path, &block_data).await?;
+        tokio::fs::rename(&partial_path, &block_path).await?;
+
+        manifest
+            .write_all(ManifestEntry::new(&header, &block_data).to_line().as_bytes())
+            .await?;
+        manifest.flush().await?;
+
+        progress.report(block_num);
+    }
+    manifest.sync_all().await?;
+
+    Ok(ChainArchiveSummary {
+        chain_tip: chain_tip.block_num(),
+        chain_tip_commitment: chain_tip.hash(),
+        num_blocks: progress.num_blocks(),
+    })
+}
+
+// IMPORT
+// ================================================================================================
+
+/// Imports the archive at `archive_dir` into the store configured by `config`.
+///
+/// A store without data is bootstrapped from the archived genesis file. A store with data must
+/// hold a prefix of the archived chain, and only the blocks above its chain tip are imported. The
+/// store must not be running.
+pub async fn import_chain(
+    config: StoreConfig,
+    archive_dir: &Path,
+) -> Result<ChainArchiveSummary, ChainArchiveError> {
+    let entries = read_manifest(&archive_dir.join(MANIFEST_FILE)).await?;
+    if entries.is_empty() {
+        return Err(ChainArchiveError::InvalidManifest {
+            line: 1,
+            reason: "the archive has no blocks".to_string(),
+        });
+    }
+
+    if !config.genesis_filepath.exists() {
+        if let Some(parent) = config.genesis_filepath.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::copy(archive_dir.join(GENESIS_FILE), &config.genesis_filepath).await?;
+    }
+
+    let note_details = NoteDetailsFilter::try_from(&config.note_details)?;
+    let write_limits = WriteLimits::try_from(&config.write_limits)?;
+    let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await?);
+    let db = Db::setup(config.clone(), Arc::clone(&block_store)).await?;
+
+    // Resume after the chain tip of the store, if it is part of the archived chain
+    let chain_tip = db
+        .select_block_header_by_block_num(None)
+        .await?
+        .ok_or(ChainArchiveError::MissingBlock(GENESIS_BLOCK))?;
+    match entries.get(chain_tip.block_num() as usize) {
+        Some(entry) if entry.commitment == chain_tip.hash() => {},
+        _ => return Err(ChainArchiveError::ChainMismatch(chain_tip.block_num())),
+    }
+
+    let state = State::load(db, block_store)
+        .await?
+        .with_note_details(note_details)
+        .with_timestamp_validation(TimestampValidation::from(&config))
+        .with_write_limits(write_limits)
+        .with_block_versions(config.block_versions);
+
+    let last_entry = entries.last().expect("the archive has blocks");
+    let mut parent_commitment = chain_tip.hash();
+    let first_block = chain_tip.block_num().saturating_advance(1);
+    let mut progress = Progress::new("Importing", first_block, last_entry.block_num);
+    for entry in &entries[chain_tip.block_num() as usize + 1..] {
+        let block_num = entry.block_num;
+
+        let block_data = tokio::fs::read(block_file_path(archive_dir, block_num)).await?;
+        if file_hash(&block_data) != entry.file_hash {
+            return Err(ChainArchiveError::FileHashMismatch(block_num));
+        }
+
+        let block = Block::read_from_bytes(&block_data)
+            .map_err(|source| ChainArchiveError::InvalidBlockFile { block_num, source })?;
+        if block.header().block_num() != block_num || block.hash() != entry.commitment {
+            return Err(ChainArchiveError::CommitmentMismatch {
+                block_num,
+                expected: entry.commitment,
+                actual: block.hash(),
+            });
+        }
+        if block.header().prev_hash() != parent_commitment
+            || entry.parent_commitment != parent_commitment
+        {
+            return Err(ChainArchiveError::BrokenParentLink(block_num));
+        }
+
+        state
+            .apply_block(block)
+            .await
+            .map_err(|source| ChainArchiveError::ApplyBlockFailed { block_num, source })?;
+
+        parent_commitment = entry.commitment;
+        progress.report(block_num);
+    }
+
+    Ok(ChainArchiveSummary {
+        chain_tip: last_entry.block_num,
+        chain_tip_commitment: last_entry.commitment,
+        num_blocks: progress.num_blocks(),
+    })
+}
+
+// PROGRESS
+// ================================================================================================
+
+/// Logs the progress of an export or import every [PROGRESS_INTERVAL], and once done.
+struct Progress {
+    action: &'static str,
+    first_block: BlockNumber,
+    last_block: BlockNumber,
+    num_blocks: u32,
+    started_at: Instant,
+    reported_at: Instant,
+}
+
+impl Progress {
+    fn new(action: &'static str, first_block: BlockNumber, last_block: BlockNumber) -> Self {
+        info!(target: COMPONENT, first_block, last_block, "{action} chain");
+
+        let now = Instant::now();
+        Self {
+            action,
+            first_block,
+            last_block,
+            num_blocks: 0,
+            started_at: now,
+            reported_at: now,
+        }
+    }
+
+    fn report(&mut self, block_num: BlockNumber) {
+        self.num_blocks += 1;
+        if block_num != self.last_block && self.reported_at.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.reported_at = Instant::now();
+
+        let elapsed = self.started_at.elapsed();
+        let blocks_per_sec = f64::from(self.num_blocks) / elapsed.as_secs_f64().max(f64::EPSILON);
+        let remaining = self.last_block - block_num;
+        info!(
+            target: COMPONENT,
+            block_num,
+            last_block = self.last_block,
+            blocks_per_sec = %format!("{blocks_per_sec:.1}"),
+            eta_secs = (f64::from(remaining) / blocks_per_sec.max(f64::EPSILON)) as u64,
+            "{} chain: {}/{} blocks",
+            self.action,
+            u64::from(block_num - self.first_block) + 1,
+            u64::from(self.last_block - self.first_block) + 1,
+        );
+    }
+
+    fn num_blocks(&self) -> u32 {
+        self.num_blocks
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_node_test_utils::block::MockChain;
+    use miden_objects::{
+        accounts::{
+            account_id::testing::ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN,
+            delta::AccountUpdateDetails, AccountId,
+        },
+        block::BlockAccountUpdate,
+        utils::Serializable,
+        Felt, ZERO,
+    };
+
+    use super::*;
+    use crate::genesis::GenesisState;
+
+    const NUM_BLOCKS: u32 = 8;
+
+    fn store_config(dir: &Path) -> StoreConfig {
+        StoreConfig {
+            database_filepath: dir.join("miden-store.sqlite3"),
+            genesis_filepath: dir.join("genesis.dat"),
+            blockstore_dir: dir.join("blocks"),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a store in `dir` with a chain of [NUM_BLOCKS] blocks on top of its genesis block,
+    /// each updating an account.
+    async fn seed_store(dir: &Path) -> StoreConfig {
+        let config = store_config(dir);
+        let genesis = GenesisState::new(vec![], 1, 0);
+        std::fs::write(&config.genesis_filepath, genesis.to_bytes()).unwrap();
+        let mut chain = MockChain::new(&genesis.into_block().unwrap());
+
+        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+        let db = Db::setup(config.clone(), Arc::clone(&block_store)).await.unwrap();
+        let state = State::load(db, block_store).await.unwrap();
+
+        let account_id =
+            AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN).unwrap();
+        for i in 1..=NUM_BLOCKS {
+            let update = BlockAccountUpdate::new(
+                account_id,
+                [Felt::from(i), ZERO, ZERO, ZERO].into(),
+                AccountUpdateDetails::Private,
+                vec![],
+            );
+            let block = chain.next_block().account_updates(vec![update]).build();
+            state.apply_block(block).await.unwrap();
+        }
+
+        config
+    }
+
+    async fn chain_tip(config: &StoreConfig) -> BlockHeader {
+        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+        let db = Db::setup(config.clone(), block_store).await.unwrap();
+        db.select_block_header_by_block_num(None).await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn imported_chain_matches_exported_chain() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let target_dir = tempfile::tempdir().unwrap();
+        let source = seed_store(source_dir.path()).await;
+        let source_tip = chain_tip(&source).await;
+
+        let exported = export_chain(source.clone(), archive_dir.path()).await.unwrap();
+        assert_eq!(exported.chain_tip, NUM_BLOCKS);
+        assert_eq!(exported.chain_tip_commitment, source_tip.hash());
+        assert_eq!(exported.num_blocks, NUM_BLOCKS + 1);
+
+        // Exporting again only checks the blocks already in the archive
+        let exported = export_chain(source, archive_dir.path()).await.unwrap();
+        assert_eq!(exported.num_blocks, 0);
+
+        let target = store_config(target_dir.path());
+        let imported = import_chain(target.clone(), archive_dir.path()).await.unwrap();
+        assert_eq!(imported, ChainArchiveSummary { num_blocks: NUM_BLOCKS, ..exported });
+        assert_eq!(chain_tip(&target).await, source_tip);
+    }
+
+    #[tokio::test]
+    async fn import_stops_at_tampered_block_and_resumes() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let target_dir = tempfile::tempdir().unwrap();
+        let source = seed_store(source_dir.path()).await;
+        export_chain(source.clone(), archive_dir.path()).await.unwrap();
+
+        let block_path = block_file_path(archive_dir.path(), 3);
+        let block_data = std::fs::read(&block_path).unwrap();
+        let mut tampered = block_data.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        std::fs::write(&block_path, tampered).unwrap();
+
+        let target = store_config(target_dir.path());
+        let err = import_chain(target.clone(), archive_dir.path()).await.unwrap_err();
+        assert!(matches!(err, ChainArchiveError::FileHashMismatch(3)), "{err}");
+        assert_eq!(chain_tip(&target).await.block_num(), 2);
+
+        // The import resumes after the blocks imported by the failed run
+        std::fs::write(&block_path, block_data).unwrap();
+        let imported = import_chain(target.clone(), archive_dir.path()).await.unwrap();
+        assert_eq!(imported.num_blocks, NUM_BLOCKS - 2);
+        assert_eq!(chain_tip(&target).await, chain_tip(&source).await);
+    }
+}
@@ -0,0 +1,145 @@
+//! Aggregate statistics of the chain over ranges of blocks.
+//!
+//! When a block is applied, the store records its number of transactions, notes, nullifiers and
+//! new accounts, along with the running totals of these counts since the genesis block. The
+//! statistics of a range of blocks are then computed from the records of its first and last
+//! blocks, whatever the length of the range, without scanning the tables of the chain data.
+
+use miden_node_proto::generated::responses::GetChainStatsResponse;
+
+use crate::types::BlockNumber;
+
+// BLOCK STATS
+// ================================================================================================
+
+/// Counts of a block, and running totals of the counts of the blocks up to it, included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockStats {
+    pub block_num: BlockNumber,
+    /// Timestamp of the block, in seconds since the Unix epoch.
+    pub timestamp: u32,
+    pub num_transactions: u64,
+    pub num_notes: u64,
+    pub num_nullifiers: u64,
+    /// Number of accounts updated for the first time by the block.
+    pub num_new_accounts: u64,
+    pub total_transactions: u64,
+    pub total_notes: u64,
+    pub total_nullifiers: u64,
+    pub total_accounts: u64,
+}
+
+impl BlockStats {
+    /// Returns the stats of a block with the given counts, following the block of `previous`, or
+    /// the first block of the chain if [None].
+    pub fn new(
+        block_num: BlockNumber,
+        timestamp: u32,
+        [num_transactions, num_notes, num_nullifiers, num_new_accounts]: [u64; 4],
+        previous: Option<&BlockStats>,
+    ) -> Self {
+        let previous = previous.copied().unwrap_or_default();
+
+        Self {
+            block_num,
+            timestamp,
+            num_transactions,
+            num_notes,
+            num_nullifiers,
+            num_new_accounts,
+            total_transactions: previous.total_transactions + num_transactions,
+            total_notes: previous.total_notes + num_notes,
+            total_nullifiers: previous.total_nullifiers + num_nullifiers,
+            total_accounts: previous.total_accounts + num_new_accounts,
+        }
+    }
+}
+
+// CHAIN STATS
+// ================================================================================================
+
+/// Statistics of a range of blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainStats {
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+    pub num_transactions: u64,
+    pub num_notes: u64,
+    pub num_nullifiers: u64,
+    pub num_new_accounts: u64,
+    /// Average duration between two consecutive blocks of the range in milliseconds, [None] for
+    /// ranges of a single block.
+    pub average_block_interval_ms: Option<u64>,
+}
+
+impl ChainStats {
+    /// Returns the statistics of the blocks from the block of `first` to the block of `last`.
+    pub fn between(first: &BlockStats, last: &BlockStats) -> Self {
+        let num_intervals = u64::from(last.block_num.saturating_sub(first.block_num));
+        let average_block_interval_ms = (num_intervals > 0).then(|| {
+            u64::from(last.timestamp.saturating_sub(first.timestamp)) * 1000 / num_intervals
+        });
+
+        Self {
+            from_block: first.block_num,
+            to_block: last.block_num,
+            num_transactions: last.total_transactions - first.total_transactions
+                + first.num_transactions,
+            num_notes: last.total_notes - first.total_notes + first.num_notes,
+            num_nullifiers: last.total_nullifiers - first.total_nullifiers + first.num_nullifiers,
+            num_new_accounts: last.total_accounts - first.total_accounts + first.num_new_accounts,
+            average_block_interval_ms,
+        }
+    }
+}
+
+impl From<ChainStats> for GetChainStatsResponse {
+    fn from(stats: ChainStats) -> Self {
+        Self {
+            from_block: stats.from_block,
+            to_block: stats.to_block,
+            num_blocks: stats.to_block - stats.from_block + 1,
+            num_transactions: stats.num_transactions,
+            num_notes: stats.num_notes,
+            num_nullifiers: stats.num_nullifiers,
+            num_new_accounts: stats.num_new_accounts,
+            average_block_interval_ms: stats.average_block_interval_ms,
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_stats_are_computed_from_the_totals() {
+        let genesis = BlockStats::new(0, 1000, [0, 0, 0, 3], None);
+        let block_1 = BlockStats::new(1, 1004, [2, 5, 1, 1], Some(&genesis));
+        let block_2 = BlockStats::new(2, 1008, [1, 2, 2, 0], Some(&block_1));
+        let block_3 = BlockStats::new(3, 1015, [4, 1, 3, 2], Some(&block_2));
+        assert_eq!(block_3.total_transactions, 7);
+        assert_eq!(block_3.total_accounts, 6);
+
+        let stats = ChainStats::between(&block_1, &block_3);
+        assert_eq!(
+            stats,
+            ChainStats {
+                from_block: 1,
+                to_block: 3,
+                num_transactions: 7,
+                num_notes: 8,
+                num_nullifiers: 6,
+                num_new_accounts: 3,
+                average_block_interval_ms: Some(5500),
+            }
+        );
+
+        let stats = ChainStats::between(&block_2, &block_2);
+        assert_eq!(stats.num_notes, 2);
+        assert_eq!(stats.average_block_interval_ms, None);
+    }
+}
@@ -3,8 +3,33 @@ use std::{
     path::PathBuf,
 };
 
-use miden_node_utils::config::{Endpoint, DEFAULT_STORE_PORT};
-use serde::{Deserialize, Serialize};
+use miden_node_proto::generated::responses::StoreMode as StoreModePb;
+use miden_node_utils::{
+    block_versions::BlockVersions,
+    config::{Endpoint, CONFIG_VERSION, DEFAULT_STORE_PORT},
+};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+
+use crate::errors::StoreModeError;
+
+/// Default maximum size of the list responses of the store, matching the default message size
+/// limit of gRPC clients.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Default duration in milliseconds above which SQL statements are logged as slow.
+pub const DEFAULT_SLOW_QUERY_THRESHOLD_MS: u64 = 500;
+
+/// Default wait for a database connection in milliseconds above which the wait is logged as slow.
+pub const DEFAULT_SLOW_POOL_WAIT_THRESHOLD_MS: u64 = 100;
+
+/// Default number of seconds the timestamp of an applied block can be ahead of the store's clock.
+pub const DEFAULT_MAX_BLOCK_TIMESTAMP_DRIFT_SECS: u64 = 300;
+
+/// Default number of account deltas per second streamed by every `ExportAccountDeltas` request.
+pub const DEFAULT_EXPORT_ROWS_PER_SECOND: u32 = 5000;
+
+/// Default number of most recent blocks whose full data is kept by a store set to the pruned mode.
+pub const DEFAULT_PRUNED_BLOCK_RETENTION_DEPTH: u32 = 100_000;
 
 // Main config
 // ================================================================================================
@@ -12,6 +37,10 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct StoreConfig {
+    /// Version of the layout of a standalone store configuration file, see [CONFIG_VERSION].
+    /// Within the node configuration file, the version of the node configuration applies.
+    #[serde(default, skip_serializing)]
+    pub config_version: u32,
     /// Defines the listening socket.
     pub endpoint: Endpoint,
     /// SQLite database file
@@ -20,19 +49,111 @@ pub struct StoreConfig {
     pub genesis_filepath: PathBuf,
     /// Block store directory
     pub blockstore_dir: PathBuf,
+    /// Which historical data the store keeps, see [StoreMode]. If not set, the mode is inferred
+    /// from the options pruning data, see [StoreConfig::store_mode].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<StoreMode>,
+    /// Number of most recent blocks for which the full block data is kept in the block store.
+    ///
+    /// Older blocks are periodically pruned from the block store, while their headers and the
+    /// data derived from them remain available. Pruning is disabled if not set, unless the store
+    /// is set to the pruned mode, which defaults to [DEFAULT_PRUNED_BLOCK_RETENTION_DEPTH]. At
+    /// least 1.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_block_retention_depth",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub block_retention_depth: Option<u32>,
+    /// Maximum size in bytes of the responses of the endpoints returning lists of notes or
+    /// account proofs. Longer lists are truncated, and the responses flag it. The block inputs
+    /// are paginated to this size.
+    #[serde(default = "default_max_response_size")]
+    pub max_response_size: usize,
+    /// Which details of public notes are stored.
+    #[serde(default)]
+    pub note_details: NoteDetailsPolicy,
+    /// Duration in milliseconds above which SQL statements are logged as slow, along with the
+    /// number of values of their parameters.
+    #[serde(default = "default_slow_query_threshold_ms")]
+    pub slow_query_threshold_ms: u64,
+    /// Duration in milliseconds above which the waits of queries for a database connection are
+    /// logged as slow, along with the state of the connection pool.
+    #[serde(default = "default_slow_pool_wait_threshold_ms")]
+    pub slow_pool_wait_threshold_ms: u64,
+    /// Number of seconds the timestamp of an applied block can be ahead of the store's clock.
+    #[serde(default = "default_max_block_timestamp_drift_secs")]
+    pub max_block_timestamp_drift_secs: u64,
+    /// Disables the validation of the timestamps of the applied blocks.
+    ///
+    /// Meant for reimporting historical blocks whose timestamps were accepted before the
+    /// validation was introduced.
+    #[serde(default)]
+    pub skip_block_timestamp_validation: bool,
+    /// Shard keys of the accounts served by the store, see [crate::server::sharding]. Account
+    /// requests routed to the store for other shards are rejected. All accounts are served by
+    /// default.
+    #[serde(default)]
+    pub shard_range: ShardRange,
+    /// Number of account deltas per second streamed by every `ExportAccountDeltas` request, so
+    /// that bulk exports don't starve the requests of the clients.
+    #[serde(default = "default_export_rows_per_second")]
+    pub export_rows_per_second: u32,
+    /// Raised limits on the size of the applied blocks, see [WriteLimitsConfig].
+    #[serde(default)]
+    pub write_limits: WriteLimitsConfig,
+    /// Versions of the applied blocks accepted by the store, and their scheduled upgrade, see
+    /// [BlockVersions]. Must match the versions configured for the block producer.
+    #[serde(default)]
+    pub block_versions: BlockVersions,
 }
 
 impl StoreConfig {
     pub fn endpoint_url(&self) -> String {
         self.endpoint.to_string()
     }
+
+    /// Returns the mode of the store.
+    ///
+    /// If the mode isn't set, the store is in pruned mode if `block_retention_depth` is set or
+    /// `note_details` isn't `full`, and in archive mode otherwise.
+    ///
+    /// # Errors
+    /// Returns an error if the store is set to the archive mode along with an option pruning data.
+    pub fn store_mode(&self) -> Result<StoreMode, StoreModeError> {
+        let pruning_option = if self.block_retention_depth.is_some() {
+            Some("block_retention_depth")
+        } else if self.note_details != NoteDetailsPolicy::Full {
+            Some("note_details")
+        } else {
+            None
+        };
+
+        match (self.mode, pruning_option) {
+            (Some(StoreMode::Archive), Some(option)) => {
+                Err(StoreModeError::PruningInArchiveMode(option))
+            },
+            (Some(mode), _) => Ok(mode),
+            (None, Some(_)) => Ok(StoreMode::Pruned),
+            (None, None) => Ok(StoreMode::Archive),
+        }
+    }
+
+    /// Returns the number of most recent blocks whose full data is kept, or [None] if the data of
+    /// old blocks is not pruned.
+    pub fn retention_depth(&self) -> Option<u32> {
+        match (self.mode, self.block_retention_depth) {
+            (Some(StoreMode::Pruned), None) => Some(DEFAULT_PRUNED_BLOCK_RETENTION_DEPTH),
+            (_, retention_depth) => retention_depth,
+        }
+    }
 }
 
 impl Display for StoreConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "{{ endpoint: \"{}\",  database_filepath: {:?}, genesis_filepath: {:?}, blockstore_dir: {:?} }}",
-            self.endpoint, self.database_filepath, self.genesis_filepath, self.blockstore_dir
+            "{{ endpoint: \"{}\",  database_filepath: {:?}, genesis_filepath: {:?}, blockstore_dir: {:?}, mode: {:?}, block_retention_depth: {:?}, max_response_size: {}, note_details: {:?}, slow_query_threshold_ms: {}, slow_pool_wait_threshold_ms: {}, max_block_timestamp_drift_secs: {}, skip_block_timestamp_validation: {}, shard_range: {}, export_rows_per_second: {}, write_limits: {:?}, block_versions: {} }}",
+            self.endpoint, self.database_filepath, self.genesis_filepath, self.blockstore_dir, self.mode, self.block_retention_depth, self.max_response_size, self.note_details, self.slow_query_threshold_ms, self.slow_pool_wait_threshold_ms, self.max_block_timestamp_drift_secs, self.skip_block_timestamp_validation, self.shard_range, self.export_rows_per_second, self.write_limits, self.block_versions
         ))
     }
 }
@@ -41,10 +162,198 @@ impl Default for StoreConfig {
     fn default() -> Self {
         const NODE_STORE_DIR: &str = "./";
         Self {
+            config_version: CONFIG_VERSION,
             endpoint: Endpoint::localhost(DEFAULT_STORE_PORT),
             database_filepath: PathBuf::from(NODE_STORE_DIR.to_string() + "miden-store.sqlite3"),
             genesis_filepath: PathBuf::from(NODE_STORE_DIR.to_string() + "genesis.dat"),
             blockstore_dir: PathBuf::from(NODE_STORE_DIR.to_string() + "blocks"),
+            mode: None,
+            block_retention_depth: None,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            note_details: NoteDetailsPolicy::Full,
+            slow_query_threshold_ms: DEFAULT_SLOW_QUERY_THRESHOLD_MS,
+            slow_pool_wait_threshold_ms: DEFAULT_SLOW_POOL_WAIT_THRESHOLD_MS,
+            max_block_timestamp_drift_secs: DEFAULT_MAX_BLOCK_TIMESTAMP_DRIFT_SECS,
+            skip_block_timestamp_validation: false,
+            shard_range: ShardRange::default(),
+            export_rows_per_second: DEFAULT_EXPORT_ROWS_PER_SECOND,
+            write_limits: WriteLimitsConfig::default(),
+            block_versions: BlockVersions::default(),
+        }
+    }
+}
+
+fn default_max_response_size() -> usize {
+    DEFAULT_MAX_RESPONSE_SIZE
+}
+
+fn default_slow_query_threshold_ms() -> u64 {
+    DEFAULT_SLOW_QUERY_THRESHOLD_MS
+}
+
+fn default_slow_pool_wait_threshold_ms() -> u64 {
+    DEFAULT_SLOW_POOL_WAIT_THRESHOLD_MS
+}
+
+fn default_max_block_timestamp_drift_secs() -> u64 {
+    DEFAULT_MAX_BLOCK_TIMESTAMP_DRIFT_SECS
+}
+
+fn default_export_rows_per_second() -> u32 {
+    DEFAULT_EXPORT_ROWS_PER_SECOND
+}
+
+/// Deserializes a block retention depth, rejecting a depth of 0 which would only keep the data of
+/// the chain tip.
+fn deserialize_block_retention_depth<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u32>, D::Error> {
+    let block_retention_depth = Option::<u32>::deserialize(deserializer)?;
+    if block_retention_depth == Some(0) {
+        return Err(D::Error::custom("block retention depth must be at least 1 block"));
+    }
+
+    Ok(block_retention_depth)
+}
+
+// Store mode
+// ================================================================================================
+
+/// Which historical data the store keeps.
+///
+/// The mode is recorded in the database: a store which ran in pruned mode can't be switched to
+/// the archive mode, as the data it pruned can't be recovered.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StoreMode {
+    /// All historical data is kept: the data of all blocks, the details of all public notes and
+    /// the deltas of all public accounts.
+    Archive,
+    /// Only the data needed to serve the current state and the recent history is kept. The data
+    /// of the blocks older than the retention depth is pruned, the details of public notes are
+    /// stored according to `note_details`, and the account deltas are only served since the
+    /// oldest retained block.
+    Pruned,
+}
+
+impl StoreMode {
+    /// Returns the name of the mode, as in the configuration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Archive => "archive",
+            Self::Pruned => "pruned",
         }
     }
 }
+
+impl Display for StoreMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<StoreMode> for StoreModePb {
+    fn from(mode: StoreMode) -> Self {
+        match mode {
+            StoreMode::Archive => Self::Archive,
+            StoreMode::Pruned => Self::Pruned,
+        }
+    }
+}
+
+// Note details policy
+// ================================================================================================
+
+/// Data availability policy of the store, deciding which details of public notes are stored.
+///
+/// The note tree, the metadata and the nullifiers of all notes are always stored, as they are
+/// required to validate and sync the chain. Nodes whose operators rely on an external data
+/// availability layer can skip storing the details of some or all public notes. The policy only
+/// applies to the notes of the blocks applied while it is set, so it can be changed at any time.
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(tag = "policy", rename_all = "kebab-case")]
+pub enum NoteDetailsPolicy {
+    /// The details of all public notes are stored.
+    #[default]
+    Full,
+    /// The details of public notes are never stored.
+    MetadataOnly,
+    /// The details of public notes are only stored for the listed note scripts and tags.
+    Selective {
+        /// Hex-encoded roots of the note scripts whose notes are stored.
+        #[serde(default)]
+        script_roots: Vec<String>,
+        /// Ranges of the tags of the notes which are stored.
+        #[serde(default)]
+        tags: Vec<NoteTagRange>,
+    },
+}
+
+/// Inclusive range of note tags.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoteTagRange {
+    pub from: u32,
+    pub to: u32,
+}
+
+// Write limits
+// ================================================================================================
+
+/// Limits on the size of the applied blocks raised above their defaults, e.g. for experimental
+/// networks with larger blocks.
+///
+/// The limits can't be lowered below their defaults, which are aligned with the protocol: blocks
+/// accepted by the rest of the network would be rejected by the store. Unset limits keep their
+/// defaults, see [crate::write_limits::WriteLimits].
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WriteLimitsConfig {
+    /// Maximum number of notes created by a block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_notes_per_block: Option<usize>,
+    /// Maximum number of nullifiers of a block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_nullifiers_per_block: Option<usize>,
+    /// Maximum number of accounts updated by a block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_accounts_per_block: Option<usize>,
+    /// Maximum size in bytes of the serialized details of a public note.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_note_details_size: Option<usize>,
+    /// Maximum size in bytes of the serialized details of an account update, i.e. the new
+    /// account or the delta of its state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_account_details_size: Option<usize>,
+}
+
+// Shard range
+// ================================================================================================
+
+/// Inclusive range of shard keys.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShardRange {
+    pub from: u8,
+    pub to: u8,
+}
+
+impl ShardRange {
+    /// Returns whether the range includes the shard key.
+    pub fn contains(&self, shard_key: u8) -> bool {
+        (self.from..=self.to).contains(&shard_key)
+    }
+}
+
+impl Default for ShardRange {
+    /// Returns the range of all shard keys.
+    fn default() -> Self {
+        Self { from: u8::MIN, to: u8::MAX }
+    }
+}
+
+impl Display for ShardRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.from, self.to)
+    }
+}
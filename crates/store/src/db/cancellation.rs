@@ -0,0 +1,162 @@
+use std::{
+    pin::pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use deadpool_sqlite::Pool;
+use rusqlite::{Connection, InterruptHandle};
+use tokio::time;
+use tracing::Span;
+
+use super::pool_metrics::record_pool_wait;
+use crate::errors::DatabaseError;
+
+/// Maximum duration of the queries serving a client request.
+pub const REQUEST_QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum duration of the queries listing whole tables.
+pub const LISTING_QUERY_TIMEOUT: Duration = Duration::from_secs(60);
+
+// QUERY CANCELLATION
+// ================================================================================================
+
+/// Cancellation of a query running on a pooled connection.
+///
+/// Cancelling interrupts the SQLite statement being executed, if any. SQLite ignores interruptions
+/// happening between two statements, so queries made of several statements have to
+/// [check](Self::check) for cancellation between them.
+#[derive(Default)]
+pub struct QueryCancellation {
+    state: Mutex<CancellationState>,
+}
+
+#[derive(Default)]
+struct CancellationState {
+    cancelled: bool,
+    /// Interrupts the connection running the query, set while the query is running.
+    interrupt: Option<InterruptHandle>,
+}
+
+impl QueryCancellation {
+    /// Returns [DatabaseError::QueryCancelled] if the query was cancelled.
+    pub fn check(&self) -> Result<(), DatabaseError> {
+        if self.lock().cancelled {
+            return Err(DatabaseError::QueryCancelled);
+        }
+
+        Ok(())
+    }
+
+    fn cancel(&self) {
+        let mut state = self.lock();
+        state.cancelled = true;
+        if let Some(interrupt) = state.interrupt.take() {
+            interrupt.interrupt();
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.lock().cancelled
+    }
+
+    /// Registers the connection which runs the query.
+    fn start(&self, conn: &Connection) -> Result<(), DatabaseError> {
+        let mut state = self.lock();
+        if state.cancelled {
+            return Err(DatabaseError::QueryCancelled);
+        }
+        state.interrupt = Some(conn.get_interrupt_handle());
+
+        Ok(())
+    }
+
+    /// Unregisters the connection, so that it can't be interrupted once it runs other queries.
+    fn finish(&self) {
+        self.lock().interrupt = None;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CancellationState> {
+        self.state.lock().expect("query cancellation lock poisoned")
+    }
+}
+
+/// Cancels the query when dropped, i.e. when the future waiting for the query is dropped.
+struct CancelOnDrop(Arc<QueryCancellation>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+// CANCELLABLE INTERACTION
+// ================================================================================================
+
+/// Runs `query` on a pooled connection on behalf of a client request.
+///
+/// The query is cancelled if the returned future is dropped, which is what happens to the request
+/// handlers when the client disconnects or the request deadline expires. It is also cancelled if it
+/// runs for longer than `timeout`, failing with [DatabaseError::QueryTimeout].
+///
+/// Cancelled queries fail with [DatabaseError::QueryCancelled], and release their connection as
+/// soon as SQLite notices the interruption, instead of running to completion.
+pub async fn interact<T, E, F>(
+    pool: &Pool,
+    timeout: Duration,
+    name: &'static str,
+    query: F,
+) -> Result<T, E>
+where
+    T: Send + 'static,
+    E: From<DatabaseError> + Send + 'static,
+    F: FnOnce(&mut Connection, &QueryCancellation) -> Result<T, E> + Send + 'static,
+{
+    let wait_start = Instant::now();
+    let conn = pool.get().await.map_err(DatabaseError::MissingDbConnection)?;
+    record_pool_wait(pool, name, wait_start.elapsed());
+
+    let cancellation = Arc::new(QueryCancellation::default());
+    let _cancel_on_drop = CancelOnDrop(cancellation.clone());
+
+    let task_cancellation = cancellation.clone();
+    let mut task = pin!(conn.interact(in_current_span(move |conn| {
+        task_cancellation.start(conn)?;
+        let result = query(conn, &task_cancellation);
+        task_cancellation.finish();
+
+        // Failures of cancelled queries are caused by the interruption
+        match result {
+            Err(_) if task_cancellation.is_cancelled() => Err(DatabaseError::QueryCancelled.into()),
+            result => result,
+        }
+    })));
+
+    let result = match time::timeout(timeout, &mut task).await {
+        Ok(result) => result,
+        Err(_) => {
+            cancellation.cancel();
+            // Wait for the interrupted query to release the connection
+            let _ = task.await;
+
+            return Err(DatabaseError::QueryTimeout(timeout).into());
+        },
+    };
+
+    result.map_err(|err| DatabaseError::InteractError(format!("{name} task failed: {err}")))?
+}
+
+// TRACING
+// ================================================================================================
+
+/// Wraps `query` to run within the current span.
+///
+/// Queries run on the blocking threads of the connection pool, where the span of the request or
+/// task making the query is otherwise lost, and their spans and logs would be unconnected roots.
+pub fn in_current_span<T, F>(query: F) -> impl FnOnce(&mut Connection) -> T + Send + 'static
+where
+    F: FnOnce(&mut Connection) -> T + Send + 'static,
+{
+    let span = Span::current();
+    move |conn| span.in_scope(|| query(conn))
+}
@@ -1,19 +1,35 @@
 use std::sync::LazyLock;
 
 use miden_objects::crypto::hash::blake::{Blake3Digest, Blake3_160};
-use rusqlite::Connection;
-use rusqlite_migration::{Migrations, SchemaVersion, M};
+use rusqlite::{Connection, Transaction};
+use rusqlite_migration::{HookError, HookResult, Migrations, SchemaVersion, M};
 use tracing::{debug, error, info, instrument};
 
 use crate::{
-    db::{settings::Settings, sql::schema_version},
+    db::{
+        settings::Settings,
+        sql::{self, schema_version},
+    },
     errors::DatabaseError,
     COMPONENT,
 };
 
 type Hash = Blake3Digest<20>;
 
-const MIGRATION_SCRIPTS: [&str; 1] = [include_str!("migrations/001-init.sql")];
+const MIGRATION_SCRIPTS: [&str; 12] = [
+    include_str!("migrations/001-init.sql"),
+    include_str!("migrations/002-note-recipients.sql"),
+    include_str!("migrations/003-block-commitments.sql"),
+    include_str!("migrations/004-block-production-stats.sql"),
+    include_str!("migrations/005-note-tags.sql"),
+    include_str!("migrations/006-block-provenance.sql"),
+    include_str!("migrations/007-note-creators.sql"),
+    include_str!("migrations/008-account-deltas-export.sql"),
+    include_str!("migrations/009-note-nullifiers.sql"),
+    include_str!("migrations/010-block-note-filters.sql"),
+    include_str!("migrations/011-block-stats.sql"),
+    include_str!("migrations/012-nullifier-prefix-index.sql"),
+];
 static MIGRATION_HASHES: LazyLock<Vec<Hash>> = LazyLock::new(compute_migration_hashes);
 static MIGRATIONS: LazyLock<Migrations> = LazyLock::new(prepare_migrations);
 
@@ -24,6 +40,10 @@ fn up(s: &'static str) -> M<'static> {
 const DB_MIGRATION_HASH_FIELD: &str = "db-migration-hash";
 const DB_SCHEMA_VERSION_FIELD: &str = "db-schema-version";
 
+/// Chain tip at the time the `created_by_tx` column of the notes was added, the notes of the blocks
+/// up to it are backfilled in the background. Removed once the backfill completes.
+pub(super) const NOTE_CREATORS_BACKFILL_TIP_FIELD: &str = "note-creators-backfill-tip";
+
 #[instrument(target = "miden-store", skip_all, err)]
 pub fn apply_migrations(conn: &mut Connection) -> super::Result<()> {
     let version_before = MIGRATIONS.current_version(conn)?;
@@ -85,7 +105,85 @@ pub fn apply_migrations(conn: &mut Connection) -> super::Result<()> {
 }
 
 fn prepare_migrations() -> Migrations<'static> {
-    Migrations::new(MIGRATION_SCRIPTS.map(up).to_vec())
+    Migrations::new(vec![
+        up(MIGRATION_SCRIPTS[0]),
+        M::up_with_hook(MIGRATION_SCRIPTS[1], backfill_note_recipients).foreign_key_check(),
+        M::up_with_hook(MIGRATION_SCRIPTS[2], backfill_block_commitments).foreign_key_check(),
+        up(MIGRATION_SCRIPTS[3]),
+        up(MIGRATION_SCRIPTS[4]),
+        up(MIGRATION_SCRIPTS[5]),
+        M::up_with_hook(MIGRATION_SCRIPTS[6], schedule_note_creators_backfill).foreign_key_check(),
+        up(MIGRATION_SCRIPTS[7]),
+        M::up_with_hook(MIGRATION_SCRIPTS[8], backfill_note_nullifiers).foreign_key_check(),
+        M::up_with_hook(MIGRATION_SCRIPTS[9], backfill_block_note_filters).foreign_key_check(),
+        M::up_with_hook(MIGRATION_SCRIPTS[10], backfill_block_stats).foreign_key_check(),
+        up(MIGRATION_SCRIPTS[11]),
+    ])
+}
+
+/// Fills the `recipient` column of the public notes stored before the column was added.
+fn backfill_note_recipients(transaction: &Transaction) -> HookResult {
+    let count = sql::backfill_note_recipients(transaction)
+        .map_err(|err| HookError::Hook(err.to_string()))?;
+
+    info!(target: COMPONENT, count, "Backfilled note recipients");
+
+    Ok(())
+}
+
+/// Fills the `block_commitment` column of the block headers stored before the column was added.
+fn backfill_block_commitments(transaction: &Transaction) -> HookResult {
+    let count = sql::backfill_block_commitments(transaction)
+        .map_err(|err| HookError::Hook(err.to_string()))?;
+
+    info!(target: COMPONENT, count, "Backfilled block commitments");
+
+    Ok(())
+}
+
+/// Records the chain tip, so that the creators of the notes stored before the `created_by_tx`
+/// column was added are backfilled from the block data, which is not accessible to migrations.
+fn schedule_note_creators_backfill(transaction: &Transaction) -> HookResult {
+    let chain_tip: Option<u32> =
+        transaction.query_row("SELECT MAX(block_num) FROM block_headers", [], |row| row.get(0))?;
+
+    if let Some(chain_tip) = chain_tip {
+        Settings::set_value(transaction, NOTE_CREATORS_BACKFILL_TIP_FIELD, &chain_tip)?;
+        info!(target: COMPONENT, chain_tip, "Scheduled backfill of note creators");
+    }
+
+    Ok(())
+}
+
+/// Fills the `nullifier` column of the public notes stored before the column was added, from their
+/// retained details. The nullifiers of the notes whose details are not retained remain unknown.
+fn backfill_note_nullifiers(transaction: &Transaction) -> HookResult {
+    let count = sql::backfill_note_nullifiers(transaction)
+        .map_err(|err| HookError::Hook(err.to_string()))?;
+
+    info!(target: COMPONENT, count, "Backfilled note nullifiers");
+
+    Ok(())
+}
+
+/// Computes the note tag filters of the blocks stored before the filters were added.
+fn backfill_block_note_filters(transaction: &Transaction) -> HookResult {
+    let count = sql::backfill_block_note_filters(transaction)
+        .map_err(|err| HookError::Hook(err.to_string()))?;
+
+    info!(target: COMPONENT, count, "Backfilled block note filters");
+
+    Ok(())
+}
+
+/// Computes the statistics of the blocks stored before the statistics were recorded.
+fn backfill_block_stats(transaction: &Transaction) -> HookResult {
+    let count =
+        sql::backfill_block_stats(transaction).map_err(|err| HookError::Hook(err.to_string()))?;
+
+    info!(target: COMPONENT, count, "Backfilled block stats");
+
+    Ok(())
 }
 
 fn compute_migration_hashes() -> Vec<Hash> {
@@ -114,3 +212,13 @@ fn remove_spaces(str: &str) -> String {
 fn migrations_validate() {
     assert_eq!(MIGRATIONS.validate(), Ok(()));
 }
+
+#[test]
+fn all_migration_scripts_are_registered() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    MIGRATIONS.to_latest(&mut conn).unwrap();
+    assert_eq!(
+        MIGRATIONS.current_version(&conn).unwrap(),
+        SchemaVersion::Inside(MIGRATION_SCRIPTS.len().try_into().unwrap())
+    );
+}
@@ -0,0 +1,6 @@
+-- Recipient digest of public notes, extracted from the note details. Private notes have no
+-- details, so their recipient is unknown and left NULL. Existing public notes are backfilled by
+-- the migration hook.
+ALTER TABLE notes ADD COLUMN recipient BLOB;
+
+CREATE INDEX idx_notes_recipient ON notes(recipient, block_num, batch_index, note_index);
@@ -0,0 +1,5 @@
+-- Commitment of the block headers, for the lookup of headers by commitment. Existing headers are
+-- backfilled by the migration hook.
+ALTER TABLE block_headers ADD COLUMN block_commitment BLOB;
+
+CREATE UNIQUE INDEX idx_block_headers_commitment ON block_headers(block_commitment);
@@ -0,0 +1,15 @@
+-- Durations of the production stages of the committed blocks, in microseconds. The stages measured
+-- by the block producer are NULL for blocks it didn't report them for.
+CREATE TABLE
+    block_production_stats
+(
+    block_num       INTEGER NOT NULL,
+    inputs_fetch_us INTEGER,
+    propose_us      INTEGER,
+    prove_us        INTEGER,
+    commit_us       INTEGER,
+    total_us        INTEGER,
+
+    PRIMARY KEY (block_num),
+    FOREIGN KEY (block_num) REFERENCES block_headers(block_num)
+) STRICT, WITHOUT ROWID;
@@ -0,0 +1,4 @@
+-- Index of the note tags by block, for the tag statistics of block ranges. The statistics group the
+-- notes by a prefix of their tag, which is computed from the index entries without reading the
+-- notes table.
+CREATE INDEX idx_notes_block_num_tag ON notes(block_num, tag);
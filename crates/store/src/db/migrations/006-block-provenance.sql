@@ -0,0 +1,14 @@
+-- Latest submission of the committed blocks, as reported by their block producer. Blocks applied
+-- without producer metadata have no row. Timestamps are in milliseconds since the UNIX epoch.
+CREATE TABLE
+    block_provenance
+(
+    block_num   INTEGER NOT NULL,
+    producer_id TEXT    NOT NULL,
+    attempt     INTEGER NOT NULL,
+    produced_at INTEGER NOT NULL,
+    received_at INTEGER NOT NULL,
+
+    PRIMARY KEY (block_num),
+    FOREIGN KEY (block_num) REFERENCES block_headers(block_num)
+) STRICT, WITHOUT ROWID;
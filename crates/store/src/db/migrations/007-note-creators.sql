@@ -0,0 +1,5 @@
+-- Transaction which created each note. NULL if the sender of the note updated its account with
+-- several transactions in the block, so that the creating transaction can't be told apart. The
+-- notes stored before the column was added are backfilled in the background from the retained
+-- block data, up to the chain tip recorded by the migration hook.
+ALTER TABLE notes ADD COLUMN created_by_tx BLOB;
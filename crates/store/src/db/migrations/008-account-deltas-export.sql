@@ -0,0 +1,3 @@
+-- Exports walk the account deltas in block order, which the primary key ordered by account can't
+-- serve without sorting all the deltas of the exported accounts.
+CREATE INDEX idx_account_deltas_block_num ON account_deltas(block_num, account_id);
@@ -0,0 +1,7 @@
+-- Nullifier of public notes, computed from the notes of the applied blocks whether or not their
+-- details are retained, so that the consumption of notes can be checked by note ID. Private notes
+-- have no known nullifier and are left NULL. Existing public notes are backfilled by the migration
+-- hook from their retained details.
+ALTER TABLE notes ADD COLUMN nullifier BLOB;
+
+CREATE INDEX idx_notes_note_id ON notes(note_id);
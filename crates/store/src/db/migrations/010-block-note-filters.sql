@@ -0,0 +1,12 @@
+-- Bloom filter of the tags of the notes created in every block, see
+-- `miden_node_proto::domain::note_filters`. Blocks without notes have an empty filter. The filters
+-- of the existing blocks are backfilled by the migration hook from their notes.
+CREATE TABLE
+    block_note_filters
+(
+    block_num INTEGER NOT NULL,
+    filter    BLOB    NOT NULL,
+
+    PRIMARY KEY (block_num),
+    FOREIGN KEY (block_num) REFERENCES block_headers(block_num)
+) STRICT, WITHOUT ROWID;
@@ -0,0 +1,25 @@
+-- Counts of transactions, notes, nullifiers and new accounts of every block, with their running
+-- totals since the genesis block, so that the statistics of a range of blocks are computed from the
+-- rows of its first and last blocks. The timestamp is the one of the block header.
+--
+-- The rows of the existing blocks are backfilled by the migration hook. Accounts are then counted
+-- as new in the block of their first transaction, or in the block of their last update if they
+-- have no transaction, so that genesis accounts updated since are counted in the block of their
+-- first transaction.
+CREATE TABLE
+    block_stats
+(
+    block_num          INTEGER NOT NULL,
+    timestamp          INTEGER NOT NULL,
+    num_transactions   INTEGER NOT NULL,
+    num_notes          INTEGER NOT NULL,
+    num_nullifiers     INTEGER NOT NULL,
+    num_new_accounts   INTEGER NOT NULL,
+    total_transactions INTEGER NOT NULL,
+    total_notes        INTEGER NOT NULL,
+    total_nullifiers   INTEGER NOT NULL,
+    total_accounts     INTEGER NOT NULL,
+
+    PRIMARY KEY (block_num),
+    FOREIGN KEY (block_num) REFERENCES block_headers(block_num)
+) STRICT, WITHOUT ROWID;
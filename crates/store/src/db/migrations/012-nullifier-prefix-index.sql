@@ -0,0 +1,3 @@
+-- Index of the nullifiers by prefix in the order of their pages in `CheckNullifiersByPrefix`, so
+-- that a page is read from its position instead of scanning all the nullifiers of the prefixes.
+CREATE INDEX idx_nullifiers_prefix_block_num ON nullifiers(nullifier_prefix, block_num, nullifier);
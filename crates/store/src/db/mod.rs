@@ -2,42 +2,69 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     fs::{self, create_dir_all},
     sync::Arc,
+    time::Duration,
 };
 
 use deadpool_sqlite::{Config as SqliteConfig, Hook, HookError, Pool, Runtime};
 use miden_node_proto::{
-    domain::accounts::{AccountInfo, AccountSummary},
-    generated::note::{Note as NotePb, NoteSyncRecord as NoteSyncRecordPb},
+    domain::{
+        accounts::{AccountInfo, AccountSummary},
+        note_filters::NoteTagFilter,
+    },
+    generated::{
+        merkle::{CompressedMerklePath as CompressedMerklePathPb, MerklePath as MerklePathPb},
+        note::{
+            BlockNoteFilter as BlockNoteFilterPb, Note as NotePb,
+            NoteConsumption as NoteConsumptionPb, NoteSyncRecord as NoteSyncRecordPb,
+            NoteTagBucket as NoteTagBucketPb,
+        },
+    },
 };
 use miden_objects::{
     accounts::AccountDelta,
     block::{Block, BlockNoteIndex},
     crypto::{hash::rpo::RpoDigest, merkle::MerklePath, utils::Deserializable},
-    notes::{NoteId, NoteInclusionProof, NoteMetadata, Nullifier},
+    notes::{NoteId, NoteInclusionProof, NoteMetadata, NoteType, Nullifier},
     transaction::TransactionId,
     utils::Serializable,
     BlockHeader, GENESIS_BLOCK,
 };
-use rusqlite::vtab::array;
+use rusqlite::{vtab::array, Connection};
 use tokio::sync::oneshot;
 use tracing::{info, info_span, instrument};
 
 use crate::{
+    block_provenance::BlockProvenance,
+    block_stats::BlockProductionStats,
     blocks::BlockStore,
-    config::StoreConfig,
-    db::migrations::apply_migrations,
-    errors::{DatabaseError, DatabaseSetupError, GenesisError, NoteSyncError, StateSyncError},
+    chain_stats::ChainStats,
+    config::{StoreConfig, StoreMode},
+    db::{
+        cancellation::{in_current_span, LISTING_QUERY_TIMEOUT, REQUEST_QUERY_TIMEOUT},
+        migrations::{apply_migrations, NOTE_CREATORS_BACKFILL_TIP_FIELD},
+        pool_metrics::set_slow_pool_wait_threshold,
+        settings::Settings,
+        sql::instrumentation::set_slow_query_threshold,
+    },
+    errors::{
+        DatabaseError, DatabaseSetupError, GenesisError, NoteSyncError, StateSyncError,
+        StoreModeError,
+    },
     genesis::GenesisState,
     types::{AccountId, BlockNumber},
     COMPONENT, SQL_STATEMENT_CACHE_CAPACITY,
 };
 
+mod cancellation;
 mod migrations;
+mod pool_metrics;
 mod sql;
 
 mod settings;
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
+
+pub use pool_metrics::PoolStats;
 
 pub type Result<T, E = DatabaseError> = std::result::Result<T, E>;
 
@@ -51,6 +78,29 @@ pub struct NullifierInfo {
     pub block_num: BlockNumber,
 }
 
+/// Consumption status of a note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteConsumption {
+    pub note_id: RpoDigest,
+    /// The block which created the note.
+    pub block_num: BlockNumber,
+    /// The nullifier of the note, [None] for private notes.
+    pub nullifier: Option<Nullifier>,
+    /// The block which consumed the note, [None] if it wasn't consumed or is private.
+    pub consumed_block_num: Option<BlockNumber>,
+}
+
+impl From<NoteConsumption> for NoteConsumptionPb {
+    fn from(note: NoteConsumption) -> Self {
+        Self {
+            note_id: Some(note.note_id.into()),
+            block_num: note.block_num,
+            nullifier: note.nullifier.map(Into::into),
+            consumed_block_num: note.consumed_block_num,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct TransactionSummary {
     pub account_id: AccountId,
@@ -58,6 +108,15 @@ pub struct TransactionSummary {
     pub transaction_id: TransactionId,
 }
 
+/// Delta of the state of an account by a block, as stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountDeltaRecord {
+    pub block_num: BlockNumber,
+    pub account_id: AccountId,
+    /// The [AccountDelta], encoded using miden native format.
+    pub delta: Vec<u8>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct NoteRecord {
     pub block_num: BlockNumber,
@@ -66,17 +125,72 @@ pub struct NoteRecord {
     pub metadata: NoteMetadata,
     pub details: Option<Vec<u8>>,
     pub merkle_path: MerklePath,
+    /// The transaction which created the note, [None] if it is unknown.
+    pub created_by_tx: Option<TransactionId>,
+    /// The nullifier of the note, [None] for private notes.
+    pub nullifier: Option<Nullifier>,
+}
+
+impl NoteRecord {
+    /// Converts the record into its protobuf representation, with the Merkle path in compressed
+    /// form if `compress_merkle_path` is set.
+    pub fn into_proto(self, compress_merkle_path: bool) -> NotePb {
+        let (merkle_path, compressed_merkle_path) =
+            encode_merkle_path(&self.merkle_path, compress_merkle_path);
+
+        // The details of public notes are always part of the blocks, so they are missing only if
+        // the node didn't store them
+        let details_not_retained =
+            self.details.is_none() && self.metadata.note_type() != NoteType::Private;
+
+        NotePb {
+            block_num: self.block_num,
+            note_index: self.note_index.leaf_index_value().into(),
+            note_id: Some(self.note_id.into()),
+            metadata: Some(self.metadata.into()),
+            merkle_path,
+            details: self.details,
+            compressed_merkle_path,
+            details_not_retained,
+            created_by_tx: self.created_by_tx.map(Into::into),
+        }
+    }
 }
 
 impl From<NoteRecord> for NotePb {
     fn from(note: NoteRecord) -> Self {
+        note.into_proto(false)
+    }
+}
+
+/// Number of notes whose tags share the same prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoteTagBucket {
+    pub prefix: u32,
+    pub num_notes: u32,
+}
+
+impl From<NoteTagBucket> for NoteTagBucketPb {
+    fn from(bucket: NoteTagBucket) -> Self {
         Self {
-            block_num: note.block_num,
-            note_index: note.note_index.leaf_index_value().into(),
-            note_id: Some(note.note_id.into()),
-            metadata: Some(note.metadata.into()),
-            merkle_path: Some(Into::into(&note.merkle_path)),
-            details: note.details,
+            prefix: bucket.prefix,
+            num_notes: bucket.num_notes,
+        }
+    }
+}
+
+/// Bloom filter of the tags of the notes created in a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockNoteFilter {
+    pub block_num: BlockNumber,
+    pub filter: NoteTagFilter,
+}
+
+impl From<BlockNoteFilter> for BlockNoteFilterPb {
+    fn from(filter: BlockNoteFilter) -> Self {
+        Self {
+            block_num: filter.block_num,
+            filter: filter.filter.into_bytes(),
         }
     }
 }
@@ -105,14 +219,39 @@ pub struct NoteSyncRecord {
     pub merkle_path: MerklePath,
 }
 
+impl NoteSyncRecord {
+    /// Converts the record into its protobuf representation, with the Merkle path in compressed
+    /// form if `compress_merkle_path` is set.
+    pub fn into_proto(self, compress_merkle_path: bool) -> NoteSyncRecordPb {
+        let (merkle_path, compressed_merkle_path) =
+            encode_merkle_path(&self.merkle_path, compress_merkle_path);
+
+        NoteSyncRecordPb {
+            note_index: self.note_index.leaf_index_value().into(),
+            note_id: Some(self.note_id.into()),
+            metadata: Some(self.metadata.into()),
+            merkle_path,
+            compressed_merkle_path,
+        }
+    }
+}
+
 impl From<NoteSyncRecord> for NoteSyncRecordPb {
     fn from(note: NoteSyncRecord) -> Self {
-        Self {
-            note_index: note.note_index.leaf_index_value().into(),
-            note_id: Some(note.note_id.into()),
-            metadata: Some(note.metadata.into()),
-            merkle_path: Some(Into::into(&note.merkle_path)),
-        }
+        note.into_proto(false)
+    }
+}
+
+/// Returns the protobuf representation of a note's Merkle path, either in its plain or in its
+/// compressed form.
+fn encode_merkle_path(
+    merkle_path: &MerklePath,
+    compress: bool,
+) -> (Option<MerklePathPb>, Option<CompressedMerklePathPb>) {
+    if compress {
+        (None, Some(merkle_path.into()))
+    } else {
+        (Some(merkle_path.into()), None)
     }
 }
 
@@ -131,7 +270,6 @@ impl From<NoteRecord> for NoteSyncRecord {
 impl Db {
     /// Open a connection to the DB, apply any pending migrations, and ensure that the genesis block
     /// is as expected and present in the database.
-    // TODO: This span is logged in a root span, we should connect it to the parent one.
     #[instrument(target = "miden-store", skip_all)]
     pub async fn setup(
         config: StoreConfig,
@@ -139,6 +277,9 @@ impl Db {
     ) -> Result<Self, DatabaseSetupError> {
         info!(target: COMPONENT, %config, "Connecting to the database");
 
+        set_slow_query_threshold(Duration::from_millis(config.slow_query_threshold_ms));
+        set_slow_pool_wait_threshold(Duration::from_millis(config.slow_pool_wait_threshold_ms));
+
         if let Some(p) = config.database_filepath.parent() {
             create_dir_all(p).map_err(DatabaseError::IoError)?;
         }
@@ -187,10 +328,20 @@ impl Db {
 
         let conn = pool.get().await.map_err(DatabaseError::MissingDbConnection)?;
 
-        conn.interact(apply_migrations).await.map_err(|err| {
+        conn.interact(in_current_span(apply_migrations)).await.map_err(|err| {
             DatabaseError::InteractError(format!("Migration task failed: {err}"))
         })??;
 
+        let mode = config.store_mode()?;
+        if mode == StoreMode::Archive && block_store.pruned_below() > GENESIS_BLOCK + 1 {
+            return Err(StoreModeError::BlocksPruned(block_store.pruned_below()).into());
+        }
+        conn.interact(in_current_span(move |conn| record_store_mode(conn, mode)))
+            .await
+            .map_err(|err| {
+                DatabaseError::InteractError(format!("Record store mode task failed: {err}"))
+            })??;
+
         let db = Db { pool };
         db.ensure_genesis_block(&config.genesis_filepath.as_path().to_string_lossy(), block_store)
             .await?;
@@ -198,54 +349,63 @@ impl Db {
         Ok(db)
     }
 
+    /// Returns the state of the connection pool and the histogram of the waits for a connection.
+    pub fn pool_stats(&self) -> PoolStats {
+        PoolStats::of(&self.pool)
+    }
+
     /// Loads all the nullifiers from the DB.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
     pub async fn select_all_nullifiers(&self) -> Result<Vec<(Nullifier, BlockNumber)>> {
-        self.pool
-            .get()
-            .await?
-            .interact(sql::select_all_nullifiers)
-            .await
-            .map_err(|err| {
-                DatabaseError::InteractError(format!("Select nullifiers task failed: {err}"))
-            })?
+        cancellation::interact(&self.pool, LISTING_QUERY_TIMEOUT, "Select nullifiers", |conn, _| {
+            sql::select_all_nullifiers(conn)
+        })
+        .await
     }
 
-    /// Loads the nullifiers that match the prefixes from the DB.
+    /// Loads a page of the nullifiers that match the prefixes from the DB, after the nullifier at
+    /// position `after`.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
     pub async fn select_nullifiers_by_prefix(
         &self,
         prefix_len: u32,
         nullifier_prefixes: Vec<u32>,
+        after: Option<(BlockNumber, Nullifier)>,
+        limit: usize,
     ) -> Result<Vec<NullifierInfo>> {
-        self.pool
-            .get()
-            .await?
-            .interact(move |conn| {
-                sql::select_nullifiers_by_prefix(conn, prefix_len, &nullifier_prefixes)
-            })
-            .await
-            .map_err(|err| {
-                DatabaseError::InteractError(format!(
-                    "Select nullifiers by prefix task failed: {err}"
-                ))
-            })?
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select nullifiers by prefix",
+            move |conn, _| {
+                sql::select_nullifiers_by_prefix(
+                    conn,
+                    prefix_len,
+                    &nullifier_prefixes,
+                    after,
+                    limit,
+                )
+            },
+        )
+        .await
     }
 
     /// Loads all the notes from the DB.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
     pub async fn select_all_notes(&self) -> Result<Vec<NoteRecord>> {
-        self.pool.get().await?.interact(sql::select_all_notes).await.map_err(|err| {
-            DatabaseError::InteractError(format!("Select notes task failed: {err}"))
-        })?
+        cancellation::interact(&self.pool, LISTING_QUERY_TIMEOUT, "Select notes", |conn, _| {
+            sql::select_all_notes(conn)
+        })
+        .await
     }
 
     /// Loads all the accounts from the DB.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
     pub async fn select_all_accounts(&self) -> Result<Vec<AccountInfo>> {
-        self.pool.get().await?.interact(sql::select_all_accounts).await.map_err(|err| {
-            DatabaseError::InteractError(format!("Select accounts task failed: {err}"))
-        })?
+        cancellation::interact(&self.pool, LISTING_QUERY_TIMEOUT, "Select accounts", |conn, _| {
+            sql::select_all_accounts(conn)
+        })
+        .await
     }
 
     /// Search for a [BlockHeader] from the database by its `block_num`.
@@ -256,29 +416,103 @@ impl Db {
         &self,
         block_number: Option<BlockNumber>,
     ) -> Result<Option<BlockHeader>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select block header",
+            move |conn, _| sql::select_block_header_by_block_num(conn, block_number),
+        )
+        .await
+    }
+
+    /// Search for a [BlockHeader] from the database by its commitment.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_block_header_by_commitment(
+        &self,
+        block_commitment: RpoDigest,
+    ) -> Result<Option<BlockHeader>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select block header by commitment",
+            move |conn, _| sql::select_block_header_by_commitment(conn, block_commitment),
+        )
+        .await
+    }
+
+    /// Records the production stats of a block, replacing any previous stats of the block.
+    #[instrument(target = "miden-store", skip_all, err)]
+    pub async fn upsert_block_production_stats(&self, stats: BlockProductionStats) -> Result<()> {
         self.pool
             .get()
             .await?
-            .interact(move |conn| sql::select_block_header_by_block_num(conn, block_number))
+            .interact(in_current_span(move |conn| sql::upsert_block_production_stats(conn, &stats)))
             .await
             .map_err(|err| {
-                DatabaseError::InteractError(format!("Select block header task failed: {err}"))
-            })?
+                DatabaseError::InteractError(format!(
+                    "Upsert block production stats task failed: {err}"
+                ))
+            })??;
+
+        Ok(())
     }
 
-    /// Loads multiple block headers from the DB.
+    /// Loads the production stats of the blocks in the given range from the DB.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
-    pub async fn select_block_headers(&self, blocks: Vec<BlockNumber>) -> Result<Vec<BlockHeader>> {
+    pub async fn select_block_production_stats(
+        &self,
+        block_from: BlockNumber,
+        block_to: BlockNumber,
+    ) -> Result<Vec<BlockProductionStats>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select block production stats",
+            move |conn, _| sql::select_block_production_stats(conn, block_from, block_to),
+        )
+        .await
+    }
+
+    /// Records the provenance of a block, see [sql::upsert_block_provenance].
+    #[instrument(target = "miden-store", skip_all, err)]
+    pub async fn upsert_block_provenance(&self, provenance: BlockProvenance) -> Result<()> {
         self.pool
             .get()
             .await?
-            .interact(move |conn| sql::select_block_headers(conn, blocks))
+            .interact(in_current_span(move |conn| sql::upsert_block_provenance(conn, &provenance)))
             .await
             .map_err(|err| {
-                DatabaseError::InteractError(format!(
-                    "Select many block headers task failed: {err}"
-                ))
-            })?
+                DatabaseError::InteractError(format!("Upsert block provenance task failed: {err}"))
+            })??;
+
+        Ok(())
+    }
+
+    /// Loads the provenance of a block from the DB.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_block_provenance(
+        &self,
+        block_num: BlockNumber,
+    ) -> Result<Option<BlockProvenance>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select block provenance",
+            move |conn, _| sql::select_block_provenance(conn, block_num),
+        )
+        .await
+    }
+
+    /// Loads multiple block headers from the DB.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_block_headers(&self, blocks: Vec<BlockNumber>) -> Result<Vec<BlockHeader>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select many block headers",
+            move |conn, _| sql::select_block_headers(conn, blocks),
+        )
+        .await
     }
 
     /// Loads all the block headers from the DB.
@@ -287,55 +521,157 @@ impl Db {
         self.pool
             .get()
             .await?
-            .interact(sql::select_all_block_headers)
+            .interact(in_current_span(sql::select_all_block_headers))
             .await
             .map_err(|err| {
                 DatabaseError::InteractError(format!("Select block headers task failed: {err}"))
             })?
     }
 
+    /// Loads a chunk of the block headers from the DB, see [sql::select_block_headers_chunk].
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_block_headers_chunk(
+        &self,
+        after: Option<BlockNumber>,
+        limit: usize,
+    ) -> Result<Vec<BlockHeader>> {
+        self.pool
+            .get()
+            .await?
+            .interact(in_current_span(move |conn| {
+                sql::select_block_headers_chunk(conn, after, limit)
+            }))
+            .await
+            .map_err(|err| {
+                DatabaseError::InteractError(format!(
+                    "Select block headers chunk task failed: {err}"
+                ))
+            })?
+    }
+
     /// Loads all the account hashes from the DB.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
     pub async fn select_all_account_hashes(&self) -> Result<Vec<(AccountId, RpoDigest)>> {
         self.pool
             .get()
             .await?
-            .interact(sql::select_all_account_hashes)
+            .interact(in_current_span(sql::select_all_account_hashes))
             .await
             .map_err(|err| {
                 DatabaseError::InteractError(format!("Select account hashes task failed: {err}"))
             })?
     }
 
-    /// Loads public account details from the DB.
+    /// Loads a chunk of the account hashes from the DB, see [sql::select_account_hashes_chunk].
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
-    pub async fn select_account(&self, id: AccountId) -> Result<AccountInfo> {
+    pub async fn select_account_hashes_chunk(
+        &self,
+        after: Option<AccountId>,
+        limit: usize,
+    ) -> Result<Vec<(AccountId, RpoDigest)>> {
         self.pool
             .get()
             .await?
-            .interact(move |conn| sql::select_account(conn, id))
+            .interact(in_current_span(move |conn| {
+                sql::select_account_hashes_chunk(conn, after, limit)
+            }))
             .await
             .map_err(|err| {
-                DatabaseError::InteractError(format!("Get account details task failed: {err}"))
+                DatabaseError::InteractError(format!(
+                    "Select account hashes chunk task failed: {err}"
+                ))
             })?
     }
 
-    /// Loads public accounts details from the DB.
+    /// Loads a chunk of the nullifiers from the DB, see [sql::select_nullifiers_chunk].
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
-    pub async fn select_accounts_by_ids(
+    pub async fn select_nullifiers_chunk(
         &self,
-        account_ids: Vec<AccountId>,
-    ) -> Result<Vec<AccountInfo>> {
+        after: Option<Nullifier>,
+        limit: usize,
+    ) -> Result<Vec<(Nullifier, BlockNumber)>> {
+        self.pool
+            .get()
+            .await?
+            .interact(in_current_span(move |conn| {
+                sql::select_nullifiers_chunk(conn, after.as_ref(), limit)
+            }))
+            .await
+            .map_err(|err| {
+                DatabaseError::InteractError(format!("Select nullifiers chunk task failed: {err}"))
+            })?
+    }
+
+    /// Returns the number of rows of one of the tables of the schema.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn count_rows(&self, table_name: &'static str) -> Result<u64> {
         self.pool
             .get()
             .await?
-            .interact(move |conn| sql::select_accounts_by_ids(conn, &account_ids))
+            .interact(in_current_span(move |conn| sql::count_rows(conn, table_name)))
             .await
             .map_err(|err| {
-                DatabaseError::InteractError(format!("Get accounts details task failed: {err}"))
+                DatabaseError::InteractError(format!("Count {table_name} task failed: {err}"))
             })?
     }
 
+    /// Loads public account details from the DB.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_account(&self, id: AccountId) -> Result<AccountInfo> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Get account details",
+            move |conn, _| sql::select_account(conn, id),
+        )
+        .await
+    }
+
+    /// Loads public accounts details from the DB.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_accounts_by_ids(
+        &self,
+        account_ids: Vec<AccountId>,
+    ) -> Result<Vec<AccountInfo>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Get accounts details",
+            move |conn, _| sql::select_accounts_by_ids(conn, &account_ids),
+        )
+        .await
+    }
+
+    /// Loads the latest commitments of the given accounts from the DB.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_account_commitments(
+        &self,
+        account_ids: Vec<AccountId>,
+    ) -> Result<Vec<AccountSummary>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Get account commitments",
+            move |conn, _| sql::select_account_commitments(conn, &account_ids),
+        )
+        .await
+    }
+
+    /// Loads the transactions with the given IDs which were committed in a block from the DB.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_transactions_by_ids(
+        &self,
+        transaction_ids: Vec<TransactionId>,
+    ) -> Result<Vec<TransactionSummary>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select transactions by id",
+            move |conn, _| sql::select_transactions_by_ids(conn, &transaction_ids),
+        )
+        .await
+    }
+
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
     pub async fn get_state_sync(
         &self,
@@ -344,17 +680,22 @@ impl Db {
         note_tags: Vec<u32>,
         nullifier_prefixes: Vec<u32>,
     ) -> Result<StateSyncUpdate, StateSyncError> {
-        self.pool
-            .get()
-            .await
-            .map_err(DatabaseError::MissingDbConnection)?
-            .interact(move |conn| {
-                sql::get_state_sync(conn, block_num, &account_ids, &note_tags, &nullifier_prefixes)
-            })
-            .await
-            .map_err(|err| {
-                DatabaseError::InteractError(format!("Get state sync task failed: {err}"))
-            })?
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Get state sync",
+            move |conn, cancellation| {
+                sql::get_state_sync(
+                    conn,
+                    block_num,
+                    &account_ids,
+                    &note_tags,
+                    &nullifier_prefixes,
+                    cancellation,
+                )
+            },
+        )
+        .await
     }
 
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
@@ -363,28 +704,120 @@ impl Db {
         block_num: BlockNumber,
         note_tags: Vec<u32>,
     ) -> Result<NoteSyncUpdate, NoteSyncError> {
-        self.pool
-            .get()
-            .await
-            .map_err(DatabaseError::MissingDbConnection)?
-            .interact(move |conn| sql::get_note_sync(conn, block_num, &note_tags))
-            .await
-            .map_err(|err| {
-                DatabaseError::InteractError(format!("Get notes sync task failed: {err}"))
-            })?
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Get notes sync",
+            move |conn, cancellation| sql::get_note_sync(conn, block_num, &note_tags, cancellation),
+        )
+        .await
     }
 
     /// Loads all the Note's matching a certain NoteId from the database.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
     pub async fn select_notes_by_id(&self, note_ids: Vec<NoteId>) -> Result<Vec<NoteRecord>> {
-        self.pool
-            .get()
-            .await?
-            .interact(move |conn| sql::select_notes_by_id(conn, &note_ids))
-            .await
-            .map_err(|err| {
-                DatabaseError::InteractError(format!("Select note by id task failed: {err}"))
-            })?
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select note by id",
+            move |conn, _| sql::select_notes_by_id(conn, &note_ids),
+        )
+        .await
+    }
+
+    /// Loads the consumption status of the notes matching the given NoteId's from the database.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_notes_consumption(
+        &self,
+        note_ids: Vec<NoteId>,
+    ) -> Result<Vec<NoteConsumption>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select notes consumption",
+            move |conn, _| sql::select_notes_consumption(conn, &note_ids),
+        )
+        .await
+    }
+
+    /// Loads a page of the public notes sent to any of the given recipients, starting from block
+    /// `from_block` and after the note at position `after`.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_notes_by_recipient(
+        &self,
+        recipients: Vec<RpoDigest>,
+        from_block: BlockNumber,
+        after: Option<(BlockNumber, BlockNoteIndex)>,
+        limit: usize,
+    ) -> Result<Vec<NoteRecord>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select notes by recipient",
+            move |conn, _| {
+                sql::select_notes_by_recipient(conn, &recipients, from_block, after, limit)
+            },
+        )
+        .await
+    }
+
+    /// Loads the number of notes per tag prefix in the blocks `from_block..=to_block`.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_note_tag_statistics(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        prefix_len: u32,
+        exclude_network_notes: bool,
+    ) -> Result<Vec<NoteTagBucket>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select note tag statistics",
+            move |conn, _| {
+                sql::select_note_tag_statistics(
+                    conn,
+                    from_block,
+                    to_block,
+                    prefix_len,
+                    exclude_network_notes,
+                )
+            },
+        )
+        .await
+    }
+
+    /// Loads the note tag filters of the blocks `from_block..=to_block`.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_block_note_filters(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> Result<Vec<BlockNoteFilter>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select block note filters",
+            move |conn, _| sql::select_block_note_filters(conn, from_block, to_block),
+        )
+        .await
+    }
+
+    /// Loads the statistics of the blocks `from_block..=to_block`, [None] if `from_block` is not
+    /// part of the chain.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_chain_stats(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> Result<Option<ChainStats>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select chain stats",
+            move |conn, _| sql::select_chain_stats(conn, from_block, to_block),
+        )
+        .await
     }
 
     /// Loads inclusion proofs for notes matching the given IDs.
@@ -393,16 +826,13 @@ impl Db {
         &self,
         note_ids: BTreeSet<NoteId>,
     ) -> Result<BTreeMap<NoteId, NoteInclusionProof>> {
-        self.pool
-            .get()
-            .await?
-            .interact(move |conn| sql::select_note_inclusion_proofs(conn, note_ids))
-            .await
-            .map_err(|err| {
-                DatabaseError::InteractError(format!(
-                    "Select block note inclusion proofs task failed: {err}"
-                ))
-            })?
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select block note inclusion proofs",
+            move |conn, _| sql::select_note_inclusion_proofs(conn, note_ids),
+        )
+        .await
     }
 
     /// Loads all note IDs matching a certain NoteId from the database.
@@ -417,7 +847,6 @@ impl Db {
     ///
     /// `allow_acquire` and `acquire_done` are used to synchronize writes to the DB with writes to
     /// the in-memory trees. Further details available on [super::state::State::apply_block].
-    // TODO: This span is logged in a root span, we should connect it to the parent one.
     #[instrument(target = "miden-store", skip_all, err)]
     pub async fn apply_block(
         &self,
@@ -429,8 +858,7 @@ impl Db {
         self.pool
             .get()
             .await?
-            .interact(move |conn| -> Result<()> {
-                // TODO: This span is logged in a root span, we should connect it to the parent one.
+            .interact(in_current_span(move |conn| -> Result<()> {
                 let _span = info_span!(target: COMPONENT, "write_block_to_db").entered();
 
                 let transaction = conn.transaction()?;
@@ -448,7 +876,7 @@ impl Db {
                 transaction.commit()?;
 
                 Ok(())
-            })
+            }))
             .await
             .map_err(|err| {
                 DatabaseError::InteractError(format!("Apply block task failed: {err}"))
@@ -457,23 +885,147 @@ impl Db {
         Ok(())
     }
 
-    /// Loads account deltas from the DB for given account ID and block range.
-    /// Note, that `from_block` is exclusive and `to_block` is inclusive.
-    pub(crate) async fn select_account_state_deltas(
+    /// Returns the last block whose notes' creators must be backfilled, [None] if there is no
+    /// backfill pending.
+    pub async fn select_note_creators_backfill_tip(&self) -> Result<Option<BlockNumber>> {
+        self.pool
+            .get()
+            .await?
+            .interact(in_current_span(|conn| {
+                Settings::get_value(conn, NOTE_CREATORS_BACKFILL_TIP_FIELD)
+            }))
+            .await
+            .map_err(|err| {
+                DatabaseError::InteractError(format!(
+                    "Select note creators backfill tip task failed: {err}"
+                ))
+            })?
+            .map_err(Into::into)
+    }
+
+    /// Marks the backfill of the notes' creators as completed.
+    pub async fn finish_note_creators_backfill(&self) -> Result<()> {
+        self.pool
+            .get()
+            .await?
+            .interact(in_current_span(|conn| {
+                Settings::remove_value(conn, NOTE_CREATORS_BACKFILL_TIP_FIELD)
+            }))
+            .await
+            .map_err(|err| {
+                DatabaseError::InteractError(format!(
+                    "Finish note creators backfill task failed: {err}"
+                ))
+            })??;
+
+        Ok(())
+    }
+
+    /// Loads the number of the next block with notes to backfill, see
+    /// [sql::select_next_block_with_unlinked_notes].
+    pub async fn select_next_block_with_unlinked_notes(
         &self,
-        account_id: AccountId,
-        from_block: BlockNumber,
-        to_block: BlockNumber,
-    ) -> Result<Vec<AccountDelta>> {
+        after: Option<BlockNumber>,
+        up_to: BlockNumber,
+    ) -> Result<Option<BlockNumber>> {
         self.pool
             .get()
+            .await?
+            .interact(in_current_span(move |conn| {
+                sql::select_next_block_with_unlinked_notes(conn, after, up_to)
+            }))
             .await
-            .map_err(DatabaseError::MissingDbConnection)?
-            .interact(move |conn| -> Result<Vec<AccountDelta>> {
-                sql::select_account_deltas(conn, account_id, from_block, to_block)
-            })
+            .map_err(|err| {
+                DatabaseError::InteractError(format!(
+                    "Select next block with unlinked notes task failed: {err}"
+                ))
+            })?
+    }
+
+    /// Sets the creating transaction of the notes of a block, see [sql::update_note_creators].
+    #[instrument(target = "miden-store", skip_all, err)]
+    pub async fn update_note_creators(
+        &self,
+        block_num: BlockNumber,
+        creators: BTreeMap<NoteId, TransactionId>,
+    ) -> Result<usize> {
+        self.pool
+            .get()
+            .await?
+            .interact(in_current_span(move |conn| -> Result<usize> {
+                let transaction = conn.transaction()?;
+                let count = sql::update_note_creators(&transaction, block_num, &creators)?;
+                transaction.commit()?;
+
+                Ok(count)
+            }))
             .await
-            .map_err(|err| DatabaseError::InteractError(err.to_string()))?
+            .map_err(|err| {
+                DatabaseError::InteractError(format!("Update note creators task failed: {err}"))
+            })?
+    }
+
+    /// Loads the accumulated account delta from the DB for given account ID and block range.
+    /// Note, that `from_block` is exclusive and `to_block` is inclusive.
+    pub(crate) async fn select_account_state_delta(
+        &self,
+        account_id: AccountId,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> Result<AccountDelta> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select account state delta",
+            move |conn, _| sql::select_account_delta(conn, account_id, from_block, to_block),
+        )
+        .await
+    }
+
+    /// Counts the blocks updating the account in the given block range.
+    /// Note, that `from_block` is exclusive and `to_block` is inclusive.
+    pub(crate) async fn count_account_updates(
+        &self,
+        account_id: AccountId,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> Result<u32> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Count account updates",
+            move |conn, _| sql::count_account_updates(conn, account_id, from_block, to_block),
+        )
+        .await
+    }
+
+    /// Loads a chunk of the deltas of the given accounts in the blocks `from_block..=to_block`,
+    /// after the delta at position `after`, see [sql::select_account_deltas_chunk].
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_account_deltas_chunk(
+        &self,
+        account_ids: Vec<AccountId>,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        after: Option<(BlockNumber, AccountId)>,
+        limit: usize,
+    ) -> Result<Vec<AccountDeltaRecord>> {
+        cancellation::interact(
+            &self.pool,
+            REQUEST_QUERY_TIMEOUT,
+            "Select account deltas chunk",
+            move |conn, _| {
+                sql::select_account_deltas_chunk(
+                    conn,
+                    &account_ids,
+                    from_block,
+                    to_block,
+                    after,
+                    limit,
+                )
+            },
+        )
+        .await
     }
 
     // HELPERS
@@ -525,9 +1077,7 @@ impl Db {
                     .get()
                     .await
                     .map_err(DatabaseError::MissingDbConnection)?
-                    .interact(move |conn| -> Result<()> {
-                        // TODO: This span is logged in a root span, we should connect it to the
-                        // parent one.
+                    .interact(in_current_span(move |conn| -> Result<()> {
                         let span = info_span!(target: COMPONENT, "write_genesis_block_to_db");
                         let guard = span.enter();
 
@@ -546,7 +1096,7 @@ impl Db {
 
                         drop(guard);
                         Ok(())
-                    })
+                    }))
                     .await
                     .map_err(|err| GenesisError::ApplyBlockFailed(err.to_string()))??;
             },
@@ -555,3 +1105,24 @@ impl Db {
         Ok(())
     }
 }
+
+/// Name of the settings field recording the mode the store last ran in.
+const STORE_MODE_FIELD: &str = "store-mode";
+
+/// Records the mode the store runs in.
+///
+/// # Errors
+/// Returns [StoreModeError::PreviouslyPruned] if the store is in archive mode while it ran in
+/// pruned mode before, as the data it pruned can't be recovered.
+fn record_store_mode(conn: &mut Connection, mode: StoreMode) -> Result<(), DatabaseSetupError> {
+    let previous_mode: Option<String> =
+        Settings::get_value(conn, STORE_MODE_FIELD).map_err(DatabaseError::SqliteError)?;
+    if mode == StoreMode::Archive && previous_mode.as_deref() == Some(StoreMode::Pruned.as_str()) {
+        return Err(StoreModeError::PreviouslyPruned.into());
+    }
+
+    Settings::set_value(conn, STORE_MODE_FIELD, &mode.as_str())
+        .map_err(DatabaseError::SqliteError)?;
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+//! Metrics of the database connection pool.
+//!
+//! Every query waits for a pooled connection before running. When the store is overloaded, the
+//! connections are all in use and the wait grows, which otherwise only shows as rising latencies of
+//! the requests. The waits are recorded in a histogram, and waits longer than the slow wait
+//! threshold emit a `WARN` event with the state of the pool.
+//!
+//! The metrics are shared by the pools of the process, and reported along with the current state of
+//! the pool by [PoolStats].
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use deadpool_sqlite::Pool;
+use miden_node_proto::generated::responses::{
+    GetDatabasePoolStatsResponse, PoolWaitBucket as PoolWaitBucketPb,
+};
+use tracing::warn;
+
+use crate::{config::DEFAULT_SLOW_POOL_WAIT_THRESHOLD_MS, COMPONENT};
+
+/// Upper bounds of the buckets of the waits for a connection, in milliseconds. Longer waits are
+/// counted in a last, unbounded, bucket.
+const POOL_WAIT_BUCKETS_MS: [u64; 10] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 5000];
+
+static SLOW_POOL_WAIT_THRESHOLD_US: AtomicU64 =
+    AtomicU64::new(DEFAULT_SLOW_POOL_WAIT_THRESHOLD_MS * 1000);
+
+static POOL_WAITS: PoolWaits = PoolWaits::new();
+
+/// Sets the wait for a connection above which the waits are reported as slow.
+pub fn set_slow_pool_wait_threshold(threshold: Duration) {
+    SLOW_POOL_WAIT_THRESHOLD_US.store(threshold.as_micros() as u64, Ordering::Relaxed);
+}
+
+fn slow_pool_wait_threshold() -> Duration {
+    Duration::from_micros(SLOW_POOL_WAIT_THRESHOLD_US.load(Ordering::Relaxed))
+}
+
+/// Records that the query `name` waited `wait` for a connection of `pool`.
+pub fn record_pool_wait(pool: &Pool, name: &'static str, wait: Duration) {
+    let slow = wait > slow_pool_wait_threshold();
+    POOL_WAITS.record(wait, slow);
+
+    if slow {
+        let status = pool.status();
+        warn!(
+            target: COMPONENT,
+            query = name,
+            wait_ms = wait.as_millis(),
+            pool_size = status.size,
+            max_pool_size = status.max_size,
+            available = status.available,
+            waiting = status.waiting,
+            "Slow wait for a database connection"
+        );
+    }
+}
+
+// POOL WAITS
+// ================================================================================================
+
+/// Histogram of the waits for a connection.
+struct PoolWaits {
+    /// Number of waits per bucket of [POOL_WAIT_BUCKETS_MS], not cumulative, and the longer waits.
+    buckets: [AtomicU64; POOL_WAIT_BUCKETS_MS.len() + 1],
+    num_waits: AtomicU64,
+    total_wait_us: AtomicU64,
+    num_slow_waits: AtomicU64,
+}
+
+impl PoolWaits {
+    const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const ZERO: AtomicU64 = AtomicU64::new(0);
+        Self {
+            buckets: [ZERO; POOL_WAIT_BUCKETS_MS.len() + 1],
+            num_waits: ZERO,
+            total_wait_us: ZERO,
+            num_slow_waits: ZERO,
+        }
+    }
+
+    fn record(&self, wait: Duration, slow: bool) {
+        let wait_ms = wait.as_millis();
+        let bucket = POOL_WAIT_BUCKETS_MS
+            .iter()
+            .position(|upper_bound| wait_ms <= u128::from(*upper_bound))
+            .unwrap_or(POOL_WAIT_BUCKETS_MS.len());
+
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.num_waits.fetch_add(1, Ordering::Relaxed);
+        self.total_wait_us.fetch_add(wait.as_micros() as u64, Ordering::Relaxed);
+        if slow {
+            self.num_slow_waits.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+// POOL STATS
+// ================================================================================================
+
+/// State of the connection pool and histogram of the waits for a connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Maximum number of connections of the pool.
+    pub max_size: usize,
+    /// Number of open connections.
+    pub size: usize,
+    /// Number of connections running a query.
+    pub in_use: usize,
+    /// Number of queries waiting for a connection.
+    pub waiting: usize,
+    /// Upper bound in milliseconds and number of waits of every bucket, [None] for the last,
+    /// unbounded, bucket. The counts are not cumulative.
+    pub wait_buckets: Vec<(Option<u64>, u64)>,
+    pub num_waits: u64,
+    pub total_wait_us: u64,
+    /// Number of waits longer than the slow wait threshold.
+    pub num_slow_waits: u64,
+}
+
+impl PoolStats {
+    /// Returns the current state of `pool`, along with the waits of the pools of the process.
+    pub fn of(pool: &Pool) -> Self {
+        let status = pool.status();
+        let upper_bounds = POOL_WAIT_BUCKETS_MS.iter().copied().map(Some).chain([None]);
+
+        Self {
+            max_size: status.max_size,
+            size: status.size,
+            in_use: status.size.saturating_sub(status.available),
+            waiting: status.waiting,
+            wait_buckets: upper_bounds
+                .zip(&POOL_WAITS.buckets)
+                .map(|(upper_bound, count)| (upper_bound, count.load(Ordering::Relaxed)))
+                .collect(),
+            num_waits: POOL_WAITS.num_waits.load(Ordering::Relaxed),
+            total_wait_us: POOL_WAITS.total_wait_us.load(Ordering::Relaxed),
+            num_slow_waits: POOL_WAITS.num_slow_waits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl From<PoolStats> for GetDatabasePoolStatsResponse {
+    fn from(stats: PoolStats) -> Self {
+        Self {
+            max_size: stats.max_size as u32,
+            size: stats.size as u32,
+            in_use: stats.in_use as u32,
+            waiting: stats.waiting as u32,
+            wait_buckets: stats
+                .wait_buckets
+                .into_iter()
+                .map(|(upper_bound_ms, count)| PoolWaitBucketPb { upper_bound_ms, count })
+                .collect(),
+            num_waits: stats.num_waits,
+            total_wait_us: stats.total_wait_us,
+            num_slow_waits: stats.num_slow_waits,
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waits_are_counted_in_their_bucket() {
+        let waits = PoolWaits::new();
+        waits.record(Duration::from_micros(500), false);
+        waits.record(Duration::from_millis(1), false);
+        waits.record(Duration::from_millis(30), false);
+        waits.record(Duration::from_secs(10), true);
+
+        let counts: Vec<u64> =
+            waits.buckets.iter().map(|count| count.load(Ordering::Relaxed)).collect();
+        assert_eq!(counts, [2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(waits.num_waits.load(Ordering::Relaxed), 4);
+        assert_eq!(waits.total_wait_us.load(Ordering::Relaxed), 10_031_500);
+        assert_eq!(waits.num_slow_waits.load(Ordering::Relaxed), 1);
+    }
+}
@@ -26,4 +26,10 @@ impl Settings {
 
         Ok(())
     }
+
+    pub fn remove_value(conn: &Connection, name: &str) -> Result<()> {
+        conn.execute("DELETE FROM settings WHERE name = $1", params![name])?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,269 @@
+//! Instrumentation of the SQL statements.
+//!
+//! Every statement runs in a `DEBUG` span carrying a stable label, the number of rows it produced
+//! and its duration, so that the statements making up a slow request can be told apart. Statements
+//! running for longer than the slow query threshold additionally emit a `WARN` event with the
+//! cardinalities of their parameters. Parameter values are never logged.
+//!
+//! The overhead for fast statements is reading the clock twice: the span is disabled unless
+//! `DEBUG` is enabled for the store, and events are only built above the threshold.
+
+use std::{
+    fmt::{Display, Formatter},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use tracing::{debug_span, field, span::EnteredSpan, warn};
+
+use crate::{config::DEFAULT_SLOW_QUERY_THRESHOLD_MS, COMPONENT};
+
+static SLOW_QUERY_THRESHOLD_US: AtomicU64 = AtomicU64::new(DEFAULT_SLOW_QUERY_THRESHOLD_MS * 1000);
+
+/// Sets the duration above which statements are reported as slow.
+pub fn set_slow_query_threshold(threshold: Duration) {
+    SLOW_QUERY_THRESHOLD_US.store(threshold.as_micros() as u64, Ordering::Relaxed);
+}
+
+fn slow_query_threshold() -> Duration {
+    Duration::from_micros(SLOW_QUERY_THRESHOLD_US.load(Ordering::Relaxed))
+}
+
+// STATEMENT LABELS
+// ================================================================================================
+
+/// Labels of the statements, named after the function running them.
+///
+/// The labels are part of the logs and traces consumed by dashboards, so they must not change.
+pub mod labels {
+    pub const SELECT_ALL_ACCOUNTS: &str = "select_all_accounts";
+    pub const SELECT_ALL_ACCOUNT_HASHES: &str = "select_all_account_hashes";
+    pub const SELECT_ACCOUNT_HASHES_CHUNK: &str = "select_account_hashes_chunk";
+    pub const SELECT_ACCOUNTS_BY_BLOCK_RANGE: &str = "select_accounts_by_block_range";
+    pub const SELECT_ACCOUNT: &str = "select_account";
+    pub const SELECT_ACCOUNTS_BY_IDS: &str = "select_accounts_by_ids";
+    pub const SELECT_ACCOUNT_COMMITMENTS: &str = "select_account_commitments";
+    pub const SELECT_ACCOUNT_DELTAS: &str = "select_account_deltas";
+    pub const SELECT_ACCOUNT_DELTAS_CHUNK: &str = "select_account_deltas_chunk";
+    pub const COUNT_ACCOUNT_UPDATES: &str = "count_account_updates";
+    pub const UPSERT_ACCOUNTS: &str = "upsert_accounts";
+    pub const INSERT_NULLIFIERS_FOR_BLOCK: &str = "insert_nullifiers_for_block";
+    pub const SELECT_ALL_NULLIFIERS: &str = "select_all_nullifiers";
+    pub const SELECT_NULLIFIERS_CHUNK: &str = "select_nullifiers_chunk";
+    pub const SELECT_NULLIFIERS_BY_BLOCK_RANGE: &str = "select_nullifiers_by_block_range";
+    pub const SELECT_NULLIFIERS_BY_PREFIX: &str = "select_nullifiers_by_prefix";
+    pub const SELECT_ALL_NOTES: &str = "select_all_notes";
+    pub const INSERT_NOTES: &str = "insert_notes";
+    pub const BACKFILL_NOTE_RECIPIENTS: &str = "backfill_note_recipients";
+    pub const BACKFILL_NOTE_NULLIFIERS: &str = "backfill_note_nullifiers";
+    pub const SELECT_NEXT_BLOCK_WITH_UNLINKED_NOTES: &str = "select_next_block_with_unlinked_notes";
+    pub const UPDATE_NOTE_CREATORS: &str = "update_note_creators";
+    pub const SELECT_NOTES_SINCE_BLOCK_BY_TAG_AND_SENDER: &str =
+        "select_notes_since_block_by_tag_and_sender";
+    pub const SELECT_NOTES_BY_ID: &str = "select_notes_by_id";
+    pub const SELECT_NOTES_CONSUMPTION: &str = "select_notes_consumption";
+    pub const SELECT_NOTES_BY_RECIPIENT: &str = "select_notes_by_recipient";
+    pub const SELECT_NOTE_TAG_STATISTICS: &str = "select_note_tag_statistics";
+    pub const INSERT_BLOCK_NOTE_FILTER: &str = "insert_block_note_filter";
+    pub const BACKFILL_BLOCK_NOTE_FILTERS: &str = "backfill_block_note_filters";
+    pub const SELECT_BLOCK_NOTE_FILTERS: &str = "select_block_note_filters";
+    pub const SELECT_NOTE_INCLUSION_PROOFS: &str = "select_note_inclusion_proofs";
+    pub const INSERT_BLOCK_HEADER: &str = "insert_block_header";
+    pub const BACKFILL_BLOCK_COMMITMENTS: &str = "backfill_block_commitments";
+    pub const SELECT_BLOCK_HEADER_BY_BLOCK_NUM: &str = "select_block_header_by_block_num";
+    pub const SELECT_BLOCK_HEADER_BY_COMMITMENT: &str = "select_block_header_by_commitment";
+    pub const UPSERT_BLOCK_PRODUCTION_STATS: &str = "upsert_block_production_stats";
+    pub const SELECT_BLOCK_PRODUCTION_STATS: &str = "select_block_production_stats";
+    pub const UPSERT_BLOCK_PROVENANCE: &str = "upsert_block_provenance";
+    pub const SELECT_BLOCK_PROVENANCE: &str = "select_block_provenance";
+    pub const INSERT_BLOCK_STATS: &str = "insert_block_stats";
+    pub const BACKFILL_BLOCK_STATS: &str = "backfill_block_stats";
+    pub const SELECT_CHAIN_STATS: &str = "select_chain_stats";
+    pub const SELECT_BLOCK_HEADERS: &str = "select_block_headers";
+    pub const SELECT_ALL_BLOCK_HEADERS: &str = "select_all_block_headers";
+    pub const SELECT_BLOCK_HEADERS_CHUNK: &str = "select_block_headers_chunk";
+    pub const INSERT_TRANSACTIONS: &str = "insert_transactions";
+    pub const SELECT_TRANSACTIONS_BY_IDS: &str = "select_transactions_by_ids";
+    pub const SELECT_TRANSACTIONS_BY_ACCOUNTS_AND_BLOCK_RANGE: &str =
+        "select_transactions_by_accounts_and_block_range";
+    pub const COUNT_ROWS: &str = "count_rows";
+}
+
+// STATEMENT TIMER
+// ================================================================================================
+
+/// Times a statement from its creation until it is dropped.
+///
+/// `N` is the number of parameters whose cardinality is reported for slow statements, e.g. the
+/// number of requested note IDs.
+pub struct StatementTimer<const N: usize> {
+    label: &'static str,
+    params: [(&'static str, usize); N],
+    threshold: Duration,
+    rows: Option<usize>,
+    started_at: Instant,
+    span: EnteredSpan,
+}
+
+impl<const N: usize> StatementTimer<N> {
+    /// Starts timing the statement with the given `label`, and the given parameter cardinalities.
+    pub fn start(label: &'static str, params: [(&'static str, usize); N]) -> Self {
+        Self::with_threshold(label, params, slow_query_threshold())
+    }
+
+    fn with_threshold(
+        label: &'static str,
+        params: [(&'static str, usize); N],
+        threshold: Duration,
+    ) -> Self {
+        let span = debug_span!(
+            target: COMPONENT,
+            "sql_statement",
+            label,
+            rows = field::Empty,
+            duration_us = field::Empty,
+        )
+        .entered();
+
+        Self {
+            label,
+            params,
+            threshold,
+            rows: None,
+            started_at: Instant::now(),
+            span,
+        }
+    }
+
+    /// Records the number of rows produced by the statement, and stops timing it.
+    ///
+    /// Statements which fail are timed until the timer is dropped, and report no rows.
+    pub fn finish(mut self, rows: usize) {
+        self.rows = Some(rows);
+    }
+}
+
+impl<const N: usize> Drop for StatementTimer<N> {
+    fn drop(&mut self) {
+        let duration = self.started_at.elapsed();
+
+        self.span.record("rows", self.rows);
+        self.span.record("duration_us", duration.as_micros() as u64);
+
+        if duration >= self.threshold {
+            warn!(
+                target: COMPONENT,
+                label = self.label,
+                duration_ms = duration.as_millis() as u64,
+                rows = self.rows,
+                params = %ParamCardinalities(&self.params),
+                "Slow SQL statement"
+            );
+        }
+    }
+}
+
+/// Formats parameter cardinalities as `name=count` pairs.
+struct ParamCardinalities<'a>(&'a [(&'static str, usize)]);
+
+impl Display for ParamCardinalities<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, (name, count)) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{name}={count}")?;
+        }
+
+        Ok(())
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use tracing::{field::Visit, Event, Level, Subscriber};
+    use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+    use super::*;
+
+    /// Collects the fields of the `WARN` events.
+    #[derive(Clone, Default)]
+    struct WarnCollector(Arc<Mutex<Vec<Vec<(String, String)>>>>);
+
+    impl<S: Subscriber> Layer<S> for WarnCollector {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            if *event.metadata().level() != Level::WARN {
+                return;
+            }
+
+            let mut fields = FieldCollector::default();
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    #[derive(Default)]
+    struct FieldCollector(Vec<(String, String)>);
+
+    impl Visit for FieldCollector {
+        fn record_debug(&mut self, field: &field::Field, value: &dyn std::fmt::Debug) {
+            self.0.push((field.name().to_string(), format!("{value:?}")));
+        }
+
+        fn record_str(&mut self, field: &field::Field, value: &str) {
+            self.0.push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    /// Runs a statement taking `delay`, with the given slow query `threshold`, and returns the
+    /// fields of the emitted warnings.
+    fn run_statement(delay: Duration, threshold: Duration) -> Vec<Vec<(String, String)>> {
+        let collector = WarnCollector::default();
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let timer = StatementTimer::with_threshold(
+                labels::SELECT_NOTES_BY_ID,
+                [("note_ids", 3), ("tags", 0)],
+                threshold,
+            );
+            thread::sleep(delay);
+            timer.finish(2);
+        });
+
+        let warnings = collector.0.lock().unwrap().clone();
+        warnings
+    }
+
+    #[test]
+    fn slow_statements_are_reported() {
+        let warnings = run_statement(Duration::from_millis(20), Duration::from_millis(10));
+
+        assert_eq!(warnings.len(), 1);
+        let field = |name: &str| {
+            warnings[0]
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(field("label"), Some(labels::SELECT_NOTES_BY_ID));
+        assert_eq!(field("rows"), Some("2"));
+        assert_eq!(field("params"), Some("note_ids=3, tags=0"));
+        assert!(field("duration_ms").unwrap().parse::<u64>().unwrap() >= 20);
+    }
+
+    #[test]
+    fn fast_statements_are_not_reported() {
+        let warnings = run_statement(Duration::ZERO, Duration::from_secs(10));
+
+        assert!(warnings.is_empty());
+    }
+}
@@ -0,0 +1,2547 @@
+//! Wrapper functions for SQL statements.
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use miden_node_proto::domain::{
+    accounts::{AccountInfo, AccountSummary},
+    note_filters::NoteTagFilter,
+    prefixes::{
+        note_tag_prefix_shift, nullifier_prefix, NOTE_TAG_EXECUTION_MODE_BIT, NULLIFIER_PREFIX_LEN,
+    },
+};
+use miden_objects::{
+    accounts::{delta::AccountUpdateDetails, Account, AccountDelta},
+    block::{BlockAccountUpdate, BlockNoteIndex},
+    crypto::{hash::rpo::RpoDigest, merkle::MerklePath},
+    notes::{Note, NoteId, NoteInclusionProof, NoteMetadata, NoteType, Nullifier},
+    transaction::TransactionId,
+    utils::serde::{Deserializable, Serializable},
+    BlockHeader,
+};
+use rusqlite::{
+    params,
+    types::{Value, ValueRef},
+    Connection, OptionalExtension, Transaction,
+};
+
+use self::instrumentation::{labels, StatementTimer};
+use super::{
+    cancellation::QueryCancellation, AccountDeltaRecord, BlockNoteFilter, NoteConsumption,
+    NoteRecord, NoteSyncRecord, NoteSyncUpdate, NoteTagBucket, NullifierInfo, Result,
+    StateSyncUpdate, TransactionSummary,
+};
+use crate::{
+    block_provenance::BlockProvenance,
+    block_stats::BlockProductionStats,
+    chain_stats::{BlockStats, ChainStats},
+    errors::{DatabaseError, NoteSyncError, StateSyncError},
+    types::{AccountId, BlockNumber},
+};
+
+pub mod instrumentation;
+
+// ACCOUNT QUERIES
+// ================================================================================================
+
+/// Select all accounts from the DB using the given [Connection].
+///
+/// # Returns
+///
+/// A vector with accounts, or an error.
+pub fn select_all_accounts(conn: &mut Connection) -> Result<Vec<AccountInfo>> {
+    let timer = StatementTimer::start(labels::SELECT_ALL_ACCOUNTS, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            account_id,
+            account_hash,
+            block_num,
+            details
+        FROM
+            accounts
+        ORDER BY
+            block_num ASC;
+    ",
+    )?;
+    let mut rows = stmt.query([])?;
+
+    let mut accounts = vec![];
+    while let Some(row) = rows.next()? {
+        accounts.push(account_info_from_row(row)?)
+    }
+    timer.finish(accounts.len());
+    Ok(accounts)
+}
+
+/// Select all account hashes from the DB using the given [Connection].
+///
+/// # Returns
+///
+/// The vector with the account id and corresponding hash, or an error.
+pub fn select_all_account_hashes(conn: &mut Connection) -> Result<Vec<(AccountId, RpoDigest)>> {
+    let timer = StatementTimer::start(labels::SELECT_ALL_ACCOUNT_HASHES, []);
+    let mut stmt = conn
+        .prepare_cached("SELECT account_id, account_hash FROM accounts ORDER BY block_num ASC;")?;
+    let mut rows = stmt.query([])?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let account_id = column_value_as_u64(row, 0)?;
+        let account_hash_data = row.get_ref(1)?.as_blob()?;
+        let account_hash = RpoDigest::read_from_bytes(account_hash_data)?;
+
+        result.push((account_id, account_hash));
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select a chunk of the account hashes from the DB using the given [Connection], ordered by
+/// account ID.
+///
+/// The chunk starts after the account `after`, or at the first account if [None], so the whole
+/// table can be iterated by passing the last account ID of every chunk to the next call.
+///
+/// # Returns
+///
+/// At most `limit` account ids with their corresponding hashes, or an error.
+pub fn select_account_hashes_chunk(
+    conn: &mut Connection,
+    after: Option<AccountId>,
+    limit: usize,
+) -> Result<Vec<(AccountId, RpoDigest)>> {
+    let timer = StatementTimer::start(labels::SELECT_ACCOUNT_HASHES_CHUNK, [("limit", limit)]);
+    // Account IDs are stored as signed integers, so the chunks are ordered by their signed value
+    let from = match after {
+        None => i64::MIN,
+        Some(account_id) => match (account_id as i64).checked_add(1) {
+            Some(from) => from,
+            None => return Ok(Vec::new()),
+        },
+    };
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            account_id,
+            account_hash
+        FROM
+            accounts
+        WHERE
+            account_id >= ?1
+        ORDER BY
+            account_id ASC
+        LIMIT ?2;
+    ",
+    )?;
+    let mut rows = stmt.query(params![from, limit])?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let account_id = column_value_as_u64(row, 0)?;
+        let account_hash_data = row.get_ref(1)?.as_blob()?;
+        let account_hash = RpoDigest::read_from_bytes(account_hash_data)?;
+
+        result.push((account_id, account_hash));
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select [AccountSummary] from the DB using the given [Connection], given that the account
+/// update was done between `(block_start, block_end]`.
+///
+/// # Returns
+///
+/// The vector of [AccountSummary] with the matching accounts.
+pub fn select_accounts_by_block_range(
+    conn: &mut Connection,
+    block_start: BlockNumber,
+    block_end: BlockNumber,
+    account_ids: &[AccountId],
+) -> Result<Vec<AccountSummary>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_ACCOUNTS_BY_BLOCK_RANGE,
+        [("account_ids", account_ids.len())],
+    );
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            account_id,
+            account_hash,
+            block_num,
+            details IS NOT NULL
+        FROM
+            accounts
+        WHERE
+            block_num > ?1 AND
+            block_num <= ?2 AND
+            account_id IN rarray(?3)
+        ORDER BY
+            block_num ASC
+    ",
+    )?;
+
+    let account_ids: Vec<Value> = account_ids.iter().copied().map(u64_to_value).collect();
+    let mut rows = stmt.query(params![block_start, block_end, Rc::new(account_ids)])?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        result.push(account_hash_update_from_row(row)?)
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select the latest account details by account id from the DB using the given [Connection].
+///
+/// # Returns
+///
+/// The latest account details, or an error.
+pub fn select_account(conn: &mut Connection, account_id: AccountId) -> Result<AccountInfo> {
+    let timer = StatementTimer::start(labels::SELECT_ACCOUNT, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            account_id,
+            account_hash,
+            block_num,
+            details
+        FROM
+            accounts
+        WHERE
+            account_id = ?1;
+    ",
+    )?;
+
+    let mut rows = stmt.query(params![u64_to_value(account_id)])?;
+    let row = rows.next()?.ok_or(DatabaseError::AccountNotFoundInDb(account_id))?;
+
+    let account = account_info_from_row(row)?;
+    timer.finish(1);
+
+    Ok(account)
+}
+
+/// Select the latest accounts' details filtered by IDs from the DB using the given [Connection].
+///
+/// # Returns
+///
+/// The account details vector, or an error.
+pub fn select_accounts_by_ids(
+    conn: &mut Connection,
+    account_ids: &[AccountId],
+) -> Result<Vec<AccountInfo>> {
+    let timer =
+        StatementTimer::start(labels::SELECT_ACCOUNTS_BY_IDS, [("account_ids", account_ids.len())]);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            account_id,
+            account_hash,
+            block_num,
+            details
+        FROM
+            accounts
+        WHERE
+            account_id IN rarray(?1);
+    ",
+    )?;
+
+    let account_ids: Vec<Value> = account_ids.iter().copied().map(u64_to_value).collect();
+    let mut rows = stmt.query(params![Rc::new(account_ids)])?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        result.push(account_info_from_row(row)?)
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select the latest commitments of the accounts filtered by IDs from the DB using the given
+/// [Connection], without reading their details.
+///
+/// # Returns
+///
+/// The vector of [AccountSummary] of the accounts found in the DB, or an error.
+pub fn select_account_commitments(
+    conn: &mut Connection,
+    account_ids: &[AccountId],
+) -> Result<Vec<AccountSummary>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_ACCOUNT_COMMITMENTS,
+        [("account_ids", account_ids.len())],
+    );
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            account_id,
+            account_hash,
+            block_num,
+            details IS NOT NULL
+        FROM
+            accounts
+        WHERE
+            account_id IN rarray(?1);
+    ",
+    )?;
+
+    let account_ids: Vec<Value> = account_ids.iter().copied().map(u64_to_value).collect();
+    let mut rows = stmt.query(params![Rc::new(account_ids)])?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        result.push(account_hash_update_from_row(row)?)
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select account deltas by account id and block range from the DB using the given [Connection].
+///
+/// # Note:
+///
+/// `block_start` is exclusive and `block_end` is inclusive.
+///
+/// # Returns
+///
+/// The account deltas, or an error.
+pub fn select_account_deltas(
+    conn: &mut Connection,
+    account_id: AccountId,
+    block_start: BlockNumber,
+    block_end: BlockNumber,
+) -> Result<Vec<AccountDelta>> {
+    let timer = StatementTimer::start(labels::SELECT_ACCOUNT_DELTAS, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            delta
+        FROM
+            account_deltas
+        WHERE
+            account_id = ?1 AND block_num > ?2 AND block_num <= ?3
+        ORDER BY
+            block_num ASC
+    ",
+    )?;
+
+    let mut rows = stmt.query(params![u64_to_value(account_id), block_start, block_end])?;
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let delta = AccountDelta::read_from_bytes(row.get_ref(0)?.as_blob()?)?;
+        result.push(delta);
+    }
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select a chunk of the deltas of the given accounts in the blocks `from_block..=to_block`, using
+/// the given [Connection].
+///
+/// Deltas are returned in the order of their block, and then of their account. If `after` is set,
+/// only the deltas positioned after the given `(block_num, account_id)` are returned, which allows
+/// to walk the deltas chunk by chunk with a single forward scan of the block number index.
+///
+/// # Returns
+///
+/// At most `limit` deltas, with their encoded [AccountDelta] as stored.
+pub fn select_account_deltas_chunk(
+    conn: &mut Connection,
+    account_ids: &[AccountId],
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+    after: Option<(BlockNumber, AccountId)>,
+    limit: usize,
+) -> Result<Vec<AccountDeltaRecord>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_ACCOUNT_DELTAS_CHUNK,
+        [("accounts", account_ids.len()), ("limit", limit)],
+    );
+    let account_ids: Vec<Value> = account_ids.iter().copied().map(u64_to_value).collect();
+    let (after_block_num, after_account_id) = match after {
+        Some((block_num, account_id)) => (Some(block_num), Some(u64_to_value(account_id))),
+        None => (None, None),
+    };
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_num,
+            account_id,
+            delta
+        FROM
+            account_deltas INDEXED BY idx_account_deltas_block_num
+        WHERE
+            account_id IN rarray(?1) AND
+            block_num >= ?2 AND
+            block_num <= ?3 AND
+            (?4 IS NULL OR (block_num, account_id) > (?4, ?5))
+        ORDER BY
+            block_num ASC,
+            account_id ASC
+        LIMIT ?6
+        ",
+    )?;
+    let mut rows = stmt.query(params![
+        Rc::new(account_ids),
+        from_block,
+        to_block,
+        after_block_num,
+        after_account_id,
+        limit,
+    ])?;
+
+    let mut deltas = Vec::new();
+    while let Some(row) = rows.next()? {
+        deltas.push(AccountDeltaRecord {
+            block_num: row.get(0)?,
+            account_id: column_value_as_u64(row, 1)?,
+            delta: row.get_ref(2)?.as_blob()?.to_vec(),
+        });
+    }
+
+    timer.finish(deltas.len());
+    Ok(deltas)
+}
+
+/// Select the accumulated delta of an account's state in a block range from the DB using the given
+/// [Connection].
+///
+/// # Note:
+///
+/// `block_start` is exclusive and `block_end` is inclusive.
+///
+/// # Returns
+///
+/// The merged account deltas of all blocks in the range, or an error.
+pub fn select_account_delta(
+    conn: &mut Connection,
+    account_id: AccountId,
+    block_start: BlockNumber,
+    block_end: BlockNumber,
+) -> Result<AccountDelta> {
+    select_account_deltas(conn, account_id, block_start, block_end)?
+        .into_iter()
+        .try_fold(AccountDelta::default(), |mut accumulator, delta| {
+            accumulator.merge(delta).map(|_| accumulator)
+        })
+        .map_err(Into::into)
+}
+
+/// Count the blocks updating an account in a block range from the DB using the given
+/// [Connection], i.e. the blocks holding a transaction of the account.
+///
+/// # Note:
+///
+/// `block_start` is exclusive and `block_end` is inclusive.
+///
+/// # Returns
+///
+/// The number of blocks updating the account, or an error.
+pub fn count_account_updates(
+    conn: &mut Connection,
+    account_id: AccountId,
+    block_start: BlockNumber,
+    block_end: BlockNumber,
+) -> Result<u32> {
+    let timer = StatementTimer::start(labels::COUNT_ACCOUNT_UPDATES, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            COUNT(DISTINCT block_num)
+        FROM
+            transactions
+        WHERE
+            account_id = ?1 AND block_num > ?2 AND block_num <= ?3
+    ",
+    )?;
+
+    let count = stmt
+        .query_row(params![u64_to_value(account_id), block_start, block_end], |row| row.get(0))?;
+    timer.finish(1);
+
+    Ok(count)
+}
+
+/// Inserts or updates accounts to the DB using the given [Transaction].
+///
+/// # Returns
+///
+/// The number of affected rows.
+///
+/// # Note
+///
+/// The [Transaction] object is not consumed. It's up to the caller to commit or rollback the
+/// transaction.
+pub fn upsert_accounts(
+    transaction: &Transaction,
+    accounts: &[BlockAccountUpdate],
+    block_num: BlockNumber,
+) -> Result<usize> {
+    let timer = StatementTimer::start(labels::UPSERT_ACCOUNTS, [("accounts", accounts.len())]);
+    let mut upsert_stmt = transaction.prepare_cached(
+        "INSERT OR REPLACE INTO accounts (account_id, account_hash, block_num, details) VALUES (?1, ?2, ?3, ?4);",
+    )?;
+    let mut insert_delta_stmt = transaction.prepare_cached(
+        "INSERT INTO account_deltas (account_id, block_num, delta) VALUES (?1, ?2, ?3);",
+    )?;
+    let mut select_details_stmt =
+        transaction.prepare_cached("SELECT details FROM accounts WHERE account_id = ?1;")?;
+
+    let mut count = 0;
+    for update in accounts.iter() {
+        let account_id = update.account_id().into();
+        let full_account = match update.details() {
+            AccountUpdateDetails::Private => None,
+            AccountUpdateDetails::New(account) => {
+                debug_assert_eq!(account_id, u64::from(account.id()));
+
+                if account.hash() != update.new_state_hash() {
+                    return Err(DatabaseError::AccountHashesMismatch {
+                        calculated: account.hash(),
+                        expected: update.new_state_hash(),
+                    });
+                }
+
+                Some(Cow::Borrowed(account))
+            },
+            AccountUpdateDetails::Delta(delta) => {
+                let mut rows = select_details_stmt.query(params![u64_to_value(account_id)])?;
+                let Some(row) = rows.next()? else {
+                    return Err(DatabaseError::AccountNotFoundInDb(account_id));
+                };
+
+                insert_delta_stmt.execute(params![
+                    u64_to_value(account_id),
+                    block_num,
+                    delta.to_bytes()
+                ])?;
+
+                let account =
+                    apply_delta(account_id, &row.get_ref(0)?, delta, &update.new_state_hash())?;
+
+                Some(Cow::Owned(account))
+            },
+        };
+
+        let inserted = upsert_stmt.execute(params![
+            u64_to_value(account_id),
+            update.new_state_hash().to_bytes(),
+            block_num,
+            full_account.as_ref().map(|account| account.to_bytes()),
+        ])?;
+
+        debug_assert_eq!(inserted, 1);
+
+        count += inserted;
+    }
+
+    timer.finish(count);
+    Ok(count)
+}
+
+// NULLIFIER QUERIES
+// ================================================================================================
+
+/// Insert nullifiers to the DB using the given [Transaction].
+///
+/// # Returns
+///
+/// The number of affected rows.
+///
+/// # Note
+///
+/// The [Transaction] object is not consumed. It's up to the caller to commit or rollback the
+/// transaction.
+pub fn insert_nullifiers_for_block(
+    transaction: &Transaction,
+    nullifiers: &[Nullifier],
+    block_num: BlockNumber,
+) -> Result<usize> {
+    let timer = StatementTimer::start(
+        labels::INSERT_NULLIFIERS_FOR_BLOCK,
+        [("nullifiers", nullifiers.len())],
+    );
+    let mut stmt = transaction.prepare_cached(
+        "INSERT INTO nullifiers (nullifier, nullifier_prefix, block_num) VALUES (?1, ?2, ?3);",
+    )?;
+
+    let mut count = 0;
+    for nullifier in nullifiers.iter() {
+        count +=
+            stmt.execute(params![nullifier.to_bytes(), nullifier_prefix(nullifier), block_num])?
+    }
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Select all nullifiers from the DB using the given [Connection].
+///
+/// # Returns
+///
+/// A vector with nullifiers and the block height at which they were created, or an error.
+pub fn select_all_nullifiers(conn: &mut Connection) -> Result<Vec<(Nullifier, BlockNumber)>> {
+    let timer = StatementTimer::start(labels::SELECT_ALL_NULLIFIERS, []);
+    let mut stmt =
+        conn.prepare_cached("SELECT nullifier, block_num FROM nullifiers ORDER BY block_num ASC;")?;
+    let mut rows = stmt.query([])?;
+
+    let mut result = vec![];
+    while let Some(row) = rows.next()? {
+        let nullifier_data = row.get_ref(0)?.as_blob()?;
+        let nullifier = Nullifier::read_from_bytes(nullifier_data)?;
+        let block_number = row.get(1)?;
+        result.push((nullifier, block_number));
+    }
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select a chunk of the nullifiers from the DB using the given [Connection], ordered by
+/// nullifier.
+///
+/// The chunk starts after the nullifier `after`, or at the first nullifier if [None], so the whole
+/// table can be iterated by passing the last nullifier of every chunk to the next call.
+///
+/// # Returns
+///
+/// At most `limit` nullifiers with the block height at which they were created, or an error.
+pub fn select_nullifiers_chunk(
+    conn: &mut Connection,
+    after: Option<&Nullifier>,
+    limit: usize,
+) -> Result<Vec<(Nullifier, BlockNumber)>> {
+    let timer = StatementTimer::start(labels::SELECT_NULLIFIERS_CHUNK, [("limit", limit)]);
+    // An empty blob sorts before any nullifier
+    let after = after.map(Nullifier::to_bytes).unwrap_or_default();
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            nullifier,
+            block_num
+        FROM
+            nullifiers
+        WHERE
+            nullifier > ?1
+        ORDER BY
+            nullifier ASC
+        LIMIT ?2;
+    ",
+    )?;
+    let mut rows = stmt.query(params![after, limit])?;
+
+    let mut result = vec![];
+    while let Some(row) = rows.next()? {
+        let nullifier_data = row.get_ref(0)?.as_blob()?;
+        let nullifier = Nullifier::read_from_bytes(nullifier_data)?;
+        let block_number = row.get(1)?;
+        result.push((nullifier, block_number));
+    }
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select nullifiers created between `(block_start, block_end]` that also match the
+/// `nullifier_prefixes` filter using the given [Connection].
+///
+/// Each value of the `nullifier_prefixes` is only the 16 most significant bits of the nullifier of
+/// interest to the client. This hides the details of the specific nullifier being requested.
+///
+/// # Returns
+///
+/// A vector of [NullifierInfo] with the nullifiers and the block height at which they were
+/// created, or an error.
+pub fn select_nullifiers_by_block_range(
+    conn: &mut Connection,
+    block_start: BlockNumber,
+    block_end: BlockNumber,
+    nullifier_prefixes: &[u32],
+) -> Result<Vec<NullifierInfo>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_NULLIFIERS_BY_BLOCK_RANGE,
+        [("nullifier_prefixes", nullifier_prefixes.len())],
+    );
+    let nullifier_prefixes: Vec<Value> =
+        nullifier_prefixes.iter().copied().map(u32_to_value).collect();
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            nullifier,
+            block_num
+        FROM
+            nullifiers
+        WHERE
+            block_num > ?1 AND
+            block_num <= ?2 AND
+            nullifier_prefix IN rarray(?3)
+        ORDER BY
+            block_num ASC
+    ",
+    )?;
+
+    let mut rows = stmt.query(params![block_start, block_end, Rc::new(nullifier_prefixes)])?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let nullifier_data = row.get_ref(0)?.as_blob()?;
+        let nullifier = Nullifier::read_from_bytes(nullifier_data)?;
+        let block_num = row.get(1)?;
+        result.push(NullifierInfo { nullifier, block_num });
+    }
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select nullifiers created that match the `nullifier_prefixes` filter using the given
+/// [Connection].
+///
+/// Each value of the `nullifier_prefixes` is only the `prefix_len` most significant bits
+/// of the nullifier of interest to the client. This hides the details of the specific
+/// nullifier being requested, see [nullifier_prefix]. Currently the only supported prefix length
+/// is [NULLIFIER_PREFIX_LEN] bits.
+///
+/// Nullifiers are returned ordered by block number and then by nullifier. If `after` is set, only
+/// the nullifiers positioned after the given `(block_num, nullifier)` are returned, which allows
+/// to fetch the matching nullifiers page by page.
+///
+/// # Returns
+///
+/// At most `limit` [NullifierInfo] with the nullifiers and the block height at which they were
+/// created, or an error.
+pub fn select_nullifiers_by_prefix(
+    conn: &mut Connection,
+    prefix_len: u32,
+    nullifier_prefixes: &[u32],
+    after: Option<(BlockNumber, Nullifier)>,
+    limit: usize,
+) -> Result<Vec<NullifierInfo>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_NULLIFIERS_BY_PREFIX,
+        [("nullifier_prefixes", nullifier_prefixes.len()), ("limit", limit)],
+    );
+    assert_eq!(prefix_len, NULLIFIER_PREFIX_LEN, "Only 16-bit prefixes are supported");
+
+    let nullifier_prefixes: Vec<Value> =
+        nullifier_prefixes.iter().copied().map(u32_to_value).collect();
+    let (after_block_num, after_nullifier) = match after {
+        Some((block_num, nullifier)) => (Some(block_num), Some(nullifier.to_bytes())),
+        None => (None, None),
+    };
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            nullifier,
+            block_num
+        FROM
+            nullifiers
+        WHERE
+            nullifier_prefix IN rarray(?1) AND
+            (?2 IS NULL OR (block_num, nullifier) > (?2, ?3))
+        ORDER BY
+            block_num ASC,
+            nullifier ASC
+        LIMIT ?4
+    ",
+    )?;
+
+    let mut rows =
+        stmt.query(params![Rc::new(nullifier_prefixes), after_block_num, after_nullifier, limit])?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let nullifier_data = row.get_ref(0)?.as_blob()?;
+        let nullifier = Nullifier::read_from_bytes(nullifier_data)?;
+        let block_num = row.get(1)?;
+        result.push(NullifierInfo { nullifier, block_num });
+    }
+    timer.finish(result.len());
+    Ok(result)
+}
+
+// NOTE QUERIES
+// ================================================================================================
+
+/// Select all notes from the DB using the given [Connection].
+///
+///
+/// # Returns
+///
+/// A vector with notes, or an error.
+pub fn select_all_notes(conn: &mut Connection) -> Result<Vec<NoteRecord>> {
+    let timer = StatementTimer::start(labels::SELECT_ALL_NOTES, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_num,
+            batch_index,
+            note_index,
+            note_id,
+            note_type,
+            sender,
+            tag,
+            aux,
+            execution_hint,
+            merkle_path,
+            details,
+            created_by_tx,
+            nullifier
+        FROM
+            notes
+        ORDER BY
+            block_num ASC;
+        ",
+    )?;
+    let mut rows = stmt.query([])?;
+
+    let mut notes = vec![];
+    while let Some(row) = rows.next()? {
+        notes.push(note_record_from_row(row)?);
+    }
+    timer.finish(notes.len());
+    Ok(notes)
+}
+
+/// Insert notes to the DB using the given [Transaction].
+///
+/// # Returns
+///
+/// The number of affected rows.
+///
+/// # Note
+///
+/// The [Transaction] object is not consumed. It's up to the caller to commit or rollback the
+/// transaction.
+pub fn insert_notes(transaction: &Transaction, notes: &[NoteRecord]) -> Result<usize> {
+    let timer = StatementTimer::start(labels::INSERT_NOTES, [("notes", notes.len())]);
+    let mut stmt = transaction.prepare_cached(
+        "
+        INSERT INTO
+        notes
+        (
+            block_num,
+            batch_index,
+            note_index,
+            note_id,
+            note_type,
+            sender,
+            tag,
+            aux,
+            execution_hint,
+            merkle_path,
+            details,
+            recipient,
+            created_by_tx,
+            nullifier
+        )
+        VALUES
+        (
+            ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14
+        );",
+    )?;
+
+    let mut count = 0;
+    for note in notes.iter() {
+        let details = note.details.as_ref().map(|details| details.to_bytes());
+        let recipient = note
+            .details
+            .as_deref()
+            .and_then(note_recipient)
+            .map(|recipient| recipient.to_bytes());
+        count += stmt.execute(params![
+            note.block_num,
+            note.note_index.batch_idx(),
+            note.note_index.note_idx_in_batch(),
+            note.note_id.to_bytes(),
+            note.metadata.note_type() as u8,
+            u64_to_value(note.metadata.sender().into()),
+            note.metadata.tag().inner(),
+            u64_to_value(note.metadata.aux().into()),
+            Into::<u64>::into(note.metadata.execution_hint()),
+            note.merkle_path.to_bytes(),
+            details,
+            recipient,
+            note.created_by_tx.map(|tx_id| tx_id.to_bytes()),
+            note.nullifier.map(|nullifier| nullifier.to_bytes()),
+        ])?;
+    }
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Fills the `recipient` column of the public notes which don't have it set yet.
+///
+/// # Returns
+///
+/// The number of updated notes.
+pub fn backfill_note_recipients(transaction: &Transaction) -> Result<usize> {
+    let timer = StatementTimer::start(labels::BACKFILL_NOTE_RECIPIENTS, []);
+    // The recipients are collected before updating, so the rows are not modified while scanned
+    let mut recipients = Vec::new();
+    {
+        let mut stmt = transaction.prepare(
+            "SELECT rowid, details FROM notes WHERE details IS NOT NULL AND recipient IS NULL",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let rowid: i64 = row.get(0)?;
+            let details = <Vec<u8>>::read_from_bytes(row.get_ref(1)?.as_blob()?)?;
+            if let Some(recipient) = note_recipient(&details) {
+                recipients.push((rowid, recipient));
+            }
+        }
+    }
+
+    let mut stmt = transaction.prepare("UPDATE notes SET recipient = ?2 WHERE rowid = ?1")?;
+    let mut count = 0;
+    for (rowid, recipient) in recipients {
+        count += stmt.execute(params![rowid, recipient.to_bytes()])?;
+    }
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Fills the `nullifier` column of the public notes whose details are retained and which don't
+/// have it set yet.
+///
+/// # Returns
+///
+/// The number of updated notes.
+pub fn backfill_note_nullifiers(transaction: &Transaction) -> Result<usize> {
+    let timer = StatementTimer::start(labels::BACKFILL_NOTE_NULLIFIERS, []);
+    // The nullifiers are collected before updating, so the rows are not modified while scanned
+    let mut nullifiers = Vec::new();
+    {
+        let mut stmt = transaction.prepare(
+            "SELECT rowid, details FROM notes WHERE details IS NOT NULL AND nullifier IS NULL",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let rowid: i64 = row.get(0)?;
+            let details = <Vec<u8>>::read_from_bytes(row.get_ref(1)?.as_blob()?)?;
+            if let Ok(note) = Note::read_from_bytes(&details) {
+                nullifiers.push((rowid, note.nullifier()));
+            }
+        }
+    }
+
+    let mut stmt = transaction.prepare("UPDATE notes SET nullifier = ?2 WHERE rowid = ?1")?;
+    let mut count = 0;
+    for (rowid, nullifier) in nullifiers {
+        count += stmt.execute(params![rowid, nullifier.to_bytes()])?;
+    }
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Select the first block after `after` and up to `up_to` containing notes without a creating
+/// transaction, using the given [Connection].
+///
+/// # Returns
+///
+/// The number of the block, [None] if there are no such blocks.
+pub fn select_next_block_with_unlinked_notes(
+    conn: &mut Connection,
+    after: Option<BlockNumber>,
+    up_to: BlockNumber,
+) -> Result<Option<BlockNumber>> {
+    let timer = StatementTimer::start(labels::SELECT_NEXT_BLOCK_WITH_UNLINKED_NOTES, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            MIN(block_num)
+        FROM
+            notes
+        WHERE
+            created_by_tx IS NULL AND
+            (?1 IS NULL OR block_num > ?1) AND
+            block_num <= ?2
+        ",
+    )?;
+    let block_num: Option<BlockNumber> = stmt.query_row(params![after, up_to], |row| row.get(0))?;
+
+    timer.finish(usize::from(block_num.is_some()));
+    Ok(block_num)
+}
+
+/// Set the creating transaction of the notes of a block which don't have it set yet, using the
+/// given [Transaction].
+///
+/// # Returns
+///
+/// The number of updated notes.
+pub fn update_note_creators(
+    transaction: &Transaction,
+    block_num: BlockNumber,
+    creators: &BTreeMap<NoteId, TransactionId>,
+) -> Result<usize> {
+    let timer = StatementTimer::start(labels::UPDATE_NOTE_CREATORS, [("notes", creators.len())]);
+    let mut stmt = transaction.prepare_cached(
+        "
+        UPDATE
+            notes
+        SET
+            created_by_tx = ?3
+        WHERE
+            block_num = ?1 AND
+            note_id = ?2 AND
+            created_by_tx IS NULL
+        ",
+    )?;
+
+    let mut count = 0;
+    for (note_id, tx_id) in creators {
+        count += stmt.execute(params![block_num, note_id.to_bytes(), tx_id.to_bytes()])?;
+    }
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Select notes matching the tags and account IDs search criteria using the given [Connection].
+///
+/// # Returns
+///
+/// All matching notes from the first block greater than `block_num` containing a matching note.
+/// A note is considered a match if it has any of the given tags, or if its sender is one of the
+/// given account IDs. If no matching notes are found at all, then an empty vector is returned.
+///
+/// # Note
+///
+/// This method returns notes from a single block. To fetch all notes up to the chain tip,
+/// multiple requests are necessary.
+pub fn select_notes_since_block_by_tag_and_sender(
+    conn: &mut Connection,
+    tags: &[u32],
+    account_ids: &[AccountId],
+    block_num: BlockNumber,
+) -> Result<Vec<NoteSyncRecord>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_NOTES_SINCE_BLOCK_BY_TAG_AND_SENDER,
+        [("tags", tags.len()), ("account_ids", account_ids.len())],
+    );
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_num,
+            batch_index,
+            note_index,
+            note_id,
+            note_type,
+            sender,
+            tag,
+            aux,
+            execution_hint,
+            merkle_path
+        FROM
+            notes
+        WHERE
+            -- find the next block which contains at least one note with a matching tag or sender
+            block_num = (
+                SELECT
+                    block_num
+                FROM
+                    notes
+                WHERE
+                    (tag IN rarray(?1) OR sender IN rarray(?2)) AND
+                    block_num > ?3
+                ORDER BY
+                    block_num ASC
+                LIMIT
+                    1
+            ) AND
+            -- filter the block's notes and return only the ones matching the requested tags
+            -- or senders
+            (tag IN rarray(?1) OR sender IN rarray(?2));
+    ",
+    )?;
+
+    let tags: Vec<Value> = tags.iter().copied().map(u32_to_value).collect();
+    let account_ids: Vec<Value> = account_ids.iter().copied().map(u64_to_value).collect();
+    let mut rows = stmt.query(params![Rc::new(tags), Rc::new(account_ids), block_num])?;
+
+    let mut res = Vec::new();
+    while let Some(row) = rows.next()? {
+        let block_num = row.get(0)?;
+        let note_index = BlockNoteIndex::new(row.get(1)?, row.get(2)?)?;
+        let note_id_data = row.get_ref(3)?.as_blob()?;
+        let note_id = RpoDigest::read_from_bytes(note_id_data)?;
+        let note_type = row.get::<_, u8>(4)?;
+        let sender = column_value_as_u64(row, 5)?;
+        let tag: u32 = row.get(6)?;
+        let aux: u64 = row.get(7)?;
+        let aux = aux.try_into().map_err(DatabaseError::InvalidFelt)?;
+        let execution_hint = column_value_as_u64(row, 8)?;
+        let merkle_path_data = row.get_ref(9)?.as_blob()?;
+        let merkle_path = MerklePath::read_from_bytes(merkle_path_data)?;
+
+        let metadata = NoteMetadata::new(
+            sender.try_into()?,
+            NoteType::try_from(note_type)?,
+            tag.into(),
+            execution_hint.try_into()?,
+            aux,
+        )?;
+
+        let note = NoteSyncRecord {
+            block_num,
+            note_index,
+            note_id,
+            metadata,
+            merkle_path,
+        };
+        res.push(note);
+    }
+    timer.finish(res.len());
+    Ok(res)
+}
+
+/// Select Note's matching the NoteId using the given [Connection].
+///
+/// # Returns
+///
+/// - Empty vector if no matching `note`.
+/// - Otherwise, notes which `note_id` matches the `NoteId` as bytes.
+pub fn select_notes_by_id(conn: &mut Connection, note_ids: &[NoteId]) -> Result<Vec<NoteRecord>> {
+    let timer = StatementTimer::start(labels::SELECT_NOTES_BY_ID, [("note_ids", note_ids.len())]);
+    let note_ids: Vec<Value> = note_ids.iter().map(|id| id.to_bytes().into()).collect();
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_num,
+            batch_index,
+            note_index,
+            note_id,
+            note_type,
+            sender,
+            tag,
+            aux,
+            execution_hint,
+            merkle_path,
+            details,
+            created_by_tx,
+            nullifier
+        FROM
+            notes
+        WHERE
+            note_id IN rarray(?1)
+        ",
+    )?;
+    let mut rows = stmt.query(params![Rc::new(note_ids)])?;
+
+    let mut notes = Vec::new();
+    while let Some(row) = rows.next()? {
+        notes.push(note_record_from_row(row)?);
+    }
+
+    timer.finish(notes.len());
+    Ok(notes)
+}
+
+/// Select the consumption status of the notes matching the given IDs, using the given
+/// [Connection].
+///
+/// # Returns
+///
+/// The status of the matching notes, which are consumed if their nullifier was recorded by a block.
+/// Notes which don't match any of `note_ids` are left out.
+pub fn select_notes_consumption(
+    conn: &mut Connection,
+    note_ids: &[NoteId],
+) -> Result<Vec<NoteConsumption>> {
+    let timer =
+        StatementTimer::start(labels::SELECT_NOTES_CONSUMPTION, [("note_ids", note_ids.len())]);
+    let note_ids: Vec<Value> = note_ids.iter().map(|id| id.to_bytes().into()).collect();
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            notes.note_id,
+            notes.block_num,
+            notes.nullifier,
+            nullifiers.block_num
+        FROM
+            notes
+        LEFT JOIN
+            nullifiers ON nullifiers.nullifier = notes.nullifier
+        WHERE
+            notes.note_id IN rarray(?1)
+        ",
+    )?;
+    let mut rows = stmt.query(params![Rc::new(note_ids)])?;
+
+    let mut notes = Vec::new();
+    while let Some(row) = rows.next()? {
+        let note_id = RpoDigest::read_from_bytes(row.get_ref(0)?.as_blob()?)?;
+        let nullifier_data = row.get_ref(2)?.as_blob_or_null()?;
+        let nullifier = nullifier_data.map(Nullifier::read_from_bytes).transpose()?;
+
+        notes.push(NoteConsumption {
+            note_id,
+            block_num: row.get(1)?,
+            nullifier,
+            consumed_block_num: row.get(3)?,
+        });
+    }
+
+    timer.finish(notes.len());
+    Ok(notes)
+}
+
+/// Select public notes sent to any of the given recipients, using the given [Connection].
+///
+/// Notes are returned in the order of their position in the chain, starting from block
+/// `from_block`. If `after` is set, only notes positioned after the given `(block_num,
+/// note_index)` are returned, which allows to fetch the matching notes page by page.
+///
+/// # Returns
+///
+/// At most `limit` notes whose recipient digest matches one of `recipients` exactly. Private notes
+/// are never returned, as their recipient is not known to the node.
+pub fn select_notes_by_recipient(
+    conn: &mut Connection,
+    recipients: &[RpoDigest],
+    from_block: BlockNumber,
+    after: Option<(BlockNumber, BlockNoteIndex)>,
+    limit: usize,
+) -> Result<Vec<NoteRecord>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_NOTES_BY_RECIPIENT,
+        [("recipients", recipients.len()), ("limit", limit)],
+    );
+    let recipients: Vec<Value> =
+        recipients.iter().map(|recipient| recipient.to_bytes().into()).collect();
+    let (after_block_num, after_batch_index, after_note_index) = match after {
+        Some((block_num, note_index)) => (
+            Some(block_num),
+            Some(note_index.batch_idx()),
+            Some(note_index.note_idx_in_batch()),
+        ),
+        None => (None, None, None),
+    };
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_num,
+            batch_index,
+            note_index,
+            note_id,
+            note_type,
+            sender,
+            tag,
+            aux,
+            execution_hint,
+            merkle_path,
+            details,
+            created_by_tx,
+            nullifier
+        FROM
+            notes
+        WHERE
+            recipient IN rarray(?1) AND
+            block_num >= ?2 AND
+            (?3 IS NULL OR (block_num, batch_index, note_index) > (?3, ?4, ?5))
+        ORDER BY
+            block_num ASC,
+            batch_index ASC,
+            note_index ASC
+        LIMIT ?6
+        ",
+    )?;
+    let mut rows = stmt.query(params![
+        Rc::new(recipients),
+        from_block,
+        after_block_num,
+        after_batch_index,
+        after_note_index,
+        limit,
+    ])?;
+
+    let mut notes = Vec::new();
+    while let Some(row) = rows.next()? {
+        notes.push(note_record_from_row(row)?);
+    }
+
+    timer.finish(notes.len());
+    Ok(notes)
+}
+
+/// Select the number of notes per tag prefix in the blocks `from_block..=to_block`, using the
+/// given [Connection].
+///
+/// The notes are grouped by the `prefix_len` most significant bits of their tag. Notes whose tag
+/// is meant for network execution, i.e. has its execution mode bit cleared, are left out if
+/// `exclude_network_notes` is set. The bit layout of the tags is described in
+/// [miden_node_proto::domain::prefixes].
+///
+/// # Returns
+///
+/// The non-empty buckets, ordered by prefix.
+pub fn select_note_tag_statistics(
+    conn: &mut Connection,
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+    prefix_len: u32,
+    exclude_network_notes: bool,
+) -> Result<Vec<NoteTagBucket>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_NOTE_TAG_STATISTICS,
+        [("blocks", (to_block.saturating_sub(from_block) as usize).saturating_add(1))],
+    );
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            tag >> ?3 AS prefix,
+            COUNT(*)
+        FROM
+            notes
+        WHERE
+            block_num BETWEEN ?1 AND ?2 AND
+            (?4 = 0 OR tag >> ?5 = 1)
+        GROUP BY
+            prefix
+        ORDER BY
+            prefix ASC
+        ",
+    )?;
+    let mut rows = stmt.query(params![
+        from_block,
+        to_block,
+        note_tag_prefix_shift(prefix_len),
+        exclude_network_notes,
+        NOTE_TAG_EXECUTION_MODE_BIT,
+    ])?;
+
+    let mut buckets = Vec::new();
+    while let Some(row) = rows.next()? {
+        buckets.push(NoteTagBucket {
+            prefix: row.get(0)?,
+            num_notes: row.get(1)?,
+        });
+    }
+
+    timer.finish(buckets.len());
+    Ok(buckets)
+}
+
+/// Inserts the note tag filter of a block, using the given [Transaction].
+///
+/// # Note
+///
+/// The [Transaction] object is not consumed. It's up to the caller to commit or rollback the
+/// transaction.
+pub fn insert_block_note_filter(
+    transaction: &Transaction,
+    block_num: BlockNumber,
+    notes: &[NoteRecord],
+) -> Result<usize> {
+    let timer = StatementTimer::start(labels::INSERT_BLOCK_NOTE_FILTER, [("notes", notes.len())]);
+    let filter = NoteTagFilter::from_tags(notes.iter().map(|note| note.metadata.tag().into()));
+    let mut stmt = transaction
+        .prepare_cached("INSERT INTO block_note_filters (block_num, filter) VALUES (?1, ?2);")?;
+    let count = stmt.execute(params![block_num, filter.as_bytes()])?;
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Computes the note tag filters of the blocks which have none, from their notes, using the given
+/// [Transaction].
+///
+/// # Returns
+///
+/// The number of inserted filters.
+pub fn backfill_block_note_filters(transaction: &Transaction) -> Result<usize> {
+    let timer = StatementTimer::start(labels::BACKFILL_BLOCK_NOTE_FILTERS, []);
+    // The tags are collected before inserting, so the tables are not modified while scanned
+    let mut tags: BTreeMap<BlockNumber, Vec<u32>> = BTreeMap::new();
+    {
+        let mut stmt = transaction.prepare(
+            "
+            SELECT
+                block_headers.block_num,
+                notes.tag
+            FROM
+                block_headers
+            LEFT JOIN
+                notes ON notes.block_num = block_headers.block_num
+            WHERE
+                block_headers.block_num NOT IN (SELECT block_num FROM block_note_filters)
+            ",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let block_tags = tags.entry(row.get(0)?).or_default();
+            if let Some(tag) = row.get::<_, Option<u32>>(1)? {
+                block_tags.push(tag);
+            }
+        }
+    }
+
+    let mut stmt = transaction
+        .prepare("INSERT INTO block_note_filters (block_num, filter) VALUES (?1, ?2)")?;
+    let mut count = 0;
+    for (block_num, block_tags) in tags {
+        let filter = NoteTagFilter::from_tags(block_tags);
+        count += stmt.execute(params![block_num, filter.as_bytes()])?;
+    }
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Select the note tag filters of the blocks `from_block..=to_block` using the given
+/// [Connection].
+///
+/// # Returns
+///
+/// The filters of the blocks of the range which are part of the chain, ordered by block number.
+pub fn select_block_note_filters(
+    conn: &mut Connection,
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+) -> Result<Vec<BlockNoteFilter>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_BLOCK_NOTE_FILTERS,
+        [("blocks", (to_block.saturating_sub(from_block) as usize).saturating_add(1))],
+    );
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_num,
+            filter
+        FROM
+            block_note_filters
+        WHERE
+            block_num BETWEEN ?1 AND ?2
+        ORDER BY
+            block_num ASC
+        ",
+    )?;
+    let mut rows = stmt.query(params![from_block, to_block])?;
+
+    let mut filters = Vec::new();
+    while let Some(row) = rows.next()? {
+        filters.push(BlockNoteFilter {
+            block_num: row.get(0)?,
+            filter: NoteTagFilter::from_bytes(row.get(1)?),
+        });
+    }
+
+    timer.finish(filters.len());
+    Ok(filters)
+}
+
+/// Select note inclusion proofs matching the NoteId, using the given [Connection].
+///
+/// # Returns
+///
+/// - Empty map if no matching `note`.
+/// - Otherwise, note inclusion proofs, which `note_id` matches the `NoteId` as bytes.
+pub fn select_note_inclusion_proofs(
+    conn: &mut Connection,
+    note_ids: BTreeSet<NoteId>,
+) -> Result<BTreeMap<NoteId, NoteInclusionProof>> {
+    let timer =
+        StatementTimer::start(labels::SELECT_NOTE_INCLUSION_PROOFS, [("note_ids", note_ids.len())]);
+    let note_ids: Vec<Value> = note_ids.into_iter().map(|id| id.to_bytes().into()).collect();
+
+    let mut select_notes_stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_num,
+            note_id,
+            batch_index,
+            note_index,
+            merkle_path
+        FROM
+            notes
+        WHERE
+            note_id IN rarray(?1)
+        ORDER BY
+            block_num ASC
+        ",
+    )?;
+
+    let mut result = BTreeMap::new();
+    let mut rows = select_notes_stmt.query(params![Rc::new(note_ids)])?;
+    while let Some(row) = rows.next()? {
+        let block_num = row.get(0)?;
+
+        let note_id_data = row.get_ref(1)?.as_blob()?;
+        let note_id = NoteId::read_from_bytes(note_id_data)?;
+
+        let batch_index = row.get(2)?;
+        let note_index = row.get(3)?;
+        let node_index_in_block = BlockNoteIndex::new(batch_index, note_index)?.leaf_index_value();
+
+        let merkle_path_data = row.get_ref(4)?.as_blob()?;
+        let merkle_path = MerklePath::read_from_bytes(merkle_path_data)?;
+
+        let proof = NoteInclusionProof::new(block_num, node_index_in_block, merkle_path)?;
+
+        result.insert(note_id, proof);
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+// BLOCK CHAIN QUERIES
+// ================================================================================================
+
+/// Insert a [BlockHeader] to the DB using the given [Transaction].
+///
+/// # Returns
+///
+/// The number of affected rows.
+///
+/// # Note
+///
+/// The [Transaction] object is not consumed. It's up to the caller to commit or rollback the
+/// transaction.
+pub fn insert_block_header(transaction: &Transaction, block_header: &BlockHeader) -> Result<usize> {
+    let timer = StatementTimer::start(labels::INSERT_BLOCK_HEADER, []);
+    let mut stmt = transaction.prepare_cached(
+        "INSERT INTO block_headers (block_num, block_header, block_commitment) VALUES (?1, ?2, ?3);",
+    )?;
+    let count = stmt.execute(params![
+        block_header.block_num(),
+        block_header.to_bytes(),
+        block_header.hash().to_bytes()
+    ])?;
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Computes the `block_commitment` of the block headers where it is missing, using the given
+/// [Transaction].
+///
+/// # Returns
+///
+/// The number of updated block headers.
+pub fn backfill_block_commitments(transaction: &Transaction) -> Result<usize> {
+    let timer = StatementTimer::start(labels::BACKFILL_BLOCK_COMMITMENTS, []);
+    // The commitments are collected before updating, so the rows are not modified while scanned
+    let mut commitments = Vec::new();
+    {
+        let mut stmt = transaction.prepare(
+            "SELECT block_num, block_header FROM block_headers WHERE block_commitment IS NULL",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let block_num: BlockNumber = row.get(0)?;
+            let block_header = BlockHeader::read_from_bytes(row.get_ref(1)?.as_blob()?)?;
+            commitments.push((block_num, block_header.hash()));
+        }
+    }
+
+    let mut stmt = transaction
+        .prepare("UPDATE block_headers SET block_commitment = ?2 WHERE block_num = ?1")?;
+    let mut count = 0;
+    for (block_num, commitment) in commitments {
+        count += stmt.execute(params![block_num, commitment.to_bytes()])?;
+    }
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Select a [BlockHeader] from the DB by its `block_num` using the given [Connection].
+///
+/// # Returns
+///
+/// When `block_number` is [None], the latest block header is returned. Otherwise, the block with
+/// the given block height is returned.
+pub fn select_block_header_by_block_num(
+    conn: &mut Connection,
+    block_number: Option<BlockNumber>,
+) -> Result<Option<BlockHeader>> {
+    let timer = StatementTimer::start(labels::SELECT_BLOCK_HEADER_BY_BLOCK_NUM, []);
+    let mut stmt;
+    let mut rows = match block_number {
+        Some(block_number) => {
+            stmt =
+                conn.prepare_cached("SELECT block_header FROM block_headers WHERE block_num = ?1")?;
+            stmt.query([block_number])?
+        },
+        None => {
+            stmt = conn.prepare_cached(
+                "SELECT block_header FROM block_headers ORDER BY block_num DESC LIMIT 1",
+            )?;
+            stmt.query([])?
+        },
+    };
+
+    let block_header = match rows.next()? {
+        Some(row) => {
+            let data = row.get_ref(0)?.as_blob()?;
+            Some(BlockHeader::read_from_bytes(data)?)
+        },
+        None => None,
+    };
+
+    timer.finish(block_header.is_some().into());
+    Ok(block_header)
+}
+
+/// Select a [BlockHeader] from the DB by its commitment using the given [Connection].
+///
+/// # Returns
+///
+/// The block header with the given commitment, or [None] if there is no such block.
+pub fn select_block_header_by_commitment(
+    conn: &mut Connection,
+    block_commitment: RpoDigest,
+) -> Result<Option<BlockHeader>> {
+    let timer = StatementTimer::start(labels::SELECT_BLOCK_HEADER_BY_COMMITMENT, []);
+    let mut stmt =
+        conn.prepare_cached("SELECT block_header FROM block_headers WHERE block_commitment = ?1")?;
+    let mut rows = stmt.query(params![block_commitment.to_bytes()])?;
+
+    let block_header = match rows.next()? {
+        Some(row) => {
+            let data = row.get_ref(0)?.as_blob()?;
+            Some(BlockHeader::read_from_bytes(data)?)
+        },
+        None => None,
+    };
+
+    timer.finish(block_header.is_some().into());
+    Ok(block_header)
+}
+
+/// Insert or replace the production stats of a block using the given [Connection].
+pub fn upsert_block_production_stats(
+    conn: &mut Connection,
+    stats: &BlockProductionStats,
+) -> Result<usize> {
+    let timer = StatementTimer::start(labels::UPSERT_BLOCK_PRODUCTION_STATS, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        INSERT OR REPLACE INTO block_production_stats
+            (block_num, inputs_fetch_us, propose_us, prove_us, commit_us, total_us)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6);
+        ",
+    )?;
+    let count = stmt.execute(params![
+        stats.block_num,
+        stats.inputs_fetch_us.map(u64_to_value),
+        stats.propose_us.map(u64_to_value),
+        stats.prove_us.map(u64_to_value),
+        stats.commit_us.map(u64_to_value),
+        stats.total_us.map(u64_to_value),
+    ])?;
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Select the production stats of the blocks in the given range using the given [Connection].
+///
+/// # Returns
+///
+/// The production stats of every block of the range which is part of the chain, ordered by block
+/// number. The durations of blocks without recorded stats are [None].
+pub fn select_block_production_stats(
+    conn: &mut Connection,
+    block_from: BlockNumber,
+    block_to: BlockNumber,
+) -> Result<Vec<BlockProductionStats>> {
+    let timer = StatementTimer::start(labels::SELECT_BLOCK_PRODUCTION_STATS, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_headers.block_num,
+            stats.inputs_fetch_us,
+            stats.propose_us,
+            stats.prove_us,
+            stats.commit_us,
+            stats.total_us
+        FROM
+            block_headers
+        LEFT JOIN
+            block_production_stats AS stats ON stats.block_num = block_headers.block_num
+        WHERE
+            block_headers.block_num BETWEEN ?1 AND ?2
+        ORDER BY
+            block_headers.block_num ASC
+        ",
+    )?;
+    let mut rows = stmt.query(params![block_from, block_to])?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let duration = |index| -> rusqlite::Result<Option<u64>> {
+            Ok(row.get::<_, Option<i64>>(index)?.map(|value| value as u64))
+        };
+
+        result.push(BlockProductionStats {
+            block_num: row.get(0)?,
+            inputs_fetch_us: duration(1)?,
+            propose_us: duration(2)?,
+            prove_us: duration(3)?,
+            commit_us: duration(4)?,
+            total_us: duration(5)?,
+        });
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Record the provenance of a block using the given [Connection].
+///
+/// The provenance of a previous submission of the block is only replaced by submissions with the
+/// same or a later attempt, so that delayed submissions don't hide the retries.
+///
+/// # Returns
+///
+/// The number of recorded rows, zero if a later attempt was already recorded.
+pub fn upsert_block_provenance(
+    conn: &mut Connection,
+    provenance: &BlockProvenance,
+) -> Result<usize> {
+    let timer = StatementTimer::start(labels::UPSERT_BLOCK_PROVENANCE, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        INSERT INTO block_provenance
+            (block_num, producer_id, attempt, produced_at, received_at)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT (block_num) DO UPDATE SET
+            producer_id = excluded.producer_id,
+            attempt = excluded.attempt,
+            produced_at = excluded.produced_at,
+            received_at = excluded.received_at
+        WHERE
+            excluded.attempt >= block_provenance.attempt;
+        ",
+    )?;
+    let count = stmt.execute(params![
+        provenance.block_num,
+        provenance.producer_id,
+        provenance.attempt,
+        u64_to_value(provenance.produced_at),
+        u64_to_value(provenance.received_at),
+    ])?;
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Select the provenance of a block using the given [Connection].
+///
+/// # Returns
+///
+/// The latest recorded submission of the block, [None] if the block was applied without producer
+/// metadata or is not part of the chain.
+pub fn select_block_provenance(
+    conn: &mut Connection,
+    block_num: BlockNumber,
+) -> Result<Option<BlockProvenance>> {
+    let timer = StatementTimer::start(labels::SELECT_BLOCK_PROVENANCE, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_num,
+            producer_id,
+            attempt,
+            produced_at,
+            received_at
+        FROM
+            block_provenance
+        WHERE
+            block_num = ?1
+        ",
+    )?;
+    let provenance = stmt
+        .query_row(params![block_num], |row| {
+            Ok(BlockProvenance {
+                block_num: row.get(0)?,
+                producer_id: row.get(1)?,
+                attempt: row.get(2)?,
+                produced_at: column_value_as_u64(row, 3)?,
+                received_at: column_value_as_u64(row, 4)?,
+            })
+        })
+        .optional()?;
+
+    timer.finish(usize::from(provenance.is_some()));
+    Ok(provenance)
+}
+
+/// Inserts the statistics of a block, along with the running totals of the blocks up to it, using
+/// the given [Transaction].
+///
+/// # Note
+///
+/// Must be called before the accounts of the block are upserted, so that the accounts updated for
+/// the first time are told apart. The [Transaction] object is not consumed. It's up to the caller
+/// to commit or rollback the transaction.
+pub fn insert_block_stats(
+    transaction: &Transaction,
+    block_header: &BlockHeader,
+    num_notes: usize,
+    num_nullifiers: usize,
+    accounts: &[BlockAccountUpdate],
+) -> Result<usize> {
+    let timer = StatementTimer::start(labels::INSERT_BLOCK_STATS, [("accounts", accounts.len())]);
+    let account_ids: Vec<Value> =
+        accounts.iter().map(|update| u64_to_value(update.account_id().into())).collect();
+    let num_existing_accounts: usize = transaction
+        .prepare_cached("SELECT COUNT(*) FROM accounts WHERE account_id IN rarray(?1)")?
+        .query_row(params![Rc::new(account_ids)], |row| row.get(0))?;
+    let num_transactions: usize = accounts.iter().map(|update| update.transactions().len()).sum();
+
+    let previous = transaction
+        .prepare_cached(
+            "
+            SELECT
+                block_num,
+                timestamp,
+                num_transactions,
+                num_notes,
+                num_nullifiers,
+                num_new_accounts,
+                total_transactions,
+                total_notes,
+                total_nullifiers,
+                total_accounts
+            FROM
+                block_stats
+            WHERE
+                block_num < ?1
+            ORDER BY
+                block_num DESC
+            LIMIT 1
+            ",
+        )?
+        .query_row(params![block_header.block_num()], block_stats_from_row)
+        .optional()?;
+
+    let counts = [
+        num_transactions,
+        num_notes,
+        num_nullifiers,
+        accounts.len() - num_existing_accounts,
+    ]
+    .map(|count| count as u64);
+    let stats = BlockStats::new(
+        block_header.block_num(),
+        block_header.timestamp(),
+        counts,
+        previous.as_ref(),
+    );
+    let count = insert_block_stats_row(transaction, &stats)?;
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Computes the statistics of the blocks which have none, using the given [Transaction].
+///
+/// Accounts are counted as new in the block of their first transaction, or in the block of their
+/// last update if they have no transaction.
+///
+/// # Returns
+///
+/// The number of inserted statistics.
+pub fn backfill_block_stats(transaction: &Transaction) -> Result<usize> {
+    let timer = StatementTimer::start(labels::BACKFILL_BLOCK_STATS, []);
+
+    // Number of rows per block of each query, the rows are collected before inserting
+    let count_per_block = |query: &str| -> Result<BTreeMap<BlockNumber, u64>> {
+        let mut stmt = transaction.prepare(query)?;
+        let mut rows = stmt.query([])?;
+        let mut counts = BTreeMap::new();
+        while let Some(row) = rows.next()? {
+            counts.insert(row.get(0)?, column_value_as_u64(row, 1)?);
+        }
+        Ok(counts)
+    };
+    let transactions =
+        count_per_block("SELECT block_num, COUNT(*) FROM transactions GROUP BY block_num")?;
+    let notes = count_per_block("SELECT block_num, COUNT(*) FROM notes GROUP BY block_num")?;
+    let nullifiers =
+        count_per_block("SELECT block_num, COUNT(*) FROM nullifiers GROUP BY block_num")?;
+    let new_accounts = count_per_block(
+        "
+        SELECT
+            first_block,
+            COUNT(*)
+        FROM (
+            SELECT
+                COALESCE(MIN(transactions.block_num), accounts.block_num) AS first_block
+            FROM
+                accounts
+            LEFT JOIN
+                transactions ON transactions.account_id = accounts.account_id
+            GROUP BY
+                accounts.account_id
+        )
+        GROUP BY
+            first_block
+        ",
+    )?;
+
+    let mut headers = Vec::new();
+    {
+        let mut stmt = transaction.prepare(
+            "
+            SELECT
+                block_num,
+                block_header
+            FROM
+                block_headers
+            WHERE
+                block_num NOT IN (SELECT block_num FROM block_stats)
+            ORDER BY
+                block_num ASC
+            ",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let block_num: BlockNumber = row.get(0)?;
+            let block_header = BlockHeader::read_from_bytes(row.get_ref(1)?.as_blob()?)?;
+            headers.push((block_num, block_header.timestamp()));
+        }
+    }
+
+    let mut previous: Option<BlockStats> = None;
+    let mut count = 0;
+    for (block_num, timestamp) in headers {
+        let count_of = |counts: &BTreeMap<BlockNumber, u64>| {
+            counts.get(&block_num).copied().unwrap_or_default()
+        };
+        let counts = [&transactions, &notes, &nullifiers, &new_accounts].map(count_of);
+        let stats = BlockStats::new(block_num, timestamp, counts, previous.as_ref());
+        count += insert_block_stats_row(transaction, &stats)?;
+        previous = Some(stats);
+    }
+
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Select the statistics of the blocks `from_block..=to_block` using the given [Connection].
+///
+/// # Returns
+///
+/// The statistics of the blocks of the range up to the chain tip, [None] if `from_block` is not
+/// part of the chain.
+pub fn select_chain_stats(
+    conn: &mut Connection,
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+) -> Result<Option<ChainStats>> {
+    let timer = StatementTimer::start(labels::SELECT_CHAIN_STATS, []);
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            block_num,
+            timestamp,
+            num_transactions,
+            num_notes,
+            num_nullifiers,
+            num_new_accounts,
+            total_transactions,
+            total_notes,
+            total_nullifiers,
+            total_accounts
+        FROM
+            block_stats
+        WHERE
+            block_num = ?1 OR
+            block_num = (SELECT MAX(block_num) FROM block_stats WHERE block_num <= ?2)
+        ORDER BY
+            block_num ASC
+        ",
+    )?;
+    let mut rows = stmt.query(params![from_block, to_block])?;
+
+    let mut stats = Vec::new();
+    while let Some(row) = rows.next()? {
+        stats.push(block_stats_from_row(row)?);
+    }
+    let chain_stats = match stats.as_slice() {
+        [first, last] => Some(ChainStats::between(first, last)),
+        [single] if single.block_num == from_block => Some(ChainStats::between(single, single)),
+        _ => None,
+    };
+
+    timer.finish(stats.len());
+    Ok(chain_stats)
+}
+
+fn insert_block_stats_row(transaction: &Transaction, stats: &BlockStats) -> Result<usize> {
+    let mut stmt = transaction.prepare_cached(
+        "
+        INSERT INTO block_stats (
+            block_num,
+            timestamp,
+            num_transactions,
+            num_notes,
+            num_nullifiers,
+            num_new_accounts,
+            total_transactions,
+            total_notes,
+            total_nullifiers,
+            total_accounts
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        ",
+    )?;
+
+    Ok(stmt.execute(params![
+        stats.block_num,
+        stats.timestamp,
+        u64_to_value(stats.num_transactions),
+        u64_to_value(stats.num_notes),
+        u64_to_value(stats.num_nullifiers),
+        u64_to_value(stats.num_new_accounts),
+        u64_to_value(stats.total_transactions),
+        u64_to_value(stats.total_notes),
+        u64_to_value(stats.total_nullifiers),
+        u64_to_value(stats.total_accounts),
+    ])?)
+}
+
+/// Select all the given block headers from the DB using the given [Connection].
+///
+/// # Note
+///
+/// Only returns the block headers that are actually present.
+///
+/// # Returns
+///
+/// A vector of [BlockHeader] or an error.
+pub fn select_block_headers(
+    conn: &mut Connection,
+    blocks: Vec<BlockNumber>,
+) -> Result<Vec<BlockHeader>> {
+    let timer = StatementTimer::start(labels::SELECT_BLOCK_HEADERS, [("blocks", blocks.len())]);
+    let mut headers = Vec::with_capacity(blocks.len());
+
+    let blocks: Vec<Value> = blocks.iter().copied().map(u32_to_value).collect();
+    let mut stmt = conn
+        .prepare_cached("SELECT block_header FROM block_headers WHERE block_num IN rarray(?1);")?;
+    let mut rows = stmt.query(params![Rc::new(blocks)])?;
+
+    while let Some(row) = rows.next()? {
+        let header = row.get_ref(0)?.as_blob()?;
+        let header = BlockHeader::read_from_bytes(header)?;
+        headers.push(header);
+    }
+
+    timer.finish(headers.len());
+    Ok(headers)
+}
+
+/// Select all block headers from the DB using the given [Connection].
+///
+/// # Returns
+///
+/// A vector of [BlockHeader] or an error.
+pub fn select_all_block_headers(conn: &mut Connection) -> Result<Vec<BlockHeader>> {
+    let timer = StatementTimer::start(labels::SELECT_ALL_BLOCK_HEADERS, []);
+    let mut stmt =
+        conn.prepare_cached("SELECT block_header FROM block_headers ORDER BY block_num ASC;")?;
+    let mut rows = stmt.query([])?;
+    let mut result = vec![];
+    while let Some(row) = rows.next()? {
+        let block_header_data = row.get_ref(0)?.as_blob()?;
+        let block_header = BlockHeader::read_from_bytes(block_header_data)?;
+        result.push(block_header);
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select a chunk of the block headers from the DB using the given [Connection], ordered by block
+/// number.
+///
+/// The chunk starts after the block `after`, or at the genesis block if [None].
+///
+/// # Returns
+///
+/// At most `limit` block headers, or an error.
+pub fn select_block_headers_chunk(
+    conn: &mut Connection,
+    after: Option<BlockNumber>,
+    limit: usize,
+) -> Result<Vec<BlockHeader>> {
+    let timer = StatementTimer::start(labels::SELECT_BLOCK_HEADERS_CHUNK, [("limit", limit)]);
+    let from = after.map_or(0, |block_num| i64::from(block_num) + 1);
+
+    let mut stmt = conn.prepare_cached(
+        "SELECT block_header FROM block_headers WHERE block_num >= ?1 ORDER BY block_num ASC LIMIT ?2;",
+    )?;
+    let mut rows = stmt.query(params![from, limit])?;
+    let mut result = vec![];
+    while let Some(row) = rows.next()? {
+        let block_header_data = row.get_ref(0)?.as_blob()?;
+        let block_header = BlockHeader::read_from_bytes(block_header_data)?;
+        result.push(block_header);
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+// TRANSACTIONS QUERIES
+// ================================================================================================
+
+/// Insert transactions to the DB using the given [Transaction].
+///
+/// # Returns
+///
+/// The number of affected rows.
+///
+/// # Note
+///
+/// The [Transaction] object is not consumed. It's up to the caller to commit or rollback the
+/// transaction.
+pub fn insert_transactions(
+    transaction: &Transaction,
+    block_num: BlockNumber,
+    accounts: &[BlockAccountUpdate],
+) -> Result<usize> {
+    let timer = StatementTimer::start(labels::INSERT_TRANSACTIONS, [("accounts", accounts.len())]);
+    let mut stmt = transaction.prepare_cached(
+        "INSERT INTO transactions (transaction_id, account_id, block_num) VALUES (?1, ?2, ?3);",
+    )?;
+    let mut count = 0;
+    for update in accounts {
+        let account_id = update.account_id().into();
+        for transaction_id in update.transactions() {
+            count += stmt.execute(params![
+                transaction_id.to_bytes(),
+                u64_to_value(account_id),
+                block_num
+            ])?
+        }
+    }
+    timer.finish(count);
+    Ok(count)
+}
+
+/// Select transaction IDs from the DB using the given [Connection], filtered by account IDS,
+/// given that the account updates were done between `(block_start, block_end]`.
+///
+/// # Returns
+///
+/// The vector of [RpoDigest] with the transaction IDs.
+pub fn select_transactions_by_accounts_and_block_range(
+    conn: &mut Connection,
+    block_start: BlockNumber,
+    block_end: BlockNumber,
+    account_ids: &[AccountId],
+) -> Result<Vec<TransactionSummary>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_TRANSACTIONS_BY_ACCOUNTS_AND_BLOCK_RANGE,
+        [("account_ids", account_ids.len())],
+    );
+    let account_ids: Vec<Value> = account_ids.iter().copied().map(u64_to_value).collect();
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            account_id,
+            block_num,
+            transaction_id
+        FROM
+            transactions
+        WHERE
+            block_num > ?1 AND
+            block_num <= ?2 AND
+            account_id IN rarray(?3)
+        ORDER BY
+            transaction_id ASC
+    ",
+    )?;
+
+    let mut rows = stmt.query(params![block_start, block_end, Rc::new(account_ids)])?;
+
+    let mut result = vec![];
+    while let Some(row) = rows.next()? {
+        let account_id = column_value_as_u64(row, 0)?;
+        let block_num = row.get(1)?;
+        let transaction_id_data = row.get_ref(2)?.as_blob()?;
+        let transaction_id = TransactionId::read_from_bytes(transaction_id_data)?;
+
+        result.push(TransactionSummary { account_id, block_num, transaction_id });
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+/// Select the transactions filtered by IDs from the DB using the given [Connection].
+///
+/// # Returns
+///
+/// The vector of [TransactionSummary] of the transactions found in the DB, i.e. committed in a
+/// block, ordered by transaction ID.
+pub fn select_transactions_by_ids(
+    conn: &mut Connection,
+    transaction_ids: &[TransactionId],
+) -> Result<Vec<TransactionSummary>> {
+    let timer = StatementTimer::start(
+        labels::SELECT_TRANSACTIONS_BY_IDS,
+        [("transaction_ids", transaction_ids.len())],
+    );
+    let transaction_ids: Vec<Value> =
+        transaction_ids.iter().map(|id| id.to_bytes().into()).collect();
+
+    let mut stmt = conn.prepare_cached(
+        "
+        SELECT
+            account_id,
+            block_num,
+            transaction_id
+        FROM
+            transactions
+        WHERE
+            transaction_id IN rarray(?1)
+        ORDER BY
+            transaction_id ASC
+    ",
+    )?;
+
+    let mut rows = stmt.query(params![Rc::new(transaction_ids)])?;
+
+    let mut result = vec![];
+    while let Some(row) = rows.next()? {
+        let account_id = column_value_as_u64(row, 0)?;
+        let block_num = row.get(1)?;
+        let transaction_id_data = row.get_ref(2)?.as_blob()?;
+        let transaction_id = TransactionId::read_from_bytes(transaction_id_data)?;
+
+        result.push(TransactionSummary { account_id, block_num, transaction_id });
+    }
+
+    timer.finish(result.len());
+    Ok(result)
+}
+
+// STATE SYNC
+// ================================================================================================
+
+/// Loads the state necessary for a state sync.
+pub fn get_state_sync(
+    conn: &mut Connection,
+    block_num: BlockNumber,
+    account_ids: &[AccountId],
+    note_tag_prefixes: &[u32],
+    nullifier_prefixes: &[u32],
+    cancellation: &QueryCancellation,
+) -> Result<StateSyncUpdate, StateSyncError> {
+    let notes = select_notes_since_block_by_tag_and_sender(
+        conn,
+        note_tag_prefixes,
+        account_ids,
+        block_num,
+    )?;
+
+    cancellation.check()?;
+    let block_header =
+        select_block_header_by_block_num(conn, notes.first().map(|note| note.block_num))?
+            .ok_or(StateSyncError::EmptyBlockHeadersTable)?;
+
+    cancellation.check()?;
+    let account_updates =
+        select_accounts_by_block_range(conn, block_num, block_header.block_num(), account_ids)?;
+
+    cancellation.check()?;
+    let transactions = select_transactions_by_accounts_and_block_range(
+        conn,
+        block_num,
+        block_header.block_num(),
+        account_ids,
+    )?;
+
+    cancellation.check()?;
+    let nullifiers = select_nullifiers_by_block_range(
+        conn,
+        block_num,
+        block_header.block_num(),
+        nullifier_prefixes,
+    )?;
+
+    Ok(StateSyncUpdate {
+        notes,
+        block_header,
+        account_updates,
+        transactions,
+        nullifiers,
+    })
+}
+
+// NOTE SYNC
+// ================================================================================================
+
+/// Loads the data necessary for a note sync.
+pub fn get_note_sync(
+    conn: &mut Connection,
+    block_num: BlockNumber,
+    note_tags: &[u32],
+    cancellation: &QueryCancellation,
+) -> Result<NoteSyncUpdate, NoteSyncError> {
+    let notes = select_notes_since_block_by_tag_and_sender(conn, note_tags, &[], block_num)?;
+
+    cancellation.check()?;
+    let block_header =
+        select_block_header_by_block_num(conn, notes.first().map(|note| note.block_num))?
+            .ok_or(NoteSyncError::EmptyBlockHeadersTable)?;
+
+    Ok(NoteSyncUpdate { notes, block_header })
+}
+
+// APPLY BLOCK
+// ================================================================================================
+
+/// Updates the DB with the state of a new block.
+///
+/// # Returns
+///
+/// The number of affected rows in the DB.
+pub fn apply_block(
+    transaction: &Transaction,
+    block_header: &BlockHeader,
+    notes: &[NoteRecord],
+    nullifiers: &[Nullifier],
+    accounts: &[BlockAccountUpdate],
+) -> Result<usize> {
+    let mut count = 0;
+    count += insert_block_header(transaction, block_header)?;
+    count += insert_notes(transaction, notes)?;
+    count += insert_block_note_filter(transaction, block_header.block_num(), notes)?;
+    count +=
+        insert_block_stats(transaction, block_header, notes.len(), nullifiers.len(), accounts)?;
+    count += upsert_accounts(transaction, accounts, block_header.block_num())?;
+    count += insert_transactions(transaction, block_header.block_num(), accounts)?;
+    count += insert_nullifiers_for_block(transaction, nullifiers, block_header.block_num())?;
+    Ok(count)
+}
+
+// UTILITIES
+// ================================================================================================
+
+/// Checks if a table exists in the database.
+pub(crate) fn table_exists(conn: &Connection, table_name: &str) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = $1",
+            params![table_name],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Returns the number of rows of a table.
+///
+/// The `table_name` is interpolated into the query, so it must be the name of one of the tables of
+/// the schema and never user input.
+pub fn count_rows(conn: &mut Connection, table_name: &str) -> Result<u64> {
+    let timer = StatementTimer::start(labels::COUNT_ROWS, []);
+    let count: i64 =
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table_name}"), [], |row| row.get(0))?;
+
+    timer.finish(1);
+    Ok(count as u64)
+}
+
+/// Returns the schema version of the database.
+pub(crate) fn schema_version(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.query_row("SELECT * FROM pragma_schema_version", [], |row| row.get(0))
+}
+
+/// Converts a `u64` into a [Value].
+///
+/// Sqlite uses `i64` as its internal representation format. Note that the `as` operator performs a
+/// lossless conversion from `u64` to `i64`.
+fn u64_to_value(v: u64) -> Value {
+    Value::Integer(v as i64)
+}
+
+/// Converts a `u32` into a [Value].
+///
+/// Sqlite uses `i64` as its internal representation format.
+fn u32_to_value(v: u32) -> Value {
+    let v: i64 = v.into();
+    Value::Integer(v)
+}
+
+/// Gets a `u64` value from the database.
+///
+/// Sqlite uses `i64` as its internal representation format, and so when retrieving
+/// we need to make sure we cast as `u64` to get the original value
+fn column_value_as_u64<I: rusqlite::RowIndex>(
+    row: &rusqlite::Row<'_>,
+    index: I,
+) -> rusqlite::Result<u64> {
+    let value: i64 = row.get(index)?;
+    Ok(value as u64)
+}
+
+/// Constructs [BlockStats] from the row of the `block_stats` table.
+///
+/// Note: field ordering must be the same, as in `block_stats` table!
+fn block_stats_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<BlockStats> {
+    Ok(BlockStats {
+        block_num: row.get(0)?,
+        timestamp: row.get(1)?,
+        num_transactions: column_value_as_u64(row, 2)?,
+        num_notes: column_value_as_u64(row, 3)?,
+        num_nullifiers: column_value_as_u64(row, 4)?,
+        num_new_accounts: column_value_as_u64(row, 5)?,
+        total_transactions: column_value_as_u64(row, 6)?,
+        total_notes: column_value_as_u64(row, 7)?,
+        total_nullifiers: column_value_as_u64(row, 8)?,
+        total_accounts: column_value_as_u64(row, 9)?,
+    })
+}
+
+/// Constructs `AccountSummary` from the row of `accounts` table, whose fourth column is whether
+/// the details of the account are stored.
+///
+/// Note: field ordering must be the same, as in `accounts` table!
+fn account_hash_update_from_row(row: &rusqlite::Row<'_>) -> Result<AccountSummary> {
+    account_summary_from_row(row, row.get(3)?)
+}
+
+/// Constructs `AccountSummary` from the first columns of the row of `accounts` table.
+fn account_summary_from_row(
+    row: &rusqlite::Row<'_>,
+    details_available: bool,
+) -> Result<AccountSummary> {
+    let account_id = column_value_as_u64(row, 0)?;
+    let account_hash_data = row.get_ref(1)?.as_blob()?;
+    let account_hash = RpoDigest::read_from_bytes(account_hash_data)?;
+    let block_num = row.get(2)?;
+
+    Ok(AccountSummary {
+        account_id: account_id.try_into()?,
+        account_hash,
+        block_num,
+        details_available,
+    })
+}
+
+/// Extracts the recipient digest from the serialized details of a public note.
+///
+/// Returns `None` if the details can't be deserialized into a note.
+fn note_recipient(details: &[u8]) -> Option<RpoDigest> {
+    Note::read_from_bytes(details).ok().map(|note| note.recipient().digest())
+}
+
+/// Constructs `NoteRecord` from the row of `notes` table.
+///
+/// Note: field ordering must be the same, as in `notes` table!
+fn note_record_from_row(row: &rusqlite::Row<'_>) -> Result<NoteRecord> {
+    let note_id_data = row.get_ref(3)?.as_blob()?;
+    let note_id = RpoDigest::read_from_bytes(note_id_data)?;
+
+    let merkle_path_data = row.get_ref(9)?.as_blob()?;
+    let merkle_path = MerklePath::read_from_bytes(merkle_path_data)?;
+
+    let details_data = row.get_ref(10)?.as_blob_or_null()?;
+    let details = details_data.map(<Vec<u8>>::read_from_bytes).transpose()?;
+
+    let note_type = row.get::<_, u8>(4)?.try_into()?;
+    let sender = column_value_as_u64(row, 5)?;
+    let tag: u32 = row.get(6)?;
+    let aux: u64 = row.get(7)?;
+    let aux = aux.try_into().map_err(DatabaseError::InvalidFelt)?;
+    let execution_hint = column_value_as_u64(row, 8)?;
+
+    let metadata = NoteMetadata::new(
+        sender.try_into()?,
+        note_type,
+        tag.into(),
+        execution_hint.try_into()?,
+        aux,
+    )?;
+
+    let created_by_tx_data = row.get_ref(11)?.as_blob_or_null()?;
+    let created_by_tx = created_by_tx_data.map(TransactionId::read_from_bytes).transpose()?;
+
+    let nullifier_data = row.get_ref(12)?.as_blob_or_null()?;
+    let nullifier = nullifier_data.map(Nullifier::read_from_bytes).transpose()?;
+
+    Ok(NoteRecord {
+        block_num: row.get(0)?,
+        note_index: BlockNoteIndex::new(row.get(1)?, row.get(2)?)?,
+        note_id,
+        metadata,
+        details,
+        merkle_path,
+        created_by_tx,
+        nullifier,
+    })
+}
+
+/// Constructs `AccountInfo` from the row of `accounts` table.
+///
+/// Note: field ordering must be the same, as in `accounts` table!
+fn account_info_from_row(row: &rusqlite::Row<'_>) -> Result<AccountInfo> {
+    let details = row.get_ref(3)?.as_blob_or_null()?;
+    let update = account_summary_from_row(row, details.is_some())?;
+
+    let details = details.map(Account::read_from_bytes).transpose().map_err(|err| {
+        DatabaseError::DataCorrupted(format!(
+            "Failed to deserialize the details of account {}: {err}",
+            update.account_id
+        ))
+    })?;
+
+    Ok(AccountInfo { summary: update, details })
+}
+
+/// Deserializes account and applies account delta.
+fn apply_delta(
+    account_id: u64,
+    value: &ValueRef<'_>,
+    delta: &AccountDelta,
+    final_state_hash: &RpoDigest,
+) -> Result<Account, DatabaseError> {
+    let account = value.as_blob_or_null()?;
+    let account = account.map(Account::read_from_bytes).transpose()?;
+
+    let Some(mut account) = account else {
+        return Err(DatabaseError::AccountNotOnChain(account_id));
+    };
+
+    account.apply_delta(delta)?;
+
+    let actual_hash = account.hash();
+    if &actual_hash != final_state_hash {
+        return Err(DatabaseError::AccountHashesMismatch {
+            calculated: actual_hash,
+            expected: *final_state_hash,
+        });
+    }
+
+    Ok(account)
+}
@@ -1,5 +1,11 @@
-use miden_lib::transaction::TransactionKernel;
-use miden_node_proto::domain::accounts::AccountSummary;
+use std::{fs, sync::Arc, time::Duration};
+
+use deadpool_sqlite::{Config as SqliteConfig, Pool, Runtime};
+use miden_lib::{notes::create_p2id_note, transaction::TransactionKernel};
+use miden_node_proto::{
+    domain::{accounts::AccountSummary, note_filters::NoteTagFilter, prefixes::nullifier_prefix},
+    generated::block::BlockStageDurations,
+};
 use miden_objects::{
     accounts::{
         account_id::testing::{
@@ -13,14 +19,32 @@ use miden_objects::{
     },
     assets::{Asset, AssetVault, FungibleAsset, NonFungibleAsset, NonFungibleAssetDetails},
     block::{BlockAccountUpdate, BlockNoteIndex, BlockNoteTree},
-    crypto::{hash::rpo::RpoDigest, merkle::MerklePath},
-    notes::{NoteExecutionHint, NoteId, NoteMetadata, NoteType, Nullifier},
-    BlockHeader, Felt, FieldElement, Word, ONE, ZERO,
+    crypto::{
+        hash::rpo::RpoDigest,
+        merkle::{MerklePath, Mmr, SimpleSmt},
+        rand::RpoRandomCoin,
+    },
+    notes::{Note, NoteAssets, NoteExecutionHint, NoteId, NoteMetadata, NoteType, Nullifier},
+    utils::Serializable,
+    BlockHeader, Felt, FieldElement, Word, ACCOUNT_TREE_DEPTH, ONE, ZERO,
 };
+use prost::Message;
 use rusqlite::{vtab::array, Connection};
 
-use super::{sql, AccountInfo, NoteRecord, NullifierInfo};
-use crate::db::{migrations::apply_migrations, TransactionSummary};
+use super::{
+    cancellation::{self, QueryCancellation, REQUEST_QUERY_TIMEOUT},
+    sql, AccountInfo, Db, NoteConsumption, NoteRecord, NoteSyncRecord, NullifierInfo,
+};
+use crate::{
+    block_stats::BlockProductionStats,
+    blocks::BlockStore,
+    chain_stats::ChainStats,
+    config::StoreConfig,
+    db::{migrations::apply_migrations, TransactionSummary},
+    errors::DatabaseError,
+    genesis::GenesisState,
+    nullifier_tree::NullifierTree,
+};
 
 fn create_db() -> Connection {
     let mut conn = Connection::open_in_memory().unwrap();
@@ -179,6 +203,8 @@ fn test_sql_select_notes() {
             .unwrap(),
             details: Some(vec![1, 2, 3]),
             merkle_path: MerklePath::new(vec![]),
+            created_by_tx: None,
+            nullifier: None,
         };
         state.push(note.clone());
 
@@ -219,6 +245,8 @@ fn test_sql_select_notes_different_execution_hints() {
         .unwrap(),
         details: Some(vec![1, 2, 3]),
         merkle_path: MerklePath::new(vec![]),
+        created_by_tx: None,
+        nullifier: None,
     };
     state.push(note_none.clone());
 
@@ -243,6 +271,8 @@ fn test_sql_select_notes_different_execution_hints() {
         .unwrap(),
         details: Some(vec![1, 2, 3]),
         merkle_path: MerklePath::new(vec![]),
+        created_by_tx: None,
+        nullifier: None,
     };
     state.push(note_always.clone());
 
@@ -267,6 +297,8 @@ fn test_sql_select_notes_different_execution_hints() {
         .unwrap(),
         details: Some(vec![1, 2, 3]),
         merkle_path: MerklePath::new(vec![]),
+        created_by_tx: None,
+        nullifier: None,
     };
     state.push(note_after_block.clone());
 
@@ -299,6 +331,7 @@ fn test_sql_select_accounts() {
                 account_id: account_id.try_into().unwrap(),
                 account_hash,
                 block_num,
+                details_available: false,
             },
             details: None,
         });
@@ -380,8 +413,14 @@ fn test_sql_public_account_details() {
 
     assert_eq!(accounts_in_db.len(), 1, "One element must have been inserted");
 
-    let account_read = accounts_in_db.pop().unwrap().details.unwrap();
-    assert_eq!(account_read, account);
+    let account_read = accounts_in_db.pop().unwrap();
+    assert!(account_read.summary.details_available);
+    assert_eq!(account_read.details.unwrap(), account);
+
+    // The details of public accounts are reported available in the account summaries
+    let summaries =
+        sql::select_accounts_by_block_range(&mut conn, 0, u32::MAX, &[account_id.into()]).unwrap();
+    assert!(summaries[0].details_available);
 
     let storage_delta =
         AccountStorageDelta::from_iters([3], [(4, num_to_word(5)), (5, num_to_word(6))], []);
@@ -485,6 +524,104 @@ fn test_sql_public_account_details() {
     assert_eq!(read_deltas, vec![delta, delta2]);
 }
 
+#[test]
+fn test_sql_select_account_delta() {
+    let mut conn = create_db();
+
+    let account_id =
+        AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN).unwrap();
+    let fungible_faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let asset = |amount| Asset::Fungible(FungibleAsset::new(fungible_faucet_id, amount).unwrap());
+
+    let (code, storage) = mock_account_code_and_storage(account_id.account_type());
+    let mut account = Account::from_parts(
+        account_id,
+        AssetVault::new(&[asset(100)]).unwrap(),
+        storage,
+        code,
+        ZERO,
+    );
+
+    let deltas = [
+        AccountDelta::new(
+            AccountStorageDelta::from_iters([], [(1, num_to_word(10))], []),
+            AccountVaultDelta::from_iters([asset(50)], []),
+            Some(ONE),
+        )
+        .unwrap(),
+        AccountDelta::new(
+            AccountStorageDelta::from_iters([], [(1, num_to_word(11)), (3, num_to_word(12))], []),
+            AccountVaultDelta::from_iters([], [asset(30)]),
+            Some(Felt::new(2)),
+        )
+        .unwrap(),
+        AccountDelta::new(
+            AccountStorageDelta::from_iters([], [(5, num_to_word(13))], []),
+            AccountVaultDelta::default(),
+            Some(Felt::new(3)),
+        )
+        .unwrap(),
+    ];
+
+    // The account is created in block 1, and updated in each of the following blocks
+    let mut updates = vec![AccountUpdateDetails::New(account.clone())];
+    updates.extend(deltas.iter().cloned().map(AccountUpdateDetails::Delta));
+
+    let mut states = Vec::new();
+    for (block_num, details) in (1..).zip(updates) {
+        if let AccountUpdateDetails::Delta(delta) = &details {
+            account.apply_delta(delta).unwrap();
+        }
+        states.push(account.clone());
+
+        create_block(&mut conn, block_num);
+        let transaction = conn.transaction().unwrap();
+        sql::upsert_accounts(
+            &transaction,
+            &[BlockAccountUpdate::new(account_id, account.hash(), details, vec![])],
+            block_num,
+        )
+        .unwrap();
+        transaction.commit().unwrap();
+    }
+
+    let latest_block_num = states.len() as u32;
+    let account_hash =
+        sql::select_account(&mut conn, account_id.into()).unwrap().summary.account_hash;
+    assert_eq!(account_hash, account.hash());
+
+    // Applying the delta since any known block to the state at that block yields the latest
+    // committed account hash
+    for (known_block_num, known_state) in (1..).zip(&states) {
+        let delta = sql::select_account_delta(
+            &mut conn,
+            account_id.into(),
+            known_block_num,
+            latest_block_num,
+        )
+        .unwrap();
+
+        let mut updated_state = known_state.clone();
+        if known_block_num < latest_block_num {
+            updated_state.apply_delta(&delta).unwrap();
+        } else {
+            assert_eq!(delta, AccountDelta::default());
+        }
+
+        assert_eq!(updated_state.hash(), account_hash);
+    }
+
+    // Ranges may end at the last block number
+    let delta = sql::select_account_delta(&mut conn, account_id.into(), 1, u32::MAX).unwrap();
+    let mut updated_state = states[0].clone();
+    updated_state.apply_delta(&delta).unwrap();
+    assert_eq!(updated_state.hash(), account_hash);
+
+    let delta =
+        sql::select_account_delta(&mut conn, account_id.into(), u32::MAX, u32::MAX).unwrap();
+    assert_eq!(delta, AccountDelta::default());
+}
+
 #[test]
 fn test_sql_select_nullifiers_by_block_range() {
     let mut conn = create_db();
@@ -506,7 +643,7 @@ fn test_sql_select_nullifiers_by_block_range() {
         &mut conn,
         0,
         u32::MAX,
-        &[sql::get_nullifier_prefix(&nullifier1)],
+        &[nullifier_prefix(&nullifier1)],
     )
     .unwrap();
     assert_eq!(
@@ -534,7 +671,7 @@ fn test_sql_select_nullifiers_by_block_range() {
         &mut conn,
         0,
         u32::MAX,
-        &[sql::get_nullifier_prefix(&nullifier1)],
+        &[nullifier_prefix(&nullifier1)],
     )
     .unwrap();
     assert_eq!(
@@ -548,7 +685,7 @@ fn test_sql_select_nullifiers_by_block_range() {
         &mut conn,
         0,
         u32::MAX,
-        &[sql::get_nullifier_prefix(&nullifier2)],
+        &[nullifier_prefix(&nullifier2)],
     )
     .unwrap();
     assert_eq!(
@@ -564,7 +701,7 @@ fn test_sql_select_nullifiers_by_block_range() {
         &mut conn,
         0,
         1,
-        &[sql::get_nullifier_prefix(&nullifier1), sql::get_nullifier_prefix(&nullifier2)],
+        &[nullifier_prefix(&nullifier1), nullifier_prefix(&nullifier2)],
     )
     .unwrap();
     assert_eq!(
@@ -580,7 +717,7 @@ fn test_sql_select_nullifiers_by_block_range() {
         &mut conn,
         1,
         u32::MAX,
-        &[sql::get_nullifier_prefix(&nullifier1), sql::get_nullifier_prefix(&nullifier2)],
+        &[nullifier_prefix(&nullifier1), nullifier_prefix(&nullifier2)],
     )
     .unwrap();
     assert_eq!(
@@ -597,7 +734,7 @@ fn test_sql_select_nullifiers_by_block_range() {
         &mut conn,
         2,
         2,
-        &[sql::get_nullifier_prefix(&nullifier1), sql::get_nullifier_prefix(&nullifier2)],
+        &[nullifier_prefix(&nullifier1), nullifier_prefix(&nullifier2)],
     )
     .unwrap();
     assert!(nullifiers.is_empty());
@@ -608,7 +745,8 @@ fn test_select_nullifiers_by_prefix() {
     let mut conn = create_db();
     const PREFIX_LEN: u32 = 16;
     // test empty table
-    let nullifiers = sql::select_nullifiers_by_prefix(&mut conn, PREFIX_LEN, &[]).unwrap();
+    let nullifiers =
+        sql::select_nullifiers_by_prefix(&mut conn, PREFIX_LEN, &[], None, 10).unwrap();
     assert!(nullifiers.is_empty());
 
     // test single item
@@ -623,7 +761,9 @@ fn test_select_nullifiers_by_prefix() {
     let nullifiers = sql::select_nullifiers_by_prefix(
         &mut conn,
         PREFIX_LEN,
-        &[sql::get_nullifier_prefix(&nullifier1)],
+        &[nullifier_prefix(&nullifier1)],
+        None,
+        10,
     )
     .unwrap();
     assert_eq!(
@@ -650,7 +790,9 @@ fn test_select_nullifiers_by_prefix() {
     let nullifiers = sql::select_nullifiers_by_prefix(
         &mut conn,
         PREFIX_LEN,
-        &[sql::get_nullifier_prefix(&nullifier1)],
+        &[nullifier_prefix(&nullifier1)],
+        None,
+        10,
     )
     .unwrap();
     assert_eq!(
@@ -663,7 +805,9 @@ fn test_select_nullifiers_by_prefix() {
     let nullifiers = sql::select_nullifiers_by_prefix(
         &mut conn,
         PREFIX_LEN,
-        &[sql::get_nullifier_prefix(&nullifier2)],
+        &[nullifier_prefix(&nullifier2)],
+        None,
+        10,
     )
     .unwrap();
     assert_eq!(
@@ -678,7 +822,9 @@ fn test_select_nullifiers_by_prefix() {
     let nullifiers = sql::select_nullifiers_by_prefix(
         &mut conn,
         PREFIX_LEN,
-        &[sql::get_nullifier_prefix(&nullifier1), sql::get_nullifier_prefix(&nullifier2)],
+        &[nullifier_prefix(&nullifier1), nullifier_prefix(&nullifier2)],
+        None,
+        10,
     )
     .unwrap();
     assert_eq!(
@@ -699,10 +845,46 @@ fn test_select_nullifiers_by_prefix() {
     let nullifiers = sql::select_nullifiers_by_prefix(
         &mut conn,
         PREFIX_LEN,
-        &[sql::get_nullifier_prefix(&num_to_nullifier(3 << 48))],
+        &[nullifier_prefix(&num_to_nullifier(3 << 48))],
+        None,
+        10,
     )
     .unwrap();
     assert!(nullifiers.is_empty());
+
+    // Nullifiers sharing the prefix of the first one, in the same block and in a later one
+    let block_number3 = 3;
+    create_block(&mut conn, block_number3);
+    let transaction = conn.transaction().unwrap();
+    let same_block = [num_to_nullifier((1 << 48) + 1), num_to_nullifier((1 << 48) + 2)];
+    sql::insert_nullifiers_for_block(&transaction, &same_block, block_number2).unwrap();
+    sql::insert_nullifiers_for_block(
+        &transaction,
+        &[num_to_nullifier((1 << 48) + 3)],
+        block_number3,
+    )
+    .unwrap();
+    transaction.commit().unwrap();
+
+    let prefixes = [nullifier_prefix(&nullifier1)];
+    let all = sql::select_nullifiers_by_prefix(&mut conn, PREFIX_LEN, &prefixes, None, 10).unwrap();
+    assert_eq!(all.len(), 4);
+    assert!(all.windows(2).all(|pair| pair[0].block_num <= pair[1].block_num));
+
+    // Pages resume after the position of the last nullifier of the previous page
+    let mut paged = Vec::new();
+    let mut after = None;
+    loop {
+        let page =
+            sql::select_nullifiers_by_prefix(&mut conn, PREFIX_LEN, &prefixes, after, 3).unwrap();
+        after = page.last().map(|info| (info.block_num, info.nullifier));
+        let last_page = page.len() < 3;
+        paged.extend(page);
+        if last_page {
+            break;
+        }
+    }
+    assert_eq!(paged, all);
 }
 
 #[test]
@@ -777,6 +959,105 @@ fn test_db_block_header() {
     assert_eq!(res, [block_header, block_header2]);
 }
 
+#[test]
+fn test_select_block_header_by_commitment() {
+    let mut conn = create_db();
+
+    let block_header = |block_num, prev_hash| {
+        BlockHeader::new(
+            1_u8.into(),
+            prev_hash,
+            block_num,
+            num_to_rpo_digest(4),
+            num_to_rpo_digest(5),
+            num_to_rpo_digest(6),
+            num_to_rpo_digest(7),
+            num_to_rpo_digest(8),
+            num_to_rpo_digest(9),
+            num_to_rpo_digest(10),
+            11_u8.into(),
+        )
+    };
+    let genesis = block_header(0, RpoDigest::default());
+    let block_1 = block_header(1, genesis.hash());
+    let tip = block_header(2, block_1.hash());
+
+    let transaction = conn.transaction().unwrap();
+    for header in [&genesis, &block_1, &tip] {
+        sql::insert_block_header(&transaction, header).unwrap();
+    }
+    transaction.commit().unwrap();
+
+    let assert_lookups = |conn: &mut Connection| {
+        for header in [&genesis, &tip] {
+            let res = sql::select_block_header_by_commitment(conn, header.hash()).unwrap();
+            assert_eq!(res.as_ref(), Some(header));
+        }
+        let res = sql::select_block_header_by_commitment(conn, num_to_rpo_digest(42)).unwrap();
+        assert!(res.is_none());
+    };
+    assert_lookups(&mut conn);
+
+    // Block headers stored before the commitment column was added are backfilled
+    conn.execute("UPDATE block_headers SET block_commitment = NULL", []).unwrap();
+    let res = sql::select_block_header_by_commitment(&mut conn, tip.hash()).unwrap();
+    assert!(res.is_none());
+
+    let transaction = conn.transaction().unwrap();
+    assert_eq!(sql::backfill_block_commitments(&transaction).unwrap(), 3);
+    transaction.commit().unwrap();
+    assert_lookups(&mut conn);
+
+    // Backfilling again is a no-op
+    let transaction = conn.transaction().unwrap();
+    assert_eq!(sql::backfill_block_commitments(&transaction).unwrap(), 0);
+}
+
+#[test]
+fn test_block_production_stats() {
+    let mut conn = create_db();
+    for block_num in 0..4 {
+        create_block(&mut conn, block_num);
+    }
+
+    let reported = BlockStageDurations {
+        inputs_fetch_us: 100,
+        propose_us: 200,
+        prove_us: 300,
+        build_us: 650,
+    };
+    let block_1 = BlockProductionStats::new(1, Some(reported), Duration::from_micros(50));
+    let block_2 = BlockProductionStats::new(2, None, Duration::from_micros(60));
+    for stats in [&block_1, &block_2] {
+        assert_eq!(sql::upsert_block_production_stats(&mut conn, stats).unwrap(), 1);
+    }
+
+    // Blocks without recorded stats are returned with unknown durations, and blocks which are not
+    // part of the chain are left out
+    let res = sql::select_block_production_stats(&mut conn, 0, 10).unwrap();
+    assert_eq!(
+        res,
+        [
+            BlockProductionStats { block_num: 0, ..Default::default() },
+            block_1,
+            block_2,
+            BlockProductionStats { block_num: 3, ..Default::default() },
+        ]
+    );
+    assert_eq!(res[1].total_us, Some(700));
+    assert_eq!(res[2].inputs_fetch_us, None);
+    assert_eq!(res[2].commit_us, Some(60));
+
+    let res = sql::select_block_production_stats(&mut conn, 2, 2).unwrap();
+    assert_eq!(res, [block_2]);
+
+    // Recording the stats of a block again replaces them
+    let block_2 = BlockProductionStats::new(2, Some(reported), Duration::from_micros(70));
+    sql::upsert_block_production_stats(&mut conn, &block_2).unwrap();
+    let res = sql::select_block_production_stats(&mut conn, 2, 2).unwrap();
+    assert_eq!(res, [block_2]);
+}
+
 #[test]
 fn test_db_account() {
     let mut conn = create_db();
@@ -817,6 +1098,7 @@ fn test_db_account() {
             account_id: account_id.try_into().unwrap(),
             account_hash,
             block_num,
+            details_available: false,
         }]
     );
 
@@ -831,6 +1113,188 @@ fn test_db_account() {
     assert!(res.is_empty());
 }
 
+#[test]
+fn test_select_account_with_corrupted_details() {
+    let mut conn = create_db();
+
+    let block_num = 1;
+    create_block(&mut conn, block_num);
+
+    let account_id =
+        AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN).unwrap();
+    let (code, storage) = mock_account_code_and_storage(account_id.account_type());
+    let account = Account::from_parts(account_id, AssetVault::default(), storage, code, ZERO);
+
+    let transaction = conn.transaction().unwrap();
+    sql::upsert_accounts(
+        &transaction,
+        &[BlockAccountUpdate::new(
+            account_id,
+            account.hash(),
+            AccountUpdateDetails::New(account.clone()),
+            vec![],
+        )],
+        block_num,
+    )
+    .unwrap();
+    transaction.commit().unwrap();
+
+    let account_read = sql::select_account(&mut conn, account_id.into()).unwrap();
+    assert_eq!(account_read.details, Some(account));
+
+    conn.execute("UPDATE accounts SET details = x'00'", []).unwrap();
+    let err = sql::select_account(&mut conn, account_id.into()).unwrap_err();
+    assert!(matches!(err, DatabaseError::DataCorrupted(_)), "{err}");
+}
+
+#[test]
+fn test_chunked_loading() {
+    let mut conn = create_db();
+
+    for block_num in 0..7 {
+        create_block(&mut conn, block_num);
+    }
+
+    let transaction = conn.transaction().unwrap();
+    for i in 0..20 {
+        sql::insert_nullifiers_for_block(&transaction, &[num_to_nullifier(i)], i as u32 % 7)
+            .unwrap();
+    }
+    for i in 0..10 {
+        let account_id =
+            ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN + (i << 32) + 0b1111100000;
+        sql::upsert_accounts(
+            &transaction,
+            &[mock_block_account_update(account_id.try_into().unwrap(), i)],
+            i as u32 % 7,
+        )
+        .unwrap();
+    }
+    transaction.commit().unwrap();
+
+    assert_eq!(sql::count_rows(&mut conn, "nullifiers").unwrap(), 20);
+    assert_eq!(sql::count_rows(&mut conn, "accounts").unwrap(), 10);
+    assert_eq!(sql::count_rows(&mut conn, "block_headers").unwrap(), 7);
+
+    let nullifier_root =
+        |nullifiers: Vec<(Nullifier, u32)>| NullifierTree::with_entries(nullifiers).unwrap().root();
+    let account_root = |accounts: Vec<(u64, RpoDigest)>| {
+        SimpleSmt::<ACCOUNT_TREE_DEPTH>::with_leaves(
+            accounts.into_iter().map(|(id, hash)| (id, hash.into())),
+        )
+        .unwrap()
+        .root()
+    };
+    let chain_peaks = |headers: Vec<BlockHeader>| {
+        Mmr::from(headers.iter().map(BlockHeader::hash)).peaks().hash_peaks()
+    };
+
+    let expected_nullifier_root = nullifier_root(sql::select_all_nullifiers(&mut conn).unwrap());
+    let expected_account_root = account_root(sql::select_all_account_hashes(&mut conn).unwrap());
+    let expected_chain_peaks = chain_peaks(sql::select_all_block_headers(&mut conn).unwrap());
+
+    // The chunks cover every row exactly once, whatever their size
+    for chunk_size in [1, 3, 7, 1000] {
+        let mut nullifiers: Vec<(Nullifier, u32)> = Vec::new();
+        loop {
+            let after = nullifiers.last().map(|(nullifier, _)| nullifier);
+            let chunk = sql::select_nullifiers_chunk(&mut conn, after, chunk_size).unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            assert!(chunk.len() <= chunk_size);
+            nullifiers.extend(chunk);
+        }
+        assert_eq!(nullifiers.len(), 20);
+        assert_eq!(nullifier_root(nullifiers), expected_nullifier_root);
+
+        let mut accounts: Vec<(u64, RpoDigest)> = Vec::new();
+        loop {
+            let after = accounts.last().map(|(account_id, _)| *account_id);
+            let chunk = sql::select_account_hashes_chunk(&mut conn, after, chunk_size).unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            accounts.extend(chunk);
+        }
+        assert_eq!(accounts.len(), 10);
+        assert_eq!(account_root(accounts), expected_account_root);
+
+        let mut headers: Vec<BlockHeader> = Vec::new();
+        loop {
+            let after = headers.last().map(BlockHeader::block_num);
+            let chunk = sql::select_block_headers_chunk(&mut conn, after, chunk_size).unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            headers.extend(chunk);
+        }
+        assert_eq!(
+            headers.iter().map(BlockHeader::block_num).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(chain_peaks(headers), expected_chain_peaks);
+    }
+}
+
+#[test]
+fn test_notes_without_retained_details() {
+    let mut conn = create_db();
+
+    let block_num = 1;
+    create_block(&mut conn, block_num);
+
+    // A public note with details, a public note whose details were not retained, and a private
+    // note, all with the same tag
+    let tag = 5u32;
+    let sender = AccountId::new_unchecked(Felt::new(ACCOUNT_ID_OFF_CHAIN_SENDER));
+    let metadata = |note_type| {
+        NoteMetadata::new(sender, note_type, tag.into(), NoteExecutionHint::none(), ZERO).unwrap()
+    };
+    let notes: Vec<_> = [
+        (NoteType::Public, Some(vec![1, 2, 3])),
+        (NoteType::Public, None),
+        (NoteType::Private, None),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (note_type, details))| NoteRecord {
+        block_num,
+        note_index: BlockNoteIndex::new(0, i).unwrap(),
+        note_id: num_to_rpo_digest(i as u64),
+        metadata: metadata(note_type),
+        details,
+        merkle_path: MerklePath::new(vec![]),
+        created_by_tx: None,
+        nullifier: None,
+    })
+    .collect();
+
+    let transaction = conn.transaction().unwrap();
+    sql::insert_notes(&transaction, &notes).unwrap();
+    transaction.commit().unwrap();
+
+    // Syncing is unaffected by the missing details
+    let synced =
+        sql::select_notes_since_block_by_tag_and_sender(&mut conn, &[tag], &[], 0).unwrap();
+    assert_eq!(synced, notes.iter().cloned().map(Into::into).collect::<Vec<NoteSyncRecord>>());
+
+    // Only the public note without details is flagged
+    let note_ids: Vec<NoteId> = notes.iter().map(|note| note.note_id.into()).collect();
+    let mut fetched = sql::select_notes_by_id(&mut conn, &note_ids).unwrap();
+    fetched.sort_by_key(|note| note.note_index.leaf_index_value());
+    assert_eq!(
+        fetched
+            .into_iter()
+            .map(|note| {
+                let note = note.into_proto(false);
+                (note.details.is_some(), note.details_not_retained)
+            })
+            .collect::<Vec<_>>(),
+        [(true, false), (false, true), (false, false)]
+    );
+}
+
 #[test]
 fn test_notes() {
     let mut conn = create_db();
@@ -874,6 +1338,8 @@ fn test_notes() {
         .unwrap(),
         details,
         merkle_path: merkle_path.clone(),
+        created_by_tx: None,
+        nullifier: None,
     };
 
     let transaction = conn.transaction().unwrap();
@@ -906,6 +1372,8 @@ fn test_notes() {
         metadata: note.metadata,
         details: None,
         merkle_path,
+        created_by_tx: None,
+        nullifier: None,
     };
 
     let transaction = conn.transaction().unwrap();
@@ -938,8 +1406,594 @@ fn test_notes() {
     assert_eq!(note_1.details, None)
 }
 
+#[test]
+fn test_select_notes_consumption() {
+    let mut conn = create_db();
+    for block_num in 1..=2 {
+        create_block(&mut conn, block_num);
+    }
+
+    let sender = AccountId::try_from(ACCOUNT_ID_OFF_CHAIN_SENDER).unwrap();
+    let target = AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let mut rng = RpoRandomCoin::new(num_to_word(1));
+    let mut p2id_note = |amount: u64| {
+        let asset = FungibleAsset::new(faucet_id, amount).unwrap().into();
+        create_p2id_note(sender, target, vec![asset], NoteType::Public, ZERO, &mut rng).unwrap()
+    };
+    let consumed = p2id_note(10);
+    let unconsumed = p2id_note(20);
+    let backfilled = p2id_note(30);
+
+    let note_record = |note_idx, note_id, details: Option<Vec<u8>>, nullifier| NoteRecord {
+        block_num: 1,
+        note_index: BlockNoteIndex::new(0, note_idx).unwrap(),
+        note_id,
+        metadata: *consumed.metadata(),
+        details,
+        merkle_path: MerklePath::new(vec![]),
+        created_by_tx: None,
+        nullifier,
+    };
+    let notes = [
+        // The nullifier is known even if the details are not retained
+        note_record(0, consumed.id().into(), None, Some(consumed.nullifier())),
+        note_record(1, unconsumed.id().into(), None, Some(unconsumed.nullifier())),
+        note_record(2, num_to_rpo_digest(99), None, None),
+        // Notes stored before the nullifiers are backfilled from their details
+        note_record(3, backfilled.id().into(), Some(backfilled.to_bytes()), None),
+    ];
+
+    let transaction = conn.transaction().unwrap();
+    sql::insert_notes(&transaction, &notes).unwrap();
+    sql::insert_nullifiers_for_block(&transaction, &[consumed.nullifier()], 2).unwrap();
+    assert_eq!(sql::backfill_note_nullifiers(&transaction).unwrap(), 1);
+    transaction.commit().unwrap();
+
+    let note_ids = [
+        consumed.id(),
+        unconsumed.id(),
+        num_to_rpo_digest(99).into(),
+        backfilled.id(),
+        num_to_rpo_digest(100).into(),
+    ];
+    let mut res = sql::select_notes_consumption(&mut conn, &note_ids).unwrap();
+    res.sort_by_key(|note| note.note_id);
+
+    let status = |note_id, nullifier, consumed_block_num| NoteConsumption {
+        note_id,
+        block_num: 1,
+        nullifier,
+        consumed_block_num,
+    };
+    let mut expected = vec![
+        status(consumed.id().into(), Some(consumed.nullifier()), Some(2)),
+        status(unconsumed.id().into(), Some(unconsumed.nullifier()), None),
+        // Private notes have no known nullifier, and unknown notes are left out
+        status(num_to_rpo_digest(99), None, None),
+        status(backfilled.id().into(), Some(backfilled.nullifier()), None),
+    ];
+    expected.sort_by_key(|note| note.note_id);
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn test_state_sync_with_duplicated_params() {
+    let mut conn = create_db();
+
+    let block_num = 1;
+    create_block(&mut conn, block_num);
+
+    let account_id = ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN;
+    let tag = 5u32;
+    let nullifier = num_to_nullifier(1 << 48);
+
+    let sender = AccountId::new_unchecked(Felt::new(ACCOUNT_ID_OFF_CHAIN_SENDER));
+    let note_index = BlockNoteIndex::new(0, 0).unwrap();
+    let note = NoteRecord {
+        block_num,
+        note_index,
+        note_id: num_to_rpo_digest(3),
+        metadata: NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            tag.into(),
+            NoteExecutionHint::none(),
+            ZERO,
+        )
+        .unwrap(),
+        details: None,
+        merkle_path: MerklePath::new(vec![]),
+        created_by_tx: None,
+        nullifier: None,
+    };
+
+    let transaction = conn.transaction().unwrap();
+    sql::insert_notes(&transaction, &[note]).unwrap();
+    sql::upsert_accounts(
+        &transaction,
+        &[mock_block_account_update(account_id.try_into().unwrap(), 1)],
+        block_num,
+    )
+    .unwrap();
+    sql::insert_transactions(
+        &transaction,
+        block_num,
+        &[mock_block_account_update(account_id.try_into().unwrap(), 1)],
+    )
+    .unwrap();
+    sql::insert_nullifiers_for_block(&transaction, &[nullifier], block_num).unwrap();
+    transaction.commit().unwrap();
+
+    let mut sync = |account_ids: &[u64], note_tags: &[u32], nullifier_prefixes: &[u32]| {
+        sql::get_state_sync(
+            &mut conn,
+            0,
+            account_ids,
+            note_tags,
+            nullifier_prefixes,
+            &QueryCancellation::default(),
+        )
+        .unwrap()
+    };
+
+    let prefix = nullifier_prefix(&nullifier);
+    let deduplicated = sync(&[account_id], &[tag, 7], &[prefix, 3]);
+    let duplicated = sync(&[account_id; 50], &[7, tag].repeat(40), &[3, prefix, prefix].repeat(30));
+
+    assert_eq!(duplicated.notes.len(), 1);
+    assert_eq!(duplicated.account_updates.len(), 1);
+    assert_eq!(duplicated.transactions.len(), 1);
+    assert_eq!(duplicated.nullifiers.len(), 1);
+    assert_eq!(duplicated, deduplicated);
+}
+
+#[test]
+fn test_note_merkle_path_compression() {
+    // Notes spread over the batches of a block as in a moderately busy network
+    const NOTES_PER_BATCH: [usize; 8] = [12, 3, 57, 1, 30, 8, 0, 21];
+
+    let sender = AccountId::new_unchecked(Felt::new(ACCOUNT_ID_OFF_CHAIN_SENDER));
+    let metadata =
+        NoteMetadata::new(sender, NoteType::Private, 5u32.into(), NoteExecutionHint::none(), ZERO)
+            .unwrap();
+
+    let note_indices: Vec<_> = NOTES_PER_BATCH
+        .iter()
+        .enumerate()
+        .flat_map(|(batch, num_notes)| {
+            (0..*num_notes).map(move |note| BlockNoteIndex::new(batch, note).unwrap())
+        })
+        .collect();
+    let note_tree =
+        BlockNoteTree::with_entries(note_indices.iter().enumerate().map(|(i, note_index)| {
+            (*note_index, num_to_rpo_digest(i as u64 + 1).into(), metadata)
+        }))
+        .unwrap();
+
+    let note = |note_index: BlockNoteIndex| NoteRecord {
+        block_num: 1,
+        note_index,
+        note_id: num_to_rpo_digest(1),
+        metadata,
+        details: None,
+        merkle_path: note_tree.get_note_path(note_index),
+        created_by_tx: None,
+        nullifier: None,
+    };
+
+    let mut plain_size = 0;
+    let mut compressed_size = 0;
+    for note_index in note_indices {
+        let plain = note(note_index).into_proto(false);
+        let compressed = note(note_index).into_proto(true);
+
+        assert!(plain.compressed_merkle_path.is_none());
+        assert!(compressed.merkle_path.is_none());
+        let decompressed: MerklePath =
+            compressed.compressed_merkle_path.as_ref().unwrap().try_into().unwrap();
+        assert_eq!(decompressed, note_tree.get_note_path(note_index));
+
+        plain_size += plain.encoded_len();
+        compressed_size += compressed.encoded_len();
+    }
+
+    // Roughly half of the siblings are empty subtree roots for this distribution of notes
+    assert!(
+        compressed_size * 10 < plain_size * 6,
+        "compressed {compressed_size} bytes, plain {plain_size} bytes"
+    );
+}
+
+#[test]
+fn test_select_notes_by_recipient() {
+    let mut conn = create_db();
+    for block_num in 1..=3 {
+        create_block(&mut conn, block_num);
+    }
+
+    let sender = AccountId::try_from(ACCOUNT_ID_OFF_CHAIN_SENDER).unwrap();
+    let target = AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN).unwrap();
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+    let mut rng = RpoRandomCoin::new(num_to_word(1));
+
+    // Every P2ID note gets a random serial number, hence its own recipient
+    let mut p2id_note = |amount: u64| {
+        let asset = FungibleAsset::new(faucet_id, amount).unwrap().into();
+        create_p2id_note(sender, target, vec![asset], NoteType::Public, ZERO, &mut rng).unwrap()
+    };
+    let note_a = p2id_note(10);
+    let note_b = p2id_note(20);
+    let recipient_a = note_a.recipient().digest();
+    let recipient_b = note_b.recipient().digest();
+    let unknown_recipient = p2id_note(30).recipient().digest();
+
+    // A second note sent to the first recipient, with different assets
+    let asset = FungibleAsset::new(faucet_id, 40).unwrap().into();
+    let note_a2 = Note::new(
+        NoteAssets::new(vec![asset]).unwrap(),
+        *note_a.metadata(),
+        note_a.recipient().clone(),
+    );
+
+    let note_record = |block_num, batch, note, details: Option<&Note>| {
+        let note_id = details.map(|note| note.id().into()).unwrap_or(num_to_rpo_digest(99));
+        NoteRecord {
+            block_num,
+            note_index: BlockNoteIndex::new(batch, note).unwrap(),
+            note_id,
+            metadata: *note_a.metadata(),
+            details: details.map(|note| note.to_bytes()),
+            merkle_path: MerklePath::new(vec![]),
+            created_by_tx: None,
+            nullifier: None,
+        }
+    };
+    let notes = [
+        note_record(1, 0, 0, Some(&note_a)),
+        note_record(1, 0, 1, Some(&note_b)),
+        note_record(1, 1, 0, Some(&note_a2)),
+        // Private notes are never discovered by recipient
+        note_record(2, 0, 0, None),
+        note_record(2, 0, 1, Some(&note_a)),
+        note_record(3, 0, 0, Some(&note_a2)),
+    ];
+
+    let transaction = conn.transaction().unwrap();
+    sql::insert_notes(&transaction, &notes).unwrap();
+    transaction.commit().unwrap();
+
+    let select = |conn: &mut Connection, recipients: &[RpoDigest], from_block, after, limit| {
+        sql::select_notes_by_recipient(conn, recipients, from_block, after, limit).unwrap()
+    };
+
+    // Only exact matches are returned, in chain order
+    let res = select(&mut conn, &[recipient_a], 0, None, 10);
+    assert_eq!(res, [notes[0].clone(), notes[2].clone(), notes[4].clone(), notes[5].clone()]);
+    let res = select(&mut conn, &[recipient_b], 0, None, 10);
+    assert_eq!(res, [notes[1].clone()]);
+    let res = select(&mut conn, &[recipient_a, recipient_b], 2, None, 10);
+    assert_eq!(res, [notes[4].clone(), notes[5].clone()]);
+    assert!(select(&mut conn, &[unknown_recipient], 0, None, 10).is_empty());
+    assert!(select(&mut conn, &[], 0, None, 10).is_empty());
+
+    // Pages continue after the last note of the previous page
+    let mut pages = Vec::new();
+    let mut after = None;
+    loop {
+        let page = select(&mut conn, &[recipient_a, recipient_b], 0, after, 2);
+        let Some(last) = page.last() else { break };
+        after = Some((last.block_num, last.note_index));
+        pages.push(page);
+    }
+    assert_eq!(
+        pages,
+        [
+            vec![notes[0].clone(), notes[1].clone()],
+            vec![notes[2].clone(), notes[4].clone()],
+            vec![notes[5].clone()],
+        ]
+    );
+
+    // Notes stored before the recipient column was added are backfilled
+    conn.execute("UPDATE notes SET recipient = NULL", []).unwrap();
+    assert!(select(&mut conn, &[recipient_a], 0, None, 10).is_empty());
+
+    let transaction = conn.transaction().unwrap();
+    assert_eq!(sql::backfill_note_recipients(&transaction).unwrap(), 5);
+    transaction.commit().unwrap();
+
+    let res = select(&mut conn, &[recipient_a], 0, None, 10);
+    assert_eq!(res, [notes[0].clone(), notes[2].clone(), notes[4].clone(), notes[5].clone()]);
+}
+
+#[test]
+fn test_select_note_tag_statistics() {
+    let mut conn = create_db();
+    for block_num in 1..=3 {
+        create_block(&mut conn, block_num);
+    }
+
+    let sender = AccountId::new_unchecked(Felt::new(ACCOUNT_ID_OFF_CHAIN_SENDER));
+    let note_record = |block_num, note_index, tag: u32| NoteRecord {
+        block_num,
+        note_index: BlockNoteIndex::new(0, note_index).unwrap(),
+        note_id: num_to_rpo_digest((u64::from(block_num) << 32) | note_index as u64),
+        metadata: NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            tag.into(),
+            NoteExecutionHint::none(),
+            ZERO,
+        )
+        .unwrap(),
+        details: None,
+        merkle_path: MerklePath::new(vec![]),
+        created_by_tx: None,
+        nullifier: None,
+    };
+    // The tag of the last note of block 2 is meant for network execution
+    let notes = [
+        note_record(1, 0, 0xc000_0001),
+        note_record(1, 1, 0xc000_0001),
+        note_record(1, 2, 0xc001_0002),
+        note_record(2, 0, 0xc001_0003),
+        note_record(2, 1, 0x8100_0000),
+        note_record(2, 2, 0x4000_0001),
+        note_record(3, 0, 0xc000_0001),
+    ];
+
+    let transaction = conn.transaction().unwrap();
+    sql::insert_notes(&transaction, &notes).unwrap();
+    transaction.commit().unwrap();
+
+    let select =
+        |conn: &mut Connection, from_block, to_block, prefix_len, exclude_network_notes| {
+            sql::select_note_tag_statistics(
+                conn,
+                from_block,
+                to_block,
+                prefix_len,
+                exclude_network_notes,
+            )
+            .unwrap()
+            .into_iter()
+            .map(|bucket| (bucket.prefix, bucket.num_notes))
+            .collect::<Vec<_>>()
+        };
+
+    assert_eq!(select(&mut conn, 1, 3, 8, false), [(0x40, 1), (0x81, 1), (0xc0, 5)]);
+    assert_eq!(select(&mut conn, 1, 3, 8, true), [(0x81, 1), (0xc0, 5)]);
+    assert_eq!(
+        select(&mut conn, 1, 2, 16, false),
+        [(0x4000, 1), (0x8100, 1), (0xc000, 2), (0xc001, 2)]
+    );
+    assert_eq!(select(&mut conn, 1, 1, 32, false), [(0xc000_0001, 2), (0xc001_0002, 1)]);
+    assert_eq!(select(&mut conn, 2, 2, 32, true), [(0x8100_0000, 1), (0xc001_0003, 1)]);
+
+    // Blocks without notes have no buckets
+    assert!(select(&mut conn, 4, 10, 8, false).is_empty());
+}
+
+#[test]
+fn test_block_note_filters() {
+    let mut conn = create_db();
+    for block_num in 1..=3 {
+        create_block(&mut conn, block_num);
+    }
+
+    let sender = AccountId::new_unchecked(Felt::new(ACCOUNT_ID_OFF_CHAIN_SENDER));
+    let note_record = |block_num, note_index, tag: u32| NoteRecord {
+        block_num,
+        note_index: BlockNoteIndex::new(0, note_index).unwrap(),
+        note_id: num_to_rpo_digest((u64::from(block_num) << 32) | note_index as u64),
+        metadata: NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            tag.into(),
+            NoteExecutionHint::none(),
+            ZERO,
+        )
+        .unwrap(),
+        details: None,
+        merkle_path: MerklePath::new(vec![]),
+        created_by_tx: None,
+        nullifier: None,
+    };
+
+    // The filters of the blocks stored without one are backfilled from their notes
+    let transaction = conn.transaction().unwrap();
+    sql::insert_notes(
+        &transaction,
+        &[note_record(1, 0, 0xc000_0001), note_record(1, 1, 0xc000_0002)],
+    )
+    .unwrap();
+    assert_eq!(sql::backfill_block_note_filters(&transaction).unwrap(), 3);
+    assert_eq!(sql::backfill_block_note_filters(&transaction).unwrap(), 0);
+    transaction.commit().unwrap();
+
+    create_block(&mut conn, 4);
+    let transaction = conn.transaction().unwrap();
+    let notes = [note_record(4, 0, 0xc000_0003)];
+    sql::insert_notes(&transaction, &notes).unwrap();
+    sql::insert_block_note_filter(&transaction, 4, &notes).unwrap();
+    transaction.commit().unwrap();
+
+    let filters = sql::select_block_note_filters(&mut conn, 1, 10).unwrap();
+    assert_eq!(filters.iter().map(|filter| filter.block_num).collect::<Vec<_>>(), [1, 2, 3, 4]);
+    assert!(filters[0].filter.may_contain(0xc000_0001));
+    assert!(filters[0].filter.may_contain(0xc000_0002));
+    assert!(filters[3].filter.may_contain(0xc000_0003));
+
+    // Blocks without notes have empty filters
+    assert_eq!(filters[1].filter, NoteTagFilter::default());
+    assert_eq!(filters[2].filter, NoteTagFilter::default());
+
+    assert_eq!(sql::select_block_note_filters(&mut conn, 2, 3).unwrap(), filters[1..3]);
+}
+
+#[test]
+fn test_chain_stats() {
+    let mut conn = create_db();
+    for block_num in 1..=2 {
+        create_block(&mut conn, block_num);
+    }
+
+    let account = AccountId::new_unchecked(Felt::ONE);
+    let new_account = AccountId::new_unchecked(Felt::new(2));
+
+    // The stats of the blocks stored without them are backfilled
+    let transaction = conn.transaction().unwrap();
+    let updates = [mock_block_account_update(account, 1)];
+    sql::upsert_accounts(&transaction, &updates, 1).unwrap();
+    sql::insert_transactions(&transaction, 1, &updates).unwrap();
+    sql::insert_nullifiers_for_block(&transaction, &[num_to_nullifier(1 << 48)], 1).unwrap();
+    assert_eq!(sql::backfill_block_stats(&transaction).unwrap(), 2);
+    assert_eq!(sql::backfill_block_stats(&transaction).unwrap(), 0);
+    transaction.commit().unwrap();
+
+    // Only the accounts not updated before are counted as new
+    create_block(&mut conn, 3);
+    let block_header = sql::select_block_header_by_block_num(&mut conn, Some(3)).unwrap().unwrap();
+    let transaction = conn.transaction().unwrap();
+    let updates =
+        [mock_block_account_update(account, 2), mock_block_account_update(new_account, 3)];
+    sql::insert_block_stats(&transaction, &block_header, 1, 0, &updates).unwrap();
+    transaction.commit().unwrap();
+
+    let stats = sql::select_chain_stats(&mut conn, 1, 10).unwrap().unwrap();
+    assert_eq!(
+        stats,
+        ChainStats {
+            from_block: 1,
+            to_block: 3,
+            num_transactions: 6,
+            num_notes: 1,
+            num_nullifiers: 1,
+            num_new_accounts: 2,
+            average_block_interval_ms: Some(0),
+        }
+    );
+
+    let stats = sql::select_chain_stats(&mut conn, 2, 3).unwrap().unwrap();
+    assert_eq!(stats.num_transactions, 4);
+    assert_eq!(stats.num_nullifiers, 0);
+    assert_eq!(stats.num_new_accounts, 1);
+
+    let stats = sql::select_chain_stats(&mut conn, 2, 2).unwrap().unwrap();
+    assert_eq!(stats.num_transactions, 0);
+    assert_eq!(stats.average_block_interval_ms, None);
+
+    assert_eq!(sql::select_chain_stats(&mut conn, 4, 10).unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_reopened_database_is_fully_migrated() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = StoreConfig {
+        database_filepath: dir.path().join("miden-store.sqlite3"),
+        genesis_filepath: dir.path().join("genesis.dat"),
+        blockstore_dir: dir.path().join("blocks"),
+        ..Default::default()
+    };
+    fs::write(&config.genesis_filepath, GenesisState::new(vec![], 1, 0).to_bytes()).unwrap();
+
+    // The migration hash recorded when creating the database must match the migrations on reopen
+    for _ in 0..2 {
+        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+        Db::setup(config.clone(), block_store).await.unwrap();
+    }
+
+    let conn = Connection::open(&config.database_filepath).unwrap();
+    let num_indexes: u32 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master \
+            WHERE type = 'index' AND name = 'idx_nullifiers_prefix_block_num'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(num_indexes, 1);
+}
+
+#[tokio::test]
+async fn test_dropped_query_is_interrupted() {
+    let pool = create_single_connection_pool();
+
+    let query = cancellation::interact(&pool, REQUEST_QUERY_TIMEOUT, "Slow query", slow_query);
+    // Dropping the query future, as done by tonic when the client goes away
+    assert!(tokio::time::timeout(Duration::from_millis(50), query).await.is_err());
+
+    assert_connection_released(&pool).await;
+}
+
+#[tokio::test]
+async fn test_query_timeout() {
+    let pool = create_single_connection_pool();
+
+    let result =
+        cancellation::interact(&pool, Duration::from_millis(50), "Slow query", slow_query).await;
+    assert!(matches!(result, Err(DatabaseError::QueryTimeout(_))), "{result:?}");
+
+    assert_connection_released(&pool).await;
+}
+
+#[tokio::test]
+async fn test_cancelled_query_stops_between_statements() {
+    let pool = create_single_connection_pool();
+
+    let query = cancellation::interact(
+        &pool,
+        REQUEST_QUERY_TIMEOUT,
+        "Sleeping query",
+        |conn, cancellation| -> Result<u64, DatabaseError> {
+            // Interruptions are ignored by SQLite while no statement is running
+            std::thread::sleep(Duration::from_millis(200));
+            cancellation.check()?;
+            slow_query(conn, cancellation)
+        },
+    );
+    assert!(tokio::time::timeout(Duration::from_millis(50), query).await.is_err());
+
+    assert_connection_released(&pool).await;
+}
+
 // UTILITIES
 // -------------------------------------------------------------------------------------------
+fn create_single_connection_pool() -> Pool {
+    SqliteConfig::new(":memory:")
+        .builder(Runtime::Tokio1)
+        .unwrap()
+        .max_size(1)
+        .build()
+        .unwrap()
+}
+
+/// Counts up to a billion, which takes way longer than any of the test timeouts.
+fn slow_query(conn: &mut Connection, _: &QueryCancellation) -> Result<u64, DatabaseError> {
+    let count = conn.query_row(
+        "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c WHERE x < 1000000000) \
+        SELECT count(*) FROM c",
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(count)
+}
+
+/// Checks that the only connection of the pool is promptly available for other queries.
+async fn assert_connection_released(pool: &Pool) {
+    let query = cancellation::interact(pool, REQUEST_QUERY_TIMEOUT, "Select one", |conn, _| {
+        conn.query_row("SELECT 1", [], |row| row.get::<_, u64>(0))
+            .map_err(DatabaseError::from)
+    });
+    let result = tokio::time::timeout(Duration::from_secs(1), query)
+        .await
+        .expect("connection should be released");
+
+    assert_eq!(result.unwrap(), 1);
+}
+
 fn num_to_rpo_digest(n: u64) -> RpoDigest {
     RpoDigest::new(num_to_word(n))
 }
@@ -977,7 +2031,10 @@ fn insert_transactions(conn: &mut Connection) -> usize {
     count
 }
 
-fn mock_account_code_and_storage(account_type: AccountType) -> (AccountCode, AccountStorage) {
+/// Returns the code and the storage of a mock account of the given type, with six value slots.
+pub(crate) fn mock_account_code_and_storage(
+    account_type: AccountType,
+) -> (AccountCode, AccountStorage) {
     let component_code = "\
     export.account_procedure_1
         push.1.2
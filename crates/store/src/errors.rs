@@ -1,13 +1,15 @@
-use std::io;
+use std::{io, time::Duration};
 
 use deadpool_sqlite::PoolError;
+use miden_node_proto::errors::ErrorChainHelper;
+use miden_node_utils::{block_versions::UnsupportedBlockVersion, notes::NoteValidationError};
 use miden_objects::{
     crypto::{
         hash::rpo::RpoDigest,
         merkle::{MerkleError, MmrError},
         utils::DeserializationError,
     },
-    notes::Nullifier,
+    notes::{NoteId, Nullifier},
     transaction::OutputNote,
     AccountDeltaError, AccountError, BlockError, BlockHeader, NoteError,
 };
@@ -16,7 +18,10 @@ use thiserror::Error;
 use tokio::sync::oneshot::error::RecvError;
 use tonic::Status;
 
-use crate::types::{AccountId, BlockNumber};
+use crate::{
+    types::{AccountId, BlockNumber},
+    COMPONENT,
+};
 
 // INTERNAL ERRORS
 // =================================================================================================
@@ -79,10 +84,16 @@ pub enum DatabaseError {
     AccountNotOnChain(AccountId),
     #[error("Block {0} not found in the database")]
     BlockNotFoundInDb(BlockNumber),
+    #[error("Data corrupted: {0}")]
+    DataCorrupted(String),
     #[error("SQLite pool interaction task failed: {0}")]
     InteractError(String),
     #[error("Invalid Felt: {0}")]
     InvalidFelt(String),
+    #[error("Query was cancelled")]
+    QueryCancelled,
+    #[error("Query timed out after {0:?}")]
+    QueryTimeout(Duration),
     #[error(
         "Unsupported database version. There is no migration chain from/to this version. \
         Remove all database files and try again."
@@ -98,14 +109,18 @@ impl From<DeserializationError> for DatabaseError {
 
 impl From<DatabaseError> for Status {
     fn from(err: DatabaseError) -> Self {
-        match err {
+        let status = match err {
             DatabaseError::AccountNotFoundInDb(_)
             | DatabaseError::AccountsNotFoundInDb(_)
             | DatabaseError::AccountNotOnChain(_)
             | DatabaseError::BlockNotFoundInDb(_) => Status::not_found(err.to_string()),
+            DatabaseError::QueryCancelled => Status::cancelled(err.to_string()),
+            DatabaseError::QueryTimeout(_) => Status::deadline_exceeded(err.to_string()),
 
             _ => Status::internal(err.to_string()),
-        }
+        };
+
+        status.with_error_source(COMPONENT, &err)
     }
 }
 
@@ -122,6 +137,34 @@ pub enum StateInitializationError {
     FailedToCreateAccountsTree(MerkleError),
 }
 
+#[derive(Debug, Error)]
+pub enum NoteDetailsPolicyError {
+    #[error("Invalid note script root {root}: {reason}")]
+    InvalidScriptRoot { root: String, reason: String },
+    #[error("Invalid note tag range: {from} is greater than {to}")]
+    InvalidTagRange { from: u32, to: u32 },
+}
+
+#[derive(Debug, Error)]
+pub enum WriteLimitsError {
+    #[error("Write limit {name} can't be lowered to {value}, below its default of {default}")]
+    BelowDefault {
+        name: &'static str,
+        value: usize,
+        default: usize,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum StoreModeError {
+    #[error("`{0}` prunes data, and can't be set in archive mode")]
+    PruningInArchiveMode(&'static str),
+    #[error("The store can't run in archive mode, it ran in pruned mode before")]
+    PreviouslyPruned,
+    #[error("The store can't run in archive mode, the data of the blocks below {0} was pruned")]
+    BlocksPruned(BlockNumber),
+}
+
 #[derive(Debug, Error)]
 pub enum DatabaseSetupError {
     #[error("I/O error: {0}")]
@@ -134,6 +177,8 @@ pub enum DatabaseSetupError {
     PoolBuildError(#[from] deadpool_sqlite::BuildError),
     #[error("SQLite migration error: {0}")]
     SqliteMigrationError(#[from] rusqlite_migration::Error),
+    #[error("Invalid store mode: {0}")]
+    StoreModeError(#[from] StoreModeError),
 }
 
 #[derive(Debug, Error)]
@@ -175,6 +220,11 @@ pub enum InvalidBlockError {
     DuplicatedNullifiers(Vec<Nullifier>),
     #[error("Invalid output note type: {0:?}")]
     InvalidOutputNoteType(Box<OutputNote>),
+    #[error("Invalid output note {note_id}: {source}")]
+    InvalidOutputNote {
+        note_id: NoteId,
+        source: NoteValidationError,
+    },
     #[error("Invalid tx hash: expected {expected}, but got {actual}")]
     InvalidTxHash { expected: RpoDigest, actual: RpoDigest },
     #[error("Received invalid account tree root")]
@@ -189,6 +239,38 @@ pub enum InvalidBlockError {
     NewBlockInvalidNullifierRoot,
     #[error("New block `prev_hash` must match the chain's tip")]
     NewBlockInvalidPrevHash,
+    #[error("New block timestamp {timestamp} is more than {max_drift_secs}s ahead of the store's clock {now}")]
+    NewBlockTimestampInFuture {
+        timestamp: u32,
+        now: u64,
+        max_drift_secs: u64,
+    },
+    #[error("New block timestamp {timestamp} must be greater than the chain tip's timestamp {prev_timestamp}")]
+    NewBlockTimestampNotIncreasing { timestamp: u32, prev_timestamp: u32 },
+    #[error("New block has an unsupported version: {0}")]
+    UnsupportedVersion(#[from] UnsupportedBlockVersion),
+    #[error("Block creates {count} notes, more than the limit of {limit}")]
+    TooManyNotes { count: usize, limit: usize },
+    #[error("Block has {count} nullifiers, more than the limit of {limit}")]
+    TooManyNullifiers { count: usize, limit: usize },
+    #[error("Block updates {count} accounts, more than the limit of {limit}")]
+    TooManyAccountUpdates { count: usize, limit: usize },
+    #[error("Details of note {note_id} are {size} bytes, more than the limit of {limit}")]
+    NoteDetailsTooLarge {
+        note_id: NoteId,
+        size: usize,
+        limit: usize,
+    },
+    #[error(
+        "Details of account {account_id:#018x} are {size} bytes, more than the limit of {limit}"
+    )]
+    AccountDetailsTooLarge {
+        account_id: AccountId,
+        size: usize,
+        limit: usize,
+    },
+    #[error("Merkle path of note {note_id} has depth {depth}, more than the limit of {limit}")]
+    NoteMerklePathTooDeep { note_id: NoteId, depth: u8, limit: u8 },
 }
 
 #[derive(Error, Debug)]
@@ -218,11 +300,13 @@ pub enum ApplyBlockError {
 
 impl From<ApplyBlockError> for Status {
     fn from(err: ApplyBlockError) -> Self {
-        match err {
+        let status = match err {
             ApplyBlockError::InvalidBlockError(_) => Status::invalid_argument(err.to_string()),
 
             _ => Status::internal(err.to_string()),
-        }
+        };
+
+        status.with_error_source(COMPONENT, &err)
     }
 }
 
@@ -234,6 +318,53 @@ pub enum GetBlockHeaderError {
     MmrError(#[from] MmrError),
 }
 
+#[derive(Error, Debug)]
+pub enum GetBlockError {
+    #[error("I/O error: {0}")]
+    IoError(#[from] io::Error),
+    #[error("Block {0} pruned, available from archive nodes")]
+    BlockPruned(BlockNumber),
+}
+
+impl From<GetBlockError> for Status {
+    fn from(err: GetBlockError) -> Self {
+        let status = match err {
+            GetBlockError::BlockPruned(_) => Status::failed_precondition(err.to_string()),
+
+            _ => Status::internal(err.to_string()),
+        };
+
+        status.with_error_source(COMPONENT, &err)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum GetAccountWitnessesError {
+    #[error("Block {block_num} is after the chain tip {chain_tip}")]
+    UnknownBlock {
+        block_num: BlockNumber,
+        chain_tip: BlockNumber,
+    },
+    #[error("Account tree of block {block_num} is not retained, the oldest retained is {oldest}")]
+    BlockNotRetained {
+        block_num: BlockNumber,
+        oldest: BlockNumber,
+    },
+}
+
+impl From<GetAccountWitnessesError> for Status {
+    fn from(err: GetAccountWitnessesError) -> Self {
+        let status = match err {
+            GetAccountWitnessesError::UnknownBlock { .. } => Status::not_found(err.to_string()),
+            GetAccountWitnessesError::BlockNotRetained { .. } => {
+                Status::failed_precondition(err.to_string())
+            },
+        };
+
+        status.with_error_source(COMPONENT, &err)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum GetBlockInputsError {
     #[error("Account error: {0}")]
@@ -269,6 +400,15 @@ pub enum StateSyncError {
     FailedToBuildMmrDelta(MmrError),
 }
 
+impl From<StateSyncError> for Status {
+    fn from(err: StateSyncError) -> Self {
+        match err {
+            StateSyncError::DatabaseError(err) => err.into(),
+            _ => Status::internal(err.to_string()).with_error_source(COMPONENT, &err),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum NoteSyncError {
     #[error("Database error: {0}")]
@@ -279,6 +419,15 @@ pub enum NoteSyncError {
     MmrError(#[from] MmrError),
 }
 
+impl From<NoteSyncError> for Status {
+    fn from(err: NoteSyncError) -> Self {
+        match err {
+            NoteSyncError::DatabaseError(err) => err.into(),
+            _ => Status::internal(err.to_string()).with_error_source(COMPONENT, &err),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum GetNoteInclusionProofError {
     #[error("Database error: {0}")]
@@ -286,3 +435,55 @@ pub enum GetNoteInclusionProofError {
     #[error("Mmr error: {0}")]
     MmrError(#[from] MmrError),
 }
+
+// CHAIN ARCHIVE ERRORS
+// =================================================================================================
+
+#[derive(Error, Debug)]
+pub enum ChainArchiveError {
+    // ERRORS WITH AUTOMATIC CONVERSIONS FROM NESTED ERROR TYPES
+    // ---------------------------------------------------------------------------------------------
+    #[error("I/O error: {0}")]
+    IoError(#[from] io::Error),
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] DatabaseError),
+    #[error("Database setup error: {0}")]
+    DatabaseSetupError(#[from] DatabaseSetupError),
+    #[error("State initialization error: {0}")]
+    StateInitializationError(#[from] StateInitializationError),
+    #[error("Invalid note details policy: {0}")]
+    NoteDetailsPolicyError(#[from] NoteDetailsPolicyError),
+    #[error("Invalid write limits: {0}")]
+    WriteLimitsError(#[from] WriteLimitsError),
+
+    // OTHER ERRORS
+    // ---------------------------------------------------------------------------------------------
+    #[error("Invalid manifest at line {line}: {reason}")]
+    InvalidManifest { line: usize, reason: String },
+    #[error("Genesis file of the archive doesn't match the store's")]
+    GenesisMismatch,
+    #[error("Block {0} of the store is not part of the archived chain")]
+    ChainMismatch(BlockNumber),
+    #[error("Block {0} is missing from the block store")]
+    MissingBlock(BlockNumber),
+    #[error("File of block {0} doesn't match its hash in the manifest")]
+    FileHashMismatch(BlockNumber),
+    #[error("Failed to deserialize block {block_num}: {source}")]
+    InvalidBlockFile {
+        block_num: BlockNumber,
+        source: DeserializationError,
+    },
+    #[error("Block {block_num} has commitment {actual}, expected {expected}")]
+    CommitmentMismatch {
+        block_num: BlockNumber,
+        expected: RpoDigest,
+        actual: RpoDigest,
+    },
+    #[error("Block {0} doesn't extend the previous block of the archive")]
+    BrokenParentLink(BlockNumber),
+    #[error("Failed to apply block {block_num}: {source}")]
+    ApplyBlockFailed {
+        block_num: BlockNumber,
+        source: ApplyBlockError,
+    },
+}
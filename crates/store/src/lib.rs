@@ -1,12 +1,24 @@
+use std::time::Duration;
+
+mod account_tree;
+mod account_updates;
+mod accounts;
+mod block_provenance;
+mod block_stats;
 mod blocks;
+pub mod chain_archive;
+mod chain_stats;
 pub mod config;
 pub mod db;
 pub mod errors;
 pub mod genesis;
+pub mod load_progress;
+pub mod note_details;
 mod nullifier_tree;
 pub mod server;
 pub mod state;
 pub mod types;
+pub mod write_limits;
 
 // CONSTANTS
 // =================================================================================================
@@ -14,3 +26,74 @@ pub const COMPONENT: &str = "miden-store";
 
 /// Number of sql statements that each connection will cache.
 const SQL_STATEMENT_CACHE_CAPACITY: usize = 32;
+
+/// Maximum size of a serialized account delta returned alongside an account proof. Larger deltas
+/// must be fetched through the account details instead.
+const MAX_ACCOUNT_PROOF_DELTA_SIZE: usize = 64 * 1024;
+
+/// How often the block store is pruned, if pruning is enabled.
+const BLOCK_PRUNING_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum number of recipients in a `GetNotesByRecipient` request.
+pub const MAX_NOTE_RECIPIENTS: usize = 1000;
+
+/// Maximum number of accounts in a `GetAccountCommitments` request.
+pub const MAX_ACCOUNT_COMMITMENTS: usize = 1000;
+
+/// Maximum number of accounts in a `GetAccountWitnesses` request.
+pub const MAX_ACCOUNT_WITNESSES: usize = 1000;
+
+/// Number of blocks below the chain tip whose account tree is retained, so that account witnesses
+/// can be requested against any of them.
+const ACCOUNT_TREE_REVISIONS: usize = 16;
+
+/// Maximum number of accounts in a `SubscribeAccountUpdates` request.
+pub const MAX_SUBSCRIBED_ACCOUNTS: usize = 100;
+
+/// Maximum number of blocks whose account updates are buffered for the slowest subscription, which
+/// is ended once it falls further behind.
+const ACCOUNT_UPDATES_CAPACITY: usize = 64;
+
+/// Maximum number of accounts in an `ExportAccountDeltas` request.
+pub const MAX_EXPORTED_ACCOUNTS: usize = 1000;
+
+/// Maximum number of account deltas loaded from the database at once by `ExportAccountDeltas`.
+const ACCOUNT_DELTAS_EXPORT_CHUNK_SIZE: usize = 500;
+
+/// Time during which the later pages of the block inputs can be fetched after the first one.
+const BLOCK_INPUTS_SNAPSHOT_LIFETIME: Duration = Duration::from_secs(30);
+
+/// Maximum number of transactions in a `FilterKnownTransactions` request.
+pub const MAX_KNOWN_TRANSACTIONS: usize = 1000;
+
+/// Maximum number of blocks in a `GetBlockProductionStats` request.
+pub const MAX_BLOCK_PRODUCTION_STATS_RANGE: u32 = 1000;
+
+/// Maximum length of the block producer identifiers recorded in the block provenance, longer
+/// identifiers are truncated.
+const MAX_PRODUCER_ID_LEN: usize = 64;
+
+/// Maximum number of blocks in a `GetBlockHeadersRange` request.
+pub const MAX_BLOCK_HEADERS_RANGE: u32 = 1000;
+
+/// Maximum number of blocks in a `GetBlockNoteFilters` request.
+pub const MAX_BLOCK_NOTE_FILTERS_RANGE: u32 = 1000;
+
+/// Maximum number of blocks in a `GetNoteTagStatistics` request.
+pub const MAX_NOTE_TAG_STATISTICS_RANGE: u32 = 10_000;
+
+/// Tag prefix lengths, in bits, supported by `GetNoteTagStatistics`.
+pub const NOTE_TAG_STATISTICS_PREFIX_LENS: [u32; 3] = [8, 16, 32];
+
+/// Maximum number of notes returned in a page of `GetNotesByRecipient`.
+const NOTES_BY_RECIPIENT_PAGE_SIZE: usize = 100;
+
+/// Maximum number of nullifiers returned in a page of `CheckNullifiersByPrefix`.
+const NULLIFIERS_BY_PREFIX_PAGE_SIZE: usize = 1000;
+
+/// Maximum number of assets returned in a page of `GetAccountVaultAssets`.
+const VAULT_ASSETS_PAGE_SIZE: usize = 1000;
+
+/// Number of most recent block headers kept in memory, to authenticate notes without reading the
+/// headers of their blocks from the database.
+const RECENT_BLOCK_HEADERS: usize = 256;
@@ -0,0 +1,257 @@
+//! Progress of the loading of the in-memory state at startup.
+//!
+//! Loading the nullifier tree, the chain MMR and the account tree reads every row of their tables,
+//! which takes minutes on large stores. The rows are read in chunks, and the progress of every
+//! phase is reported periodically, so that operators can tell a slow startup from a stuck one.
+
+use std::{
+    fmt::{Display, Formatter},
+    time::{Duration, Instant},
+};
+
+use tracing::info;
+
+use crate::COMPONENT;
+
+/// Default number of rows read from the database at once while loading the state.
+pub const DEFAULT_LOAD_CHUNK_SIZE: usize = 10_000;
+
+/// Default interval between two progress reports of a loading phase.
+pub const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Default number of rows loaded between two progress reports of a loading phase.
+pub const DEFAULT_PROGRESS_ROWS: u64 = 1_000_000;
+
+// LOAD SETTINGS
+// ================================================================================================
+
+/// Settings of the loading of the in-memory state.
+///
+/// A progress event is reported whenever either `progress_interval` elapsed or `progress_rows`
+/// rows were loaded since the previous event of the phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadSettings {
+    /// Number of rows read from the database at once.
+    pub chunk_size: usize,
+    pub progress_interval: Duration,
+    pub progress_rows: u64,
+}
+
+impl Default for LoadSettings {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_LOAD_CHUNK_SIZE,
+            progress_interval: DEFAULT_PROGRESS_INTERVAL,
+            progress_rows: DEFAULT_PROGRESS_ROWS,
+        }
+    }
+}
+
+// LOAD PHASE
+// ================================================================================================
+
+/// Phases of the loading of the in-memory state, in the order they are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LoadPhase {
+    NullifierTree,
+    ChainMmr,
+    AccountTree,
+}
+
+impl Display for LoadPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LoadPhase::NullifierTree => "nullifier tree",
+            LoadPhase::ChainMmr => "chain MMR",
+            LoadPhase::AccountTree => "account tree",
+        })
+    }
+}
+
+// LOAD PROGRESS EVENT
+// ================================================================================================
+
+/// Progress of a loading phase.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadProgressEvent {
+    pub phase: LoadPhase,
+    /// Number of rows loaded so far.
+    pub processed: u64,
+    /// Number of rows counted before the phase started.
+    pub total: u64,
+    /// Average number of rows loaded per second since the phase started.
+    pub rows_per_sec: f64,
+    /// Estimated time until the phase completes, [None] until the rate is known.
+    pub eta: Option<Duration>,
+}
+
+impl LoadProgressEvent {
+    /// Returns the completion of the phase, in percent.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+
+        (self.processed as f64 * 100.0 / self.total as f64).min(100.0)
+    }
+}
+
+// PROGRESS TRACKER
+// ================================================================================================
+
+/// Tracks the rows loaded during a phase, and reports the progress to the log and to a callback.
+pub(crate) struct ProgressTracker<'a, F> {
+    phase: LoadPhase,
+    total: u64,
+    processed: u64,
+    settings: LoadSettings,
+    started_at: Instant,
+    last_report: (Instant, u64),
+    on_progress: &'a mut F,
+}
+
+impl<'a, F: FnMut(LoadProgressEvent)> ProgressTracker<'a, F> {
+    /// Starts tracking a phase of `total` rows. The start of the phase is reported right away.
+    pub fn start(
+        phase: LoadPhase,
+        total: u64,
+        settings: LoadSettings,
+        on_progress: &'a mut F,
+    ) -> Self {
+        let now = Instant::now();
+        let mut tracker = Self {
+            phase,
+            total,
+            processed: 0,
+            settings,
+            started_at: now,
+            last_report: (now, 0),
+            on_progress,
+        };
+        tracker.report();
+
+        tracker
+    }
+
+    /// Records `rows` more loaded rows, reporting the progress if an event is due.
+    pub fn advance(&mut self, rows: usize) {
+        self.processed += rows as u64;
+
+        let (reported_at, reported_rows) = self.last_report;
+        if reported_at.elapsed() >= self.settings.progress_interval
+            || self.processed - reported_rows >= self.settings.progress_rows
+        {
+            self.report();
+        }
+    }
+
+    /// Reports the completion of the phase.
+    ///
+    /// Rows are only written once the state is loaded, so the count done at the start of the
+    /// phase is exact. The total is still adjusted to the loaded rows in case it isn't, so that
+    /// the last event of a phase is always at 100%.
+    pub fn finish(mut self) {
+        self.total = self.processed;
+        self.report();
+    }
+
+    fn report(&mut self) {
+        let elapsed = self.started_at.elapsed();
+        let rows_per_sec = if elapsed.is_zero() {
+            0.0
+        } else {
+            self.processed as f64 / elapsed.as_secs_f64()
+        };
+        let eta = (rows_per_sec > 0.0).then(|| {
+            Duration::from_secs_f64(self.total.saturating_sub(self.processed) as f64 / rows_per_sec)
+        });
+
+        let event = LoadProgressEvent {
+            phase: self.phase,
+            processed: self.processed,
+            total: self.total,
+            rows_per_sec,
+            eta,
+        };
+        info!(
+            target: COMPONENT,
+            phase = %event.phase,
+            processed = event.processed,
+            total = event.total,
+            percent = format!("{:.1}", event.percent()),
+            rows_per_sec = format!("{rows_per_sec:.0}"),
+            eta_secs = eta.map(|eta| eta.as_secs()),
+            "Loading state"
+        );
+        (self.on_progress)(event);
+
+        self.last_report = (Instant::now(), self.processed);
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_is_reported_every_n_rows() {
+        let settings = LoadSettings {
+            chunk_size: 10,
+            progress_interval: Duration::from_secs(3600),
+            progress_rows: 25,
+        };
+
+        let mut events = Vec::new();
+        let mut on_progress = |event: LoadProgressEvent| events.push(event);
+        let mut tracker =
+            ProgressTracker::start(LoadPhase::ChainMmr, 100, settings, &mut on_progress);
+        for _ in 0..10 {
+            tracker.advance(10);
+        }
+        tracker.finish();
+
+        // The start, every 30 rows, and the completion are reported
+        assert_eq!(
+            events.iter().map(|event| event.processed).collect::<Vec<_>>(),
+            [0, 30, 60, 90, 100]
+        );
+        assert_eq!(events[0].percent(), 0.0);
+        assert_eq!(events[4].percent(), 100.0);
+        assert!(events.iter().all(|event| event.phase == LoadPhase::ChainMmr));
+    }
+
+    #[test]
+    fn last_event_is_complete() {
+        let mut events = Vec::new();
+        let mut on_progress = |event: LoadProgressEvent| events.push(event);
+
+        // Empty phases complete right away
+        ProgressTracker::start(
+            LoadPhase::AccountTree,
+            0,
+            LoadSettings::default(),
+            &mut on_progress,
+        )
+        .finish();
+
+        // Fewer rows than counted were loaded
+        let mut tracker = ProgressTracker::start(
+            LoadPhase::NullifierTree,
+            10,
+            LoadSettings::default(),
+            &mut on_progress,
+        );
+        tracker.advance(7);
+        tracker.finish();
+
+        assert_eq!(events.len(), 4);
+        assert_eq!((events[1].processed, events[1].total), (0, 0));
+        assert_eq!((events[3].processed, events[3].total), (7, 7));
+        assert_eq!(events[1].percent(), 100.0);
+        assert_eq!(events[3].percent(), 100.0);
+        assert_eq!(events[3].eta.unwrap_or_default(), Duration::ZERO);
+    }
+}
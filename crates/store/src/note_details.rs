@@ -0,0 +1,193 @@
+//! Application of the data availability policy to the notes of the applied blocks.
+
+use std::{collections::BTreeSet, ops::RangeInclusive};
+
+use miden_node_proto::generated::note::{
+    NoteDetailsAvailability, NoteDetailsPolicy as NoteDetailsPolicyProto,
+    NoteTagRange as NoteTagRangeProto,
+};
+use miden_objects::{crypto::hash::rpo::RpoDigest, notes::Note};
+
+use crate::{config::NoteDetailsPolicy, errors::NoteDetailsPolicyError};
+
+/// Decides which details of the public notes of the applied blocks are stored, as configured by
+/// a [NoteDetailsPolicy].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum NoteDetailsFilter {
+    #[default]
+    All,
+    Nothing,
+    Selective {
+        script_roots: BTreeSet<RpoDigest>,
+        tags: Vec<RangeInclusive<u32>>,
+    },
+}
+
+impl NoteDetailsFilter {
+    /// Returns whether the details of the `note` are stored.
+    pub fn retains(&self, note: &Note) -> bool {
+        match self {
+            Self::All => true,
+            Self::Nothing => false,
+            Self::Selective { script_roots, tags } => {
+                let tag = note.metadata().tag().inner();
+                script_roots.contains(&note.script().hash())
+                    || tags.iter().any(|range| range.contains(&tag))
+            },
+        }
+    }
+}
+
+impl TryFrom<&NoteDetailsPolicy> for NoteDetailsFilter {
+    type Error = NoteDetailsPolicyError;
+
+    fn try_from(policy: &NoteDetailsPolicy) -> Result<Self, Self::Error> {
+        match policy {
+            NoteDetailsPolicy::Full => Ok(Self::All),
+            NoteDetailsPolicy::MetadataOnly => Ok(Self::Nothing),
+            NoteDetailsPolicy::Selective { script_roots, tags } => {
+                let script_roots = script_roots
+                    .iter()
+                    .map(|root| {
+                        RpoDigest::try_from(root.as_str()).map_err(|err| {
+                            NoteDetailsPolicyError::InvalidScriptRoot {
+                                root: root.clone(),
+                                reason: err.to_string(),
+                            }
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                let tags = tags
+                    .iter()
+                    .map(|range| {
+                        if range.from > range.to {
+                            return Err(NoteDetailsPolicyError::InvalidTagRange {
+                                from: range.from,
+                                to: range.to,
+                            });
+                        }
+                        Ok(range.from..=range.to)
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Self::Selective { script_roots, tags })
+            },
+        }
+    }
+}
+
+impl From<&NoteDetailsFilter> for NoteDetailsAvailability {
+    fn from(filter: &NoteDetailsFilter) -> Self {
+        match filter {
+            NoteDetailsFilter::All => Self {
+                policy: NoteDetailsPolicyProto::Full.into(),
+                ..Default::default()
+            },
+            NoteDetailsFilter::Nothing => Self {
+                policy: NoteDetailsPolicyProto::MetadataOnly.into(),
+                ..Default::default()
+            },
+            NoteDetailsFilter::Selective { script_roots, tags } => Self {
+                policy: NoteDetailsPolicyProto::Selective.into(),
+                script_roots: script_roots.iter().map(Into::into).collect(),
+                tags: tags
+                    .iter()
+                    .map(|range| NoteTagRangeProto { from: *range.start(), to: *range.end() })
+                    .collect(),
+            },
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_lib::notes::create_p2id_note;
+    use miden_objects::{
+        accounts::{
+            account_id::testing::{
+                ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_OFF_CHAIN_SENDER,
+                ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN,
+            },
+            AccountId,
+        },
+        assets::FungibleAsset,
+        crypto::rand::RpoRandomCoin,
+        notes::{NoteExecutionHint, NoteMetadata, NoteTag, NoteType},
+        Felt, ZERO,
+    };
+
+    use super::*;
+    use crate::config::NoteTagRange;
+
+    /// Returns a public P2ID note with the given tag.
+    fn note_with_tag(tag: u32) -> Note {
+        let sender = AccountId::try_from(ACCOUNT_ID_OFF_CHAIN_SENDER).unwrap();
+        let target =
+            AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset = FungibleAsset::new(faucet_id, 10).unwrap().into();
+        let mut rng = RpoRandomCoin::new([ZERO, ZERO, ZERO, Felt::new(tag.into())]);
+        let note = create_p2id_note(sender, target, vec![asset], NoteType::Public, ZERO, &mut rng)
+            .unwrap();
+
+        let metadata = NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            NoteTag::from(tag),
+            NoteExecutionHint::always(),
+            ZERO,
+        )
+        .unwrap();
+        Note::new(note.assets().clone(), metadata, note.recipient().clone())
+    }
+
+    #[test]
+    fn policies_select_retained_notes() {
+        let notes = [note_with_tag(5), note_with_tag(50), note_with_tag(500)];
+        let retained = |filter: &NoteDetailsFilter| {
+            notes.iter().map(|note| filter.retains(note)).collect::<Vec<_>>()
+        };
+
+        let filter = NoteDetailsFilter::try_from(&NoteDetailsPolicy::Full).unwrap();
+        assert_eq!(retained(&filter), [true, true, true]);
+
+        let filter = NoteDetailsFilter::try_from(&NoteDetailsPolicy::MetadataOnly).unwrap();
+        assert_eq!(retained(&filter), [false, false, false]);
+
+        let filter = NoteDetailsFilter::try_from(&NoteDetailsPolicy::Selective {
+            script_roots: vec![],
+            tags: vec![NoteTagRange { from: 0, to: 9 }, NoteTagRange { from: 100, to: 500 }],
+        })
+        .unwrap();
+        assert_eq!(retained(&filter), [true, false, true]);
+
+        // All the notes are P2ID notes, hence share their script
+        let filter = NoteDetailsFilter::try_from(&NoteDetailsPolicy::Selective {
+            script_roots: vec![notes[0].script().hash().to_hex()],
+            tags: vec![],
+        })
+        .unwrap();
+        assert_eq!(retained(&filter), [true, true, true]);
+    }
+
+    #[test]
+    fn invalid_policies_are_rejected() {
+        let err = NoteDetailsFilter::try_from(&NoteDetailsPolicy::Selective {
+            script_roots: vec!["0x1234".to_string()],
+            tags: vec![],
+        })
+        .unwrap_err();
+        assert!(matches!(err, NoteDetailsPolicyError::InvalidScriptRoot { .. }));
+
+        let err = NoteDetailsFilter::try_from(&NoteDetailsPolicy::Selective {
+            script_roots: vec![],
+            tags: vec![NoteTagRange { from: 10, to: 9 }],
+        })
+        .unwrap_err();
+        assert!(matches!(err, NoteDetailsPolicyError::InvalidTagRange { from: 10, to: 9 }));
+    }
+}
@@ -1,29 +1,52 @@
-use std::{collections::BTreeSet, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use miden_node_proto::{
     convert,
-    domain::notes::NoteAuthenticationInfo,
-    errors::ConversionError,
+    domain::{accounts::AccountProofBundle, notes::NoteAuthenticationInfo},
+    errors::{ConversionError, ErrorChainHelper},
     generated::{
         self,
-        account::AccountSummary,
-        note::NoteAuthenticationInfo as NoteAuthenticationInfoProto,
+        account::AccountDeltaCursor,
+        note::{
+            NoteAuthenticationInfo as NoteAuthenticationInfoProto, NotePosition, NullifierPosition,
+        },
         requests::{
-            ApplyBlockRequest, CheckNullifiersByPrefixRequest, CheckNullifiersRequest,
-            GetAccountDetailsRequest, GetAccountProofsRequest, GetAccountStateDeltaRequest,
-            GetBlockByNumberRequest, GetBlockHeaderByNumberRequest, GetBlockInputsRequest,
-            GetNoteAuthenticationInfoRequest, GetNotesByIdRequest, GetTransactionInputsRequest,
-            ListAccountsRequest, ListNotesRequest, ListNullifiersRequest, SyncNoteRequest,
-            SyncStateRequest,
+            ApplyBlockRequest, CheckNotesConsumedRequest, CheckNullifiersByPrefixRequest,
+            CheckNullifiersRequest, ExportAccountDeltasRequest, FilterKnownTransactionsRequest,
+            GetAccountCommitmentsRequest, GetAccountDetailsRequest, GetAccountProofsRequest,
+            GetAccountStateDeltaRequest, GetAccountStorageHeaderRequest,
+            GetAccountVaultAssetsRequest, GetAccountWitnessesRequest, GetBlockByNumberRequest,
+            GetBlockHeaderByCommitmentRequest, GetBlockHeaderByNumberRequest,
+            GetBlockHeadersRangeRequest, GetBlockInputsRequest, GetBlockNoteFiltersRequest,
+            GetBlockProductionStatsRequest, GetChainStatsRequest, GetDataAvailabilityRequest,
+            GetDatabasePoolStatsRequest, GetNoteAuthenticationInfoRequest,
+            GetNoteTagStatisticsRequest, GetNotesByIdRequest, GetNotesByRecipientRequest,
+            GetSupportedBlockVersionsRequest, GetTransactionInputsRequest, ListAccountsRequest,
+            ListNotesRequest, ListNullifiersRequest, SubscribeAccountUpdatesRequest,
+            SyncAccountCompactRequest, SyncNoteRequest, SyncStateRequest,
         },
         responses::{
-            AccountTransactionInputRecord, ApplyBlockResponse, CheckNullifiersByPrefixResponse,
-            CheckNullifiersResponse, GetAccountDetailsResponse, GetAccountProofsResponse,
-            GetAccountStateDeltaResponse, GetBlockByNumberResponse, GetBlockHeaderByNumberResponse,
-            GetBlockInputsResponse, GetNoteAuthenticationInfoResponse, GetNotesByIdResponse,
-            GetTransactionInputsResponse, ListAccountsResponse, ListNotesResponse,
-            ListNullifiersResponse, NullifierTransactionInputRecord, NullifierUpdate,
-            SyncNoteResponse, SyncStateResponse,
+            subscribe_account_updates_response::Update as AccountUpdate, AccountBlockInputRecord,
+            AccountBlockUpdate, AccountBlockUpdates, AccountProofsResponse,
+            AccountTransactionInputRecord, ApplyBlockResponse, CheckNotesConsumedResponse,
+            CheckNullifiersByPrefixResponse, CheckNullifiersResponse, ExportAccountDeltasResponse,
+            FilterKnownTransactionsResponse, GetAccountCommitmentsResponse,
+            GetAccountDetailsResponse, GetAccountProofsResponse, GetAccountStateDeltaResponse,
+            GetAccountStorageHeaderResponse, GetAccountVaultAssetsResponse,
+            GetAccountWitnessesResponse, GetBlockByNumberResponse,
+            GetBlockHeaderByCommitmentResponse, GetBlockHeaderByNumberResponse,
+            GetBlockHeadersRangeResponse, GetBlockInputsResponse, GetBlockNoteFiltersResponse,
+            GetBlockProductionStatsResponse, GetChainStatsResponse, GetDataAvailabilityResponse,
+            GetDatabasePoolStatsResponse, GetNoteAuthenticationInfoResponse,
+            GetNoteTagStatisticsResponse, GetNotesByIdResponse, GetNotesByRecipientResponse,
+            GetSupportedBlockVersionsResponse, GetTransactionInputsResponse, ListAccountsResponse,
+            ListNotesResponse, ListNullifiersResponse, NullifierTransactionInputRecord,
+            NullifierUpdate, StoreMode as StoreModePb, SubscribeAccountUpdatesResponse,
+            SyncAccountCompactResponse, SyncNoteResponse, SyncStateResponse,
         },
         smt::SmtLeafEntry,
         store::api_server,
@@ -32,26 +55,67 @@ use miden_node_proto::{
     try_convert,
 };
 use miden_objects::{
-    block::Block,
+    accounts::delta::AccountUpdateDetails,
+    block::{Block, BlockAccountUpdate, BlockNoteIndex},
     crypto::hash::rpo::RpoDigest,
     notes::{NoteId, Nullifier},
+    transaction::TransactionId,
     utils::{Deserializable, Serializable},
-    Felt, ZERO,
+    Felt, MAX_OUTPUT_NOTES_PER_BATCH, ZERO,
 };
+use prost::Message;
+use tokio::{sync::mpsc, time::Instant};
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
-use tracing::{debug, info, instrument};
-
-use crate::{state::State, types::AccountId, COMPONENT};
+use tracing::{debug, info, instrument, warn, Instrument};
+
+use super::{
+    block_inputs::BlockInputsSnapshots,
+    response_size::truncate_to_size,
+    sharding::ShardKeys,
+    sync_params::{
+        check_nullifier_prefixes, normalize_sync_note_request, normalize_sync_state_request,
+    },
+};
+use crate::{
+    account_updates::{AccountSubscriptionError, AccountUpdatesSubscription},
+    block_provenance::BlockProvenance,
+    block_stats::{BlockProductionStats, StageDurationPercentiles},
+    config::ShardRange,
+    errors::ApplyBlockError,
+    state::State,
+    types::{AccountId, BlockNumber},
+    ACCOUNT_DELTAS_EXPORT_CHUNK_SIZE, BLOCK_INPUTS_SNAPSHOT_LIFETIME, COMPONENT,
+    MAX_ACCOUNT_COMMITMENTS, MAX_ACCOUNT_WITNESSES, MAX_BLOCK_HEADERS_RANGE,
+    MAX_BLOCK_NOTE_FILTERS_RANGE, MAX_BLOCK_PRODUCTION_STATS_RANGE, MAX_EXPORTED_ACCOUNTS,
+    MAX_KNOWN_TRANSACTIONS, MAX_NOTE_RECIPIENTS, MAX_NOTE_TAG_STATISTICS_RANGE,
+    MAX_SUBSCRIBED_ACCOUNTS, NOTES_BY_RECIPIENT_PAGE_SIZE, NOTE_TAG_STATISTICS_PREFIX_LENS,
+    NULLIFIERS_BY_PREFIX_PAGE_SIZE, VAULT_ASSETS_PAGE_SIZE,
+};
 
 // STORE API
 // ================================================================================================
 
 pub struct StoreApi {
     pub(super) state: Arc<State>,
+    /// Maximum size of the list responses, see [truncate_to_size].
+    pub(super) max_response_size: usize,
+    /// Shard keys of the accounts served, see [super::sharding].
+    pub(super) shard_range: ShardRange,
+    /// Number of most recent blocks whose data is retained, reported by `get_data_availability`.
+    pub(super) block_retention_depth: Option<u32>,
+    /// Number of account deltas per second streamed by every `export_account_deltas` request.
+    pub(super) export_rows_per_second: u32,
+    /// Block inputs whose later pages were not fetched yet, see [super::block_inputs].
+    pub(super) block_inputs_snapshots: BlockInputsSnapshots,
 }
 
 #[tonic::async_trait]
 impl api_server::Api for StoreApi {
+    type ExportAccountDeltasStream = ReceiverStream<Result<ExportAccountDeltasResponse, Status>>;
+    type SubscribeAccountUpdatesStream =
+        ReceiverStream<Result<SubscribeAccountUpdatesResponse, Status>>;
+
     // CLIENT ENDPOINTS
     // --------------------------------------------------------------------------------------------
 
@@ -73,7 +137,7 @@ impl api_server::Api for StoreApi {
         let request = request.into_inner();
 
         let block_num = request.block_num;
-        let (block_header, mmr_proof) = self
+        let (anchor, block_header, mmr_proof) = self
             .state
             .get_block_header(block_num, request.include_mmr_proof.unwrap_or(false))
             .await
@@ -83,6 +147,113 @@ impl api_server::Api for StoreApi {
             block_header: block_header.map(Into::into),
             chain_length: mmr_proof.as_ref().map(|p| p.forest as u32),
             mmr_path: mmr_proof.map(|p| Into::into(&p.merkle_path)),
+            anchor: Some(anchor),
+        }))
+    }
+
+    /// Returns the consecutive block headers of a range, and optionally the MMR delta to catch up
+    /// with them.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_block_headers_range",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_block_headers_range(
+        &self,
+        request: Request<GetBlockHeadersRangeRequest>,
+    ) -> Result<Response<GetBlockHeadersRangeResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let GetBlockHeadersRangeRequest { from_block, to_block, include_mmr_delta } = request;
+        validate_block_range(from_block, to_block, MAX_BLOCK_HEADERS_RANGE)?;
+
+        let (anchor, block_headers, mmr_delta) = self
+            .state
+            .get_block_headers_range(from_block, to_block, include_mmr_delta)
+            .await
+            .map_err(internal_error)?;
+
+        Ok(Response::new(GetBlockHeadersRangeResponse {
+            block_headers: block_headers.into_iter().map(Into::into).collect(),
+            mmr_delta: mmr_delta.map(Into::into),
+            anchor: Some(anchor),
+        }))
+    }
+
+    /// Returns the block header with the specified commitment, together with its block number.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_block_header_by_commitment",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_block_header_by_commitment(
+        &self,
+        request: Request<GetBlockHeaderByCommitmentRequest>,
+    ) -> Result<Response<GetBlockHeaderByCommitmentResponse>, Status> {
+        info!(target: COMPONENT, ?request);
+        let request = request.into_inner();
+
+        let block_commitment: RpoDigest = request
+            .block_commitment
+            .ok_or(invalid_argument("`block_commitment` missing"))?
+            .try_into()
+            .map_err(|_| invalid_argument("Digest field is not in the modulus range"))?;
+        let (anchor, block_header, mmr_proof) = self
+            .state
+            .get_block_header_by_commitment(
+                block_commitment,
+                request.include_mmr_proof.unwrap_or(false),
+            )
+            .await
+            .map_err(internal_error)?;
+        let block_header = block_header.ok_or_else(|| {
+            Status::not_found(format!(
+                "Block with commitment {} not found",
+                block_commitment.to_hex()
+            ))
+        })?;
+
+        Ok(Response::new(GetBlockHeaderByCommitmentResponse {
+            block_num: block_header.block_num(),
+            block_header: Some(block_header.into()),
+            chain_length: mmr_proof.as_ref().map(|p| p.forest as u32),
+            mmr_path: mmr_proof.map(|p| Into::into(&p.merkle_path)),
+            anchor: Some(anchor),
+        }))
+    }
+
+    /// Returns whether the specified notes have been consumed, and by which block.
+    ///
+    /// Notes unknown to the store are left out of the response. The consumption of private notes is
+    /// unknown, as their nullifier is not known to the store.
+    #[instrument(
+        target = "miden-store",
+        name = "store:check_notes_consumed",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn check_notes_consumed(
+        &self,
+        request: Request<CheckNotesConsumedRequest>,
+    ) -> Result<Response<CheckNotesConsumedResponse>, Status> {
+        let request = request.into_inner();
+
+        let note_ids: Vec<RpoDigest> = try_convert(request.note_ids)
+            .map_err(|err| Status::invalid_argument(format!("Invalid NoteId: {}", err)))?;
+        let note_ids: Vec<NoteId> = note_ids.into_iter().map(From::from).collect();
+
+        let (anchor, notes) = self.state.anchored(self.state.check_notes_consumed(note_ids)).await;
+
+        Ok(Response::new(CheckNotesConsumedResponse {
+            notes: convert(notes?),
+            anchor: Some(anchor),
         }))
     }
 
@@ -106,14 +277,19 @@ impl api_server::Api for StoreApi {
         let nullifiers = validate_nullifiers(&request.nullifiers)?;
 
         // Query the state for the request's nullifiers
-        let proofs = self.state.check_nullifiers(&nullifiers).await;
+        let (anchor, proofs) = self.state.check_nullifiers(&nullifiers).await;
 
-        Ok(Response::new(CheckNullifiersResponse { proofs: convert(proofs) }))
+        Ok(Response::new(CheckNullifiersResponse {
+            proofs: convert(proofs),
+            anchor: Some(anchor),
+        }))
     }
 
-    /// Returns nullifiers that match the specified prefixes and have been consumed.
+    /// Returns a page of the nullifiers that match the specified prefixes and have been consumed.
     ///
-    /// Currently the only supported prefix length is 16 bits.
+    /// Nullifiers are ordered by block number and then by nullifier. If the page is full,
+    /// `next_page` is set to the position of its last nullifier, and the following nullifiers are
+    /// fetched by passing it back as `page`. Currently the only supported prefix length is 16 bits.
     #[instrument(
         target = "miden-store",
         name = "store:check_nullifiers_by_prefix",
@@ -127,14 +303,41 @@ impl api_server::Api for StoreApi {
     ) -> Result<Response<CheckNullifiersByPrefixResponse>, Status> {
         let request = request.into_inner();
 
-        if request.prefix_len != 16 {
-            return Err(Status::invalid_argument("Only 16-bit prefixes are supported"));
-        }
+        check_nullifier_prefixes(request.prefix_len, &request.nullifiers)?;
+
+        let after = request
+            .page
+            .map(|page| {
+                let nullifier = page
+                    .nullifier
+                    .ok_or_else(|| invalid_argument("Page has no nullifier"))?
+                    .try_into()
+                    .map_err(|err| Status::invalid_argument(format!("Invalid page: {err}")))?;
+                Ok::<_, Status>((page.block_num, nullifier))
+            })
+            .transpose()?;
 
-        let nullifiers = self
+        let (anchor, nullifiers) = self
             .state
-            .check_nullifiers_by_prefix(request.prefix_len, request.nullifiers)
-            .await?
+            .anchored(self.state.check_nullifiers_by_prefix(
+                request.prefix_len,
+                request.nullifiers,
+                after,
+                NULLIFIERS_BY_PREFIX_PAGE_SIZE,
+            ))
+            .await;
+        let nullifiers = nullifiers?;
+
+        // A full page may be followed by more nullifiers
+        let next_page =
+            nullifiers
+                .get(NULLIFIERS_BY_PREFIX_PAGE_SIZE - 1)
+                .map(|info| NullifierPosition {
+                    block_num: info.block_num,
+                    nullifier: Some(info.nullifier.into()),
+                });
+
+        let nullifiers = nullifiers
             .into_iter()
             .map(|nullifier_info| NullifierUpdate {
                 nullifier: Some(nullifier_info.nullifier.into()),
@@ -142,7 +345,11 @@ impl api_server::Api for StoreApi {
             })
             .collect();
 
-        Ok(Response::new(CheckNullifiersByPrefixResponse { nullifiers }))
+        Ok(Response::new(CheckNullifiersByPrefixResponse {
+            nullifiers,
+            anchor: Some(anchor),
+            next_page,
+        }))
     }
 
     /// Returns info which can be used by the client to sync up to the latest state of the chain
@@ -151,6 +358,14 @@ impl api_server::Api for StoreApi {
         target = "miden-store",
         name = "store:sync_state",
         skip_all,
+        fields(
+            account_ids,
+            unique_account_ids,
+            note_tags,
+            unique_note_tags,
+            nullifier_prefixes,
+            unique_nullifier_prefixes
+        ),
         ret(level = "debug"),
         err
     )]
@@ -158,25 +373,18 @@ impl api_server::Api for StoreApi {
         &self,
         request: Request<SyncStateRequest>,
     ) -> Result<Response<SyncStateResponse>, Status> {
-        let request = request.into_inner();
+        let shard_keys = ShardKeys::check(&request, self.shard_range)?;
+        let mut request = request.into_inner();
+        normalize_sync_state_request(&mut request)?;
 
         let account_ids: Vec<u64> = request.account_ids.iter().map(|e| e.id).collect();
 
-        let (state, delta) = self
+        let (anchor, state, delta) = self
             .state
             .sync_state(request.block_num, account_ids, request.note_tags, request.nullifiers)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
-        let accounts = state
-            .account_updates
-            .into_iter()
-            .map(|account_info| AccountSummary {
-                account_id: Some(account_info.account_id.into()),
-                account_hash: Some(account_info.account_hash.into()),
-                block_num: account_info.block_num,
-            })
-            .collect();
+        let accounts = state.account_updates.iter().map(Into::into).collect();
 
         let transactions = state
             .transactions
@@ -188,7 +396,11 @@ impl api_server::Api for StoreApi {
             })
             .collect();
 
-        let notes = state.notes.into_iter().map(Into::into).collect();
+        let notes = state
+            .notes
+            .into_iter()
+            .map(|note| note.into_proto(request.compress_merkle_paths))
+            .collect();
 
         let nullifiers = state
             .nullifiers
@@ -199,15 +411,54 @@ impl api_server::Api for StoreApi {
             })
             .collect();
 
-        Ok(Response::new(SyncStateResponse {
-            chain_tip: self.state.latest_block_num().await,
+        Ok(shard_keys.echo(Response::new(SyncStateResponse {
+            chain_tip: anchor.block_num,
             block_header: Some(state.block_header.into()),
             mmr_delta: Some(delta.into()),
             accounts,
             transactions,
             notes,
             nullifiers,
-        }))
+            anchor: Some(anchor),
+        })))
+    }
+
+    /// Returns the latest state of an account and its merged delta since the block known by the
+    /// client, instead of its update in every block in-between.
+    #[instrument(
+        target = "miden-store",
+        name = "store:sync_account_compact",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn sync_account_compact(
+        &self,
+        request: Request<SyncAccountCompactRequest>,
+    ) -> Result<Response<SyncAccountCompactResponse>, Status> {
+        let shard_keys = ShardKeys::check(&request, self.shard_range)?;
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let account_id = request.account_id.ok_or(invalid_argument("account_id is missing"))?.id;
+
+        let (anchor, sync) =
+            self.state.sync_account_compact(account_id, request.known_block).await?;
+        if request.known_block > anchor.block_num {
+            return Err(Status::invalid_argument(format!(
+                "Known block {} is ahead of the chain tip {}",
+                request.known_block, anchor.block_num
+            )));
+        }
+
+        Ok(shard_keys.echo(Response::new(SyncAccountCompactResponse {
+            account_hash: Some(sync.summary.account_hash.into()),
+            last_update_block: sync.summary.block_num,
+            skipped_updates: sync.skipped_updates,
+            delta: sync.delta,
+            anchor: Some(anchor),
+        })))
     }
 
     /// Returns info which can be used by the client to sync note state.
@@ -215,6 +466,7 @@ impl api_server::Api for StoreApi {
         target = "miden-store",
         name = "store:sync_notes",
         skip_all,
+        fields(note_tags, unique_note_tags),
         ret(level = "debug"),
         err
     )]
@@ -222,27 +474,33 @@ impl api_server::Api for StoreApi {
         &self,
         request: Request<SyncNoteRequest>,
     ) -> Result<Response<SyncNoteResponse>, Status> {
-        let request = request.into_inner();
+        let mut request = request.into_inner();
+        normalize_sync_note_request(&mut request)?;
 
-        let (state, mmr_proof) = self
-            .state
-            .sync_notes(request.block_num, request.note_tags)
-            .await
-            .map_err(internal_error)?;
+        let (anchor, state, mmr_proof) =
+            self.state.sync_notes(request.block_num, request.note_tags).await?;
 
-        let notes = state.notes.into_iter().map(Into::into).collect();
+        let notes = state
+            .notes
+            .into_iter()
+            .map(|note| note.into_proto(request.compress_merkle_paths))
+            .collect();
 
         Ok(Response::new(SyncNoteResponse {
-            chain_tip: self.state.latest_block_num().await,
+            chain_tip: anchor.block_num,
             block_header: Some(state.block_header.into()),
             mmr_path: Some((&mmr_proof.merkle_path).into()),
             notes,
+            anchor: Some(anchor),
         }))
     }
 
     /// Returns a list of Note's for the specified NoteId's.
     ///
     /// If the list is empty or no Note matched the requested NoteId and empty list is returned.
+    ///
+    /// If the notes exceed the maximum response size, only part of them is returned and
+    /// `truncated` is set. The other notes are fetched by requesting them again.
     #[instrument(
         target = "miden-store",
         name = "store:get_notes_by_id",
@@ -256,22 +514,175 @@ impl api_server::Api for StoreApi {
     ) -> Result<Response<GetNotesByIdResponse>, Status> {
         info!(target: COMPONENT, ?request);
 
-        let note_ids = request.into_inner().note_ids;
+        let request = request.into_inner();
 
-        let note_ids: Vec<RpoDigest> = try_convert(note_ids)
+        let note_ids: Vec<RpoDigest> = try_convert(request.note_ids)
             .map_err(|err| Status::invalid_argument(format!("Invalid NoteId: {}", err)))?;
 
         let note_ids: Vec<NoteId> = note_ids.into_iter().map(From::from).collect();
 
-        let notes = self
+        let (anchor, notes) = self.state.anchored(self.state.get_notes_by_id(note_ids)).await;
+        let mut notes: Vec<_> = notes?
+            .into_iter()
+            .map(|note| note.into_proto(request.compress_merkle_paths))
+            .collect();
+
+        let base_size = GetNotesByIdResponse {
+            notes: vec![],
+            truncated: true,
+            anchor: Some(anchor),
+        }
+        .encoded_len();
+        let truncated = truncate_to_size(&mut notes, 1, base_size, self.max_response_size);
+
+        Ok(Response::new(GetNotesByIdResponse { notes, truncated, anchor: Some(anchor) }))
+    }
+
+    /// Returns a page of the public notes sent to the specified recipients.
+    ///
+    /// Notes are returned in chain order. If the page is full, `next_page` is set to the position
+    /// of its last note, and the following notes are fetched by passing it back as `page`.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_notes_by_recipient",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_notes_by_recipient(
+        &self,
+        request: Request<GetNotesByRecipientRequest>,
+    ) -> Result<Response<GetNotesByRecipientResponse>, Status> {
+        info!(target: COMPONENT, ?request);
+
+        let request = request.into_inner();
+
+        if request.recipients.len() > MAX_NOTE_RECIPIENTS {
+            return Err(Status::invalid_argument(format!(
+                "Too many recipients requested: {}, limit: {MAX_NOTE_RECIPIENTS}",
+                request.recipients.len()
+            )));
+        }
+
+        let recipients: Vec<RpoDigest> = try_convert(request.recipients)
+            .map_err(|err| Status::invalid_argument(format!("Invalid recipient: {}", err)))?;
+
+        let after = request
+            .page
+            .map(|page| {
+                let note_index = page.note_index as usize;
+                BlockNoteIndex::new(
+                    note_index / MAX_OUTPUT_NOTES_PER_BATCH,
+                    note_index % MAX_OUTPUT_NOTES_PER_BATCH,
+                )
+                .map(|note_index| (page.block_num, note_index))
+                .map_err(|err| Status::invalid_argument(format!("Invalid page: {err}")))
+            })
+            .transpose()?;
+
+        let (anchor, notes) = self
             .state
-            .get_notes_by_id(note_ids)
-            .await?
+            .anchored(self.state.get_notes_by_recipient(
+                recipients,
+                request.from_block,
+                after,
+                NOTES_BY_RECIPIENT_PAGE_SIZE,
+            ))
+            .await;
+        let notes = notes?;
+
+        // A full page may be followed by more notes
+        let next_page = notes.get(NOTES_BY_RECIPIENT_PAGE_SIZE - 1).map(|note| NotePosition {
+            block_num: note.block_num,
+            note_index: note.note_index.leaf_index_value().into(),
+        });
+
+        let notes = notes
             .into_iter()
-            .map(Into::into)
+            .map(|note| note.into_proto(request.compress_merkle_paths))
             .collect();
 
-        Ok(Response::new(GetNotesByIdResponse { notes }))
+        Ok(Response::new(GetNotesByRecipientResponse {
+            notes,
+            next_page,
+            anchor: Some(anchor),
+        }))
+    }
+
+    /// Returns the Bloom filters of the note tags of a range of blocks.
+    ///
+    /// Clients test the filters against the tags they track, and only request the notes of the
+    /// blocks which may contain one of them.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_block_note_filters",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_block_note_filters(
+        &self,
+        request: Request<GetBlockNoteFiltersRequest>,
+    ) -> Result<Response<GetBlockNoteFiltersResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let GetBlockNoteFiltersRequest { from_block, to_block } = request;
+        validate_block_range(from_block, to_block, MAX_BLOCK_NOTE_FILTERS_RANGE)?;
+
+        let (anchor, filters) = self
+            .state
+            .anchored(self.state.get_block_note_filters(from_block, to_block))
+            .await;
+
+        Ok(Response::new(GetBlockNoteFiltersResponse {
+            filters: filters?.into_iter().map(Into::into).collect(),
+            anchor: Some(anchor),
+        }))
+    }
+
+    /// Returns the number of notes per tag prefix in a range of blocks.
+    ///
+    /// Wallets use it to pick tags which are shared by enough notes to hide theirs, without
+    /// having to sync too many unrelated notes.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_note_tag_statistics",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_note_tag_statistics(
+        &self,
+        request: Request<GetNoteTagStatisticsRequest>,
+    ) -> Result<Response<GetNoteTagStatisticsResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        validate_note_tag_statistics_request(&request)?;
+        let GetNoteTagStatisticsRequest {
+            from_block,
+            to_block,
+            prefix_len,
+            exclude_network_notes,
+        } = request;
+
+        let (anchor, buckets) = self
+            .state
+            .anchored(self.state.get_note_tag_statistics(
+                from_block,
+                to_block,
+                prefix_len,
+                exclude_network_notes,
+            ))
+            .await;
+
+        Ok(Response::new(GetNoteTagStatisticsResponse {
+            buckets: buckets?.into_iter().map(Into::into).collect(),
+            anchor: Some(anchor),
+        }))
     }
 
     /// Returns a list of Note inclusion proofs for the specified NoteId's.
@@ -322,23 +733,95 @@ impl api_server::Api for StoreApi {
         &self,
         request: Request<GetAccountDetailsRequest>,
     ) -> Result<Response<GetAccountDetailsResponse>, Status> {
+        let shard_keys = ShardKeys::check(&request, self.shard_range)?;
+        let request = request.into_inner();
+        let account_id = request.account_id.ok_or(invalid_argument("Account missing id"))?.into();
+
+        let (anchor, account_info) =
+            self.state.anchored(self.state.get_account_details(account_id)).await;
+
+        Ok(shard_keys.echo(Response::new(GetAccountDetailsResponse {
+            details: Some((&account_info?).into()),
+            anchor: Some(anchor),
+        })))
+    }
+
+    /// Returns the storage layout of a public account, without the stored values.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_account_storage_header",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_storage_header(
+        &self,
+        request: Request<GetAccountStorageHeaderRequest>,
+    ) -> Result<Response<GetAccountStorageHeaderResponse>, Status> {
+        let shard_keys = ShardKeys::check(&request, self.shard_range)?;
+        let request = request.into_inner();
+        let account_id = request.account_id.ok_or(invalid_argument("Account missing id"))?.into();
+
+        let (anchor, block_header, storage_commitment, slots) =
+            self.state.get_account_storage_header(account_id).await?;
+
+        Ok(shard_keys.echo(Response::new(GetAccountStorageHeaderResponse {
+            block_header: Some(block_header.into()),
+            storage_commitment: Some(storage_commitment.into()),
+            slots: slots.into_iter().map(Into::into).collect(),
+            anchor: Some(anchor),
+        })))
+    }
+
+    /// Returns a page of the assets in the vault of a public account.
+    ///
+    /// Assets are returned in the order of their vault key. If the page is full, `next_page` is
+    /// set to the key of its last asset, and the following assets are fetched by passing it back
+    /// as `page`.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_account_vault_assets",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_vault_assets(
+        &self,
+        request: Request<GetAccountVaultAssetsRequest>,
+    ) -> Result<Response<GetAccountVaultAssetsResponse>, Status> {
+        let shard_keys = ShardKeys::check(&request, self.shard_range)?;
         let request = request.into_inner();
-        let account_info = self
+        let account_id = request.account_id.ok_or(invalid_argument("Account missing id"))?.into();
+        let after = request
+            .page
+            .map(RpoDigest::try_from)
+            .transpose()
+            .map_err(|err| Status::invalid_argument(format!("Invalid page: {err}")))?;
+
+        let (anchor, block_num, vault_root, assets) = self
             .state
-            .get_account_details(
-                request.account_id.ok_or(invalid_argument("Account missing id"))?.into(),
-            )
+            .get_account_vault_assets(account_id, after, VAULT_ASSETS_PAGE_SIZE)
             .await?;
 
-        Ok(Response::new(GetAccountDetailsResponse {
-            details: Some((&account_info).into()),
-        }))
+        // A full page may be followed by more assets
+        let next_page = assets.get(VAULT_ASSETS_PAGE_SIZE - 1).map(|asset| asset.vault_key.into());
+
+        Ok(shard_keys.echo(Response::new(GetAccountVaultAssetsResponse {
+            block_num,
+            vault_root: Some(vault_root.into()),
+            assets: assets.into_iter().map(Into::into).collect(),
+            next_page,
+            anchor: Some(anchor),
+        })))
     }
 
     // BLOCK PRODUCER ENDPOINTS
     // --------------------------------------------------------------------------------------------
 
     /// Updates the local DB by inserting a new block header and the related data.
+    ///
+    /// Resubmissions of the latest committed blocks succeed without applying them again, so that
+    /// the block producer can safely retry submissions whose outcome it doesn't know.
     #[instrument(
         target = "miden-store",
         name = "store:apply_block",
@@ -350,6 +833,7 @@ impl api_server::Api for StoreApi {
         &self,
         request: Request<ApplyBlockRequest>,
     ) -> Result<Response<ApplyBlockResponse>, Status> {
+        let received_at = SystemTime::now();
         let request = request.into_inner();
 
         debug!(target: COMPONENT, ?request);
@@ -367,14 +851,115 @@ impl api_server::Api for StoreApi {
             account_count = block.updated_accounts().len(),
             note_count = block.notes().count(),
             nullifier_count = block.nullifiers().len(),
+            producer = ?request.producer,
         );
 
-        self.state.apply_block(block).await?;
+        if self.state.is_block_committed(&block.header()).await.map_err(internal_error)? {
+            info!(target: COMPONENT, block_num, "Block is already committed, skipping it");
+        } else {
+            let commit_started_at = Instant::now();
+            // The block is applied in its own task, so that dropping the request, e.g. when its
+            // client disconnects, can't interrupt it between the commit of the database
+            // transaction and the update of the in-memory state
+            let state = Arc::clone(&self.state);
+            tokio::spawn(async move { state.apply_block(block).await }.in_current_span())
+                .await
+                .map_err(ApplyBlockError::from)??;
+            let commit = commit_started_at.elapsed();
+
+            // The block is committed at this point, failing to record its stats doesn't fail the
+            // request
+            let stats = BlockProductionStats::new(block_num, request.stage_durations, commit);
+            if let Err(err) = self.state.record_block_production_stats(stats).await {
+                warn!(target: COMPONENT, block_num, %err, "Failed to record block production stats");
+            }
+        }
+
+        // The provenance is informational, resubmissions record it as well
+        if let Some(producer) = request.producer {
+            let provenance = BlockProvenance::new(block_num, producer, received_at);
+            if let Err(err) = self.state.record_block_provenance(provenance).await {
+                warn!(target: COMPONENT, block_num, %err, "Failed to record block provenance");
+            }
+        }
 
         Ok(Response::new(ApplyBlockResponse {}))
     }
 
+    /// Returns the durations of the production stages of a range of blocks, together with their
+    /// percentiles over the range.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_block_production_stats",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_block_production_stats(
+        &self,
+        request: Request<GetBlockProductionStatsRequest>,
+    ) -> Result<Response<GetBlockProductionStatsResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let GetBlockProductionStatsRequest { block_from, block_to } = request;
+        validate_block_range(block_from, block_to, MAX_BLOCK_PRODUCTION_STATS_RANGE)?;
+
+        let stats = self
+            .state
+            .get_block_production_stats(block_from, block_to)
+            .await
+            .map_err(internal_error)?;
+        let percentiles = StageDurationPercentiles::from_stats(&stats);
+
+        Ok(Response::new(GetBlockProductionStatsResponse {
+            blocks: stats.into_iter().map(Into::into).collect(),
+            percentiles: percentiles.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    /// Returns aggregate statistics of a range of blocks.
+    ///
+    /// The statistics are computed from the running totals recorded for every block, so the range
+    /// is not limited. A range going beyond the chain tip is truncated to it.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_chain_stats",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_chain_stats(
+        &self,
+        request: Request<GetChainStatsRequest>,
+    ) -> Result<Response<GetChainStatsResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let GetChainStatsRequest { from_block, to_block } = request;
+        if from_block > to_block {
+            return Err(invalid_argument(format!(
+                "Invalid block range: {from_block} is after {to_block}"
+            )));
+        }
+
+        let stats = self
+            .state
+            .get_chain_stats(from_block, to_block)
+            .await
+            .map_err(internal_error)?
+            .ok_or_else(|| Status::not_found(format!("Block {from_block} not found")))?;
+
+        Ok(Response::new(stats.into()))
+    }
+
     /// Returns data needed by the block producer to construct and prove the next block.
+    ///
+    /// The account states and nullifiers are paginated to the maximum response size: the
+    /// following pages are fetched with the cursor of the previous one, from a snapshot of the
+    /// inputs taken by the first request.
     #[instrument(
         target = "miden-store",
         name = "store:get_block_inputs",
@@ -388,17 +973,27 @@ impl api_server::Api for StoreApi {
     ) -> Result<Response<GetBlockInputsResponse>, Status> {
         let request = request.into_inner();
 
+        if let Some(cursor) = request.cursor {
+            return self
+                .block_inputs_snapshots
+                .next_page(cursor, self.max_response_size)
+                .map(Response::new);
+        }
+
         let nullifiers = validate_nullifiers(&request.nullifiers)?;
         let account_ids: Vec<AccountId> = request.account_ids.iter().map(|e| e.id).collect();
         let unauthenticated_notes = validate_notes(&request.unauthenticated_notes)?;
         let unauthenticated_notes = unauthenticated_notes.into_iter().collect();
 
-        self.state
+        let inputs = self
+            .state
             .get_block_inputs(&account_ids, &nullifiers, unauthenticated_notes)
             .await
-            .map(Into::into)
-            .map(Response::new)
-            .map_err(internal_error)
+            .map_err(internal_error)?;
+
+        Ok(Response::new(
+            self.block_inputs_snapshots.first_page(inputs.into(), self.max_response_size),
+        ))
     }
 
     #[instrument(
@@ -419,10 +1014,15 @@ impl api_server::Api for StoreApi {
         let account_id = request.account_id.ok_or(invalid_argument("`account_id` missing"))?.id;
         let nullifiers = validate_nullifiers(&request.nullifiers)?;
         let unauthenticated_notes = validate_notes(&request.unauthenticated_notes)?;
+        let reference_block: RpoDigest = request
+            .reference_block
+            .ok_or(invalid_argument("`reference_block` missing"))?
+            .try_into()
+            .map_err(|_| invalid_argument("Digest field is not in the modulus range"))?;
 
         let tx_inputs = self
             .state
-            .get_transaction_inputs(account_id, &nullifiers, unauthenticated_notes)
+            .get_transaction_inputs(account_id, &nullifiers, unauthenticated_notes, reference_block)
             .await?;
 
         let block_height = self.state.latest_block_num().await;
@@ -446,6 +1046,235 @@ impl api_server::Api for StoreApi {
                 .map(Into::into)
                 .collect(),
             block_height,
+            reference_block_num: tx_inputs.reference_block_num,
+        }))
+    }
+
+    /// Returns the latest commitments of the requested accounts, without their details.
+    ///
+    /// This is a lighter alternative to `get_transaction_inputs` for the checks which only need
+    /// the committed account states.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_account_commitments",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_commitments(
+        &self,
+        request: Request<GetAccountCommitmentsRequest>,
+    ) -> Result<Response<GetAccountCommitmentsResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        if request.account_ids.len() > MAX_ACCOUNT_COMMITMENTS {
+            return Err(Status::invalid_argument(format!(
+                "Too many accounts requested: {}, limit: {MAX_ACCOUNT_COMMITMENTS}",
+                request.account_ids.len()
+            )));
+        }
+
+        let account_ids = convert(request.account_ids);
+        let (block_num, accounts) = self.state.get_account_commitments(account_ids).await?;
+
+        Ok(Response::new(GetAccountCommitmentsResponse {
+            block_num,
+            accounts: accounts.iter().map(Into::into).collect(),
+        }))
+    }
+
+    /// Returns the witnesses of the requested accounts against the account tree of a recent block.
+    ///
+    /// All the witnesses are against the same block, even if blocks are applied concurrently, so
+    /// that block builders and external verifiers can combine them with the witnesses of other
+    /// requests pinned to this block.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_account_witnesses",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_witnesses(
+        &self,
+        request: Request<GetAccountWitnessesRequest>,
+    ) -> Result<Response<GetAccountWitnessesResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        if request.account_ids.len() > MAX_ACCOUNT_WITNESSES {
+            return Err(Status::invalid_argument(format!(
+                "Too many accounts requested: {}, limit: {MAX_ACCOUNT_WITNESSES}",
+                request.account_ids.len()
+            )));
+        }
+
+        let account_ids: Vec<AccountId> = convert(request.account_ids);
+        let (anchor, block_num, witnesses) =
+            self.state.get_account_witnesses(&account_ids, request.block_num).await?;
+
+        Ok(Response::new(GetAccountWitnessesResponse {
+            block_num,
+            witnesses: witnesses
+                .into_iter()
+                .map(|(account_id, witness)| AccountBlockInputRecord {
+                    account_id: Some(account_id.into()),
+                    account_hash: Some(witness.value.into()),
+                    proof: Some(witness.path.into()),
+                })
+                .collect(),
+            anchor: Some(anchor),
+        }))
+    }
+
+    /// Streams the updates of the requested accounts by the blocks applied from now on.
+    ///
+    /// The first message is a snapshot of the latest commitments of the accounts, followed by a
+    /// message for every later block updating any of them. The stream is ended with an error if
+    /// the client falls too far behind.
+    #[instrument(target = "miden-store", name = "store:subscribe_account_updates", skip_all, err)]
+    async fn subscribe_account_updates(
+        &self,
+        request: Request<SubscribeAccountUpdatesRequest>,
+    ) -> Result<Response<Self::SubscribeAccountUpdatesStream>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        if request.account_ids.len() > MAX_SUBSCRIBED_ACCOUNTS {
+            return Err(Status::invalid_argument(format!(
+                "Too many accounts requested: {}, limit: {MAX_SUBSCRIBED_ACCOUNTS}",
+                request.account_ids.len()
+            )));
+        }
+
+        let account_ids: Vec<AccountId> = convert(request.account_ids);
+
+        // Subscribing first, so that no update after the snapshot is missed
+        let subscription =
+            self.state.subscribe_account_updates(account_ids.iter().copied().collect());
+        let (block_num, accounts) = self.state.get_account_commitments(account_ids).await?;
+
+        let (sender, receiver) = mpsc::channel(ACCOUNT_UPDATES_STREAM_BUFFER);
+        let snapshot = GetAccountCommitmentsResponse {
+            block_num,
+            accounts: accounts.iter().map(Into::into).collect(),
+        };
+        sender
+            .try_send(Ok(SubscribeAccountUpdatesResponse {
+                update: Some(AccountUpdate::Snapshot(snapshot)),
+            }))
+            .expect("The stream buffer is empty");
+
+        tokio::spawn(stream_account_updates(subscription, block_num, sender).in_current_span());
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    /// Streams the deltas of public accounts in the blocks `from_block..=to_block`, one message
+    /// per account and block, in the order of their block.
+    ///
+    /// Meant for bulk exports of the history of accounts: the deltas are loaded chunk by chunk and
+    /// streamed at the configured number of rows per second, or slower if the client doesn't keep
+    /// up. Every message carries the cursor from which an interrupted export can be resumed.
+    #[instrument(target = "miden-store", name = "store:export_account_deltas", skip_all, err)]
+    async fn export_account_deltas(
+        &self,
+        request: Request<ExportAccountDeltasRequest>,
+    ) -> Result<Response<Self::ExportAccountDeltasStream>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        if request.account_ids.len() > MAX_EXPORTED_ACCOUNTS {
+            return Err(Status::invalid_argument(format!(
+                "Too many accounts requested: {}, limit: {MAX_EXPORTED_ACCOUNTS}",
+                request.account_ids.len()
+            )));
+        }
+        if request.from_block > request.to_block {
+            return Err(Status::invalid_argument(format!(
+                "Invalid block range: {} is after {}",
+                request.from_block, request.to_block
+            )));
+        }
+
+        check_account_deltas_served(&self.state, request.from_block.saturating_sub(1))?;
+
+        let after = request
+            .cursor
+            .map(|cursor| {
+                let account_id = cursor.account_id.ok_or_else(|| {
+                    Status::invalid_argument("Invalid cursor: missing account ID")
+                })?;
+                Ok::<_, Status>((cursor.block_num, account_id.into()))
+            })
+            .transpose()?;
+
+        let export = AccountDeltasExport {
+            account_ids: convert(request.account_ids),
+            from_block: request.from_block,
+            to_block: request.to_block,
+            after,
+        };
+
+        let (sender, receiver) = mpsc::channel(ACCOUNT_DELTAS_EXPORT_CHUNK_SIZE);
+        tokio::spawn(
+            stream_account_deltas(
+                Arc::clone(&self.state),
+                export,
+                self.export_rows_per_second,
+                sender,
+            )
+            .in_current_span(),
+        );
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    /// Returns which of the requested transactions were committed, and in which block.
+    ///
+    /// Used to skip the transactions which are committed already when resubmitting them.
+    #[instrument(
+        target = "miden-store",
+        name = "store:filter_known_transactions",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn filter_known_transactions(
+        &self,
+        request: Request<FilterKnownTransactionsRequest>,
+    ) -> Result<Response<FilterKnownTransactionsResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        if request.transaction_ids.len() > MAX_KNOWN_TRANSACTIONS {
+            return Err(Status::invalid_argument(format!(
+                "Too many transactions requested: {}, limit: {MAX_KNOWN_TRANSACTIONS}",
+                request.transaction_ids.len()
+            )));
+        }
+
+        let transaction_ids: Vec<TransactionId> = try_convert(request.transaction_ids)
+            .map_err(|err| Status::invalid_argument(format!("Invalid TransactionId: {err}")))?;
+        let (block_num, transactions) =
+            self.state.filter_known_transactions(transaction_ids).await?;
+
+        Ok(Response::new(FilterKnownTransactionsResponse {
+            block_num,
+            transactions: transactions
+                .into_iter()
+                .map(|transaction| TransactionSummary {
+                    account_id: Some(transaction.account_id.into()),
+                    block_num: transaction.block_num,
+                    transaction_id: Some(transaction.transaction_id.into()),
+                })
+                .collect(),
         }))
     }
 
@@ -464,11 +1293,28 @@ impl api_server::Api for StoreApi {
 
         debug!(target: COMPONENT, ?request);
 
-        let block = self.state.load_block(request.block_num).await?;
-
-        Ok(Response::new(GetBlockByNumberResponse { block }))
+        let (anchor, block) = self.state.load_block(request.block_num).await?;
+        let provenance = if block.is_some() {
+            self.state
+                .get_block_provenance(request.block_num)
+                .await
+                .map_err(internal_error)?
+                .map(Into::into)
+        } else {
+            None
+        };
+
+        Ok(Response::new(GetBlockByNumberResponse {
+            block,
+            anchor: Some(anchor),
+            provenance,
+        }))
     }
 
+    /// Returns the proofs of the requested accounts.
+    ///
+    /// If the proofs exceed the maximum response size, only part of them is returned and
+    /// `truncated` is set. The other proofs are fetched by requesting the remaining accounts again.
     #[instrument(
         target = "miden-store",
         name = "store:get_account_proofs",
@@ -480,6 +1326,7 @@ impl api_server::Api for StoreApi {
         &self,
         request: Request<GetAccountProofsRequest>,
     ) -> Result<Response<GetAccountProofsResponse>, Status> {
+        let shard_keys = ShardKeys::check(&request, self.shard_range)?;
         let request = request.into_inner();
         if request.account_ids.len() < request.code_commitments.len() {
             return Err(Status::invalid_argument(
@@ -490,21 +1337,88 @@ impl api_server::Api for StoreApi {
         debug!(target: COMPONENT, ?request);
 
         let include_headers = request.include_headers.unwrap_or_default();
+        let bundle_proofs = request.bundle_proofs.unwrap_or_default();
         let account_ids: Vec<u64> = convert(request.account_ids);
         let request_code_commitments: BTreeSet<RpoDigest> = try_convert(request.code_commitments)
             .map_err(|err| {
             Status::invalid_argument(format!("Invalid code commitment: {}", err))
         })?;
 
-        let (block_num, infos) = self
+        let known_blocks = request
+            .known_blocks
+            .into_iter()
+            .map(|known_block| {
+                let account_id = known_block
+                    .account_id
+                    .ok_or(invalid_argument("known_blocks.account_id is missing"))?
+                    .id;
+                if !account_ids.contains(&account_id) {
+                    return Err(Status::invalid_argument(format!(
+                        "Known block provided for account {account_id:x}, which is not requested"
+                    )));
+                }
+
+                Ok((account_id, known_block.block_num))
+            })
+            .collect::<Result<BTreeMap<_, _>, Status>>()?;
+
+        let latest_block_num = self.state.latest_block_num().await;
+        if let Some((account_id, block_num)) =
+            known_blocks.iter().find(|(_, block_num)| **block_num > latest_block_num)
+        {
+            return Err(Status::invalid_argument(format!(
+                "Known block {block_num} of account {account_id:x} is after the chain tip \
+                 {latest_block_num}"
+            )));
+        }
+
+        let (anchor, infos) = self
             .state
-            .get_account_proofs(account_ids, request_code_commitments, include_headers)
+            .get_account_proofs(
+                account_ids,
+                request_code_commitments,
+                include_headers,
+                known_blocks,
+            )
             .await?;
 
-        Ok(Response::new(GetAccountProofsResponse {
-            block_num,
-            account_proofs: infos.into_iter().map(Into::into).collect(),
-        }))
+        let mut account_proofs: Vec<_> = infos.into_iter().map(Into::into).collect();
+
+        // Bundled proofs replace the account hash, proof and state header of the responses
+        if bundle_proofs {
+            let block_commitment = anchor.commitment().map_err(internal_error)?;
+            for proof in &mut account_proofs {
+                let bundle = AccountProofBundle::from_proofs_response(
+                    anchor.block_num,
+                    block_commitment,
+                    proof,
+                )
+                .map_err(internal_error)?;
+
+                *proof = AccountProofsResponse {
+                    account_id: proof.account_id,
+                    state_delta: proof.state_delta.take(),
+                    bundle: Some(bundle.to_bytes()),
+                    ..Default::default()
+                };
+            }
+        }
+
+        let base_size = GetAccountProofsResponse {
+            block_num: anchor.block_num,
+            account_proofs: vec![],
+            truncated: true,
+            anchor: Some(anchor),
+        }
+        .encoded_len();
+        let truncated = truncate_to_size(&mut account_proofs, 2, base_size, self.max_response_size);
+
+        Ok(shard_keys.echo(Response::new(GetAccountProofsResponse {
+            block_num: anchor.block_num,
+            account_proofs,
+            truncated,
+            anchor: Some(anchor),
+        })))
     }
 
     #[instrument(
@@ -518,20 +1432,83 @@ impl api_server::Api for StoreApi {
         &self,
         request: Request<GetAccountStateDeltaRequest>,
     ) -> Result<Response<GetAccountStateDeltaResponse>, Status> {
+        let shard_keys = ShardKeys::check(&request, self.shard_range)?;
         let request = request.into_inner();
 
         debug!(target: COMPONENT, ?request);
 
-        let delta = self
+        let account_id = request.account_id.ok_or(invalid_argument("account_id is missing"))?.id;
+        check_account_deltas_served(&self.state, request.from_block_num)?;
+
+        let (anchor, delta) = self
             .state
-            .get_account_state_delta(
-                request.account_id.ok_or(invalid_argument("account_id is missing"))?.id,
+            .anchored(self.state.get_account_state_delta(
+                account_id,
                 request.from_block_num,
                 request.to_block_num,
-            )
-            .await?;
+            ))
+            .await;
+
+        Ok(shard_keys.echo(Response::new(GetAccountStateDeltaResponse {
+            delta: Some(delta?.to_bytes()),
+            anchor: Some(anchor),
+        })))
+    }
+
+    /// Returns the historical data served by the store.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_data_availability",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_data_availability(
+        &self,
+        _request: Request<GetDataAvailabilityRequest>,
+    ) -> Result<Response<GetDataAvailabilityResponse>, Status> {
+        let (anchor, availability) =
+            self.state.get_data_availability().await.map_err(internal_error)?;
+
+        Ok(Response::new(GetDataAvailabilityResponse {
+            oldest_block_data: availability.oldest_block_data,
+            block_retention_depth: self.block_retention_depth,
+            oldest_account_delta_block: availability.oldest_account_delta_block,
+            note_details: Some((&availability.note_details).into()),
+            anchor: Some(anchor),
+            mode: StoreModePb::from(availability.mode).into(),
+        }))
+    }
+
+    /// Returns the state of the database connection pool and the histogram of the waits for a
+    /// connection, to tell an overloaded store from other causes of rising latencies.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_database_pool_stats",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_database_pool_stats(
+        &self,
+        _request: Request<GetDatabasePoolStatsRequest>,
+    ) -> Result<Response<GetDatabasePoolStatsResponse>, Status> {
+        Ok(Response::new(self.state.database_pool_stats().into()))
+    }
 
-        Ok(Response::new(GetAccountStateDeltaResponse { delta: Some(delta.to_bytes()) }))
+    /// Returns the versions of the blocks accepted by the store.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_supported_block_versions",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_supported_block_versions(
+        &self,
+        _request: Request<GetSupportedBlockVersionsRequest>,
+    ) -> Result<Response<GetSupportedBlockVersionsResponse>, Status> {
+        Ok(Response::new(self.state.block_versions().into()))
     }
 
     // TESTING ENDPOINTS
@@ -596,9 +1573,152 @@ impl api_server::Api for StoreApi {
 // UTILITIES
 // ================================================================================================
 
-/// Formats an "Internal error" error
-fn internal_error<E: core::fmt::Display>(err: E) -> Status {
-    Status::internal(err.to_string())
+/// Number of messages of an account updates stream buffered for a slow client, on top of the
+/// blocks buffered by its subscription.
+const ACCOUNT_UPDATES_STREAM_BUFFER: usize = 4;
+
+/// Streams the account updates of the blocks after `snapshot_block_num` to the `sender`, until
+/// the subscription falls too far behind.
+///
+/// Stops as soon as the client disconnects, dropping the subscription.
+async fn stream_account_updates(
+    mut subscription: AccountUpdatesSubscription,
+    snapshot_block_num: BlockNumber,
+    sender: mpsc::Sender<Result<SubscribeAccountUpdatesResponse, Status>>,
+) {
+    loop {
+        let next = tokio::select! {
+            next = subscription.next() => next,
+            _ = sender.closed() => {
+                debug!(target: COMPONENT, "Account updates subscriber disconnected");
+                return;
+            },
+        };
+
+        let message = match next {
+            // The block was applied before the snapshot was taken
+            Ok((block_num, _)) if block_num <= snapshot_block_num => continue,
+            Ok((block_num, updates)) => {
+                let updates = updates.iter().map(account_block_update).collect();
+                Ok(SubscribeAccountUpdatesResponse {
+                    update: Some(AccountUpdate::Block(AccountBlockUpdates { block_num, updates })),
+                })
+            },
+            Err(err @ AccountSubscriptionError::Lagged(_)) => {
+                warn!(target: COMPONENT, %err, "Ending account updates subscription");
+                Err(Status::resource_exhausted(format!(
+                    "Account updates subscription ended: {err}"
+                )))
+            },
+            Err(err @ AccountSubscriptionError::Closed) => {
+                Err(Status::unavailable(format!("Account updates subscription ended: {err}")))
+            },
+        };
+
+        let end = message.is_err();
+        if sender.send(message).await.is_err() || end {
+            return;
+        }
+    }
+}
+
+/// Number of chunks per second loaded by an account deltas export, bounding the bursts of rows
+/// allowed by the throttle to a fraction of a second.
+const ACCOUNT_DELTAS_EXPORT_CHUNKS_PER_SECOND: u32 = 10;
+
+/// Account deltas requested by an `ExportAccountDeltas` request.
+#[derive(Debug)]
+struct AccountDeltasExport {
+    account_ids: Vec<AccountId>,
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+    /// Position of the last delta exported, in the order of the export.
+    after: Option<(BlockNumber, AccountId)>,
+}
+
+/// Streams the account deltas of the export to the `sender`, at most `rows_per_second` deltas per
+/// second.
+///
+/// A database connection is only used while loading a chunk, never while waiting for the client
+/// or the throttle. Stops as soon as the client disconnects.
+async fn stream_account_deltas(
+    state: Arc<State>,
+    mut export: AccountDeltasExport,
+    rows_per_second: u32,
+    sender: mpsc::Sender<Result<ExportAccountDeltasResponse, Status>>,
+) {
+    let rows_per_second = rows_per_second.max(1);
+    let chunk_size = (rows_per_second / ACCOUNT_DELTAS_EXPORT_CHUNKS_PER_SECOND)
+        .clamp(1, ACCOUNT_DELTAS_EXPORT_CHUNK_SIZE as u32) as usize;
+    let mut next_chunk_at = Instant::now();
+
+    loop {
+        tokio::time::sleep_until(next_chunk_at).await;
+
+        let chunk = state
+            .get_account_deltas_chunk(
+                export.account_ids.clone(),
+                export.from_block,
+                export.to_block,
+                export.after,
+                chunk_size,
+            )
+            .await;
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                warn!(target: COMPONENT, %err, "Failed to load account deltas to export");
+                let _ = sender.send(Err(err.into())).await;
+                return;
+            },
+        };
+
+        // Time spent waiting for the client isn't made up for with a burst
+        next_chunk_at = next_chunk_at.max(Instant::now())
+            + Duration::from_secs(chunk.len() as u64) / rows_per_second;
+
+        let last_chunk = chunk.len() < chunk_size;
+        for delta in chunk {
+            export.after = Some((delta.block_num, delta.account_id));
+            let cursor = AccountDeltaCursor {
+                block_num: delta.block_num,
+                account_id: Some(delta.account_id.into()),
+            };
+            let message = ExportAccountDeltasResponse {
+                account_id: Some(delta.account_id.into()),
+                block_num: delta.block_num,
+                delta: delta.delta,
+                cursor: Some(cursor),
+            };
+
+            if sender.send(Ok(message)).await.is_err() {
+                debug!(target: COMPONENT, "Account deltas export interrupted by the client");
+                return;
+            }
+        }
+
+        if last_chunk {
+            return;
+        }
+    }
+}
+
+fn account_block_update(update: &BlockAccountUpdate) -> AccountBlockUpdate {
+    let delta = match update.details() {
+        AccountUpdateDetails::Delta(delta) => Some(delta.to_bytes()),
+        AccountUpdateDetails::Private | AccountUpdateDetails::New(_) => None,
+    };
+
+    AccountBlockUpdate {
+        account_id: Some(update.account_id().into()),
+        account_hash: Some(update.new_state_hash().into()),
+        delta,
+    }
+}
+
+/// Formats an "Internal error" error, recording the error chain in the status details
+fn internal_error<E: std::error::Error + 'static>(err: E) -> Status {
+    Status::internal(err.to_string()).with_error_source(COMPONENT, &err)
 }
 
 /// Formats an "Invalid argument" error
@@ -606,6 +1726,21 @@ fn invalid_argument<E: core::fmt::Display>(err: E) -> Status {
     Status::invalid_argument(err.to_string())
 }
 
+/// Checks that the state deltas of public accounts since `known_block_num` are served.
+///
+/// # Errors
+/// Returns a `FAILED_PRECONDITION` status if the store is in pruned mode and the deltas were
+/// pruned, see [State::serves_account_deltas_since].
+fn check_account_deltas_served(state: &State, known_block_num: BlockNumber) -> Result<(), Status> {
+    if state.serves_account_deltas_since(known_block_num) {
+        Ok(())
+    } else {
+        Err(Status::failed_precondition(format!(
+            "Account deltas since block {known_block_num} pruned, available from archive nodes"
+        )))
+    }
+}
+
 #[instrument(target = "miden-store", skip_all, err)]
 fn validate_nullifiers(nullifiers: &[generated::digest::Digest]) -> Result<Vec<Nullifier>, Status> {
     nullifiers
@@ -624,3 +1759,1059 @@ fn validate_notes(notes: &[generated::digest::Digest]) -> Result<Vec<NoteId>, St
         .collect::<Result<_, ConversionError>>()
         .map_err(|_| invalid_argument("Digest field is not in the modulus range"))
 }
+
+fn validate_note_tag_statistics_request(
+    request: &GetNoteTagStatisticsRequest,
+) -> Result<(), Status> {
+    let GetNoteTagStatisticsRequest { from_block, to_block, prefix_len, .. } = *request;
+
+    if !NOTE_TAG_STATISTICS_PREFIX_LENS.contains(&prefix_len) {
+        return Err(invalid_argument(format!(
+            "Invalid tag prefix length: {prefix_len}, supported: {NOTE_TAG_STATISTICS_PREFIX_LENS:?}"
+        )));
+    }
+    validate_block_range(from_block, to_block, MAX_NOTE_TAG_STATISTICS_RANGE)
+}
+
+/// Checks that `from_block..=to_block` is a range of at most `max_blocks` blocks.
+fn validate_block_range(
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+    max_blocks: u32,
+) -> Result<(), Status> {
+    if from_block > to_block {
+        return Err(invalid_argument(format!(
+            "Invalid block range: {from_block} is after {to_block}"
+        )));
+    }
+    if to_block - from_block >= max_blocks {
+        return Err(invalid_argument(format!(
+            "Too many blocks requested: {}, limit: {max_blocks}",
+            u64::from(to_block - from_block) + 1
+        )));
+    }
+
+    Ok(())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        future::{poll_fn, Future},
+        pin::pin,
+        task::Poll,
+    };
+
+    use generated::{
+        block::BlockProducerMetadata,
+        note::{NoteDetailsAvailability, NoteDetailsPolicy as NoteDetailsPolicyProto},
+        store::api_server::Api,
+    };
+    use miden_node_proto::domain::prefixes::{nullifier_prefix, NULLIFIER_PREFIX_LEN};
+    use miden_node_test_utils::{
+        block::MockChain,
+        mock_account_id,
+        note::{mock_nullifier, mock_output_note},
+    };
+    use tokio::time;
+    use tonic::Code;
+
+    use super::*;
+    use crate::{
+        blocks::BlockStore,
+        config::{NoteDetailsPolicy, StoreConfig, StoreMode, DEFAULT_PRUNED_BLOCK_RETENTION_DEPTH},
+        db::Db,
+        errors::{DatabaseSetupError, StoreModeError},
+        genesis::GenesisState,
+        note_details::NoteDetailsFilter,
+        server::{
+            maintenance,
+            sharding::{SHARD_KEY_METADATA, SHARD_RANGE_METADATA},
+        },
+    };
+
+    /// Returns the API of a new store, along with a chain starting at its genesis.
+    async fn store_api(dir: &std::path::Path) -> (StoreApi, MockChain) {
+        store_api_with(dir, |_| {}).await
+    }
+
+    /// Returns the API of a new store whose default configuration is adjusted by `configure`,
+    /// along with a chain starting at its genesis.
+    async fn store_api_with(
+        dir: &std::path::Path,
+        configure: impl FnOnce(&mut StoreConfig),
+    ) -> (StoreApi, MockChain) {
+        let mut config = StoreConfig {
+            database_filepath: dir.join("miden-store.sqlite3"),
+            genesis_filepath: dir.join("genesis.dat"),
+            blockstore_dir: dir.join("blocks"),
+            ..Default::default()
+        };
+        configure(&mut config);
+        let note_details = NoteDetailsFilter::try_from(&config.note_details).unwrap();
+        let mode = config.store_mode().unwrap();
+        let block_retention_depth = config.retention_depth();
+        let block_versions = config.block_versions;
+        let genesis = GenesisState::new(vec![], 1, 0);
+        fs::write(&config.genesis_filepath, genesis.to_bytes()).unwrap();
+        let chain = MockChain::new(&genesis.into_block().unwrap());
+
+        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+        let db = Db::setup(config, Arc::clone(&block_store)).await.unwrap();
+        let state = State::load(db, block_store)
+            .await
+            .unwrap()
+            .with_mode(mode)
+            .with_note_details(note_details)
+            .with_block_versions(block_versions);
+
+        (
+            StoreApi {
+                state: Arc::new(state),
+                max_response_size: usize::MAX,
+                shard_range: ShardRange::default(),
+                block_retention_depth,
+                export_rows_per_second: u32::MAX,
+                block_inputs_snapshots: BlockInputsSnapshots::new(BLOCK_INPUTS_SNAPSHOT_LIFETIME),
+            },
+            chain,
+        )
+    }
+
+    /// Returns a request of the block inputs of `num` accounts and as many nullifiers.
+    fn block_inputs_request(num: u8) -> GetBlockInputsRequest {
+        GetBlockInputsRequest {
+            account_ids: (0..num).map(|num| mock_account_id(num).into()).collect(),
+            nullifiers: (0..u32::from(num)).map(|index| mock_nullifier(index).into()).collect(),
+            unauthenticated_notes: vec![],
+            cursor: None,
+        }
+    }
+
+    /// Fetches the pages following `inputs`, and returns the assembled inputs along with the
+    /// number of pages fetched.
+    async fn fetch_next_pages(
+        api: &StoreApi,
+        mut inputs: GetBlockInputsResponse,
+    ) -> (GetBlockInputsResponse, usize) {
+        let mut num_pages = 0;
+        while let Some(cursor) = inputs.next.take() {
+            let request = GetBlockInputsRequest {
+                cursor: Some(cursor),
+                ..Default::default()
+            };
+            let page = api.get_block_inputs(Request::new(request)).await.unwrap().into_inner();
+            assert_eq!(page.block_header, inputs.block_header);
+            assert_eq!(page.mmr_peaks, inputs.mmr_peaks);
+            assert_eq!(page.found_unauthenticated_notes, None);
+
+            inputs.account_states.extend(page.account_states);
+            inputs.nullifiers.extend(page.nullifiers);
+            inputs.next = page.next;
+            num_pages += 1;
+        }
+
+        (inputs, num_pages)
+    }
+
+    #[tokio::test]
+    async fn block_inputs_are_paginated_from_a_snapshot() {
+        const MAX_RESPONSE_SIZE: usize = 8 * 1024;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (mut api, mut chain) = store_api(dir.path()).await;
+
+        let request = || Request::new(block_inputs_request(16));
+        let single_shot = api.get_block_inputs(request()).await.unwrap().into_inner();
+        assert_eq!(single_shot.next, None);
+        assert!(single_shot.encoded_len() > 4 * MAX_RESPONSE_SIZE);
+
+        api.max_response_size = MAX_RESPONSE_SIZE;
+        let first_page = api.get_block_inputs(request()).await.unwrap().into_inner();
+        assert!(first_page.encoded_len() <= MAX_RESPONSE_SIZE);
+        let first_cursor = first_page.next.expect("The inputs must not fit in a single page");
+
+        // Blocks applied in the meantime don't change the inputs of the following pages
+        let block = chain.next_block().build();
+        api.apply_block(Request::new(ApplyBlockRequest {
+            block: block.to_bytes(),
+            stage_durations: None,
+            producer: None,
+        }))
+        .await
+        .unwrap();
+
+        let (paginated, num_pages) = fetch_next_pages(&api, first_page).await;
+        assert!(num_pages >= 4);
+        assert_eq!(paginated, single_shot);
+
+        // The last page released the snapshot
+        assert_eq!(api.block_inputs_snapshots.num_snapshots(), 0);
+        let request = GetBlockInputsRequest {
+            cursor: Some(first_cursor),
+            ..Default::default()
+        };
+        let err = api.get_block_inputs(Request::new(request)).await.unwrap_err();
+        assert_eq!(err.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn expired_block_inputs_snapshots_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut api, _) = store_api(dir.path()).await;
+        api.max_response_size = 8 * 1024;
+        api.block_inputs_snapshots = BlockInputsSnapshots::new(Duration::ZERO);
+
+        let request = Request::new(block_inputs_request(16));
+        let first_page = api.get_block_inputs(request).await.unwrap().into_inner();
+
+        let request = GetBlockInputsRequest {
+            cursor: first_page.next,
+            ..Default::default()
+        };
+        let err = api.get_block_inputs(Request::new(request)).await.unwrap_err();
+        assert_eq!(err.code(), Code::NotFound);
+        assert_eq!(api.block_inputs_snapshots.num_snapshots(), 0);
+    }
+
+    #[tokio::test]
+    async fn nullifiers_are_found_by_their_documented_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) = store_api(dir.path()).await;
+
+        // The prefix is the high bits of the most significant element, not of the first one
+        let nullifier: Nullifier = RpoDigest::new([
+            Felt::new(0xbeef_0000_0000_0000),
+            ZERO,
+            ZERO,
+            Felt::new(0x1234_5678_9abc_def0),
+        ])
+        .into();
+        assert_eq!(nullifier_prefix(&nullifier), 0x1234);
+
+        let block = chain.next_block().nullifiers(vec![nullifier]).build();
+        api.apply_block(Request::new(ApplyBlockRequest {
+            block: block.to_bytes(),
+            stage_durations: None,
+            producer: None,
+        }))
+        .await
+        .unwrap();
+
+        let check = |prefix| {
+            api.check_nullifiers_by_prefix(Request::new(CheckNullifiersByPrefixRequest {
+                prefix_len: NULLIFIER_PREFIX_LEN,
+                nullifiers: vec![prefix],
+                page: None,
+            }))
+        };
+        let found = check(0x1234).await.unwrap().into_inner().nullifiers;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].nullifier, Some(nullifier.into()));
+        assert_eq!(found[0].block_num, block.header().block_num());
+        assert!(check(0xbeef).await.unwrap().into_inner().nullifiers.is_empty());
+        assert_eq!(check(0x1_1234).await.unwrap_err().code(), Code::InvalidArgument);
+
+        let synced = api
+            .sync_state(Request::new(SyncStateRequest {
+                nullifiers: vec![nullifier_prefix(&nullifier)],
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(synced.nullifiers.len(), 1);
+        assert_eq!(synced.nullifiers[0].nullifier, Some(nullifier.into()));
+    }
+
+    #[tokio::test]
+    async fn resubmitted_blocks_record_the_latest_attempt() {
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) = store_api(dir.path()).await;
+        let api = &api;
+
+        let block = chain.next_block().build();
+        let producer = |attempt| BlockProducerMetadata {
+            producer_id: "producer-1".to_string(),
+            attempt,
+            produced_at: 1_000,
+        };
+        let apply_block = |producer| {
+            api.apply_block(Request::new(ApplyBlockRequest {
+                block: block.to_bytes(),
+                stage_durations: None,
+                producer,
+            }))
+        };
+        let provenance = |block_num| async move {
+            let request = Request::new(GetBlockByNumberRequest { block_num });
+            let response = api.get_block_by_number(request).await.unwrap().into_inner();
+            assert!(response.block.is_some());
+            response.provenance.map(|provenance| provenance.producer.unwrap())
+        };
+
+        // Retrying the submission doesn't apply the block again
+        apply_block(Some(producer(1))).await.unwrap();
+        apply_block(Some(producer(2))).await.unwrap();
+        assert_eq!(api.state.latest_block_num().await, 1);
+        assert_eq!(provenance(1).await, Some(producer(2)));
+
+        // Delayed submissions don't replace later attempts, and resubmissions without metadata
+        // keep the recorded provenance
+        apply_block(Some(producer(1))).await.unwrap();
+        apply_block(None).await.unwrap();
+        assert_eq!(provenance(1).await, Some(producer(2)));
+
+        // Blocks submitted without metadata have no provenance
+        let block = chain.next_block().build();
+        api.apply_block(Request::new(ApplyBlockRequest {
+            block: block.to_bytes(),
+            stage_durations: None,
+            producer: None,
+        }))
+        .await
+        .unwrap();
+        assert_eq!(provenance(2).await, None);
+
+        // Other blocks with the number of a committed block are still rejected
+        let mut fork = MockChain::new(&GenesisState::new(vec![], 1, 0).into_block().unwrap());
+        let fork_block = fork.next_block().timestamp(100).build();
+        let result = api
+            .apply_block(Request::new(ApplyBlockRequest {
+                block: fork_block.to_bytes(),
+                stage_durations: None,
+                producer: None,
+            }))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn dropped_apply_block_requests_leave_the_state_consistent() {
+        const MAX_POLLS: u32 = 16;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) = store_api(dir.path()).await;
+
+        // The requests are dropped at every stage of the application of their block, as if their
+        // client had disconnected
+        for polls in 1..=MAX_POLLS {
+            let block = chain.next_block().nullifiers(vec![mock_nullifier(polls)]).build();
+            let block_num = block.header().block_num();
+            let request = || {
+                Request::new(ApplyBlockRequest {
+                    block: block.to_bytes(),
+                    stage_durations: None,
+                    producer: None,
+                })
+            };
+
+            {
+                let mut apply_block = pin!(api.apply_block(request()));
+                for _ in 0..polls {
+                    if poll_fn(|cx| Poll::Ready(apply_block.as_mut().poll(cx).is_ready())).await {
+                        break;
+                    }
+                    tokio::task::yield_now().await;
+                }
+            }
+
+            // The resubmission fails while the dropped request is still applying the block, and
+            // then either applies it, or finds it committed
+            let mut attempts = 0;
+            while api.apply_block(request()).await.is_err() {
+                attempts += 1;
+                assert!(attempts < 100, "block {block_num} was never applied");
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+
+            // The in-memory state must have caught up with the database
+            let (anchor, header, _) = api.state.get_block_header(None, false).await.unwrap();
+            assert_eq!(header.unwrap().block_num(), block_num);
+            assert_eq!(anchor.block_num, block_num);
+        }
+    }
+
+    #[test]
+    fn note_tag_statistics_requests_are_capped() {
+        let request = |from_block, to_block, prefix_len| GetNoteTagStatisticsRequest {
+            from_block,
+            to_block,
+            prefix_len,
+            exclude_network_notes: false,
+        };
+        let is_valid =
+            |request| validate_note_tag_statistics_request(&request).map_err(|err| err.code());
+
+        for prefix_len in NOTE_TAG_STATISTICS_PREFIX_LENS {
+            assert_eq!(is_valid(request(5, 5, prefix_len)), Ok(()));
+        }
+        assert_eq!(is_valid(request(5, 5, 24)), Err(Code::InvalidArgument));
+        assert_eq!(is_valid(request(6, 5, 8)), Err(Code::InvalidArgument));
+
+        // The span includes both ends of the range
+        let last_block = 10 + MAX_NOTE_TAG_STATISTICS_RANGE;
+        assert_eq!(is_valid(request(10, last_block - 1, 8)), Ok(()));
+        assert_eq!(is_valid(request(10, last_block, 8)), Err(Code::InvalidArgument));
+        assert_eq!(is_valid(request(0, u32::MAX, 8)), Err(Code::InvalidArgument));
+    }
+
+    #[tokio::test]
+    async fn account_requests_are_served_by_their_shard() {
+        let dir = tempfile::tempdir().unwrap();
+        let (api, _) = store_api(dir.path()).await;
+        let served = ShardRange { from: 0, to: 127 };
+        let api = &StoreApi { shard_range: served, ..api };
+
+        let sync_state = |shard_keys: &[&str]| {
+            let mut request = Request::new(SyncStateRequest::default());
+            for shard_key in shard_keys {
+                request.metadata_mut().append(SHARD_KEY_METADATA, shard_key.parse().unwrap());
+            }
+            api.sync_state(request)
+        };
+
+        // Requests without shard keys aren't routed, and are always served
+        let response = sync_state(&[]).await.unwrap();
+        assert!(response.metadata().get(SHARD_KEY_METADATA).is_none());
+
+        let response = sync_state(&["0", "127"]).await.unwrap();
+        let echoed: Vec<_> = response
+            .metadata()
+            .get_all(SHARD_KEY_METADATA)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(echoed, ["0", "127"]);
+
+        // A single shard key out of the range rejects the request, with a hint of the range
+        for shard_keys in [&["128"][..], &["5", "200"]] {
+            let err = sync_state(shard_keys).await.unwrap_err();
+            assert_eq!(err.code(), Code::FailedPrecondition);
+            assert_eq!(err.error_chain()[0].kind, "ShardError::WrongShard");
+            let hint = err.metadata().get(SHARD_RANGE_METADATA).unwrap();
+            assert_eq!(hint.to_str().unwrap(), "0-127");
+        }
+
+        let err = sync_state(&["256"]).await.unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn data_availability_reports_pruning_and_note_details_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let (api, _) = store_api(dir.path()).await;
+        let availability = api
+            .get_data_availability(Request::new(GetDataAvailabilityRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(availability.oldest_block_data, 1, "Only the genesis block is served");
+        assert_eq!(availability.block_retention_depth, None);
+        assert_eq!(availability.oldest_account_delta_block, 0);
+        assert_eq!(availability.note_details.unwrap().policy(), NoteDetailsPolicyProto::Full);
+        assert_eq!(availability.mode(), StoreModePb::Archive);
+
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) = store_api_with(dir.path(), |config| {
+            config.block_retention_depth = Some(2);
+            config.note_details = NoteDetailsPolicy::MetadataOnly;
+        })
+        .await;
+        for _ in 0..7 {
+            api.state.apply_block(chain.next_block().build()).await.unwrap();
+        }
+        let get_data_availability = || async {
+            api.get_data_availability(Request::new(GetDataAvailabilityRequest {}))
+                .await
+                .unwrap()
+                .into_inner()
+        };
+
+        let availability = get_data_availability().await;
+        assert_eq!(availability.oldest_block_data, 1, "No block was pruned yet");
+        assert_eq!(availability.block_retention_depth, Some(2));
+        assert_eq!(availability.oldest_account_delta_block, 0);
+        assert_eq!(availability.anchor.unwrap().block_num, 6);
+        assert_eq!(availability.mode(), StoreModePb::Pruned, "Inferred from the pruning options");
+
+        // A retention depth beyond the chain tip retains every block
+        api.state.prune_blocks(u32::MAX).await.unwrap();
+        assert_eq!(get_data_availability().await.oldest_block_data, 1);
+
+        api.state.prune_blocks(2).await.unwrap();
+        let availability = get_data_availability().await;
+        assert_eq!(availability.oldest_block_data, 4);
+        assert_eq!(availability.oldest_account_delta_block, 3);
+        assert_eq!(
+            availability.note_details.unwrap(),
+            NoteDetailsAvailability {
+                policy: NoteDetailsPolicyProto::MetadataOnly.into(),
+                ..Default::default()
+            }
+        );
+
+        // The block store is the reference, e.g. if the operator removed blocks by hand
+        fs::remove_file(dir.path().join("blocks/0000/block_00000004.dat")).unwrap();
+        assert_eq!(get_data_availability().await.oldest_block_data, 5);
+    }
+
+    #[tokio::test]
+    async fn pruned_blocks_keep_their_headers_notes_and_proofs() {
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) =
+            store_api_with(dir.path(), |config| config.block_retention_depth = Some(2)).await;
+        let note = mock_output_note(1);
+        let block = chain.next_block().output_notes(vec![vec![note.clone()]]).build();
+        api.state.apply_block(block).await.unwrap();
+        for _ in 0..5 {
+            api.state.apply_block(chain.next_block().build()).await.unwrap();
+        }
+
+        // The maintenance task prunes the blocks below block 4 on its first run
+        let get_block = |block_num| {
+            api.get_block_by_number(Request::new(GetBlockByNumberRequest { block_num }))
+        };
+        let pruning = tokio::spawn(maintenance::prune_blocks(Arc::clone(&api.state), 2));
+        time::timeout(Duration::from_secs(10), async {
+            while get_block(1).await.is_ok() {
+                time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("block 1 must be pruned by the maintenance task");
+        pruning.abort();
+
+        assert_eq!(get_block(1).await.unwrap_err().code(), Code::FailedPrecondition);
+        assert!(get_block(4).await.unwrap().into_inner().block.is_some());
+
+        let header = api
+            .get_block_header_by_number(Request::new(GetBlockHeaderByNumberRequest {
+                block_num: Some(1),
+                include_mmr_proof: Some(true),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(header.block_header.is_some());
+        assert!(header.mmr_path.is_some());
+
+        let notes = api
+            .get_notes_by_id(Request::new(GetNotesByIdRequest {
+                note_ids: vec![note.id().into()],
+                compress_merkle_paths: false,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .notes;
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].block_num, 1);
+        assert!(notes[0].merkle_path.is_some());
+
+        let proofs = api
+            .get_note_authentication_info(Request::new(GetNoteAuthenticationInfoRequest {
+                note_ids: vec![note.id().into()],
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .proofs
+            .unwrap();
+        assert_eq!(proofs.note_proofs.len(), 1);
+        assert_eq!(proofs.block_proofs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pruned_store_refuses_pruned_history_and_archive_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) =
+            store_api_with(dir.path(), |config| config.mode = Some(StoreMode::Pruned)).await;
+        assert_eq!(api.block_retention_depth, Some(DEFAULT_PRUNED_BLOCK_RETENTION_DEPTH));
+        for _ in 0..5 {
+            api.state.apply_block(chain.next_block().build()).await.unwrap();
+        }
+        api.state.prune_blocks(2).await.unwrap();
+
+        let account_id = mock_account_id(1);
+        let delta = |from_block_num| {
+            api.get_account_state_delta(Request::new(GetAccountStateDeltaRequest {
+                account_id: Some(account_id.into()),
+                from_block_num,
+                to_block_num: 5,
+            }))
+        };
+        assert!(delta(2).await.is_ok());
+        assert_eq!(delta(1).await.unwrap_err().code(), Code::FailedPrecondition);
+
+        let export = api
+            .export_account_deltas(Request::new(ExportAccountDeltasRequest {
+                account_ids: vec![account_id.into()],
+                from_block: 1,
+                to_block: 5,
+                cursor: None,
+            }))
+            .await;
+        assert_eq!(export.unwrap_err().code(), Code::FailedPrecondition);
+
+        // The pruned data can't be recovered, so the store can't be reopened as an archive
+        let config = StoreConfig {
+            database_filepath: dir.path().join("miden-store.sqlite3"),
+            genesis_filepath: dir.path().join("genesis.dat"),
+            blockstore_dir: dir.path().join("blocks"),
+            mode: Some(StoreMode::Archive),
+            ..Default::default()
+        };
+        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+        let err = Db::setup(config.clone(), Arc::clone(&block_store)).await.err().unwrap();
+        assert!(matches!(
+            err,
+            DatabaseSetupError::StoreModeError(StoreModeError::BlocksPruned(3))
+        ));
+
+        // Even if no block was pruned yet
+        fs::remove_file(dir.path().join("blocks/pruned_below")).unwrap();
+        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+        let err = Db::setup(config, block_store).await.err().unwrap();
+        assert!(matches!(
+            err,
+            DatabaseSetupError::StoreModeError(StoreModeError::PreviouslyPruned)
+        ));
+    }
+
+    #[tokio::test]
+    async fn account_updates_are_streamed_after_a_snapshot() {
+        use tokio_stream::StreamExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) = store_api(dir.path()).await;
+        let update = |num: u8| {
+            let hash = RpoDigest::new([Felt::new(num.into()), ZERO, ZERO, ZERO]);
+            BlockAccountUpdate::new(
+                mock_account_id(num),
+                hash,
+                AccountUpdateDetails::Private,
+                vec![],
+            )
+        };
+        let block = chain.next_block().account_updates(vec![update(1)]).build();
+        api.state.apply_block(block).await.unwrap();
+
+        let subscribe = |nums: &[u8]| {
+            let account_ids = nums.iter().map(|num| mock_account_id(*num).into()).collect();
+            api.subscribe_account_updates(Request::new(SubscribeAccountUpdatesRequest {
+                account_ids,
+            }))
+        };
+        let mut stream = subscribe(&[1, 2]).await.unwrap().into_inner();
+
+        for updates in [vec![update(3)], vec![update(3), update(2)]] {
+            let block = chain.next_block().account_updates(updates).build();
+            api.state.apply_block(block).await.unwrap();
+        }
+
+        let Some(AccountUpdate::Snapshot(snapshot)) = stream.next().await.unwrap().unwrap().update
+        else {
+            panic!("The stream must start with a snapshot");
+        };
+        assert_eq!(snapshot.block_num, 1);
+        assert_eq!(snapshot.accounts.len(), 1, "Unknown accounts are omitted");
+        assert_eq!(snapshot.accounts[0].account_id, Some(mock_account_id(1).into()));
+
+        let Some(AccountUpdate::Block(block)) = stream.next().await.unwrap().unwrap().update else {
+            panic!("Snapshots are only sent first");
+        };
+        assert_eq!(block.block_num, 3, "Blocks not updating the accounts are skipped");
+        assert_eq!(block.updates, [account_block_update(&update(2))]);
+
+        let too_many: Vec<u8> = (0..=MAX_SUBSCRIBED_ACCOUNTS as u8).collect();
+        assert_eq!(subscribe(&too_many).await.unwrap_err().code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn bundled_account_proofs_verify_against_their_block_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) = store_api(dir.path()).await;
+        let hash = RpoDigest::new([Felt::new(7), ZERO, ZERO, ZERO]);
+        let update = BlockAccountUpdate::new(
+            mock_account_id(1),
+            hash,
+            AccountUpdateDetails::Private,
+            vec![],
+        );
+        let block = chain.next_block().account_updates(vec![update]).build();
+        api.state.apply_block(block).await.unwrap();
+
+        let response = api
+            .get_account_proofs(Request::new(GetAccountProofsRequest {
+                account_ids: vec![mock_account_id(1).into()],
+                include_headers: Some(true),
+                bundle_proofs: Some(true),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let proof = &response.account_proofs[0];
+        assert!(proof.account_proof.is_none() && proof.account_hash.is_none());
+
+        let bundle = AccountProofBundle::read_from_bytes(proof.bundle.as_ref().unwrap()).unwrap();
+        assert_eq!(bundle.account_hash, hash);
+        let (_, header, _) = api.state.get_block_header(Some(1), false).await.unwrap();
+        bundle.verify(&header.unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn account_proofs_return_the_delta_since_the_known_block() {
+        use generated::{requests::KnownAccountBlock, responses::account_state_delta_info::Data};
+        use miden_objects::{
+            accounts::{
+                account_id::testing::{
+                    ACCOUNT_ID_NON_FUNGIBLE_FAUCET_ON_CHAIN,
+                    ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN,
+                },
+                Account, AccountDelta, AccountId, AccountStorageDelta, AccountVaultDelta,
+            },
+            assets::{Asset, AssetVault, NonFungibleAsset, NonFungibleAssetDetails},
+        };
+
+        use crate::{db::tests::mock_account_code_and_storage, MAX_ACCOUNT_PROOF_DELTA_SIZE};
+
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) =
+            store_api_with(dir.path(), |config| config.block_retention_depth = Some(2)).await;
+        let account_id =
+            AccountId::try_from(ACCOUNT_ID_REGULAR_ACCOUNT_IMMUTABLE_CODE_ON_CHAIN).unwrap();
+        let faucet_id = AccountId::try_from(ACCOUNT_ID_NON_FUNGIBLE_FAUCET_ON_CHAIN).unwrap();
+        let asset = |data: u32| {
+            let details = NonFungibleAssetDetails::new(faucet_id, data.to_le_bytes().to_vec());
+            Asset::NonFungible(NonFungibleAsset::new(&details.unwrap()).unwrap())
+        };
+
+        let (code, storage) = mock_account_code_and_storage(account_id.account_type());
+        let mut account =
+            Account::from_parts(account_id, AssetVault::default(), storage, code, ZERO);
+        let creation = AccountUpdateDetails::New(account.clone());
+        let mut apply = |details: AccountUpdateDetails| {
+            if let AccountUpdateDetails::Delta(delta) = &details {
+                account.apply_delta(delta).unwrap();
+            }
+            let update = BlockAccountUpdate::new(account_id, account.hash(), details, vec![]);
+            let block = chain.next_block().account_updates(vec![update]).build();
+            let state = account.clone();
+            let state_api = Arc::clone(&api.state);
+            async move {
+                state_api.apply_block(block).await.unwrap();
+                state
+            }
+        };
+        let delta = |slot: u64, assets: Vec<Asset>, nonce: u64| {
+            AccountDelta::new(
+                AccountStorageDelta::from_iters([], [(1, [Felt::new(slot), ZERO, ZERO, ZERO])], []),
+                AccountVaultDelta::from_iters(assets, []),
+                Some(Felt::new(nonce)),
+            )
+            .unwrap()
+        };
+        let get_account_proof = |known_block_num| {
+            api.get_account_proofs(Request::new(GetAccountProofsRequest {
+                account_ids: vec![account_id.into()],
+                known_blocks: vec![KnownAccountBlock {
+                    account_id: Some(account_id.into()),
+                    block_num: known_block_num,
+                }],
+                ..Default::default()
+            }))
+        };
+        let state_delta = |response: GetAccountProofsResponse| {
+            let [proof] = response.account_proofs.as_slice() else {
+                panic!("The proof of the account must be returned");
+            };
+            (proof.account_hash, proof.state_delta.clone().unwrap().data.unwrap())
+        };
+
+        // The account is created in block 1, and updated in blocks 2 and 3
+        let known_state = apply(creation).await;
+        apply(AccountUpdateDetails::Delta(delta(10, vec![asset(0)], 1))).await;
+        let latest_state = apply(AccountUpdateDetails::Delta(delta(11, vec![asset(1)], 2))).await;
+
+        let response = get_account_proof(1).await.unwrap().into_inner();
+        let (account_hash, Data::Delta(delta_bytes)) = state_delta(response) else {
+            panic!("The delta since the known block must be returned");
+        };
+        let mut updated_state = known_state;
+        updated_state
+            .apply_delta(&AccountDelta::read_from_bytes(&delta_bytes).unwrap())
+            .unwrap();
+        assert_eq!(updated_state.hash(), latest_state.hash());
+        assert_eq!(account_hash, Some(updated_state.hash().into()));
+
+        // Block 4 adds more assets than fit in a delta returned with the proof
+        let assets: Vec<_> = (2..2500).map(asset).collect();
+        assert!(assets.to_bytes().len() > MAX_ACCOUNT_PROOF_DELTA_SIZE);
+        apply(AccountUpdateDetails::Delta(delta(12, assets, 3))).await;
+        api.state.apply_block(chain.next_block().build()).await.unwrap();
+
+        let response = get_account_proof(3).await.unwrap().into_inner();
+        assert_eq!(state_delta(response).1, Data::FetchDetails(true));
+
+        // Pruning the data of the blocks up to 2 stops serving the deltas since block 1
+        api.state.prune_blocks(2).await.unwrap();
+        let response = get_account_proof(1).await.unwrap().into_inner();
+        assert_eq!(state_delta(response).1, Data::TooOld(true));
+
+        let status = get_account_proof(6).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument, "{status:?}");
+        assert!(status.message().contains("after the chain tip 5"), "{status:?}");
+    }
+
+    #[tokio::test]
+    async fn compact_account_sync_matches_the_per_block_sync() {
+        use miden_objects::accounts::{
+            account_id::testing::ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, AccountDelta,
+            AccountStorageDelta, AccountVaultDelta,
+        };
+
+        const KNOWN_BLOCK: u32 = 10;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) = store_api(dir.path()).await;
+        let faucet_id =
+            miden_objects::accounts::AccountId::try_from(ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN)
+                .unwrap();
+        let private_id = mock_account_id(1);
+
+        // The faucet is updated in 50 consecutive blocks, by a transaction each
+        let conn = rusqlite::Connection::open(dir.path().join("miden-store.sqlite3")).unwrap();
+        for block_num in 1..=50_u32 {
+            let update = |account_id, tag: u64| {
+                let hash = RpoDigest::new([Felt::from(block_num), Felt::new(tag), ZERO, ZERO]);
+                let tx_id =
+                    RpoDigest::new([Felt::from(block_num), Felt::new(tag), ZERO, Felt::new(1)]);
+                BlockAccountUpdate::new(
+                    account_id,
+                    hash,
+                    AccountUpdateDetails::Private,
+                    vec![tx_id.into()],
+                )
+            };
+            let mut updates = vec![update(faucet_id, 1)];
+            if block_num % 10 == 0 {
+                updates.push(update(private_id, 2));
+            }
+            api.state
+                .apply_block(chain.next_block().account_updates(updates).build())
+                .await
+                .unwrap();
+
+            let slot = [Felt::from(block_num), ZERO, ZERO, ZERO];
+            let delta = AccountDelta::new(
+                AccountStorageDelta::from_iters([], [(0, slot)], []),
+                AccountVaultDelta::from_iters([], []),
+                Some(Felt::from(block_num)),
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO account_deltas (account_id, block_num, delta) VALUES (?1, ?2, ?3)",
+                rusqlite::params![u64::from(faucet_id) as i64, block_num, delta.to_bytes()],
+            )
+            .unwrap();
+        }
+
+        let sync_compact = |account_id: miden_objects::accounts::AccountId, known_block| {
+            api.sync_account_compact(Request::new(SyncAccountCompactRequest {
+                account_id: Some(account_id.into()),
+                known_block,
+            }))
+        };
+        let compact = sync_compact(faucet_id, KNOWN_BLOCK).await.unwrap().into_inner();
+
+        // Replay the per-block path: the delta of every block, and the latest synced state
+        let mut merged = AccountDelta::default();
+        let mut updates = 0;
+        for block_num in KNOWN_BLOCK + 1..=50 {
+            let response = api
+                .get_account_state_delta(Request::new(GetAccountStateDeltaRequest {
+                    account_id: Some(faucet_id.into()),
+                    from_block_num: block_num - 1,
+                    to_block_num: block_num,
+                }))
+                .await
+                .unwrap()
+                .into_inner();
+            let delta = AccountDelta::read_from_bytes(&response.delta.unwrap()).unwrap();
+            if delta != AccountDelta::default() {
+                updates += 1;
+            }
+            merged.merge(delta).unwrap();
+        }
+        let synced = api
+            .sync_state(Request::new(SyncStateRequest {
+                block_num: KNOWN_BLOCK,
+                account_ids: vec![faucet_id.into()],
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let [latest] = synced.accounts.as_slice() else {
+            panic!("The faucet must be synced");
+        };
+
+        assert_eq!(compact.account_hash, latest.account_hash);
+        assert_eq!(compact.last_update_block, latest.block_num);
+        assert_eq!(compact.skipped_updates, updates);
+        assert_eq!(compact.skipped_updates, 40);
+        let Some(generated::responses::account_state_delta_info::Data::Delta(delta)) =
+            compact.delta.unwrap().data
+        else {
+            panic!("The delta of the faucet must be returned");
+        };
+        assert_eq!(AccountDelta::read_from_bytes(&delta).unwrap(), merged);
+        assert_eq!(compact.anchor.unwrap().block_num, 50);
+
+        // The deltas of private accounts aren't recorded, only their updates are counted
+        let compact = sync_compact(private_id, KNOWN_BLOCK).await.unwrap().into_inner();
+        assert_eq!(compact.last_update_block, 50);
+        assert_eq!(compact.skipped_updates, 4);
+        assert_eq!(compact.delta, None);
+
+        let err = sync_compact(faucet_id, 51).await.unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn account_deltas_exports_can_be_resumed() {
+        use miden_objects::accounts::{AccountDelta, AccountStorageDelta, AccountVaultDelta};
+        use tokio_stream::StreamExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (mut api, mut chain) = store_api(dir.path()).await;
+        for _ in 0..7 {
+            api.state.apply_block(chain.next_block().build()).await.unwrap();
+        }
+        // Small chunks, so that the export spans several of them
+        api.export_rows_per_second = 30;
+
+        // Every account skips a different block, and the deltas are inserted out of order
+        let mut fixture = vec![];
+        let conn = rusqlite::Connection::open(dir.path().join("miden-store.sqlite3")).unwrap();
+        let blocks = [
+            (3_u8, [1, 2, 3, 4, 5, 6, 7]),
+            (1, [7, 2, 3, 1, 4, 5, 6]),
+            (2, [5, 3, 1, 6, 7, 4, 2]),
+        ];
+        for (num, blocks) in blocks {
+            let account_id = u64::from(mock_account_id(num));
+            for block_num in blocks.into_iter().filter(|block_num| *block_num != u32::from(num) + 2)
+            {
+                let slot = [Felt::from(block_num), Felt::from(num), ZERO, ZERO];
+                let delta = AccountDelta::new(
+                    AccountStorageDelta::from_iters([], [(0, slot)], []),
+                    AccountVaultDelta::from_iters([], []),
+                    Some(Felt::from(block_num)),
+                )
+                .unwrap()
+                .to_bytes();
+                conn.execute(
+                    "INSERT INTO account_deltas (account_id, block_num, delta) VALUES (?1, ?2, ?3)",
+                    rusqlite::params![account_id as i64, block_num, delta],
+                )
+                .unwrap();
+                fixture.push((block_num, account_id, delta));
+            }
+        }
+
+        // Deltas of the exported accounts in the range, ordered by block and by account as stored
+        let mut reference: Vec<_> = fixture
+            .into_iter()
+            .filter(|(block_num, account_id, _)| {
+                (2..=6).contains(block_num) && *account_id != u64::from(mock_account_id(3))
+            })
+            .collect();
+        reference.sort_by_key(|(block_num, account_id, _)| (*block_num, *account_id as i64));
+        assert!(reference.len() > 6, "The export spans several chunks");
+
+        let export = |cursor: Option<AccountDeltaCursor>| {
+            api.export_account_deltas(Request::new(ExportAccountDeltasRequest {
+                account_ids: vec![mock_account_id(1).into(), mock_account_id(2).into()],
+                from_block: 2,
+                to_block: 6,
+                cursor,
+            }))
+        };
+        let exported = |message: ExportAccountDeltasResponse| {
+            (message.block_num, u64::from(message.account_id.unwrap()), message.delta)
+        };
+
+        // The export is interrupted after a few deltas, and resumed from the last cursor
+        let mut stream = export(None).await.unwrap().into_inner();
+        let mut deltas = vec![];
+        let mut cursor = None;
+        for _ in 0..4 {
+            let message = stream.next().await.unwrap().unwrap();
+            cursor = message.cursor;
+            deltas.push(exported(message));
+        }
+        drop(stream);
+
+        let stream = export(cursor).await.unwrap().into_inner();
+        let resumed: Vec<_> = stream.map(|message| exported(message.unwrap())).collect().await;
+        deltas.extend(resumed);
+
+        assert_eq!(deltas, reference);
+
+        let err = api
+            .export_account_deltas(Request::new(ExportAccountDeltasRequest {
+                account_ids: vec![],
+                from_block: 5,
+                to_block: 2,
+                cursor: None,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn supported_block_versions_are_reported_and_enforced() {
+        use miden_node_utils::block_versions::{
+            BlockVersionRange, BlockVersionUpgrade, BlockVersions,
+        };
+
+        let block_versions = BlockVersions {
+            supported: BlockVersionRange { min: 1, max: 1 },
+            upgrade: Some(BlockVersionUpgrade {
+                activation_block: 2,
+                supported: BlockVersionRange { min: 2, max: 3 },
+            }),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let (api, mut chain) =
+            store_api_with(dir.path(), |config| config.block_versions = block_versions).await;
+
+        let response = api
+            .get_supported_block_versions(Request::new(GetSupportedBlockVersionsRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(BlockVersions::try_from(response).unwrap(), block_versions);
+
+        let apply = |block: Block| {
+            api.apply_block(Request::new(ApplyBlockRequest {
+                block: block.to_bytes(),
+                stage_durations: None,
+                producer: None,
+            }))
+        };
+        apply(chain.next_block().build()).await.unwrap();
+        let status = apply(chain.clone().next_block().version(4).build()).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument, "{status:?}");
+        assert!(status.message().contains("unsupported version"), "{status:?}");
+        apply(chain.next_block().version(3).build()).await.unwrap();
+    }
+}
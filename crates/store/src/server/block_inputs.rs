@@ -0,0 +1,170 @@
+//! Pagination of the block inputs.
+//!
+//! Blocks updating very many accounts or consuming very many notes need block inputs larger than
+//! the message size limit of the block producer. Instead, the account states and nullifiers are
+//! returned in pages of at most the configured response size. The first request computes all the
+//! block inputs and keeps them as a snapshot, from which the following pages are served, so that
+//! every page is anchored to the same block whatever blocks are applied in the meantime. The
+//! snapshot is released once its last page is served, or once it expires.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use miden_node_proto::generated::{block::BlockInputsCursor, responses::GetBlockInputsResponse};
+use prost::{encoding::message, Message};
+use tonic::Status;
+
+// BLOCK INPUTS SNAPSHOTS
+// ================================================================================================
+
+/// Block inputs whose pages were not all served yet.
+#[derive(Debug)]
+pub struct BlockInputsSnapshots {
+    /// Time after which the pages of a snapshot can no longer be fetched.
+    lifetime: Duration,
+    next_token: AtomicU64,
+    snapshots: Mutex<HashMap<u64, Snapshot>>,
+}
+
+#[derive(Debug)]
+struct Snapshot {
+    inputs: GetBlockInputsResponse,
+    expires_at: Instant,
+}
+
+impl BlockInputsSnapshots {
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            lifetime,
+            next_token: AtomicU64::new(1),
+            snapshots: Mutex::default(),
+        }
+    }
+
+    /// Returns the first page of `inputs`, keeping them as a snapshot if they don't fit in
+    /// `max_size` bytes.
+    pub fn first_page(
+        &self,
+        inputs: GetBlockInputsResponse,
+        max_size: usize,
+    ) -> GetBlockInputsResponse {
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let cursor = BlockInputsCursor { snapshot: token, ..Default::default() };
+        let page = page(&inputs, cursor, max_size);
+
+        if page.next.is_some() {
+            let expires_at = Instant::now() + self.lifetime;
+            let mut snapshots = self.snapshots.lock().expect("Snapshots lock poisoned");
+            snapshots.retain(|_, snapshot| snapshot.expires_at > Instant::now());
+            snapshots.insert(token, Snapshot { inputs, expires_at });
+        }
+
+        page
+    }
+
+    /// Returns the page of the snapshot starting at `cursor`, releasing the snapshot if it is the
+    /// last one.
+    ///
+    /// Fails with `NotFound` if the snapshot expired, or was already released.
+    pub fn next_page(
+        &self,
+        cursor: BlockInputsCursor,
+        max_size: usize,
+    ) -> Result<GetBlockInputsResponse, Status> {
+        let mut snapshots = self.snapshots.lock().expect("Snapshots lock poisoned");
+        snapshots.retain(|_, snapshot| snapshot.expires_at > Instant::now());
+
+        let snapshot = snapshots.get(&cursor.snapshot).ok_or_else(|| {
+            Status::not_found(format!(
+                "Block inputs snapshot {} expired or was released",
+                cursor.snapshot
+            ))
+        })?;
+        if cursor.account_offset as usize > snapshot.inputs.account_states.len()
+            || cursor.nullifier_offset as usize > snapshot.inputs.nullifiers.len()
+        {
+            return Err(Status::invalid_argument("Block inputs cursor is out of range"));
+        }
+
+        let page = page(&snapshot.inputs, cursor, max_size);
+        if page.next.is_none() {
+            snapshots.remove(&cursor.snapshot);
+        }
+
+        Ok(page)
+    }
+
+    /// Returns the number of snapshots held, including the expired ones not discarded yet.
+    #[cfg(test)]
+    pub fn num_snapshots(&self) -> usize {
+        self.snapshots.lock().expect("Snapshots lock poisoned").len()
+    }
+}
+
+/// Returns the page of `inputs` starting at `cursor`: the account states and then the nullifiers
+/// fitting in `max_size` bytes, at least one of them if any is left.
+///
+/// Every page holds the block header and the chain peaks, and the found notes are only returned
+/// by the first page.
+fn page(
+    inputs: &GetBlockInputsResponse,
+    cursor: BlockInputsCursor,
+    max_size: usize,
+) -> GetBlockInputsResponse {
+    let is_first = cursor.account_offset == 0 && cursor.nullifier_offset == 0;
+    let mut page = GetBlockInputsResponse {
+        block_header: inputs.block_header,
+        mmr_peaks: inputs.mmr_peaks.clone(),
+        account_states: vec![],
+        nullifiers: vec![],
+        found_unauthenticated_notes: if is_first {
+            inputs.found_unauthenticated_notes.clone()
+        } else {
+            None
+        },
+        // Accounted for in the size of the page
+        next: Some(cursor),
+    };
+
+    let mut size = page.encoded_len();
+    let mut fits = |len: usize, is_empty: bool| {
+        size += len;
+        size <= max_size || is_empty
+    };
+
+    let account_states = &inputs.account_states[cursor.account_offset as usize..];
+    for account_state in account_states {
+        if !fits(message::encoded_len(3, account_state), page.account_states.is_empty()) {
+            break;
+        }
+        page.account_states.push(account_state.clone());
+    }
+
+    let all_accounts_fit = page.account_states.len() == account_states.len();
+    let nullifiers = &inputs.nullifiers[cursor.nullifier_offset as usize..];
+    if all_accounts_fit {
+        for nullifier in nullifiers {
+            let is_empty = page.account_states.is_empty() && page.nullifiers.is_empty();
+            if !fits(message::encoded_len(4, nullifier), is_empty) {
+                break;
+            }
+            page.nullifiers.push(nullifier.clone());
+        }
+    }
+
+    page.next = (!all_accounts_fit || page.nullifiers.len() < nullifiers.len()).then(|| {
+        BlockInputsCursor {
+            snapshot: cursor.snapshot,
+            account_offset: cursor.account_offset + page.account_states.len() as u32,
+            nullifier_offset: cursor.nullifier_offset + page.nullifiers.len() as u32,
+        }
+    });
+
+    page
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use tokio::time::{self, MissedTickBehavior};
+use tracing::{error, info};
+
+use crate::{state::State, BLOCK_PRUNING_INTERVAL, COMPONENT};
+
+/// Links the notes committed before their creating transactions were recorded, see
+/// [State::backfill_note_creators].
+pub(super) async fn backfill_note_creators(state: Arc<State>) {
+    match state.backfill_note_creators().await {
+        Ok(0) => {},
+        Ok(num_notes) => info!(target: COMPONENT, num_notes, "Backfilled note creators"),
+        Err(err) => error!(target: COMPONENT, %err, "Failed to backfill note creators"),
+    }
+}
+
+/// Periodically removes the data of blocks older than `retention_depth` from the block store.
+///
+/// The first run happens immediately, which also completes any pruning interrupted by a restart.
+pub(super) async fn prune_blocks(state: Arc<State>, retention_depth: u32) {
+    let mut interval = time::interval(BLOCK_PRUNING_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        match state.prune_blocks(retention_depth).await {
+            Ok(pruned) if pruned.num_blocks > 0 => info!(
+                target: COMPONENT,
+                num_blocks = pruned.num_blocks,
+                freed_bytes = pruned.freed_bytes,
+                "Pruned block store",
+            ),
+            Ok(_) => {},
+            Err(err) => error!(target: COMPONENT, %err, "Failed to prune block store"),
+        }
+    }
+}
@@ -1,14 +1,34 @@
-use std::{net::ToSocketAddrs, sync::Arc};
+use std::{net::SocketAddr, sync::Arc};
 
 use miden_node_proto::generated::store::api_server;
-use miden_node_utils::errors::ApiError;
+use miden_node_utils::{
+    errors::ApiError,
+    freshness::{ChainFreshness, FreshnessLayer},
+    request_id::RequestIdLayer,
+    tasks::TaskSupervisor,
+};
+use miden_objects::{Digest, GENESIS_BLOCK};
 use tokio::net::TcpListener;
 use tokio_stream::wrappers::TcpListenerStream;
-use tracing::info;
+use tracing::{info, instrument};
 
-use crate::{blocks::BlockStore, config::StoreConfig, db::Db, state::State, COMPONENT};
+use self::block_inputs::BlockInputsSnapshots;
+use crate::{
+    blocks::BlockStore,
+    config::StoreConfig,
+    db::Db,
+    note_details::NoteDetailsFilter,
+    state::{State, TimestampValidation},
+    write_limits::WriteLimits,
+    BLOCK_INPUTS_SNAPSHOT_LIFETIME, COMPONENT,
+};
 
 mod api;
+mod block_inputs;
+mod maintenance;
+mod response_size;
+pub mod sharding;
+pub mod sync_params;
 
 /// Represents an initialized store component where the RPC connection is open, but not yet actively
 /// responding to requests.
@@ -19,15 +39,30 @@ mod api;
 pub struct Store {
     api_service: api_server::ApiServer<api::StoreApi>,
     listener: TcpListener,
+    local_addr: SocketAddr,
+    state: Arc<State>,
+    block_retention_depth: Option<u32>,
+    genesis_commitment: Digest,
+    freshness: ChainFreshness,
 }
 
 impl Store {
     /// Loads the required database data and initializes the TCP listener without
     /// serving the API yet. Incoming requests will be queued until [`serve`](Self::serve) is
     /// called.
+    #[instrument(target = "miden-store", name = "store:init", skip_all)]
     pub async fn init(config: StoreConfig) -> Result<Self, ApiError> {
         info!(target: COMPONENT, %config, "Loading database");
 
+        let note_details = NoteDetailsFilter::try_from(&config.note_details)
+            .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?;
+        let write_limits = WriteLimits::try_from(&config.write_limits)
+            .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?;
+        let mode = config
+            .store_mode()
+            .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?;
+        let block_retention_depth = config.retention_depth();
+
         let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await?);
 
         let db = Db::setup(config.clone(), Arc::clone(&block_store))
@@ -37,33 +72,97 @@ impl Store {
         let state = Arc::new(
             State::load(db, block_store)
                 .await
-                .map_err(|err| ApiError::DatabaseConnectionFailed(err.to_string()))?,
+                .map_err(|err| ApiError::DatabaseConnectionFailed(err.to_string()))?
+                .with_mode(mode)
+                .with_note_details(note_details)
+                .with_timestamp_validation(TimestampValidation::from(&config))
+                .with_write_limits(write_limits)
+                .with_block_versions(config.block_versions),
         );
 
-        let api_service = api_server::ApiServer::new(api::StoreApi { state });
+        let genesis_commitment = state
+            .get_block_header(Some(GENESIS_BLOCK), false)
+            .await
+            .map_err(|err| ApiError::DatabaseConnectionFailed(err.to_string()))?
+            .1
+            .ok_or_else(|| {
+                ApiError::ApiInitialisationFailed("The genesis block is missing".to_string())
+            })?
+            .hash();
 
-        let addr = config
-            .endpoint
-            .to_socket_addrs()
-            .map_err(ApiError::EndpointToSocketFailed)?
-            .next()
-            .ok_or_else(|| ApiError::AddressResolutionFailed(config.endpoint.to_string()))?;
+        let api_service = api_server::ApiServer::new(api::StoreApi {
+            state: Arc::clone(&state),
+            max_response_size: config.max_response_size,
+            shard_range: config.shard_range,
+            block_retention_depth,
+            export_rows_per_second: config.export_rows_per_second,
+            block_inputs_snapshots: BlockInputsSnapshots::new(BLOCK_INPUTS_SNAPSHOT_LIFETIME),
+        });
 
-        let listener = TcpListener::bind(addr).await?;
+        let freshness = state.freshness().clone();
+        let listener = config.endpoint.bind().await?;
+        let local_addr = listener.local_addr()?;
 
         info!(target: COMPONENT, "Database loaded");
+        info!(target: COMPONENT, %local_addr, "Server initialized");
+
+        Ok(Self {
+            api_service,
+            listener,
+            local_addr,
+            state,
+            block_retention_depth,
+            genesis_commitment,
+            freshness,
+        })
+    }
 
-        Ok(Self { api_service, listener })
+    /// Returns the address the store listens on, with the port chosen by the system if the
+    /// endpoint's port is 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Returns the commitment of the genesis block of the loaded chain.
+    pub fn genesis_commitment(&self) -> Digest {
+        self.genesis_commitment
+    }
+
+    /// Returns the freshness of the chain served by the store, shared with its state.
+    pub fn freshness(&self) -> &ChainFreshness {
+        &self.freshness
     }
 
     /// Serves the store's RPC API.
     ///
+    /// The background backfill of the notes' creators is started as well, along with the block
+    /// pruning if it is enabled. The server shuts down if one of these tasks panics.
+    ///
     /// Note: this blocks until the server dies.
     pub async fn serve(self) -> Result<(), ApiError> {
+        let supervisor = TaskSupervisor::new(COMPONENT);
+        supervisor.spawn_supervised(
+            "backfill_note_creators",
+            maintenance::backfill_note_creators(Arc::clone(&self.state)),
+        );
+        if let Some(retention_depth) = self.block_retention_depth {
+            supervisor.spawn_supervised(
+                "prune_blocks",
+                maintenance::prune_blocks(self.state, retention_depth),
+            );
+        }
+
         tonic::transport::Server::builder()
+            .layer(RequestIdLayer)
+            .layer(FreshnessLayer::new(self.freshness))
             .add_service(self.api_service)
-            .serve_with_incoming(TcpListenerStream::new(self.listener))
+            .serve_with_incoming_shutdown(
+                TcpListenerStream::new(self.listener),
+                supervisor.wait_for_failure(),
+            )
             .await
-            .map_err(ApiError::ApiServeFailed)
+            .map_err(ApiError::ApiServeFailed)?;
+
+        Ok(supervisor.check()?)
     }
 }
@@ -0,0 +1,99 @@
+//! Bounding of the size of list responses.
+//!
+//! Some endpoints return lists whose size depends on the stored data rather than on the request,
+//! e.g. notes with large details or account proofs with large deltas. Instead of failing at the
+//! transport level once a response exceeds the message size limit of the client, the lists are
+//! truncated to the configured response size, and the responses flag it. Clients fetch the items
+//! which were left out with follow-up requests.
+
+use prost::{encoding::message, Message};
+
+/// Truncates the `items` of the repeated field with the given `tag` so that the response fits in
+/// `max_size` bytes, given the `base_size` of the response without any items.
+///
+/// At least one item is always kept, so that clients repeating truncated requests make progress
+/// even if a single item exceeds the limit.
+///
+/// Returns whether items were left out.
+pub fn truncate_to_size<M: Message>(
+    items: &mut Vec<M>,
+    tag: u32,
+    base_size: usize,
+    max_size: usize,
+) -> bool {
+    let mut size = base_size;
+    let Some(exceeding) = items.iter().position(|item| {
+        size += message::encoded_len(tag, item);
+        size > max_size
+    }) else {
+        return false;
+    };
+
+    let kept = exceeding.max(1);
+    let truncated = kept < items.len();
+    items.truncate(kept);
+
+    truncated
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_node_proto::generated::{
+        note::{Note, NoteMetadata},
+        responses::GetNotesByIdResponse,
+    };
+
+    use super::*;
+
+    fn note(details_len: usize) -> Note {
+        Note {
+            metadata: Some(NoteMetadata::default()),
+            details: Some(vec![7; details_len]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn responses_are_truncated_to_max_size() {
+        const MAX_SIZE: usize = 10_000;
+
+        let all_notes: Vec<_> = (0..10).map(|_| note(3_000)).collect();
+        let mut response = GetNotesByIdResponse {
+            notes: all_notes.clone(),
+            truncated: false,
+            anchor: None,
+        };
+        assert!(response.encoded_len() > MAX_SIZE);
+
+        let base_size = GetNotesByIdResponse {
+            notes: vec![],
+            truncated: true,
+            anchor: None,
+        }
+        .encoded_len();
+        response.truncated = truncate_to_size(&mut response.notes, 1, base_size, MAX_SIZE);
+
+        assert!(response.truncated);
+        assert_eq!(response.notes.len(), 3);
+        assert!(response.encoded_len() <= MAX_SIZE);
+
+        // Responses within the limit are left untouched
+        let mut notes = all_notes[..3].to_vec();
+        assert!(!truncate_to_size(&mut notes, 1, base_size, MAX_SIZE));
+        assert_eq!(notes.len(), 3);
+    }
+
+    #[test]
+    fn oversized_items_are_returned_alone() {
+        let mut notes = vec![note(20_000), note(10)];
+        assert!(truncate_to_size(&mut notes, 1, 0, 10_000));
+        assert_eq!(notes.len(), 1);
+
+        let mut notes = vec![note(20_000)];
+        assert!(!truncate_to_size(&mut notes, 1, 0, 10_000));
+        assert_eq!(notes.len(), 1);
+    }
+}
@@ -0,0 +1,117 @@
+//! Routing metadata of the account requests.
+//!
+//! The accounts are meant to be split between stores by their shard key, derived from the bits of
+//! the account ID right below its storage mode and type. The RPC attaches the shard keys of the
+//! requested accounts to the account requests it forwards, in the [SHARD_KEY_METADATA] metadata,
+//! and the store echoes them back in its responses. A store only serving some of the shard keys
+//! rejects the requests for the other ones with a `WrongShard` error, whose [SHARD_RANGE_METADATA]
+//! metadata is the range of shard keys it serves.
+//!
+//! Requests without shard keys, e.g. those of the block producer, are always served.
+
+use std::collections::BTreeSet;
+
+use miden_node_proto::errors::ErrorChainHelper;
+use thiserror::Error;
+use tonic::{
+    metadata::{AsciiMetadataValue, MetadataMap},
+    Request, Response, Status,
+};
+
+use crate::{config::ShardRange, COMPONENT};
+
+/// Metadata holding the shard keys of the accounts of a request, one value per shard key.
+pub const SHARD_KEY_METADATA: &str = "x-miden-shard-key";
+
+/// Metadata holding the range of shard keys served by the store, as `<from>-<to>`.
+pub const SHARD_RANGE_METADATA: &str = "x-miden-shard-range";
+
+/// Number of most significant bits of an account ID encoding its storage mode and type, which
+/// are skipped by the shard key.
+const ACCOUNT_ID_METADATA_BITS: u32 = 4;
+
+/// Returns the shard key of the account.
+pub fn shard_key(account_id: u64) -> u8 {
+    (account_id << ACCOUNT_ID_METADATA_BITS >> (u64::BITS - u8::BITS)) as u8
+}
+
+/// Replaces the shard keys of the request by the distinct shard keys of the accounts.
+pub fn attach_shard_keys<T>(request: &mut Request<T>, account_ids: impl IntoIterator<Item = u64>) {
+    let metadata = request.metadata_mut();
+    metadata.remove(SHARD_KEY_METADATA);
+
+    let shard_keys: BTreeSet<u8> = account_ids.into_iter().map(shard_key).collect();
+    for shard_key in shard_keys {
+        metadata.append(SHARD_KEY_METADATA, AsciiMetadataValue::from(u16::from(shard_key)));
+    }
+}
+
+// SHARD KEYS
+// ================================================================================================
+
+#[derive(Debug, Error)]
+pub enum ShardError {
+    #[error("Invalid shard key metadata: {0}")]
+    InvalidShardKey(String),
+    #[error("Shard key {shard_key} is not served by this store, which serves shard keys {served}")]
+    WrongShard { shard_key: u8, served: ShardRange },
+}
+
+impl From<ShardError> for Status {
+    fn from(err: ShardError) -> Self {
+        let status = match err {
+            ShardError::InvalidShardKey(_) => Status::invalid_argument(err.to_string()),
+            ShardError::WrongShard { served, .. } => {
+                let mut status = Status::failed_precondition(err.to_string());
+                let served = served.to_string().parse().expect("Shard ranges are valid metadata");
+                status.metadata_mut().insert(SHARD_RANGE_METADATA, served);
+                status
+            },
+        };
+
+        status.with_error_source(COMPONENT, &err)
+    }
+}
+
+/// Shard keys of a request served by the store.
+#[derive(Debug)]
+pub(crate) struct ShardKeys(BTreeSet<u8>);
+
+impl ShardKeys {
+    /// Returns the shard keys of the request, after checking they are all in the `served` range.
+    pub fn check<T>(request: &Request<T>, served: ShardRange) -> Result<Self, ShardError> {
+        let shard_keys = shard_keys(request.metadata())?;
+
+        if let Some(&shard_key) = shard_keys.iter().find(|shard_key| !served.contains(**shard_key))
+        {
+            return Err(ShardError::WrongShard { shard_key, served });
+        }
+
+        Ok(Self(shard_keys))
+    }
+
+    /// Echoes the shard keys in the metadata of the response.
+    pub fn echo<T>(&self, mut response: Response<T>) -> Response<T> {
+        let metadata = response.metadata_mut();
+        for &shard_key in &self.0 {
+            metadata.append(SHARD_KEY_METADATA, AsciiMetadataValue::from(u16::from(shard_key)));
+        }
+
+        response
+    }
+}
+
+/// Returns the shard keys in the metadata.
+pub fn shard_keys(metadata: &MetadataMap) -> Result<BTreeSet<u8>, ShardError> {
+    metadata
+        .get_all(SHARD_KEY_METADATA)
+        .iter()
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| ShardError::InvalidShardKey(format!("{value:?}")))
+        })
+        .collect()
+}
@@ -0,0 +1,215 @@
+//! Normalization of the parameters of the sync requests.
+//!
+//! Clients sometimes repeat the same account ID, note tag or nullifier prefix many times in a
+//! single request. The sync queries are set-based, so duplicates don't change the response, but
+//! they inflate the array parameters of the queries and skew the estimates of the query planner.
+//! The parameters are therefore deduplicated before the queries are built, and the number of
+//! distinct values is capped.
+//!
+//! The nullifier prefixes are also checked against the layout of
+//! [miden_node_proto::domain::prefixes], as a prefix of another length matches no nullifier.
+
+use miden_node_proto::{
+    domain::prefixes::{is_nullifier_prefix, NULLIFIER_PREFIX_LEN},
+    generated::requests::{SyncNoteRequest, SyncStateRequest},
+};
+use tonic::Status;
+use tracing::Span;
+
+/// Maximum number of distinct account IDs in a sync request.
+pub const MAX_SYNC_ACCOUNT_IDS: usize = 1000;
+
+/// Maximum number of distinct note tags in a sync request.
+pub const MAX_SYNC_NOTE_TAGS: usize = 1000;
+
+/// Maximum number of distinct nullifier prefixes in a sync request.
+pub const MAX_SYNC_NULLIFIER_PREFIXES: usize = 1000;
+
+/// Deduplicates the parameters of a [SyncStateRequest].
+///
+/// The requested and distinct numbers of values of every parameter are recorded in the
+/// `account_ids`, `unique_account_ids`, `note_tags`, `unique_note_tags`, `nullifier_prefixes` and
+/// `unique_nullifier_prefixes` fields of the current span.
+///
+/// # Errors
+/// Returns an `INVALID_ARGUMENT` status if a parameter has too many distinct values, or if a
+/// nullifier prefix is invalid.
+pub fn normalize_sync_state_request(request: &mut SyncStateRequest) -> Result<(), Status> {
+    let span = Span::current();
+
+    span.record("account_ids", request.account_ids.len());
+    request.account_ids.sort_unstable_by_key(|account_id| account_id.id);
+    request.account_ids.dedup_by_key(|account_id| account_id.id);
+    span.record("unique_account_ids", request.account_ids.len());
+    check_limit("account IDs", request.account_ids.len(), MAX_SYNC_ACCOUNT_IDS)?;
+
+    normalize_note_tags(&mut request.note_tags)?;
+
+    span.record("nullifier_prefixes", request.nullifiers.len());
+    dedup(&mut request.nullifiers);
+    span.record("unique_nullifier_prefixes", request.nullifiers.len());
+    check_limit("nullifier prefixes", request.nullifiers.len(), MAX_SYNC_NULLIFIER_PREFIXES)?;
+    check_nullifier_prefixes(NULLIFIER_PREFIX_LEN, &request.nullifiers)
+}
+
+/// Checks that the nullifier prefixes have the supported length and fit in it.
+///
+/// # Errors
+/// Returns an `INVALID_ARGUMENT` status if `prefix_len` isn't [NULLIFIER_PREFIX_LEN], or if a
+/// prefix has more bits.
+pub fn check_nullifier_prefixes(prefix_len: u32, prefixes: &[u32]) -> Result<(), Status> {
+    if prefix_len != NULLIFIER_PREFIX_LEN {
+        return Err(Status::invalid_argument(format!(
+            "Only {NULLIFIER_PREFIX_LEN}-bit prefixes are supported"
+        )));
+    }
+    if let Some(prefix) = prefixes.iter().find(|prefix| !is_nullifier_prefix(**prefix)) {
+        return Err(Status::invalid_argument(format!(
+            "Invalid nullifier prefix {prefix:#x}, prefixes are {NULLIFIER_PREFIX_LEN} bits"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Deduplicates the parameters of a [SyncNoteRequest].
+///
+/// The requested and distinct numbers of note tags are recorded in the `note_tags` and
+/// `unique_note_tags` fields of the current span.
+///
+/// # Errors
+/// Returns an `INVALID_ARGUMENT` status if the request has too many distinct note tags.
+pub fn normalize_sync_note_request(request: &mut SyncNoteRequest) -> Result<(), Status> {
+    normalize_note_tags(&mut request.note_tags)
+}
+
+fn normalize_note_tags(note_tags: &mut Vec<u32>) -> Result<(), Status> {
+    let span = Span::current();
+
+    span.record("note_tags", note_tags.len());
+    dedup(note_tags);
+    span.record("unique_note_tags", note_tags.len());
+    check_limit("note tags", note_tags.len(), MAX_SYNC_NOTE_TAGS)
+}
+
+fn dedup(values: &mut Vec<u32>) {
+    values.sort_unstable();
+    values.dedup();
+}
+
+fn check_limit(name: &str, count: usize, limit: usize) -> Result<(), Status> {
+    if count > limit {
+        return Err(Status::invalid_argument(format!(
+            "Too many {name} requested: {count}, limit: {limit}"
+        )));
+    }
+
+    Ok(())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_node_proto::generated::account::AccountId;
+
+    use super::*;
+
+    fn account_ids(ids: impl IntoIterator<Item = u64>) -> Vec<AccountId> {
+        ids.into_iter().map(|id| AccountId { id }).collect()
+    }
+
+    #[test]
+    fn duplicated_params_are_removed() {
+        let mut request = SyncStateRequest {
+            block_num: 7,
+            account_ids: account_ids([3, 1, 3, 3, 2, 1].repeat(20)),
+            note_tags: [5, 5, 9, 5, 1].repeat(50),
+            nullifiers: vec![42; 100],
+            compress_merkle_paths: true,
+        };
+        let requested_params =
+            request.account_ids.len() + request.note_tags.len() + request.nullifiers.len();
+
+        normalize_sync_state_request(&mut request).unwrap();
+
+        assert_eq!(
+            request,
+            SyncStateRequest {
+                block_num: 7,
+                account_ids: account_ids([1, 2, 3]),
+                note_tags: vec![1, 5, 9],
+                nullifiers: vec![42],
+                compress_merkle_paths: true,
+            }
+        );
+
+        // The query parameters shrink from 420 to 7 values
+        assert_eq!(requested_params, 420);
+        assert_eq!(
+            request.account_ids.len() + request.note_tags.len() + request.nullifiers.len(),
+            7
+        );
+
+        let mut request = SyncNoteRequest {
+            block_num: 7,
+            note_tags: [5, 5, 9, 5, 1].repeat(50),
+            compress_merkle_paths: false,
+        };
+        normalize_sync_note_request(&mut request).unwrap();
+        assert_eq!(request.note_tags, [1, 5, 9]);
+    }
+
+    #[test]
+    fn limits_apply_to_distinct_params() {
+        // Duplicates don't count towards the limits
+        let mut request = SyncStateRequest {
+            note_tags: vec![1; MAX_SYNC_NOTE_TAGS * 2],
+            ..Default::default()
+        };
+        assert!(normalize_sync_state_request(&mut request).is_ok());
+
+        let cases = [
+            SyncStateRequest {
+                account_ids: account_ids(0..=MAX_SYNC_ACCOUNT_IDS as u64),
+                ..Default::default()
+            },
+            SyncStateRequest {
+                note_tags: (0..=MAX_SYNC_NOTE_TAGS as u32).collect(),
+                ..Default::default()
+            },
+            SyncStateRequest {
+                nullifiers: (0..=MAX_SYNC_NULLIFIER_PREFIXES as u32).collect(),
+                ..Default::default()
+            },
+        ];
+        for mut request in cases {
+            let err = normalize_sync_state_request(&mut request).unwrap_err();
+            assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        }
+
+        let mut request = SyncNoteRequest {
+            note_tags: (0..=MAX_SYNC_NOTE_TAGS as u32).collect(),
+            ..Default::default()
+        };
+        assert!(normalize_sync_note_request(&mut request).is_err());
+    }
+
+    #[test]
+    fn nullifier_prefixes_must_fit_in_their_length() {
+        assert!(check_nullifier_prefixes(NULLIFIER_PREFIX_LEN, &[0, 0xffff]).is_ok());
+
+        let err = check_nullifier_prefixes(8, &[0x12]).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        let err = check_nullifier_prefixes(NULLIFIER_PREFIX_LEN, &[0x1234, 0x1_0000]).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        let mut request = SyncStateRequest {
+            nullifiers: vec![u32::MAX],
+            ..Default::default()
+        };
+        let err = normalize_sync_state_request(&mut request).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+}
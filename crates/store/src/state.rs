@@ -4,21 +4,40 @@
 //! data is atomically written, and that reads are consistent.
 
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    future::Future,
+    io,
     ops::Not,
     sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use miden_node_proto::{
     convert,
-    domain::{accounts::AccountInfo, blocks::BlockInclusionProof, notes::NoteAuthenticationInfo},
-    generated::responses::{AccountProofsResponse, AccountStateHeader, GetBlockInputsResponse},
+    domain::{
+        accounts::{AccountInfo, AccountSummary},
+        blocks::BlockInclusionProof,
+        notes::NoteAuthenticationInfo,
+    },
+    generated::{
+        block::ChainAnchor,
+        responses::{
+            account_state_delta_info, AccountProofsResponse, AccountStateDeltaInfo,
+            AccountStateHeader, GetBlockInputsResponse,
+        },
+    },
     AccountInputRecord, NullifierWitness,
 };
-use miden_node_utils::formatting::{format_account_id, format_array};
+use miden_node_utils::{
+    block_num::BlockNumberExt,
+    block_versions::BlockVersions,
+    formatting::{format_account_id, format_array},
+    freshness::ChainFreshness,
+    notes::validate_output_note,
+};
 use miden_objects::{
     accounts::{AccountDelta, AccountHeader},
-    block::Block,
+    block::{Block, BlockNoteIndex},
     crypto::{
         hash::rpo::RpoDigest,
         merkle::{
@@ -26,28 +45,46 @@ use miden_objects::{
         },
     },
     notes::{NoteId, Nullifier},
-    transaction::OutputNote,
-    utils::Serializable,
-    AccountError, BlockHeader, ACCOUNT_TREE_DEPTH,
+    transaction::{OutputNote, TransactionId},
+    utils::{Deserializable, Serializable},
+    AccountError, BlockHeader, ACCOUNT_TREE_DEPTH, GENESIS_BLOCK,
 };
 use tokio::{
     sync::{oneshot, Mutex, RwLock},
     time::Instant,
 };
-use tracing::{info, info_span, instrument};
+use tracing::{info, info_span, instrument, Instrument};
 
 use crate::{
-    blocks::BlockStore,
-    db::{Db, NoteRecord, NoteSyncUpdate, NullifierInfo, StateSyncUpdate},
+    account_tree::AccountTree,
+    account_updates::{AccountUpdateEvents, AccountUpdatesSubscription, BlockAccountUpdates},
+    accounts::{storage_slot_headers, vault_assets_page, StorageSlotHeader, VaultAsset},
+    block_provenance::BlockProvenance,
+    block_stats::BlockProductionStats,
+    blocks::{BlockStore, PrunedBlocks},
+    chain_stats::ChainStats,
+    config::{StoreConfig, StoreMode, DEFAULT_MAX_BLOCK_TIMESTAMP_DRIFT_SECS},
+    db::{
+        AccountDeltaRecord, BlockNoteFilter, Db, NoteConsumption, NoteRecord, NoteSyncUpdate,
+        NoteTagBucket, NullifierInfo, PoolStats, StateSyncUpdate, TransactionSummary,
+    },
     errors::{
-        ApplyBlockError, DatabaseError, GetBlockHeaderError, GetBlockInputsError,
-        GetNoteInclusionProofError, InvalidBlockError, NoteSyncError, StateInitializationError,
-        StateSyncError,
+        ApplyBlockError, DatabaseError, GetAccountWitnessesError, GetBlockError,
+        GetBlockHeaderError, GetBlockInputsError, GetNoteInclusionProofError, InvalidBlockError,
+        NoteSyncError, StateInitializationError, StateSyncError,
     },
+    load_progress::{LoadPhase, LoadProgressEvent, LoadSettings, ProgressTracker},
+    note_details::NoteDetailsFilter,
     nullifier_tree::NullifierTree,
     types::{AccountId, BlockNumber},
-    COMPONENT,
+    write_limits::WriteLimits,
+    ACCOUNT_TREE_REVISIONS, COMPONENT, MAX_ACCOUNT_PROOF_DELTA_SIZE, RECENT_BLOCK_HEADERS,
 };
+#[cfg(test)]
+mod concurrency;
+#[cfg(test)]
+mod fixtures;
+
 // STRUCTURES
 // ================================================================================================
 
@@ -78,6 +115,7 @@ impl From<BlockInputs> for GetBlockInputsResponse {
             account_states: convert(value.account_states),
             nullifiers: convert(value.nullifiers),
             found_unauthenticated_notes: Some(value.found_unauthenticated_notes.into()),
+            next: None,
         }
     }
 }
@@ -87,13 +125,105 @@ pub struct TransactionInputs {
     pub account_hash: RpoDigest,
     pub nullifiers: Vec<NullifierInfo>,
     pub missing_unauthenticated_notes: Vec<NoteId>,
+    /// Number of the transaction's reference block, if the block is part of the chain.
+    pub reference_block_num: Option<BlockNumber>,
+}
+
+/// Latest state of an account along with its changes since a known block, see
+/// [State::sync_account_compact].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountSyncCompact {
+    /// Latest commitment to the account's state, and block of its latest update.
+    pub summary: AccountSummary,
+    /// Number of blocks updating the account since the known block.
+    pub skipped_updates: u32,
+    /// Delta of the account's state since the known block, `None` for private accounts.
+    pub delta: Option<AccountStateDeltaInfo>,
+}
+
+/// Historical data served by the store, see [State::get_data_availability].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataAvailability {
+    /// Oldest block whose full data is served besides the genesis block, or the block after the
+    /// chain tip if there is none.
+    pub oldest_block_data: BlockNumber,
+    /// Oldest block known to clients since which the state deltas of public accounts are served.
+    pub oldest_account_delta_block: BlockNumber,
+    /// Which details of the public notes of the applied blocks are stored.
+    pub note_details: NoteDetailsFilter,
+    /// Which historical data the store keeps.
+    pub mode: StoreMode,
+}
+
+/// Validation of the timestamps of the blocks applied to the state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampValidation {
+    /// Blocks must be more recent than the chain tip, and at most `max_drift` ahead of the
+    /// store's clock.
+    Enabled { max_drift: Duration },
+    /// Timestamps are not validated, e.g. to reimport historical blocks.
+    Disabled,
+}
+
+impl TimestampValidation {
+    /// Checks the `timestamp` of a new block, given the timestamp of the chain tip.
+    fn validate(&self, timestamp: u32, prev_timestamp: u32) -> Result<(), InvalidBlockError> {
+        let Self::Enabled { max_drift } = self else {
+            return Ok(());
+        };
+
+        if timestamp <= prev_timestamp {
+            return Err(InvalidBlockError::NewBlockTimestampNotIncreasing {
+                timestamp,
+                prev_timestamp,
+            });
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("today is expected to be after 1970")
+            .as_secs();
+        if u64::from(timestamp) > now.saturating_add(max_drift.as_secs()) {
+            return Err(InvalidBlockError::NewBlockTimestampInFuture {
+                timestamp,
+                now,
+                max_drift_secs: max_drift.as_secs(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for TimestampValidation {
+    fn default() -> Self {
+        Self::Enabled {
+            max_drift: Duration::from_secs(DEFAULT_MAX_BLOCK_TIMESTAMP_DRIFT_SECS),
+        }
+    }
+}
+
+impl From<&StoreConfig> for TimestampValidation {
+    fn from(config: &StoreConfig) -> Self {
+        if config.skip_block_timestamp_validation {
+            Self::Disabled
+        } else {
+            Self::Enabled {
+                max_drift: Duration::from_secs(config.max_block_timestamp_drift_secs),
+            }
+        }
+    }
 }
 
 /// Container for state that needs to be updated atomically.
 struct InnerState {
     nullifier_tree: NullifierTree,
     chain_mmr: Mmr,
-    account_tree: SimpleSmt<ACCOUNT_TREE_DEPTH>,
+    /// Maps the hashes of all blocks in the chain to their block numbers.
+    block_nums: BTreeMap<RpoDigest, BlockNumber>,
+    /// Headers of the [RECENT_BLOCK_HEADERS] most recent blocks, in ascending block number order.
+    recent_headers: VecDeque<BlockHeader>,
+    account_tree: AccountTree,
 }
 
 impl InnerState {
@@ -103,6 +233,34 @@ impl InnerState {
             .try_into()
             .expect("chain_mmr always has, at least, the genesis block")
     }
+
+    /// Returns the anchor of the latest block.
+    fn anchor(&self) -> ChainAnchor {
+        let block_num = self.latest_block_num();
+        let block_commitment = self
+            .chain_mmr
+            .get(block_num as usize)
+            .expect("the latest block is part of chain_mmr");
+
+        ChainAnchor::new(block_num, block_commitment)
+    }
+
+    /// Returns the header of the block, if it is one of the most recent blocks.
+    fn recent_header(&self, block_num: BlockNumber) -> Option<BlockHeader> {
+        let oldest = self.recent_headers.front()?.block_num();
+        let index = block_num.checked_sub(oldest)?;
+
+        self.recent_headers.get(index as usize).copied()
+    }
+
+    /// Adds the header of the latest block to the recent headers, evicting the oldest one if
+    /// needed.
+    fn push_recent_header(&mut self, header: BlockHeader) {
+        self.recent_headers.push_back(header);
+        if self.recent_headers.len() > RECENT_BLOCK_HEADERS {
+            self.recent_headers.pop_front();
+        }
+    }
 }
 
 /// The rollup state
@@ -122,25 +280,135 @@ pub struct State {
     /// To allow readers to access the tree data while an update in being performed, and prevent
     /// TOCTOU issues, there must be no concurrent writers. This locks to serialize the writers.
     writer: Mutex<()>,
+
+    /// Which historical data the store keeps.
+    mode: StoreMode,
+
+    /// Decides which details of the public notes of the applied blocks are stored.
+    note_details: NoteDetailsFilter,
+
+    /// Validation of the timestamps of the applied blocks.
+    timestamp_validation: TimestampValidation,
+
+    /// Limits on the size of the applied blocks.
+    write_limits: WriteLimits,
+
+    /// Versions of the applied blocks accepted at every height.
+    block_versions: BlockVersions,
+
+    /// Time at which the latest block was applied, reported to the clients.
+    freshness: ChainFreshness,
+
+    /// Notifies the subscriptions of the account updates of the applied blocks.
+    account_updates: AccountUpdateEvents,
 }
 
 impl State {
     /// Loads the state from the `db`.
-    #[instrument(target = "miden-store", skip_all)]
     pub async fn load(
-        mut db: Db,
+        db: Db,
         block_store: Arc<BlockStore>,
     ) -> Result<Self, StateInitializationError> {
-        let nullifier_tree = load_nullifier_tree(&mut db).await?;
-        let chain_mmr = load_mmr(&mut db).await?;
-        let account_tree = load_accounts(&mut db).await?;
+        Self::load_with_progress(db, block_store, LoadSettings::default(), &mut |_| {}).await
+    }
 
-        let inner = RwLock::new(InnerState { nullifier_tree, chain_mmr, account_tree });
+    /// Loads the state from the `db`, reading the rows in chunks.
+    ///
+    /// The progress of every phase of the loading is logged and passed to `on_progress`, as
+    /// configured by the `settings`.
+    #[instrument(target = "miden-store", skip_all)]
+    pub async fn load_with_progress<F>(
+        mut db: Db,
+        block_store: Arc<BlockStore>,
+        settings: LoadSettings,
+        on_progress: &mut F,
+    ) -> Result<Self, StateInitializationError>
+    where
+        F: FnMut(LoadProgressEvent) + Send,
+    {
+        let nullifier_tree = load_nullifier_tree(&mut db, settings, on_progress).await?;
+        let (chain_mmr, block_nums, recent_headers) =
+            load_chain(&mut db, settings, on_progress).await?;
+        let account_tree = load_accounts(&mut db, settings, on_progress).await?;
+        let latest_block_num = (chain_mmr.forest() - 1)
+            .try_into()
+            .expect("chain_mmr always has, at least, the genesis block");
+        let account_tree = AccountTree::new(account_tree, latest_block_num, ACCOUNT_TREE_REVISIONS);
+
+        // The time at which the chain tip was applied before the restart is unknown, its
+        // timestamp is the closest approximation
+        let freshness = ChainFreshness::new(
+            recent_headers
+                .back()
+                .filter(|header| header.block_num() != GENESIS_BLOCK)
+                .map(|header| header.timestamp().into()),
+        );
+
+        let inner = RwLock::new(InnerState {
+            nullifier_tree,
+            chain_mmr,
+            block_nums,
+            recent_headers,
+            account_tree,
+        });
 
         let writer = Mutex::new(());
         let db = Arc::new(db);
 
-        Ok(Self { db, block_store, inner, writer })
+        Ok(Self {
+            db,
+            block_store,
+            inner,
+            writer,
+            mode: StoreMode::Archive,
+            note_details: NoteDetailsFilter::default(),
+            timestamp_validation: TimestampValidation::default(),
+            write_limits: WriteLimits::default(),
+            block_versions: BlockVersions::default(),
+            freshness,
+            account_updates: AccountUpdateEvents::default(),
+        })
+    }
+
+    /// Sets which historical data the store keeps.
+    pub fn with_mode(mut self, mode: StoreMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets which details of the public notes of the blocks applied from now on are stored.
+    pub fn with_note_details(mut self, note_details: NoteDetailsFilter) -> Self {
+        self.note_details = note_details;
+        self
+    }
+
+    /// Sets the validation of the timestamps of the blocks applied from now on.
+    pub fn with_timestamp_validation(mut self, timestamp_validation: TimestampValidation) -> Self {
+        self.timestamp_validation = timestamp_validation;
+        self
+    }
+
+    /// Returns the time at which the latest block was applied, shared with the server reporting
+    /// it.
+    pub fn freshness(&self) -> &ChainFreshness {
+        &self.freshness
+    }
+
+    /// Sets the limits on the size of the blocks applied from now on.
+    pub fn with_write_limits(mut self, write_limits: WriteLimits) -> Self {
+        self.write_limits = write_limits;
+        self
+    }
+
+    /// Sets the versions of the blocks accepted from now on.
+    pub fn with_block_versions(mut self, block_versions: BlockVersions) -> Self {
+        self.block_versions = block_versions;
+        self
+    }
+
+    /// Returns the versions of the blocks accepted by the state.
+    pub fn block_versions(&self) -> BlockVersions {
+        self.block_versions
     }
 
     /// Apply changes of a new block to the DB and in-memory data structures.
@@ -166,7 +434,12 @@ impl State {
     ///   use the fresh data.
     /// - the in-memory structures are updated, including the latest block pointer and the lock is
     ///   released.
-    // TODO: This span is logged in a root span, we should connect it to the parent span.
+    ///
+    /// ## Cancellation
+    ///
+    /// The returned future must be polled to completion: dropping it after the DB transaction was
+    /// committed, but before the in-memory structures were updated, leaves the DB ahead of them.
+    /// Callers which may be cancelled, e.g. request handlers, must run it in its own task.
     #[instrument(target = "miden-store", skip_all, err)]
     pub async fn apply_block(&self, block: Block) -> Result<(), ApplyBlockError> {
         let _lock = self.writer.try_lock().map_err(|_| ApplyBlockError::ConcurrentWrite)?;
@@ -182,6 +455,13 @@ impl State {
             .into());
         }
 
+        self.block_versions
+            .check(header.block_num(), header.version())
+            .map_err(InvalidBlockError::from)?;
+
+        // Oversized blocks are rejected before anything is written, including the block file
+        self.write_limits.check_block(&block)?;
+
         let block_num = header.block_num();
         let block_hash = block.hash();
 
@@ -192,12 +472,13 @@ impl State {
             .await?
             .ok_or(ApplyBlockError::DbBlockHeaderEmpty)?;
 
-        if block_num != prev_block.block_num() + 1 {
+        if Some(block_num) != prev_block.block_num().child() {
             return Err(InvalidBlockError::NewBlockInvalidBlockNum.into());
         }
         if header.prev_hash() != prev_block.hash() {
             return Err(InvalidBlockError::NewBlockInvalidPrevHash.into());
         }
+        self.timestamp_validation.validate(header.timestamp(), prev_block.timestamp())?;
 
         let block_data = block.to_bytes();
 
@@ -207,8 +488,9 @@ impl State {
         // finalized blocks. So we should check for the latest block when getting block from
         // the store.
         let store = Arc::clone(&self.block_store);
-        let block_save_task =
-            tokio::spawn(async move { store.save_block(block_num, &block_data).await });
+        let block_save_task = tokio::spawn(
+            async move { store.save_block(block_num, &block_data).await }.in_current_span(),
+        );
 
         // scope to read in-memory data, compute mutations required for updating account
         // and nullifier trees, and validate the request
@@ -251,14 +533,13 @@ impl State {
             }
 
             // compute update for account tree
-            let account_tree_update = inner.account_tree.compute_mutations(
-                block.updated_accounts().iter().map(|update| {
+            let account_tree_update =
+                inner.account_tree.compute_update(block.updated_accounts().iter().map(|update| {
                     (
                         LeafIndex::new_max_depth(update.account_id().into()),
                         update.new_state_hash().into(),
                     )
-                }),
-            );
+                }));
 
             if account_tree_update.root() != header.account_root() {
                 return Err(InvalidBlockError::NewBlockInvalidAccountRoot.into());
@@ -278,12 +559,20 @@ impl State {
             return Err(InvalidBlockError::NewBlockInvalidNoteRoot.into());
         }
 
+        let note_creators = note_creators(&block);
         let notes = block
             .notes()
             .map(|(note_index, note)| {
-                let details = match note {
-                    OutputNote::Full(note) => Some(note.to_bytes()),
-                    OutputNote::Header(_) => None,
+                validate_output_note(note).map_err(|source| {
+                    InvalidBlockError::InvalidOutputNote { note_id: note.id(), source }
+                })?;
+
+                let (details, nullifier) = match note {
+                    OutputNote::Full(note) => (
+                        self.note_details.retains(note).then(|| note.to_bytes()),
+                        Some(note.nullifier()),
+                    ),
+                    OutputNote::Header(_) => (None, None),
                     note => {
                         return Err(InvalidBlockError::InvalidOutputNoteType(Box::new(
                             note.clone(),
@@ -292,6 +581,7 @@ impl State {
                 };
 
                 let merkle_path = note_tree.get_note_path(note_index);
+                self.write_limits.check_note_path(note.id(), &merkle_path)?;
 
                 Ok(NoteRecord {
                     block_num,
@@ -300,6 +590,8 @@ impl State {
                     metadata: *note.metadata(),
                     details,
                     merkle_path,
+                    created_by_tx: note_creators.get(&note.id()).copied(),
+                    nullifier,
                 })
             })
             .collect::<Result<Vec<NoteRecord>, InvalidBlockError>>()?;
@@ -313,11 +605,15 @@ impl State {
         // overlapping. Namely, the DB transaction only proceeds after this task acquires the
         // in-memory write lock. This requires the DB update to run concurrently, so a new task is
         // spawned.
+        // Subscriptions taken while the block is applied can't have seen it in their snapshot, so
+        // the updates are published even if there is no subscription yet
+        let account_updates = block.updated_accounts().to_vec();
+
         let db = Arc::clone(&self.db);
-        let db_update_task =
-            tokio::spawn(
-                async move { db.apply_block(allow_acquire, acquire_done, block, notes).await },
-            );
+        let db_update_task = tokio::spawn(
+            async move { db.apply_block(allow_acquire, acquire_done, block, notes).await }
+                .in_current_span(),
+        );
 
         // Wait for the message from the DB update task, that we ready to commit the DB transaction
         acquired_allowed.await.map_err(ApplyBlockError::ClosedChannel)?;
@@ -363,11 +659,17 @@ impl State {
                 .expect("Unreachable: old nullifier tree root must be checked before this step");
             inner
                 .account_tree
-                .apply_mutations(account_tree_update)
+                .apply_update(account_tree_update)
                 .expect("Unreachable: old account tree root must be checked before this step");
             inner.chain_mmr.add(block_hash);
+            inner.block_nums.insert(block_hash, block_num);
+            inner.push_recent_header(header);
+            self.freshness.block_applied();
         }
 
+        self.account_updates
+            .publish(BlockAccountUpdates { block_num, updates: account_updates });
+
         info!(%block_hash, block_num, COMPONENT, "apply_block successful");
 
         Ok(())
@@ -382,28 +684,101 @@ impl State {
         &self,
         block_num: Option<BlockNumber>,
         include_mmr_proof: bool,
-    ) -> Result<(Option<BlockHeader>, Option<MmrProof>), GetBlockHeaderError> {
+    ) -> Result<(ChainAnchor, Option<BlockHeader>, Option<MmrProof>), GetBlockHeaderError> {
+        let inner = self.inner.read().await;
+
         let block_header = self.db.select_block_header_by_block_num(block_num).await?;
-        if let Some(header) = block_header {
-            let mmr_proof = if include_mmr_proof {
-                let inner = self.inner.read().await;
-                let mmr_proof = inner.chain_mmr.open(header.block_num() as usize)?;
-                Some(mmr_proof)
-            } else {
-                None
-            };
-            Ok((Some(header), mmr_proof))
-        } else {
-            Ok((None, None))
+        Self::with_mmr_proof(&inner, block_header, include_mmr_proof)
+    }
+
+    /// Returns the block header with the given commitment, and optionally its MMR proof.
+    pub async fn get_block_header_by_commitment(
+        &self,
+        block_commitment: RpoDigest,
+        include_mmr_proof: bool,
+    ) -> Result<(ChainAnchor, Option<BlockHeader>, Option<MmrProof>), GetBlockHeaderError> {
+        let inner = self.inner.read().await;
+
+        let block_header = self.db.select_block_header_by_commitment(block_commitment).await?;
+        Self::with_mmr_proof(&inner, block_header, include_mmr_proof)
+    }
+
+    fn with_mmr_proof(
+        inner: &InnerState,
+        block_header: Option<BlockHeader>,
+        include_mmr_proof: bool,
+    ) -> Result<(ChainAnchor, Option<BlockHeader>, Option<MmrProof>), GetBlockHeaderError> {
+        let mmr_proof = match &block_header {
+            Some(header) if include_mmr_proof => {
+                Some(inner.chain_mmr.open(header.block_num() as usize)?)
+            },
+            _ => None,
+        };
+
+        Ok((inner.anchor(), block_header, mmr_proof))
+    }
+
+    /// Returns the consecutive block headers `from_block..=to_block`, truncated to the chain tip,
+    /// and optionally the MMR delta to catch up with them.
+    ///
+    /// The delta extends a partial MMR of the blocks before `from_block` to the blocks before the
+    /// last returned header, whose chain root authenticates the result. As with
+    /// [State::sync_state], the last header itself is then added to the partial MMR by the client.
+    pub async fn get_block_headers_range(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        include_mmr_delta: bool,
+    ) -> Result<(ChainAnchor, Vec<BlockHeader>, Option<MmrDelta>), GetBlockHeaderError> {
+        let inner = self.inner.read().await;
+
+        let to_block = to_block.min(inner.latest_block_num());
+        if from_block > to_block {
+            return Ok((inner.anchor(), Vec::new(), None));
         }
+
+        let mut block_headers =
+            self.db.select_block_headers((from_block..=to_block).collect()).await?;
+        block_headers.sort_unstable_by_key(BlockHeader::block_num);
+
+        let mmr_delta = if !include_mmr_delta {
+            None
+        } else if from_block == to_block {
+            Some(MmrDelta { forest: to_block as usize, data: vec![] })
+        } else {
+            Some(inner.chain_mmr.get_delta(from_block as usize, to_block as usize)?)
+        };
+
+        Ok((inner.anchor(), block_headers, mmr_delta))
     }
 
+    /// Runs the database `read`, and returns its result together with the anchor of the chain tip
+    /// it was computed against.
+    ///
+    /// Blocks are committed to the database while holding the write lock on the in-memory state,
+    /// so holding the read lock for the duration of the `read` guarantees that the database is at
+    /// the anchored block. The `read` must not acquire the lock itself.
+    pub async fn anchored<T>(&self, read: impl Future<Output = T>) -> (ChainAnchor, T) {
+        let inner = self.inner.read().await;
+
+        let result = read.await;
+        (inner.anchor(), result)
+    }
+
+    /// Queries a page of the consumed nullifiers matching the given prefixes.
+    ///
+    /// Nullifiers are returned ordered by block number and then by nullifier, after the nullifier
+    /// at position `after`, up to `limit` nullifiers.
     pub async fn check_nullifiers_by_prefix(
         &self,
         prefix_len: u32,
         nullifier_prefixes: Vec<u32>,
+        after: Option<(BlockNumber, Nullifier)>,
+        limit: usize,
     ) -> Result<Vec<NullifierInfo>, DatabaseError> {
-        self.db.select_nullifiers_by_prefix(prefix_len, nullifier_prefixes).await
+        self.db
+            .select_nullifiers_by_prefix(prefix_len, nullifier_prefixes, after, limit)
+            .await
     }
 
     /// Generates membership proofs for each one of the `nullifiers` against the latest nullifier
@@ -411,9 +786,11 @@ impl State {
     ///
     /// Note: these proofs are invalidated once the nullifier tree is modified, i.e. on a new block.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"))]
-    pub async fn check_nullifiers(&self, nullifiers: &[Nullifier]) -> Vec<SmtProof> {
+    pub async fn check_nullifiers(&self, nullifiers: &[Nullifier]) -> (ChainAnchor, Vec<SmtProof>) {
         let inner = self.inner.read().await;
-        nullifiers.iter().map(|n| inner.nullifier_tree.open(n)).collect()
+        let proofs = nullifiers.iter().map(|n| inner.nullifier_tree.open(n)).collect();
+
+        (inner.anchor(), proofs)
     }
 
     /// Queries a list of [NoteRecord] from the database.
@@ -427,6 +804,108 @@ impl State {
         self.db.select_notes_by_id(note_ids).await
     }
 
+    /// Queries the consumption status of the notes matching the given [NoteId]s.
+    ///
+    /// Notes which are not found are left out of the result.
+    pub async fn check_notes_consumed(
+        &self,
+        note_ids: Vec<NoteId>,
+    ) -> Result<Vec<NoteConsumption>, DatabaseError> {
+        self.db.select_notes_consumption(note_ids).await
+    }
+
+    /// Queries a page of the public notes sent to any of the given recipients.
+    ///
+    /// Notes are returned in chain order, starting from block `from_block` and after the note at
+    /// position `after`, up to `limit` notes.
+    pub async fn get_notes_by_recipient(
+        &self,
+        recipients: Vec<RpoDigest>,
+        from_block: BlockNumber,
+        after: Option<(BlockNumber, BlockNoteIndex)>,
+        limit: usize,
+    ) -> Result<Vec<NoteRecord>, DatabaseError> {
+        self.db.select_notes_by_recipient(recipients, from_block, after, limit).await
+    }
+
+    /// Queries the number of notes per tag prefix in the blocks `from_block..=to_block`, grouping
+    /// the notes by the `prefix_len` most significant bits of their tag.
+    pub async fn get_note_tag_statistics(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        prefix_len: u32,
+        exclude_network_notes: bool,
+    ) -> Result<Vec<NoteTagBucket>, DatabaseError> {
+        self.db
+            .select_note_tag_statistics(from_block, to_block, prefix_len, exclude_network_notes)
+            .await
+    }
+
+    /// Queries the note tag filters of the blocks `from_block..=to_block`.
+    pub async fn get_block_note_filters(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> Result<Vec<BlockNoteFilter>, DatabaseError> {
+        self.db.select_block_note_filters(from_block, to_block).await
+    }
+
+    /// Queries the statistics of the blocks `from_block..=to_block`, [None] if `from_block` is not
+    /// part of the chain.
+    pub async fn get_chain_stats(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> Result<Option<ChainStats>, DatabaseError> {
+        self.db.select_chain_stats(from_block, to_block).await
+    }
+
+    /// Returns whether the chain already contains the block with the given header.
+    pub async fn is_block_committed(&self, header: &BlockHeader) -> Result<bool, DatabaseError> {
+        let committed = self.db.select_block_header_by_block_num(Some(header.block_num())).await?;
+
+        Ok(committed.is_some_and(|committed| committed.hash() == header.hash()))
+    }
+
+    /// Records the provenance of a committed block, unless a later attempt was already recorded.
+    pub async fn record_block_provenance(
+        &self,
+        provenance: BlockProvenance,
+    ) -> Result<(), DatabaseError> {
+        self.db.upsert_block_provenance(provenance).await
+    }
+
+    /// Queries the latest recorded submission of a block.
+    pub async fn get_block_provenance(
+        &self,
+        block_num: BlockNumber,
+    ) -> Result<Option<BlockProvenance>, DatabaseError> {
+        self.db.select_block_provenance(block_num).await
+    }
+
+    /// Records the production stats of a committed block.
+    pub async fn record_block_production_stats(
+        &self,
+        stats: BlockProductionStats,
+    ) -> Result<(), DatabaseError> {
+        self.db.upsert_block_production_stats(stats).await
+    }
+
+    /// Queries the production stats of the blocks in the range `block_from..=block_to`.
+    pub async fn get_block_production_stats(
+        &self,
+        block_from: BlockNumber,
+        block_to: BlockNumber,
+    ) -> Result<Vec<BlockProductionStats>, DatabaseError> {
+        self.db.select_block_production_stats(block_from, block_to).await
+    }
+
+    /// Returns the state of the database connection pool and the waits for a connection.
+    pub fn database_pool_stats(&self) -> PoolStats {
+        self.db.pool_stats()
+    }
+
     /// Queries all the note inclusion proofs matching a certain Note IDs from the database.
     pub async fn get_note_authentication_info(
         &self,
@@ -450,8 +929,9 @@ impl State {
         // NOTE: Scoped block to automatically drop the mutex guard asap.
         //
         // We also avoid accessing the db in the block as this would delay
-        // dropping the guard.
-        let (chain_length, merkle_paths) = {
+        // dropping the guard. The headers of the recent blocks are taken from memory, and only the
+        // older ones are read from the db afterwards.
+        let (chain_length, merkle_paths, mut headers) = {
             let state = self.inner.read().await;
             let chain_length = state.chain_mmr.forest();
 
@@ -467,14 +947,23 @@ impl State {
             let chain_length = BlockNumber::try_from(chain_length)
                 .expect("Forest is a chain length so should fit into block number");
 
-            (chain_length, paths)
+            let headers = blocks
+                .iter()
+                .filter_map(|&block_num| state.recent_header(block_num))
+                .map(|header| (header.block_num(), header))
+                .collect::<BTreeMap<BlockNumber, _>>();
+
+            (chain_length, paths, headers)
         };
 
-        let headers = self.db.select_block_headers(blocks).await?;
-        let headers = headers
+        let missing_blocks = blocks
             .into_iter()
-            .map(|header| (header.block_num(), header))
-            .collect::<BTreeMap<BlockNumber, _>>();
+            .filter(|block_num| !headers.contains_key(block_num))
+            .collect::<Vec<_>>();
+        if !missing_blocks.is_empty() {
+            let missing_headers = self.db.select_block_headers(missing_blocks).await?;
+            headers.extend(missing_headers.into_iter().map(|header| (header.block_num(), header)));
+        }
 
         let mut block_proofs = Vec::with_capacity(merkle_paths.len());
         for (block_num, mmr_path) in merkle_paths {
@@ -509,7 +998,7 @@ impl State {
         account_ids: Vec<AccountId>,
         note_tags: Vec<u32>,
         nullifier_prefixes: Vec<u32>,
-    ) -> Result<(StateSyncUpdate, MmrDelta), StateSyncError> {
+    ) -> Result<(ChainAnchor, StateSyncUpdate, MmrDelta), StateSyncError> {
         let inner = self.inner.read().await;
 
         let state_sync = self
@@ -530,7 +1019,7 @@ impl State {
             // - Mmr::get_delta is inclusive, whereas the sync_state request block_num is defined to
             //   be
             // exclusive, so the from_forest has to be adjusted with a +1
-            let from_forest = (block_num + 1) as usize;
+            let from_forest = block_num as usize + 1;
             let to_forest = state_sync.block_header.block_num() as usize;
             inner
                 .chain_mmr
@@ -538,7 +1027,7 @@ impl State {
                 .map_err(StateSyncError::FailedToBuildMmrDelta)?
         };
 
-        Ok((state_sync, delta))
+        Ok((inner.anchor(), state_sync, delta))
     }
 
     /// Loads data to synchronize a client's notes.
@@ -557,14 +1046,14 @@ impl State {
         &self,
         block_num: BlockNumber,
         note_tags: Vec<u32>,
-    ) -> Result<(NoteSyncUpdate, MmrProof), NoteSyncError> {
+    ) -> Result<(ChainAnchor, NoteSyncUpdate, MmrProof), NoteSyncError> {
         let inner = self.inner.read().await;
 
         let note_sync = self.db.get_note_sync(block_num, note_tags).await?;
 
         let mmr_proof = inner.chain_mmr.open(note_sync.block_header.block_num() as usize)?;
 
-        Ok((note_sync, mmr_proof))
+        Ok((inner.anchor(), note_sync, mmr_proof))
     }
 
     /// Returns data needed by the block producer to construct and prove the next block.
@@ -641,11 +1130,14 @@ impl State {
         account_id: AccountId,
         nullifiers: &[Nullifier],
         unauthenticated_notes: Vec<NoteId>,
+        reference_block: RpoDigest,
     ) -> Result<TransactionInputs, DatabaseError> {
-        info!(target: COMPONENT, account_id = %format_account_id(account_id), nullifiers = %format_array(nullifiers));
+        info!(target: COMPONENT, account_id = %format_account_id(account_id), nullifiers = %format_array(nullifiers), %reference_block);
 
         let inner = self.inner.read().await;
 
+        let reference_block_num = inner.block_nums.get(&reference_block).copied();
+
         let account_hash = inner.account_tree.open(&LeafIndex::new_max_depth(account_id)).value;
 
         let nullifiers = nullifiers
@@ -669,9 +1161,86 @@ impl State {
             account_hash,
             nullifiers,
             missing_unauthenticated_notes,
+            reference_block_num,
         })
     }
 
+    /// Returns the latest commitments of the given accounts, and the block number they are
+    /// returned as of.
+    ///
+    /// Unlike [State::get_transaction_inputs], only the `accounts` table is read. Accounts which
+    /// are not in the store are omitted.
+    pub async fn get_account_commitments(
+        &self,
+        account_ids: Vec<AccountId>,
+    ) -> Result<(BlockNumber, Vec<AccountSummary>), DatabaseError> {
+        let (anchor, accounts) =
+            self.anchored(self.db.select_account_commitments(account_ids)).await;
+
+        Ok((anchor.block_num, accounts?))
+    }
+
+    /// Returns the witnesses of the given accounts against the account tree of block `block_num`,
+    /// or of the chain tip if [None], along with the number of the block.
+    ///
+    /// Pinning the block lets the witnesses of several requests be combined, as long as the block
+    /// is one of the `ACCOUNT_TREE_REVISIONS` most recent ones before the chain tip. Accounts
+    /// which are not in the tree are returned with a witness of their empty leaf.
+    pub async fn get_account_witnesses(
+        &self,
+        account_ids: &[AccountId],
+        block_num: Option<BlockNumber>,
+    ) -> Result<(ChainAnchor, BlockNumber, Vec<(AccountId, ValuePath)>), GetAccountWitnessesError>
+    {
+        let inner = self.inner.read().await;
+
+        let chain_tip = inner.latest_block_num();
+        let block_num = block_num.unwrap_or(chain_tip);
+        if block_num > chain_tip {
+            return Err(GetAccountWitnessesError::UnknownBlock { block_num, chain_tip });
+        }
+        let oldest = inner.account_tree.oldest_block_num();
+        if block_num < oldest {
+            return Err(GetAccountWitnessesError::BlockNotRetained { block_num, oldest });
+        }
+
+        let witnesses = account_ids
+            .iter()
+            .map(|account_id| {
+                let witness = inner
+                    .account_tree
+                    .open_at(&LeafIndex::new_max_depth(*account_id), block_num)
+                    .expect("the account tree of the block is retained");
+                (*account_id, witness)
+            })
+            .collect();
+
+        Ok((inner.anchor(), block_num, witnesses))
+    }
+
+    /// Returns a subscription to the updates of the given accounts by the blocks applied from now
+    /// on.
+    pub fn subscribe_account_updates(
+        &self,
+        account_ids: BTreeSet<AccountId>,
+    ) -> AccountUpdatesSubscription {
+        self.account_updates.subscribe(account_ids)
+    }
+
+    /// Returns the given transactions which were committed in a block, with the block they were
+    /// committed in, and the block number they are returned as of.
+    ///
+    /// Transactions which are not in the store, i.e. not committed yet, are omitted.
+    pub async fn filter_known_transactions(
+        &self,
+        transaction_ids: Vec<TransactionId>,
+    ) -> Result<(BlockNumber, Vec<TransactionSummary>), DatabaseError> {
+        let (anchor, transactions) =
+            self.anchored(self.db.select_transactions_by_ids(transaction_ids)).await;
+
+        Ok((anchor.block_num, transactions?))
+    }
+
     /// Lists all known nullifiers with their inclusion blocks, intended for testing.
     pub async fn list_nullifiers(&self) -> Result<Vec<(Nullifier, u32)>, DatabaseError> {
         self.db.select_all_nullifiers().await
@@ -693,17 +1262,95 @@ impl State {
         self.db.select_account(id).await
     }
 
+    /// Returns the storage layout of a public account as of the latest block, together with the
+    /// header of that block and the commitment to the account's storage.
+    ///
+    /// Returns [DatabaseError::AccountNotOnChain] for private accounts.
+    pub async fn get_account_storage_header(
+        &self,
+        account_id: AccountId,
+    ) -> Result<(ChainAnchor, BlockHeader, RpoDigest, Vec<StorageSlotHeader>), DatabaseError> {
+        // The lock prevents blocks from being applied, so the account is read as of the block
+        let inner_state = self.inner.read().await;
+        let block_num = inner_state.latest_block_num();
+
+        let account = self
+            .db
+            .select_account(account_id)
+            .await?
+            .details
+            .ok_or(DatabaseError::AccountNotOnChain(account_id))?;
+        let block_header = self
+            .db
+            .select_block_header_by_block_num(Some(block_num))
+            .await?
+            .ok_or(DatabaseError::BlockNotFoundInDb(block_num))?;
+
+        let storage = account.storage();
+        Ok((
+            inner_state.anchor(),
+            block_header,
+            storage.commitment(),
+            storage_slot_headers(storage),
+        ))
+    }
+
+    /// Returns up to `limit` assets of the vault of a public account as of the latest block,
+    /// ordered by vault key and starting after the key `after`, together with the number of that
+    /// block and the root of the vault.
+    ///
+    /// Returns [DatabaseError::AccountNotOnChain] for private accounts.
+    pub async fn get_account_vault_assets(
+        &self,
+        account_id: AccountId,
+        after: Option<RpoDigest>,
+        limit: usize,
+    ) -> Result<(ChainAnchor, BlockNumber, RpoDigest, Vec<VaultAsset>), DatabaseError> {
+        // The lock prevents blocks from being applied, so the account is read as of the block
+        let inner_state = self.inner.read().await;
+
+        let account = self
+            .db
+            .select_account(account_id)
+            .await?
+            .details
+            .ok_or(DatabaseError::AccountNotOnChain(account_id))?;
+
+        let vault = account.vault();
+        Ok((
+            inner_state.anchor(),
+            inner_state.latest_block_num(),
+            vault.commitment(),
+            vault_assets_page(vault, after, limit),
+        ))
+    }
+
     /// Returns account proofs with optional account and storage headers.
+    ///
+    /// For every public account in `known_blocks`, the response also contains the delta of the
+    /// account's state since the given block, up to the returned block number.
     pub async fn get_account_proofs(
         &self,
         account_ids: Vec<AccountId>,
         request_code_commitments: BTreeSet<RpoDigest>,
         include_headers: bool,
-    ) -> Result<(BlockNumber, Vec<AccountProofsResponse>), DatabaseError> {
+        known_blocks: BTreeMap<AccountId, BlockNumber>,
+    ) -> Result<(ChainAnchor, Vec<AccountProofsResponse>), DatabaseError> {
         // Lock inner state for the whole operation. We need to hold this lock to prevent the
         // database, account tree and latest block number from changing during the operation,
         // because changing one of them would lead to inconsistent state.
         let inner_state = self.inner.read().await;
+        let latest_block_num = inner_state.latest_block_num();
+
+        let mut state_deltas = BTreeMap::new();
+        for (account_id, known_block_num) in known_blocks {
+            if let Some(delta) = self
+                .account_state_delta_info(account_id, known_block_num, latest_block_num)
+                .await?
+            {
+                state_deltas.insert(account_id, delta);
+            }
+        }
 
         let state_headers = if !include_headers {
             BTreeMap::<AccountId, AccountStateHeader>::default()
@@ -745,17 +1392,87 @@ impl State {
                 let acc_leaf_idx = LeafIndex::new_max_depth(account_id);
                 let opening = inner_state.account_tree.open(&acc_leaf_idx);
                 let state_header = state_headers.get(&account_id).cloned();
+                let state_delta = state_deltas.remove(&account_id);
 
                 AccountProofsResponse {
                     account_id: Some(account_id.into()),
                     account_hash: Some(opening.value.into()),
                     account_proof: Some(opening.path.into()),
                     state_header,
+                    state_delta,
                 }
             })
             .collect();
 
-        Ok((inner_state.latest_block_num(), responses))
+        Ok((inner_state.anchor(), responses))
+    }
+
+    /// Returns the latest state of the account, along with its delta since `known_block` and the
+    /// number of blocks updating it since then, as of the chain tip.
+    ///
+    /// The delta is only returned for public accounts, see [State::account_state_delta_info].
+    pub async fn sync_account_compact(
+        &self,
+        account_id: AccountId,
+        known_block: BlockNumber,
+    ) -> Result<(ChainAnchor, AccountSyncCompact), DatabaseError> {
+        // The lock prevents blocks from being applied, so that the account, its delta and its
+        // updates are all read as of the chain tip
+        let inner_state = self.inner.read().await;
+        let latest_block_num = inner_state.latest_block_num();
+
+        let summary = self.db.select_account(account_id).await?.summary;
+        let skipped_updates =
+            self.db.count_account_updates(account_id, known_block, latest_block_num).await?;
+        let delta =
+            self.account_state_delta_info(account_id, known_block, latest_block_num).await?;
+
+        Ok((inner_state.anchor(), AccountSyncCompact { summary, skipped_updates, delta }))
+    }
+
+    /// Returns the delta of a public account's state from `known_block_num` (exclusive) up to
+    /// `latest_block_num` (inclusive), or `None` for private accounts whose deltas aren't recorded.
+    ///
+    /// Instead of the delta, the result tells to fetch the account details if the data of the
+    /// blocks after `known_block_num` was pruned, or if the delta is too large.
+    async fn account_state_delta_info(
+        &self,
+        account_id: AccountId,
+        known_block_num: BlockNumber,
+        latest_block_num: BlockNumber,
+    ) -> Result<Option<AccountStateDeltaInfo>, DatabaseError> {
+        // Deltas are only recorded for public accounts
+        if !miden_objects::accounts::AccountId::try_from(account_id)?.is_public() {
+            return Ok(None);
+        }
+
+        let data = if !self.serves_account_deltas_since(known_block_num) {
+            account_state_delta_info::Data::TooOld(true)
+        } else {
+            let delta = self
+                .db
+                .select_account_state_delta(account_id, known_block_num, latest_block_num)
+                .await?
+                .to_bytes();
+
+            if delta.len() > MAX_ACCOUNT_PROOF_DELTA_SIZE {
+                account_state_delta_info::Data::FetchDetails(true)
+            } else {
+                account_state_delta_info::Data::Delta(delta)
+            }
+        };
+
+        Ok(Some(AccountStateDeltaInfo { data: Some(data) }))
+    }
+
+    /// Returns whether the state deltas of public accounts since `known_block_num` are served.
+    ///
+    /// In pruned mode, the deltas are only served while the data of the block after
+    /// `known_block_num` is retained. They are kept in the database, but are not part of the
+    /// history served by a pruned store, see [DataAvailability::oldest_account_delta_block].
+    pub fn serves_account_deltas_since(&self, known_block_num: BlockNumber) -> bool {
+        self.mode == StoreMode::Archive
+            || !self.block_store.is_pruned(known_block_num.saturating_advance(1))
     }
 
     /// Returns the state delta between `from_block` (exclusive) and `to_block` (inclusive) for the
@@ -766,25 +1483,118 @@ impl State {
         from_block: BlockNumber,
         to_block: BlockNumber,
     ) -> Result<AccountDelta, DatabaseError> {
-        let deltas = self.db.select_account_state_deltas(account_id, from_block, to_block).await?;
+        self.db.select_account_state_delta(account_id, from_block, to_block).await
+    }
 
-        deltas
-            .into_iter()
-            .try_fold(AccountDelta::default(), |mut accumulator, delta| {
-                accumulator.merge(delta).map(|_| accumulator)
-            })
-            .map_err(Into::into)
+    /// Returns a chunk of the deltas of the given accounts in the blocks `from_block..=to_block`,
+    /// in the order of their block and then of their account, after the delta at position `after`.
+    pub(crate) async fn get_account_deltas_chunk(
+        &self,
+        account_ids: Vec<AccountId>,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        after: Option<(BlockNumber, AccountId)>,
+        limit: usize,
+    ) -> Result<Vec<AccountDeltaRecord>, DatabaseError> {
+        self.db
+            .select_account_deltas_chunk(account_ids, from_block, to_block, after, limit)
+            .await
     }
 
     /// Loads a block from the block store. Return `Ok(None)` if the block is not found.
+    ///
+    /// Returns [GetBlockError::BlockPruned] if the block's data has been pruned from the store.
     pub async fn load_block(
         &self,
         block_num: BlockNumber,
-    ) -> Result<Option<Vec<u8>>, DatabaseError> {
-        if block_num > self.latest_block_num().await {
-            return Ok(None);
+    ) -> Result<(ChainAnchor, Option<Vec<u8>>), GetBlockError> {
+        let inner = self.inner.read().await;
+        let anchor = inner.anchor();
+        if block_num > anchor.block_num {
+            return Ok((anchor, None));
+        }
+
+        // The pruning height is updated before the block files are removed, so checking it after
+        // a failed read is enough to distinguish pruned blocks from missing ones.
+        match self.block_store.load_block(block_num).await? {
+            Some(block) => Ok((anchor, Some(block))),
+            None if self.block_store.is_pruned(block_num) => {
+                Err(GetBlockError::BlockPruned(block_num))
+            },
+            None => Ok((anchor, None)),
+        }
+    }
+
+    /// Returns the historical data served by the store.
+    ///
+    /// The oldest block whose data is served is looked up in the block store, as the pruning
+    /// configuration doesn't tell which blocks were pruned before it was set or changed.
+    pub async fn get_data_availability(
+        &self,
+    ) -> Result<(ChainAnchor, DataAvailability), io::Error> {
+        let anchor = self.inner.read().await.anchor();
+        let oldest_block_data = self
+            .block_store
+            .oldest_block()
+            .await?
+            .unwrap_or(anchor.block_num.saturating_advance(1));
+
+        // The deltas since a known block are only served while the data of the next block is
+        // retained, see `get_account_proofs`
+        let oldest_account_delta_block = self.block_store.pruned_below().saturating_rewind(1);
+
+        Ok((
+            anchor,
+            DataAvailability {
+                oldest_block_data,
+                oldest_account_delta_block,
+                note_details: self.note_details.clone(),
+                mode: self.mode,
+            },
+        ))
+    }
+
+    /// Links the notes committed before their creating transactions were recorded to these
+    /// transactions, from the data of the blocks containing them.
+    ///
+    /// Best effort: the notes of blocks whose data was pruned stay unlinked. Returns the number of
+    /// linked notes, the backfill is not repeated once completed.
+    pub async fn backfill_note_creators(&self) -> Result<usize, DatabaseError> {
+        let Some(backfill_tip) = self.db.select_note_creators_backfill_tip().await? else {
+            return Ok(0);
+        };
+
+        let mut num_linked = 0;
+        let mut last_block_num = None;
+        while let Some(block_num) = self
+            .db
+            .select_next_block_with_unlinked_notes(last_block_num, backfill_tip)
+            .await?
+        {
+            last_block_num = Some(block_num);
+
+            let Some(block_data) = self.block_store.load_block(block_num).await? else {
+                continue;
+            };
+            let block = Block::read_from_bytes(&block_data)?;
+            num_linked += self.db.update_note_creators(block_num, note_creators(&block)).await?;
         }
-        self.block_store.load_block(block_num).await.map_err(Into::into)
+
+        self.db.finish_note_creators_backfill().await?;
+
+        Ok(num_linked)
+    }
+
+    /// Removes the data of blocks more than `retention_depth` blocks behind the chain tip from the
+    /// block store.
+    ///
+    /// Only full block data is removed. Block headers, notes, nullifiers and accounts are kept in
+    /// the database, so all other queries keep working for pruned blocks.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn prune_blocks(&self, retention_depth: u32) -> Result<PrunedBlocks, io::Error> {
+        let chain_tip = self.latest_block_num().await;
+
+        self.block_store.prune_below(chain_tip.saturating_rewind(retention_depth)).await
     }
 
     /// Returns the latest block number.
@@ -796,9 +1606,55 @@ impl State {
 // UTILITIES
 // ================================================================================================
 
+/// Returns the transactions which created the notes of the block, for the notes whose creating
+/// transaction is known.
+///
+/// Blocks record the transactions updating every account, but not the transaction creating every
+/// note. A note is thus linked to the transaction of its sender if the sender updated its account
+/// with a single transaction in the block. Notes created and consumed within the block are erased
+/// from it, and are never linked.
+fn note_creators(block: &Block) -> BTreeMap<NoteId, TransactionId> {
+    let single_transactions: BTreeMap<_, _> = block
+        .updated_accounts()
+        .iter()
+        .filter_map(|update| match update.transactions() {
+            [tx_id] => Some((update.account_id(), *tx_id)),
+            _ => None,
+        })
+        .collect();
+
+    block
+        .notes()
+        .filter_map(|(_, note)| {
+            single_transactions
+                .get(&note.metadata().sender())
+                .map(|tx_id| (note.id(), *tx_id))
+        })
+        .collect()
+}
+
 #[instrument(target = "miden-store", skip_all)]
-async fn load_nullifier_tree(db: &mut Db) -> Result<NullifierTree, StateInitializationError> {
-    let nullifiers = db.select_all_nullifiers().await?;
+async fn load_nullifier_tree<F: FnMut(LoadProgressEvent)>(
+    db: &mut Db,
+    settings: LoadSettings,
+    on_progress: &mut F,
+) -> Result<NullifierTree, StateInitializationError> {
+    let total = db.count_rows("nullifiers").await?;
+    let mut progress =
+        ProgressTracker::start(LoadPhase::NullifierTree, total, settings, on_progress);
+
+    let mut nullifiers: Vec<(Nullifier, BlockNumber)> = Vec::new();
+    loop {
+        let after = nullifiers.last().map(|(nullifier, _)| *nullifier);
+        let chunk = db.select_nullifiers_chunk(after, settings.chunk_size).await?;
+        if chunk.is_empty() {
+            break;
+        }
+
+        progress.advance(chunk.len());
+        nullifiers.extend(chunk);
+    }
+    progress.finish();
     let len = nullifiers.len();
 
     let now = Instant::now();
@@ -816,24 +1672,711 @@ async fn load_nullifier_tree(db: &mut Db) -> Result<NullifierTree, StateInitiali
 }
 
 #[instrument(target = "miden-store", skip_all)]
-async fn load_mmr(db: &mut Db) -> Result<Mmr, StateInitializationError> {
-    let block_hashes: Vec<RpoDigest> =
-        db.select_all_block_headers().await?.iter().map(BlockHeader::hash).collect();
+async fn load_chain<F: FnMut(LoadProgressEvent)>(
+    db: &mut Db,
+    settings: LoadSettings,
+    on_progress: &mut F,
+) -> Result<(Mmr, BTreeMap<RpoDigest, BlockNumber>, VecDeque<BlockHeader>), StateInitializationError>
+{
+    let total = db.count_rows("block_headers").await?;
+    let mut progress = ProgressTracker::start(LoadPhase::ChainMmr, total, settings, on_progress);
+
+    let mut chain_mmr = Mmr::new();
+    let mut block_nums = BTreeMap::new();
+    let mut recent_headers = VecDeque::with_capacity(RECENT_BLOCK_HEADERS + 1);
+    let mut last_block_num = None;
+    loop {
+        let chunk = db.select_block_headers_chunk(last_block_num, settings.chunk_size).await?;
+        let Some(last_header) = chunk.last() else {
+            break;
+        };
+        last_block_num = Some(last_header.block_num());
+
+        progress.advance(chunk.len());
+        for header in chunk {
+            let hash = header.hash();
+            chain_mmr.add(hash);
+            block_nums.insert(hash, header.block_num());
 
-    Ok(block_hashes.into())
+            recent_headers.push_back(header);
+            if recent_headers.len() > RECENT_BLOCK_HEADERS {
+                recent_headers.pop_front();
+            }
+        }
+    }
+    progress.finish();
+
+    Ok((chain_mmr, block_nums, recent_headers))
 }
 
 #[instrument(target = "miden-store", skip_all)]
-async fn load_accounts(
+async fn load_accounts<F: FnMut(LoadProgressEvent)>(
     db: &mut Db,
+    settings: LoadSettings,
+    on_progress: &mut F,
 ) -> Result<SimpleSmt<ACCOUNT_TREE_DEPTH>, StateInitializationError> {
-    let account_data: Vec<_> = db
-        .select_all_account_hashes()
-        .await?
-        .into_iter()
-        .map(|(id, account_hash)| (id, account_hash.into()))
-        .collect();
+    let total = db.count_rows("accounts").await?;
+    let mut progress = ProgressTracker::start(LoadPhase::AccountTree, total, settings, on_progress);
+
+    let mut account_data: Vec<(AccountId, RpoDigest)> = Vec::new();
+    loop {
+        let after = account_data.last().map(|(account_id, _)| *account_id);
+        let chunk = db.select_account_hashes_chunk(after, settings.chunk_size).await?;
+        if chunk.is_empty() {
+            break;
+        }
+
+        progress.advance(chunk.len());
+        account_data.extend(chunk);
+    }
+    progress.finish();
+
+    SimpleSmt::with_leaves(
+        account_data.into_iter().map(|(id, account_hash)| (id, account_hash.into())),
+    )
+    .map_err(StateInitializationError::FailedToCreateAccountsTree)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use miden_node_test_utils::{
+        block::MockChain,
+        mock_account_id,
+        note::{mock_invalid_output_notes, mock_note_with_tag, mock_output_note},
+        MockProvenTxBuilder,
+    };
+    use miden_objects::{
+        accounts::{
+            account_id::testing::ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN,
+            delta::AccountUpdateDetails,
+        },
+        block::BlockAccountUpdate,
+        crypto::merkle::PartialMmr,
+        notes::NoteType,
+        Felt, ONE, ZERO,
+    };
+
+    use super::*;
+    use crate::{config::StoreConfig, genesis::GenesisState};
+
+    const NUM_BLOCKS: u32 = 20;
+
+    /// Loads the state of a new store, and returns it along with a chain starting at its genesis.
+    async fn load_state(dir: &std::path::Path) -> (State, MockChain) {
+        let config = StoreConfig {
+            database_filepath: dir.join("miden-store.sqlite3"),
+            genesis_filepath: dir.join("genesis.dat"),
+            blockstore_dir: dir.join("blocks"),
+            ..Default::default()
+        };
+        let genesis = GenesisState::new(vec![], 1, 0);
+        fs::write(&config.genesis_filepath, genesis.to_bytes()).unwrap();
+        let chain = MockChain::new(&genesis.into_block().unwrap());
+
+        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+        let db = Db::setup(config, Arc::clone(&block_store)).await.unwrap();
+
+        (State::load(db, block_store).await.unwrap(), chain)
+    }
+
+    #[tokio::test]
+    async fn reads_are_anchored_to_the_data_they_return() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+        let state = Arc::new(state);
+
+        let writer = tokio::spawn({
+            let state = Arc::clone(&state);
+            async move {
+                for _ in 0..NUM_BLOCKS {
+                    let block = chain.next_block().build();
+                    state.apply_block(block).await.unwrap();
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+
+        // Every read observes the chain tip matching the data it returned, while blocks are
+        // being applied
+        let mut reads = 0;
+        while !writer.is_finished() {
+            let (anchor, header, _) = state.get_block_header(None, false).await.unwrap();
+            let header = header.unwrap();
+            assert_eq!(anchor.block_num, header.block_num());
+            assert_eq!(anchor.commitment().unwrap(), header.hash());
+
+            let (anchor, header) =
+                state.anchored(state.db.select_block_header_by_block_num(None)).await;
+            let header = header.unwrap().unwrap();
+            assert_eq!(anchor.block_num, header.block_num());
+            assert_eq!(anchor.commitment().unwrap(), header.hash());
+
+            let (anchor, block) = state.load_block(anchor.block_num).await.unwrap();
+            assert!(block.is_some());
+            assert!(anchor.block_num >= header.block_num());
+
+            reads += 1;
+            tokio::task::yield_now().await;
+        }
+        writer.await.unwrap();
+
+        assert!(reads > 0);
+        assert_eq!(state.latest_block_num().await, NUM_BLOCKS);
+    }
+
+    #[tokio::test]
+    async fn block_headers_range_extends_partial_mmr() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+        for _ in 0..NUM_BLOCKS {
+            state.apply_block(chain.next_block().build()).await.unwrap();
+        }
+
+        // The range is truncated to the chain tip
+        let (anchor, headers, delta) = state.get_block_headers_range(5, 100, true).await.unwrap();
+        assert_eq!(anchor.block_num, NUM_BLOCKS);
+        let block_nums: Vec<_> = headers.iter().map(BlockHeader::block_num).collect();
+        assert_eq!(block_nums, (5..=NUM_BLOCKS).collect::<Vec<_>>());
+
+        // A client knowing the blocks before the range catches up with the last returned header
+        let (_, known_headers, _) = state.get_block_headers_range(0, 4, false).await.unwrap();
+        let known_mmr = Mmr::from(known_headers.iter().map(BlockHeader::hash));
+        let mut partial_mmr = PartialMmr::from_peaks(known_mmr.peaks());
+        partial_mmr.apply(delta.unwrap()).unwrap();
+        assert_eq!(partial_mmr.peaks().hash_peaks(), headers.last().unwrap().chain_root());
+
+        let (_, headers, delta) = state.get_block_headers_range(3, 3, true).await.unwrap();
+        assert_eq!(headers.len(), 1);
+        assert!(delta.unwrap().data.is_empty());
+
+        let (_, headers, delta) = state
+            .get_block_headers_range(NUM_BLOCKS + 1, NUM_BLOCKS + 5, true)
+            .await
+            .unwrap();
+        assert!(headers.is_empty());
+        assert!(delta.is_none());
+    }
 
-    SimpleSmt::with_leaves(account_data)
-        .map_err(StateInitializationError::FailedToCreateAccountsTree)
+    #[tokio::test]
+    async fn account_commitments_match_transaction_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+
+        let account_ids: Vec<AccountId> = (0..3)
+            .map(|i| ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN + (i << 32) + 0b1111100000)
+            .collect();
+        let updates: Vec<_> = account_ids
+            .iter()
+            .zip(1..)
+            .map(|(account_id, i)| {
+                BlockAccountUpdate::new(
+                    (*account_id).try_into().unwrap(),
+                    [Felt::new(i), ZERO, ZERO, ZERO].into(),
+                    AccountUpdateDetails::Private,
+                    vec![],
+                )
+            })
+            .collect();
+        let genesis = chain.latest_header();
+        let block = chain.next_block().account_updates(updates).build();
+        state.apply_block(block).await.unwrap();
+
+        // The unknown account is omitted, while its transaction inputs have an empty commitment
+        let unknown_account_id = ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN;
+        let requested = [account_ids.clone(), vec![unknown_account_id]].concat();
+        let (block_num, commitments) = state.get_account_commitments(requested).await.unwrap();
+        assert_eq!(block_num, 1);
+        assert_eq!(commitments.len(), account_ids.len());
+
+        for account_id in account_ids.into_iter().chain([unknown_account_id]) {
+            let tx_inputs = state
+                .get_transaction_inputs(account_id, &[], vec![], genesis.hash())
+                .await
+                .unwrap();
+            let commitment =
+                commitments.iter().find(|summary| u64::from(summary.account_id) == account_id);
+
+            match commitment {
+                Some(summary) => {
+                    assert_eq!(summary.account_hash, tx_inputs.account_hash);
+                    assert_eq!(summary.block_num, 1);
+                },
+                None => assert_eq!(tx_inputs.account_hash, RpoDigest::default()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn committed_transactions_are_known() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+
+        let tx_id = |i| TransactionId::from(RpoDigest::from([Felt::new(i), ZERO, ZERO, ZERO]));
+        let account_id: AccountId = ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN;
+        let block = chain
+            .next_block()
+            .account_updates(vec![BlockAccountUpdate::new(
+                account_id.try_into().unwrap(),
+                [ONE, ZERO, ZERO, ZERO].into(),
+                AccountUpdateDetails::Private,
+                vec![tx_id(1), tx_id(2)],
+            )])
+            .build();
+        state.apply_block(block).await.unwrap();
+
+        // The transaction which is not committed yet is omitted
+        let (block_num, transactions) = state
+            .filter_known_transactions(vec![tx_id(3), tx_id(2), tx_id(1)])
+            .await
+            .unwrap();
+        assert_eq!(block_num, 1);
+
+        let expected: Vec<_> = [tx_id(1), tx_id(2)]
+            .into_iter()
+            .map(|transaction_id| TransactionSummary { account_id, block_num: 1, transaction_id })
+            .collect();
+        assert_eq!(transactions, expected);
+    }
+
+    /// Returns the time of the store's clock, in seconds.
+    fn now() -> u32 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .try_into()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn blocks_with_invalid_timestamps_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+        state.apply_block(chain.next_block().timestamp(now()).build()).await.unwrap();
+        let tip = chain.latest_header();
+
+        // A day ahead of the store's clock
+        let block = chain.clone().next_block().timestamp(now() + 24 * 3600).build();
+        let err = state.apply_block(block).await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ApplyBlockError::InvalidBlockError(
+                    InvalidBlockError::NewBlockTimestampInFuture { .. }
+                )
+            ),
+            "{err}"
+        );
+
+        // As old as the chain tip
+        let block = chain.clone().next_block().timestamp(tip.timestamp()).build();
+        let err = state.apply_block(block).await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ApplyBlockError::InvalidBlockError(
+                    InvalidBlockError::NewBlockTimestampNotIncreasing { .. }
+                )
+            ),
+            "{err}"
+        );
+
+        // Nothing was persisted, and blocks within the allowed drift are applied on the same tip
+        assert_eq!(state.latest_block_num().await, tip.block_num());
+        state
+            .apply_block(chain.next_block().timestamp(now() + 60).build())
+            .await
+            .unwrap();
+        assert_eq!(state.latest_block_num().await, tip.block_num() + 1);
+    }
+
+    #[tokio::test]
+    async fn applied_blocks_refresh_the_chain_freshness() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+
+        // Genesis-only chains are never stale
+        assert_eq!(state.freshness().staleness(), None);
+
+        state.freshness().set_last_block_applied_at(Some((now() - 600).into()));
+        state.apply_block(chain.next_block().build()).await.unwrap();
+        assert!(state.freshness().staleness().unwrap() <= 1);
+
+        // After a restart, the chain tip is assumed to be applied at its timestamp
+        drop(state);
+        let block_store = Arc::new(BlockStore::new(dir.path().join("blocks")).await.unwrap());
+        let config = StoreConfig {
+            database_filepath: dir.path().join("miden-store.sqlite3"),
+            genesis_filepath: dir.path().join("genesis.dat"),
+            blockstore_dir: dir.path().join("blocks"),
+            ..Default::default()
+        };
+        let db = Db::setup(config, Arc::clone(&block_store)).await.unwrap();
+        let state = State::load(db, block_store).await.unwrap();
+        let staleness = u64::from(now().saturating_sub(chain.latest_header().timestamp()));
+        assert!(state.freshness().staleness().unwrap().abs_diff(staleness) <= 1);
+    }
+
+    #[tokio::test]
+    async fn timestamp_validation_can_be_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+        let state = state.with_timestamp_validation(TimestampValidation::Disabled);
+
+        let timestamp = now() + 24 * 3600;
+        state
+            .apply_block(chain.next_block().timestamp(timestamp).build())
+            .await
+            .unwrap();
+        state
+            .apply_block(chain.next_block().timestamp(timestamp).build())
+            .await
+            .unwrap();
+
+        assert_eq!(state.latest_block_num().await, 2);
+    }
+
+    #[tokio::test]
+    async fn blocks_with_invalid_notes_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, chain) = load_state(dir.path()).await;
+
+        for note in mock_invalid_output_notes() {
+            let block = chain.clone().next_block().output_notes(vec![vec![note.clone()]]).build();
+            let err = state.apply_block(block).await.unwrap_err();
+            assert!(
+                matches!(
+                    &err,
+                    ApplyBlockError::InvalidBlockError(InvalidBlockError::InvalidOutputNote {
+                        note_id,
+                        ..
+                    }) if *note_id == note.id()
+                ),
+                "{err}"
+            );
+        }
+
+        assert_eq!(state.latest_block_num().await, chain.latest_header().block_num());
+        assert!(state.list_notes().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn blocks_over_the_write_limits_are_rejected() {
+        use miden_node_test_utils::note::mock_nullifier;
+        use miden_objects::accounts::{
+            account_id::testing::ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, AccountDelta,
+            AccountStorageDelta, AccountVaultDelta,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let (mut state, mut chain) = load_state(dir.path()).await;
+        let tip = chain.latest_header().block_num();
+
+        let note = mock_output_note(1);
+        let public_update = BlockAccountUpdate::new(
+            ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN.try_into().unwrap(),
+            [ONE, ZERO, ZERO, ZERO].into(),
+            AccountUpdateDetails::Delta(
+                AccountDelta::new(
+                    AccountStorageDelta::from_iters([], [(0, [ONE, ONE, ZERO, ZERO])], []),
+                    AccountVaultDelta::from_iters([], []),
+                    Some(ONE),
+                )
+                .unwrap(),
+            ),
+            vec![],
+        );
+        let private_update = |num| {
+            BlockAccountUpdate::new(
+                mock_account_id(num),
+                [Felt::from(num), ZERO, ZERO, ZERO].into(),
+                AccountUpdateDetails::Private,
+                vec![],
+            )
+        };
+
+        let cases: Vec<(WriteLimits, Block)> = vec![
+            (
+                WriteLimits {
+                    max_notes_per_block: 1,
+                    ..Default::default()
+                },
+                chain
+                    .clone()
+                    .next_block()
+                    .output_notes(vec![vec![note.clone(), mock_output_note(2)]])
+                    .build(),
+            ),
+            (
+                WriteLimits {
+                    max_nullifiers_per_block: 1,
+                    ..Default::default()
+                },
+                chain
+                    .clone()
+                    .next_block()
+                    .nullifiers(vec![mock_nullifier(1), mock_nullifier(2)])
+                    .build(),
+            ),
+            (
+                WriteLimits {
+                    max_accounts_per_block: 1,
+                    ..Default::default()
+                },
+                chain
+                    .clone()
+                    .next_block()
+                    .account_updates(vec![private_update(1), private_update(2)])
+                    .build(),
+            ),
+            (
+                WriteLimits {
+                    max_note_details_size: 16,
+                    ..Default::default()
+                },
+                chain.clone().next_block().output_notes(vec![vec![note.clone()]]).build(),
+            ),
+            (
+                WriteLimits {
+                    max_account_details_size: 16,
+                    ..Default::default()
+                },
+                chain.clone().next_block().account_updates(vec![public_update]).build(),
+            ),
+            (
+                WriteLimits {
+                    max_note_path_depth: miden_objects::BLOCK_NOTE_TREE_DEPTH - 1,
+                    ..Default::default()
+                },
+                chain.clone().next_block().output_notes(vec![vec![note.clone()]]).build(),
+            ),
+        ];
+
+        for (limits, block) in cases {
+            state = state.with_write_limits(limits);
+            let err = state.apply_block(block).await.unwrap_err();
+            let ApplyBlockError::InvalidBlockError(err) = err else {
+                panic!("Unexpected error {err}");
+            };
+            match &err {
+                InvalidBlockError::TooManyNotes { count: 2, limit: 1 }
+                | InvalidBlockError::TooManyNullifiers { count: 2, limit: 1 }
+                | InvalidBlockError::TooManyAccountUpdates { count: 2, limit: 1 } => {},
+                InvalidBlockError::NoteDetailsTooLarge { note_id, limit: 16, .. }
+                | InvalidBlockError::NoteMerklePathTooDeep { note_id, .. }
+                    if *note_id == note.id() => {},
+                InvalidBlockError::AccountDetailsTooLarge { account_id, limit: 16, .. }
+                    if *account_id == ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN => {},
+                err => panic!("Unexpected error {err}"),
+            }
+
+            // Nothing was persisted. The note paths are only checked once the block file is
+            // written, as a candidate which isn't served
+            assert_eq!(state.latest_block_num().await, tip);
+            assert!(state.list_notes().await.unwrap().is_empty());
+            if !matches!(err, InvalidBlockError::NoteMerklePathTooDeep { .. }) {
+                assert_eq!(state.block_store.load_block(tip + 1).await.unwrap(), None);
+            }
+        }
+
+        // Blocks within the limits are applied
+        let state = state.with_write_limits(WriteLimits::default());
+        let block = chain
+            .next_block()
+            .output_notes(vec![vec![note]])
+            .account_updates(vec![private_update(1)])
+            .nullifiers(vec![mock_nullifier(1)])
+            .build();
+        state.apply_block(block).await.unwrap();
+        assert_eq!(state.latest_block_num().await, tip + 1);
+    }
+
+    #[tokio::test]
+    async fn blocks_with_unsupported_versions_are_rejected() {
+        use miden_node_utils::block_versions::{
+            BlockVersionRange, BlockVersionUpgrade, UnsupportedBlockVersion,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+        let upgraded = BlockVersionRange { min: 2, max: 2 };
+        let state = state.with_block_versions(BlockVersions {
+            supported: BlockVersionRange::default(),
+            upgrade: Some(BlockVersionUpgrade { activation_block: 3, supported: upgraded }),
+        });
+
+        let assert_unsupported =
+            |err: ApplyBlockError, block_num: u32, version: u32, supported: BlockVersionRange| {
+                let ApplyBlockError::InvalidBlockError(InvalidBlockError::UnsupportedVersion(err)) =
+                    err
+                else {
+                    panic!("Unexpected error {err}");
+                };
+                assert_eq!(err, UnsupportedBlockVersion { block_num, version, supported });
+            };
+
+        // Versions newer than the supported ones are rejected before the upgrade
+        let block = chain.clone().next_block().version(2).build();
+        let err = state.apply_block(block).await.unwrap_err();
+        assert_unsupported(err, 1, 2, BlockVersionRange::default());
+        assert_eq!(state.block_store.load_block(1).await.unwrap(), None);
+
+        for _ in 1..3 {
+            state.apply_block(chain.next_block().build()).await.unwrap();
+        }
+
+        // The former versions are rejected from the activation block onwards
+        let block = chain.clone().next_block().build();
+        let err = state.apply_block(block).await.unwrap_err();
+        assert_unsupported(err, 3, 1, upgraded);
+
+        state.apply_block(chain.next_block().version(2).build()).await.unwrap();
+        assert_eq!(state.latest_block_num().await, 3);
+    }
+
+    #[tokio::test]
+    async fn notes_are_linked_to_their_creating_transaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+
+        // The first sender updates its account with a single transaction, creating a note in each
+        // batch, while the second sender updates its account with two transactions
+        let tx_ids: Vec<_> = (0..3)
+            .map(|i| MockProvenTxBuilder::with_account_index(i).build().id())
+            .collect();
+        let updates = vec![
+            BlockAccountUpdate::new(
+                mock_account_id(1),
+                [ONE, ZERO, ZERO, ZERO].into(),
+                AccountUpdateDetails::Private,
+                vec![tx_ids[0]],
+            ),
+            BlockAccountUpdate::new(
+                mock_account_id(2),
+                [ONE, ONE, ZERO, ZERO].into(),
+                AccountUpdateDetails::Private,
+                vec![tx_ids[1], tx_ids[2]],
+            ),
+        ];
+        let first_note = mock_output_note(1);
+        let second_note = OutputNote::Full(mock_note_with_tag(1, NoteType::Public, 0xc000_0000));
+        let ambiguous_note = mock_output_note(2);
+        let block = chain
+            .next_block()
+            .account_updates(updates)
+            .output_notes(vec![
+                vec![first_note.clone()],
+                vec![ambiguous_note.clone(), second_note.clone()],
+            ])
+            .build();
+        state.apply_block(block).await.unwrap();
+
+        // The stored block only attributes a single transaction to the first sender
+        let (_, block_data) = state.load_block(1).await.unwrap();
+        let block = Block::read_from_bytes(&block_data.unwrap()).unwrap();
+        let sender_update = block
+            .updated_accounts()
+            .iter()
+            .find(|update| update.account_id() == first_note.metadata().sender())
+            .unwrap();
+        assert_eq!(sender_update.transactions(), &tx_ids[..1]);
+        assert_eq!(block.notes().count(), 3);
+
+        let note_ids = vec![first_note.id(), second_note.id(), ambiguous_note.id()];
+        let creators = || async {
+            state
+                .db
+                .select_notes_by_id(note_ids.clone())
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|note| (NoteId::from(note.note_id), note.created_by_tx))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let expected = BTreeMap::from([
+            (first_note.id(), Some(tx_ids[0])),
+            (second_note.id(), Some(tx_ids[0])),
+            (ambiguous_note.id(), None),
+        ]);
+        assert_eq!(creators().await, expected);
+
+        // Notes stored before the creators were recorded are backfilled once from the block data
+        let conn = rusqlite::Connection::open(dir.path().join("miden-store.sqlite3")).unwrap();
+        let forget_creators = || {
+            conn.execute("UPDATE notes SET created_by_tx = NULL", []).unwrap();
+        };
+        forget_creators();
+        conn.execute(
+            "INSERT INTO settings (name, value) VALUES ('note-creators-backfill-tip', 1)",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(state.backfill_note_creators().await.unwrap(), 2);
+        assert_eq!(creators().await, expected);
+
+        forget_creators();
+        assert_eq!(state.backfill_note_creators().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn notes_are_authenticated_with_recent_and_older_block_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, mut chain) = load_state(dir.path()).await;
+
+        // The first block falls out of the recent headers, while the last one is still cached
+        let num_blocks = RECENT_BLOCK_HEADERS as u32 + 1;
+        let old_note = mock_output_note(1);
+        let recent_note = mock_output_note(2);
+        let mut headers = BTreeMap::new();
+        for block_num in 1..=num_blocks {
+            let output_notes = match block_num {
+                1 => vec![vec![old_note.clone()]],
+                _ if block_num == num_blocks => vec![vec![recent_note.clone()]],
+                _ => vec![],
+            };
+            let block = chain.next_block().output_notes(output_notes).build();
+            headers.insert(block_num, block.header());
+            state.apply_block(block).await.unwrap();
+        }
+
+        {
+            let inner = state.inner.read().await;
+            assert_eq!(inner.recent_headers.len(), RECENT_BLOCK_HEADERS);
+            assert!(inner.recent_header(1).is_none());
+            assert_eq!(inner.recent_header(2), Some(headers[&2]));
+            assert_eq!(inner.recent_header(num_blocks), Some(headers[&num_blocks]));
+            assert!(inner.recent_header(num_blocks + 1).is_none());
+        }
+
+        let info = state
+            .get_note_authentication_info(BTreeSet::from([old_note.id(), recent_note.id()]))
+            .await
+            .unwrap();
+        assert_eq!(info.note_proofs.len(), 2);
+        let block_headers: Vec<_> =
+            info.block_proofs.iter().map(|proof| proof.block_header).collect();
+        assert_eq!(block_headers, vec![headers[&1], headers[&num_blocks]]);
+        assert!(info.block_proofs.iter().all(|proof| proof.chain_length == num_blocks + 1));
+
+        // The recent headers are restored when the state is loaded again
+        let config = StoreConfig {
+            database_filepath: dir.path().join("miden-store.sqlite3"),
+            genesis_filepath: dir.path().join("genesis.dat"),
+            blockstore_dir: dir.path().join("blocks"),
+            ..Default::default()
+        };
+        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+        let db = Db::setup(config, Arc::clone(&block_store)).await.unwrap();
+        let loaded = State::load(db, block_store).await.unwrap();
+        assert_eq!(
+            loaded.inner.read().await.recent_headers,
+            state.inner.read().await.recent_headers
+        );
+    }
 }
@@ -0,0 +1,531 @@
+//! Concurrency tests of the reads served while blocks are being applied.
+//!
+//! SQLite's WAL mode lets readers query the database while a block is being written, so the
+//! consistency of the responses relies on [State::apply_block] committing its transaction while
+//! holding the write lock of the in-memory state, and on the reads holding its read lock. Every
+//! test applies a seeded sequence of blocks while clients concurrently sync their state and notes,
+//! and request note, account and nullifier proofs:
+//!
+//! - every response is checked against the trees computed from the same blocks, at the chain tip it
+//!   is anchored to.
+//! - every client checks that the chain tip it observes never goes backwards.
+//!
+//! The workloads are generated from fixed seeds, so that a failure can be replayed.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use miden_node_proto::{domain::prefixes::nullifier_prefix, generated::block::ChainAnchor};
+use miden_node_test_utils::{block::MockChain, mock_account_id, note::mock_nullifier};
+use miden_objects::{
+    accounts::delta::AccountUpdateDetails,
+    block::{Block, BlockAccountUpdate},
+    crypto::merkle::{LeafIndex, MerklePath, Mmr, SimpleSmt, Smt},
+    notes::{NoteExecutionHint, NoteHeader, NoteId, NoteMetadata, NoteTag, NoteType},
+    transaction::OutputNote,
+    utils::Serializable,
+    BlockHeader, Digest, Felt, Hasher, ACCOUNT_TREE_DEPTH, ONE, ZERO,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::State;
+use crate::{
+    blocks::BlockStore,
+    config::StoreConfig,
+    db::Db,
+    genesis::GenesisState,
+    types::{AccountId, BlockNumber},
+};
+
+/// Seeds of the workloads.
+const SEEDS: [u64; 3] = [1, 2, 3];
+
+/// Number of blocks applied by every workload.
+const NUM_BLOCKS: u32 = 30;
+
+/// Number of clients reading the state while the blocks are applied.
+const NUM_CLIENTS: u64 = 4;
+
+/// Number of private accounts updated by the blocks.
+const NUM_ACCOUNTS: u8 = 6;
+
+/// Number of local use cases of the note tags.
+const NUM_NOTE_TAGS: u16 = 4;
+
+// WORKLOAD
+// ================================================================================================
+
+/// A seeded sequence of blocks, along with the trees of the chain after each of them.
+struct Workload {
+    /// The blocks of the chain, starting with its genesis block.
+    blocks: Vec<Block>,
+    /// The account tree after each block.
+    account_trees: Vec<SimpleSmt<ACCOUNT_TREE_DEPTH>>,
+    /// The nullifier tree after each block.
+    nullifier_trees: Vec<Smt>,
+    /// The chain MMR holding every block.
+    chain_mmr: Mmr,
+    /// The block, index and merkle path of every note.
+    notes: BTreeMap<NoteId, (BlockNumber, u16, MerklePath)>,
+    /// The block consuming every nullifier.
+    spent_nullifiers: BTreeMap<Digest, BlockNumber>,
+    /// The accounts updated by the blocks.
+    account_ids: Vec<AccountId>,
+}
+
+impl Workload {
+    fn new(genesis: Block, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut chain = MockChain::new(&genesis);
+        let account_ids: Vec<_> = (1..=NUM_ACCOUNTS).map(mock_account_id).collect();
+
+        let mut account_tree = SimpleSmt::new().unwrap();
+        let mut nullifier_tree = Smt::new();
+        let mut workload = Self {
+            chain_mmr: Mmr::new(),
+            blocks: Vec::new(),
+            account_trees: Vec::new(),
+            nullifier_trees: Vec::new(),
+            notes: BTreeMap::new(),
+            spent_nullifiers: BTreeMap::new(),
+            account_ids: account_ids.iter().map(|&account_id| account_id.into()).collect(),
+        };
+        workload.push(genesis, &account_tree, &nullifier_tree);
+
+        let (mut num_notes, mut num_nullifiers) = (0, 0);
+        for block_num in 1..=NUM_BLOCKS {
+            let num_updates = rng.gen_range(0..=2);
+            let updated: Vec<_> =
+                account_ids.choose_multiple(&mut rng, num_updates).copied().collect();
+            let updates = updated
+                .into_iter()
+                .map(|account_id| {
+                    let state = [Felt::from(block_num), Felt::from(rng.gen::<u32>()), ZERO, ZERO];
+                    BlockAccountUpdate::new(
+                        account_id,
+                        state.into(),
+                        AccountUpdateDetails::Private,
+                        vec![],
+                    )
+                })
+                .collect();
+
+            let mut batches = Vec::new();
+            for _ in 0..rng.gen_range(0..=2) {
+                let mut batch = Vec::new();
+                for _ in 0..rng.gen_range(1..=3) {
+                    num_notes += 1;
+                    batch.push(private_note(num_notes, rng.gen_range(0..NUM_NOTE_TAGS)));
+                }
+                batches.push(batch);
+            }
+
+            let mut nullifiers = Vec::new();
+            for _ in 0..rng.gen_range(0..=2) {
+                num_nullifiers += 1;
+                nullifiers.push(mock_nullifier(num_nullifiers));
+            }
+
+            let block = chain
+                .next_block()
+                .account_updates(updates)
+                .output_notes(batches)
+                .nullifiers(nullifiers)
+                .build();
+
+            for update in block.updated_accounts() {
+                account_tree.insert(
+                    LeafIndex::new_max_depth(update.account_id().into()),
+                    update.new_state_hash().into(),
+                );
+            }
+            for nullifier in block.nullifiers() {
+                nullifier_tree.insert(nullifier.inner(), [Felt::from(block_num), ZERO, ZERO, ZERO]);
+            }
+            assert_eq!(account_tree.root(), block.header().account_root());
+            assert_eq!(nullifier_tree.root(), block.header().nullifier_root());
+
+            workload.push(block, &account_tree, &nullifier_tree);
+        }
+
+        workload
+    }
+
+    /// Appends a block to the chain, along with the trees of the chain after it.
+    fn push(
+        &mut self,
+        block: Block,
+        account_tree: &SimpleSmt<ACCOUNT_TREE_DEPTH>,
+        nullifier_tree: &Smt,
+    ) {
+        let block_num = block.header().block_num();
+
+        let note_tree = block.build_note_tree();
+        for (note_index, note) in block.notes() {
+            let merkle_path = note_tree.get_note_path(note_index);
+            self.notes
+                .insert(note.id(), (block_num, note_index.leaf_index_value(), merkle_path));
+        }
+        for nullifier in block.nullifiers() {
+            self.spent_nullifiers.insert(nullifier.inner(), block_num);
+        }
+
+        self.chain_mmr.add(block.hash());
+        self.account_trees.push(account_tree.clone());
+        self.nullifier_trees.push(nullifier_tree.clone());
+        self.blocks.push(block);
+    }
+
+    fn header(&self, block_num: BlockNumber) -> BlockHeader {
+        self.blocks[block_num as usize].header()
+    }
+
+    /// Returns the chain MMR holding the first `forest` blocks.
+    fn chain_mmr_at(&self, forest: usize) -> Mmr {
+        let mut chain_mmr = Mmr::new();
+        for block in &self.blocks[..forest] {
+            chain_mmr.add(block.hash());
+        }
+
+        chain_mmr
+    }
+
+    /// Checks the location and merkle path of a note against the block creating it.
+    fn check_note(
+        &self,
+        note_id: NoteId,
+        block_num: BlockNumber,
+        note_index: u16,
+        merkle_path: &MerklePath,
+    ) {
+        let (expected_block_num, expected_index, expected_path) = self
+            .notes
+            .get(&note_id)
+            .expect("the note must have been created by the workload");
+
+        assert_eq!(block_num, *expected_block_num);
+        assert_eq!(note_index, *expected_index);
+        assert_eq!(merkle_path, expected_path);
+    }
+}
+
+/// Returns a private note whose ID is derived from `num`, with a local use case tag.
+fn private_note(num: u32, use_case: u16) -> OutputNote {
+    let metadata = NoteMetadata::new(
+        mock_account_id(1),
+        NoteType::Private,
+        NoteTag::for_local_use_case(use_case, 0).unwrap(),
+        NoteExecutionHint::none(),
+        ONE,
+    )
+    .unwrap();
+
+    OutputNote::Header(NoteHeader::new(Hasher::hash(&num.to_be_bytes()).into(), metadata))
+}
+
+// CLIENTS
+// ================================================================================================
+
+/// A client reading the state while blocks are applied, checking every response against the
+/// workload.
+struct Client {
+    state: Arc<State>,
+    workload: Arc<Workload>,
+    rng: StdRng,
+    /// Latest chain tip observed by the client.
+    chain_tip: BlockNumber,
+    /// Latest block synced by `sync_state`.
+    synced_block: BlockNumber,
+    /// Latest block synced by `sync_notes`.
+    synced_notes_block: BlockNumber,
+    account_ids: Vec<AccountId>,
+    note_tags: Vec<u32>,
+    nullifier_prefixes: Vec<u32>,
+}
+
+impl Client {
+    fn new(state: Arc<State>, workload: Arc<Workload>, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let account_ids: Vec<AccountId> =
+            workload.account_ids.choose_multiple(&mut rng, 2).copied().collect();
+        let note_tags: Vec<u32> = (0..NUM_NOTE_TAGS)
+            .filter(|_| rng.gen_bool(0.5))
+            .map(|use_case| NoteTag::for_local_use_case(use_case, 0).unwrap().into())
+            .collect();
+        let nullifier_prefixes: Vec<u32> = workload
+            .spent_nullifiers
+            .keys()
+            .filter(|_| rng.gen_bool(0.5))
+            .map(|&nullifier| nullifier_prefix(&nullifier.into()))
+            .collect();
+
+        Self {
+            state,
+            workload,
+            rng,
+            chain_tip: 0,
+            synced_block: 0,
+            synced_notes_block: 0,
+            account_ids,
+            note_tags,
+            nullifier_prefixes,
+        }
+    }
+
+    /// Runs random reads until `applied` is set, then syncs up to the chain tip. Returns the
+    /// number of reads.
+    async fn run(mut self, applied: Arc<AtomicBool>) -> usize {
+        let mut reads = 0;
+        while !applied.load(Ordering::Acquire) {
+            match self.rng.gen_range(0..5) {
+                0 => self.sync_state().await,
+                1 => self.sync_notes().await,
+                2 => self.note_authentication_info().await,
+                3 => self.account_proofs().await,
+                _ => self.nullifier_proofs().await,
+            }
+            reads += 1;
+            tokio::task::yield_now().await;
+        }
+
+        while self.synced_block < NUM_BLOCKS {
+            self.sync_state().await;
+        }
+        assert_eq!(self.chain_tip, NUM_BLOCKS);
+
+        reads
+    }
+
+    /// Records the chain tip a response is anchored to, which must be a block of the workload no
+    /// older than the chain tips of the previous responses.
+    fn observe(&mut self, anchor: &ChainAnchor) {
+        assert!(
+            anchor.block_num >= self.chain_tip,
+            "the chain tip went back from block {} to block {}",
+            self.chain_tip,
+            anchor.block_num
+        );
+        assert_eq!(anchor.commitment().unwrap(), self.workload.header(anchor.block_num).hash());
+
+        self.chain_tip = anchor.block_num;
+    }
+
+    async fn sync_state(&mut self) {
+        let (anchor, update, delta) = self
+            .state
+            .sync_state(
+                self.synced_block,
+                self.account_ids.clone(),
+                self.note_tags.clone(),
+                self.nullifier_prefixes.clone(),
+            )
+            .await
+            .unwrap();
+        self.observe(&anchor);
+
+        let header = update.block_header;
+        let block_num = header.block_num();
+        assert!((self.synced_block..=anchor.block_num).contains(&block_num));
+        assert_eq!(header, self.workload.header(block_num));
+
+        for note in &update.notes {
+            assert_eq!(note.block_num, block_num);
+            assert!(self.note_tags.contains(&u32::from(note.metadata.tag())));
+            self.workload.check_note(
+                note.note_id.into(),
+                note.block_num,
+                note.note_index.leaf_index_value(),
+                &note.merkle_path,
+            );
+        }
+        for nullifier in &update.nullifiers {
+            assert!(nullifier.block_num > self.synced_block && nullifier.block_num <= block_num);
+            assert_eq!(
+                self.workload.spent_nullifiers.get(&nullifier.nullifier.inner()),
+                Some(&nullifier.block_num)
+            );
+        }
+        for account in &update.account_updates {
+            let account_id: AccountId = account.account_id.into();
+            assert!(self.account_ids.contains(&account_id));
+            let opening = self.workload.account_trees[block_num as usize]
+                .open(&LeafIndex::new_max_depth(account_id));
+            assert_eq!(account.account_hash, opening.value);
+        }
+
+        // The delta brings the chain MMR of the client up to the synced block
+        if block_num == self.synced_block {
+            assert!(delta.data.is_empty());
+        } else {
+            let expected = self
+                .workload
+                .chain_mmr
+                .get_delta(self.synced_block as usize + 1, block_num as usize)
+                .unwrap();
+            assert_eq!(delta.forest, expected.forest);
+            assert_eq!(delta.data, expected.data);
+        }
+
+        self.synced_block = block_num;
+    }
+
+    async fn sync_notes(&mut self) {
+        let (anchor, update, mmr_proof) = self
+            .state
+            .sync_notes(self.synced_notes_block, self.note_tags.clone())
+            .await
+            .unwrap();
+        self.observe(&anchor);
+
+        let header = update.block_header;
+        let block_num = header.block_num();
+        assert!((self.synced_notes_block..=anchor.block_num).contains(&block_num));
+        assert_eq!(header, self.workload.header(block_num));
+
+        for note in &update.notes {
+            assert_eq!(note.block_num, block_num);
+            self.workload.check_note(
+                note.note_id.into(),
+                note.block_num,
+                note.note_index.leaf_index_value(),
+                &note.merkle_path,
+            );
+        }
+
+        // The block is opened in the chain MMR of the anchor
+        let forest = anchor.block_num as usize + 1;
+        let expected = self.workload.chain_mmr_at(forest).open(block_num as usize).unwrap();
+        assert_eq!(mmr_proof.forest, forest);
+        assert_eq!(mmr_proof.position, expected.position);
+        assert_eq!(mmr_proof.merkle_path, expected.merkle_path);
+
+        self.synced_notes_block = block_num;
+    }
+
+    async fn note_authentication_info(&mut self) {
+        let committed = self.state.latest_block_num().await;
+        assert!(committed >= self.chain_tip);
+        self.chain_tip = committed;
+
+        let block_num = self.rng.gen_range(1..=NUM_BLOCKS);
+        let note_ids: BTreeSet<NoteId> = self.workload.blocks[block_num as usize]
+            .notes()
+            .map(|(_, note)| note.id())
+            .collect();
+        let info = self.state.get_note_authentication_info(note_ids.clone()).await.unwrap();
+
+        // The notes of the blocks committed before the request are always found
+        if block_num <= committed {
+            assert_eq!(info.note_proofs.len(), note_ids.len());
+        }
+        for (note_id, proof) in &info.note_proofs {
+            let location = proof.location();
+            self.workload.check_note(
+                *note_id,
+                location.block_num(),
+                location.node_index_in_block(),
+                proof.note_path(),
+            );
+        }
+
+        for proof in &info.block_proofs {
+            let proven_block_num = proof.block_header.block_num();
+            assert_eq!(proof.block_header, self.workload.header(proven_block_num));
+
+            let forest = proof.chain_length as usize;
+            assert!(forest > proven_block_num as usize);
+            let expected =
+                self.workload.chain_mmr_at(forest).open(proven_block_num as usize).unwrap();
+            assert_eq!(proof.mmr_path, expected.merkle_path);
+        }
+    }
+
+    async fn account_proofs(&mut self) {
+        let (anchor, responses) = self
+            .state
+            .get_account_proofs(self.account_ids.clone(), BTreeSet::new(), false, BTreeMap::new())
+            .await
+            .unwrap();
+        self.observe(&anchor);
+
+        let account_tree = &self.workload.account_trees[anchor.block_num as usize];
+        for (&account_id, response) in self.account_ids.iter().zip(responses) {
+            let expected = account_tree.open(&LeafIndex::new_max_depth(account_id));
+            let account_hash = Digest::try_from(response.account_hash.unwrap()).unwrap();
+            let account_proof = MerklePath::try_from(&response.account_proof.unwrap()).unwrap();
+
+            assert_eq!(account_hash, expected.value);
+            assert_eq!(account_proof, expected.path);
+        }
+    }
+
+    async fn nullifier_proofs(&mut self) {
+        // Some of the nullifiers are never consumed
+        let nullifiers: Vec<_> =
+            (0..2).map(|_| mock_nullifier(self.rng.gen_range(1..=2 * NUM_BLOCKS))).collect();
+        let (anchor, proofs) = self.state.check_nullifiers(&nullifiers).await;
+        self.observe(&anchor);
+
+        let nullifier_tree = &self.workload.nullifier_trees[anchor.block_num as usize];
+        for (nullifier, proof) in nullifiers.iter().zip(proofs) {
+            assert_eq!(proof, nullifier_tree.open(&nullifier.inner()));
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+/// Loads the state of a new store, and returns it along with its genesis block.
+async fn load_state(dir: &std::path::Path) -> (State, Block) {
+    let config = StoreConfig {
+        database_filepath: dir.join("miden-store.sqlite3"),
+        genesis_filepath: dir.join("genesis.dat"),
+        blockstore_dir: dir.join("blocks"),
+        ..Default::default()
+    };
+    let genesis = GenesisState::new(vec![], 1, 0);
+    fs::write(&config.genesis_filepath, genesis.to_bytes()).unwrap();
+
+    let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+    let db = Db::setup(config, Arc::clone(&block_store)).await.unwrap();
+
+    (State::load(db, block_store).await.unwrap(), genesis.into_block().unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_reads_are_consistent_with_their_chain_tip() {
+    for seed in SEEDS {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, genesis) = load_state(dir.path()).await;
+        let state = Arc::new(state);
+        let workload = Arc::new(Workload::new(genesis, seed));
+        let applied = Arc::new(AtomicBool::new(false));
+
+        let clients: Vec<_> = (0..NUM_CLIENTS)
+            .map(|client| {
+                let client =
+                    Client::new(Arc::clone(&state), Arc::clone(&workload), seed * 100 + client);
+                tokio::spawn(client.run(Arc::clone(&applied)))
+            })
+            .collect();
+
+        for block in &workload.blocks[1..] {
+            state.apply_block(block.clone()).await.unwrap();
+            tokio::task::yield_now().await;
+        }
+        applied.store(true, Ordering::Release);
+
+        let mut reads = 0;
+        for client in clients {
+            reads += client.await.unwrap();
+        }
+        assert!(reads > 0, "no read was served while the blocks of workload {seed} were applied");
+    }
+}
@@ -0,0 +1,339 @@
+//! Regression fixtures of the chain state computed by the store.
+//!
+//! Every fixture applies a deterministic block sequence on top of a deterministic genesis state,
+//! through [State::apply_block]. The resulting chain tip, chain MMR root, account root and
+//! nullifier root are compared against the golden values committed in
+//! `fixtures/chain_state_goldens.txt`, so that a change of the commitments computed for identical
+//! blocks, e.g. by a tree library upgrade, fails the tests.
+//!
+//! If the change is intentional, the goldens are regenerated by running the tests with
+//! `UPDATE_STORE_GOLDENS` set to `1`.
+
+use std::{collections::BTreeMap, env, fmt::Write, fs, path::Path, sync::Arc};
+
+use miden_node_test_utils::{
+    block::MockChain,
+    mock_account_id,
+    note::{mock_note, mock_nullifier, mock_output_note},
+    MockPrivateAccount, MockProvenTxBuilder,
+};
+use miden_objects::{
+    accounts::delta::AccountUpdateDetails,
+    block::{Block, BlockAccountUpdate},
+    transaction::OutputNote,
+    utils::Serializable,
+    Digest,
+};
+
+use super::State;
+use crate::{
+    blocks::BlockStore,
+    config::StoreConfig,
+    db::Db,
+    errors::{ApplyBlockError, InvalidBlockError},
+    genesis::GenesisState,
+};
+
+const UPDATE_GOLDENS_COMMAND: &str =
+    "UPDATE_STORE_GOLDENS=1 cargo test -p miden-node-store fixtures";
+
+/// Timestamp of the genesis of the fixtures which don't start at the epoch.
+const GENESIS_TIMESTAMP: u32 = 1_700_000_000;
+
+// FIXTURES
+// ================================================================================================
+
+/// A block of a fixture, along with the expected outcome of applying it.
+enum Step {
+    Apply(Block),
+    /// The block must be rejected for consuming nullifiers which were already consumed.
+    RejectRespentNullifiers(Block),
+}
+
+struct Fixture {
+    name: &'static str,
+    genesis_timestamp: u32,
+    blocks: fn(&mut MockChain) -> Vec<Step>,
+}
+
+const FIXTURES: [Fixture; 5] = [
+    Fixture {
+        name: "empty_blocks",
+        genesis_timestamp: 0,
+        blocks: empty_blocks,
+    },
+    Fixture {
+        name: "private_accounts",
+        genesis_timestamp: GENESIS_TIMESTAMP,
+        blocks: private_accounts,
+    },
+    Fixture {
+        name: "notes_and_nullifiers",
+        genesis_timestamp: GENESIS_TIMESTAMP,
+        blocks: notes_and_nullifiers,
+    },
+    Fixture {
+        name: "erased_notes",
+        genesis_timestamp: GENESIS_TIMESTAMP,
+        blocks: erased_notes,
+    },
+    Fixture {
+        name: "respent_nullifier",
+        genesis_timestamp: GENESIS_TIMESTAMP,
+        blocks: respent_nullifier,
+    },
+];
+
+/// Blocks without any account update, note or nullifier.
+fn empty_blocks(chain: &mut MockChain) -> Vec<Step> {
+    (0..3).map(|_| Step::Apply(chain.next_block().build())).collect()
+}
+
+/// Blocks only updating private accounts, including an account updated by several transactions.
+fn private_accounts(chain: &mut MockChain) -> Vec<Step> {
+    let accounts: Vec<MockPrivateAccount> = (1..=3).map(MockPrivateAccount::from).collect();
+    let txs: Vec<_> = accounts
+        .iter()
+        .map(|account| {
+            MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1])
+                .build()
+        })
+        .collect();
+    let first = chain.next_block().transactions(&txs).build();
+
+    let account = accounts[0];
+    let tx_ids = [1, 2].map(|i| {
+        MockProvenTxBuilder::with_account(account.id, account.states[i - 1], account.states[i])
+            .build()
+            .id()
+    });
+    let second = chain
+        .next_block()
+        .account_updates(vec![BlockAccountUpdate::new(
+            account.id,
+            account.states[2],
+            AccountUpdateDetails::Private,
+            tx_ids.to_vec(),
+        )])
+        .build();
+
+    vec![Step::Apply(first), Step::Apply(second)]
+}
+
+/// Blocks creating notes in several batches, and consuming notes.
+fn notes_and_nullifiers(chain: &mut MockChain) -> Vec<Step> {
+    let first = chain
+        .next_block()
+        .output_notes(vec![
+            vec![mock_output_note(1)],
+            vec![mock_output_note(2), mock_output_note(3)],
+        ])
+        .build();
+    let second = chain
+        .next_block()
+        .output_notes(vec![vec![mock_output_note(4)]])
+        .nullifiers(vec![mock_note(1).nullifier(), mock_nullifier(1), mock_nullifier(2)])
+        .build();
+
+    vec![Step::Apply(first), Step::Apply(second)]
+}
+
+/// A block whose transactions created and consumed a note, which was erased from the block along
+/// with its nullifier.
+fn erased_notes(chain: &mut MockChain) -> Vec<Step> {
+    let erased_note = mock_note(5);
+    let kept_note = mock_output_note(6);
+    let creator = MockProvenTxBuilder::with_account_index(1)
+        .output_notes(vec![OutputNote::Full(erased_note.clone()), kept_note.clone()])
+        .build();
+    let consumer = MockProvenTxBuilder::with_account_index(2)
+        .unauthenticated_notes(vec![erased_note])
+        .build();
+
+    let updates = [creator, consumer]
+        .iter()
+        .map(|tx| {
+            BlockAccountUpdate::new(
+                tx.account_id(),
+                tx.account_update().final_state_hash(),
+                AccountUpdateDetails::Private,
+                vec![tx.id()],
+            )
+        })
+        .collect();
+    let block = chain
+        .next_block()
+        .account_updates(updates)
+        .output_notes(vec![vec![kept_note]])
+        .build();
+
+    vec![Step::Apply(block)]
+}
+
+/// A block consuming a nullifier consumed by an earlier block, which must be rejected without
+/// changing the state.
+fn respent_nullifier(chain: &mut MockChain) -> Vec<Step> {
+    let first = chain.next_block().nullifiers(vec![mock_nullifier(7)]).build();
+    let respent = chain
+        .clone()
+        .next_block()
+        .account_updates(vec![BlockAccountUpdate::new(
+            mock_account_id(8),
+            MockPrivateAccount::<3>::from(8).states[1],
+            AccountUpdateDetails::Private,
+            vec![],
+        )])
+        .nullifiers(vec![mock_nullifier(8), mock_nullifier(7)])
+        .build();
+    let next = chain.next_block().nullifiers(vec![mock_nullifier(8)]).build();
+
+    vec![Step::Apply(first), Step::RejectRespentNullifiers(respent), Step::Apply(next)]
+}
+
+// CHAIN STATE
+// ================================================================================================
+
+/// Commitments of the chain state of the store.
+struct ChainState {
+    block_num: u32,
+    chain_tip: Digest,
+    chain_root: Digest,
+    account_root: Digest,
+    nullifier_root: Digest,
+}
+
+impl ChainState {
+    async fn read(state: &State) -> Self {
+        let latest = state.db.select_block_header_by_block_num(None).await.unwrap().unwrap();
+        let inner = state.inner.read().await;
+
+        let chain_state = Self {
+            block_num: latest.block_num(),
+            chain_tip: latest.hash(),
+            chain_root: inner.chain_mmr.peaks().hash_peaks(),
+            account_root: inner.account_tree.root(),
+            nullifier_root: inner.nullifier_tree.root(),
+        };
+
+        // The in-memory trees must agree with the committed chain tip
+        assert_eq!(chain_state.block_num, inner.latest_block_num());
+        assert_eq!(chain_state.account_root, latest.account_root());
+        assert_eq!(chain_state.nullifier_root, latest.nullifier_root());
+
+        chain_state
+    }
+
+    /// Returns the golden entry of the chain state, as lines indented by 4 spaces.
+    fn to_golden(&self) -> String {
+        format!(
+            "    block_num {}\n    chain_tip {}\n    chain_root {}\n    account_root {}\n    \
+             nullifier_root {}\n",
+            self.block_num, self.chain_tip, self.chain_root, self.account_root, self.nullifier_root
+        )
+    }
+}
+
+/// Applies the blocks of the fixture to a new store, and returns its final chain state.
+async fn run_fixture(fixture: &Fixture, dir: &Path) -> ChainState {
+    let config = StoreConfig {
+        database_filepath: dir.join("miden-store.sqlite3"),
+        genesis_filepath: dir.join("genesis.dat"),
+        blockstore_dir: dir.join("blocks"),
+        ..Default::default()
+    };
+    let genesis = GenesisState::new(vec![], 1, fixture.genesis_timestamp);
+    fs::write(&config.genesis_filepath, genesis.to_bytes()).unwrap();
+    let mut chain = MockChain::new(&genesis.into_block().unwrap());
+
+    let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await.unwrap());
+    let db = Db::setup(config, Arc::clone(&block_store)).await.unwrap();
+    let state = State::load(db, block_store).await.unwrap();
+
+    for step in (fixture.blocks)(&mut chain) {
+        match step {
+            Step::Apply(block) => state.apply_block(block).await.unwrap(),
+            Step::RejectRespentNullifiers(block) => {
+                let err = state.apply_block(block).await.unwrap_err();
+                assert!(
+                    matches!(
+                        err,
+                        ApplyBlockError::InvalidBlockError(
+                            InvalidBlockError::DuplicatedNullifiers(_)
+                        )
+                    ),
+                    "{err}"
+                );
+            },
+        }
+    }
+
+    ChainState::read(&state).await
+}
+
+/// Returns the golden entries of the fixtures, by name.
+fn parse_goldens(goldens: &str) -> BTreeMap<String, String> {
+    let mut entries = BTreeMap::new();
+    let mut current = None;
+    for line in goldens.lines().filter(|line| !line.starts_with('#')) {
+        if let Some(name) = line.strip_prefix("fixture ") {
+            current = Some(name.to_string());
+        } else if let Some(name) = &current {
+            let entry: &mut String = entries.entry(name.clone()).or_default();
+            writeln!(entry, "{line}").unwrap();
+        }
+    }
+
+    entries
+}
+
+// TESTS
+// ================================================================================================
+
+/// Checks that the chain states of the fixtures match the committed goldens. The goldens are
+/// updated by running the tests with `UPDATE_STORE_GOLDENS` set to `1`.
+#[tokio::test]
+async fn chain_state_matches_goldens() {
+    let goldens_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join("chain_state_goldens.txt");
+
+    let mut actual = BTreeMap::new();
+    for fixture in &FIXTURES {
+        let dir = tempfile::tempdir().unwrap();
+        let chain_state = run_fixture(fixture, dir.path()).await;
+        actual.insert(fixture.name.to_string(), chain_state.to_golden());
+    }
+
+    if env::var("UPDATE_STORE_GOLDENS").is_ok_and(|var| var == "1") {
+        let mut goldens = format!(
+            "# Chain state of the store after applying the fixtures of `state/fixtures.rs`, \
+             generated by `{UPDATE_GOLDENS_COMMAND}`.\n"
+        );
+        for fixture in &FIXTURES {
+            write!(goldens, "fixture {}\n{}", fixture.name, actual[fixture.name]).unwrap();
+        }
+        fs::write(&goldens_path, goldens).unwrap();
+        return;
+    }
+
+    let expected = parse_goldens(&fs::read_to_string(&goldens_path).unwrap());
+    let divergences: Vec<String> = FIXTURES
+        .iter()
+        .filter(|fixture| expected.get(fixture.name) != actual.get(fixture.name))
+        .map(|fixture| {
+            format!(
+                "fixture {}\n  expected:\n{}  actual:\n{}",
+                fixture.name,
+                expected.get(fixture.name).map_or("    <missing>\n", String::as_str),
+                actual[fixture.name]
+            )
+        })
+        .collect();
+
+    assert!(
+        divergences.is_empty(),
+        "The chain state computed by the store diverged from the goldens:\n{}\nIf the change is \
+         intentional, run `{UPDATE_GOLDENS_COMMAND}` to update the goldens.",
+        divergences.join("\n")
+    );
+}
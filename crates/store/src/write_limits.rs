@@ -0,0 +1,212 @@
+//! Sanity limits on the size of the applied blocks.
+//!
+//! The store trusts the block producer to only send blocks built from valid batches, but a corrupt
+//! or malicious `ApplyBlock` payload could still hold absurdly many notes, or multi-megabyte note
+//! and account details, which would be written to the database and break its readers later on.
+//! The limits are checked before anything is written, and default to the maximums allowed by the
+//! protocol.
+
+/// Default maximum size in bytes of the serialized details of a public note, matching the
+/// limit on the notes of the transactions submitted to the block producer.
+pub use miden_node_utils::message_limits::DEFAULT_MAX_NOTE_DETAILS_SIZE;
+use miden_objects::{
+    accounts::delta::AccountUpdateDetails, block::Block, crypto::merkle::MerklePath, notes::NoteId,
+    transaction::OutputNote, utils::Serializable, BLOCK_NOTE_TREE_DEPTH, MAX_ACCOUNTS_PER_BATCH,
+    MAX_BATCHES_PER_BLOCK, MAX_INPUT_NOTES_PER_BATCH, MAX_OUTPUT_NOTES_PER_BATCH,
+};
+
+use crate::{
+    config::WriteLimitsConfig,
+    errors::{InvalidBlockError, WriteLimitsError},
+};
+
+/// Default maximum size in bytes of the serialized details of an account update, so that the
+/// details of the account can be served within the default message size limit of gRPC clients.
+pub const DEFAULT_MAX_ACCOUNT_DETAILS_SIZE: usize = 1024 * 1024;
+
+// WRITE LIMITS
+// ================================================================================================
+
+/// Limits on the size of the blocks applied to the state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteLimits {
+    /// Maximum depth of the merkle paths of the notes in the note tree of their block.
+    pub max_note_path_depth: u8,
+    /// Maximum number of notes created by a block.
+    pub max_notes_per_block: usize,
+    /// Maximum number of nullifiers of a block.
+    pub max_nullifiers_per_block: usize,
+    /// Maximum number of accounts updated by a block.
+    pub max_accounts_per_block: usize,
+    /// Maximum size in bytes of the serialized details of a public note.
+    pub max_note_details_size: usize,
+    /// Maximum size in bytes of the serialized details of an account update.
+    pub max_account_details_size: usize,
+}
+
+impl WriteLimits {
+    /// Checks the number of notes, nullifiers and account updates of the block, and the size of
+    /// the details of its notes and accounts.
+    pub fn check_block(&self, block: &Block) -> Result<(), InvalidBlockError> {
+        let num_notes = block.notes().count();
+        if num_notes > self.max_notes_per_block {
+            return Err(InvalidBlockError::TooManyNotes {
+                count: num_notes,
+                limit: self.max_notes_per_block,
+            });
+        }
+        if block.nullifiers().len() > self.max_nullifiers_per_block {
+            return Err(InvalidBlockError::TooManyNullifiers {
+                count: block.nullifiers().len(),
+                limit: self.max_nullifiers_per_block,
+            });
+        }
+        if block.updated_accounts().len() > self.max_accounts_per_block {
+            return Err(InvalidBlockError::TooManyAccountUpdates {
+                count: block.updated_accounts().len(),
+                limit: self.max_accounts_per_block,
+            });
+        }
+
+        for (_, note) in block.notes() {
+            let OutputNote::Full(details) = note else {
+                continue;
+            };
+            let size = details.to_bytes().len();
+            if size > self.max_note_details_size {
+                return Err(InvalidBlockError::NoteDetailsTooLarge {
+                    note_id: note.id(),
+                    size,
+                    limit: self.max_note_details_size,
+                });
+            }
+        }
+
+        for update in block.updated_accounts() {
+            let size = match update.details() {
+                AccountUpdateDetails::Private => continue,
+                details => details.to_bytes().len(),
+            };
+            if size > self.max_account_details_size {
+                return Err(InvalidBlockError::AccountDetailsTooLarge {
+                    account_id: update.account_id().into(),
+                    size,
+                    limit: self.max_account_details_size,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the depth of the merkle path of a note in the note tree of its block.
+    pub fn check_note_path(
+        &self,
+        note_id: NoteId,
+        merkle_path: &MerklePath,
+    ) -> Result<(), InvalidBlockError> {
+        if merkle_path.depth() > self.max_note_path_depth {
+            return Err(InvalidBlockError::NoteMerklePathTooDeep {
+                note_id,
+                depth: merkle_path.depth(),
+                limit: self.max_note_path_depth,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for WriteLimits {
+    fn default() -> Self {
+        Self {
+            max_note_path_depth: BLOCK_NOTE_TREE_DEPTH,
+            max_notes_per_block: MAX_OUTPUT_NOTES_PER_BATCH * MAX_BATCHES_PER_BLOCK,
+            max_nullifiers_per_block: MAX_INPUT_NOTES_PER_BATCH * MAX_BATCHES_PER_BLOCK,
+            max_accounts_per_block: MAX_ACCOUNTS_PER_BATCH * MAX_BATCHES_PER_BLOCK,
+            max_note_details_size: DEFAULT_MAX_NOTE_DETAILS_SIZE,
+            max_account_details_size: DEFAULT_MAX_ACCOUNT_DETAILS_SIZE,
+        }
+    }
+}
+
+impl TryFrom<&WriteLimitsConfig> for WriteLimits {
+    type Error = WriteLimitsError;
+
+    /// Raises the default limits to the configured ones, rejecting the limits lower than their
+    /// defaults.
+    fn try_from(config: &WriteLimitsConfig) -> Result<Self, Self::Error> {
+        let defaults = Self::default();
+        let raise = |name: &'static str, configured: Option<usize>, default: usize| match configured
+        {
+            Some(value) if value < default => {
+                Err(WriteLimitsError::BelowDefault { name, value, default })
+            },
+            Some(value) => Ok(value),
+            None => Ok(default),
+        };
+
+        Ok(Self {
+            max_note_path_depth: defaults.max_note_path_depth,
+            max_notes_per_block: raise(
+                "max_notes_per_block",
+                config.max_notes_per_block,
+                defaults.max_notes_per_block,
+            )?,
+            max_nullifiers_per_block: raise(
+                "max_nullifiers_per_block",
+                config.max_nullifiers_per_block,
+                defaults.max_nullifiers_per_block,
+            )?,
+            max_accounts_per_block: raise(
+                "max_accounts_per_block",
+                config.max_accounts_per_block,
+                defaults.max_accounts_per_block,
+            )?,
+            max_note_details_size: raise(
+                "max_note_details_size",
+                config.max_note_details_size,
+                defaults.max_note_details_size,
+            )?,
+            max_account_details_size: raise(
+                "max_account_details_size",
+                config.max_account_details_size,
+                defaults.max_account_details_size,
+            )?,
+        })
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_limits_can_only_be_raised() {
+        let defaults = WriteLimits::default();
+        assert_eq!(WriteLimits::try_from(&WriteLimitsConfig::default()).unwrap(), defaults);
+
+        let config = WriteLimitsConfig {
+            max_notes_per_block: Some(defaults.max_notes_per_block * 2),
+            max_note_details_size: Some(defaults.max_note_details_size),
+            ..Default::default()
+        };
+        let limits = WriteLimits::try_from(&config).unwrap();
+        assert_eq!(limits.max_notes_per_block, defaults.max_notes_per_block * 2);
+        assert_eq!(limits.max_note_details_size, defaults.max_note_details_size);
+        assert_eq!(limits.max_nullifiers_per_block, defaults.max_nullifiers_per_block);
+
+        let config = WriteLimitsConfig {
+            max_account_details_size: Some(defaults.max_account_details_size - 1),
+            ..Default::default()
+        };
+        let err = WriteLimits::try_from(&config).unwrap_err();
+        assert!(
+            matches!(err, WriteLimitsError::BelowDefault { name: "max_account_details_size", .. }),
+            "{err}"
+        );
+    }
+}
@@ -1,16 +1,14 @@
 use std::{collections::HashMap, ops::Not, sync::LazyLock};
 
 use miden_objects::{
-    accounts::{get_account_seed, AccountStorageMode, AccountType},
-    Hasher,
+    accounts::{get_account_seed, AccountId, AccountStorageMode, AccountType},
+    Digest, Hasher,
 };
 
-use super::*;
-
 pub static MOCK_ACCOUNTS: LazyLock<std::sync::Mutex<HashMap<u32, (AccountId, Digest)>>> =
     LazyLock::new(Default::default);
 
-/// A mock representation fo private accounts. An account starts in state `states[0]`, is modified
+/// A mock representation of private accounts. An account starts in state `states[0]`, is modified
 /// to state `states[1]`, and so on.
 #[derive(Clone, Copy, Debug)]
 pub struct MockPrivateAccount<const NUM_STATES: usize = 3> {
@@ -0,0 +1,205 @@
+use miden_objects::{
+    accounts::delta::AccountUpdateDetails,
+    block::{Block, BlockAccountUpdate, BlockNoteIndex, BlockNoteTree, NoteBatch},
+    crypto::merkle::{Mmr, SimpleSmt, Smt},
+    notes::Nullifier,
+    transaction::{OutputNote, ProvenTransaction},
+    BlockHeader, Digest, Felt, ACCOUNT_TREE_DEPTH, ZERO,
+};
+
+// MOCK CHAIN
+// ================================================================================================
+
+/// A chain of blocks which can be applied to the store, starting from its genesis block.
+///
+/// The chain tracks the chain MMR, the account tree and the nullifier tree, so that every block
+/// it builds passes the store's checks against the previous one. Blocks carry no proof.
+#[derive(Debug, Clone)]
+pub struct MockChain {
+    last_header: BlockHeader,
+    chain_mmr: Mmr,
+    accounts: SimpleSmt<ACCOUNT_TREE_DEPTH>,
+    nullifiers: Smt,
+}
+
+impl MockChain {
+    /// Starts a chain at the `genesis` block.
+    pub fn new(genesis: &Block) -> Self {
+        let mut chain_mmr = Mmr::new();
+        chain_mmr.add(genesis.hash());
+
+        let accounts = SimpleSmt::with_leaves(
+            genesis
+                .updated_accounts()
+                .iter()
+                .map(|update| (update.account_id().into(), update.new_state_hash().into())),
+        )
+        .unwrap();
+
+        Self {
+            last_header: genesis.header(),
+            chain_mmr,
+            accounts,
+            nullifiers: Smt::default(),
+        }
+    }
+
+    /// Returns the header of the latest block of the chain.
+    pub fn latest_header(&self) -> BlockHeader {
+        self.last_header
+    }
+
+    /// Starts building the next block of the chain.
+    pub fn next_block(&mut self) -> MockChainBlockBuilder<'_> {
+        MockChainBlockBuilder {
+            chain: self,
+            updated_accounts: Vec::new(),
+            output_notes: Vec::new(),
+            nullifiers: Vec::new(),
+            timestamp: None,
+            version: None,
+        }
+    }
+}
+
+// MOCK CHAIN BLOCK BUILDER
+// ================================================================================================
+
+/// Builds the next block of a [MockChain].
+#[derive(Debug)]
+pub struct MockChainBlockBuilder<'a> {
+    chain: &'a mut MockChain,
+    updated_accounts: Vec<BlockAccountUpdate>,
+    output_notes: Vec<NoteBatch>,
+    nullifiers: Vec<Nullifier>,
+    timestamp: Option<u32>,
+    version: Option<u32>,
+}
+
+impl MockChainBlockBuilder<'_> {
+    pub fn account_updates(mut self, updated_accounts: Vec<BlockAccountUpdate>) -> Self {
+        self.updated_accounts.extend(updated_accounts);
+
+        self
+    }
+
+    pub fn output_notes(mut self, output_notes: Vec<NoteBatch>) -> Self {
+        self.output_notes.extend(output_notes);
+
+        self
+    }
+
+    pub fn nullifiers(mut self, nullifiers: Vec<Nullifier>) -> Self {
+        self.nullifiers.extend(nullifiers);
+
+        self
+    }
+
+    /// Sets the timestamp of the block, one second after the previous block by default.
+    pub fn timestamp(mut self, timestamp: u32) -> Self {
+        self.timestamp = Some(timestamp);
+
+        self
+    }
+
+    /// Sets the version of the block, the version of the previous block by default.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = Some(version);
+
+        self
+    }
+
+    /// Includes the transactions in the block, as a single batch.
+    ///
+    /// The transactions must update private accounts, and update every account at most once.
+    pub fn transactions(self, txs: &[ProvenTransaction]) -> Self {
+        let updated_accounts = txs
+            .iter()
+            .map(|tx| {
+                BlockAccountUpdate::new(
+                    tx.account_id(),
+                    tx.account_update().final_state_hash(),
+                    AccountUpdateDetails::Private,
+                    vec![tx.id()],
+                )
+            })
+            .collect();
+        let nullifiers = txs.iter().flat_map(ProvenTransaction::get_nullifiers).collect();
+        let output_notes: NoteBatch =
+            txs.iter().flat_map(|tx| tx.output_notes().iter().cloned()).collect();
+        let output_notes = if output_notes.is_empty() {
+            vec![]
+        } else {
+            vec![output_notes]
+        };
+
+        self.account_updates(updated_accounts)
+            .nullifiers(nullifiers)
+            .output_notes(output_notes)
+    }
+
+    /// Builds the block and appends it to the chain.
+    pub fn build(self) -> Block {
+        let chain = self.chain;
+        let block_num = chain.last_header.block_num() + 1;
+
+        let mut accounts = chain.accounts.clone();
+        for update in &self.updated_accounts {
+            accounts.insert(update.account_id().into(), update.new_state_hash().into());
+        }
+
+        // The store maps nullifiers to the number of the block consuming them
+        let mut nullifiers = chain.nullifiers.clone();
+        for nullifier in &self.nullifiers {
+            nullifiers.insert(nullifier.inner(), [Felt::from(block_num), ZERO, ZERO, ZERO]);
+        }
+
+        let note_tree = note_created_smt_from_note_batches(self.output_notes.iter());
+
+        let header = BlockHeader::new(
+            self.version.unwrap_or(chain.last_header.version()),
+            chain.last_header.hash(),
+            block_num,
+            chain.chain_mmr.peaks().hash_peaks(),
+            accounts.root(),
+            nullifiers.root(),
+            note_tree.root(),
+            Digest::default(),
+            chain.last_header.kernel_root(),
+            Digest::default(),
+            self.timestamp.unwrap_or(chain.last_header.timestamp() + 1),
+        );
+
+        let block =
+            Block::new(header, self.updated_accounts, self.output_notes, self.nullifiers).unwrap();
+
+        chain.chain_mmr.add(block.hash());
+        chain.last_header = header;
+        chain.accounts = accounts;
+        chain.nullifiers = nullifiers;
+
+        block
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+pub fn flatten_output_notes<'a>(
+    batches: impl Iterator<Item = &'a NoteBatch>,
+) -> impl Iterator<Item = (BlockNoteIndex, &'a OutputNote)> {
+    batches.enumerate().flat_map(|(batch_idx, batch)| {
+        batch.iter().enumerate().map(move |(note_idx_in_batch, note)| {
+            (BlockNoteIndex::new(batch_idx, note_idx_in_batch).unwrap(), note)
+        })
+    })
+}
+
+pub fn note_created_smt_from_note_batches<'a>(
+    batches: impl Iterator<Item = &'a NoteBatch>,
+) -> BlockNoteTree {
+    let note_leaf_iterator =
+        flatten_output_notes(batches).map(|(index, note)| (index, note.id(), *note.metadata()));
+
+    BlockNoteTree::with_entries(note_leaf_iterator).unwrap()
+}
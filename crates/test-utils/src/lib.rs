@@ -0,0 +1,16 @@
+//! Deterministic fixtures shared by the tests of the node components.
+//!
+//! The same seed always gives rise to the same account, note, nullifier or transaction, so the
+//! fixtures of different crates agree with each other.
+
+mod account;
+
+pub use account::{mock_account_id, MockPrivateAccount};
+
+mod proven_tx;
+
+pub use proven_tx::{mock_proven_tx, MockProvenTxBuilder};
+
+pub mod block;
+
+pub mod note;
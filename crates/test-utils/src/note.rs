@@ -0,0 +1,65 @@
+use miden_lib::transaction::TransactionKernel;
+use miden_objects::{
+    notes::{Note, NoteType, Nullifier},
+    testing::notes::NoteBuilder,
+    transaction::{InputNote, InputNoteCommitment, OutputNote},
+    Hasher, EMPTY_WORD, ZERO,
+};
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+use crate::mock_account_id;
+
+pub fn mock_note(num: u8) -> Note {
+    let sender = mock_account_id(num);
+    NoteBuilder::new(sender, ChaCha20Rng::from_seed([num; 32]))
+        .build(&TransactionKernel::assembler().with_debug_mode(true))
+        .unwrap()
+}
+
+/// Returns a note with the given type and tag, without checking that they are consistent.
+pub fn mock_note_with_tag(num: u8, note_type: NoteType, tag: u32) -> Note {
+    let sender = mock_account_id(num);
+    NoteBuilder::new(sender, ChaCha20Rng::from_seed([num; 32]))
+        .note_type(note_type)
+        .tag(tag)
+        .build(&TransactionKernel::assembler().with_debug_mode(true))
+        .unwrap()
+}
+
+pub fn mock_unauthenticated_note_commitment(num: u8) -> InputNoteCommitment {
+    InputNote::unauthenticated(mock_note(num)).into()
+}
+
+pub fn mock_output_note(num: u8) -> OutputNote {
+    OutputNote::Full(mock_note(num))
+}
+
+/// Returns output notes which must be rejected by the node, one per inconsistency between a note's
+/// tag, its metadata and its details.
+pub fn mock_invalid_output_notes() -> Vec<OutputNote> {
+    vec![
+        // Network execution tag on a private note
+        OutputNote::Header(*mock_note_with_tag(20, NoteType::Private, 0).header()),
+        // Network execution tag which doesn't hold an account prefix
+        OutputNote::Full(mock_note_with_tag(21, NoteType::Public, 0x4000_0000)),
+        // Public note without its details
+        OutputNote::Header(*mock_note_with_tag(22, NoteType::Public, 0).header()),
+        // Private note with its details
+        OutputNote::Full(mock_note_with_tag(23, NoteType::Private, 0xc000_0000)),
+    ]
+}
+
+/// Returns the nullifier of a note with the given index, without building the note.
+pub fn mock_nullifier(index: u32) -> Nullifier {
+    Nullifier::new(
+        Hasher::hash(&index.to_be_bytes()),
+        Hasher::hash(
+            &[index.to_be_bytes(), index.to_be_bytes()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>(),
+        ),
+        EMPTY_WORD.into(),
+        [ZERO, ZERO, ZERO, index.into()],
+    )
+}
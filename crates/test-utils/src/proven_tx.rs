@@ -1,21 +1,23 @@
 use std::ops::Range;
 
 use miden_air::HashFunction;
+use miden_node_utils::block_num::NEVER_EXPIRES;
 use miden_objects::{
     accounts::AccountId,
-    notes::{Note, NoteExecutionHint, NoteHeader, NoteMetadata, NoteType, Nullifier},
+    notes::{Note, NoteExecutionHint, NoteHeader, NoteMetadata, NoteTag, NoteType, Nullifier},
     transaction::{InputNote, OutputNote, ProvenTransaction, ProvenTransactionBuilder},
     vm::ExecutionProof,
     Digest, Felt, Hasher, ONE,
 };
 use winterfell::Proof;
 
-use super::MockPrivateAccount;
+use crate::MockPrivateAccount;
 
 pub struct MockProvenTxBuilder {
     account_id: AccountId,
     initial_account_hash: Digest,
     final_account_hash: Digest,
+    block_ref: Digest,
     expiration_block_num: u32,
     output_notes: Option<Vec<OutputNote>>,
     input_notes: Option<Vec<InputNote>>,
@@ -38,7 +40,8 @@ impl MockProvenTxBuilder {
             account_id,
             initial_account_hash,
             final_account_hash,
-            expiration_block_num: u32::MAX,
+            block_ref: Digest::default(),
+            expiration_block_num: NEVER_EXPIRES,
             output_notes: None,
             input_notes: None,
             nullifiers: None,
@@ -57,6 +60,12 @@ impl MockProvenTxBuilder {
         self
     }
 
+    pub fn block_ref(mut self, block_ref: Digest) -> Self {
+        self.block_ref = block_ref;
+
+        self
+    }
+
     pub fn expiration_block_num(mut self, expiration_block_num: u32) -> Self {
         self.expiration_block_num = expiration_block_num;
 
@@ -88,7 +97,7 @@ impl MockProvenTxBuilder {
                 let note_metadata = NoteMetadata::new(
                     self.account_id,
                     NoteType::Private,
-                    0.into(),
+                    NoteTag::for_local_use_case(0, 0).unwrap(),
                     NoteExecutionHint::none(),
                     ONE,
                 )
@@ -106,7 +115,7 @@ impl MockProvenTxBuilder {
             self.account_id,
             self.initial_account_hash,
             self.final_account_hash,
-            Digest::default(),
+            self.block_ref,
             self.expiration_block_num,
             ExecutionProof::new(Proof::new_dummy(), HashFunction::Blake3_192),
         )
@@ -0,0 +1,87 @@
+//! Arithmetic on block numbers.
+//!
+//! Block numbers are `u32`s, so plain `+` overflows near `u32::MAX`: it panics in debug builds and
+//! wraps around to the genesis block in release builds. Block numbers are only advanced and
+//! rewound through [BlockNumberExt], which makes the behavior at the boundaries explicit.
+
+/// Expiration block number of transactions which never expire.
+///
+/// Such transactions can be included in any block, including the last one.
+pub const NEVER_EXPIRES: u32 = u32::MAX;
+
+/// Checked and saturating arithmetic on block numbers.
+pub trait BlockNumberExt: Copy {
+    /// Returns the number of the block following this one, or `None` if this is the last block
+    /// number.
+    fn child(self) -> Option<u32>;
+
+    /// Returns the number of the block `blocks` blocks after this one, saturating at the last
+    /// block number.
+    fn saturating_advance(self, blocks: u32) -> u32;
+
+    /// Returns the number of the block `blocks` blocks before this one, saturating at the genesis
+    /// block.
+    fn saturating_rewind(self, blocks: u32) -> u32;
+
+    /// Returns `true` if a transaction expiring at this block number can't be included in block
+    /// `block_num` or later.
+    ///
+    /// Transactions expiring at [NEVER_EXPIRES] never expire, even when `block_num` saturated at
+    /// the last block number.
+    fn expires_before(self, block_num: u32) -> bool;
+}
+
+impl BlockNumberExt for u32 {
+    fn child(self) -> Option<u32> {
+        self.checked_add(1)
+    }
+
+    fn saturating_advance(self, blocks: u32) -> u32 {
+        self.saturating_add(blocks)
+    }
+
+    fn saturating_rewind(self, blocks: u32) -> u32 {
+        self.saturating_sub(blocks)
+    }
+
+    fn expires_before(self, block_num: u32) -> bool {
+        self != NEVER_EXPIRES && self < block_num
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_saturates_at_the_boundaries() {
+        assert_eq!(0_u32.child(), Some(1));
+        assert_eq!((u32::MAX - 1).child(), Some(u32::MAX));
+        assert_eq!(u32::MAX.child(), None);
+
+        assert_eq!((u32::MAX - 2).saturating_advance(2), u32::MAX);
+        assert_eq!((u32::MAX - 2).saturating_advance(3), u32::MAX);
+        assert_eq!(u32::MAX.saturating_advance(u32::MAX), u32::MAX);
+
+        assert_eq!(2_u32.saturating_rewind(2), 0);
+        assert_eq!(2_u32.saturating_rewind(3), 0);
+        assert_eq!(u32::MAX.saturating_rewind(u32::MAX), 0);
+    }
+
+    #[test]
+    fn expiration_at_the_last_block_numbers() {
+        assert!(!10_u32.expires_before(10));
+        assert!(10_u32.expires_before(11));
+
+        assert!(!(u32::MAX - 1).expires_before(u32::MAX - 1));
+        assert!((u32::MAX - 1).expires_before(u32::MAX));
+
+        // The inclusion block with a slack saturates at the last block number
+        let inclusion_block = (u32::MAX - 1).saturating_advance(5);
+        assert!(!NEVER_EXPIRES.expires_before(inclusion_block));
+        assert!(!NEVER_EXPIRES.expires_before(0));
+    }
+}
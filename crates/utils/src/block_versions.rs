@@ -0,0 +1,200 @@
+//! Versions of the blocks supported by the node.
+//!
+//! Block headers carry the version of the protocol they were built with. Rolling out a new version
+//! across a network requires every store to accept it, and every block producer to switch to it at
+//! the same height, so both components share the [BlockVersions] configured for the network:
+//!
+//! - the store rejects the applied blocks whose version is not supported at their height.
+//! - the block producer stamps the blocks it builds with the version of their parent, raised to the
+//!   minimum supported at their height, and refuses to build blocks whose parent version is no
+//!   longer supported.
+//!
+//! An upgrade is scheduled by configuring the range of versions supported from its activation
+//! block onwards. Both components report their configuration through their
+//! `GetSupportedBlockVersions` endpoint, so that operators can check that all the nodes are ready
+//! before the activation block is reached.
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Oldest version of the blocks built by the node.
+pub const MIN_BLOCK_VERSION: u32 = 0;
+
+/// Latest version of the blocks built by the node, and the version of its genesis blocks.
+pub const MAX_BLOCK_VERSION: u32 = 1;
+
+// BLOCK VERSION RANGE
+// ================================================================================================
+
+/// Inclusive range of block versions.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockVersionRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl BlockVersionRange {
+    /// Returns whether the range contains `version`.
+    pub fn contains(&self, version: u32) -> bool {
+        (self.min..=self.max).contains(&version)
+    }
+}
+
+impl Default for BlockVersionRange {
+    fn default() -> Self {
+        Self {
+            min: MIN_BLOCK_VERSION,
+            max: MAX_BLOCK_VERSION,
+        }
+    }
+}
+
+impl Display for BlockVersionRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)
+    }
+}
+
+// BLOCK VERSIONS
+// ================================================================================================
+
+/// Versions of the blocks supported by the node, see the [module docs](self).
+#[derive(
+    Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+pub struct BlockVersions {
+    /// Versions supported by the blocks preceding the scheduled upgrade, or by all the blocks if
+    /// no upgrade is scheduled.
+    #[serde(default)]
+    pub supported: BlockVersionRange,
+
+    /// Upgrade of the supported versions scheduled at a given block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade: Option<BlockVersionUpgrade>,
+}
+
+/// Upgrade of the supported block versions, see [BlockVersions].
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockVersionUpgrade {
+    /// Number of the first block supporting the upgraded versions.
+    pub activation_block: u32,
+    /// Versions supported from the activation block onwards.
+    pub supported: BlockVersionRange,
+}
+
+impl BlockVersions {
+    /// Returns the versions supported by the block `block_num`.
+    pub fn supported_at(&self, block_num: u32) -> BlockVersionRange {
+        match self.upgrade {
+            Some(upgrade) if block_num >= upgrade.activation_block => upgrade.supported,
+            _ => self.supported,
+        }
+    }
+
+    /// Checks that the version of the block `block_num` is supported at its height.
+    pub fn check(&self, block_num: u32, version: u32) -> Result<(), UnsupportedBlockVersion> {
+        let supported = self.supported_at(block_num);
+        if !supported.contains(version) {
+            return Err(UnsupportedBlockVersion { block_num, version, supported });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the version of the block `block_num` built on top of a block of version
+    /// `prev_version`: the version of its parent, raised to the minimum version supported at its
+    /// height.
+    ///
+    /// Fails if the version of the parent is newer than the versions supported at the height of
+    /// the block, i.e. if the node doesn't know how to build it.
+    pub fn next_version(
+        &self,
+        block_num: u32,
+        prev_version: u32,
+    ) -> Result<u32, UnsupportedBlockVersion> {
+        let version = prev_version.max(self.supported_at(block_num).min);
+        self.check(block_num, version)?;
+
+        Ok(version)
+    }
+}
+
+impl Display for BlockVersions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.upgrade {
+            Some(upgrade) => write!(
+                f,
+                "{{ supported: {}, upgrade: {{ activation_block: {}, supported: {} }} }}",
+                self.supported, upgrade.activation_block, upgrade.supported
+            ),
+            None => write!(f, "{{ supported: {} }}", self.supported),
+        }
+    }
+}
+
+/// A block version which is not supported at the height of its block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error(
+    "version {version} of block {block_num} is not supported, expected a version in {supported}"
+)]
+pub struct UnsupportedBlockVersion {
+    pub block_num: u32,
+    pub version: u32,
+    pub supported: BlockVersionRange,
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upgrade_at(activation_block: u32) -> BlockVersions {
+        BlockVersions {
+            supported: BlockVersionRange { min: 0, max: 1 },
+            upgrade: Some(BlockVersionUpgrade {
+                activation_block,
+                supported: BlockVersionRange { min: 2, max: 2 },
+            }),
+        }
+    }
+
+    #[test]
+    fn versions_are_checked_against_the_range_of_their_height() {
+        let versions = BlockVersions::default();
+        assert!(versions.check(1, 0).is_ok());
+        assert!(versions.check(1, 1).is_ok());
+        assert_eq!(
+            versions.check(1, 2),
+            Err(UnsupportedBlockVersion {
+                block_num: 1,
+                version: 2,
+                supported: versions.supported
+            })
+        );
+
+        let versions = upgrade_at(100);
+        assert!(versions.check(99, 1).is_ok());
+        assert!(versions.check(99, 2).is_err());
+        assert!(versions.check(100, 1).is_err());
+        assert!(versions.check(100, 2).is_ok());
+    }
+
+    #[test]
+    fn produced_versions_switch_at_the_activation_block() {
+        let versions = upgrade_at(100);
+        assert_eq!(versions.next_version(99, 1), Ok(1));
+        assert_eq!(versions.next_version(100, 1), Ok(2));
+        assert_eq!(versions.next_version(101, 2), Ok(2));
+
+        // Parents newer than the supported versions can't be built upon
+        assert!(versions.next_version(99, 2).is_err());
+        assert!(BlockVersions::default().next_version(1, 3).is_err());
+    }
+}
@@ -1,28 +1,53 @@
 use std::{
     fmt::{Display, Formatter},
     io,
-    net::{SocketAddr, ToSocketAddrs},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
     path::Path,
+    str::FromStr,
     vec,
 };
 
 use figment::{
-    providers::{Format, Toml},
-    Figment,
+    error::Kind,
+    providers::{Format, Serialized, Toml},
+    value::{Dict, Value},
+    Figment, Profile,
 };
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, value::MapAccessDeserializer, DeserializeOwned, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use thiserror::Error;
+use tokio::net::{lookup_host, TcpListener};
+use tracing::warn;
 
 pub const DEFAULT_NODE_RPC_PORT: u16 = 57291;
 pub const DEFAULT_BLOCK_PRODUCER_PORT: u16 = 48046;
 pub const DEFAULT_STORE_PORT: u16 = 28943;
 pub const DEFAULT_FAUCET_SERVER_PORT: u16 = 8080;
 
+/// Version of the layout of the configuration files, increased by every release renaming, moving
+/// or making required any of their keys.
+///
+/// Files without a `config_version` key predate the versioning and are at version 0. Older files
+/// are upgraded by the `migrate-config` command of the node.
+pub const CONFIG_VERSION: u32 = 1;
+
 /// The `(host, port)` pair for the server's listening socket.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+///
+/// In configuration files, an endpoint is either a table of `host` and `port`, or a string in one
+/// of the forms accepted by its [FromStr] implementation, e.g. `"0.0.0.0:57291"` or
+/// `"http://[::1]:57291"`.
+///
+/// The host is either a name, or an IP address. Binding `0.0.0.0` listens on all the IPv4
+/// interfaces, and binding `::` listens on all the IPv6 interfaces, as well as the IPv4 ones on
+/// systems enabling dual-stack sockets by default, e.g. Linux. Binding port 0 listens on a free
+/// port chosen by the system, which the components report once bound.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize)]
 pub struct Endpoint {
-    /// Host used by the store.
+    /// Host name or IP address, without brackets for IPv6 addresses.
     pub host: String,
-    /// Port number used by the store.
+    /// Port number, 0 for a free port chosen by the system.
     pub port: u16,
 }
 
@@ -30,6 +55,71 @@ impl Endpoint {
     pub fn localhost(port: u16) -> Self {
         Endpoint { host: "localhost".to_string(), port }
     }
+
+    /// Returns the endpoint of `host` and `port`, validating the host.
+    pub fn new(host: impl Into<String>, port: u16) -> Result<Self, EndpointParseError> {
+        let host = host.into();
+        if host.is_empty() {
+            return Err(EndpointParseError::MissingHost);
+        }
+        if host.contains(['/', '[', ']', ' ']) {
+            return Err(EndpointParseError::InvalidHost(host));
+        }
+        if host.contains(':') && host.parse::<Ipv6Addr>().is_err() {
+            return Err(EndpointParseError::InvalidHost(host));
+        }
+
+        Ok(Self { host, port })
+    }
+
+    /// Binds a TCP listener to the endpoint.
+    ///
+    /// A host resolving to several addresses is bound on the first one only, and the ignored
+    /// addresses are logged. The address actually bound, with the port chosen by the system for
+    /// port 0, is returned by the listener's `local_addr`.
+    pub async fn bind(&self) -> Result<TcpListener, EndpointBindError> {
+        let mut addrs = lookup_host((self.host.as_str(), self.port))
+            .await
+            .map_err(|source| EndpointBindError::Unresolvable { endpoint: self.clone(), source })?;
+        let addr = addrs.next().ok_or_else(|| EndpointBindError::Unresolvable {
+            endpoint: self.clone(),
+            source: io::Error::new(io::ErrorKind::NotFound, "no address found"),
+        })?;
+
+        let ignored = addrs.map(|addr| addr.ip().to_string()).collect::<Vec<_>>();
+        if !ignored.is_empty() {
+            warn!(
+                endpoint = %self,
+                %addr,
+                ignored = %ignored.join(", "),
+                "Host resolves to several addresses, binding the first one only"
+            );
+        }
+
+        TcpListener::bind(addr).await.map_err(|source| match source.kind() {
+            io::ErrorKind::PermissionDenied if addr.port() < 1024 => {
+                EndpointBindError::PrivilegedPort { addr, source }
+            },
+            io::ErrorKind::AddrInUse => EndpointBindError::AddressInUse { addr, source },
+            io::ErrorKind::AddrNotAvailable => {
+                EndpointBindError::AddressNotAvailable { addr, source }
+            },
+            _ => EndpointBindError::BindFailed { addr, source },
+        })
+    }
+}
+
+/// Returns the url of a server listening on `addr`, for the clients running on the same host.
+///
+/// Unspecified addresses, i.e. `0.0.0.0` and `::`, are replaced by the loopback address.
+pub fn local_url(addr: SocketAddr) -> String {
+    let ip = match addr.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ip => ip,
+    };
+
+    format!("http://{}", SocketAddr::new(ip, addr.port()))
 }
 
 impl ToSocketAddrs for Endpoint {
@@ -41,18 +131,447 @@ impl ToSocketAddrs for Endpoint {
 
 impl Display for Endpoint {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("http://{}:{}", self.host, self.port))
+        if self.host.contains(':') {
+            f.write_fmt(format_args!("http://[{}]:{}", self.host, self.port))
+        } else {
+            f.write_fmt(format_args!("http://{}:{}", self.host, self.port))
+        }
+    }
+}
+
+impl FromStr for Endpoint {
+    type Err = EndpointParseError;
+
+    /// Parses an endpoint given as `host:port`, or as an `http` or `https` url without a path.
+    ///
+    /// IPv6 addresses are enclosed in brackets, e.g. `[::]:57291`. The port is required.
+    fn from_str(endpoint: &str) -> Result<Self, Self::Err> {
+        let endpoint = endpoint.trim();
+        let authority = match endpoint.split_once("://") {
+            Some(("http" | "https", rest)) => rest.strip_suffix('/').unwrap_or(rest),
+            Some((scheme, _)) => return Err(EndpointParseError::UnsupportedScheme(scheme.into())),
+            None if endpoint.starts_with("unix:") => {
+                return Err(EndpointParseError::UnsupportedScheme("unix".into()))
+            },
+            None => endpoint,
+        };
+        if authority.contains('/') {
+            return Err(EndpointParseError::UnexpectedPath(endpoint.into()));
+        }
+
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(rest) => {
+                let (host, port) = rest
+                    .split_once(']')
+                    .ok_or_else(|| EndpointParseError::InvalidHost(authority.into()))?;
+                if host.parse::<Ipv6Addr>().is_err() {
+                    return Err(EndpointParseError::InvalidHost(host.into()));
+                }
+                let port = port.strip_prefix(':').ok_or(EndpointParseError::MissingPort)?;
+                (host, port)
+            },
+            None => {
+                let (host, port) =
+                    authority.rsplit_once(':').ok_or(EndpointParseError::MissingPort)?;
+                if host.contains(':') {
+                    return Err(EndpointParseError::UnbracketedIpv6(authority.into()));
+                }
+                (host, port)
+            },
+        };
+        let port = port.parse().map_err(|_| EndpointParseError::InvalidPort(port.into()))?;
+
+        Self::new(host, port)
+    }
+}
+
+impl<'de> Deserialize<'de> for Endpoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// The table form of an endpoint.
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct EndpointTable {
+            host: String,
+            port: u16,
+        }
+
+        struct EndpointVisitor;
+
+        impl<'de> Visitor<'de> for EndpointVisitor {
+            type Value = Endpoint;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a `host:port` string, an url, or a table of `host` and `port`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Endpoint, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Endpoint, A::Error> {
+                let table = EndpointTable::deserialize(MapAccessDeserializer::new(map))?;
+                Endpoint::new(table.host, table.port).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(EndpointVisitor)
     }
 }
 
-/// Loads the user configuration.
+/// Error raised when an [Endpoint] is invalid.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum EndpointParseError {
+    #[error("the endpoint has no host")]
+    MissingHost,
+    #[error("invalid host `{0}`")]
+    InvalidHost(String),
+    #[error("IPv6 addresses must be enclosed in brackets, e.g. `[::1]:57291`, got `{0}`")]
+    UnbracketedIpv6(String),
+    #[error("the endpoint has no port, e.g. `localhost:57291`")]
+    MissingPort,
+    #[error("invalid port `{0}`")]
+    InvalidPort(String),
+    #[error("unsupported scheme `{0}`, endpoints are `http` or `https` urls, or `host:port`")]
+    UnsupportedScheme(String),
+    #[error("endpoints can't have a path, got `{0}`")]
+    UnexpectedPath(String),
+}
+
+/// Error raised when a TCP listener can't be bound to an [Endpoint].
+#[derive(Debug, Error)]
+pub enum EndpointBindError {
+    #[error("failed to resolve the host of {endpoint}")]
+    Unresolvable {
+        endpoint: Endpoint,
+        #[source]
+        source: io::Error,
+    },
+    #[error("binding privileged port {} of {addr} requires elevated permissions", addr.port())]
+    PrivilegedPort {
+        addr: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+    #[error("{addr} is already in use")]
+    AddressInUse {
+        addr: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+    #[error("{} is not an address of this host", addr.ip())]
+    AddressNotAvailable {
+        addr: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+    #[error("failed to bind {addr}")]
+    BindFailed {
+        addr: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// How keys of a configuration file which are not part of the configuration are handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownKeys {
+    /// Unknown keys are reported as errors.
+    #[default]
+    Deny,
+    /// Unknown keys are logged as warnings and ignored.
+    Warn,
+}
+
+/// Error raised when a configuration file can't be loaded, listing all the problems found in it.
+#[derive(Debug, Error)]
+#[error("Invalid configuration file {file}:{}", format_problems(.problems))]
+pub struct ConfigError {
+    /// Path of the configuration file.
+    pub file: String,
+    pub problems: Vec<ConfigProblem>,
+}
+
+/// A problem of a configuration file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// Dotted path of the offending key, empty if the problem is not about a specific key.
+    pub key: String,
+    pub kind: ConfigProblemKind,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigProblemKind {
+    UnknownKey,
+    MissingKey,
+    InvalidValue,
+    /// The file can't be read or is not valid TOML.
+    InvalidFile,
+}
+
+/// Loads the user configuration, rejecting unknown keys.
 ///
 /// This function will look for the configuration file at the provided path. If the path is
 /// relative, searches in parent directories all the way to the root as well.
 ///
 /// The above configuration options are indented to support easy of packaging and deployment.
-pub fn load_config<T: for<'a> Deserialize<'a>>(
+pub fn load_config<T: DeserializeOwned>(config_file: impl AsRef<Path>) -> Result<T, ConfigError> {
+    load_config_with(config_file, UnknownKeys::Deny)
+}
+
+/// Loads the user configuration like [load_config], handling unknown keys as requested.
+///
+/// All the unknown keys and invalid values are reported at once. The offending keys are removed
+/// from the configuration one at a time until it can be deserialized, which only stops early at a
+/// missing required key.
+pub fn load_config_with<T: DeserializeOwned>(
     config_file: impl AsRef<Path>,
-) -> figment::Result<T> {
-    Figment::from(Toml::file(config_file.as_ref())).extract()
+    unknown_keys: UnknownKeys,
+) -> Result<T, ConfigError> {
+    let figment = Figment::from(Toml::file(config_file.as_ref()));
+    let file = figment
+        .metadata()
+        .find_map(|metadata| metadata.source.as_ref())
+        .map_or_else(|| config_file.as_ref().display().to_string(), ToString::to_string);
+
+    let mut config = match figment.data() {
+        Ok(mut data) => data.remove(&Profile::Default).unwrap_or_default(),
+        Err(err) => {
+            let problem = ConfigProblem {
+                key: String::new(),
+                kind: ConfigProblemKind::InvalidFile,
+                message: err.kind.to_string(),
+            };
+            return Err(ConfigError { file, problems: vec![problem] });
+        },
+    };
+
+    let mut problems = Vec::new();
+    loop {
+        let err = match Figment::from(Serialized::defaults(&config)).extract() {
+            Ok(config) if problems.is_empty() => return Ok(config),
+            Ok(_) => return Err(ConfigError { file, problems }),
+            Err(err) => err,
+        };
+
+        let problem = ConfigProblem::from(err);
+        if problem.kind == ConfigProblemKind::MissingKey {
+            // A key removed because of its invalid value is reported already
+            if !problems.iter().any(|reported| reported.key == problem.key) {
+                problems.push(problem);
+            }
+            return Err(ConfigError { file, problems });
+        }
+
+        if !remove_key(&mut config, &problem.key) {
+            problems.push(problem);
+            return Err(ConfigError { file, problems });
+        }
+
+        if problem.kind == ConfigProblemKind::UnknownKey && unknown_keys == UnknownKeys::Warn {
+            warn!(%file, key = %problem.key, "Ignoring unknown configuration key");
+        } else {
+            problems.push(problem);
+        }
+    }
+}
+
+impl From<figment::Error> for ConfigProblem {
+    fn from(err: figment::Error) -> Self {
+        let mut path = err.path;
+        let (kind, message) = match err.kind {
+            Kind::UnknownField(field, expected) => {
+                path.push(field);
+                let expected = expected.iter().map(|key| format!("`{key}`")).collect::<Vec<_>>();
+                (
+                    ConfigProblemKind::UnknownKey,
+                    format!("unknown key, expected one of {}", expected.join(", ")),
+                )
+            },
+            Kind::MissingField(field) => {
+                if path.last().map(String::as_str) != Some(&*field) {
+                    path.push(field.into_owned());
+                }
+                (ConfigProblemKind::MissingKey, "missing key".to_string())
+            },
+            kind => (ConfigProblemKind::InvalidValue, kind.to_string()),
+        };
+
+        Self { key: path.join("."), kind, message }
+    }
+}
+
+/// Removes the value at the dotted `key` path, returning `false` if there is no such value.
+fn remove_key(config: &mut Dict, key: &str) -> bool {
+    match key.split_once('.') {
+        None => config.remove(key).is_some(),
+        Some((first, rest)) => {
+            config.get_mut(first).is_some_and(|value| remove_nested_key(value, rest))
+        },
+    }
+}
+
+fn remove_nested_key(value: &mut Value, key: &str) -> bool {
+    match value {
+        Value::Dict(_, dict) => remove_key(dict, key),
+        Value::Array(_, array) => key
+            .split_once('.')
+            .and_then(|(index, rest)| Some((index.parse::<usize>().ok()?, rest)))
+            .and_then(|(index, rest)| Some((array.get_mut(index)?, rest)))
+            .is_some_and(|(value, rest)| remove_nested_key(value, rest)),
+        _ => false,
+    }
+}
+
+fn format_problems(problems: &[ConfigProblem]) -> String {
+    problems
+        .iter()
+        .map(|problem| {
+            if problem.key.is_empty() {
+                format!("\n  - {}", problem.message)
+            } else {
+                format!("\n  - `{}`: {}", problem.key, problem.message)
+            }
+        })
+        .collect()
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use tokio::net::TcpStream;
+
+    use super::*;
+
+    fn endpoint(host: &str, port: u16) -> Endpoint {
+        Endpoint { host: host.to_string(), port }
+    }
+
+    #[test]
+    fn endpoints_are_parsed_from_urls_and_host_port_pairs() {
+        let valid = [
+            ("localhost:57291", endpoint("localhost", 57291)),
+            ("http://localhost:57291", endpoint("localhost", 57291)),
+            ("https://node.example.com:443/", endpoint("node.example.com", 443)),
+            (" 0.0.0.0:0 ", endpoint("0.0.0.0", 0)),
+            ("[::]:57291", endpoint("::", 57291)),
+            ("http://[::1]:57291", endpoint("::1", 57291)),
+        ];
+        for (input, expected) in valid {
+            assert_eq!(input.parse::<Endpoint>(), Ok(expected), "{input}");
+        }
+
+        let invalid = [
+            ("localhost", EndpointParseError::MissingPort),
+            ("http://localhost", EndpointParseError::MissingPort),
+            ("[::1]", EndpointParseError::MissingPort),
+            (":57291", EndpointParseError::MissingHost),
+            ("localhost:65536", EndpointParseError::InvalidPort("65536".into())),
+            ("localhost:port", EndpointParseError::InvalidPort("port".into())),
+            ("::1:57291", EndpointParseError::UnbracketedIpv6("::1:57291".into())),
+            ("[localhost]:57291", EndpointParseError::InvalidHost("localhost".into())),
+            ("grpc://localhost:57291", EndpointParseError::UnsupportedScheme("grpc".into())),
+            ("unix:/run/miden/rpc.sock", EndpointParseError::UnsupportedScheme("unix".into())),
+            (
+                "http://localhost:57291/rpc",
+                EndpointParseError::UnexpectedPath("http://localhost:57291/rpc".into()),
+            ),
+        ];
+        for (input, expected) in invalid {
+            assert_eq!(input.parse::<Endpoint>(), Err(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn endpoints_are_displayed_as_urls() {
+        assert_eq!(endpoint("localhost", 57291).to_string(), "http://localhost:57291");
+        assert_eq!(endpoint("::1", 57291).to_string(), "http://[::1]:57291");
+
+        let endpoint = endpoint("::", 0);
+        assert_eq!(endpoint.to_string().parse(), Ok(endpoint));
+    }
+
+    #[test]
+    fn endpoints_are_deserialized_from_strings_and_tables() {
+        #[derive(Debug, Deserialize)]
+        struct Config {
+            string: Endpoint,
+            table: Endpoint,
+        }
+
+        let config: Config = Figment::from(Toml::string(
+            r#"
+            string = "0.0.0.0:57291"
+            table = { host = "::", port = 0 }
+            "#,
+        ))
+        .extract()
+        .unwrap();
+        assert_eq!(config.string, endpoint("0.0.0.0", 57291));
+        assert_eq!(config.table, endpoint("::", 0));
+
+        let toml = r#"endpoint = { host = "localhost", port = 1, tls = true }"#;
+        let err = Figment::from(Toml::string(toml))
+            .extract::<BTreeMap<String, Endpoint>>()
+            .unwrap_err();
+        assert_eq!(ConfigProblem::from(err).key, "endpoint.tls");
+
+        let err = Figment::from(Toml::string(r#"endpoint = "localhost""#))
+            .extract::<BTreeMap<String, Endpoint>>()
+            .unwrap_err();
+        assert_eq!(ConfigProblem::from(err).kind, ConfigProblemKind::InvalidValue);
+    }
+
+    #[tokio::test]
+    async fn port_zero_binds_a_free_port() {
+        let listener = endpoint("127.0.0.1", 0).bind().await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        assert_ne!(addr.port(), 0);
+
+        assert_eq!(local_url(addr), format!("http://127.0.0.1:{}", addr.port()));
+        TcpStream::connect(addr).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn unspecified_addresses_are_reached_through_loopback() {
+        let listener = endpoint("0.0.0.0", 0).bind().await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        assert!(addr.ip().is_unspecified());
+
+        let url = local_url(addr);
+        assert_eq!(url, format!("http://127.0.0.1:{}", addr.port()));
+        TcpStream::connect(url.trim_start_matches("http://")).await.unwrap();
+    }
+
+    /// Linux enables dual-stack sockets by default, so binding `::` accepts IPv4 connections too.
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn binding_ipv6_unspecified_address_accepts_ipv4_connections() {
+        let Ok(listener) = endpoint("::", 0).bind().await else {
+            // IPv6 is disabled on this host
+            return;
+        };
+        let port = listener.local_addr().unwrap().port();
+
+        assert_eq!(local_url(listener.local_addr().unwrap()), format!("http://[::1]:{port}"));
+        TcpStream::connect((Ipv4Addr::LOCALHOST, port)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn bind_failures_are_explained() {
+        let listener = endpoint("127.0.0.1", 0).bind().await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let err = endpoint("127.0.0.1", port).bind().await.unwrap_err();
+        assert!(matches!(err, EndpointBindError::AddressInUse { .. }), "{err:?}");
+
+        // Documentation address, which is not assigned to the host
+        let err = endpoint("192.0.2.1", 0).bind().await.unwrap_err();
+        assert!(matches!(err, EndpointBindError::AddressNotAvailable { .. }), "{err:?}");
+        assert_eq!(err.to_string(), "192.0.2.1 is not an address of this host");
+    }
 }
@@ -1,6 +1,8 @@
 use thiserror::Error;
 use tonic::transport::Error as TransportError;
 
+use crate::{config::EndpointBindError, tasks::TaskPanic};
+
 #[derive(Debug, Error)]
 pub enum ApiError {
     #[error("An I/O error has occurred: {0}")]
@@ -12,13 +14,14 @@ pub enum ApiError {
     #[error("Serving the Api server has failed.")]
     ApiServeFailed(TransportError),
 
-    #[error("Resolution of the server address has failed: {0}")]
-    AddressResolutionFailed(String),
-
-    /// Converting the provided `Endpoint` into a socket address has failed
-    #[error("Converting the `Endpoint` into a socket address failed: {0}")]
-    EndpointToSocketFailed(std::io::Error),
+    /// The server could not listen on its configured `Endpoint`
+    #[error("Binding the server endpoint has failed: {0}")]
+    EndpointBindFailed(#[from] EndpointBindError),
 
     #[error("Connection to the database has failed: {0}")]
     DatabaseConnectionFailed(String),
+
+    /// A long-lived task of the component panicked, shutting the component down
+    #[error("{0}")]
+    TaskPanicked(#[from] TaskPanic),
 }
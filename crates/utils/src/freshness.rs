@@ -0,0 +1,348 @@
+//! Freshness of the chain tip served by a node.
+//!
+//! A node catching up with the chain, e.g. a store restarted after a long downtime or syncing its
+//! blocks from a peer, serves answers which are valid for its chain tip, but stale. Clients can't
+//! tell these answers apart from fresh ones, so the node reports how far behind it may be in the
+//! headers of every response:
+//!
+//! - the store tracks the wall-clock time at which it applied its latest block in a
+//!   [ChainFreshness], and its [FreshnessLayer] reports the seconds elapsed since then in the
+//!   [STALENESS_HEADER]. The header is omitted while the chain only holds its genesis block, as no
+//!   block is expected to have been applied yet.
+//! - the RPC's [SyncingFlagLayer] sets the [SYNCING_HEADER] to `true` if the staleness exceeds the
+//!   configured maximum, or if the store is syncing its blocks. Clients seeing it can retry against
+//!   another node.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tonic::codegen::{
+    http::{self, HeaderValue},
+    BoxFuture, Service,
+};
+use tower::Layer;
+
+/// Header of the responses holding the number of seconds since the latest block was applied.
+pub const STALENESS_HEADER: &str = "x-miden-staleness-seconds";
+
+/// Header of the responses telling whether the node is behind the chain.
+pub const SYNCING_HEADER: &str = "x-miden-syncing";
+
+/// Returns the current time, in seconds since the UNIX epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("today is expected to be after 1970")
+        .as_secs()
+}
+
+// CHAIN FRESHNESS
+// ================================================================================================
+
+/// Time at which the latest block was applied, shared by the state and the [FreshnessLayer].
+#[derive(Debug, Clone, Default)]
+pub struct ChainFreshness {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Seconds since the UNIX epoch at which the latest block was applied, 0 if no block was
+    /// applied after the genesis block.
+    last_block_applied_at: AtomicU64,
+    /// Number of processes syncing blocks, see [ChainFreshness::start_sync].
+    syncs: AtomicU64,
+}
+
+impl ChainFreshness {
+    /// Returns the freshness of a chain whose latest block was applied at the given time, in
+    /// seconds since the UNIX epoch, or of a chain only holding its genesis block if `None`.
+    pub fn new(last_block_applied_at: Option<u64>) -> Self {
+        let freshness = Self::default();
+        freshness.set_last_block_applied_at(last_block_applied_at);
+        freshness
+    }
+
+    /// Records that a block was applied now.
+    pub fn block_applied(&self) {
+        self.set_last_block_applied_at(Some(now()));
+    }
+
+    /// Sets the time at which the latest block was applied, in seconds since the UNIX epoch.
+    pub fn set_last_block_applied_at(&self, last_block_applied_at: Option<u64>) {
+        self.inner
+            .last_block_applied_at
+            .store(last_block_applied_at.unwrap_or_default(), Ordering::Relaxed);
+    }
+
+    /// Returns the number of seconds elapsed since the latest block was applied, `None` if the
+    /// chain only holds its genesis block.
+    pub fn staleness(&self) -> Option<u64> {
+        match self.inner.last_block_applied_at.load(Ordering::Relaxed) {
+            0 => None,
+            applied_at => Some(now().saturating_sub(applied_at)),
+        }
+    }
+
+    /// Flags the node as syncing its blocks until the returned guard is dropped, e.g. while
+    /// importing blocks from a peer or an archive.
+    pub fn start_sync(&self) -> SyncGuard {
+        self.inner.syncs.fetch_add(1, Ordering::Relaxed);
+        SyncGuard { freshness: self.clone() }
+    }
+
+    /// Returns whether a process is syncing blocks.
+    pub fn is_syncing(&self) -> bool {
+        self.inner.syncs.load(Ordering::Relaxed) > 0
+    }
+}
+
+/// Flags the node as syncing its blocks until dropped, see [ChainFreshness::start_sync].
+#[derive(Debug)]
+pub struct SyncGuard {
+    freshness: ChainFreshness,
+}
+
+impl Drop for SyncGuard {
+    fn drop(&mut self) {
+        self.freshness.inner.syncs.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// STORE LAYER
+// ================================================================================================
+
+/// Layer reporting the freshness of the chain in the headers of every response, see the
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct FreshnessLayer {
+    freshness: ChainFreshness,
+}
+
+impl FreshnessLayer {
+    pub fn new(freshness: ChainFreshness) -> Self {
+        Self { freshness }
+    }
+}
+
+impl<S> Layer<S> for FreshnessLayer {
+    type Service = FreshnessService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FreshnessService { inner, freshness: self.freshness.clone() }
+    }
+}
+
+/// Service reporting the freshness of the chain, see [FreshnessLayer].
+#[derive(Debug, Clone)]
+pub struct FreshnessService<S> {
+    inner: S,
+    freshness: ChainFreshness,
+}
+
+impl<S, B, ResBody> Service<http::Request<B>> for FreshnessService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let response = self.inner.call(request);
+        let freshness = self.freshness.clone();
+
+        Box::pin(async move {
+            let mut response = response.await?;
+            let headers = response.headers_mut();
+            if let Some(staleness) = freshness.staleness() {
+                headers.insert(STALENESS_HEADER, HeaderValue::from(staleness));
+            }
+            if freshness.is_syncing() {
+                headers.insert(SYNCING_HEADER, HeaderValue::from_static("true"));
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+// RPC LAYER
+// ================================================================================================
+
+/// Layer setting the [SYNCING_HEADER] of every response, from the staleness reported by the
+/// store, see the [module docs](self).
+#[derive(Debug, Clone, Copy)]
+pub struct SyncingFlagLayer {
+    max_staleness: Duration,
+}
+
+impl SyncingFlagLayer {
+    /// Flags the node as syncing once its latest block was applied more than `max_staleness` ago.
+    pub fn new(max_staleness: Duration) -> Self {
+        Self { max_staleness }
+    }
+
+    /// Returns whether the node is syncing, given the headers of a response of the store.
+    fn is_syncing(&self, headers: &http::HeaderMap) -> bool {
+        let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+        let staleness = header(STALENESS_HEADER).and_then(|staleness| staleness.parse().ok());
+        header(SYNCING_HEADER) == Some("true")
+            || staleness.is_some_and(|staleness| staleness > self.max_staleness.as_secs())
+    }
+}
+
+impl<S> Layer<S> for SyncingFlagLayer {
+    type Service = SyncingFlagService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SyncingFlagService { inner, layer: *self }
+    }
+}
+
+/// Service setting the [SYNCING_HEADER] of every response, see [SyncingFlagLayer].
+#[derive(Debug, Clone)]
+pub struct SyncingFlagService<S> {
+    inner: S,
+    layer: SyncingFlagLayer,
+}
+
+impl<S, B, ResBody> Service<http::Request<B>> for SyncingFlagService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let response = self.inner.call(request);
+        let layer = self.layer;
+
+        Box::pin(async move {
+            let mut response = response.await?;
+            let syncing = layer.is_syncing(response.headers());
+            let value = HeaderValue::from_static(if syncing { "true" } else { "false" });
+            response.headers_mut().insert(SYNCING_HEADER, value);
+
+            Ok(response)
+        })
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    /// Serves a request through the RPC layer, in front of a store service, and returns the
+    /// freshness headers of the response.
+    async fn serve<S>(store: S, max_staleness: Duration) -> (Option<u64>, bool)
+    where
+        S: Service<http::Request<()>, Response = http::Response<()>, Error = Infallible>,
+        S::Future: Send + 'static,
+    {
+        let rpc = SyncingFlagLayer::new(max_staleness).layer(store);
+
+        let response = rpc.oneshot(http::Request::new(())).await.unwrap();
+        let header = |name| response.headers().get(name).map(|value| value.to_str().unwrap());
+        let staleness = header(STALENESS_HEADER).map(|staleness| staleness.parse().unwrap());
+
+        (staleness, header(SYNCING_HEADER) == Some("true"))
+    }
+
+    /// Returns a store service reporting the given freshness.
+    fn store(
+        freshness: &ChainFreshness,
+    ) -> impl Service<
+        http::Request<()>,
+        Response = http::Response<()>,
+        Error = Infallible,
+        Future = impl Send + 'static,
+    > {
+        FreshnessLayer::new(freshness.clone())
+            .layer(service_fn(|_| async { Ok::<_, Infallible>(http::Response::new(())) }))
+    }
+
+    /// Returns a store service reporting the given staleness header.
+    fn store_with_staleness(
+        staleness: u64,
+    ) -> impl Service<
+        http::Request<()>,
+        Response = http::Response<()>,
+        Error = Infallible,
+        Future = impl Send + 'static,
+    > {
+        service_fn(move |_| async move {
+            let mut response = http::Response::new(());
+            response.headers_mut().insert(STALENESS_HEADER, HeaderValue::from(staleness));
+            Ok::<_, Infallible>(response)
+        })
+    }
+
+    #[tokio::test]
+    async fn nodes_are_flagged_as_syncing_past_the_maximum_staleness() {
+        let max_staleness = Duration::from_secs(60);
+        assert_eq!(serve(store_with_staleness(60), max_staleness).await, (Some(60), false));
+        assert_eq!(serve(store_with_staleness(61), max_staleness).await, (Some(61), true));
+
+        let freshness = ChainFreshness::default();
+        freshness.set_last_block_applied_at(Some(now() - 30));
+        assert!(!serve(store(&freshness), max_staleness).await.1);
+
+        freshness.set_last_block_applied_at(Some(now() - 90));
+        let (staleness, syncing) = serve(store(&freshness), max_staleness).await;
+        assert!(staleness.unwrap() >= 90);
+        assert!(syncing);
+
+        // The flag is cleared once a new block is applied
+        freshness.block_applied();
+        let (staleness, syncing) = serve(store(&freshness), max_staleness).await;
+        assert!(staleness.unwrap() <= 1);
+        assert!(!syncing);
+    }
+
+    #[tokio::test]
+    async fn genesis_only_chains_are_never_stale() {
+        let freshness = ChainFreshness::new(None);
+        assert_eq!(serve(store(&freshness), Duration::ZERO).await, (None, false));
+    }
+
+    #[tokio::test]
+    async fn nodes_syncing_their_blocks_are_flagged() {
+        let max_staleness = Duration::from_secs(60);
+        let freshness = ChainFreshness::new(None);
+
+        let sync = freshness.start_sync();
+        let second_sync = freshness.start_sync();
+        assert_eq!(serve(store(&freshness), max_staleness).await, (None, true));
+
+        drop(sync);
+        assert!(serve(store(&freshness), max_staleness).await.1);
+        drop(second_sync);
+        assert!(!serve(store(&freshness), max_staleness).await.1);
+    }
+}
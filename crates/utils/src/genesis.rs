@@ -0,0 +1,76 @@
+//! Check of the network of the store the components connect to.
+//!
+//! The RPC and the block producer are configured with the URL of their store only, so nothing
+//! prevents pointing, e.g., a testnet RPC at a devnet store. Since the genesis block commitment
+//! identifies the network, both components can be configured with the commitment they expect, and
+//! refuse to start if the genesis block of their store differs.
+
+use miden_objects::Digest;
+use thiserror::Error;
+
+// GENESIS COMMITMENT
+// ================================================================================================
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GenesisCommitmentError {
+    #[error("expected genesis commitment {0:?} is not a hex encoded digest: {1}")]
+    InvalidExpectedCommitment(String, String),
+    #[error(
+        "the genesis block commitment of the store is {actual}, expected {expected}: the store \
+         belongs to another network"
+    )]
+    Mismatch { expected: String, actual: String },
+}
+
+/// Checks that the genesis block commitment of the store is the `expected` one, as configured in
+/// hex, if any.
+pub fn check_genesis_commitment(
+    expected: Option<&str>,
+    actual: Digest,
+) -> Result<(), GenesisCommitmentError> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+
+    let expected_digest = Digest::try_from(expected).map_err(|err| {
+        GenesisCommitmentError::InvalidExpectedCommitment(expected.to_string(), err.to_string())
+    })?;
+    if expected_digest != actual {
+        return Err(GenesisCommitmentError::Mismatch {
+            expected: expected_digest.to_hex(),
+            actual: actual.to_hex(),
+        });
+    }
+
+    Ok(())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::Felt;
+
+    use super::*;
+
+    #[test]
+    fn genesis_commitment_must_match_the_expected_one() {
+        let genesis = Digest::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+        let other = Digest::new([Felt::new(5), Felt::new(6), Felt::new(7), Felt::new(8)]);
+
+        assert_eq!(check_genesis_commitment(None, genesis), Ok(()));
+        assert_eq!(check_genesis_commitment(Some(&genesis.to_hex()), genesis), Ok(()));
+        assert_eq!(
+            check_genesis_commitment(Some(&genesis.to_hex()), other),
+            Err(GenesisCommitmentError::Mismatch {
+                expected: genesis.to_hex(),
+                actual: other.to_hex(),
+            })
+        );
+        assert!(matches!(
+            check_genesis_commitment(Some("0x1234"), genesis),
+            Err(GenesisCommitmentError::InvalidExpectedCommitment(..))
+        ));
+    }
+}
@@ -1,6 +1,17 @@
+pub mod block_num;
+pub mod block_versions;
 pub mod config;
 pub mod crypto;
 pub mod errors;
 pub mod formatting;
+pub mod freshness;
+pub mod genesis;
 pub mod logging;
+pub mod message_limits;
+pub mod notes;
+pub mod request_id;
+pub mod runtime;
+pub mod runtime_info;
+pub mod tasks;
+pub mod trace_context;
 pub mod version;
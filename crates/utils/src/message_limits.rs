@@ -0,0 +1,80 @@
+//! Limits on the size of the gRPC messages exchanged by the components, and of the notes they
+//! accept.
+//!
+//! Tonic limits the size of the decoded messages to 4 MiB by default, but doesn't limit the size of
+//! the encoded ones, so a component can send messages its peer refuses to decode. The RPC and the
+//! block producer apply the same [MessageLimits] to their servers and to their clients, in both
+//! directions, so that oversized messages are rejected by the sender with a clear error.
+//!
+//! The submitted transactions are also rejected if the details of any of their public notes exceed
+//! the maximum note details size, which defaults to the limit enforced by the store when applying
+//! blocks. Otherwise, such transactions would be accepted, but the blocks including them rejected.
+
+use miden_objects::{
+    notes::NoteId,
+    transaction::{OutputNote, OutputNotes},
+    utils::Serializable,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Default maximum size in bytes of the gRPC messages, matching the decoding limit of tonic.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Default maximum size in bytes of the serialized details of a public note.
+pub const DEFAULT_MAX_NOTE_DETAILS_SIZE: usize = 256 * 1024;
+
+// MESSAGE LIMITS
+// ================================================================================================
+
+/// Limits on the size of the messages of a component, see the [module docs](self).
+#[derive(
+    Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+pub struct MessageLimits {
+    /// Maximum size in bytes of the gRPC messages sent and received, 4 MiB by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_message_size: Option<usize>,
+    /// Maximum size in bytes of the serialized details of the public notes of the submitted
+    /// transactions, 256 KiB by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_note_details_size: Option<usize>,
+}
+
+impl MessageLimits {
+    /// Returns the maximum size in bytes of the gRPC messages.
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE)
+    }
+
+    /// Returns the maximum size in bytes of the serialized details of a public note.
+    pub fn max_note_details_size(&self) -> usize {
+        self.max_note_details_size.unwrap_or(DEFAULT_MAX_NOTE_DETAILS_SIZE)
+    }
+
+    /// Checks the size of the details of the public notes created by a transaction.
+    pub fn check_output_notes(&self, notes: &OutputNotes) -> Result<(), NoteDetailsTooLarge> {
+        let limit = self.max_note_details_size();
+        for note in notes.iter() {
+            let OutputNote::Full(details) = note else {
+                continue;
+            };
+            let size = details.to_bytes().len();
+            if size > limit {
+                return Err(NoteDetailsTooLarge { note_id: note.id(), size, limit });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The details of a public note exceed the configured maximum size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Details of note {note_id} are {size} bytes, exceeding the maximum of {limit} bytes")]
+pub struct NoteDetailsTooLarge {
+    pub note_id: NoteId,
+    pub size: usize,
+    pub limit: usize,
+}
@@ -0,0 +1,63 @@
+//! Validation of the notes created by transactions.
+//!
+//! The block producer validates the notes when proposing batches, and the store when applying
+//! blocks, so that notes whose tag and metadata disagree are never persisted.
+
+use miden_objects::{
+    notes::{NoteTag, NoteType},
+    transaction::OutputNote,
+};
+use thiserror::Error;
+
+/// Mask of the two most significant bits of a tag, which encode its execution mode and target.
+const TAG_KIND_MASK: u32 = 0xc000_0000;
+
+/// Tags with the most significant bit cleared request network execution.
+const NETWORK_EXECUTION_BIT: u32 = 0x8000_0000;
+
+/// Network execution tags with the second most significant bit cleared hold the prefix of the
+/// account executing the note.
+const NETWORK_ACCOUNT: u32 = 0x0000_0000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NoteValidationError {
+    #[error("Tag {tag} requests network execution, which requires a public note, got a {note_type:?} note")]
+    ExecutionModeMismatch { tag: NoteTag, note_type: NoteType },
+    #[error("Tag {0} requests network execution, but doesn't hold the prefix of an account")]
+    InvalidNetworkTag(NoteTag),
+    #[error("Public note is missing its details")]
+    MissingDetails,
+    #[error("{0:?} note must not include its details")]
+    UnexpectedDetails(NoteType),
+}
+
+/// Returns whether the `tag` requests the note to be executed by the network.
+pub fn is_network_tag(tag: NoteTag) -> bool {
+    tag.inner() & NETWORK_EXECUTION_BIT == 0
+}
+
+/// Checks that the note's tag is consistent with its metadata, and that the note includes its
+/// details if and only if it is public.
+///
+/// Notes executed by the network must be public, and target an account.
+pub fn validate_output_note(note: &OutputNote) -> Result<(), NoteValidationError> {
+    let metadata = note.metadata();
+    let tag = metadata.tag();
+    let note_type = metadata.note_type();
+
+    if is_network_tag(tag) {
+        if note_type != NoteType::Public {
+            return Err(NoteValidationError::ExecutionModeMismatch { tag, note_type });
+        }
+        if tag.inner() & TAG_KIND_MASK != NETWORK_ACCOUNT {
+            return Err(NoteValidationError::InvalidNetworkTag(tag));
+        }
+    }
+
+    match (note, note_type) {
+        (OutputNote::Full(_), NoteType::Public) => Ok(()),
+        (_, NoteType::Public) => Err(NoteValidationError::MissingDetails),
+        (OutputNote::Header(_), _) => Ok(()),
+        (_, note_type) => Err(NoteValidationError::UnexpectedDetails(note_type)),
+    }
+}
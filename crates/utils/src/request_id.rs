@@ -0,0 +1,304 @@
+//! Correlation IDs following a request across the node's components.
+//!
+//! Every gRPC server of the node is wrapped in a [RequestIdLayer], which takes the ID of the
+//! request from its `x-request-id` header, or generates one, and echoes it in the response. The
+//! request is served within a span recording the ID, so that all the logs of the request carry it,
+//! and the clients of the internal components attach it to the requests made on its behalf (see
+//! [RequestIdPropagator]).
+//!
+//! The W3C trace context of the requests is propagated along with their IDs, see
+//! [trace_context](crate::trace_context).
+
+use std::{
+    future::Future,
+    task::{Context, Poll},
+};
+
+use tonic::{
+    codegen::{
+        http::{self, HeaderValue},
+        BoxFuture, Service,
+    },
+    metadata::AsciiMetadataValue,
+    service::{interceptor::InterceptedService, Interceptor},
+    transport::{Channel, Endpoint},
+    Request, Status,
+};
+use tower::Layer;
+use tracing::{info_span, Instrument};
+
+use crate::trace_context::{
+    current_trace_context, with_trace_context, TraceContext, TRACEPARENT_HEADER, TRACESTATE_HEADER,
+};
+
+/// Header, and gRPC metadata key, of the request IDs.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Maximum length of the request IDs accepted from clients.
+pub const MAX_REQUEST_ID_LEN: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Returns the ID of the request being served by the current task, if any.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Runs `future` on behalf of the request with the given ID.
+pub fn with_request_id<F: Future>(
+    request_id: String,
+    future: F,
+) -> impl Future<Output = F::Output> {
+    REQUEST_ID.scope(request_id, future)
+}
+
+/// Returns `true` if the ID received from a client can be used to correlate its request.
+///
+/// Only short IDs of alphanumeric characters, `-`, `_` and `.` are accepted, so that they can be
+/// logged and forwarded as is.
+fn is_valid_request_id(request_id: &str) -> bool {
+    !request_id.is_empty()
+        && request_id.len() <= MAX_REQUEST_ID_LEN
+        && request_id
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_' | b'.'))
+}
+
+/// Generates a random (version 4) UUID.
+fn generate_request_id() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+// SERVER LAYER
+// ================================================================================================
+
+/// Layer assigning an ID and a span of a trace to every request served, see the
+/// [module docs](self).
+///
+/// IDs received from clients are kept if they are valid, and replaced otherwise. Likewise, the
+/// trace of the `traceparent` header is continued if it is valid, and a new trace is started
+/// otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestIdService { inner }
+    }
+}
+
+/// Service assigning an ID to every request served, see [RequestIdLayer].
+#[derive(Debug, Clone)]
+pub struct RequestIdService<S> {
+    inner: S,
+}
+
+impl<S, B, ResBody> Service<http::Request<B>> for RequestIdService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        let request_id = request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|request_id| is_valid_request_id(request_id))
+            .map(ToString::to_string)
+            .unwrap_or_else(generate_request_id);
+        let header = HeaderValue::from_str(&request_id).expect("request IDs are valid headers");
+        request.headers_mut().insert(REQUEST_ID_HEADER, header.clone());
+
+        let header_str = |name| request.headers().get(name).and_then(|value| value.to_str().ok());
+        let trace_context = TraceContext::continue_from(
+            header_str(TRACEPARENT_HEADER),
+            header_str(TRACESTATE_HEADER),
+        );
+
+        let span = info_span!(
+            "request",
+            %request_id,
+            trace_id = %trace_context.trace_id_hex(),
+            span_id = %trace_context.span_id_hex(),
+            parent_span_id = trace_context.parent_span_id_hex().as_deref(),
+        );
+        let response = span.in_scope(|| self.inner.call(request));
+
+        Box::pin(
+            with_request_id(
+                request_id,
+                with_trace_context(trace_context, async move {
+                    let mut response = response.await?;
+                    response.headers_mut().insert(REQUEST_ID_HEADER, header);
+
+                    Ok(response)
+                }),
+            )
+            .instrument(span),
+        )
+    }
+}
+
+// CLIENT INTERCEPTOR
+// ================================================================================================
+
+/// Interceptor attaching the ID and the trace context of the request being served to the outgoing
+/// requests.
+///
+/// Requests made outside of a served request, e.g. by background tasks, are left without an ID and
+/// start their own trace.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestIdPropagator;
+
+impl Interceptor for RequestIdPropagator {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let request_id = current_request_id()
+            .and_then(|request_id| AsciiMetadataValue::try_from(request_id).ok());
+        if let Some(request_id) = request_id {
+            request.metadata_mut().insert(REQUEST_ID_HEADER, request_id);
+        }
+
+        if let Some(trace_context) = current_trace_context() {
+            let traceparent = AsciiMetadataValue::try_from(trace_context.traceparent())
+                .expect("traceparent headers are ASCII");
+            request.metadata_mut().insert(TRACEPARENT_HEADER, traceparent);
+
+            let trace_state = trace_context
+                .trace_state
+                .and_then(|trace_state| AsciiMetadataValue::try_from(trace_state).ok());
+            if let Some(trace_state) = trace_state {
+                request.metadata_mut().insert(TRACESTATE_HEADER, trace_state);
+            }
+        }
+
+        Ok(request)
+    }
+}
+
+/// Channel to an internal component, propagating the IDs and trace contexts of the requests being
+/// served.
+pub type RequestIdChannel = InterceptedService<Channel, RequestIdPropagator>;
+
+/// Connects to the gRPC server at `url`, propagating the IDs and trace contexts of the requests
+/// being served.
+pub async fn connect_with_request_ids(
+    url: impl Into<String>,
+) -> Result<RequestIdChannel, tonic::transport::Error> {
+    let channel = Endpoint::new(url.into())?.connect().await?;
+
+    Ok(InterceptedService::new(channel, RequestIdPropagator))
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    /// Serves a request with the given ID header, returning the ID seen by the handler and the ID
+    /// echoed in the response.
+    async fn serve(request_id: Option<&str>) -> (String, String) {
+        let service = RequestIdLayer.layer(service_fn(|request: http::Request<()>| async move {
+            let header = request.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+            assert_eq!(current_request_id(), Some(header.clone()));
+            Ok::<_, Infallible>(http::Response::new(header))
+        }));
+
+        let mut request = http::Request::new(());
+        if let Some(request_id) = request_id {
+            request.headers_mut().insert(REQUEST_ID_HEADER, request_id.parse().unwrap());
+        }
+        let response = service.oneshot(request).await.unwrap();
+        let echoed = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+
+        (response.into_body(), echoed)
+    }
+
+    #[tokio::test]
+    async fn request_ids_are_kept_or_generated() {
+        assert_eq!(
+            serve(Some("bug-report.42")).await,
+            ("bug-report.42".into(), "bug-report.42".into())
+        );
+
+        let too_long = "a".repeat(MAX_REQUEST_ID_LEN + 1);
+        for request_id in [None, Some(""), Some("with spaces"), Some(too_long.as_str())] {
+            let (seen, echoed) = serve(request_id).await;
+            assert_eq!(seen, echoed);
+            assert_eq!(seen.len(), 36, "A UUID is generated instead of {request_id:?}");
+            assert_eq!(&seen[14..15], "4");
+        }
+
+        assert_ne!(serve(None).await, serve(None).await);
+    }
+
+    #[tokio::test]
+    async fn request_ids_are_propagated_while_serving_a_request() {
+        let propagate = || RequestIdPropagator.call(Request::new(())).unwrap();
+
+        assert!(propagate().metadata().get(REQUEST_ID_HEADER).is_none());
+
+        let request = with_request_id("fixed-id".to_string(), async { propagate() }).await;
+        let request_id = request.metadata().get(REQUEST_ID_HEADER).unwrap();
+        assert_eq!(request_id.to_str().unwrap(), "fixed-id");
+    }
+
+    #[tokio::test]
+    async fn trace_contexts_are_continued_by_the_requests_made_on_their_behalf() {
+        const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+        // Serves a request, and returns the `traceparent` of a request made on its behalf
+        let service = RequestIdLayer.layer(service_fn(|_: http::Request<()>| async {
+            let request = RequestIdPropagator.call(Request::new(())).unwrap();
+            let traceparent = request.metadata().get(TRACEPARENT_HEADER).unwrap();
+            Ok::<_, Infallible>(http::Response::new(traceparent.to_str().unwrap().to_string()))
+        }));
+        let serve = |traceparent: Option<&str>| {
+            let mut request = http::Request::new(());
+            if let Some(traceparent) = traceparent {
+                request.headers_mut().insert(TRACEPARENT_HEADER, traceparent.parse().unwrap());
+            }
+            service.clone().oneshot(request)
+        };
+
+        let forwarded = serve(Some(TRACEPARENT)).await.unwrap().into_body();
+        let forwarded = TraceContext::continue_from(Some(&forwarded), None);
+        assert_eq!(forwarded.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_ne!(forwarded.parent_span_id_hex().unwrap(), "00f067aa0ba902b7");
+
+        // Requests without a trace context start a trace
+        let forwarded = serve(None).await.unwrap().into_body();
+        let forwarded = TraceContext::continue_from(Some(&forwarded), None);
+        assert_ne!(forwarded.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert!(forwarded.parent_span_id.is_some());
+
+        assert!(RequestIdPropagator
+            .call(Request::new(()))
+            .unwrap()
+            .metadata()
+            .get(TRACEPARENT_HEADER)
+            .is_none());
+    }
+}
@@ -0,0 +1,334 @@
+//! Runtime the node's components run on, and the pool running their blocking work.
+//!
+//! In bundled mode, the store, block producer and RPC share the runtime of the process, so the
+//! runtime is configured explicitly from the `[runtime]` section of the node configuration:
+//!
+//! - the threads of the runtime are named `miden-rt-<n>`, so that CPU profiles tell them apart from
+//!   the other threads of the process. The blocking pool of the runtime, which runs the database
+//!   queries of the store, is sized separately from its worker threads.
+//! - proving batches and blocks is CPU-bound work which would otherwise hold up the worker threads
+//!   serving requests. Once `proving_threads` is configured, the block producer proves on a
+//!   dedicated [BlockingPool] whose threads are named `miden-prove-<n>`.
+//! - the long-lived tasks of the components are spawned with [spawn_named], as
+//!   `<component>:<task>`. When the node is built with the `tokio-console` feature and
+//!   `RUSTFLAGS="--cfg tokio_unstable"`, the names are recorded in the task metadata reported to
+//!   `tokio-console`.
+//!
+//! The defaults match the runtime built by `#[tokio::main]`, with proofs computed on the worker
+//! threads.
+
+use std::{
+    future::Future,
+    io,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::{
+    runtime::Runtime,
+    sync::oneshot,
+    task::{AbortHandle, JoinHandle, JoinSet},
+};
+use tracing::Span;
+
+// RUNTIME CONFIG
+// ================================================================================================
+
+/// Configuration of the runtime of the node, see the [module docs](self).
+#[derive(
+    Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+pub struct RuntimeConfig {
+    /// Number of worker threads of the runtime, the number of CPU cores by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker_threads: Option<usize>,
+    /// Maximum number of threads of the blocking pool of the runtime, 512 by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_blocking_threads: Option<usize>,
+    /// Number of threads proving batches and blocks. Proofs are computed on the worker threads by
+    /// default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proving_threads: Option<usize>,
+}
+
+impl RuntimeConfig {
+    /// Checks that none of the configured numbers of threads is zero.
+    pub fn validate(&self) -> Result<(), RuntimeConfigError> {
+        let threads = [
+            ("worker_threads", self.worker_threads),
+            ("max_blocking_threads", self.max_blocking_threads),
+            ("proving_threads", self.proving_threads),
+        ];
+        match threads.into_iter().find(|(_, threads)| *threads == Some(0)) {
+            Some((name, _)) => Err(RuntimeConfigError::NoThreads(name)),
+            None => Ok(()),
+        }
+    }
+
+    /// Builds the multi-threaded runtime of the node.
+    pub fn build_runtime(&self) -> Result<Runtime, RuntimeConfigError> {
+        self.validate()?;
+
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder.enable_all().thread_name_fn(|| {
+            static THREAD_INDEX: AtomicUsize = AtomicUsize::new(0);
+            format!("miden-rt-{}", THREAD_INDEX.fetch_add(1, Ordering::Relaxed))
+        });
+        if let Some(worker_threads) = self.worker_threads {
+            builder.worker_threads(worker_threads);
+        }
+        if let Some(max_blocking_threads) = self.max_blocking_threads {
+            builder.max_blocking_threads(max_blocking_threads);
+        }
+
+        builder.build().map_err(RuntimeConfigError::BuildFailed)
+    }
+
+    /// Returns the pool proving batches and blocks.
+    pub fn proving_pool(&self) -> Result<BlockingPool, RuntimeConfigError> {
+        self.validate()?;
+
+        match self.proving_threads {
+            Some(threads) => BlockingPool::new("miden-prove", threads),
+            None => Ok(BlockingPool::inline()),
+        }
+    }
+}
+
+/// An invalid [RuntimeConfig].
+#[derive(Debug, Error)]
+pub enum RuntimeConfigError {
+    #[error("`runtime.{0}` must be at least 1")]
+    NoThreads(&'static str),
+    #[error("Failed to build the runtime")]
+    BuildFailed(#[source] io::Error),
+    #[error("Failed to spawn the threads of the `{0}` pool")]
+    SpawnFailed(&'static str, #[source] io::Error),
+}
+
+// BLOCKING POOL
+// ================================================================================================
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Runs blocking jobs on a dedicated set of threads, or inline on the calling task.
+///
+/// Clones share the same threads, which stop once the last clone is dropped.
+#[derive(Debug, Clone, Default)]
+pub struct BlockingPool {
+    jobs: Option<mpsc::Sender<Job>>,
+}
+
+impl BlockingPool {
+    /// Returns a pool running its jobs inline, on the task awaiting them.
+    pub fn inline() -> Self {
+        Self::default()
+    }
+
+    /// Returns a pool running its jobs on `threads` threads named `<name>-<n>`.
+    pub fn new(name: &'static str, threads: usize) -> Result<Self, RuntimeConfigError> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..threads {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("{name}-{index}"))
+                .spawn(move || loop {
+                    // The lock is released before the job runs, so that the other threads can
+                    // pick the next jobs
+                    let job =
+                        receiver.lock().expect("No thread panics while waiting for a job").recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(mpsc::RecvError) => return,
+                    }
+                })
+                .map_err(|err| RuntimeConfigError::SpawnFailed(name, err))?;
+        }
+
+        Ok(Self { jobs: Some(sender) })
+    }
+
+    /// Runs `job` on the pool, within the current span, and returns its output.
+    ///
+    /// Panics of the job are resumed on the awaiting task.
+    pub async fn run<F, R>(&self, job: F) -> R
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let Some(jobs) = &self.jobs else {
+            return job();
+        };
+
+        let span = Span::current();
+        let (sender, receiver) = oneshot::channel();
+        jobs.send(Box::new(move || {
+            let output = panic::catch_unwind(AssertUnwindSafe(|| span.in_scope(job)));
+            let _ = sender.send(output);
+        }))
+        .expect("The threads of the pool run as long as the pool");
+
+        match receiver.await.expect("The threads of the pool complete every job") {
+            Ok(output) => output,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+// NAMED TASKS
+// ================================================================================================
+
+/// Spawns `future` as a task named `name`, see the [module docs](self).
+pub fn spawn_named<F>(name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(all(tokio_unstable, feature = "tokio-console"))]
+    {
+        tokio::task::Builder::new()
+            .name(name)
+            .spawn(future)
+            .expect("Tasks are spawned within the runtime")
+    }
+
+    #[cfg(not(all(tokio_unstable, feature = "tokio-console")))]
+    {
+        let _ = name;
+        tokio::spawn(future)
+    }
+}
+
+/// Spawns `future` as a task of the `join_set` named `name`, see the [module docs](self).
+pub fn spawn_named_in<F>(join_set: &mut JoinSet<F::Output>, name: &str, future: F) -> AbortHandle
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(all(tokio_unstable, feature = "tokio-console"))]
+    {
+        join_set
+            .build_task()
+            .name(name)
+            .spawn(future)
+            .expect("Tasks are spawned within the runtime")
+    }
+
+    #[cfg(not(all(tokio_unstable, feature = "tokio-console")))]
+    {
+        let _ = name;
+        join_set.spawn(future)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threads_are_validated() {
+        assert!(RuntimeConfig::default().validate().is_ok());
+
+        let config = RuntimeConfig {
+            proving_threads: Some(0),
+            ..Default::default()
+        };
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, RuntimeConfigError::NoThreads("proving_threads")), "{err}");
+        assert!(config.build_runtime().is_err());
+
+        let config = RuntimeConfig {
+            worker_threads: Some(2),
+            ..Default::default()
+        };
+        let runtime = config.build_runtime().unwrap();
+        assert_eq!(runtime.metrics().num_workers(), 2);
+        let thread_name = runtime.block_on(async {
+            spawn_named("test:thread_name", async {
+                thread::current().name().map(ToString::to_string)
+            })
+            .await
+            .unwrap()
+        });
+        assert!(thread_name.unwrap().starts_with("miden-rt-"));
+    }
+
+    #[tokio::test]
+    async fn jobs_run_on_the_threads_of_the_pool() {
+        let thread_name = || thread::current().name().map(ToString::to_string);
+
+        let pool = BlockingPool::new("test-pool", 2).unwrap();
+        assert!(pool.run(thread_name).await.unwrap().starts_with("test-pool-"));
+        assert_eq!(BlockingPool::inline().run(thread_name).await, thread_name());
+
+        // Panics are resumed by the caller, and the pool keeps running
+        let panic = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.run(|| panic!("deliberate failure")).await }
+        })
+        .await
+        .unwrap_err();
+        assert!(panic.is_panic());
+        assert_eq!(pool.run(|| 1 + 1).await, 2);
+    }
+
+    #[cfg(all(tokio_unstable, feature = "tokio-console"))]
+    #[tokio::test]
+    async fn task_names_are_recorded_in_the_task_metadata() {
+        use std::fmt::Debug;
+
+        use tracing::{
+            field::{Field, Visit},
+            span::{Attributes, Id},
+            Subscriber,
+        };
+        use tracing_subscriber::{
+            layer::{Context, SubscriberExt},
+            registry, Layer,
+        };
+
+        /// Records the names of the spawned tasks, from the spans read by `tokio-console`.
+        #[derive(Clone, Default)]
+        struct TaskNames(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for TaskNames {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                if field.name() == "task.name" {
+                    self.0.lock().unwrap().push(format!("{value:?}"));
+                }
+            }
+        }
+
+        impl<S: Subscriber> Layer<S> for TaskNames {
+            fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+                if attrs.metadata().target() == "tokio::task" {
+                    attrs.record(&mut self.clone());
+                }
+            }
+        }
+
+        let names = TaskNames::default();
+        let _guard = tracing::subscriber::set_default(registry().with(names.clone()));
+
+        spawn_named("miden-test:named", async {}).await.unwrap();
+        let mut join_set = JoinSet::new();
+        spawn_named_in(&mut join_set, "miden-test:joined", async {});
+        join_set.join_next().await.unwrap().unwrap();
+
+        let names = names.0.lock().unwrap();
+        assert!(names.contains(&"miden-test:named".to_string()), "{names:?}");
+        assert!(names.contains(&"miden-test:joined".to_string()), "{names:?}");
+    }
+}
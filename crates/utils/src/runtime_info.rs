@@ -0,0 +1,302 @@
+//! Runtime information of the started components.
+//!
+//! Once started, a component writes a small JSON file describing the running process: its version,
+//! the addresses its listeners are bound to, its data directory, its genesis block and the
+//! configuration file it was started with. Orchestration scripts and the `doctor` command read
+//! this file instead of parsing the logs, e.g. to find the port chosen by the system for an
+//! endpoint configured with port 0. The file is removed when the component shuts down.
+//!
+//! The layout of the file is versioned by [RUNTIME_INFO_SCHEMA_VERSION], which is only bumped by
+//! breaking changes; new optional fields can be added without bumping it.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    fs, io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use miden_objects::{crypto::hash::blake::Blake3_256, Digest};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::{formatting::format_blake3_digest, version::LongVersion};
+
+/// Version of the layout of the runtime info file.
+pub const RUNTIME_INFO_SCHEMA_VERSION: u32 = 1;
+
+/// Extension of the runtime info file written next to the configuration file by default.
+const RUNTIME_INFO_EXTENSION: &str = "runtime.json";
+
+// RUNTIME INFO
+// ================================================================================================
+
+/// Information about a running component, written to its runtime info file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeInfo {
+    /// Version of the layout of the file, see [RUNTIME_INFO_SCHEMA_VERSION].
+    pub schema_version: u32,
+    /// Name of the started component, e.g. `store`, or `node` for all the components.
+    pub component: String,
+    /// Build metadata of the binary.
+    pub version: BuildInfo,
+    /// Addresses the listeners are bound to, by name of the component serving them.
+    pub listeners: BTreeMap<String, SocketAddr>,
+    /// Directory holding the data of the component, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_directory: Option<PathBuf>,
+    /// Commitment of the genesis block loaded by the component, as a hex string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis_commitment: Option<String>,
+    /// Configuration file the component was started with, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_path: Option<PathBuf>,
+    /// BLAKE3 hash of the contents of the configuration file, as a hex string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_hash: Option<String>,
+    /// ID of the process running the component.
+    pub pid: u32,
+    /// Time at which the component started, in seconds since the UNIX epoch.
+    pub started_at: u64,
+}
+
+/// Build metadata of the binary running a component, see [LongVersion].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    pub version: String,
+    pub sha: String,
+    pub branch: String,
+    pub dirty: String,
+    pub features: String,
+    pub rust_version: String,
+    pub host: String,
+    pub target: String,
+    pub opt_level: String,
+    pub debug: String,
+}
+
+impl From<&LongVersion> for BuildInfo {
+    fn from(version: &LongVersion) -> Self {
+        Self {
+            version: version.version.to_string(),
+            sha: version.sha.to_string(),
+            branch: version.branch.to_string(),
+            dirty: version.dirty.to_string(),
+            features: version.features.to_string(),
+            rust_version: version.rust_version.to_string(),
+            host: version.host.to_string(),
+            target: version.target.to_string(),
+            opt_level: version.opt_level.to_string(),
+            debug: version.debug.to_string(),
+        }
+    }
+}
+
+impl RuntimeInfo {
+    /// Returns the runtime info of the given component started by the current process now, with
+    /// no listener.
+    pub fn new(component: impl Into<String>, version: &LongVersion) -> Self {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+
+        Self {
+            schema_version: RUNTIME_INFO_SCHEMA_VERSION,
+            component: component.into(),
+            version: version.into(),
+            listeners: BTreeMap::new(),
+            data_directory: None,
+            genesis_commitment: None,
+            config_path: None,
+            config_hash: None,
+            pid: std::process::id(),
+            started_at,
+        }
+    }
+
+    /// Returns the path of the runtime info file of a component started with the given
+    /// configuration file: the configuration file's path with a `runtime.json` extension.
+    pub fn default_path(config_path: &Path) -> PathBuf {
+        config_path.with_extension(RUNTIME_INFO_EXTENSION)
+    }
+
+    /// Adds the address a listener of the named component is bound to.
+    pub fn with_listener(mut self, name: impl Into<String>, addr: SocketAddr) -> Self {
+        self.listeners.insert(name.into(), addr);
+        self
+    }
+
+    /// Sets the directory holding the data of the component.
+    pub fn with_data_directory(mut self, data_directory: impl Into<PathBuf>) -> Self {
+        self.data_directory = Some(data_directory.into());
+        self
+    }
+
+    /// Sets the commitment of the genesis block loaded by the component.
+    pub fn with_genesis_commitment(mut self, genesis_commitment: Digest) -> Self {
+        self.genesis_commitment = Some(genesis_commitment.to_hex());
+        self
+    }
+
+    /// Sets the configuration file the component was started with, hashing its current contents.
+    pub fn with_config_file(mut self, config_path: &Path) -> io::Result<Self> {
+        let contents = fs::read(config_path)?;
+        self.config_hash = Some(format_blake3_digest(Blake3_256::hash(&contents)));
+        self.config_path = Some(fs::canonicalize(config_path)?);
+        Ok(self)
+    }
+
+    /// Reads a runtime info file, rejecting the files of other schema versions.
+    pub fn read(path: &Path) -> Result<Self, RuntimeInfoError> {
+        let contents = fs::read(path)?;
+
+        // The version is checked first, as the other fields may not parse in other layouts
+        #[derive(Deserialize)]
+        struct Versioned {
+            schema_version: u32,
+        }
+        let Versioned { schema_version } = serde_json::from_slice(&contents)?;
+        if schema_version != RUNTIME_INFO_SCHEMA_VERSION {
+            return Err(RuntimeInfoError::UnsupportedSchemaVersion(schema_version));
+        }
+
+        Ok(serde_json::from_slice(&contents)?)
+    }
+
+    /// Writes the runtime info to `path`, and returns a guard removing the file once dropped.
+    ///
+    /// The file is written to a temporary file first and then renamed, so that readers never see
+    /// a partially written file.
+    pub fn write(&self, path: &Path) -> Result<RuntimeInfoFile, RuntimeInfoError> {
+        let contents = serde_json::to_vec_pretty(self)?;
+        let temp_path = path.with_extension(format!("{RUNTIME_INFO_EXTENSION}.tmp"));
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, path)?;
+
+        Ok(RuntimeInfoFile { path: path.to_path_buf() })
+    }
+}
+
+/// One-line startup banner, listing the core fields as `key=value` pairs.
+impl Display for RuntimeInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "miden-node started component={} version={} sha={}",
+            self.component, self.version.version, self.version.sha
+        )?;
+        for (name, addr) in &self.listeners {
+            write!(f, " {name}_addr={addr}")?;
+        }
+        if let Some(data_directory) = &self.data_directory {
+            write!(f, " data_directory={}", data_directory.display())?;
+        }
+        if let Some(genesis_commitment) = &self.genesis_commitment {
+            write!(f, " genesis={genesis_commitment}")?;
+        }
+        if let Some(config_path) = &self.config_path {
+            write!(f, " config={}", config_path.display())?;
+        }
+
+        write!(f, " pid={} started_at={}", self.pid, self.started_at)
+    }
+}
+
+// RUNTIME INFO FILE
+// ================================================================================================
+
+/// A written runtime info file, removed once dropped, i.e. when the component shuts down.
+#[derive(Debug)]
+pub struct RuntimeInfoFile {
+    path: PathBuf,
+}
+
+impl RuntimeInfoFile {
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for RuntimeInfoFile {
+    fn drop(&mut self) {
+        match fs::remove_file(&self.path) {
+            Ok(()) => {},
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {},
+            Err(err) => warn!(
+                path = %self.path.display(),
+                %err,
+                "Failed to remove the runtime info file"
+            ),
+        }
+    }
+}
+
+// ERRORS
+// ================================================================================================
+
+#[derive(Debug, Error)]
+pub enum RuntimeInfoError {
+    #[error("I/O error: {0}")]
+    IoError(#[from] io::Error),
+    #[error("Invalid runtime info: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+    #[error("Unsupported runtime info schema version {0}, expected {RUNTIME_INFO_SCHEMA_VERSION}")]
+    UnsupportedSchemaVersion(u32),
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_version() -> LongVersion {
+        LongVersion {
+            version: "0.6.0",
+            sha: "abcdef0",
+            branch: "main",
+            dirty: "",
+            features: "",
+            rust_version: "1.82.0",
+            host: "x86_64-unknown-linux-gnu",
+            target: "x86_64-unknown-linux-gnu",
+            opt_level: "3",
+            debug: "false",
+        }
+    }
+
+    #[test]
+    fn runtime_info_files_are_removed_once_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("miden-store.toml");
+        fs::write(&config_path, "[store]").unwrap();
+        let path = RuntimeInfo::default_path(&config_path);
+        assert_eq!(path, dir.path().join("miden-store.runtime.json"));
+
+        let info = RuntimeInfo::new("store", &long_version())
+            .with_listener("store", "127.0.0.1:28943".parse().unwrap())
+            .with_config_file(&config_path)
+            .unwrap();
+        let file = info.write(&path).unwrap();
+        assert_eq!(RuntimeInfo::read(&path).unwrap(), info);
+
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn other_schema_versions_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("miden-node.runtime.json");
+        fs::write(&path, r#"{ "schema_version": 2, "component": "node" }"#).unwrap();
+
+        let err = RuntimeInfo::read(&path).unwrap_err();
+        assert!(matches!(err, RuntimeInfoError::UnsupportedSchemaVersion(2)));
+    }
+}
@@ -0,0 +1,278 @@
+//! Supervision of the long-lived tasks of the node's components.
+//!
+//! A panic in a task spawned with [tokio::spawn] only ends that task, leaving its component
+//! running without it. Components spawn their long-lived tasks with
+//! [TaskSupervisor::spawn_supervised] instead, which logs the panics of the tasks with their
+//! backtrace, marks the component as unhealthy, and signals it to shut down:
+//!
+//! ```ignore
+//! let supervisor = TaskSupervisor::new(COMPONENT);
+//! supervisor.spawn_supervised("batch_builder", batch_builder.run());
+//!
+//! server.serve_with_incoming_shutdown(incoming, supervisor.wait_for_failure()).await?;
+//! supervisor.check()?;
+//! ```
+
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    cell::{Cell, RefCell},
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{Arc, Once},
+    task::{Context, Poll},
+};
+
+use thiserror::Error;
+use tokio::{sync::watch, task::JoinHandle};
+use tracing::{error, info_span, Instrument};
+
+use crate::runtime::spawn_named;
+
+thread_local! {
+    /// Whether the thread is polling a supervised task.
+    static POLLING_SUPERVISED: Cell<bool> = const { Cell::new(false) };
+
+    /// Backtrace of the latest panic of a supervised task polled by the thread.
+    static PANIC_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+// TASK PANIC
+// ================================================================================================
+
+/// A panic of a supervised task.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Task `{task}` of the {component} panicked: {message}")]
+pub struct TaskPanic {
+    /// Component owning the task.
+    pub component: &'static str,
+    /// Name of the task.
+    pub task: &'static str,
+    /// Message of the panic, if it had one.
+    pub message: String,
+    /// Backtrace of the panic, empty if it wasn't captured.
+    pub backtrace: String,
+}
+
+// TASK SUPERVISOR
+// ================================================================================================
+
+/// Supervises the long-lived tasks of a component.
+///
+/// The component is healthy until one of its supervised tasks panics. Clones supervise the tasks
+/// of the same component.
+#[derive(Debug, Clone)]
+pub struct TaskSupervisor {
+    component: &'static str,
+    failure: Arc<watch::Sender<Option<TaskPanic>>>,
+}
+
+impl TaskSupervisor {
+    /// Creates the supervisor of the tasks of `component`.
+    pub fn new(component: &'static str) -> Self {
+        let (failure, _) = watch::channel(None);
+
+        Self { component, failure: Arc::new(failure) }
+    }
+
+    /// Spawns the `future` as a task named `<component>:<task>`, within a span recording the
+    /// component and the task.
+    ///
+    /// If the task panics, the panic is logged with its backtrace, and the component is marked
+    /// as unhealthy. Only the first panic is recorded.
+    pub fn spawn_supervised<F>(&self, task: &'static str, future: F) -> JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        install_panic_hook();
+
+        let supervisor = self.clone();
+        let span = info_span!("task", component = self.component, task);
+        let name = format!("{}:{task}", self.component);
+        let supervised = async move {
+            if let Err((payload, backtrace)) = CatchPanic(Box::pin(future)).await {
+                let panic = TaskPanic {
+                    component: supervisor.component,
+                    task,
+                    message: panic_message(payload.as_ref()),
+                    backtrace: backtrace.map(|backtrace| backtrace.to_string()).unwrap_or_default(),
+                };
+
+                error!(
+                    component = panic.component,
+                    task = panic.task,
+                    panic = %panic.message,
+                    backtrace = %panic.backtrace,
+                    "Supervised task panicked"
+                );
+
+                supervisor.failure.send_if_modified(|failure| match failure {
+                    Some(_) => false,
+                    None => {
+                        *failure = Some(panic);
+                        true
+                    },
+                });
+            }
+        };
+
+        spawn_named(&name, supervised.instrument(span))
+    }
+
+    /// Returns whether none of the supervised tasks panicked.
+    pub fn is_healthy(&self) -> bool {
+        self.failure.borrow().is_none()
+    }
+
+    /// Returns the first panic of the supervised tasks, if any.
+    pub fn check(&self) -> Result<(), TaskPanic> {
+        match &*self.failure.borrow() {
+            Some(panic) => Err(panic.clone()),
+            None => Ok(()),
+        }
+    }
+
+    /// Completes once one of the supervised tasks panicked, to be used as the shutdown signal of
+    /// the component.
+    pub async fn wait_for_failure(&self) {
+        let mut failure = self.failure.subscribe();
+        failure
+            .wait_for(Option::is_some)
+            .await
+            .expect("The supervisor holds the sender");
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Installs the panic hook capturing the backtraces of the panics of supervised tasks, instead of
+/// printing them. Panics elsewhere are handled by the previous hook.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if POLLING_SUPERVISED.get() {
+                PANIC_BACKTRACE.set(Some(Backtrace::force_capture()));
+            } else {
+                previous_hook(info);
+            }
+        }));
+    });
+}
+
+/// Returns the message of a panic from its payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::new()
+    }
+}
+
+/// Resolves to the output of the wrapped future, or to the payload and backtrace of its panic.
+struct CatchPanic<F>(Pin<Box<F>>);
+
+type PanicPayload = (Box<dyn Any + Send>, Option<Backtrace>);
+
+impl<F: Future> Future for CatchPanic<F> {
+    type Output = Result<F::Output, PanicPayload>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let was_supervised = POLLING_SUPERVISED.replace(true);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.0.as_mut().poll(cx)));
+        POLLING_SUPERVISED.set(was_supervised);
+
+        match result {
+            Ok(poll) => poll.map(Ok),
+            Err(payload) => Poll::Ready(Err((payload, PANIC_BACKTRACE.take()))),
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, fmt::Debug, sync::Mutex, time::Duration};
+
+    use tracing::{
+        field::{Field, Visit},
+        Event, Subscriber,
+    };
+    use tracing_subscriber::{layer::SubscriberExt, registry, Layer};
+
+    use super::*;
+
+    /// Records the fields of every event.
+    #[derive(Clone, Default)]
+    struct RecordedEvents(Arc<Mutex<Vec<BTreeMap<&'static str, String>>>>);
+
+    struct EventFields(BTreeMap<&'static str, String>);
+
+    impl Visit for EventFields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.insert(field.name(), format!("{value:?}"));
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for RecordedEvents {
+        fn on_event(&self, event: &Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            let mut fields = EventFields(BTreeMap::new());
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn panics_of_supervised_tasks_are_logged_and_signaled() {
+        let events = RecordedEvents::default();
+        let _guard = tracing::subscriber::set_default(registry().with(events.clone()));
+
+        let supervisor = TaskSupervisor::new("test component");
+        let healthy_task = supervisor.spawn_supervised("healthy", async {});
+        let pending_task = supervisor.spawn_supervised("pending", std::future::pending());
+        healthy_task.await.unwrap();
+        assert!(supervisor.is_healthy());
+
+        let failing_task = supervisor.spawn_supervised("failing", async {
+            tokio::task::yield_now().await;
+            let value = 42;
+            panic!("deliberate failure with {value}");
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), supervisor.wait_for_failure())
+            .await
+            .expect("Panic must signal the failure");
+        failing_task.await.expect("Panic is caught by the supervisor");
+
+        assert!(!supervisor.is_healthy());
+        let panic = supervisor.check().unwrap_err();
+        assert_eq!(panic.component, "test component");
+        assert_eq!(panic.task, "failing");
+        assert_eq!(panic.message, "deliberate failure with 42");
+        assert!(!panic.backtrace.is_empty());
+
+        // The panic is reported once, with its diagnostics
+        let events = events.0.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["message"], "Supervised task panicked");
+        assert_eq!(events[0]["component"], "test component");
+        assert_eq!(events[0]["task"], "failing");
+        assert_eq!(events[0]["panic"], "deliberate failure with 42");
+        assert_eq!(events[0]["backtrace"], panic.backtrace);
+
+        // Other tasks keep running until the component shuts down
+        assert!(!pending_task.is_finished());
+    }
+}
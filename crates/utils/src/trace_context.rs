@@ -0,0 +1,237 @@
+//! W3C trace context propagated across the node's components.
+//!
+//! The [RequestIdLayer] of the servers of the node continues the trace of the `traceparent` header
+//! of every request, or starts a new trace if the request has none. Each request served is a span
+//! of the trace, with its own span ID, and the requests made to the other components on its behalf
+//! carry a `traceparent` header pointing to it (see [RequestIdPropagator]). The `request` span of
+//! every served request records its `trace_id`, `span_id` and `parent_span_id`, so that the logs of
+//! all the components serving a request can be connected into a single trace.
+//!
+//! See <https://www.w3.org/TR/trace-context/>.
+//!
+//! [RequestIdLayer]: crate::request_id::RequestIdLayer
+//! [RequestIdPropagator]: crate::request_id::RequestIdPropagator
+
+use std::{
+    fmt::{Display, Formatter},
+    future::Future,
+};
+
+use rand::Rng;
+
+/// Header, and gRPC metadata key, of the trace context.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Header, and gRPC metadata key, of the vendor-specific trace state, forwarded as is.
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// Maximum length of the trace states forwarded to the other components.
+const MAX_TRACESTATE_LEN: usize = 512;
+
+/// Version of the `traceparent` format produced.
+const TRACEPARENT_VERSION: &str = "00";
+
+/// Flag of the traces sampled by their caller.
+const SAMPLED_FLAG: u8 = 0x01;
+
+tokio::task_local! {
+    static TRACE_CONTEXT: TraceContext;
+}
+
+/// Returns the trace context of the request being served by the current task, if any.
+pub fn current_trace_context() -> Option<TraceContext> {
+    TRACE_CONTEXT.try_with(Clone::clone).ok()
+}
+
+/// Runs `future` within the given trace context.
+pub fn with_trace_context<F: Future>(
+    trace_context: TraceContext,
+    future: F,
+) -> impl Future<Output = F::Output> {
+    TRACE_CONTEXT.scope(trace_context, future)
+}
+
+// TRACE CONTEXT
+// ================================================================================================
+
+/// Position of a span within a trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// ID of the whole trace, shared by all its spans.
+    pub trace_id: [u8; 16],
+    /// ID of the span.
+    pub span_id: [u8; 8],
+    /// ID of the parent span, [None] for the root span of the trace.
+    pub parent_span_id: Option<[u8; 8]>,
+    /// Trace flags, e.g. whether the trace is sampled.
+    pub flags: u8,
+    /// Vendor-specific trace state received from the caller.
+    pub trace_state: Option<String>,
+}
+
+impl TraceContext {
+    /// Returns the root span of a new trace.
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: random_non_zero(),
+            span_id: random_non_zero(),
+            parent_span_id: None,
+            flags: SAMPLED_FLAG,
+            trace_state: None,
+        }
+    }
+
+    /// Returns a span continuing the trace of the `traceparent` and `tracestate` headers of a
+    /// request, or the root span of a new trace if the `traceparent` is missing or invalid.
+    pub fn continue_from(traceparent: Option<&str>, trace_state: Option<&str>) -> Self {
+        let Some((trace_id, parent_span_id, flags)) = traceparent.and_then(parse_traceparent)
+        else {
+            return Self::new_root();
+        };
+
+        Self {
+            trace_id,
+            span_id: random_non_zero(),
+            parent_span_id: Some(parent_span_id),
+            flags,
+            trace_state: trace_state
+                .filter(|state| state.len() <= MAX_TRACESTATE_LEN)
+                .map(ToString::to_string),
+        }
+    }
+
+    /// Returns the `traceparent` header of the requests made on behalf of the span, i.e. whose
+    /// parent is the span.
+    pub fn traceparent(&self) -> String {
+        format!(
+            "{TRACEPARENT_VERSION}-{}-{}-{:02x}",
+            hex(&self.trace_id),
+            hex(&self.span_id),
+            self.flags
+        )
+    }
+
+    /// Returns the hex-encoded ID of the trace.
+    pub fn trace_id_hex(&self) -> String {
+        hex(&self.trace_id)
+    }
+
+    /// Returns the hex-encoded ID of the span.
+    pub fn span_id_hex(&self) -> String {
+        hex(&self.span_id)
+    }
+
+    /// Returns the hex-encoded ID of the parent span, if any.
+    pub fn parent_span_id_hex(&self) -> Option<String> {
+        self.parent_span_id.map(|span_id| hex(&span_id))
+    }
+}
+
+impl Display for TraceContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.traceparent())
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Parses a `traceparent` header into its trace ID, parent span ID and flags.
+///
+/// Headers of future versions are parsed as version `00`, ignoring their additional fields, as
+/// recommended by the specification. All-zero IDs are invalid.
+fn parse_traceparent(traceparent: &str) -> Option<([u8; 16], [u8; 8], u8)> {
+    let mut fields = traceparent.trim().split('-');
+    let version = fields.next()?;
+    let trace_id = fields.next()?;
+    let parent_span_id = fields.next()?;
+    let flags = fields.next()?;
+
+    let version = decode_hex::<1>(version)?[0];
+    if version == 0xff || (version == 0 && fields.next().is_some()) {
+        return None;
+    }
+
+    let trace_id = decode_hex::<16>(trace_id).filter(|id| id != &[0; 16])?;
+    let parent_span_id = decode_hex::<8>(parent_span_id).filter(|id| id != &[0; 8])?;
+    let flags = decode_hex::<1>(flags)?[0];
+
+    Some((trace_id, parent_span_id, flags))
+}
+
+/// Decodes a lowercase hex string of exactly `N` bytes.
+fn decode_hex<const N: usize>(encoded: &str) -> Option<[u8; N]> {
+    if encoded.len() != 2 * N || !encoded.bytes().all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+
+    let mut bytes = [0; N];
+    for (byte, pair) in bytes.iter_mut().zip(encoded.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+
+    Some(bytes)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Returns random bytes, which are not all zero.
+fn random_non_zero<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    while bytes == [0; N] {
+        rand::thread_rng().fill(&mut bytes[..]);
+    }
+
+    bytes
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn traces_are_continued_from_valid_traceparents() {
+        let context = TraceContext::continue_from(Some(TRACEPARENT), Some("vendor=value"));
+        assert_eq!(context.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.parent_span_id_hex().unwrap(), "00f067aa0ba902b7");
+        assert_ne!(context.span_id_hex(), "00f067aa0ba902b7");
+        assert_eq!(context.flags, SAMPLED_FLAG);
+        assert_eq!(context.trace_state.as_deref(), Some("vendor=value"));
+
+        // The requests made on behalf of the span are its children
+        let child = TraceContext::continue_from(Some(&context.traceparent()), None);
+        assert_eq!(child.trace_id, context.trace_id);
+        assert_eq!(child.parent_span_id, Some(context.span_id));
+
+        // Future versions are parsed as the current one
+        let future_version = "cc-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra";
+        let context = TraceContext::continue_from(Some(future_version), None);
+        assert_eq!(context.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+    }
+
+    #[test]
+    fn new_traces_are_started_from_invalid_traceparents() {
+        for traceparent in [
+            None,
+            Some(""),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7"),
+            Some("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01"),
+            Some("00-00000000000000000000000000000000-00f067aa0ba902b7-01"),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01"),
+            Some("ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra"),
+        ] {
+            let context = TraceContext::continue_from(traceparent, Some("vendor=value"));
+            assert_eq!(context.parent_span_id, None, "{traceparent:?}");
+            assert_ne!(context.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+            assert_eq!(context.trace_state, None);
+        }
+    }
+}